@@ -1,20 +1,81 @@
 //! Database management commands
+//!
+//! `migrate` and `reset` drive `sqlx-cli`. `status` and `rollback` read the
+//! `_sqlx_migrations` table directly and revert reversible migrations
+//! (`{version}_{name}.up.sql` paired with `{version}_{name}.down.sql`).
+//!
+//! `seed` runs the SQL files in `seeds/` in filename order after applying
+//! pending migrations. Each file runs once per database; the names of the
+//! files that ran are recorded in the `_acton_seeds` table. A file named
+//! `{name}.{backend}.sql` (`postgres`, `sqlite`, or `mysql`) only runs
+//! against that backend.
 
 use anyhow::{Context, Result};
 use console::style;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+#[cfg(feature = "htmx")]
+use super::doctor::ProjectEnv;
+#[cfg(feature = "htmx")]
+use sqlx::migrate::{Migrate, MigrationType, Migrator};
+#[cfg(feature = "htmx")]
+use sqlx::{AnyConnection, Connection};
+
+/// Directory holding migrations, relative to the project root
+const MIGRATIONS_DIR: &str = "migrations";
+
+/// Directory holding seed files, relative to the project root
+#[cfg(feature = "htmx")]
+const SEEDS_DIR: &str = "seeds";
+
+/// Table recording which seed files have run
+#[cfg(feature = "htmx")]
+const SEEDS_TABLE: &str = "_acton_seeds";
+
 /// Database command variants
 pub enum DbCommand {
     /// Run pending migrations
     Migrate,
     /// Reset database (drop, create, migrate)
     Reset,
-    /// Create a new migration file
+    /// Create a new reversible migration (`.up.sql` and `.down.sql`)
     Create {
         /// Name of the migration to create
         name: String,
     },
+    /// List applied and pending migrations
+    #[cfg(feature = "htmx")]
+    Status,
+    /// Revert the most recently applied migrations
+    #[cfg(feature = "htmx")]
+    Rollback {
+        /// Number of migrations to revert
+        steps: usize,
+        /// Skip the confirmation prompt outside development
+        force: bool,
+    },
+    /// Run seed files that haven't run against this database
+    #[cfg(feature = "htmx")]
+    Seed {
+        /// Drop, recreate, and migrate the database first
+        reset: bool,
+    },
+}
+
+/// A migration and whether it has been applied
+#[cfg(feature = "htmx")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// Migration version (the filename's timestamp prefix)
+    pub version: i64,
+    /// Migration description (the rest of the filename)
+    pub description: String,
+    /// Whether the version is recorded in `_sqlx_migrations`
+    pub applied: bool,
+    /// Whether a `.down.sql` file can revert it
+    pub reversible: bool,
 }
 
 impl DbCommand {
@@ -26,7 +87,28 @@ impl DbCommand {
     /// - `sqlx-cli` is not installed
     /// - Database operations fail
     pub fn execute(&self) -> Result<()> {
-        // Check if sqlx-cli is installed
+        match self {
+            Self::Migrate => Self::require_sqlx_cli().and_then(|()| Self::migrate()),
+            Self::Reset => Self::require_sqlx_cli().and_then(|()| Self::reset()),
+            Self::Create { name } => Self::create(name),
+            #[cfg(feature = "htmx")]
+            Self::Status => Self::status(),
+            #[cfg(feature = "htmx")]
+            Self::Rollback { steps, force } => Self::rollback(*steps, *force),
+            #[cfg(feature = "htmx")]
+            Self::Seed { reset } => {
+                if *reset {
+                    Self::require_sqlx_cli()?;
+                    Self::reset()?;
+                    println!();
+                }
+                Self::seed()
+            }
+        }
+    }
+
+    /// Fail with install instructions when `sqlx-cli` is missing
+    fn require_sqlx_cli() -> Result<()> {
         if !Self::is_sqlx_cli_installed() {
             println!(
                 "{} is not installed.",
@@ -43,11 +125,7 @@ impl DbCommand {
             anyhow::bail!("sqlx-cli is required for database commands");
         }
 
-        match self {
-            Self::Migrate => Self::migrate(),
-            Self::Reset => Self::reset(),
-            Self::Create { name } => Self::create(name),
-        }
+        Ok(())
     }
 
     /// Run pending migrations
@@ -122,7 +200,7 @@ impl DbCommand {
         Ok(())
     }
 
-    /// Create a new migration file
+    /// Create a new reversible migration
     fn create(name: &str) -> Result<()> {
         println!(
             "{} {}",
@@ -131,24 +209,144 @@ impl DbCommand {
         );
         println!();
 
-        let status = Command::new("sqlx")
-            .args(["migrate", "add", name])
-            .status()
-            .context("Failed to create migration")?;
+        let version = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
+        let (up, down) = create_migration_files(Path::new(MIGRATIONS_DIR), name, &version)?;
 
-        if !status.success() {
-            anyhow::bail!("Failed to create migration");
+        println!("  {} {}", style("✓").green(), style(up.display()).dim());
+        println!("  {} {}", style("✓").green(), style(down.display()).dim());
+        println!();
+        println!(
+            "{}",
+            style("✓ Migration files created in migrations/").green().bold()
+        );
+
+        Ok(())
+    }
+
+    /// Print applied and pending migrations
+    #[cfg(feature = "htmx")]
+    fn status() -> Result<()> {
+        let database_url = Self::database_url()?;
+        let migrations = block_on(migration_status(&database_url, Path::new(MIGRATIONS_DIR)))?;
+
+        if migrations.is_empty() {
+            println!("No migrations found in {MIGRATIONS_DIR}/");
+            return Ok(());
+        }
+
+        println!("{}", style("Migrations:").bold());
+        println!();
+        for migration in &migrations {
+            let state = if migration.applied {
+                style("applied").green()
+            } else {
+                style("pending").yellow()
+            };
+            let reversible = if migration.reversible { "" } else { " (irreversible)" };
+            println!(
+                "  {:<16} {:<8} {}{}",
+                migration.version,
+                state,
+                migration.description,
+                style(reversible).dim()
+            );
         }
 
+        let pending = migrations.iter().filter(|m| !m.applied).count();
         println!();
         println!(
-            "{}",
-            style("✓ Migration file created in migrations/").green().bold()
+            "{} applied, {} pending",
+            migrations.len() - pending,
+            pending
         );
 
         Ok(())
     }
 
+    /// Revert the last `steps` migrations, confirming outside development
+    #[cfg(feature = "htmx")]
+    fn rollback(steps: usize, force: bool) -> Result<()> {
+        let env = crate::htmx::config::ActonHtmxConfig::current_env();
+        if !force && env != "development" {
+            println!(
+                "{} This will revert {steps} migration(s) in the {} environment.",
+                style("Warning:").yellow(),
+                style(&env).bold()
+            );
+            println!("Are you sure? (y/N): ");
+
+            let mut input = String::new();
+            std::io::stdin()
+                .read_line(&mut input)
+                .context("Failed to read input")?;
+
+            if !input.trim().eq_ignore_ascii_case("y") {
+                println!("Cancelled.");
+                return Ok(());
+            }
+        }
+
+        let database_url = Self::database_url()?;
+        let reverted = block_on(rollback(&database_url, Path::new(MIGRATIONS_DIR), steps))?;
+
+        if reverted.is_empty() {
+            println!("No applied migrations to roll back.");
+            return Ok(());
+        }
+
+        for migration in &reverted {
+            println!(
+                "  {} Reverted {} {}",
+                style("✓").green(),
+                migration.version,
+                migration.description
+            );
+        }
+        println!();
+        println!("{}", style("✓ Rollback completed successfully!").green().bold());
+
+        Ok(())
+    }
+
+    /// Run pending migrations, then seed files that haven't run
+    #[cfg(feature = "htmx")]
+    fn seed() -> Result<()> {
+        println!(
+            "{} {}",
+            style("Seeding").green().bold(),
+            style("database...").bold()
+        );
+        println!();
+
+        let database_url = Self::database_url()?;
+        let seeded = block_on(seed(
+            &database_url,
+            Path::new(MIGRATIONS_DIR),
+            Path::new(SEEDS_DIR),
+        ))?;
+
+        if seeded.is_empty() {
+            println!("No new seed files in {SEEDS_DIR}/");
+            return Ok(());
+        }
+
+        for name in &seeded {
+            println!("  {} {}", style("✓").green(), style(name).dim());
+        }
+        println!();
+        println!("{}", style("✓ Database seeded successfully!").green().bold());
+
+        Ok(())
+    }
+
+    /// `DATABASE_URL` from the environment or the project's `.env`
+    #[cfg(feature = "htmx")]
+    fn database_url() -> Result<String> {
+        ProjectEnv::load(Path::new("."))
+            .get("DATABASE_URL")
+            .context("DATABASE_URL is not set (add it to .env or the environment)")
+    }
+
     /// Check if sqlx-cli is installed
     fn is_sqlx_cli_installed() -> bool {
         Command::new("sqlx")
@@ -156,7 +354,453 @@ impl DbCommand {
             .stdout(Stdio::null())
             .stderr(Stdio::null())
             .status()
-            .map(|status| status.success())
-            .unwrap_or(false)
+            .is_ok_and(|status| status.success())
+    }
+}
+
+/// Write empty `.up.sql` and `.down.sql` files for a new migration
+///
+/// # Errors
+///
+/// Returns an error if the name is empty or the files cannot be written.
+pub fn create_migration_files(dir: &Path, name: &str, version: &str) -> Result<(PathBuf, PathBuf)> {
+    let slug = name
+        .trim()
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    if slug.is_empty() {
+        anyhow::bail!("Migration name must contain letters or digits: '{name}'");
+    }
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let up = dir.join(format!("{version}_{slug}.up.sql"));
+    let down = dir.join(format!("{version}_{slug}.down.sql"));
+    fs::write(&up, format!("-- {name}\n"))
+        .with_context(|| format!("Failed to write file: {}", up.display()))?;
+    fs::write(&down, format!("-- Revert {name}\n"))
+        .with_context(|| format!("Failed to write file: {}", down.display()))?;
+
+    Ok((up, down))
+}
+
+/// Every migration in `dir` and in `_sqlx_migrations`, by version
+///
+/// Versions applied to the database but missing from `dir` are listed with
+/// an empty description.
+///
+/// # Errors
+///
+/// Returns an error if the migrations cannot be read or the database cannot
+/// be queried.
+#[cfg(feature = "htmx")]
+pub async fn migration_status(database_url: &str, dir: &Path) -> Result<Vec<MigrationStatus>> {
+    let migrator = load_migrator(dir).await?;
+    let mut conn = connect(database_url).await?;
+    conn.ensure_migrations_table().await?;
+    let applied = conn.list_applied_migrations().await?;
+    conn.close().await?;
+
+    let mut migrations: Vec<MigrationStatus> = migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| MigrationStatus {
+            version: m.version,
+            description: m.description.to_string(),
+            applied: applied.iter().any(|a| a.version == m.version),
+            reversible: m.migration_type == MigrationType::ReversibleUp,
+        })
+        .collect();
+
+    let missing: Vec<MigrationStatus> = applied
+        .iter()
+        .filter(|a| !migrations.iter().any(|m| m.version == a.version))
+        .map(|a| MigrationStatus {
+            version: a.version,
+            description: String::new(),
+            applied: true,
+            reversible: false,
+        })
+        .collect();
+    migrations.extend(missing);
+
+    migrations.sort_by_key(|m| m.version);
+    Ok(migrations)
+}
+
+/// Revert the last `steps` applied migrations using their `.down.sql` files
+///
+/// Nothing is reverted unless every targeted migration is reversible.
+/// Returns the reverted migrations, newest first.
+///
+/// # Errors
+///
+/// Returns an error if a targeted migration has no `.down.sql` file, or the
+/// database rejects a rollback.
+#[cfg(feature = "htmx")]
+pub async fn rollback(database_url: &str, dir: &Path, steps: usize) -> Result<Vec<MigrationStatus>> {
+    let migrator = load_migrator(dir).await?;
+    let mut conn = connect(database_url).await?;
+    conn.ensure_migrations_table().await?;
+
+    let mut applied: Vec<i64> = conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|m| m.version)
+        .collect();
+    applied.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut reverted = Vec::new();
+    for &version in applied.iter().take(steps) {
+        let up = migrator.iter().find(|m| m.version == version && !m.migration_type.is_down_migration());
+        let has_down = migrator
+            .iter()
+            .any(|m| m.version == version && m.migration_type.is_down_migration());
+        match up {
+            Some(up) if has_down => reverted.push(MigrationStatus {
+                version,
+                description: up.description.to_string(),
+                applied: false,
+                reversible: true,
+            }),
+            Some(up) => anyhow::bail!(
+                "Migration {version} ({}) has no .down.sql file; nothing was rolled back",
+                up.description
+            ),
+            None => anyhow::bail!(
+                "Applied migration {version} is missing from {}; nothing was rolled back",
+                dir.display()
+            ),
+        }
+    }
+
+    if !reverted.is_empty() {
+        // Versions above the target are reverted newest first
+        let target = applied.get(steps).copied().unwrap_or(0);
+        migrator
+            .undo(&mut conn, target)
+            .await
+            .context("Failed to roll back migrations")?;
+    }
+    conn.close().await?;
+
+    Ok(reverted)
+}
+
+/// Apply pending migrations, then run the seed files in `seeds` that
+/// haven't run against this database
+///
+/// Each file runs in a transaction together with its `_acton_seeds` record,
+/// so a failing seed leaves no partial data and runs again next time.
+/// Returns the names of the files that ran.
+///
+/// # Errors
+///
+/// Returns an error if migrating fails, or a seed file cannot be read or
+/// fails to execute.
+#[cfg(feature = "htmx")]
+pub async fn seed(database_url: &str, migrations: &Path, seeds: &Path) -> Result<Vec<String>> {
+    let mut conn = connect(database_url).await?;
+
+    if migrations.is_dir() {
+        load_migrator(migrations)
+            .await?
+            .run(&mut conn)
+            .await
+            .context("Failed to run migrations")?;
+    }
+
+    sqlx::raw_sql(&format!(
+        "CREATE TABLE IF NOT EXISTS {SEEDS_TABLE} (name VARCHAR(255) PRIMARY KEY)"
+    ))
+    .execute(&mut conn)
+    .await
+    .context("Failed to create the seeds table")?;
+
+    let backend = conn.backend_name().to_string();
+    let placeholder = if backend == "MySQL" { "?" } else { "$1" };
+    let applied: Vec<String> = sqlx::query_scalar(&format!("SELECT name FROM {SEEDS_TABLE}"))
+        .fetch_all(&mut conn)
+        .await?;
+
+    let mut seeded = Vec::new();
+    for path in seed_files(seeds, &backend)? {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if applied.contains(&name) {
+            continue;
+        }
+
+        let sql = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        let mut tx = conn.begin().await?;
+        sqlx::raw_sql(&sql)
+            .execute(&mut *tx)
+            .await
+            .with_context(|| format!("Seed {name} failed"))?;
+        sqlx::query(&format!("INSERT INTO {SEEDS_TABLE} (name) VALUES ({placeholder})"))
+            .bind(&name)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        seeded.push(name);
+    }
+    conn.close().await?;
+
+    Ok(seeded)
+}
+
+/// `.sql` files in `dir` for `backend`, in filename order
+#[cfg(feature = "htmx")]
+fn seed_files(dir: &Path, backend: &str) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(stem) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".sql"))
+        else {
+            continue;
+        };
+
+        let for_backend = match Path::new(stem).extension().and_then(|ext| ext.to_str()) {
+            Some("postgres") => backend == "PostgreSQL",
+            Some("sqlite") => backend == "SQLite",
+            Some("mysql") => backend == "MySQL",
+            _ => true,
+        };
+        if for_backend && path.is_file() {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(feature = "htmx")]
+async fn load_migrator(dir: &Path) -> Result<Migrator> {
+    Migrator::new(dir.to_path_buf())
+        .await
+        .with_context(|| format!("Failed to read migrations from {}", dir.display()))
+}
+
+#[cfg(feature = "htmx")]
+async fn connect(database_url: &str) -> Result<AnyConnection> {
+    sqlx::any::install_default_drivers();
+    AnyConnection::connect(database_url)
+        .await
+        .context("Failed to connect to the database")
+}
+
+/// Run a database future from the synchronous CLI
+#[cfg(feature = "htmx")]
+fn block_on<T>(future: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to start async runtime")?
+        .block_on(future)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_create_migration_files() {
+        let dir = tempdir().unwrap();
+        let migrations = dir.path().join("migrations");
+
+        let (up, down) =
+            create_migration_files(&migrations, "Add Posts Index", "20250101000000").unwrap();
+
+        assert_eq!(up, migrations.join("20250101000000_add_posts_index.up.sql"));
+        assert_eq!(down, migrations.join("20250101000000_add_posts_index.down.sql"));
+        assert_eq!(fs::read_to_string(down).unwrap(), "-- Revert Add Posts Index\n");
+        assert!(create_migration_files(&migrations, " - ", "20250101000001").is_err());
+    }
+
+    #[cfg(feature = "htmx")]
+    mod database {
+        use super::*;
+
+        /// A sqlite database and three reversible `CREATE TABLE` migrations
+        fn fixture(dir: &Path) -> (String, PathBuf) {
+            let migrations = dir.join("migrations");
+            for (version, table) in [(1, "users"), (2, "posts"), (3, "comments")] {
+                let (up, down) =
+                    create_migration_files(&migrations, &format!("create {table}"), &version.to_string())
+                        .unwrap();
+                fs::write(up, format!("CREATE TABLE {table} (id INTEGER PRIMARY KEY);\n")).unwrap();
+                fs::write(down, format!("DROP TABLE {table};\n")).unwrap();
+            }
+            let url = format!("sqlite://{}?mode=rwc", dir.join("test.db").display());
+            (url, migrations)
+        }
+
+        async fn migrate(url: &str, migrations: &Path) {
+            let migrator = load_migrator(migrations).await.unwrap();
+            let mut conn = connect(url).await.unwrap();
+            migrator.run(&mut conn).await.unwrap();
+        }
+
+        async fn tables(url: &str) -> Vec<String> {
+            let mut conn = connect(url).await.unwrap();
+            sqlx::query_scalar(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE '\\_%' ESCAPE '\\' ORDER BY name",
+            )
+            .fetch_all(&mut conn)
+            .await
+            .unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_status_reflects_applied_migrations() {
+            let dir = tempdir().unwrap();
+            let (url, migrations) = fixture(dir.path());
+
+            let status = migration_status(&url, &migrations).await.unwrap();
+            assert_eq!(status.len(), 3);
+            assert!(status.iter().all(|m| !m.applied && m.reversible));
+
+            migrate(&url, &migrations).await;
+
+            let status = migration_status(&url, &migrations).await.unwrap();
+            assert_eq!(
+                status
+                    .iter()
+                    .map(|m| (m.version, m.description.as_str(), m.applied))
+                    .collect::<Vec<_>>(),
+                [
+                    (1, "create users", true),
+                    (2, "create posts", true),
+                    (3, "create comments", true),
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn test_rollback_reverts_latest() {
+            let dir = tempdir().unwrap();
+            let (url, migrations) = fixture(dir.path());
+            migrate(&url, &migrations).await;
+
+            let reverted = rollback(&url, &migrations, 1).await.unwrap();
+            assert_eq!(reverted.len(), 1);
+            assert_eq!(reverted[0].version, 3);
+            assert_eq!(tables(&url).await, ["posts", "users"]);
+
+            let status = migration_status(&url, &migrations).await.unwrap();
+            assert_eq!(
+                status.iter().map(|m| m.applied).collect::<Vec<_>>(),
+                [true, true, false]
+            );
+
+            let reverted = rollback(&url, &migrations, 5).await.unwrap();
+            assert_eq!(
+                reverted.iter().map(|m| m.version).collect::<Vec<_>>(),
+                [2, 1]
+            );
+            assert!(tables(&url).await.is_empty());
+            assert!(rollback(&url, &migrations, 1).await.unwrap().is_empty());
+        }
+
+        /// Seed files for the `users` table created by [`fixture`]
+        fn seed_fixture(dir: &Path) -> PathBuf {
+            let seeds = dir.join("seeds");
+            fs::create_dir_all(&seeds).unwrap();
+            fs::write(
+                seeds.join("01_users.sql"),
+                "INSERT INTO users (id) VALUES (1);\nINSERT INTO users (id) VALUES (2);\n",
+            )
+            .unwrap();
+            fs::write(seeds.join("02_posts.postgres.sql"), "INSERT INTO missing VALUES (1);\n")
+                .unwrap();
+            fs::write(seeds.join("README.md"), "not a seed").unwrap();
+            seeds
+        }
+
+        async fn count_users(url: &str) -> i64 {
+            let mut conn = connect(url).await.unwrap();
+            sqlx::query_scalar("SELECT COUNT(*) FROM users")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_seed_migrates_and_populates() {
+            let dir = tempdir().unwrap();
+            let (url, migrations) = fixture(dir.path());
+            let seeds = seed_fixture(dir.path());
+
+            let seeded = seed(&url, &migrations, &seeds).await.unwrap();
+
+            // The postgres-only seed is skipped on sqlite
+            assert_eq!(seeded, ["01_users.sql"]);
+            assert_eq!(count_users(&url).await, 2);
+        }
+
+        #[tokio::test]
+        async fn test_reseed_does_not_duplicate_rows() {
+            let dir = tempdir().unwrap();
+            let (url, migrations) = fixture(dir.path());
+            let seeds = seed_fixture(dir.path());
+
+            seed(&url, &migrations, &seeds).await.unwrap();
+            let seeded = seed(&url, &migrations, &seeds).await.unwrap();
+
+            assert!(seeded.is_empty());
+            assert_eq!(count_users(&url).await, 2);
+        }
+
+        #[tokio::test]
+        async fn test_failed_seed_is_rolled_back() {
+            let dir = tempdir().unwrap();
+            let (url, migrations) = fixture(dir.path());
+            let seeds = dir.path().join("seeds");
+            fs::create_dir_all(&seeds).unwrap();
+            fs::write(
+                seeds.join("01_users.sql"),
+                "INSERT INTO users (id) VALUES (1);\nINSERT INTO missing VALUES (1);\n",
+            )
+            .unwrap();
+
+            assert!(seed(&url, &migrations, &seeds).await.is_err());
+            assert_eq!(count_users(&url).await, 0);
+        }
+
+        #[tokio::test]
+        async fn test_rollback_refuses_irreversible_migration() {
+            let dir = tempdir().unwrap();
+            let (url, migrations) = fixture(dir.path());
+            fs::remove_file(migrations.join("3_create_comments.down.sql")).unwrap();
+            fs::rename(
+                migrations.join("3_create_comments.up.sql"),
+                migrations.join("3_create_comments.sql"),
+            )
+            .unwrap();
+            migrate(&url, &migrations).await;
+
+            let error = rollback(&url, &migrations, 2).await.unwrap_err();
+            assert!(error.to_string().contains("no .down.sql"));
+            assert_eq!(tables(&url).await, ["comments", "posts", "users"]);
+        }
     }
 }
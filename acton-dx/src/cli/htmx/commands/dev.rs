@@ -1,18 +1,164 @@
 //! Development server command
+//!
+//! Builds and runs the project, then watches [`WATCHED_DIRS`] for changes.
+//! Bursts of file events are debounced and each changed path is classified
+//! with [`classify`]:
+//!
+//! - Rust sources and migrations are compiled into the binary, so they
+//!   trigger a rebuild and restart
+//! - Templates and config are read at runtime, so they restart the existing
+//!   binary without rebuilding
+//! - Static assets are served from disk, so they only reload the browser
+//! - Everything else, including editor swap files and paths matching a
+//!   `--watch-ignore` glob, is ignored
+//!
+//! The command also runs a live-reload WebSocket on localhost and passes its
+//! URL to the server in `ACTON_LIVE_RELOAD_URL`. Applications using
+//! `LiveReloadLayer::from_env()` inject a script into full pages that
+//! reloads the browser after every restart or static asset change. The
+//! layer is inert in release builds and when the variable is unset.
 
 use anyhow::{Context, Result};
+use axum::{
+    extract::{
+        ws::{Message, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+    routing::get,
+    Router,
+};
 use console::style;
-use std::path::Path;
-use std::process::{Command, Stdio};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use tokio::sync::broadcast;
 
-/// Start development server with hot reload
-pub struct DevCommand;
+/// Project directories watched for changes
+pub const WATCHED_DIRS: [&str; 5] = ["src", "templates", "config", "migrations", "static"];
+
+/// Quiet period that ends a burst of file events
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Environment variable passing the live-reload URL to the server
+///
+/// Read by `acton_dx::htmx::middleware::LiveReloadLayer::from_env`.
+const LIVE_RELOAD_URL_ENV: &str = "ACTON_LIVE_RELOAD_URL";
+
+/// Path of the live-reload WebSocket
+const LIVE_RELOAD_PATH: &str = "/__livereload";
+
+/// What a changed file requires of the development server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevAction {
+    /// Rebuild with cargo and restart; carries the kind of file changed
+    Rebuild(&'static str),
+    /// Restart without rebuilding; carries the kind of file changed
+    Reload(&'static str),
+    /// Reload the browser only; carries the kind of file changed
+    Refresh(&'static str),
+    /// Nothing to do
+    Ignore,
+}
+
+/// Classify a changed path, relative to the project directory
+///
+/// `ignore` holds glob patterns (`*`, `**`, `?`). Patterns without a `/`
+/// match the file name anywhere; others match the whole relative path.
+#[must_use]
+pub fn classify(relative: &Path, ignore: &[String]) -> DevAction {
+    let path = relative.to_string_lossy().replace('\\', "/");
+    let file_name = relative
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+
+    if is_editor_artifact(file_name)
+        || ignore
+            .iter()
+            .any(|pattern| is_ignored(pattern, &path, file_name))
+    {
+        return DevAction::Ignore;
+    }
+
+    let extension = relative.extension().and_then(|ext| ext.to_str());
+    let top = relative
+        .components()
+        .next()
+        .and_then(|component| component.as_os_str().to_str());
+
+    match (top, extension) {
+        (Some("src"), Some("rs")) => DevAction::Rebuild("Rust source"),
+        (Some("migrations"), Some("sql")) => DevAction::Rebuild("migration"),
+        (Some("templates"), Some(_)) => DevAction::Reload("template"),
+        (Some("config"), Some("toml")) => DevAction::Reload("config"),
+        (Some("static"), Some(_)) => DevAction::Refresh("static asset"),
+        _ => DevAction::Ignore,
+    }
+}
+
+/// Swap, backup, and hidden files written by editors
+fn is_editor_artifact(file_name: &str) -> bool {
+    file_name.starts_with('.')
+        || file_name.starts_with('#')
+        || file_name.ends_with('~')
+        || Path::new(file_name)
+            .extension()
+            .is_some_and(|ext| ext == "swp" || ext == "swx")
+}
+
+/// Whether an ignore glob matches a path
+fn is_ignored(pattern: &str, path: &str, file_name: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./");
+    if pattern.contains('/') {
+        glob_matches(pattern.as_bytes(), path.as_bytes())
+    } else {
+        glob_matches(pattern.as_bytes(), file_name.as_bytes())
+    }
+}
+
+/// Match a glob where `*` and `?` stay within a path segment and `**`
+/// crosses segments
+fn glob_matches(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&b'*', rest)) if rest.first() == Some(&b'*') => {
+            // `**/` also matches zero directories
+            let rest = &rest[1..];
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=path.len()).any(|i| glob_matches(rest, &path[i..]))
+        }
+        Some((&b'*', rest)) => (0..=path.len())
+            .take_while(|&i| i == 0 || path[i - 1] != b'/')
+            .any(|i| glob_matches(rest, &path[i..])),
+        Some((&b'?', rest)) => {
+            path.first().is_some_and(|&c| c != b'/') && glob_matches(rest, &path[1..])
+        }
+        Some((&c, rest)) => path.first() == Some(&c) && glob_matches(rest, &path[1..]),
+    }
+}
+
+/// Start development server with file watching
+#[derive(Debug, Default)]
+pub struct DevCommand {
+    ignore: Vec<String>,
+}
 
 impl DevCommand {
     /// Create a new command instance
     #[must_use]
     pub const fn new() -> Self {
-        Self
+        Self { ignore: Vec::new() }
+    }
+
+    /// Ignore changes to paths matching these globs
+    #[must_use]
+    pub fn with_ignore(mut self, globs: Vec<String>) -> Self {
+        self.ignore = globs;
+        self
     }
 
     /// Execute the command in the specified directory
@@ -21,8 +167,9 @@ impl DevCommand {
     ///
     /// Returns an error if:
     /// - The project directory doesn't exist or is not a valid project
+    /// - The file watcher cannot be started
     /// - The development server fails to start
-    pub fn execute(path: &Path) -> Result<()> {
+    pub fn execute(&self, path: &Path) -> Result<()> {
         // Canonicalize the path to get absolute path and verify it exists
         let project_dir = path
             .canonicalize()
@@ -44,87 +191,358 @@ impl DevCommand {
         );
         println!();
 
-        // Check if bacon is installed
-        if !Self::is_bacon_installed() {
-            println!(
-                "{} is not installed.",
-                style("bacon").yellow().bold()
-            );
-            println!();
-            println!("Install it with:");
-            println!(
-                "  {} {}",
-                style("$").dim(),
-                style("cargo install bacon").cyan()
-            );
-            println!();
-            println!("For now, starting without hot reload...");
-            println!();
+        let (events_tx, events_rx) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(events_tx).context("Failed to start file watcher")?;
+        for dir in WATCHED_DIRS {
+            let dir_path = project_dir.join(dir);
+            if dir_path.is_dir() {
+                watcher
+                    .watch(&dir_path, RecursiveMode::Recursive)
+                    .with_context(|| format!("Failed to watch {}", dir_path.display()))?;
+                println!("  {} {}/", style("Watching").dim(), dir);
+            }
+        }
+
+        let live_reload = match LiveReloadServer::start() {
+            Ok(live_reload) => {
+                println!("  {} {}", style("Live reload").dim(), live_reload.url);
+                Some(live_reload)
+            }
+            Err(e) => {
+                eprintln!("{} {e:#}", style("Live reload disabled:").yellow());
+                None
+            }
+        };
+        println!();
 
-            return Self::run_without_watch(&project_dir);
+        let mut server = DevServer::new(
+            project_dir.clone(),
+            live_reload.as_ref().map(|live_reload| live_reload.url.clone()),
+        );
+        server.rebuild()?;
+
+        loop {
+            let changes = collect_changes(&events_rx)?;
+            let (mut rebuild, mut reload, mut refresh) = (false, false, false);
+
+            for changed in &changes {
+                let relative = changed.strip_prefix(&project_dir).unwrap_or(changed);
+                let kind = match classify(relative, &self.ignore) {
+                    DevAction::Rebuild(kind) => {
+                        rebuild = true;
+                        kind
+                    }
+                    DevAction::Reload(kind) => {
+                        reload = true;
+                        kind
+                    }
+                    DevAction::Refresh(kind) => {
+                        refresh = true;
+                        kind
+                    }
+                    DevAction::Ignore => continue,
+                };
+                println!(
+                    "  {} {} ({kind})",
+                    style("changed").yellow(),
+                    relative.display()
+                );
+            }
+
+            let reload_browser = if rebuild {
+                println!(
+                    "{}",
+                    style("Rebuilding: Rust sources or migrations changed").cyan()
+                );
+                server.rebuild()?
+            } else if reload {
+                println!(
+                    "{}",
+                    style("Restarting without rebuild: only templates or config changed").cyan()
+                );
+                server.restart()?;
+                true
+            } else {
+                if refresh {
+                    println!(
+                        "{}",
+                        style("Reloading browser: only static assets changed").cyan()
+                    );
+                }
+                refresh
+            };
+
+            if let Some(live_reload) = live_reload.as_ref().filter(|_| reload_browser) {
+                live_reload.reload();
+            }
         }
+    }
+}
 
-        // Run with bacon for hot reload
-        Self::run_with_bacon(&project_dir)
+/// Wait for a change, then gather events until things are quiet
+///
+/// Returns the changed paths; access events are dropped.
+fn collect_changes(events: &Receiver<notify::Result<Event>>) -> Result<BTreeSet<PathBuf>> {
+    let mut paths = BTreeSet::new();
+    let mut add = |event: notify::Result<Event>| match event {
+        Ok(event) => {
+            if matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                paths.extend(event.paths);
+            }
+        }
+        Err(e) => eprintln!("{} {e}", style("Watch error:").yellow()),
+    };
+
+    add(events.recv().context("File watcher stopped")?);
+    loop {
+        match events.recv_timeout(DEBOUNCE) {
+            Ok(event) => add(event),
+            Err(RecvTimeoutError::Timeout) => return Ok(paths),
+            Err(RecvTimeoutError::Disconnected) => anyhow::bail!("File watcher stopped"),
+        }
     }
+}
 
-    /// Check if bacon is installed
-    fn is_bacon_installed() -> bool {
-        Command::new("bacon")
-            .arg("--version")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .map(|status| status.success())
-            .unwrap_or(false)
+/// Live-reload WebSocket telling browsers to reload
+struct LiveReloadServer {
+    url: String,
+    reloads: broadcast::Sender<()>,
+    _runtime: tokio::runtime::Runtime,
+}
+
+impl LiveReloadServer {
+    /// Serve the WebSocket on a free localhost port
+    fn start() -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .context("Failed to start live-reload runtime")?;
+        let listener = runtime
+            .block_on(tokio::net::TcpListener::bind(("127.0.0.1", 0)))
+            .context("Failed to bind live-reload server")?;
+        let addr = listener.local_addr()?;
+
+        let (reloads, _) = broadcast::channel(16);
+        let app = Router::new()
+            .route(LIVE_RELOAD_PATH, get(live_reload_socket))
+            .with_state(reloads.clone());
+        runtime.spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                eprintln!("{} {e}", style("Live reload stopped:").yellow());
+            }
+        });
+
+        Ok(Self {
+            url: format!("ws://{addr}{LIVE_RELOAD_PATH}"),
+            reloads,
+            _runtime: runtime,
+        })
     }
 
-    /// Run with bacon for hot reload
-    fn run_with_bacon(project_dir: &Path) -> Result<()> {
-        println!(
-            "{}",
-            style("Hot reload enabled via bacon. Watching for changes...").green()
-        );
-        println!();
+    /// Tell connected browsers to reload
+    fn reload(&self) {
+        // No receivers just means no browser is open
+        let _ = self.reloads.send(());
+    }
+}
 
-        let mut child = Command::new("bacon")
-            .arg("run")
-            .current_dir(project_dir)
-            .spawn()
-            .context("Failed to start bacon")?;
+/// Send `reload` to the browser for every change
+async fn live_reload_socket(
+    ws: WebSocketUpgrade,
+    State(reloads): State<broadcast::Sender<()>>,
+) -> Response {
+    let mut reloads = reloads.subscribe();
+    ws.on_upgrade(move |mut socket| async move {
+        while let Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) = reloads.recv().await {
+            if socket.send(Message::Text("reload".into())).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// The project's server process
+struct DevServer {
+    project_dir: PathBuf,
+    live_reload_url: Option<String>,
+    child: Option<Child>,
+}
+
+impl DevServer {
+    const fn new(project_dir: PathBuf, live_reload_url: Option<String>) -> Self {
+        Self {
+            project_dir,
+            live_reload_url,
+            child: None,
+        }
+    }
 
-        // Wait for the process to complete
-        let status = child.wait().context("Failed to wait for bacon")?;
+    /// Build with cargo, then restart on success
+    ///
+    /// A failed build leaves the server stopped until the next change.
+    /// Returns whether the server was restarted.
+    fn rebuild(&mut self) -> Result<bool> {
+        self.stop();
 
+        let status = Command::new("cargo")
+            .arg("build")
+            .current_dir(&self.project_dir)
+            .status()
+            .context("Failed to run cargo build")?;
         if !status.success() {
-            anyhow::bail!("Development server exited with error");
+            println!(
+                "{}",
+                style("Build failed. Waiting for changes...").red().bold()
+            );
+            return Ok(false);
         }
 
-        Ok(())
+        self.restart()?;
+        Ok(true)
     }
 
-    /// Run without hot reload (fallback)
-    fn run_without_watch(project_dir: &Path) -> Result<()> {
-        let mut child = Command::new("cargo")
-            .arg("run")
-            .current_dir(project_dir)
+    /// Restart the last built binary
+    ///
+    /// Falls back to `cargo run` if the binary cannot be found.
+    fn restart(&mut self) -> Result<()> {
+        self.stop();
+
+        let mut command = self.binary().map_or_else(
+            || {
+                let mut command = Command::new("cargo");
+                command.arg("run");
+                command
+            },
+            Command::new,
+        );
+        command.current_dir(&self.project_dir);
+        if let Some(url) = &self.live_reload_url {
+            command.env(LIVE_RELOAD_URL_ENV, url);
+        }
+
+        let child = command
             .spawn()
             .context("Failed to start development server")?;
+        self.child = Some(child);
+        Ok(())
+    }
 
-        let status = child
-            .wait()
-            .context("Failed to wait for development server")?;
-
-        if !status.success() {
-            anyhow::bail!("Development server exited with error");
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
         }
+    }
 
-        Ok(())
+    /// Debug binary of the project's package, if it has been built
+    fn binary(&self) -> Option<PathBuf> {
+        let manifest = std::fs::read_to_string(self.project_dir.join("Cargo.toml")).ok()?;
+        let manifest: toml::Value = toml::from_str(&manifest).ok()?;
+        let name = manifest.get("package")?.get("name")?.as_str()?;
+
+        let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+            .map_or_else(|| self.project_dir.join("target"), PathBuf::from);
+        let binary = target_dir
+            .join("debug")
+            .join(format!("{name}{}", std::env::consts::EXE_SUFFIX));
+        binary.is_file().then_some(binary)
     }
 }
 
-impl Default for DevCommand {
-    fn default() -> Self {
-        Self::new()
+impl Drop for DevServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(path: &str) -> DevAction {
+        classify(Path::new(path), &[])
+    }
+
+    #[test]
+    fn test_classify_rust_and_migrations_rebuild() {
+        assert_eq!(action("src/main.rs"), DevAction::Rebuild("Rust source"));
+        assert_eq!(
+            action("src/handlers/posts.rs"),
+            DevAction::Rebuild("Rust source")
+        );
+        assert_eq!(
+            action("migrations/001_create_users.sql"),
+            DevAction::Rebuild("migration")
+        );
+    }
+
+    #[test]
+    fn test_classify_templates_and_config_reload() {
+        assert_eq!(
+            action("templates/posts/index.html"),
+            DevAction::Reload("template")
+        );
+        assert_eq!(
+            action("config/development.toml"),
+            DevAction::Reload("config")
+        );
+    }
+
+    #[test]
+    fn test_classify_ignores_unrelated_files() {
+        assert_eq!(action("src/notes.md"), DevAction::Ignore);
+        assert_eq!(action("README.md"), DevAction::Ignore);
+        assert_eq!(action("target/debug/app"), DevAction::Ignore);
+        assert_eq!(action("public/app.css"), DevAction::Ignore);
+        assert_eq!(action("templates"), DevAction::Ignore);
+    }
+
+    #[test]
+    fn test_classify_static_assets_refresh() {
+        assert_eq!(action("static/app.css"), DevAction::Refresh("static asset"));
+        assert_eq!(
+            action("static/img/logo.svg"),
+            DevAction::Refresh("static asset")
+        );
+    }
+
+    #[test]
+    fn test_classify_ignores_editor_artifacts() {
+        assert_eq!(action("src/.main.rs.swp"), DevAction::Ignore);
+        assert_eq!(action("src/main.rs~"), DevAction::Ignore);
+        assert_eq!(action("templates/#index.html#"), DevAction::Ignore);
+    }
+
+    #[test]
+    fn test_classify_respects_ignore_globs() {
+        let ignore = vec!["src/generated/**".to_string(), "*.tmp.html".to_string()];
+
+        assert_eq!(
+            classify(Path::new("src/generated/schema.rs"), &ignore),
+            DevAction::Ignore
+        );
+        assert_eq!(
+            classify(Path::new("templates/posts/draft.tmp.html"), &ignore),
+            DevAction::Ignore
+        );
+        assert_eq!(
+            classify(Path::new("src/main.rs"), &ignore),
+            DevAction::Rebuild("Rust source")
+        );
+    }
+
+    #[test]
+    fn test_glob_matching() {
+        assert!(glob_matches(b"*.rs", b"main.rs"));
+        assert!(!glob_matches(b"*.rs", b"src/main.rs"));
+        assert!(glob_matches(b"src/**/*.rs", b"src/main.rs"));
+        assert!(glob_matches(b"src/**/*.rs", b"src/a/b/c.rs"));
+        assert!(glob_matches(b"config/?.toml", b"config/a.toml"));
+        assert!(!glob_matches(b"config/?.toml", b"config/ab.toml"));
     }
 }
@@ -0,0 +1,594 @@
+//! Project doctor command
+//!
+//! Diagnoses common configuration and environment problems without starting
+//! the server:
+//! - Project layout and configuration loading
+//! - Insecure security settings
+//! - `DATABASE_URL`, database connectivity, and pending migrations
+//! - Redis connectivity (when `REDIS_URL` is set)
+//! - OAuth2 provider credentials and email (SMTP) environment variables
+//!
+//! Variables are read from the process environment first, then from the
+//! project's `.env` file. Exits non-zero when any critical check fails, so it
+//! can gate CI pipelines.
+
+use crate::htmx::config::{ActonHtmxConfig, SameSitePolicy};
+use anyhow::Result;
+use console::{style, Emoji};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+static PASS: Emoji<'_, '_> = Emoji("✓", "√");
+static WARN: Emoji<'_, '_> = Emoji("⚠", "!");
+static FAIL: Emoji<'_, '_> = Emoji("✗", "x");
+
+/// Timeout for network connectivity checks
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Placeholder fragments left in generated `.env` templates
+const PLACEHOLDERS: &[&str] = &["CHANGE_ME", "your-", "generate-a-secure"];
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// Check passed
+    Pass,
+    /// Non-critical problem
+    Warn,
+    /// Critical problem; the command exits non-zero
+    Fail,
+}
+
+/// Result of a single diagnostic check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// Check name
+    pub name: String,
+    /// Outcome
+    pub status: CheckStatus,
+    /// What was found
+    pub detail: String,
+    /// How to fix a failing or warning check
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Environment lookup: process environment, then the project's `.env`
+#[derive(Debug, Default)]
+pub(crate) struct ProjectEnv {
+    dotenv: HashMap<String, String>,
+}
+
+impl ProjectEnv {
+    pub(crate) fn load(project: &Path) -> Self {
+        let dotenv = fs::read_to_string(project.join(".env"))
+            .map(|content| parse_dotenv(&content))
+            .unwrap_or_default();
+        Self { dotenv }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key)
+            .ok()
+            .or_else(|| self.dotenv.get(key).cloned())
+            .filter(|value| !value.trim().is_empty())
+    }
+}
+
+/// Diagnose project configuration and environment
+pub struct DoctorCommand {
+    path: PathBuf,
+}
+
+impl DoctorCommand {
+    /// Create a doctor command for the project at `path`
+    #[must_use]
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Execute the command
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any critical check fails
+    pub fn execute(&self) -> Result<()> {
+        println!(
+            "{} {}",
+            style("Diagnosing").green().bold(),
+            style(self.path.display()).cyan()
+        );
+        println!();
+
+        let checks = self.run_checks();
+        for check in &checks {
+            let marker = match check.status {
+                CheckStatus::Pass => style(PASS.to_string()).green(),
+                CheckStatus::Warn => style(WARN.to_string()).yellow(),
+                CheckStatus::Fail => style(FAIL.to_string()).red(),
+            };
+            println!("  {marker} {}: {}", style(&check.name).bold(), check.detail);
+            if let Some(fix) = &check.fix {
+                println!("      {} {fix}", style("fix:").dim());
+            }
+        }
+        println!();
+
+        let failed = checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Fail)
+            .count();
+        let warnings = checks
+            .iter()
+            .filter(|c| c.status == CheckStatus::Warn)
+            .count();
+
+        if failed > 0 {
+            println!(
+                "{}",
+                style(format!("{failed} critical check(s) failed, {warnings} warning(s)"))
+                    .red()
+                    .bold()
+            );
+            anyhow::bail!("{failed} critical check(s) failed");
+        }
+
+        println!(
+            "{}",
+            style(format!("All critical checks passed ({warnings} warning(s))"))
+                .green()
+                .bold()
+        );
+        Ok(())
+    }
+
+    /// Run every check in order
+    fn run_checks(&self) -> Vec<Check> {
+        let env = ProjectEnv::load(&self.path);
+        let mut checks = Vec::new();
+
+        let project_name = match project_name(&self.path) {
+            Ok(name) => {
+                checks.push(Check::pass("Project", format!("found Cargo.toml ({name})")));
+                name
+            }
+            Err(detail) => {
+                checks.push(Check::fail(
+                    "Project",
+                    detail,
+                    "Run from the project root or pass its path",
+                ));
+                return checks;
+            }
+        };
+
+        match load_config(&self.path, &project_name) {
+            Ok(config) => {
+                checks.push(Check::pass("Config", "configuration loaded"));
+                checks.extend(security_checks(&config));
+                checks.extend(oauth_checks(&config, &env));
+            }
+            Err(error) => checks.push(Check::fail(
+                "Config",
+                format!("failed to load configuration: {error:#}"),
+                "Fix the syntax or types in config/*.toml and ACTON_* environment variables",
+            )),
+        }
+
+        checks.extend(database_checks(&self.path, &env));
+        checks.push(redis_check(&env));
+        checks.push(email_check(&env));
+        checks
+    }
+}
+
+/// Read the package name from `Cargo.toml`
+fn project_name(project: &Path) -> Result<String, String> {
+    let manifest = fs::read_to_string(project.join("Cargo.toml"))
+        .map_err(|_| "no Cargo.toml found".to_string())?;
+    let manifest: toml::Value =
+        toml::from_str(&manifest).map_err(|e| format!("Cargo.toml is invalid: {e}"))?;
+
+    manifest
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(toml::Value::as_str)
+        .map(ToString::to_string)
+        .ok_or_else(|| "Cargo.toml has no [package] name".to_string())
+}
+
+/// Load `config/{ACTON_ENV}.toml` if present, otherwise the service config
+fn load_config(project: &Path, project_name: &str) -> Result<ActonHtmxConfig> {
+    let env = std::env::var("ACTON_ENV").unwrap_or_else(|_| "development".to_string());
+    let env_file = project.join("config").join(format!("{env}.toml"));
+
+    if env_file.exists() {
+        ActonHtmxConfig::load_from(&env_file.to_string_lossy())
+    } else {
+        ActonHtmxConfig::load_for_service(project_name)
+    }
+}
+
+/// Warn about insecure security settings
+fn security_checks(config: &ActonHtmxConfig) -> Vec<Check> {
+    let security = &config.security;
+    let mut checks = Vec::new();
+
+    if !security.csrf_enabled {
+        checks.push(Check::warn(
+            "CSRF",
+            "CSRF protection is disabled",
+            "Set security.csrf_enabled = true",
+        ));
+    }
+
+    if matches!(security.same_site, SameSitePolicy::None) && !security.secure_cookies {
+        checks.push(Check::fail(
+            "Cookies",
+            "same_site = \"none\" requires secure cookies; browsers will reject the session cookie",
+            "Set security.secure_cookies = true or use same_site = \"lax\"",
+        ));
+    } else if !security.secure_cookies {
+        checks.push(Check::warn(
+            "Cookies",
+            "secure cookies are disabled",
+            "Set security.secure_cookies = true when serving over HTTPS",
+        ));
+    }
+
+    if checks.is_empty() {
+        checks.push(Check::pass("Security", "CSRF and secure cookies enabled"));
+    }
+    checks
+}
+
+/// Check credentials of every configured OAuth2 provider
+fn oauth_checks(config: &ActonHtmxConfig, env: &ProjectEnv) -> Vec<Check> {
+    let providers = [
+        ("google", config.oauth2.google.as_ref()),
+        ("github", config.oauth2.github.as_ref()),
+        ("discord", config.oauth2.discord.as_ref()),
+        ("oidc", config.oauth2.oidc.as_ref()),
+    ];
+
+    providers
+        .into_iter()
+        .filter_map(|(name, provider)| {
+            let prefix = format!("OAUTH2_{}", name.to_uppercase());
+            let value = |field: &str, configured: Option<&String>| {
+                env.get(&format!("{prefix}_{field}"))
+                    .or_else(|| configured.filter(|v| !v.trim().is_empty()).cloned())
+            };
+
+            let client_id = value("CLIENT_ID", provider.map(|p| &p.client_id));
+            if provider.is_none() && client_id.is_none() {
+                return None;
+            }
+
+            let required = [
+                ("CLIENT_ID", client_id),
+                ("CLIENT_SECRET", value("CLIENT_SECRET", provider.map(|p| &p.client_secret))),
+                ("REDIRECT_URI", value("REDIRECT_URI", provider.map(|p| &p.redirect_uri))),
+            ];
+            let missing: Vec<String> = required
+                .iter()
+                .filter(|(_, value)| value.as_deref().is_none_or(is_placeholder))
+                .map(|(field, _)| format!("{prefix}_{field}"))
+                .collect();
+
+            let check_name = format!("OAuth2 ({name})");
+            Some(if missing.is_empty() {
+                Check::pass(check_name, "credentials set")
+            } else {
+                Check::fail(
+                    check_name,
+                    format!("missing or placeholder: {}", missing.join(", ")),
+                    "Set these in the environment or .env (see .env.example)",
+                )
+            })
+        })
+        .collect()
+}
+
+/// Check `DATABASE_URL`, connectivity, and pending migrations
+fn database_checks(project: &Path, env: &ProjectEnv) -> Vec<Check> {
+    let Some(database_url) = env.get("DATABASE_URL") else {
+        return vec![Check::fail(
+            "Database",
+            "DATABASE_URL is not set",
+            "Add DATABASE_URL to .env (e.g. sqlite://data/dev.db or postgres://...)",
+        )];
+    };
+
+    if is_placeholder(&database_url) {
+        return vec![Check::fail(
+            "Database",
+            "DATABASE_URL still contains a placeholder value",
+            "Edit .env and set a real database URL",
+        )];
+    }
+
+    if !project.join("migrations").is_dir() {
+        return vec![Check::pass("Database", "DATABASE_URL set (no migrations directory)")];
+    }
+
+    let output = Command::new("sqlx")
+        .args(["migrate", "info", "--database-url", &database_url])
+        .current_dir(project)
+        .stdin(Stdio::null())
+        .output();
+
+    match output {
+        Err(_) => vec![
+            Check::pass("Database", "DATABASE_URL set"),
+            Check::warn(
+                "Migrations",
+                "sqlx-cli not installed; connectivity and migrations not checked",
+                "cargo install sqlx-cli --no-default-features --features postgres,sqlite",
+            ),
+        ],
+        Ok(output) if !output.status.success() => vec![Check::fail(
+            "Database",
+            format!(
+                "could not connect: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            "Start the database server and verify DATABASE_URL",
+        )],
+        Ok(output) => {
+            let pending = count_pending_migrations(&String::from_utf8_lossy(&output.stdout));
+            let migrations = if pending == 0 {
+                Check::pass("Migrations", "all migrations applied")
+            } else {
+                Check::fail(
+                    "Migrations",
+                    format!("{pending} pending migration(s)"),
+                    "Run `acton-dx htmx db migrate`",
+                )
+            };
+            vec![Check::pass("Database", "connected"), migrations]
+        }
+    }
+}
+
+/// Count pending migrations in `sqlx migrate info` output
+fn count_pending_migrations(info: &str) -> usize {
+    info.lines()
+        .filter(|line| line.contains("/pending"))
+        .count()
+}
+
+/// Check Redis connectivity when `REDIS_URL` is set
+fn redis_check(env: &ProjectEnv) -> Check {
+    let Some(url) = env.get("REDIS_URL") else {
+        return Check::pass("Redis", "not configured (in-memory sessions and jobs)");
+    };
+
+    let Some(address) = redis_address(&url) else {
+        return Check::fail(
+            "Redis",
+            format!("REDIS_URL is not a valid redis:// URL: {url}"),
+            "Use the form redis://[user:password@]host[:port][/db]",
+        );
+    };
+
+    let reachable = address
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .is_some_and(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok());
+
+    if reachable {
+        Check::pass("Redis", format!("reachable at {address}"))
+    } else {
+        Check::fail(
+            "Redis",
+            format!("cannot connect to {address}"),
+            "Start Redis or unset REDIS_URL to use in-memory storage",
+        )
+    }
+}
+
+/// Extract `host:port` from a Redis URL (default port 6379)
+fn redis_address(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("redis://")
+        .or_else(|| url.strip_prefix("rediss://"))?;
+    let authority = rest.split('/').next()?;
+    let host_port = authority.rsplit('@').next()?;
+    if host_port.is_empty() {
+        return None;
+    }
+
+    // Bracketed IPv6 hosts contain ':' themselves
+    let has_port = host_port
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| !host.ends_with(':') && port.parse::<u16>().is_ok());
+    Some(if has_port {
+        host_port.to_string()
+    } else {
+        format!("{host_port}:6379")
+    })
+}
+
+/// Check SMTP environment variables
+fn email_check(env: &ProjectEnv) -> Check {
+    if env.get("SMTP_HOST").is_none() {
+        return Check::warn(
+            "Email",
+            "SMTP_HOST not set; only the console email backend will work",
+            "Set SMTP_HOST, SMTP_USERNAME, and SMTP_PASSWORD to send real email",
+        );
+    }
+
+    let missing: Vec<&str> = ["SMTP_USERNAME", "SMTP_PASSWORD"]
+        .into_iter()
+        .filter(|key| env.get(key).is_none_or(|v| is_placeholder(&v)))
+        .collect();
+
+    if let Some(port) = env.get("SMTP_PORT") {
+        if port.parse::<u16>().is_err() {
+            return Check::fail(
+                "Email",
+                format!("SMTP_PORT is not a valid port: {port}"),
+                "Set SMTP_PORT to a number such as 587",
+            );
+        }
+    }
+
+    if missing.is_empty() {
+        Check::pass("Email", "SMTP configured")
+    } else {
+        Check::fail(
+            "Email",
+            format!("SMTP_HOST is set but {} missing", missing.join(", ")),
+            "Set the remaining SMTP_* variables",
+        )
+    }
+}
+
+fn is_placeholder(value: &str) -> bool {
+    PLACEHOLDERS.iter().any(|p| value.contains(p))
+}
+
+/// Parse `KEY=value` lines of a `.env` file
+fn parse_dotenv(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> ProjectEnv {
+        ProjectEnv {
+            dotenv: pairs
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_dotenv() {
+        let parsed = parse_dotenv(
+            "# comment\nDATABASE_URL=sqlite://dev.db\nexport SECRET=\"a b\"\nEMPTY=\nNAME='x'\n",
+        );
+        assert_eq!(parsed["DATABASE_URL"], "sqlite://dev.db");
+        assert_eq!(parsed["SECRET"], "a b");
+        assert_eq!(parsed["EMPTY"], "");
+        assert_eq!(parsed["NAME"], "x");
+    }
+
+    #[test]
+    fn test_redis_address() {
+        assert_eq!(redis_address("redis://localhost").unwrap(), "localhost:6379");
+        assert_eq!(
+            redis_address("redis://:pw@cache.internal:6380/0").unwrap(),
+            "cache.internal:6380"
+        );
+        assert_eq!(redis_address("rediss://[::1]:7000").unwrap(), "[::1]:7000");
+        assert!(redis_address("http://localhost").is_none());
+    }
+
+    #[test]
+    fn test_count_pending_migrations() {
+        let info = "20240101000000/installed create users\n\
+                    20240201000000/pending add posts\n\
+                    20240301000000/pending add comments\n";
+        assert_eq!(count_pending_migrations(info), 2);
+        assert_eq!(count_pending_migrations(""), 0);
+    }
+
+    #[test]
+    fn test_oauth_checks_flag_missing_and_placeholder_credentials() {
+        let config = ActonHtmxConfig::default();
+        assert!(oauth_checks(&config, &env(&[])).is_empty());
+
+        let checks = oauth_checks(
+            &config,
+            &env(&[
+                ("OAUTH2_GITHUB_CLIENT_ID", "abc"),
+                ("OAUTH2_GITHUB_CLIENT_SECRET", "your-github-client-secret"),
+            ]),
+        );
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert!(checks[0].detail.contains("OAUTH2_GITHUB_CLIENT_SECRET"));
+        assert!(checks[0].detail.contains("OAUTH2_GITHUB_REDIRECT_URI"));
+    }
+
+    #[test]
+    fn test_email_check() {
+        assert_eq!(email_check(&env(&[])).status, CheckStatus::Warn);
+        assert_eq!(
+            email_check(&env(&[("SMTP_HOST", "smtp.example.com")])).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(
+            email_check(&env(&[
+                ("SMTP_HOST", "smtp.example.com"),
+                ("SMTP_USERNAME", "app"),
+                ("SMTP_PASSWORD", "secret"),
+            ]))
+            .status,
+            CheckStatus::Pass
+        );
+    }
+
+    #[test]
+    fn test_missing_project_is_critical() {
+        let dir = tempfile::tempdir().unwrap();
+        let checks = DoctorCommand::new(dir.path().to_path_buf()).run_checks();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert!(DoctorCommand::new(dir.path().to_path_buf()).execute().is_err());
+    }
+}
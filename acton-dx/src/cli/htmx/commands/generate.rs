@@ -1,7 +1,7 @@
 //! Code generation commands (jobs, models, etc.)
 
 use anyhow::{bail, Context, Result};
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
 use console::{style, Emoji};
 use convert_case::{Case, Casing};
 use minijinja::Environment;
@@ -9,6 +9,7 @@ use serde_json::json;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use super::super::scaffold::openapi::{self, ApiResource, API_REGISTRY_DIR};
 use super::super::static_templates::{
     DEPLOYMENT_README, DOCKER_COMPOSE, DOCKERIGNORE, DOCKERFILE, ENV_PRODUCTION, JOB_TEMPLATE,
     NGINX_CONF,
@@ -16,6 +17,25 @@ use super::super::static_templates::{
 
 static SUCCESS: Emoji = Emoji("✓", "√");
 
+/// Output format of `generate openapi`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OpenApiFormat {
+    /// JSON (default)
+    #[default]
+    Json,
+    /// YAML
+    Yaml,
+}
+
+impl OpenApiFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+        }
+    }
+}
+
 /// Code generation commands
 #[derive(Debug, Subcommand)]
 pub enum GenerateCommand {
@@ -66,6 +86,21 @@ pub enum GenerateCommand {
         #[arg(short, long, default_value = ".")]
         output: PathBuf,
     },
+
+    /// Generate an OpenAPI 3.1 document for resources created by `scaffold api`
+    ///
+    /// Examples:
+    ///   acton htmx generate openapi
+    ///   acton htmx generate openapi --format=yaml --out=docs/openapi.yaml
+    Openapi {
+        /// Output file (default: `openapi.json` or `openapi.yaml`)
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OpenApiFormat,
+    },
 }
 
 impl GenerateCommand {
@@ -93,6 +128,7 @@ impl GenerateCommand {
                 deployment_type,
                 output,
             } => Self::generate_deployment(deployment_type, output),
+            Self::Openapi { out, format } => Self::generate_openapi(out.as_deref(), *format),
         }
     }
 
@@ -332,6 +368,59 @@ impl GenerateCommand {
         Ok(())
     }
 
+    fn generate_openapi(out: Option<&Path>, format: OpenApiFormat) -> Result<()> {
+        println!(
+            "\n{} Generating OpenAPI document",
+            style("📘").bold()
+        );
+
+        let resources = ApiResource::load_all(Path::new("."))?;
+        if resources.is_empty() {
+            bail!(
+                "No API resources found in {API_REGISTRY_DIR}. Create one with `acton htmx scaffold api`"
+            );
+        }
+
+        let project_name = Self::get_project_name()?;
+        let version = Self::get_project_version().unwrap_or_else(|| "0.1.0".to_string());
+        let document = openapi::openapi_document(&project_name, &version, &resources)?;
+
+        let content = match format {
+            OpenApiFormat::Json => {
+                let mut json = serde_json::to_string_pretty(&document)?;
+                json.push('\n');
+                json
+            }
+            OpenApiFormat::Yaml => openapi::to_yaml(&document),
+        };
+
+        let default_out = PathBuf::from(format!("openapi.{}", format.extension()));
+        let out = out.unwrap_or(&default_out);
+        if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        fs::write(out, content)
+            .with_context(|| format!("Failed to write file: {}", out.display()))?;
+
+        for resource in &resources {
+            println!("  {} {}", SUCCESS, style(&resource.model).cyan());
+        }
+        println!();
+        println!("  {} Created: {}", SUCCESS, style(out.display()).green());
+        println!();
+
+        Ok(())
+    }
+
+    fn get_project_version() -> Option<String> {
+        let cargo_toml = fs::read_to_string("Cargo.toml").ok()?;
+        cargo_toml.lines().find_map(|line| {
+            line.strip_prefix("version = ")
+                .map(|version| version.trim().trim_matches('"').to_string())
+        })
+    }
+
     fn get_project_name() -> Result<String> {
         // Try to read project name from Cargo.toml
         let cargo_toml = fs::read_to_string("Cargo.toml")
@@ -0,0 +1,301 @@
+//! Application health check command
+//!
+//! Fetches a health endpoint and reads its [`HealthCheckResponse`]:
+//! - Prints a table of components with their status and response time
+//! - Exits non-zero when the application (or the selected component) is
+//!   unhealthy, and zero with a warning when it is degraded
+//!
+//! Endpoints answering with plain text (such as the liveness probe) fall back
+//! to the HTTP status.
+
+use crate::htmx::health::{ComponentHealth, HealthCheckResponse, HealthStatus};
+use anyhow::{bail, Context, Result};
+use console::{style, Emoji};
+
+static CHECKING: Emoji<'_, '_> = Emoji("🔍", ">>>");
+static SUCCESS: Emoji<'_, '_> = Emoji("✓", "√");
+static WARN: Emoji<'_, '_> = Emoji("⚠", "!");
+static ERROR: Emoji<'_, '_> = Emoji("✗", "x");
+
+/// Check the health of a running application
+pub struct HealthCheckCommand {
+    url: String,
+    component: Option<String>,
+    json: bool,
+}
+
+impl HealthCheckCommand {
+    /// Check the health endpoint at `url`
+    #[must_use]
+    pub const fn new(url: String) -> Self {
+        Self {
+            url,
+            component: None,
+            json: false,
+        }
+    }
+
+    /// Judge only the named component
+    #[must_use]
+    pub fn component(mut self, component: Option<String>) -> Self {
+        self.component = component;
+        self
+    }
+
+    /// Print the response body unchanged instead of a table
+    #[must_use]
+    pub const fn json(mut self, enabled: bool) -> Self {
+        self.json = enabled;
+        self
+    }
+
+    /// Execute the health check
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the endpoint cannot be reached, the application
+    /// or selected component is unhealthy, or the component is not reported.
+    pub fn execute(&self) -> Result<()> {
+        if !self.json {
+            println!(
+                "{} Checking application health at: {}",
+                CHECKING,
+                style(&self.url).cyan()
+            );
+            println!();
+        }
+
+        let mut response = match ureq::get(&self.url)
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .call()
+        {
+            Ok(response) => response,
+            Err(e) => {
+                println!("  {ERROR} Health check failed: {e}");
+                println!();
+                println!("Possible issues:");
+                println!("  - Application is not running");
+                println!("  - Wrong URL (check host and port)");
+                println!("  - Health endpoint not configured");
+                println!();
+                bail!("Could not reach health endpoint");
+            }
+        };
+
+        let http_status = response.status().as_u16();
+        let body = response
+            .body_mut()
+            .read_to_string()
+            .context("Failed to read health response")?;
+
+        if self.json {
+            println!("{body}");
+        }
+
+        let Ok(health) = parse_health(&body) else {
+            return self.check_http_status(http_status, &body);
+        };
+
+        let status = evaluate(&health, self.component.as_deref())?;
+        if !self.json {
+            print!("{}", render_table(&health, self.component.as_deref()));
+            println!();
+        }
+
+        let subject = self
+            .component
+            .as_deref()
+            .map_or_else(|| "Application".to_string(), |name| format!("Component '{name}'"));
+        match status {
+            HealthStatus::Healthy => {
+                if !self.json {
+                    println!("  {SUCCESS} {subject} is healthy (version {})", health.version);
+                }
+                Ok(())
+            }
+            HealthStatus::Degraded => {
+                eprintln!("  {WARN} {subject} is degraded but operational");
+                Ok(())
+            }
+            HealthStatus::Unhealthy => {
+                if !self.json {
+                    println!("  {ERROR} {subject} is unhealthy");
+                }
+                bail!("{subject} is unhealthy");
+            }
+        }
+    }
+
+    /// Judge a response that isn't a [`HealthCheckResponse`] by its status
+    fn check_http_status(&self, http_status: u16, body: &str) -> Result<()> {
+        if self.component.is_some() {
+            bail!("Response is not a structured health report; cannot check a component");
+        }
+
+        let healthy = (200..300).contains(&http_status);
+        if !self.json {
+            let (icon, summary) = if healthy {
+                (SUCCESS, "Application is healthy")
+            } else {
+                (ERROR, "Application health check failed")
+            };
+            println!("  {icon} {summary} (HTTP {http_status})");
+            println!();
+            println!("{}", style("Response:").bold());
+            println!("{body}");
+            println!();
+        }
+
+        if healthy {
+            Ok(())
+        } else {
+            bail!("Health check returned status: {http_status}");
+        }
+    }
+}
+
+/// Parse a health endpoint's JSON body
+fn parse_health(body: &str) -> Result<HealthCheckResponse> {
+    serde_json::from_str(body).context("Response is not a health check report")
+}
+
+/// Status deciding the exit code: the overall status, or `component`'s
+fn evaluate(health: &HealthCheckResponse, component: Option<&str>) -> Result<HealthStatus> {
+    let Some(name) = component else {
+        return Ok(health.status);
+    };
+
+    health.components.get(name).map(|c| c.status).with_context(|| {
+        let mut known: Vec<&str> = health.components.keys().map(String::as_str).collect();
+        known.sort_unstable();
+        format!(
+            "Component '{name}' not reported (available: {})",
+            known.join(", ")
+        )
+    })
+}
+
+/// Table of components (or just `component`) sorted by name
+fn render_table(health: &HealthCheckResponse, component: Option<&str>) -> String {
+    let mut components: Vec<(&String, &ComponentHealth)> = health
+        .components
+        .iter()
+        .filter(|(name, _)| component.is_none_or(|selected| selected == name.as_str()))
+        .collect();
+    components.sort_by(|a, b| a.0.cmp(b.0));
+
+    let width = components
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(["COMPONENT".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut table = format!("  {:<width$}  {:<9}  {:>9}  MESSAGE\n", "COMPONENT", "STATUS", "TIME");
+    for (name, health) in components {
+        let time = health
+            .response_time_ms
+            .map_or_else(|| "-".to_string(), |ms| format!("{ms} ms"));
+        let line = format!(
+            "  {name:<width$}  {:<9}  {time:>9}  {}",
+            status_label(health.status),
+            health.message.as_deref().unwrap_or_default()
+        );
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+const fn status_label(status: HealthStatus) -> &'static str {
+    match status {
+        HealthStatus::Healthy => "healthy",
+        HealthStatus::Degraded => "degraded",
+        HealthStatus::Unhealthy => "unhealthy",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEALTHY: &str = r#"{
+        "status": "healthy",
+        "version": "1.2.0",
+        "timestamp": 1700000000,
+        "components": {
+            "database": { "status": "healthy", "response_time_ms": 3 },
+            "jobs": { "status": "healthy", "message": "0 running" }
+        }
+    }"#;
+
+    const DEGRADED: &str = r#"{
+        "status": "degraded",
+        "version": "1.2.0",
+        "timestamp": 1700000000,
+        "components": {
+            "database": { "status": "healthy", "response_time_ms": 3 },
+            "redis": { "status": "degraded", "message": "slow response", "response_time_ms": 412 }
+        }
+    }"#;
+
+    const UNHEALTHY: &str = r#"{
+        "status": "unhealthy",
+        "version": "1.2.0",
+        "timestamp": 1700000000,
+        "components": {
+            "database": { "status": "unhealthy", "message": "connection refused" },
+            "redis": { "status": "healthy", "response_time_ms": 1 }
+        }
+    }"#;
+
+    #[test]
+    fn test_overall_status_from_payloads() {
+        for (body, expected) in [
+            (HEALTHY, HealthStatus::Healthy),
+            (DEGRADED, HealthStatus::Degraded),
+            (UNHEALTHY, HealthStatus::Unhealthy),
+        ] {
+            let health = parse_health(body).unwrap();
+            assert_eq!(evaluate(&health, None).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_component_filter() {
+        let health = parse_health(UNHEALTHY).unwrap();
+        assert_eq!(evaluate(&health, Some("redis")).unwrap(), HealthStatus::Healthy);
+        assert_eq!(
+            evaluate(&health, Some("database")).unwrap(),
+            HealthStatus::Unhealthy
+        );
+
+        let error = evaluate(&health, Some("cache")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Component 'cache' not reported (available: database, redis)"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_is_not_a_report() {
+        assert!(parse_health("OK").is_err());
+    }
+
+    #[test]
+    fn test_table_lists_components_sorted() {
+        let health = parse_health(DEGRADED).unwrap();
+        assert_eq!(
+            render_table(&health, None),
+            "  COMPONENT  STATUS          TIME  MESSAGE\n  \
+               database   healthy         3 ms\n  \
+               redis      degraded      412 ms  slow response\n"
+        );
+        assert_eq!(
+            render_table(&health, Some("redis")).lines().count(),
+            2
+        );
+    }
+}
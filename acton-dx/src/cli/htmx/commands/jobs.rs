@@ -95,6 +95,21 @@ pub enum JobsCommand {
         force: bool,
     },
 
+    /// Retry failed jobs matching a filter
+    RetryFailed {
+        /// Only retry jobs of this type
+        #[arg(short = 't', long)]
+        job_type: Option<String>,
+
+        /// Only retry jobs enqueued at least this many seconds ago
+        #[arg(short, long)]
+        older_than: Option<u64>,
+
+        /// Priority to assign to retried jobs
+        #[arg(short, long)]
+        priority: Option<i32>,
+    },
+
     /// Cancel a running job by ID
     Cancel {
         /// Job ID to cancel
@@ -140,6 +155,11 @@ impl JobsCommand {
                 Ok(())
             }
             Self::RetryAll { force } => Self::retry_all(*force),
+            Self::RetryFailed {
+                job_type,
+                older_than,
+                priority,
+            } => Self::retry_failed(job_type.as_deref(), *older_than, *priority),
             Self::Cancel { job_id } => {
                 Self::cancel(job_id);
                 Ok(())
@@ -360,6 +380,47 @@ impl JobsCommand {
         Ok(())
     }
 
+    fn retry_failed(
+        job_type: Option<&str>,
+        older_than: Option<u64>,
+        priority: Option<i32>,
+    ) -> Result<()> {
+        println!("{INFO} Retrying matching failed jobs...");
+
+        // Call job service API to retry filtered failed jobs
+        let base_url = std::env::var("ACTON_HTMX_API_URL")
+            .unwrap_or_else(|_| "http://localhost:3000".to_string());
+        let url = format!("{base_url}/admin/jobs/retry-failed");
+        let body = serde_json::json!({
+            "job_type": job_type,
+            "older_than_secs": older_than,
+            "priority": priority,
+        })
+        .to_string();
+
+        match ureq::post(&url)
+            .header("content-type", "application/json")
+            .send(&body)
+        {
+            Ok(response) => {
+                let body = response.into_body().read_to_string()?;
+                println!();
+                if let Ok(result) = serde_json::from_str::<RetryAllResponse>(&body) {
+                    println!("{SUCCESS} {}", result.message);
+                } else {
+                    println!("{SUCCESS} Matching failed jobs queued for retry");
+                }
+            }
+            Err(e) => {
+                println!("  {} Failed to retry jobs: {}", style("Error:").red(), e);
+                println!();
+                println!("{INFO} Make sure your Acton HTMX application is running at {}", style(base_url).cyan());
+            }
+        }
+
+        Ok(())
+    }
+
     fn cancel(job_id: &str) {
         println!("{INFO} Cancelling job: {}", style(job_id).cyan());
 
@@ -3,19 +3,29 @@
 pub mod db;
 pub mod deploy;
 pub mod dev;
+#[cfg(feature = "htmx")]
+pub mod doctor;
 pub mod generate;
+#[cfg(feature = "htmx")]
+pub mod health;
 pub mod jobs;
 pub mod new;
 pub mod oauth2;
+pub mod routes;
 pub mod scaffold;
 pub mod templates;
 
 pub use db::DbCommand;
 pub use deploy::DeployCommand;
 pub use dev::DevCommand;
+#[cfg(feature = "htmx")]
+pub use doctor::DoctorCommand;
 pub use generate::GenerateCommand;
+#[cfg(feature = "htmx")]
+pub use health::HealthCheckCommand;
 pub use jobs::JobsCommand;
 pub use new::NewCommand;
 pub use oauth2::OAuth2Command;
+pub use routes::RoutesCommand;
 pub use scaffold::ScaffoldCommand;
 pub use templates::TemplatesCommand;
@@ -0,0 +1,67 @@
+//! Route listing command
+//!
+//! Prints the route table a running application serves at `/_routes`
+//! (mounted with `RouteRegistry::dev_endpoint`, debug builds only).
+
+use anyhow::{bail, Context, Result};
+use console::{style, Emoji};
+
+static INFO: Emoji = Emoji("ℹ", "i");
+
+/// Path of the development route listing
+const ROUTES_ENDPOINT: &str = "/_routes";
+
+/// List the routes of a running application
+pub struct RoutesCommand {
+    base_url: String,
+}
+
+impl RoutesCommand {
+    /// List routes of the application at `base_url`
+    ///
+    /// Defaults to `ACTON_HTMX_API_URL`, then `http://localhost:3000`.
+    #[must_use]
+    pub fn new(base_url: Option<String>) -> Self {
+        let base_url = base_url
+            .or_else(|| std::env::var("ACTON_HTMX_API_URL").ok())
+            .unwrap_or_else(|| "http://localhost:3000".to_string());
+        Self { base_url }
+    }
+
+    /// Fetch and print the route table
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the application cannot be reached or doesn't
+    /// serve the route listing.
+    pub fn execute(&self) -> Result<()> {
+        let url = format!("{}{ROUTES_ENDPOINT}", self.base_url.trim_end_matches('/'));
+
+        let table = match ureq::get(&url).call() {
+            Ok(response) => response
+                .into_body()
+                .read_to_string()
+                .context("Failed to read response")?,
+            Err(ureq::Error::StatusCode(404)) => {
+                println!(
+                    "{INFO} Mount the listing with {} (debug builds only).",
+                    style("router.merge(routes.dev_endpoint())").cyan()
+                );
+                bail!("{url} not found");
+            }
+            Err(e) => {
+                println!(
+                    "{INFO} Make sure your application is running at {}",
+                    style(&self.base_url).cyan()
+                );
+                bail!("Failed to fetch {url}: {e}");
+            }
+        };
+
+        println!();
+        print!("{table}");
+        println!();
+
+        Ok(())
+    }
+}
@@ -20,6 +20,9 @@
 //!   published:boolean \
 //!   published_at:datetime:optional
 //! ```
+//!
+//! `scaffold api` takes the same arguments and generates only the model,
+//! migration, and JSON handlers mounted under `/api/{resource}`.
 
 use super::super::scaffold::{ScaffoldGenerator, TemplateHelpers};
 use anyhow::{Context, Result};
@@ -29,18 +32,53 @@ use std::fs;
 /// CRUD scaffold command
 ///
 /// Generates complete CRUD resources including models, handlers, templates, and tests.
+#[allow(clippy::struct_excessive_bools)] // Independent generation options
 pub struct ScaffoldCommand {
     /// Model name in PascalCase (e.g., `Post`, `UserProfile`)
     model: String,
     /// Field definitions (e.g., `title:string`, `author:references:User`)
     fields: Vec<String>,
+    /// Generate a soft-deleting model with a `deleted_at` column
+    soft_delete: bool,
+    /// Generate `has_many` methods on referenced models
+    with_inverse: bool,
+    /// Generate a JSON API instead of HTMX handlers and templates
+    api: bool,
+    /// Generate a seed file with a sample record
+    seed: bool,
 }
 
 impl ScaffoldCommand {
     /// Create a new ScaffoldCommand with the given model name and field definitions
     #[must_use]
-    pub const fn new(model: String, fields: Vec<String>) -> Self {
-        Self { model, fields }
+    pub const fn new(
+        model: String,
+        fields: Vec<String>,
+        soft_delete: bool,
+        with_inverse: bool,
+    ) -> Self {
+        Self {
+            model,
+            fields,
+            soft_delete,
+            with_inverse,
+            api: false,
+            seed: false,
+        }
+    }
+
+    /// Generate a JSON API resource instead of HTMX CRUD
+    #[must_use]
+    pub const fn api(mut self, enabled: bool) -> Self {
+        self.api = enabled;
+        self
+    }
+
+    /// Also generate a seed file for `db seed`
+    #[must_use]
+    pub const fn seed(mut self, enabled: bool) -> Self {
+        self.seed = enabled;
+        self
     }
 
     /// Execute the scaffold command
@@ -52,9 +90,10 @@ impl ScaffoldCommand {
     /// - Field definitions cannot be parsed
     /// - File operations fail
     pub fn execute(&self) -> Result<()> {
+        let kind = if self.api { "JSON API" } else { "CRUD" };
         println!(
             "\n{} {} {}",
-            style("Scaffolding CRUD for").cyan().bold(),
+            style(format!("Scaffolding {kind} for")).cyan().bold(),
             style(&self.model).green().bold(),
             style("...").cyan().bold()
         );
@@ -69,7 +108,11 @@ impl ScaffoldCommand {
             &self.fields,
             project_root.clone(),
         )
-        .context("Failed to create scaffold generator")?;
+        .context("Failed to create scaffold generator")?
+        .soft_delete(self.soft_delete)
+        .with_inverse(self.with_inverse)
+        .api(self.api)
+        .seed(self.seed);
 
         // Generate files
         let files = generator.generate()
@@ -104,7 +147,7 @@ impl ScaffoldCommand {
         }
 
         println!(
-            "\n{} CRUD scaffold for {} is ready!",
+            "\n{} {kind} scaffold for {} is ready!",
             style("✨").green().bold(),
             style(&self.model).green().bold()
         );
@@ -112,6 +155,11 @@ impl ScaffoldCommand {
         let model_snake = TemplateHelpers::to_snake_case(&self.model);
         let plural = TemplateHelpers::pluralize(&model_snake);
 
+        if self.api {
+            self.print_api_next_steps(&generator, &model_snake, &plural);
+            return Ok(());
+        }
+
         println!("\n{}", style("Next steps:").cyan().bold());
         println!("  1. Add imports to your modules:");
         println!("     {}", style(format!("src/models/mod.rs: pub mod {model_snake};")).yellow());
@@ -124,8 +172,30 @@ impl ScaffoldCommand {
         println!("     {}", style(format!(".route(\"{route_path}/:id\", get(handlers::{plural}::show).put(handlers::{plural}::update).delete(handlers::{plural}::delete))", route_path = TemplateHelpers::to_route_path(&self.model))).yellow());
         println!("     {}", style(format!(".route(\"{route_path}/:id/edit\", get(handlers::{plural}::edit))", route_path = TemplateHelpers::to_route_path(&self.model))).yellow());
         println!("     {}", style(format!(".route(\"{route_path}/search\", get(handlers::{plural}::search))", route_path = TemplateHelpers::to_route_path(&self.model))).yellow());
+        for (route, handler) in generator.nested_routes() {
+            println!("     {}", style(format!(".route(\"{route}\", get(handlers::{plural}::{handler}))")).yellow());
+        }
         println!("  4. Test your application: {}", style("cargo test").yellow());
 
         Ok(())
     }
+
+    /// Print the follow-up steps for a JSON API resource
+    fn print_api_next_steps(&self, generator: &ScaffoldGenerator, model_snake: &str, plural: &str) {
+        let route_path = TemplateHelpers::to_route_path(&self.model);
+
+        println!("\n{}", style("Next steps:").cyan().bold());
+        println!("  1. Add imports to your modules:");
+        println!("     {}", style(format!("src/models/mod.rs: pub mod {model_snake};")).yellow());
+        println!("     {}", style("src/handlers/mod.rs: pub mod api;").yellow());
+        println!("     {}", style(format!("src/handlers/api/mod.rs: pub mod {plural};")).yellow());
+        println!("  2. Run the migration: {}", style("acton htmx db migrate").yellow());
+        println!("  3. Mount the routes in your router:");
+        println!("     {}", style(format!(".nest(\"/api{route_path}\", handlers::api::{plural}::routes())")).yellow());
+        for (route, handler) in generator.nested_routes() {
+            println!("     {}", style(format!(".route(\"{route}\", get(handlers::api::{plural}::{handler}))")).yellow());
+        }
+        println!("  4. Test your application: {}", style("cargo test").yellow());
+        println!("  5. Describe the API: {}", style("acton htmx generate openapi").yellow());
+    }
 }
@@ -43,8 +43,10 @@ const TEMPLATE_NAMES: &[&str] = &[
     "errors/401.html",
     "errors/403.html",
     "errors/404.html",
+    "errors/413.html",
     "errors/422.html",
     "errors/500.html",
+    "errors/503.html",
 ];
 
 /// GitHub base URL for framework templates
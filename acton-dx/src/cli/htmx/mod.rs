@@ -5,10 +5,12 @@
 //! - `dev` - Start development server
 //! - `db` - Database management
 //! - `scaffold` - Generate CRUD resources
-//! - `generate` - Generate code (jobs, deployment)
+//! - `generate` - Generate code (jobs, deployment, OpenAPI)
 //! - `templates` - Manage framework templates
 //! - `jobs` - Manage background jobs
 //! - `deploy` - Deploy to production
+//! - `routes` - List the routes of a running application
+//! - `doctor` - Diagnose configuration and environment issues
 
 pub mod commands;
 pub mod project_template_manager;
@@ -20,8 +22,10 @@ use anyhow::Result;
 use clap::Subcommand;
 use commands::{
     DbCommand, DeployCommand, DevCommand, GenerateCommand, JobsCommand, NewCommand,
-    OAuth2Command, ScaffoldCommand, TemplatesCommand,
+    OAuth2Command, RoutesCommand, ScaffoldCommand, TemplatesCommand,
 };
+#[cfg(feature = "htmx")]
+use commands::{DoctorCommand, HealthCheckCommand};
 
 pub use project_template_manager::ProjectTemplateManager;
 pub use scaffold::{FieldDefinition, FieldType, ScaffoldGenerator, TemplateHelpers};
@@ -53,6 +57,9 @@ pub enum HtmxCommand {
         /// Project directory (defaults to current directory)
         #[arg(default_value = ".")]
         path: std::path::PathBuf,
+        /// Ignore changes to paths matching this glob (repeatable)
+        #[arg(long = "watch-ignore", value_name = "GLOB")]
+        watch_ignore: Vec<String>,
     },
     /// Database management commands
     Db {
@@ -84,11 +91,18 @@ pub enum HtmxCommand {
         #[command(subcommand)]
         command: DeployCommand,
     },
-    /// Check application health
+    /// Check application health (exits non-zero when unhealthy)
+    #[cfg(feature = "htmx")]
     HealthCheck {
         /// Health check URL (default: <http://localhost:8080/health>)
         #[arg(long, default_value = "http://localhost:8080/health")]
         url: String,
+        /// Only check this component (e.g. `database`)
+        #[arg(long)]
+        component: Option<String>,
+        /// Print the response body as-is instead of a table
+        #[arg(long)]
+        json: bool,
     },
     /// Manage framework templates
     Templates {
@@ -96,6 +110,19 @@ pub enum HtmxCommand {
         #[command(subcommand)]
         command: TemplatesCommand,
     },
+    /// List the routes of a running application (served at `/_routes` in debug builds)
+    Routes {
+        /// Application base URL (default: `ACTON_HTMX_API_URL` or <http://localhost:3000>)
+        #[arg(long)]
+        url: Option<String>,
+    },
+    /// Diagnose configuration and environment issues
+    #[cfg(feature = "htmx")]
+    Doctor {
+        /// Project directory (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: std::path::PathBuf,
+    },
 }
 
 /// Scaffold subcommands
@@ -108,6 +135,32 @@ pub enum ScaffoldCommands {
         /// Field definitions (e.g., `title:string`, `author:references:User`)
         #[arg(required = true)]
         fields: Vec<String>,
+        /// Soft-delete records with a `deleted_at` column instead of removing them
+        #[arg(long)]
+        soft_delete: bool,
+        /// Also generate `has_many` listing methods on referenced models
+        #[arg(long)]
+        with_inverse: bool,
+        /// Also generate a seed file with a sample record (`db seed`)
+        #[arg(long)]
+        seed: bool,
+    },
+    /// Generate a JSON API resource (model, migration, handlers; no templates)
+    Api {
+        /// Model name (`PascalCase`, e.g., `Post`, `UserProfile`)
+        model: String,
+        /// Field definitions (e.g., `title:string`, `author:references:User`)
+        #[arg(required = true)]
+        fields: Vec<String>,
+        /// Soft-delete records with a `deleted_at` column instead of removing them
+        #[arg(long)]
+        soft_delete: bool,
+        /// Also generate `has_many` listing methods on referenced models
+        #[arg(long)]
+        with_inverse: bool,
+        /// Also generate a seed file with a sample record (`db seed`)
+        #[arg(long)]
+        seed: bool,
     },
     /// Set up `OAuth2` authentication for a provider
     OAuth2 {
@@ -123,11 +176,31 @@ pub enum DbCommands {
     Migrate,
     /// Reset database (drop, create, migrate)
     Reset,
-    /// Create new migration
+    /// Create new reversible migration (`.up.sql` and `.down.sql`)
     Create {
         /// Migration name
         name: String,
     },
+    /// List applied and pending migrations
+    #[cfg(feature = "htmx")]
+    Status,
+    /// Revert the most recently applied migrations
+    #[cfg(feature = "htmx")]
+    Rollback {
+        /// Number of migrations to revert
+        #[arg(long, default_value = "1")]
+        steps: usize,
+        /// Skip the confirmation prompt outside development
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Run migrations, then seed files in `seeds/` that haven't run yet
+    #[cfg(feature = "htmx")]
+    Seed {
+        /// Drop, recreate, and migrate the database before seeding
+        #[arg(long = "reset-and-seed")]
+        reset: bool,
+    },
 }
 
 /// Run an HTMX CLI command
@@ -141,20 +214,44 @@ pub fn run(command: HtmxCommand) -> Result<()> {
             let cmd = NewCommand::new(name, database)?;
             cmd.execute()?;
         }
-        HtmxCommand::Dev { path } => {
-            DevCommand::execute(&path)?;
+        HtmxCommand::Dev { path, watch_ignore } => {
+            DevCommand::new().with_ignore(watch_ignore).execute(&path)?;
         }
         HtmxCommand::Db { command } => {
             let db_cmd = match command {
                 DbCommands::Migrate => DbCommand::Migrate,
                 DbCommands::Reset => DbCommand::Reset,
                 DbCommands::Create { name } => DbCommand::Create { name },
+                #[cfg(feature = "htmx")]
+                DbCommands::Status => DbCommand::Status,
+                #[cfg(feature = "htmx")]
+                DbCommands::Rollback { steps, force } => DbCommand::Rollback { steps, force },
+                #[cfg(feature = "htmx")]
+                DbCommands::Seed { reset } => DbCommand::Seed { reset },
             };
             db_cmd.execute()?;
         }
         HtmxCommand::Scaffold { command } => match command {
-            ScaffoldCommands::Crud { model, fields } => {
-                let cmd = ScaffoldCommand::new(model, fields);
+            ScaffoldCommands::Crud {
+                model,
+                fields,
+                soft_delete,
+                with_inverse,
+                seed,
+            } => {
+                let cmd = ScaffoldCommand::new(model, fields, soft_delete, with_inverse).seed(seed);
+                cmd.execute()?;
+            }
+            ScaffoldCommands::Api {
+                model,
+                fields,
+                soft_delete,
+                with_inverse,
+                seed,
+            } => {
+                let cmd = ScaffoldCommand::new(model, fields, soft_delete, with_inverse)
+                    .api(true)
+                    .seed(seed);
                 cmd.execute()?;
             }
             ScaffoldCommands::OAuth2 { provider } => {
@@ -171,68 +268,28 @@ pub fn run(command: HtmxCommand) -> Result<()> {
         HtmxCommand::Deploy { command } => {
             command.execute()?;
         }
-        HtmxCommand::HealthCheck { url } => {
-            health_check(&url)?;
+        #[cfg(feature = "htmx")]
+        HtmxCommand::HealthCheck {
+            url,
+            component,
+            json,
+        } => {
+            HealthCheckCommand::new(url)
+                .component(component)
+                .json(json)
+                .execute()?;
         }
         HtmxCommand::Templates { command } => {
             command.execute()?;
         }
-    }
-
-    Ok(())
-}
-
-/// Check application health
-fn health_check(url: &str) -> Result<()> {
-    use console::{style, Emoji};
-
-    static CHECKING: Emoji<'_, '_> = Emoji("🔍", ">>>");
-    static SUCCESS: Emoji<'_, '_> = Emoji("✓", "√");
-    static ERROR: Emoji<'_, '_> = Emoji("✗", "x");
-
-    println!(
-        "{} Checking application health at: {}",
-        CHECKING,
-        style(url).cyan()
-    );
-    println!();
-
-    // Make HTTP request (ureq 3.x call() returns Result with timeout handling)
-    let response = ureq::get(url).call();
-
-    match response {
-        Ok(mut resp) => {
-            let status = resp.status();
-            let body = resp
-                .body_mut()
-                .read_to_string()
-                .unwrap_or_else(|_| "Could not read response".to_string());
-
-            if status == 200 {
-                println!("  {SUCCESS} Application is healthy (HTTP {status})");
-                println!();
-                println!("{}", style("Response:").bold());
-                println!("{body}");
-                println!();
-                Ok(())
-            } else {
-                println!("  {ERROR} Application health check failed (HTTP {status})");
-                println!();
-                println!("{}", style("Response:").bold());
-                println!("{body}");
-                println!();
-                anyhow::bail!("Health check returned status: {status}");
-            }
+        HtmxCommand::Routes { url } => {
+            RoutesCommand::new(url).execute()?;
         }
-        Err(e) => {
-            println!("  {ERROR} Health check failed: {e}");
-            println!();
-            println!("Possible issues:");
-            println!("  - Application is not running");
-            println!("  - Wrong URL (check host and port)");
-            println!("  - Health endpoint not configured");
-            println!();
-            anyhow::bail!("Could not reach health endpoint");
+        #[cfg(feature = "htmx")]
+        HtmxCommand::Doctor { path } => {
+            DoctorCommand::new(path).execute()?;
         }
     }
+
+    Ok(())
 }
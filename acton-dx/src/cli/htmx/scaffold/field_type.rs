@@ -28,7 +28,8 @@
 //! - `array:type` - Array of primitive type
 //!
 //! ## Enums
-//! - `enum:Value1,Value2,Value3` - Enumeration type
+//! - `enum:value1,value2,value3` - Enumeration type, stored as `VARCHAR(50)`
+//!   with a `CHECK` constraint on the `snake_case` values
 //!
 //! ## Modifiers
 //! - `:optional` - Makes field nullable (Option<T>)
@@ -46,7 +47,7 @@
 //! published:boolean         → bool
 //! published_at:datetime:optional → Option<NaiveDateTime>
 //! tags:array:string         → Vec<String>
-//! status:enum:Draft,Published,Archived → Status enum
+//! status:enum:draft,published,archived → Status enum
 //! ```
 
 use anyhow::{anyhow, Result};
@@ -104,13 +105,13 @@ pub enum FieldType {
     /// Array of values
     Array {
         /// Element type
-        element_type: Box<FieldType>,
+        element_type: Box<Self>,
     },
     /// Enum type
     Enum {
         /// Enum name (derived from field name)
         name: String,
-        /// Enum variants
+        /// Enum variants (`PascalCase` Rust names)
         variants: Vec<String>,
     },
 }
@@ -218,29 +219,39 @@ impl FieldDefinition {
             });
         }
 
-        // Check for enum type: enum:Variant1,Variant2,Variant3
+        // Check for enum type: enum:variant1,variant2,variant3
         if let Some(variants_str) = type_str.strip_prefix("enum:") {
-            let variants: Vec<String> = variants_str
+            let names: Vec<&str> = variants_str
                 .split(',')
-                .map(|v| v.trim().to_string())
+                .map(str::trim)
                 .filter(|v| !v.is_empty())
                 .collect();
 
-            if variants.is_empty() {
+            if names.is_empty() {
                 return Err(anyhow!(
-                    "Enum type must have at least one variant. Format: enum:Variant1,Variant2"
+                    "Enum type must have at least one variant. Format: enum:variant1,variant2"
                 ));
             }
 
-            // Validate variant names
-            for variant in &variants {
-                if !variant.chars().next().unwrap_or('0').is_uppercase()
-                    || !variant.chars().all(char::is_alphanumeric)
+            // Validate variant names and convert them to Rust variants
+            let mut variants: Vec<String> = Vec::with_capacity(names.len());
+            for name in names {
+                if !name.chars().next().unwrap_or('0').is_alphabetic()
+                    || !name.chars().all(|c| c.is_alphanumeric() || c == '_')
                 {
                     return Err(anyhow!(
-                        "Invalid enum variant: '{variant}'. Must be PascalCase alphanumeric"
+                        "Invalid enum variant: '{name}'. Must be a valid Rust identifier (alphanumeric + underscore)"
                     ));
                 }
+
+                let variant = super::helpers::TemplateHelpers::to_pascal_case(name);
+                if variant == "Self" {
+                    return Err(anyhow!("Invalid enum variant: '{name}'. 'Self' is reserved"));
+                }
+                if variants.contains(&variant) {
+                    return Err(anyhow!("Duplicate enum variant: '{name}'"));
+                }
+                variants.push(variant);
             }
 
             // Generate enum name from field name (convert to PascalCase)
@@ -326,6 +337,32 @@ impl FieldDefinition {
     pub fn sql_type(&self) -> String {
         self.field_type.sql_type()
     }
+
+    /// Get the `CHECK` constraint for this field's column, if it has one
+    ///
+    /// Enum columns only accept the variants' stored values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use acton_htmx::scaffold::field_type::FieldDefinition;
+    /// let field = FieldDefinition::parse("status:enum:draft,published").unwrap();
+    /// assert_eq!(
+    ///     field.check_constraint().unwrap(),
+    ///     "CHECK (status IN ('draft', 'published'))"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn check_constraint(&self) -> Option<String> {
+        let values = self.field_type.enum_values()?;
+        let column = super::helpers::TemplateHelpers::to_snake_case(&self.name);
+        let allowed = values
+            .iter()
+            .map(|value| format!("'{value}'"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(format!("CHECK ({column} IN ({allowed}))"))
+    }
 }
 
 impl FieldType {
@@ -378,6 +415,22 @@ impl FieldType {
             Self::Enum { .. } => "VARCHAR(50)".to_string(), // Store as string by default
         }
     }
+
+    /// Get the stored (`snake_case`) value of each enum variant
+    ///
+    /// Returns `None` for non-enum types.
+    #[must_use]
+    pub fn enum_values(&self) -> Option<Vec<String>> {
+        match self {
+            Self::Enum { variants, .. } => Some(
+                variants
+                    .iter()
+                    .map(|variant| super::helpers::TemplateHelpers::to_snake_case(variant))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for FieldDefinition {
@@ -511,6 +564,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_lowercase_enum() {
+        let field = FieldDefinition::parse("status:enum:draft,published,in_review").unwrap();
+        assert_eq!(
+            field.field_type,
+            FieldType::Enum {
+                name: "Status".to_string(),
+                variants: vec![
+                    "Draft".to_string(),
+                    "Published".to_string(),
+                    "InReview".to_string()
+                ],
+            }
+        );
+        assert_eq!(field.rust_type(), "Status");
+        assert_eq!(field.sql_type(), "VARCHAR(50)");
+        assert_eq!(
+            field.field_type.enum_values().unwrap(),
+            vec!["draft", "published", "in_review"]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_enum_variants() {
+        assert!(FieldDefinition::parse("status:enum:").is_err());
+        assert!(FieldDefinition::parse("status:enum:draft,1st").is_err());
+        assert!(FieldDefinition::parse("status:enum:draft,is-live").is_err());
+        assert!(FieldDefinition::parse("status:enum:draft,self").is_err());
+        assert!(FieldDefinition::parse("status:enum:draft,Draft").is_err());
+    }
+
+    #[test]
+    fn test_enum_check_constraint() {
+        let field = FieldDefinition::parse("status:enum:Draft,Published,Archived").unwrap();
+        assert_eq!(
+            field.check_constraint().unwrap(),
+            "CHECK (status IN ('draft', 'published', 'archived'))"
+        );
+
+        let field = FieldDefinition::parse("title:string").unwrap();
+        assert!(field.check_constraint().is_none());
+    }
+
     #[test]
     fn test_parse_all_primitive_types() {
         let test_cases = vec![
@@ -12,21 +12,33 @@
 
 use super::field_type::FieldDefinition;
 use super::helpers::TemplateHelpers;
+use super::openapi::ApiResource;
 use super::templates::TemplateRegistry;
 use anyhow::{Context, Result};
 use std::path::PathBuf;
 
 /// CRUD scaffold generator
 #[allow(dead_code)] // Fields will be used in Week 2-3 implementation
+#[allow(clippy::struct_excessive_bools)] // Independent generation options
 pub struct ScaffoldGenerator {
     /// Model name (e.g., "Post", "`UserProfile`")
     model_name: String,
     /// Field definitions
     fields: Vec<FieldDefinition>,
+    /// Field specifications the definitions were parsed from
+    field_specs: Vec<String>,
     /// Template registry
     templates: TemplateRegistry,
     /// Project root directory
     project_root: PathBuf,
+    /// Add a `deleted_at` column and soft-delete instead of deleting
+    soft_delete: bool,
+    /// Add `has_many` methods to referenced models
+    with_inverse: bool,
+    /// Generate JSON API handlers instead of forms, HTMX handlers, and templates
+    api: bool,
+    /// Generate a seed file with a sample record
+    seed: bool,
 }
 
 impl ScaffoldGenerator {
@@ -71,36 +83,126 @@ impl ScaffoldGenerator {
         Ok(Self {
             model_name,
             fields,
+            field_specs: field_specs.to_vec(),
             templates,
             project_root,
+            soft_delete: false,
+            with_inverse: false,
+            api: false,
+            seed: false,
         })
     }
 
+    /// Generate soft-deleting models (a nullable `deleted_at` column)
+    #[must_use]
+    pub const fn soft_delete(mut self, enabled: bool) -> Self {
+        self.soft_delete = enabled;
+        self
+    }
+
+    /// Generate `has_many` listing methods on referenced models
+    #[must_use]
+    pub const fn with_inverse(mut self, enabled: bool) -> Self {
+        self.with_inverse = enabled;
+        self
+    }
+
+    /// Generate a JSON API resource under `/api` (no forms or templates)
+    #[must_use]
+    pub const fn api(mut self, enabled: bool) -> Self {
+        self.api = enabled;
+        self
+    }
+
+    /// Generate a seed file (`seeds/{timestamp}_{table}.sql`) for `db seed`
+    #[must_use]
+    pub const fn seed(mut self, enabled: bool) -> Self {
+        self.seed = enabled;
+        self
+    }
+
+    /// Nested index routes for `references` fields, with their handler names
+    ///
+    /// For `author:references:User` on `Post` this is
+    /// `("/users/:id/posts", "list_for_author")`.
+    #[must_use]
+    pub fn nested_routes(&self) -> Vec<(String, String)> {
+        use super::field_type::FieldType;
+
+        self.fields
+            .iter()
+            .filter_map(|field| match &field.field_type {
+                FieldType::Reference { model } => Some((
+                    self.nested_route(model),
+                    Self::nested_list_fn(&field.name),
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Route listing this model's records under a `parent` record
+    fn nested_route(&self, parent: &str) -> String {
+        let parent = TemplateHelpers::to_route_path(parent);
+        let child = TemplateHelpers::to_route_path(&self.model_name);
+        if self.api {
+            format!("/api{parent}/{{id}}{child}")
+        } else {
+            format!("{parent}/:id{child}")
+        }
+    }
+
+    /// Name of the function listing records by the `field` reference
+    fn nested_list_fn(field: &str) -> String {
+        format!("list_for_{}", TemplateHelpers::to_snake_case(field))
+    }
+
     /// Generate all CRUD files
     ///
     /// This orchestrates the generation of:
     /// 1. Model file (src/models/{model}.rs)
-    /// 2. Migration file (migrations/{timestamp}_create_{table}.sql)
+    /// 2. Migration files (migrations/{timestamp}_{table}.up.sql and .down.sql)
     /// 3. Form file (src/forms/{model}.rs)
     /// 4. Handler file (src/handlers/{model}s.rs)
     /// 5. Template files (templates/{model}s/*.html)
     /// 6. Test file (`tests/{model}s_test.rs`)
     ///
+    /// With [`api`](Self::api) only the model, migration, JSON handlers
+    /// (`src/handlers/api/{model}s.rs`), and the resource's OpenAPI record
+    /// (`.acton/api/{model}.json`) are generated.
+    ///
+    /// With [`seed`](Self::seed) a seed file is generated as well.
+    ///
     /// # Errors
     ///
     /// Returns an error if template rendering fails for any file
     pub fn generate(&self) -> Result<Vec<GeneratedFile>> {
-        let mut generated_files = vec![
-            self.generate_model()?,
-            self.generate_migration()?,
+        if self.api {
+            let mut generated_files = vec![self.generate_model()?];
+            generated_files.extend(self.generate_migration()?);
+            generated_files.push(self.generate_api_handlers()?);
+            generated_files.push(self.generate_api_record()?);
+            if self.seed {
+                generated_files.push(self.generate_seed()?);
+            }
+            return Ok(generated_files);
+        }
+
+        let mut generated_files = vec![self.generate_model()?];
+        generated_files.extend(self.generate_migration()?);
+        generated_files.extend([
             self.generate_forms()?,
             self.generate_handlers()?,
             self.generate_tests()?,
-        ];
+        ]);
 
         // Add all template files
         generated_files.extend(self.generate_templates()?);
 
+        if self.seed {
+            generated_files.push(self.generate_seed()?);
+        }
+
         Ok(generated_files)
     }
 
@@ -112,6 +214,7 @@ impl ScaffoldGenerator {
 
         let table_name = TemplateHelpers::to_table_name(&self.model_name);
         let model_snake = TemplateHelpers::to_snake_case(&self.model_name);
+        let route_path = TemplateHelpers::to_route_path(&self.model_name);
 
         let enums = self.collect_enums();
         let (relations, foreign_keys) = self.collect_relations();
@@ -128,13 +231,21 @@ impl ScaffoldGenerator {
         let has_decimal = self.fields.iter().any(|f| matches!(f.field_type, FieldType::Decimal));
         let has_uuid = self.fields.iter().any(|f| matches!(f.field_type, FieldType::Uuid));
         let has_enum = !enums.is_empty();
+        let search_columns: Vec<String> = self
+            .fields
+            .iter()
+            .filter(|f| matches!(f.field_type, FieldType::String | FieldType::Text))
+            .map(|f| TemplateHelpers::to_snake_case(&f.name))
+            .collect();
 
         serde_json::json!({
             "model_name": self.model_name,
             "model_snake": model_snake,
             "model_plural": TemplateHelpers::pluralize(&self.model_name),
+            "model_plural_snake": TemplateHelpers::pluralize(&model_snake),
             "table_name": table_name,
-            "route_path": TemplateHelpers::to_route_path(&self.model_name),
+            "api_route_path": format!("/api{route_path}"),
+            "route_path": route_path,
             "title": TemplateHelpers::to_title(&self.model_name),
             "plural_title": TemplateHelpers::to_plural_title(&self.model_name),
             "fields": fields,
@@ -147,6 +258,10 @@ impl ScaffoldGenerator {
             "has_decimal": has_decimal,
             "has_uuid": has_uuid,
             "has_enum": has_enum,
+            "search_columns": search_columns,
+            "soft_delete": self.soft_delete,
+            "with_inverse": self.with_inverse,
+            "api": self.api,
         })
     }
 
@@ -161,6 +276,7 @@ impl ScaffoldGenerator {
                     Some(serde_json::json!({
                         "name": name,
                         "variants": variants,
+                        "values": field.field_type.enum_values(),
                     }))
                 } else {
                     None
@@ -179,14 +295,31 @@ impl ScaffoldGenerator {
         for field in &self.fields {
             if let FieldType::Reference { model } = &field.field_type {
                 let referenced_table = TemplateHelpers::to_table_name(model);
+                let referenced_module = TemplateHelpers::to_snake_case(model);
                 let relation_name = TemplateHelpers::to_pascal_case(&field.name);
                 let field_column = TemplateHelpers::to_foreign_key(&field.name, model);
 
+                // `user:references:User` on Post gives `User::posts`, while
+                // `author:references:User` gives `User::posts_by_author`
+                let children = TemplateHelpers::to_table_name(&self.model_name);
+                let accessor = TemplateHelpers::to_snake_case(&field.name);
+                let inverse_method = if accessor == referenced_module {
+                    children
+                } else {
+                    format!("{children}_by_{accessor}")
+                };
+
                 relations.push(serde_json::json!({
                     "field_name": field.name,
                     "relation_name": relation_name,
+                    "referenced_model": model,
+                    "referenced_module": referenced_module,
                     "referenced_table": referenced_table,
                     "field_column": field_column,
+                    "accessor": accessor,
+                    "inverse_method": inverse_method,
+                    "list_fn": Self::nested_list_fn(&field.name),
+                    "nested_route": self.nested_route(model),
                 }));
 
                 foreign_keys.push(serde_json::json!({
@@ -244,6 +377,20 @@ impl ScaffoldGenerator {
 
                 let validations = Self::get_validations(f);
                 let default_value = Self::get_default_value(f);
+                let enum_options: Vec<serde_json::Value> = match &f.field_type {
+                    FieldType::Enum { name, variants } => variants
+                        .iter()
+                        .zip(f.field_type.enum_values().unwrap_or_default())
+                        .map(|(variant, value)| {
+                            serde_json::json!({
+                                "path": format!("{name}::{variant}"),
+                                "value": value,
+                                "label": TemplateHelpers::to_title(variant),
+                            })
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
 
                 serde_json::json!({
                     "name": f.name,
@@ -255,6 +402,9 @@ impl ScaffoldGenerator {
                     "indexed": f.indexed,
                     "validations": validations,
                     "default_value": default_value,
+                    "seed_value": Self::get_seed_value(f),
+                    "check_constraint": f.check_constraint(),
+                    "enum_options": enum_options,
                 })
             })
             .collect()
@@ -310,7 +460,39 @@ impl ScaffoldGenerator {
         }
     }
 
-    /// Generate `SeaORM` model file
+    /// Get a SQL literal for the sample record in the seed file
+    fn get_seed_value(field: &FieldDefinition) -> String {
+        use super::field_type::FieldType;
+
+        match &field.field_type {
+            FieldType::String | FieldType::Text if field.name.to_lowercase().contains("email") => {
+                "'user@example.com'".to_string()
+            }
+            FieldType::String | FieldType::Text => {
+                format!("'Sample {}'", TemplateHelpers::to_title(&field.name).to_lowercase())
+            }
+            FieldType::Integer | FieldType::BigInt => "1".to_string(),
+            FieldType::Boolean => "FALSE".to_string(),
+            FieldType::Float | FieldType::Double => "1.0".to_string(),
+            FieldType::Decimal => "1.00".to_string(),
+            FieldType::Date => "'2025-01-01'".to_string(),
+            FieldType::DateTime | FieldType::Timestamp => "'2025-01-01 00:00:00'".to_string(),
+            // An empty JSON object, or an empty Postgres array
+            FieldType::Json | FieldType::Array { .. } => "'{}'".to_string(),
+            FieldType::Uuid => "'00000000-0000-0000-0000-000000000001'".to_string(),
+            FieldType::Reference { model } => format!(
+                "(SELECT id FROM {} ORDER BY id LIMIT 1)",
+                TemplateHelpers::to_table_name(model)
+            ),
+            FieldType::Enum { .. } => field
+                .field_type
+                .enum_values()
+                .and_then(|values| values.into_iter().next())
+                .map_or_else(|| "''".to_string(), |value| format!("'{value}'")),
+        }
+    }
+
+    /// Generate model file with its repository
     fn generate_model(&self) -> Result<GeneratedFile> {
         let metadata = self.model_metadata();
         let content = self.templates.render("model", &metadata)?;
@@ -322,24 +504,34 @@ impl ScaffoldGenerator {
         Ok(GeneratedFile {
             path,
             content,
-            description: format!("SeaORM model for {model_name}"),
+            description: format!("Model and repository for {model_name}"),
         })
     }
 
     /// Generate database migration file
-    fn generate_migration(&self) -> Result<GeneratedFile> {
+    ///
+    /// Returns the reversible pair: the `.up.sql` migration and the
+    /// `.down.sql` file `db rollback` runs to revert it.
+    fn generate_migration(&self) -> Result<Vec<GeneratedFile>> {
         let metadata = self.model_metadata();
-        let content = self.templates.render("migration", &metadata)?;
+        let up = self.templates.render("migration", &metadata)?;
+        let down = self.templates.render("migration_down", &metadata)?;
 
         let table_name = TemplateHelpers::to_table_name(&self.model_name);
         let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
-        let path = PathBuf::from(format!("migrations/{timestamp}_{table_name}.sql"));
 
-        Ok(GeneratedFile {
-            path,
-            content,
-            description: format!("Database migration for {table_name} table"),
-        })
+        Ok(vec![
+            GeneratedFile {
+                path: PathBuf::from(format!("migrations/{timestamp}_{table_name}.up.sql")),
+                content: up,
+                description: format!("Database migration for {table_name} table"),
+            },
+            GeneratedFile {
+                path: PathBuf::from(format!("migrations/{timestamp}_{table_name}.down.sql")),
+                content: down,
+                description: format!("Rollback migration for {table_name} table"),
+            },
+        ])
     }
 
     /// Generate form struct file
@@ -375,6 +567,57 @@ impl ScaffoldGenerator {
         })
     }
 
+    /// Generate JSON API handler file
+    fn generate_api_handlers(&self) -> Result<GeneratedFile> {
+        let metadata = self.model_metadata();
+        let content = self.templates.render("api_handler", &metadata)?;
+
+        let model_snake = TemplateHelpers::to_snake_case(&self.model_name);
+        let plural = TemplateHelpers::pluralize(&model_snake);
+        let path = PathBuf::from(format!("src/handlers/api/{plural}.rs"));
+
+        let model_name = &self.model_name;
+        Ok(GeneratedFile {
+            path,
+            content,
+            description: format!("JSON API handlers for {model_name}"),
+        })
+    }
+
+    /// Generate a seed file inserting one sample record
+    fn generate_seed(&self) -> Result<GeneratedFile> {
+        let metadata = self.model_metadata();
+        let content = self.templates.render("seed", &metadata)?;
+
+        let table_name = TemplateHelpers::to_table_name(&self.model_name);
+        let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+        let path = PathBuf::from(format!("seeds/{timestamp}_{table_name}.sql"));
+
+        Ok(GeneratedFile {
+            path,
+            content,
+            description: format!("Seed data for {table_name} table"),
+        })
+    }
+
+    /// Generate the record `generate openapi` builds the API document from
+    fn generate_api_record(&self) -> Result<GeneratedFile> {
+        let resource = ApiResource {
+            model: self.model_name.clone(),
+            fields: self.field_specs.clone(),
+            soft_delete: self.soft_delete,
+        };
+        let mut content = serde_json::to_string_pretty(&resource)?;
+        content.push('\n');
+
+        let model_name = &self.model_name;
+        Ok(GeneratedFile {
+            path: resource.registry_path(),
+            content,
+            description: format!("OpenAPI record for {model_name}"),
+        })
+    }
+
     /// Generate integration tests
     fn generate_tests(&self) -> Result<GeneratedFile> {
         let metadata = self.model_metadata();
@@ -551,6 +794,11 @@ mod tests {
         assert!(generated.content.contains("pub struct Model"));
         assert!(generated.content.contains("pub title: String"));
         assert!(generated.content.contains("pub content: String"));
+        assert!(generated.content.contains("impl Record<Postgres> for Model"));
+        assert!(generated.content.contains("pub type PostRepository = SqlxRepository<Model, Postgres>;"));
+        assert!(generated
+            .content
+            .contains("WHERE title ILIKE $1 OR content ILIKE $1 ORDER BY id"));
     }
 
     #[test]
@@ -565,10 +813,18 @@ mod tests {
         .unwrap();
 
         let generated = generator.generate_migration().unwrap();
-        assert!(generated.path.to_string_lossy().contains("posts.sql"));
-        assert!(generated.content.contains("CREATE TABLE posts"));
-        assert!(generated.content.contains("title VARCHAR(255) NOT NULL"));
-        assert!(generated.content.contains("published BOOLEAN NOT NULL"));
+        let (up, down) = (&generated[0], &generated[1]);
+        assert!(up.path.to_string_lossy().ends_with("_posts.up.sql"));
+        assert!(up.content.contains("CREATE TABLE posts"));
+        assert!(up.content.contains("title VARCHAR(255) NOT NULL"));
+        assert!(up.content.contains("published BOOLEAN NOT NULL"));
+
+        // Same version, so sqlx pairs them as one reversible migration
+        assert_eq!(
+            down.path.to_string_lossy(),
+            up.path.to_string_lossy().replace(".up.sql", ".down.sql")
+        );
+        assert!(down.content.contains("DROP TABLE IF EXISTS posts;"));
     }
 
     #[test]
@@ -594,7 +850,7 @@ mod tests {
         let temp_dir = tempdir().unwrap();
         let fields = vec![
             "title:string".to_string(),
-            "status:enum:Draft,Published,Archived".to_string(),
+            "status:enum:draft,published,archived".to_string(),
         ];
         let generator = ScaffoldGenerator::new(
             "Post".to_string(),
@@ -604,10 +860,28 @@ mod tests {
         .unwrap();
 
         let generated = generator.generate_model().unwrap();
-        assert!(generated.content.contains("pub enum Status"));
-        assert!(generated.content.contains("Draft"));
-        assert!(generated.content.contains("Published"));
-        assert!(generated.content.contains("Archived"));
+        assert!(generated.content.contains(
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]\n\
+             #[serde(rename_all = \"snake_case\")]\n\
+             #[sqlx(type_name = \"VARCHAR\", rename_all = \"snake_case\")]\n\
+             pub enum Status {\n    Draft,\n    Published,\n    Archived,\n}"
+        ));
+        assert!(generated.content.contains("pub status: Status,"));
+
+        let migration = generator.generate_migration().unwrap().remove(0);
+        assert!(migration.content.contains(
+            "status VARCHAR(50) NOT NULL CHECK (status IN ('draft', 'published', 'archived')),"
+        ));
+
+        let templates = generator.generate_templates().unwrap();
+        let form = templates
+            .iter()
+            .find(|t| t.path.to_string_lossy().contains("form.html"))
+            .unwrap();
+        assert!(form.content.contains(r#"<select name="status""#));
+        assert!(form.content.contains(r#"<option value="published""#));
+        assert!(form.content.contains("record.status == post::Status::Archived"));
+        assert!(form.content.contains(">Archived</option>"));
     }
 
     #[test]
@@ -628,12 +902,86 @@ mod tests {
         let generated = generator.generate_model().unwrap();
         assert!(generated.content.contains("pub author: UserId"));
         assert!(generated.content.contains("pub post: PostId"));
+        assert!(generated.content.contains("#[sqlx(rename = \"author_id\")]"));
+        assert!(generated.content.contains("\"author_id\","));
 
-        let migration = generator.generate_migration().unwrap();
+        let migration = generator.generate_migration().unwrap().remove(0);
         assert!(migration.content.contains("FOREIGN KEY (author_id)"));
         assert!(migration.content.contains("REFERENCES users(id)"));
     }
 
+    #[test]
+    fn test_generate_relations() {
+        let temp_dir = tempdir().unwrap();
+        let fields = vec![
+            "content:text".to_string(),
+            "author:references:User".to_string(),
+            "post:references:Post".to_string(),
+        ];
+        let generator = ScaffoldGenerator::new(
+            "Comment".to_string(),
+            &fields,
+            temp_dir.path().to_path_buf(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            generator.nested_routes(),
+            vec![
+                ("/users/:id/comments".to_string(), "list_for_author".to_string()),
+                ("/posts/:id/comments".to_string(), "list_for_post".to_string()),
+            ]
+        );
+
+        let model = generator.generate_model().unwrap().content;
+        // belongs_to accessor with its join
+        assert!(model.contains(
+            "pub async fn author(\n        &self,\n        pool: &PgPool,\n    ) -> Result<Option<super::user::Model>, sqlx::Error>"
+        ));
+        assert!(model.contains(
+            "\"SELECT users.* FROM users \\\n             JOIN comments ON comments.author_id = users.id \\\n             WHERE comments.id = $1\""
+        ));
+        assert!(model.contains("pub async fn list_for_post(\n    repo: &CommentRepository,\n    post_id: i64,"));
+        assert!(model.contains("WHERE post_id = $1"));
+        // has_many only with --with-inverse
+        assert!(!model.contains("impl super::user::Model"));
+
+        let handlers = generator.generate_handlers().unwrap().content;
+        assert!(handlers.contains("/// Serves `GET /users/:id/comments` as an HTMX partial of rows."));
+        assert!(handlers.contains("pub async fn list_for_author(\n    State(state): State<ActonHtmxState>,\n    Path(id): Path<i64>,"));
+        assert!(handlers.contains("comment::list_for_author(&repo, id,"));
+    }
+
+    #[test]
+    fn test_generate_inverse_relations() {
+        let temp_dir = tempdir().unwrap();
+        let fields = vec![
+            "body:text".to_string(),
+            "author:references:User".to_string(),
+            "post:references:Post".to_string(),
+        ];
+        let generator = ScaffoldGenerator::new(
+            "Comment".to_string(),
+            &fields,
+            temp_dir.path().to_path_buf(),
+        )
+        .unwrap()
+        .with_inverse(true);
+
+        let model = generator.generate_model().unwrap().content;
+        assert!(model.contains("impl super::user::Model {"));
+        assert!(model.contains(
+            "pub async fn comments_by_author(&self, pool: &PgPool) -> Result<Vec<Model>, sqlx::Error>"
+        ));
+        assert!(model.contains("impl super::post::Model {"));
+        assert!(model.contains(
+            "pub async fn comments(&self, pool: &PgPool) -> Result<Vec<Model>, sqlx::Error>"
+        ));
+        assert!(model.contains(
+            "JOIN posts ON posts.id = comments.post_id \\\n             WHERE posts.id = $1"
+        ));
+    }
+
     #[test]
     fn test_generate_with_unique_and_indexed() {
         let temp_dir = tempdir().unwrap();
@@ -649,14 +997,43 @@ mod tests {
         .unwrap();
 
         let generated = generator.generate_model().unwrap();
-        assert!(generated.content.contains("#[sea_orm(unique)]"));
-        assert!(generated.content.contains("#[sea_orm(indexed)]"));
+        assert!(generated.content.contains("\"email\","));
+        assert!(generated.content.contains("\"username\","));
 
-        let migration = generator.generate_migration().unwrap();
+        let migration = generator.generate_migration().unwrap().remove(0);
         assert!(migration.content.contains("users_email_unique"));
         assert!(migration.content.contains("users_username_idx"));
     }
 
+    #[test]
+    fn test_generate_with_soft_delete() {
+        let temp_dir = tempdir().unwrap();
+        let fields = vec!["title:string".to_string()];
+        let generator = ScaffoldGenerator::new(
+            "Post".to_string(),
+            &fields,
+            temp_dir.path().to_path_buf(),
+        )
+        .unwrap();
+
+        let plain = generator.generate_migration().unwrap().remove(0);
+        assert!(!plain.content.contains("deleted_at"));
+
+        let generator = generator.soft_delete(true);
+        let migration = generator.generate_migration().unwrap().remove(0);
+        assert!(migration.content.contains("deleted_at TIMESTAMPTZ\n);"));
+
+        let model = generator.generate_model().unwrap();
+        assert!(model.content.contains("pub deleted_at: Option<DateTime<Utc>>"));
+        assert!(model
+            .content
+            .contains("const SOFT_DELETE_COLUMN: Option<&'static str> = Some(\"deleted_at\");"));
+        assert!(model.content.contains("WHERE deleted_at IS NULL AND (title ILIKE $1)"));
+
+        let handlers = generator.generate_handlers().unwrap();
+        assert!(handlers.content.contains(".soft_delete(id).await?"));
+    }
+
     #[test]
     fn test_complete_generation() {
         let temp_dir = tempdir().unwrap();
@@ -674,11 +1051,11 @@ mod tests {
         .unwrap();
 
         let files = generator.generate().unwrap();
-        assert_eq!(files.len(), 10); // model, migration, form, handler, test, + 5 templates
+        assert_eq!(files.len(), 11); // model, up/down migrations, form, handler, test, + 5 templates
 
         // Verify key files
         assert!(files.iter().any(|f| f.path.to_string_lossy().contains("models/post.rs")));
-        assert!(files.iter().any(|f| f.path.to_string_lossy().contains("migrations/") && f.path.to_string_lossy().contains("posts.sql")));
+        assert!(files.iter().any(|f| f.path.to_string_lossy().contains("migrations/") && f.path.to_string_lossy().contains("posts.up.sql")));
         assert!(files.iter().any(|f| f.path.to_string_lossy().contains("forms/post.rs")));
         assert!(files.iter().any(|f| f.path.to_string_lossy().contains("handlers/posts.rs")));
         assert!(files.iter().any(|f| f.path.to_string_lossy().contains("tests/posts_test.rs")));
@@ -710,6 +1087,125 @@ mod tests {
         assert!(generated.content.contains("pub async fn update("));
         assert!(generated.content.contains("pub async fn delete("));
         assert!(generated.content.contains("pub async fn search("));
+        assert!(generated
+            .content
+            .contains("post::repository(state.database_pool())"));
+        assert!(!generated.content.contains("Entity::"));
+    }
+
+    #[test]
+    fn test_api_generation() {
+        let temp_dir = tempdir().unwrap();
+        let fields = vec![
+            "title:string".to_string(),
+            "status:enum:draft,published".to_string(),
+            "author:references:User".to_string(),
+        ];
+        let generator = ScaffoldGenerator::new(
+            "Post".to_string(),
+            &fields,
+            temp_dir.path().to_path_buf(),
+        )
+        .unwrap()
+        .api(true);
+
+        let files = generator.generate().unwrap();
+        let paths: Vec<_> = files
+            .iter()
+            .map(|f| f.path.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(files.len(), 5); // model, up/down migrations, API handler, OpenAPI record
+        assert!(paths.iter().any(|p| p == "src/models/post.rs"));
+        assert!(paths.iter().any(|p| p.starts_with("migrations/") && p.ends_with("posts.up.sql")));
+        assert!(paths.iter().any(|p| p.starts_with("migrations/") && p.ends_with("posts.down.sql")));
+        assert!(paths.iter().any(|p| p == "src/handlers/api/posts.rs"));
+        assert!(paths.iter().any(|p| p == ".acton/api/post.json"));
+        assert!(!paths.iter().any(|p| p.starts_with("templates/") || p.starts_with("src/forms/")));
+
+        let handlers = &files[3].content;
+        assert!(handlers.contains("use crate::models::post::{self, Status};"));
+        assert!(handlers.contains("pub async fn list_for_author("));
+        assert!(handlers.contains("Serves `GET /api/users/{id}/posts`."));
+
+        let model = &files[0].content;
+        assert!(!model.contains("crate::forms"));
+        assert!(!model.contains("from_form"));
+
+        assert_eq!(
+            generator.nested_routes(),
+            vec![("/api/users/{id}/posts".to_string(), "list_for_author".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_seed_generation() {
+        let temp_dir = tempdir().unwrap();
+        let fields = vec![
+            "title:string".to_string(),
+            "author_email:string".to_string(),
+            "status:enum:draft,published".to_string(),
+            "author:references:User".to_string(),
+        ];
+        let generator = ScaffoldGenerator::new(
+            "Post".to_string(),
+            &fields,
+            temp_dir.path().to_path_buf(),
+        )
+        .unwrap();
+
+        let files = generator.generate().unwrap();
+        assert!(!files.iter().any(|f| f.path.starts_with("seeds")));
+
+        let files = generator.seed(true).generate().unwrap();
+        let seed = files.iter().find(|f| f.path.starts_with("seeds")).unwrap();
+        assert!(seed.path.to_string_lossy().ends_with("_posts.sql"));
+        assert!(seed
+            .content
+            .contains("INSERT INTO posts (title, author_email, status, author_id)"));
+        assert!(seed.content.contains(
+            "SELECT 'Sample title', 'user@example.com', 'draft', (SELECT id FROM users ORDER BY id LIMIT 1)"
+        ));
+        assert!(seed.content.contains("WHERE NOT EXISTS (SELECT 1 FROM posts);"));
+    }
+
+    #[test]
+    fn test_api_handler_signatures() {
+        let temp_dir = tempdir().unwrap();
+        let fields = vec!["title:string".to_string(), "email:string:optional".to_string()];
+        let generator = ScaffoldGenerator::new(
+            "Post".to_string(),
+            &fields,
+            temp_dir.path().to_path_buf(),
+        )
+        .unwrap()
+        .api(true);
+
+        let handlers = generator.generate_api_handlers().unwrap().content;
+        assert!(handlers.contains(
+            ".route(\"/\", get(list).post(create))\n        .route(\"/{id}\", get(show).put(update).delete(delete))"
+        ));
+        assert!(handlers.contains(
+            "pub async fn list(\n    State(state): State<ActonHtmxState>,\n    Query(params): Query<ListParams>,\n) -> Result<Json<Page<post::Model>>, ApiError>"
+        ));
+        assert!(handlers.contains(
+            "pub async fn show(\n    State(state): State<ActonHtmxState>,\n    Path(id): Path<i64>,\n) -> Result<Json<post::Model>, ApiError>"
+        ));
+        assert!(handlers.contains(
+            "pub async fn create(\n    State(state): State<ActonHtmxState>,\n    Json(input): Json<PostInput>,\n) -> Result<(StatusCode, Json<post::Model>), ApiError>"
+        ));
+        assert!(handlers.contains(
+            "pub async fn update(\n    State(state): State<ActonHtmxState>,\n    Path(id): Path<i64>,\n    Json(input): Json<PostInput>,\n) -> Result<Json<post::Model>, ApiError>"
+        ));
+        assert!(handlers.contains(
+            "pub async fn delete(\n    State(state): State<ActonHtmxState>,\n    Path(id): Path<i64>,\n) -> Result<StatusCode, ApiError>"
+        ));
+        assert!(handlers.contains(".found_or_404(\"Post\")?"));
+        assert!(handlers.contains("input.validate().map_err(ApiError::invalid)?;"));
+        assert!(handlers.contains("    #[validate(length(min = 1, max = 255))]\n    pub title: String,"));
+        assert!(handlers.contains("    #[validate(email)]\n    pub email: Option<String>,"));
+        assert!(handlers.contains("self.0.into_response_for(ResponseFormat::Json)"));
+        assert!(!handlers.contains("Template"));
+        assert!(!handlers.contains("HxRequest"));
     }
 
     #[test]
@@ -7,8 +7,10 @@
 pub mod field_type;
 pub mod generator;
 pub mod helpers;
+pub mod openapi;
 pub mod templates;
 
 pub use field_type::{FieldDefinition, FieldType};
 pub use generator::ScaffoldGenerator;
 pub use helpers::TemplateHelpers;
+pub use openapi::{openapi_document, ApiResource, API_REGISTRY_DIR};
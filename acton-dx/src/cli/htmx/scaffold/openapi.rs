@@ -0,0 +1,686 @@
+//! OpenAPI documents for scaffolded JSON API resources
+//!
+//! `scaffold api` records each resource in [`API_REGISTRY_DIR`] as an
+//! [`ApiResource`]. [`openapi_document`] turns those records into an
+//! OpenAPI 3.1 document describing:
+//! - The CRUD paths under `/api/{resource}` and nested `references` listings
+//! - Record, input, and page schemas derived from the field definitions
+//! - The `{ "error": "..." }` responses returned by the generated handlers
+
+use super::field_type::{FieldDefinition, FieldType};
+use super::generator::ScaffoldGenerator;
+use super::helpers::TemplateHelpers;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory, relative to the project root, holding API resource records
+pub const API_REGISTRY_DIR: &str = ".acton/api";
+
+/// OpenAPI version of generated documents
+const OPENAPI_VERSION: &str = "3.1.0";
+
+/// A scaffolded JSON API resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiResource {
+    /// Model name (`PascalCase`)
+    pub model: String,
+    /// Field specifications as given to `scaffold api`
+    pub fields: Vec<String>,
+    /// Whether records are soft-deleted
+    #[serde(default)]
+    pub soft_delete: bool,
+}
+
+impl ApiResource {
+    /// Path of this resource's record, relative to the project root
+    #[must_use]
+    pub fn registry_path(&self) -> PathBuf {
+        Path::new(API_REGISTRY_DIR).join(format!(
+            "{}.json",
+            TemplateHelpers::to_snake_case(&self.model)
+        ))
+    }
+
+    /// Load every resource recorded in `project_root`, sorted by model name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a record cannot be read or parsed.
+    pub fn load_all(project_root: &Path) -> Result<Vec<Self>> {
+        let dir = project_root.join(API_REGISTRY_DIR);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut resources = Vec::new();
+        for entry in fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let resource: Self = serde_json::from_str(&content)
+                    .with_context(|| format!("Invalid API resource record: {}", path.display()))?;
+                resources.push(resource);
+            }
+        }
+
+        resources.sort_by(|a, b| a.model.cmp(&b.model));
+        Ok(resources)
+    }
+
+    fn field_definitions(&self) -> Result<Vec<FieldDefinition>> {
+        self.fields
+            .iter()
+            .map(|spec| FieldDefinition::parse(spec))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("Invalid field definitions for {}", self.model))
+    }
+}
+
+/// Build an OpenAPI 3.1 document for `resources`
+///
+/// # Errors
+///
+/// Returns an error if a resource's field definitions cannot be parsed.
+pub fn openapi_document(title: &str, version: &str, resources: &[ApiResource]) -> Result<Value> {
+    // Keep insertion order (serde_json's `preserve_order` is enabled), so
+    // the document follows the order of `resources`
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+    let mut tags = Vec::new();
+
+    schemas.insert(
+        "Error".to_string(),
+        json!({
+            "type": "object",
+            "properties": { "error": { "type": "string" } },
+            "required": ["error"],
+        }),
+    );
+
+    for resource in resources {
+        let fields = resource.field_definitions()?;
+        let model = &resource.model;
+        let tag = TemplateHelpers::to_plural_title(model);
+        let collection = format!("/api{}", TemplateHelpers::to_route_path(model));
+
+        schemas.insert(model.clone(), record_schema(&fields, resource.soft_delete));
+        schemas.insert(format!("{model}Input"), input_schema(&fields));
+        schemas.insert(format!("{model}Page"), page_schema(model));
+
+        let names = OperationNames::new(model);
+        paths.insert(collection.clone(), collection_path(model, &tag, &names));
+        paths.insert(format!("{collection}/{{id}}"), member_path(model, &tag, &names));
+
+        let generator = ScaffoldGenerator::new(model.clone(), &resource.fields, PathBuf::new())?
+            .api(true);
+        for (route, list_fn) in generator.nested_routes() {
+            let operation_id = format!("{}_{}", names.plural, list_fn.replacen("list_", "", 1));
+            paths.insert(
+                route,
+                json!({ "get": list_operation(model, &tag, &operation_id, true) }),
+            );
+        }
+
+        tags.push(json!({ "name": tag }));
+    }
+
+    Ok(json!({
+        "openapi": OPENAPI_VERSION,
+        "info": { "title": title, "version": version },
+        "tags": tags,
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "responses": {
+                "NotFound": error_response("Record not found"),
+                "UnprocessableEntity": error_response("Validation failed"),
+                "InternalServerError": error_response("Internal server error"),
+            },
+        },
+    }))
+}
+
+/// `operationId`s of one resource
+struct OperationNames {
+    singular: String,
+    plural: String,
+}
+
+impl OperationNames {
+    fn new(model: &str) -> Self {
+        let singular = TemplateHelpers::to_snake_case(model);
+        Self {
+            plural: TemplateHelpers::pluralize(&singular),
+            singular,
+        }
+    }
+}
+
+fn collection_path(model: &str, tag: &str, names: &OperationNames) -> Value {
+    json!({
+        "get": list_operation(model, tag, &format!("list_{}", names.plural), false),
+        "post": {
+            "tags": [tag],
+            "operationId": format!("create_{}", names.singular),
+            "summary": format!("Create {}", TemplateHelpers::to_title(model)),
+            "requestBody": input_body(model),
+            "responses": {
+                "201": record_response(model, "Created"),
+                "422": { "$ref": "#/components/responses/UnprocessableEntity" },
+                "500": { "$ref": "#/components/responses/InternalServerError" },
+            },
+        },
+    })
+}
+
+fn member_path(model: &str, tag: &str, names: &OperationNames) -> Value {
+    let title = TemplateHelpers::to_title(model);
+    json!({
+        "parameters": [id_parameter()],
+        "get": {
+            "tags": [tag],
+            "operationId": format!("show_{}", names.singular),
+            "summary": format!("Show {title}"),
+            "responses": {
+                "200": record_response(model, "OK"),
+                "404": { "$ref": "#/components/responses/NotFound" },
+                "500": { "$ref": "#/components/responses/InternalServerError" },
+            },
+        },
+        "put": {
+            "tags": [tag],
+            "operationId": format!("update_{}", names.singular),
+            "summary": format!("Update {title}"),
+            "requestBody": input_body(model),
+            "responses": {
+                "200": record_response(model, "Updated"),
+                "404": { "$ref": "#/components/responses/NotFound" },
+                "422": { "$ref": "#/components/responses/UnprocessableEntity" },
+                "500": { "$ref": "#/components/responses/InternalServerError" },
+            },
+        },
+        "delete": {
+            "tags": [tag],
+            "operationId": format!("delete_{}", names.singular),
+            "summary": format!("Delete {title}"),
+            "responses": {
+                "204": { "description": "Deleted" },
+                "404": { "$ref": "#/components/responses/NotFound" },
+                "500": { "$ref": "#/components/responses/InternalServerError" },
+            },
+        },
+    })
+}
+
+fn list_operation(model: &str, tag: &str, operation_id: &str, nested: bool) -> Value {
+    let mut parameters = Vec::new();
+    if nested {
+        parameters.push(id_parameter());
+    }
+    parameters.push(query_parameter("page", "Page number, starting at 1"));
+    parameters.push(query_parameter("per_page", "Records per page (at most 100)"));
+
+    json!({
+        "tags": [tag],
+        "operationId": operation_id,
+        "summary": format!("List {}", TemplateHelpers::to_plural_title(model)),
+        "parameters": parameters,
+        "responses": {
+            "200": {
+                "description": "One page of records",
+                "content": {
+                    "application/json": {
+                        "schema": { "$ref": format!("#/components/schemas/{model}Page") },
+                    },
+                },
+            },
+            "500": { "$ref": "#/components/responses/InternalServerError" },
+        },
+    })
+}
+
+fn id_parameter() -> Value {
+    json!({
+        "name": "id",
+        "in": "path",
+        "required": true,
+        "schema": { "type": "integer", "format": "int64" },
+    })
+}
+
+fn query_parameter(name: &str, description: &str) -> Value {
+    json!({
+        "name": name,
+        "in": "query",
+        "required": false,
+        "description": description,
+        "schema": { "type": "integer", "minimum": 1 },
+    })
+}
+
+fn input_body(model: &str) -> Value {
+    json!({
+        "required": true,
+        "content": {
+            "application/json": {
+                "schema": { "$ref": format!("#/components/schemas/{model}Input") },
+            },
+        },
+    })
+}
+
+fn record_response(model: &str, description: &str) -> Value {
+    json!({
+        "description": description,
+        "content": {
+            "application/json": {
+                "schema": { "$ref": format!("#/components/schemas/{model}") },
+            },
+        },
+    })
+}
+
+fn error_response(description: &str) -> Value {
+    json!({
+        "description": description,
+        "content": {
+            "application/json": {
+                "schema": { "$ref": "#/components/schemas/Error" },
+            },
+        },
+    })
+}
+
+/// Schema of a stored record, as returned by the handlers
+fn record_schema(fields: &[FieldDefinition], soft_delete: bool) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![json!("id")];
+    properties.insert("id".to_string(), json!({ "type": "integer", "format": "int64" }));
+
+    for field in fields {
+        properties.insert(field.name.clone(), field_schema(field));
+        if !field.optional {
+            required.push(json!(field.name));
+        }
+    }
+
+    for timestamp in ["created_at", "updated_at"] {
+        properties.insert(
+            timestamp.to_string(),
+            json!({ "type": "string", "format": "date-time" }),
+        );
+        required.push(json!(timestamp));
+    }
+    if soft_delete {
+        properties.insert(
+            "deleted_at".to_string(),
+            json!({ "type": ["string", "null"], "format": "date-time" }),
+        );
+        required.push(json!("deleted_at"));
+    }
+
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+/// Schema of the create/update request body
+fn input_schema(fields: &[FieldDefinition]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+
+    for field in fields {
+        let mut schema = field_schema(field);
+        // Mirror the `#[validate]` attributes of the generated input struct
+        if !field.optional && matches!(field.field_type, FieldType::String | FieldType::Text) {
+            schema["minLength"] = json!(1);
+        }
+        if field.name.to_lowercase().contains("email") {
+            schema["format"] = json!("email");
+        }
+
+        properties.insert(field.name.clone(), schema);
+        if !field.optional {
+            required.push(json!(field.name));
+        }
+    }
+
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+fn page_schema(model: &str) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "items": {
+                "type": "array",
+                "items": { "$ref": format!("#/components/schemas/{model}") },
+            },
+            "page": { "type": "integer", "minimum": 1 },
+            "page_size": { "type": "integer", "minimum": 0 },
+            "total": { "type": "integer", "minimum": 0 },
+        },
+        "required": ["items", "page", "page_size", "total"],
+    })
+}
+
+/// Schema of one field, nullable when the field is optional
+fn field_schema(field: &FieldDefinition) -> Value {
+    let mut schema = type_schema(&field.field_type);
+    if field.optional {
+        if let Some(Value::String(kind)) = schema.get("type").cloned() {
+            schema["type"] = json!([kind, "null"]);
+        }
+        if let Some(Value::Array(values)) = schema.get_mut("enum") {
+            values.push(Value::Null);
+        }
+    }
+    schema
+}
+
+fn type_schema(field_type: &FieldType) -> Value {
+    match field_type {
+        FieldType::String => json!({ "type": "string", "maxLength": 255 }),
+        FieldType::Text => json!({ "type": "string" }),
+        FieldType::Integer => json!({ "type": "integer", "format": "int32" }),
+        FieldType::BigInt => json!({ "type": "integer", "format": "int64" }),
+        FieldType::Boolean => json!({ "type": "boolean" }),
+        FieldType::Float => json!({ "type": "number", "format": "float" }),
+        FieldType::Double => json!({ "type": "number", "format": "double" }),
+        // rust_decimal serializes as a string to keep precision
+        FieldType::Decimal => json!({ "type": "string", "format": "decimal" }),
+        FieldType::Date => json!({ "type": "string", "format": "date" }),
+        FieldType::DateTime => json!({
+            "type": "string",
+            "description": "Date and time without a UTC offset",
+        }),
+        FieldType::Timestamp => json!({ "type": "string", "format": "date-time" }),
+        FieldType::Json => json!({}),
+        FieldType::Uuid => json!({ "type": "string", "format": "uuid" }),
+        FieldType::Reference { model } => json!({
+            "type": "integer",
+            "format": "int64",
+            "description": format!("Id of the referenced {model}"),
+        }),
+        FieldType::Array { element_type } => json!({
+            "type": "array",
+            "items": type_schema(element_type),
+        }),
+        FieldType::Enum { .. } => json!({
+            "type": "string",
+            "enum": field_type.enum_values().unwrap_or_default(),
+        }),
+    }
+}
+
+/// Render a JSON value as YAML
+///
+/// Strings are always double-quoted (JSON escapes are valid YAML), so the
+/// output needs no YAML library and round-trips exactly.
+#[must_use]
+pub fn to_yaml(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_mapping(&mut out, map, 0),
+        Value::Array(items) if !items.is_empty() => write_sequence(&mut out, items, 0),
+        scalar => {
+            out.push_str(&yaml_scalar(scalar));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn write_mapping(out: &mut String, map: &Map<String, Value>, indent: usize) {
+    for (key, value) in map {
+        let key = yaml_key(key);
+        match value {
+            Value::Object(inner) if !inner.is_empty() => {
+                let _ = writeln!(out, "{:indent$}{key}:", "");
+                write_mapping(out, inner, indent + 2);
+            }
+            Value::Array(items) if !items.is_empty() => {
+                let _ = writeln!(out, "{:indent$}{key}:", "");
+                write_sequence(out, items, indent + 2);
+            }
+            scalar => {
+                let _ = writeln!(out, "{:indent$}{key}: {}", "", yaml_scalar(scalar));
+            }
+        }
+    }
+}
+
+fn write_sequence(out: &mut String, items: &[Value], indent: usize) {
+    for item in items {
+        let mut nested = String::new();
+        match item {
+            Value::Object(map) if !map.is_empty() => write_mapping(&mut nested, map, indent + 2),
+            Value::Array(inner) if !inner.is_empty() => {
+                write_sequence(&mut nested, inner, indent + 2);
+            }
+            scalar => {
+                let _ = writeln!(out, "{:indent$}- {}", "", yaml_scalar(scalar));
+                continue;
+            }
+        }
+        // Put the first nested line on the dash line
+        let _ = write!(out, "{:indent$}- {}", "", &nested[indent + 2..]);
+    }
+}
+
+fn yaml_key(key: &str) -> String {
+    let plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if plain {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Object(_) => "{}".to_string(),
+        Value::Array(_) => "[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use tempfile::tempdir;
+
+    /// Check the structural rules of the OpenAPI 3.1 schema that the
+    /// generated documents rely on
+    fn assert_valid_openapi(doc: &Value) {
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3.1."));
+        assert!(doc["info"]["title"].is_string());
+        assert!(doc["info"]["version"].is_string());
+
+        let mut operation_ids = HashSet::new();
+        for (path, item) in doc["paths"].as_object().unwrap() {
+            assert!(path.starts_with('/'), "path must start with /: {path}");
+            let templated: Vec<&str> = path
+                .split('/')
+                .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+                .collect();
+
+            for (method, operation) in item.as_object().unwrap() {
+                if method == "parameters" {
+                    continue;
+                }
+                assert!(
+                    ["get", "put", "post", "delete", "options", "head", "patch", "trace"]
+                        .contains(&method.as_str()),
+                    "unknown method {method}"
+                );
+                let id = operation["operationId"].as_str().unwrap();
+                assert!(operation_ids.insert(id.to_string()), "duplicate operationId {id}");
+
+                let responses = operation["responses"].as_object().unwrap();
+                assert!(!responses.is_empty());
+                for (status, response) in responses {
+                    assert!(status == "default" || status.parse::<u16>().is_ok());
+                    assert!(response.get("$ref").is_some() || response["description"].is_string());
+                }
+
+                // Every path template variable is declared as a path parameter
+                let parameters: Vec<&Value> = item
+                    .get("parameters")
+                    .into_iter()
+                    .chain(operation.get("parameters"))
+                    .flat_map(|list| list.as_array().unwrap())
+                    .collect();
+                for name in &templated {
+                    assert!(parameters.iter().any(|p| p["in"] == "path"
+                        && p["name"] == *name
+                        && p["required"] == true));
+                }
+            }
+        }
+
+        assert_refs_resolve(doc, doc);
+    }
+
+    fn assert_refs_resolve(root: &Value, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    let pointer = reference.strip_prefix('#').unwrap();
+                    assert!(root.pointer(pointer).is_some(), "unresolved $ref {reference}");
+                }
+                map.values().for_each(|v| assert_refs_resolve(root, v));
+            }
+            Value::Array(items) => items.iter().for_each(|v| assert_refs_resolve(root, v)),
+            _ => {}
+        }
+    }
+
+    /// A project with `User` and `Post` scaffolded as APIs
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        let resources = [
+            ("User", vec!["email:string:unique", "name:string:optional"]),
+            (
+                "Post",
+                vec![
+                    "title:string",
+                    "status:enum:draft,published",
+                    "author:references:User",
+                ],
+            ),
+        ];
+        for (model, fields) in resources {
+            let fields: Vec<String> = fields.into_iter().map(String::from).collect();
+            let generator =
+                ScaffoldGenerator::new(model.to_string(), &fields, dir.path().to_path_buf())
+                    .unwrap()
+                    .api(true);
+            for file in generator.generate().unwrap() {
+                let path = dir.path().join(&file.path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, file.content).unwrap();
+            }
+        }
+        dir
+    }
+
+    #[test]
+    fn test_two_model_document_is_valid() {
+        let dir = fixture();
+        let resources = ApiResource::load_all(dir.path()).unwrap();
+        assert_eq!(
+            resources.iter().map(|r| r.model.as_str()).collect::<Vec<_>>(),
+            ["Post", "User"]
+        );
+
+        let doc = openapi_document("blog", "0.1.0", &resources).unwrap();
+        assert_valid_openapi(&doc);
+
+        let paths = doc["paths"].as_object().unwrap();
+        for path in [
+            "/api/posts",
+            "/api/posts/{id}",
+            "/api/users",
+            "/api/users/{id}",
+            "/api/users/{id}/posts",
+        ] {
+            assert!(paths.contains_key(path), "missing {path}");
+        }
+        assert_eq!(
+            doc["paths"]["/api/users/{id}/posts"]["get"]["operationId"],
+            "posts_for_author"
+        );
+        assert_eq!(
+            doc["paths"]["/api/posts"]["post"]["responses"]["422"]["$ref"],
+            "#/components/responses/UnprocessableEntity"
+        );
+    }
+
+    #[test]
+    fn test_schemas_follow_field_definitions() {
+        let dir = fixture();
+        let resources = ApiResource::load_all(dir.path()).unwrap();
+        let doc = openapi_document("blog", "0.1.0", &resources).unwrap();
+        let schemas = &doc["components"]["schemas"];
+
+        assert_eq!(schemas["Post"]["properties"]["status"]["enum"], json!(["draft", "published"]));
+        assert_eq!(schemas["Post"]["properties"]["author"]["format"], "int64");
+        assert_eq!(
+            schemas["Post"]["required"],
+            json!(["id", "title", "status", "author", "created_at", "updated_at"])
+        );
+        assert_eq!(schemas["User"]["properties"]["name"]["type"], json!(["string", "null"]));
+
+        let input = &schemas["UserInput"];
+        assert_eq!(input["required"], json!(["email"]));
+        assert_eq!(input["properties"]["email"]["format"], "email");
+        assert_eq!(input["properties"]["email"]["minLength"], 1);
+        assert!(input["properties"].get("id").is_none());
+
+        assert_eq!(
+            schemas["PostPage"]["properties"]["items"]["items"]["$ref"],
+            "#/components/schemas/Post"
+        );
+    }
+
+    #[test]
+    fn test_yaml_output() {
+        let value = json!({
+            "openapi": "3.1.0",
+            "paths": {
+                "/api/posts/{id}": {
+                    "parameters": [{ "name": "id", "in": "path" }],
+                },
+            },
+            "required": ["id", "title"],
+            "empty": {},
+        });
+
+        assert_eq!(
+            to_yaml(&value),
+            "openapi: \"3.1.0\"\n\
+             paths:\n  \
+               \"/api/posts/{id}\":\n    \
+                 parameters:\n      \
+                   - name: \"id\"\n        \
+                     in: \"path\"\n\
+             required:\n  \
+               - \"id\"\n  \
+               - \"title\"\n\
+             empty: {}\n"
+        );
+    }
+}
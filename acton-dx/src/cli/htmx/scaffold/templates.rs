@@ -1,7 +1,7 @@
 //! Template definitions for scaffold code generation
 //!
 //! This module contains MiniJinja templates for generating:
-//! - `SeaORM` models with validation
+//! - sqlx models with a generic repository
 //! - Database migrations
 //! - Form structs
 //! - HTMX handlers
@@ -41,8 +41,11 @@ impl TemplateRegistry {
         let mut templates = HashMap::new();
         templates.insert("model".to_string(), MODEL_TEMPLATE.to_string());
         templates.insert("migration".to_string(), MIGRATION_TEMPLATE.to_string());
+        templates.insert("migration_down".to_string(), MIGRATION_DOWN_TEMPLATE.to_string());
+        templates.insert("seed".to_string(), SEED_TEMPLATE.to_string());
         templates.insert("form".to_string(), FORM_TEMPLATE.to_string());
         templates.insert("handler".to_string(), HANDLER_TEMPLATE.to_string());
+        templates.insert("api_handler".to_string(), API_HANDLER_TEMPLATE.to_string());
         templates.insert("test".to_string(), TEST_TEMPLATE.to_string());
 
         Ok(Self {
@@ -76,15 +79,20 @@ impl Default for TemplateRegistry {
 
 // Template constants - will be populated in Week 2-3
 
-/// `SeaORM` model template
+/// Model template: a sqlx row type with a repository
 pub const MODEL_TEMPLATE: &str = r#"//! {{ model_name }} model
 //!
 //! Generated by Acton HTMX scaffold
 
-use sea_orm::entity::prelude::*;
+use acton_htmx::db::{Record, SqlxRepository};
+{%- if relations %}
+use acton_htmx::pagination::Page;
+{%- endif %}
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{error::BoxDynError, Arguments, PgPool, Postgres};
 {%- if has_date_fields %}
-use chrono::{NaiveDate, NaiveDateTime, DateTime, Utc};
+use chrono::{NaiveDate, NaiveDateTime};
 {%- endif %}
 {%- if has_decimal %}
 use rust_decimal::Decimal;
@@ -92,34 +100,36 @@ use rust_decimal::Decimal;
 {%- if has_uuid %}
 use uuid::Uuid;
 {%- endif %}
+
+{%- if not api %}
+
+use crate::forms::{{ model_snake }}::{{ model_name }}Form;
+{%- endif %}
 {%- if has_enum %}
 
 {%- for enum in enums %}
 /// {{ enum.name }} enumeration
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
-#[sea_orm(rs_type = "String", db_type = "String(Some(50))")]
+///
+/// Stored as one of: {{ enum["values"] | join(", ") }}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "VARCHAR", rename_all = "snake_case")]
 pub enum {{ enum.name }} {
     {%- for variant in enum.variants %}
-    #[sea_orm(string_value = "{{ variant }}")]
     {{ variant }},
     {%- endfor %}
 }
 {%- endfor %}
 {%- endif %}
 
-/// {{ model_name }} entity
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
-#[sea_orm(table_name = "{{ table_name }}")]
+/// {{ model_name }} record
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Model {
-    #[sea_orm(primary_key)]
     #[serde(skip_deserializing)]
     pub id: i64,
     {%- for field in fields %}
-    {%- if field.unique %}
-    #[sea_orm(unique)]
-    {%- endif %}
-    {%- if field.indexed %}
-    #[sea_orm(indexed)]
+    {%- if field.column_name != field.name %}
+    #[sqlx(rename = "{{ field.column_name }}")]
     {%- endif %}
     pub {{ field.name }}: {{ field.rust_type }},
     {%- endfor %}
@@ -127,113 +137,155 @@ pub struct Model {
     pub created_at: DateTime<Utc>,
     #[serde(skip_deserializing)]
     pub updated_at: DateTime<Utc>,
+    {%- if soft_delete %}
+    #[serde(skip_deserializing)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    {%- endif %}
 }
+{%- if not api %}
 
-#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {
-    {%- for relation in relations %}
-    #[sea_orm(
-        belongs_to = "super::{{ relation.referenced_table }}::Entity",
-        from = "Column::{{ relation.field_column }}",
-        to = "super::{{ relation.referenced_table }}::Column::Id"
-    )]
-    {{ relation.relation_name }},
-    {%- endfor %}
-}
-
-{%- for relation in relations %}
-impl Related<super::{{ relation.referenced_table }}::Entity> for Entity {
-    fn to() -> RelationDef {
-        Relation::{{ relation.relation_name }}.def()
-    }
-}
-{%- endfor %}
-
-impl ActiveModelBehavior for ActiveModel {}
-
-impl Entity {
-    /// Find all {{ plural_title }}
-    pub async fn find_all(db: &DatabaseConnection) -> Result<Vec<Model>, DbErr> {
-        Self::find().all(db).await
-    }
-
-    /// Search {{ plural_title }} by query string
-    ///
-    /// Performs case-insensitive substring search across all text fields.
-    /// Returns empty vector if query is empty or only whitespace.
-    ///
-    /// # Arguments
-    ///
-    /// * `db` - Database connection
-    /// * `query` - Search query string
-    ///
-    /// # Examples
+impl Model {
+    /// Build an unsaved {{ model_name }} from form input
     ///
-    /// ```no_run
-    /// let results = Entity::search(&db, \"rust\").await?;
-    /// ```
-    pub async fn search(db: &DatabaseConnection, query: &str) -> Result<Vec<Model>, DbErr> {
-        let query = query.trim();
-
-        // Return empty vector for empty queries
-        if query.is_empty() {
-            return Ok(Vec::new());
+    /// The id and timestamps are assigned by the database on insert.
+    pub fn from_form(form: {{ model_name }}Form) -> Self {
+        let now = Utc::now();
+        Self {
+            id: 0,
+            {%- for field in fields %}
+            {{ field.name }}: form.{{ field.name }},
+            {%- endfor %}
+            created_at: now,
+            updated_at: now,
+            {%- if soft_delete %}
+            deleted_at: None,
+            {%- endif %}
         }
+    }
+}
+{%- endif %}
 
-        // Build search pattern for LIKE query
-        let search_pattern = format!(\"%{query}%\");
-
-        // Build condition to search across all string fields
-        let mut condition = Condition::any();
+impl Record<Postgres> for Model {
+    type Id = i64;
+    const TABLE: &'static str = "{{ table_name }}";
+    const COLUMNS: &'static [&'static str] = &[
         {%- for field in fields %}
-        {%- if field.rust_type == "String" %}
-        condition = condition.add(Column::{{ field.pascal_case_name }}.like(&search_pattern));
-        {%- elif field.rust_type == "Option<String>" %}
-        condition = condition.add(Column::{{ field.pascal_case_name }}.like(&search_pattern));
-        {%- endif %}
+        "{{ field.column_name }}",
         {%- endfor %}
+    ];
+    {%- if soft_delete %}
+    const SOFT_DELETE_COLUMN: Option<&'static str> = Some("deleted_at");
+    {%- endif %}
 
-        Self::find()
-            .filter(condition)
-            .all(db)
-            .await
+    fn bind_columns<'q>(
+        &'q self,
+        args: &mut <Postgres as sqlx::Database>::Arguments<'q>,
+    ) -> Result<(), BoxDynError> {
+        {%- for field in fields %}
+        args.add(&self.{{ field.name }})?;
+        {%- endfor %}
+        Ok(())
     }
+}
 
-    /// Find {{ model_name }} by ID
-    pub async fn find_by_id(db: &DatabaseConnection, id: i64) -> Result<Option<Model>, DbErr> {
-        Self::find_by_id(id).one(db).await
-    }
+/// Repository for {{ plural_title }}
+pub type {{ model_name }}Repository = SqlxRepository<Model, Postgres>;
 
-    /// Create new {{ model_name }}
-    pub async fn create(db: &DatabaseConnection, form: super::forms::{{ model_name }}Form) -> Result<Model, DbErr> {
-        let model = ActiveModel {
-            {%- for field in fields %}
-            {{ field.name }}: Set(form.{{ field.name }}),
-            {%- endfor %}
-            created_at: Set(Utc::now()),
-            updated_at: Set(Utc::now()),
-            ..Default::default()
-        };
-        model.insert(db).await
+/// {{ model_name }} repository on `pool`
+pub fn repository(pool: &PgPool) -> {{ model_name }}Repository {
+    SqlxRepository::new(pool.clone())
+}
+
+/// Search {{ plural_title }} by query string
+///
+/// Performs case-insensitive substring search across all text fields.
+/// Returns empty vector if query is empty or only whitespace.
+pub async fn search(repo: &{{ model_name }}Repository, query: &str) -> Result<Vec<Model>, sqlx::Error> {
+    let query = query.trim();
+
+    // Return empty vector for empty queries
+    if query.is_empty() {
+        return Ok(Vec::new());
     }
+    {%- if search_columns %}
+
+    sqlx::query_as(
+        "SELECT * FROM {{ table_name }} WHERE {% if soft_delete %}deleted_at IS NULL AND ({% endif %}{{ search_columns | join(" ILIKE $1 OR ") }} ILIKE $1{% if soft_delete %}){% endif %} ORDER BY id",
+    )
+    .bind(format!("%{query}%"))
+    .fetch_all(repo.pool())
+    .await
+    {%- else %}
+
+    // No text fields to search
+    let _ = repo;
+    Ok(Vec::new())
+    {%- endif %}
+}
+{%- for relation in relations %}
 
-    /// Update {{ model_name }}
-    pub async fn update(db: &DatabaseConnection, id: i64, form: super::forms::{{ model_name }}Form) -> Result<Model, DbErr> {
-        let model = Self::find_by_id(db, id).await?.ok_or(DbErr::RecordNotFound(id.to_string()))?;
-        let mut active_model: ActiveModel = model.into();
-        {%- for field in fields %}
-        active_model.{{ field.name }} = Set(form.{{ field.name }});
-        {%- endfor %}
-        active_model.updated_at = Set(Utc::now());
-        active_model.update(db).await
+impl Model {
+    /// The {{ relation.referenced_model }} this {{ model_name }} belongs to
+    pub async fn {{ relation.accessor }}(
+        &self,
+        pool: &PgPool,
+    ) -> Result<Option<super::{{ relation.referenced_module }}::Model>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT {{ relation.referenced_table }}.* FROM {{ relation.referenced_table }} \
+             JOIN {{ table_name }} ON {{ table_name }}.{{ relation.field_column }} = {{ relation.referenced_table }}.id \
+             WHERE {{ table_name }}.id = $1",
+        )
+        .bind(self.id)
+        .fetch_optional(pool)
+        .await
     }
+}
 
-    /// Delete {{ model_name }}
-    pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<DeleteResult, DbErr> {
-        let model = Self::find_by_id(db, id).await?.ok_or(DbErr::RecordNotFound(id.to_string()))?;
-        model.delete(db).await
+/// {{ plural_title }} belonging to the {{ relation.referenced_model }} with `{{ relation.field_column }}`, one page at a time
+pub async fn {{ relation.list_fn }}(
+    repo: &{{ model_name }}Repository,
+    {{ relation.field_column }}: i64,
+    page: usize,
+    page_size: usize,
+) -> Result<Page<Model>, sqlx::Error> {
+    let page = page.max(1);
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM {{ table_name }} WHERE {{ relation.field_column }} = $1{% if soft_delete %} AND deleted_at IS NULL{% endif %}",
+    )
+    .bind({{ relation.field_column }})
+    .fetch_one(repo.pool())
+    .await?;
+
+    let items = sqlx::query_as(
+        "SELECT * FROM {{ table_name }} WHERE {{ relation.field_column }} = $1{% if soft_delete %} AND deleted_at IS NULL{% endif %} \
+         ORDER BY id LIMIT $2 OFFSET $3",
+    )
+    .bind({{ relation.field_column }})
+    .bind(i64::try_from(page_size).unwrap_or(i64::MAX))
+    .bind(i64::try_from(Page::<Model>::offset(page, page_size)).unwrap_or(i64::MAX))
+    .fetch_all(repo.pool())
+    .await?;
+
+    Ok(Page::new(items, page, page_size, usize::try_from(total).unwrap_or_default()))
+}
+{%- if with_inverse %}
+
+impl super::{{ relation.referenced_module }}::Model {
+    /// {{ plural_title }} whose `{{ relation.field_name }}` is this {{ relation.referenced_model }}
+    pub async fn {{ relation.inverse_method }}(&self, pool: &PgPool) -> Result<Vec<Model>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT {{ table_name }}.* FROM {{ table_name }} \
+             JOIN {{ relation.referenced_table }} ON {{ relation.referenced_table }}.id = {{ table_name }}.{{ relation.field_column }} \
+             WHERE {{ relation.referenced_table }}.id = $1{% if soft_delete %} AND {{ table_name }}.deleted_at IS NULL{% endif %} \
+             ORDER BY {{ table_name }}.id",
+        )
+        .bind(self.id)
+        .fetch_all(pool)
+        .await
     }
 }
+{%- endif %}
+{%- endfor %}
 "#;
 
 /// Database migration template
@@ -243,10 +295,11 @@ pub const MIGRATION_TEMPLATE: &str = r"-- Create {{ table_name }} table
 CREATE TABLE {{ table_name }} (
     id BIGSERIAL PRIMARY KEY,
 {%- for field in fields %}
-    {{ field.column_name }} {{ field.sql_type }}{% if not field.optional %} NOT NULL{% endif %},
+    {{ field.column_name }} {{ field.sql_type }}{% if not field.optional %} NOT NULL{% endif %}{% if field.check_constraint %} {{ field.check_constraint }}{% endif %},
 {%- endfor %}
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
-    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(){% if soft_delete %},
+    deleted_at TIMESTAMPTZ{% endif %}
 );
 
 {%- for field in unique_fields %}
@@ -286,6 +339,23 @@ CREATE TRIGGER update_{{ table_name }}_updated_at
     EXECUTE FUNCTION update_updated_at_column();
 ";
 
+/// Database migration rollback template
+pub const MIGRATION_DOWN_TEMPLATE: &str = r"-- Drop {{ table_name }} table
+-- Generated by Acton HTMX scaffold
+
+DROP TRIGGER IF EXISTS update_{{ table_name }}_updated_at ON {{ table_name }};
+DROP TABLE IF EXISTS {{ table_name }};
+";
+
+/// Seed data template
+pub const SEED_TEMPLATE: &str = r"-- Sample {{ plural_title }}
+-- Generated by Acton HTMX scaffold; `acton htmx db seed` runs it once per database
+
+INSERT INTO {{ table_name }} ({% for field in fields %}{{ field.column_name }}{% if not loop.last %}, {% endif %}{% endfor %})
+SELECT {% for field in fields %}{{ field.seed_value }}{% if not loop.last %}, {% endif %}{% endfor %}
+WHERE NOT EXISTS (SELECT 1 FROM {{ table_name }});
+";
+
 /// HTMX handler template
 pub const HANDLER_TEMPLATE: &str = r#"//! {{ model_name }} handlers
 //!
@@ -305,19 +375,24 @@ use validator::Validate;
 use crate::{
     forms::{{ model_snake }}::{{ model_name }}Form,
     models::{{ model_snake }},
-    state::AppState,
 };
 
-/// List all {{ plural_title }}
+/// {{ plural_title }} shown per page
+const PAGE_SIZE: usize = 20;
+
+/// List {{ plural_title }}, one page at a time
 pub async fn list(
-    State(state): State<AppState>,
+    State(state): State<ActonHtmxState>,
     HxRequest(is_htmx): HxRequest,
     Query(params): Query<ListParams>,
 ) -> Result<Response, HandlerError> {
-    let {{ model_snake }}s = {{ model_snake }}::Entity::find_all(&state.db).await?;
+    let page = {{ model_snake }}::repository(state.database_pool())
+        .list(params.page.unwrap_or(1), PAGE_SIZE)
+        .await?;
 
     let template = {{ model_name }}ListTemplate {
-        {{ model_snake }}s,
+        pagination: PaginationTemplate::new(&page, "{{ route_path }}"),
+        {{ model_snake }}s: page.items,
         search_query: params.search.unwrap_or_default(),
     };
 
@@ -326,11 +401,12 @@ pub async fn list(
 
 /// Show individual {{ model_name }}
 pub async fn show(
-    State(state): State<AppState>,
+    State(state): State<ActonHtmxState>,
     HxRequest(is_htmx): HxRequest,
     Path(id): Path<i64>,
 ) -> Result<Response, HandlerError> {
-    let {{ model_snake }} = {{ model_snake }}::Entity::find_by_id(&state.db, id)
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .find(id)
         .await?
         .ok_or(HandlerError::NotFound)?;
 
@@ -353,7 +429,7 @@ pub async fn new(
 
 /// Create new {{ model_name }}
 pub async fn create(
-    State(state): State<AppState>,
+    State(state): State<ActonHtmxState>,
     mut session: Session,
     Form(form): Form<{{ model_name }}Form>,
 ) -> Result<Response, HandlerError> {
@@ -367,7 +443,9 @@ pub async fn create(
     }
 
     // Create {{ model_snake }}
-    let {{ model_snake }} = {{ model_snake }}::Entity::create(&state.db, form).await?;
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .create(&{{ model_snake }}::Model::from_form(form))
+        .await?;
 
     // Add flash message
     session.add_flash(FlashMessage::success("{{ model_name }} created successfully!"));
@@ -378,11 +456,12 @@ pub async fn create(
 
 /// Show edit {{ model_name }} form
 pub async fn edit(
-    State(state): State<AppState>,
+    State(state): State<ActonHtmxState>,
     HxRequest(is_htmx): HxRequest,
     Path(id): Path<i64>,
 ) -> Result<Response, HandlerError> {
-    let {{ model_snake }} = {{ model_snake }}::Entity::find_by_id(&state.db, id)
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .find(id)
         .await?
         .ok_or(HandlerError::NotFound)?;
 
@@ -396,14 +475,15 @@ pub async fn edit(
 
 /// Update {{ model_name }}
 pub async fn update(
-    State(state): State<AppState>,
+    State(state): State<ActonHtmxState>,
     mut session: Session,
     Path(id): Path<i64>,
     Form(form): Form<{{ model_name }}Form>,
 ) -> Result<Response, HandlerError> {
     // Validate form
     if let Err(errors) = form.validate() {
-        let {{ model_snake }} = {{ model_snake }}::Entity::find_by_id(&state.db, id)
+        let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+            .find(id)
             .await?
             .ok_or(HandlerError::NotFound)?;
 
@@ -415,7 +495,10 @@ pub async fn update(
     }
 
     // Update {{ model_snake }}
-    let {{ model_snake }} = {{ model_snake }}::Entity::update(&state.db, id, form).await?;
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .update(id, &{{ model_snake }}::Model::from_form(form))
+        .await?
+        .ok_or(HandlerError::NotFound)?;
 
     // Add flash message
     session.add_flash(FlashMessage::success("{{ model_name }} updated successfully!"));
@@ -432,11 +515,13 @@ pub async fn update(
 
 /// Delete {{ model_name }}
 pub async fn delete(
-    State(state): State<AppState>,
+    State(state): State<ActonHtmxState>,
     mut session: Session,
     Path(id): Path<i64>,
 ) -> Result<Response, HandlerError> {
-    {{ model_snake }}::Entity::delete(&state.db, id).await?;
+    if !{{ model_snake }}::repository(state.database_pool()){% if soft_delete %}.soft_delete(id){% else %}.delete(id){% endif %}.await? {
+        return Err(HandlerError::NotFound);
+    }
 
     // Add flash message
     session.add_flash(FlashMessage::success("{{ model_name }} deleted successfully!"));
@@ -450,15 +535,34 @@ pub async fn delete(
         .into_response())
 }
 
+{% for relation in relations -%}
+/// List {{ plural_title }} belonging to a {{ relation.referenced_model }}
+///
+/// Serves `GET {{ relation.nested_route }}` as an HTMX partial of rows.
+pub async fn {{ relation.list_fn }}(
+    State(state): State<ActonHtmxState>,
+    Path(id): Path<i64>,
+    Query(params): Query<ListParams>,
+) -> Result<Response, HandlerError> {
+    let repo = {{ model_snake }}::repository(state.database_pool());
+    let page = {{ model_snake }}::{{ relation.list_fn }}(&repo, id, params.page.unwrap_or(1), PAGE_SIZE).await?;
+
+    let template = {{ model_name }}RowsTemplate { {{ model_snake }}s: page.items };
+
+    Ok(template.render_partial().into_response())
+}
+
+{% endfor -%}
 /// Live search endpoint
 ///
 /// Performs real-time search across {{ model_name }} text fields.
 /// Returns filtered rows as HTML partial for HTMX swap.
 pub async fn search(
-    State(state): State<AppState>,
+    State(state): State<ActonHtmxState>,
     Query(params): Query<SearchParams>,
 ) -> Result<Response, HandlerError> {
-    let {{ model_snake }}s = {{ model_snake }}::Entity::search(&state.db, &params.q).await?;
+    let repo = {{ model_snake }}::repository(state.database_pool());
+    let {{ model_snake }}s = {{ model_snake }}::search(&repo, &params.q).await?;
 
     let template = {{ model_name }}RowsTemplate { {{ model_snake }}s };
 
@@ -469,7 +573,7 @@ pub async fn search(
 #[derive(Debug, Deserialize)]
 pub struct ListParams {
     pub search: Option<String>,
-    pub page: Option<i64>,
+    pub page: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -482,6 +586,7 @@ pub struct SearchParams {
 #[template(path = \"{{ model_snake }}s/list.html\")]
 struct {{ model_name }}ListTemplate {
     {{ model_snake }}s: Vec<{{ model_snake }}::Model>,
+    pagination: PaginationTemplate,
     search_query: String,
 }
 
@@ -519,12 +624,12 @@ struct FlashMessagesTemplate {
 // Error handling
 #[derive(Debug)]
 pub enum HandlerError {
-    Database(sea_orm::DbErr),
+    Database(sqlx::Error),
     NotFound,
 }
 
-impl From<sea_orm::DbErr> for HandlerError {
-    fn from(err: sea_orm::DbErr) -> Self {
+impl From<sqlx::Error> for HandlerError {
+    fn from(err: sqlx::Error) -> Self {
         Self::Database(err)
     }
 }
@@ -543,6 +648,204 @@ impl IntoResponse for HandlerError {
 }
 "#;
 
+/// JSON API handler template
+pub const API_HANDLER_TEMPLATE: &str = r#"//! {{ model_name }} JSON API handlers
+//!
+//! Generated by Acton HTMX scaffold
+//!
+//! Mount with `.nest("{{ api_route_path }}", handlers::api::{{ model_plural_snake }}::routes())`.
+
+use acton_htmx::prelude::*;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use validator::Validate;
+{%- if has_date_fields %}
+use chrono::{NaiveDate, NaiveDateTime, DateTime, Utc};
+{%- endif %}
+{%- if has_decimal %}
+use rust_decimal::Decimal;
+{%- endif %}
+{%- if has_uuid %}
+use uuid::Uuid;
+{%- endif %}
+
+use crate::models::{{ model_snake }}{% if has_enum %}::{self{% for enum in enums %}, {{ enum.name }}{% endfor %}}{% endif %};
+
+/// {{ plural_title }} returned per page by default
+const PAGE_SIZE: usize = 20;
+
+/// Largest page a client may request
+const MAX_PAGE_SIZE: usize = 100;
+
+/// Routes for `{{ api_route_path }}`
+pub fn routes() -> Router<ActonHtmxState> {
+    Router::new()
+        .route("/", get(list).post(create))
+        .route("/{id}", get(show).put(update).delete(delete))
+}
+
+/// List {{ plural_title }}, one page at a time
+pub async fn list(
+    State(state): State<ActonHtmxState>,
+    Query(params): Query<ListParams>,
+) -> Result<Json<Page<{{ model_snake }}::Model>>, ApiError> {
+    let page = {{ model_snake }}::repository(state.database_pool())
+        .list(params.page.unwrap_or(1), params.page_size())
+        .await?;
+
+    Ok(Json(page))
+}
+
+/// Show individual {{ model_name }}
+pub async fn show(
+    State(state): State<ActonHtmxState>,
+    Path(id): Path<i64>,
+) -> Result<Json<{{ model_snake }}::Model>, ApiError> {
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .find(id)
+        .await
+        .found_or_404("{{ model_name }}")?;
+
+    Ok(Json({{ model_snake }}))
+}
+
+/// Create new {{ model_name }}
+pub async fn create(
+    State(state): State<ActonHtmxState>,
+    Json(input): Json<{{ model_name }}Input>,
+) -> Result<(StatusCode, Json<{{ model_snake }}::Model>), ApiError> {
+    input.validate().map_err(ApiError::invalid)?;
+
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .create(&input.into_model())
+        .await?;
+
+    Ok((StatusCode::CREATED, Json({{ model_snake }})))
+}
+
+/// Update {{ model_name }}
+pub async fn update(
+    State(state): State<ActonHtmxState>,
+    Path(id): Path<i64>,
+    Json(input): Json<{{ model_name }}Input>,
+) -> Result<Json<{{ model_snake }}::Model>, ApiError> {
+    input.validate().map_err(ApiError::invalid)?;
+
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .update(id, &input.into_model())
+        .await
+        .found_or_404("{{ model_name }}")?;
+
+    Ok(Json({{ model_snake }}))
+}
+
+/// Delete {{ model_name }}
+pub async fn delete(
+    State(state): State<ActonHtmxState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    if !{{ model_snake }}::repository(state.database_pool()){% if soft_delete %}.soft_delete(id){% else %}.delete(id){% endif %}.await? {
+        return Err(ActonHtmxError::NotFound("{{ model_name }} not found".to_string()).into());
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+{% for relation in relations -%}
+/// List {{ plural_title }} belonging to a {{ relation.referenced_model }}
+///
+/// Serves `GET {{ relation.nested_route }}`.
+pub async fn {{ relation.list_fn }}(
+    State(state): State<ActonHtmxState>,
+    Path(id): Path<i64>,
+    Query(params): Query<ListParams>,
+) -> Result<Json<Page<{{ model_snake }}::Model>>, ApiError> {
+    let repo = {{ model_snake }}::repository(state.database_pool());
+    let page = {{ model_snake }}::{{ relation.list_fn }}(&repo, id, params.page.unwrap_or(1), params.page_size()).await?;
+
+    Ok(Json(page))
+}
+
+{% endfor -%}
+/// Pagination query parameters
+#[derive(Debug, Deserialize)]
+pub struct ListParams {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+impl ListParams {
+    /// Requested page size, within `1..=MAX_PAGE_SIZE`
+    fn page_size(&self) -> usize {
+        self.per_page.unwrap_or(PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+/// Request body for creating and updating a {{ model_name }}
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct {{ model_name }}Input {
+    {%- for field in fields %}
+    {%- for validation in field.validations %}
+    #[validate({{ validation }})]
+    {%- endfor %}
+    pub {{ field.name }}: {{ field.rust_type }},
+    {%- endfor %}
+}
+
+impl {{ model_name }}Input {
+    /// Build an unsaved {{ model_name }}; the database assigns id and timestamps
+    fn into_model(self) -> {{ model_snake }}::Model {
+        let now = chrono::Utc::now();
+        {{ model_snake }}::Model {
+            id: 0,
+            {%- for field in fields %}
+            {{ field.name }}: self.{{ field.name }},
+            {%- endfor %}
+            created_at: now,
+            updated_at: now,
+            {%- if soft_delete %}
+            deleted_at: None,
+            {%- endif %}
+        }
+    }
+}
+
+/// [`ActonHtmxError`] always rendered as `{ "error": "..." }`
+#[derive(Debug)]
+pub struct ApiError(pub ActonHtmxError);
+
+impl ApiError {
+    /// 422 listing the failed validations
+    fn invalid(errors: validator::ValidationErrors) -> Self {
+        Self(ActonHtmxError::UnprocessableEntity(errors.to_string()))
+    }
+}
+
+impl From<ActonHtmxError> for ApiError {
+    fn from(err: ActonHtmxError) -> Self {
+        Self(err)
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        Self(err.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        self.0.into_response_for(ResponseFormat::Json)
+    }
+}
+"#;
+
 /// Form struct template
 pub const FORM_TEMPLATE: &str = r"//! {{ model_name }} form validation
 //!
@@ -686,8 +989,8 @@ async fn test_create_{{ model_snake }}() {
     assert!(response.status().is_redirection() || response.status().is_success());
 
     // Verify {{ model_snake }} was created
-    let {{ model_snake }}s = {{ model_snake }}::Entity::find_all(&state.db).await.unwrap();
-    assert!(!{{ model_snake }}s.is_empty());
+    let {{ model_snake }}s = {{ model_snake }}::repository(state.database_pool()).count().await.unwrap();
+    assert!({{ model_snake }}s > 0);
 }
 
 #[tokio::test]
@@ -700,7 +1003,10 @@ async fn test_show_{{ model_snake }}() {
         {% if field.optional %}None{% else %}{{ field.default_value }}{% endif %},
         {%- endfor %}
     );
-    let {{ model_snake }} = {{ model_snake }}::Entity::create(&state.db, form).await.unwrap();
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .create(&{{ model_snake }}::Model::from_form(form))
+        .await
+        .unwrap();
 
     let app = app_router(state);
 
@@ -727,7 +1033,10 @@ async fn test_update_{{ model_snake }}() {
         {% if field.optional %}None{% else %}{{ field.default_value }}{% endif %},
         {%- endfor %}
     );
-    let {{ model_snake }} = {{ model_snake }}::Entity::create(&state.db, form).await.unwrap();
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .create(&{{ model_snake }}::Model::from_form(form))
+        .await
+        .unwrap();
 
     let app = app_router(state.clone());
 
@@ -754,7 +1063,8 @@ async fn test_update_{{ model_snake }}() {
     assert!(response.status().is_success());
 
     // Verify {{ model_snake }} was updated
-    let updated = {{ model_snake }}::Entity::find_by_id(&state.db, {{ model_snake }}.id)
+    let updated = {{ model_snake }}::repository(state.database_pool())
+        .find({{ model_snake }}.id)
         .await
         .unwrap()
         .expect(\"{{ model_name }} should exist\");
@@ -771,7 +1081,10 @@ async fn test_delete_{{ model_snake }}() {
         {% if field.optional %}None{% else %}{{ field.default_value }}{% endif %},
         {%- endfor %}
     );
-    let {{ model_snake }} = {{ model_snake }}::Entity::create(&state.db, form).await.unwrap();
+    let {{ model_snake }} = {{ model_snake }}::repository(state.database_pool())
+        .create(&{{ model_snake }}::Model::from_form(form))
+        .await
+        .unwrap();
 
     let app = app_router(state.clone());
 
@@ -789,7 +1102,8 @@ async fn test_delete_{{ model_snake }}() {
     assert!(response.status().is_success());
 
     // Verify {{ model_snake }} was deleted
-    let deleted = {{ model_snake }}::Entity::find_by_id(&state.db, {{ model_snake }}.id)
+    let deleted = {{ model_snake }}::repository(state.database_pool())
+        .find({{ model_snake }}.id)
         .await
         .unwrap();
     assert!(deleted.is_none());
@@ -874,6 +1188,11 @@ pub const ASKAMA_LIST_TEMPLATE: &str = r##"{% raw %}{% extends "base.html" %}
                 </tbody>
             </table>
         </div>
+
+        <!-- Pagination -->
+        <div class="mt-4">
+            {% raw %}{{ pagination|safe }}{% endraw %}
+        </div>
     </div>
 </div>
 <!-- HTMX Content End -->
@@ -1007,6 +1326,18 @@ pub const ASKAMA_FORM_TEMPLATE: &str = r##"{% raw %}{% extends "base.html" %}
                            value="{% raw %}{% if {% endraw %}{{ model_snake }}{% raw %} %}{{ {% endraw %}{{ model_snake }}{% raw %}.{% endraw %}{{ field.name }}{% raw %} }}{% endif %}{% endraw %}"
                            class="shadow appearance-none border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline {% raw %}{% if errors %}{% if errors.{% endraw %}{{ field.name }}{% raw %} %}border-red-500{% endif %}{% endif %}{% endraw %}"
                            {% if not field.optional %}required{% endif %}>
+                    {%- elif field.enum_options %}
+                    <select name="{{ field.name }}"
+                            id="{{ field.name }}"
+                            class="shadow border rounded w-full py-2 px-3 text-gray-700 leading-tight focus:outline-none focus:shadow-outline {% raw %}{% if errors %}{% if errors.{% endraw %}{{ field.name }}{% raw %} %}border-red-500{% endif %}{% endif %}{% endraw %}"
+                            {% if not field.optional %}required{% endif %}>
+                        {%- if field.optional %}
+                        <option value=""></option>
+                        {%- endif %}
+                        {%- for option in field.enum_options %}
+                        <option value="{{ option.value }}"{% raw %}{% if let Some(record) = {% endraw %}{{ model_snake }}{% raw %} %}{% if record.{% endraw %}{{ field.name }}{% raw %} == {% endraw %}{% if field.optional %}Some({% endif %}{{ model_snake }}::{{ option.path }}{% if field.optional %}){% endif %}{% raw %} %} selected{% endif %}{% endif %}{% endraw %}>{{ option.label }}</option>
+                        {%- endfor %}
+                    </select>
                     {%- elif field.rust_type == "bool" %}
                     <input type="checkbox"
                            name="{{ field.name }}"
@@ -0,0 +1,283 @@
+//! Broadcast Agent
+//!
+//! Actor-based WebSocket fan-out using acton-reactive.
+//! Maintains channel → subscriber maps and pushes rendered HTML fragments to
+//! every connection subscribed to a channel.
+//!
+//! Channels are plain strings. Use [`BroadcastAgent::session_channel`] to
+//! address every connection belonging to one session, or any application key
+//! ("dashboard", "room:7") for group updates.
+//!
+//! Fragments are sent verbatim as WebSocket text frames, which the
+//! [HTMX `ws` extension](https://htmx.org/extensions/ws/) swaps by element
+//! `id` (out-of-band by default):
+//!
+//! ```html
+//! <div hx-ext="ws" ws-connect="/ws?channel=dashboard">
+//!     <div id="visitors">0</div>
+//! </div>
+//! ```
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::agents::Publish;
+//!
+//! state
+//!     .broadcast()
+//!     .send(Publish::new("dashboard", r#"<div id="visitors" hx-swap-oob="true">42</div>"#))
+//!     .await;
+//! ```
+
+use crate::htmx::agents::default_agent_config;
+use crate::htmx::auth::session::{SessionData, SessionId};
+use acton_reactive::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+// Type alias for the ManagedAgent builder type
+type BroadcastAgentBuilder = ManagedAgent<Idle, BroadcastAgent>;
+
+/// Number of fragments buffered per connection before new ones are dropped
+pub const SUBSCRIBER_BUFFER: usize = 64;
+
+/// Receiving end of a subscription, held by the connection
+pub type BroadcastSink = mpsc::Sender<Arc<str>>;
+
+/// Decides whether a connection may subscribe to a requested channel
+///
+/// Called with the channel name and the connection's session, if any.
+pub type ChannelAuthorizer = Arc<dyn Fn(&str, Option<&SessionData>) -> bool + Send + Sync>;
+
+/// Broadcast agent model
+#[derive(Debug, Default, Clone)]
+pub struct BroadcastAgent {
+    /// Subscribers per channel
+    channels: HashMap<String, Vec<BroadcastSink>>,
+}
+
+// ============================================================================
+// Message Types
+// ============================================================================
+
+/// Register `sink` to receive fragments published to `channel`
+#[derive(Clone, Debug)]
+pub struct Subscribe {
+    /// The channel to subscribe to
+    pub channel: String,
+    /// Where published fragments are delivered
+    pub sink: BroadcastSink,
+}
+
+impl Subscribe {
+    /// Create a new subscribe request (fire-and-forget)
+    #[must_use]
+    pub fn new(channel: impl Into<String>, sink: BroadcastSink) -> Self {
+        Self {
+            channel: channel.into(),
+            sink,
+        }
+    }
+}
+
+/// Remove `sink` from `channel`
+#[derive(Clone, Debug)]
+pub struct Unsubscribe {
+    /// The channel to leave
+    pub channel: String,
+    /// The sink registered with [`Subscribe`]
+    pub sink: BroadcastSink,
+}
+
+impl Unsubscribe {
+    /// Create a new unsubscribe request (fire-and-forget)
+    #[must_use]
+    pub fn new(channel: impl Into<String>, sink: BroadcastSink) -> Self {
+        Self {
+            channel: channel.into(),
+            sink,
+        }
+    }
+}
+
+/// Send an HTML fragment to every subscriber of `channel`
+#[derive(Clone, Debug)]
+pub struct Publish {
+    /// The channel to publish to
+    pub channel: String,
+    /// Rendered HTML fragment
+    pub html: String,
+}
+
+impl Publish {
+    /// Create a new publish request (fire-and-forget)
+    #[must_use]
+    pub fn new(channel: impl Into<String>, html: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            html: html.into(),
+        }
+    }
+}
+
+impl BroadcastAgent {
+    /// Spawn broadcast agent
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent initialization fails
+    pub async fn spawn(runtime: &mut AgentRuntime) -> anyhow::Result<AgentHandle> {
+        let config = default_agent_config("broadcast")?;
+        let builder = runtime.new_agent_with_config::<Self>(config).await;
+        Self::configure_handlers(builder).await
+    }
+
+    /// Channel addressing every connection of one session
+    #[must_use]
+    pub fn session_channel(session_id: &SessionId) -> String {
+        format!("session:{session_id}")
+    }
+
+    /// Configure all message handlers for the broadcast agent
+    async fn configure_handlers(mut builder: BroadcastAgentBuilder) -> anyhow::Result<AgentHandle> {
+        builder
+            .mutate_on::<Subscribe>(|agent, envelope| {
+                let Subscribe { channel, sink } = envelope.message().clone();
+                Self::subscribe_internal(&mut agent.model, channel, sink);
+                AgentReply::immediate()
+            })
+            .mutate_on::<Unsubscribe>(|agent, envelope| {
+                let message = envelope.message();
+                Self::unsubscribe_internal(&mut agent.model, &message.channel, &message.sink);
+                AgentReply::immediate()
+            })
+            .mutate_on::<Publish>(|agent, envelope| {
+                let message = envelope.message();
+                let delivered =
+                    Self::publish_internal(&mut agent.model, &message.channel, &message.html);
+                tracing::trace!(
+                    channel = %message.channel,
+                    delivered,
+                    "Published broadcast fragment"
+                );
+                AgentReply::immediate()
+            });
+
+        Ok(builder.start().await)
+    }
+
+    /// Pure function: Add a subscriber to a channel
+    fn subscribe_internal(model: &mut Self, channel: String, sink: BroadcastSink) {
+        model.channels.entry(channel).or_default().push(sink);
+    }
+
+    /// Pure function: Remove a subscriber, dropping the channel once empty
+    fn unsubscribe_internal(model: &mut Self, channel: &str, sink: &BroadcastSink) {
+        if let Some(sinks) = model.channels.get_mut(channel) {
+            sinks.retain(|existing| !existing.same_channel(sink));
+            if sinks.is_empty() {
+                model.channels.remove(channel);
+            }
+        }
+    }
+
+    /// Pure function: Deliver a fragment to every live subscriber of a channel
+    ///
+    /// Disconnected subscribers are pruned. Subscribers whose buffer is full
+    /// miss this fragment rather than stalling the agent.
+    ///
+    /// Returns the number of subscribers the fragment was delivered to.
+    fn publish_internal(model: &mut Self, channel: &str, html: &str) -> usize {
+        let Some(sinks) = model.channels.get_mut(channel) else {
+            return 0;
+        };
+
+        let fragment: Arc<str> = Arc::from(html);
+        let mut delivered = 0;
+        sinks.retain(|sink| match sink.try_send(Arc::clone(&fragment)) {
+            Ok(()) => {
+                delivered += 1;
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                tracing::warn!(channel, "Broadcast subscriber is lagging; dropped fragment");
+                true
+            }
+            Err(TrySendError::Closed(_)) => false,
+        });
+
+        if sinks.is_empty() {
+            model.channels.remove(channel);
+        }
+        delivered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_fans_out_to_channel() {
+        let mut model = BroadcastAgent::default();
+        let (tx1, mut rx1) = mpsc::channel(SUBSCRIBER_BUFFER);
+        let (tx2, mut rx2) = mpsc::channel(SUBSCRIBER_BUFFER);
+        let (other, mut other_rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+        BroadcastAgent::subscribe_internal(&mut model, "room".to_string(), tx1);
+        BroadcastAgent::subscribe_internal(&mut model, "room".to_string(), tx2);
+        BroadcastAgent::subscribe_internal(&mut model, "lobby".to_string(), other);
+
+        assert_eq!(BroadcastAgent::publish_internal(&mut model, "room", "<p>hi</p>"), 2);
+        assert_eq!(&*rx1.try_recv().unwrap(), "<p>hi</p>");
+        assert_eq!(&*rx2.try_recv().unwrap(), "<p>hi</p>");
+        assert!(other_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_unsubscribe_removes_sink_and_empty_channel() {
+        let mut model = BroadcastAgent::default();
+        let (tx, mut rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+        BroadcastAgent::subscribe_internal(&mut model, "room".to_string(), tx.clone());
+        BroadcastAgent::unsubscribe_internal(&mut model, "room", &tx);
+
+        assert!(model.channels.is_empty());
+        assert_eq!(BroadcastAgent::publish_internal(&mut model, "room", "x"), 0);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_publish_prunes_closed_and_skips_full_sinks() {
+        let mut model = BroadcastAgent::default();
+        let (closed, closed_rx) = mpsc::channel(1);
+        let (full, mut full_rx) = mpsc::channel(1);
+        drop(closed_rx);
+        BroadcastAgent::subscribe_internal(&mut model, "room".to_string(), closed);
+        BroadcastAgent::subscribe_internal(&mut model, "room".to_string(), full);
+
+        assert_eq!(BroadcastAgent::publish_internal(&mut model, "room", "first"), 1);
+        assert_eq!(BroadcastAgent::publish_internal(&mut model, "room", "second"), 0);
+        assert_eq!(model.channels["room"].len(), 1);
+        assert_eq!(&*full_rx.try_recv().unwrap(), "first");
+    }
+
+    #[test]
+    fn test_session_channel() {
+        let session_id = SessionId::generate();
+        assert_eq!(
+            BroadcastAgent::session_channel(&session_id),
+            format!("session:{}", session_id.as_str())
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_broadcast_agent_delivers_publish() {
+        let mut runtime = ActonApp::launch();
+        let handle = BroadcastAgent::spawn(&mut runtime).await.unwrap();
+
+        let (tx, mut rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+        handle.send(Subscribe::new("room", tx)).await;
+        handle.send(Publish::new("room", "<p>hi</p>")).await;
+
+        let fragment = rx.recv().await.expect("Failed to receive fragment");
+        assert_eq!(&*fragment, "<p>hi</p>");
+    }
+}
@@ -279,8 +279,7 @@ impl CsrfManagerAgent {
         let valid = model
             .tokens
             .get(session_id)
-            .filter(|data| !data.is_expired() && &data.token == token)
-            .is_some();
+            .is_some_and(|data| !data.is_expired() && &data.token == token);
 
         if valid {
             let new_token = CsrfToken::generate();
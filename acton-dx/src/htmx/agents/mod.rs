@@ -5,11 +5,16 @@
 
 use acton_reactive::prelude::{AgentConfig, Ern};
 
+pub mod broadcast;
 pub mod csrf_manager;
 pub mod request_reply;
 pub mod session_manager;
+pub mod session_store;
 
 // Re-export public types for use by middleware and extractors
+pub use broadcast::{
+    BroadcastAgent, BroadcastSink, ChannelAuthorizer, Publish, Subscribe, Unsubscribe,
+};
 pub use csrf_manager::{
     CleanupExpired as CsrfCleanupExpired, CsrfManagerAgent, CsrfToken, DeleteToken,
     GetOrCreateToken, ValidateToken,
@@ -17,9 +22,20 @@ pub use csrf_manager::{
 pub use request_reply::{create_request_reply, send_response, ResponseChannel};
 pub use session_manager::{
     // Unified messages (support both web handler and agent-to-agent patterns)
-    AddFlash, CleanupExpired, DeleteSession, LoadSession, SaveSession, SessionManagerAgent,
-    TakeFlashes,
+    ActiveSession, AddFlash, CleanupExpired, DeleteSession, DeleteUserSessions,
+    LimitUserSessions, ListUserSessions, LoadSession, RegenerateSession, RevokeUserSession,
+    SaveSession, SessionManagerAgent, TakeFlashes,
 };
+pub use session_store::{InMemorySessionStore, SessionStore};
+
+#[cfg(feature = "postgres")]
+pub use session_store::PostgresSessionStore;
+
+#[cfg(feature = "sqlite")]
+pub use session_store::SqliteSessionStore;
+
+#[cfg(feature = "redis")]
+pub use session_store::RedisSessionStore;
 
 /// Create a default agent configuration with the given name
 ///
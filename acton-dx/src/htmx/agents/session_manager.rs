@@ -1,39 +1,53 @@
 //! Session Manager Agent
 //!
 //! Actor-based session management using acton-reactive.
-//! Implements hybrid in-memory + Redis storage strategy.
+//! Persistence is delegated to a [`SessionStore`]: in-memory by default, or
+//! database-backed via [`SessionManagerAgent::spawn_with_store`].
 //!
 //! This module uses unified message patterns that support both:
 //! 1. **Agent-to-Agent**: Using `reply_envelope` for inter-agent communication
 //! 2. **Web Handler**: Using optional oneshot channels for request-reply from Axum handlers
 //!
 //! Messages with optional `response_tx` fields can be used from both contexts.
+//!
+//! With a per-user session limit (see
+//! [`SessionManagerAgent::spawn_with_session_limit`]), [`LimitUserSessions`]
+//! evicts a user's least recently active sessions on login.
 
 use crate::htmx::agents::request_reply::{create_request_reply, send_response, ResponseChannel};
 use crate::htmx::agents::default_agent_config;
-use crate::htmx::auth::session::{FlashMessage, SessionData, SessionId};
+use crate::htmx::agents::session_store::{InMemorySessionStore, SessionStore};
+use crate::htmx::auth::session::{FlashMessage, SessionData, SessionError, SessionId};
 use acton_reactive::prelude::*;
 use chrono::{DateTime, Duration, Utc};
 use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
 use tokio::sync::oneshot;
 
 // Type alias for the ManagedAgent builder type
 type SessionAgentBuilder = ManagedAgent<Idle, SessionManagerAgent>;
 
+#[cfg(feature = "redis")]
+use crate::htmx::agents::session_store::RedisSessionStore;
 #[cfg(feature = "redis")]
 use deadpool_redis::Pool as RedisPool;
 
 /// Session manager agent model
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct SessionManagerAgent {
-    /// In-memory session storage
-    sessions: HashMap<SessionId, SessionData>,
-    /// Expiry queue for cleanup (min-heap by expiration time)
-    expiry_queue: BinaryHeap<Reverse<(DateTime<Utc>, SessionId)>>,
-    /// Optional Redis backend for distributed sessions
-    #[cfg(feature = "redis")]
-    redis: Option<RedisPool>,
+    /// Session persistence backend
+    store: Arc<dyn SessionStore>,
+    /// Most sessions a user may be logged in with at once (unlimited if `None`)
+    max_sessions_per_user: Option<usize>,
+}
+
+impl Default for SessionManagerAgent {
+    fn default() -> Self {
+        Self {
+            store: Arc::new(InMemorySessionStore::new()),
+            max_sessions_per_user: None,
+        }
+    }
 }
 
 // ============================================================================
@@ -153,6 +167,197 @@ pub struct DeleteSession {
     pub session_id: SessionId,
 }
 
+/// Move a session to a newly generated ID
+///
+/// The session data is saved under the new ID and the old ID is deleted, so
+/// a session ID known before a login (session fixation) no longer works
+/// after it. Replies with the new ID, even if the old session didn't exist.
+#[derive(Clone, Debug)]
+pub struct RegenerateSession {
+    /// The session ID to replace
+    pub old_id: SessionId,
+    /// Optional response channel receiving the new session ID
+    pub response_tx: Option<ResponseChannel<SessionId>>,
+}
+
+impl RegenerateSession {
+    /// Create a new regenerate session message (fire-and-forget)
+    #[must_use]
+    pub const fn new(old_id: SessionId) -> Self {
+        Self {
+            old_id,
+            response_tx: None,
+        }
+    }
+
+    /// Create a new regenerate session request receiving the new ID
+    #[must_use]
+    pub fn with_response(old_id: SessionId) -> (Self, oneshot::Receiver<SessionId>) {
+        let (response_tx, rx) = create_request_reply();
+        let request = Self {
+            old_id,
+            response_tx: Some(response_tx),
+        };
+        (request, rx)
+    }
+}
+
+/// Message to delete every session logged in as a user
+///
+/// Used to sign a user out everywhere, e.g. after a password reset.
+#[derive(Clone, Debug)]
+pub struct DeleteUserSessions {
+    /// The user whose sessions to delete
+    pub user_id: i64,
+    /// Optional response channel receiving the number of sessions deleted
+    pub response_tx: Option<ResponseChannel<u64>>,
+}
+
+impl DeleteUserSessions {
+    /// Create a new delete user sessions message (fire-and-forget)
+    #[must_use]
+    pub const fn new(user_id: i64) -> Self {
+        Self {
+            user_id,
+            response_tx: None,
+        }
+    }
+
+    /// Create a new delete user sessions request with confirmation
+    #[must_use]
+    pub fn with_confirmation(user_id: i64) -> (Self, oneshot::Receiver<u64>) {
+        let (response_tx, rx) = create_request_reply();
+        let request = Self {
+            user_id,
+            response_tx: Some(response_tx),
+        };
+        (request, rx)
+    }
+}
+
+/// Message to enforce the per-user session limit after a login
+///
+/// If the user has more sessions than the manager's limit allows, the least
+/// recently active ones are deleted. `keep` (the session that just logged in)
+/// is never evicted. Does nothing when no limit is configured.
+#[derive(Clone, Debug)]
+pub struct LimitUserSessions {
+    /// The user who logged in
+    pub user_id: i64,
+    /// The session that logged in
+    pub keep: SessionId,
+    /// Optional response channel receiving the number of sessions evicted
+    pub response_tx: Option<ResponseChannel<u64>>,
+}
+
+impl LimitUserSessions {
+    /// Create a new limit user sessions message (fire-and-forget)
+    #[must_use]
+    pub const fn new(user_id: i64, keep: SessionId) -> Self {
+        Self {
+            user_id,
+            keep,
+            response_tx: None,
+        }
+    }
+
+    /// Create a new limit user sessions request with confirmation
+    #[must_use]
+    pub fn with_confirmation(user_id: i64, keep: SessionId) -> (Self, oneshot::Receiver<u64>) {
+        let (response_tx, rx) = create_request_reply();
+        let request = Self {
+            user_id,
+            keep,
+            response_tx: Some(response_tx),
+        };
+        (request, rx)
+    }
+}
+
+/// A logged-in session, as listed to its user
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveSession {
+    /// Non-secret name of the session (see [`SessionId::handle`])
+    pub handle: String,
+    /// Client `User-Agent` from the session's most recent request
+    pub user_agent: Option<String>,
+    /// Client IP address from the session's most recent request
+    pub ip_address: Option<String>,
+    /// When the session was created
+    pub created_at: DateTime<Utc>,
+    /// When the session was last used
+    pub last_seen: DateTime<Utc>,
+}
+
+/// List a user's unexpired sessions, most recently active first
+#[derive(Clone, Debug)]
+pub struct ListUserSessions {
+    /// The user whose sessions to list
+    pub user_id: i64,
+    /// Optional response channel receiving the sessions
+    pub response_tx: Option<ResponseChannel<Vec<ActiveSession>>>,
+}
+
+impl ListUserSessions {
+    /// Create a new list user sessions message (fire-and-forget)
+    #[must_use]
+    pub const fn new(user_id: i64) -> Self {
+        Self {
+            user_id,
+            response_tx: None,
+        }
+    }
+
+    /// Create a new list user sessions request with response channel
+    #[must_use]
+    pub fn with_response(user_id: i64) -> (Self, oneshot::Receiver<Vec<ActiveSession>>) {
+        let (response_tx, rx) = create_request_reply();
+        let request = Self {
+            user_id,
+            response_tx: Some(response_tx),
+        };
+        (request, rx)
+    }
+}
+
+/// Delete one of a user's sessions by its [handle](ActiveSession::handle)
+///
+/// Only sessions logged in as `user_id` can be revoked. Replies whether a
+/// session was deleted.
+#[derive(Clone, Debug)]
+pub struct RevokeUserSession {
+    /// The user who owns the session
+    pub user_id: i64,
+    /// Handle of the session to delete
+    pub handle: String,
+    /// Optional response channel receiving whether a session was deleted
+    pub response_tx: Option<ResponseChannel<bool>>,
+}
+
+impl RevokeUserSession {
+    /// Create a new revoke user session message (fire-and-forget)
+    #[must_use]
+    pub const fn new(user_id: i64, handle: String) -> Self {
+        Self {
+            user_id,
+            handle,
+            response_tx: None,
+        }
+    }
+
+    /// Create a new revoke user session request with confirmation
+    #[must_use]
+    pub fn with_confirmation(user_id: i64, handle: String) -> (Self, oneshot::Receiver<bool>) {
+        let (response_tx, rx) = create_request_reply();
+        let request = Self {
+            user_id,
+            handle,
+            response_tx: Some(response_tx),
+        };
+        (request, rx)
+    }
+}
+
 /// Message to trigger cleanup of expired sessions
 #[derive(Clone, Debug)]
 pub struct CleanupExpired;
@@ -169,7 +374,7 @@ pub struct AddFlash {
 impl SessionManagerAgent {
     /// Spawn session manager agent without Redis backend
     ///
-    /// Uses in-memory storage only. Suitable for development or single-instance deployments.
+    /// Uses [`InMemorySessionStore`]. Suitable for development or single-instance deployments.
     ///
     /// # Errors
     ///
@@ -180,9 +385,47 @@ impl SessionManagerAgent {
         Self::configure_handlers(builder).await
     }
 
+    /// Spawn session manager with a custom storage backend
+    ///
+    /// Use a database-backed store such as
+    /// [`PostgresSessionStore`](crate::htmx::agents::PostgresSessionStore) so
+    /// sessions survive restarts and are shared between instances.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent initialization fails
+    pub async fn spawn_with_store(
+        runtime: &mut AgentRuntime,
+        store: Box<dyn SessionStore>,
+    ) -> anyhow::Result<AgentHandle> {
+        Self::spawn_with_session_limit(runtime, store, None).await
+    }
+
+    /// Spawn session manager with a custom storage backend and a per-user
+    /// session limit
+    ///
+    /// With `max_sessions_per_user` set, [`LimitUserSessions`] evicts a
+    /// user's least recently active sessions beyond the limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent initialization fails
+    pub async fn spawn_with_session_limit(
+        runtime: &mut AgentRuntime,
+        store: Box<dyn SessionStore>,
+        max_sessions_per_user: Option<usize>,
+    ) -> anyhow::Result<AgentHandle> {
+        let config = default_agent_config("session_manager")?;
+        let mut builder = runtime.new_agent_with_config::<Self>(config).await;
+        builder.model.store = Arc::from(store);
+        builder.model.max_sessions_per_user = max_sessions_per_user;
+        Self::configure_handlers(builder).await
+    }
+
     /// Spawn session manager with Redis backend
     ///
-    /// Uses Redis for distributed session storage with in-memory caching.
+    /// Uses [`RedisSessionStore`] with its default TTL for distributed
+    /// session storage.
     ///
     /// # Errors
     ///
@@ -192,13 +435,15 @@ impl SessionManagerAgent {
         runtime: &mut AgentRuntime,
         redis_pool: RedisPool,
     ) -> anyhow::Result<AgentHandle> {
-        let config = default_agent_config("session_manager")?;
-        let mut builder = runtime.new_agent_with_config::<Self>(config).await;
-        builder.model.redis = Some(redis_pool);
-        Self::configure_handlers(builder).await
+        Self::spawn_with_store(runtime, Box::new(RedisSessionStore::new(redis_pool))).await
     }
 
     /// Configure all message handlers for the session manager
+    ///
+    /// Store calls run on their own tasks, which mutating handlers await, so
+    /// read-modify-write operations such as [`AddFlash`] still run one at a
+    /// time within this agent.
+    #[allow(clippy::too_many_lines)]
     async fn configure_handlers(mut builder: SessionAgentBuilder) -> anyhow::Result<AgentHandle> {
         builder
             // ================================================================
@@ -207,10 +452,20 @@ impl SessionManagerAgent {
             .act_on::<LoadSession>(|agent, envelope| {
                 let session_id = envelope.message().session_id.clone();
                 let response_tx = envelope.message().response_tx.clone();
-                let session = agent.model.sessions.get(&session_id).cloned();
+                let store = Arc::clone(&agent.model.store);
                 let reply_envelope = envelope.reply_envelope();
 
                 Box::pin(async move {
+                    // Store futures need not be `Sync`, so run them on a task
+                    let session = tokio::spawn(async move { store.load(&session_id).await })
+                        .await
+                        .map_err(anyhow::Error::from)
+                        .and_then(|result| result.map_err(anyhow::Error::from))
+                        .unwrap_or_else(|e| {
+                            tracing::warn!(error = %e, "Failed to load session");
+                            None
+                        });
+
                     // Use validate_and_touch to combine expiry check and touch
                     let result = session.and_then(|mut data| {
                         if data.validate_and_touch(Duration::hours(24)) {
@@ -233,37 +488,36 @@ impl SessionManagerAgent {
                 let session_id = envelope.message().session_id.clone();
                 let data = envelope.message().data.clone();
                 let response_tx = envelope.message().response_tx.clone();
-
-                agent
-                    .model
-                    .sessions
-                    .insert(session_id.clone(), data.clone());
-                agent
-                    .model
-                    .expiry_queue
-                    .push(Reverse((data.expires_at, session_id)));
+                let store = Arc::clone(&agent.model.store);
 
                 AgentReply::from_async(async move {
+                    let saved = tokio::spawn(async move {
+                        store.save(&session_id, &data).await.map_err(|e| {
+                            tracing::error!(error = %e, "Failed to save session");
+                        })
+                    })
+                    .await
+                    .is_ok_and(|result| result.is_ok());
+
                     // Send confirmation to web handler if channel provided
                     if let Some(tx) = response_tx {
-                        let _ = send_response(tx, true).await;
+                        let _ = send_response(tx, saved).await;
                     }
                 })
             })
             .mutate_on::<TakeFlashes>(|agent, envelope| {
                 let session_id = envelope.message().session_id.clone();
                 let response_tx = envelope.message().response_tx.clone();
+                let store = Arc::clone(&agent.model.store);
                 let reply_envelope = envelope.reply_envelope();
 
-                // Take and clear flash messages atomically
-                let messages = agent
-                    .model
-                    .sessions
-                    .get_mut(&session_id)
-                    .map(|session| std::mem::take(&mut session.flash_messages))
+                AgentReply::from_async(async move {
+                    let messages = tokio::spawn(async move {
+                        Self::take_flashes(store.as_ref(), &session_id).await
+                    })
+                    .await
                     .unwrap_or_default();
 
-                AgentReply::from_async(async move {
                     // Send response to web handler if channel provided
                     if let Some(tx) = response_tx {
                         let _ = send_response(tx, messages.clone()).await;
@@ -274,53 +528,286 @@ impl SessionManagerAgent {
                 })
             })
             .mutate_on::<DeleteSession>(|agent, envelope| {
-                agent.model.sessions.remove(&envelope.message().session_id);
-                AgentReply::immediate()
+                let session_id = envelope.message().session_id.clone();
+                let store = Arc::clone(&agent.model.store);
+
+                AgentReply::from_async(async move {
+                    let _ = tokio::spawn(async move {
+                        if let Err(e) = store.delete(&session_id).await {
+                            tracing::error!(error = %e, "Failed to delete session");
+                        }
+                    })
+                    .await;
+                })
             })
-            .mutate_on::<CleanupExpired>(|agent, _envelope| {
-                let now = Utc::now();
-                let mut expired = Vec::new();
-
-                loop {
-                    let should_pop = agent
-                        .model
-                        .expiry_queue
-                        .peek()
-                        .is_some_and(|Reverse((expiry, _))| *expiry <= now);
-
-                    if should_pop {
-                        if let Some(Reverse((_, session_id))) = agent.model.expiry_queue.pop() {
-                            expired.push(session_id);
+            .mutate_on::<RegenerateSession>(|agent, envelope| {
+                let old_id = envelope.message().old_id.clone();
+                let response_tx = envelope.message().response_tx.clone();
+                let store = Arc::clone(&agent.model.store);
+                let reply_envelope = envelope.reply_envelope();
+
+                AgentReply::from_async(async move {
+                    let new_id = SessionId::generate();
+                    let moved_id = new_id.clone();
+                    let _ = tokio::spawn(async move {
+                        if let Err(e) = Self::move_session(store.as_ref(), &old_id, &moved_id).await
+                        {
+                            tracing::error!(error = %e, "Failed to regenerate session");
                         }
-                    } else {
-                        break;
+                    })
+                    .await;
+
+                    // Send response to web handler if channel provided
+                    if let Some(tx) = response_tx {
+                        let _ = send_response(tx, new_id.clone()).await;
+                    }
+
+                    // Always send reply envelope for agent-to-agent
+                    let _: () = reply_envelope.send(new_id).await;
+                })
+            })
+            .mutate_on::<DeleteUserSessions>(|agent, envelope| {
+                let user_id = envelope.message().user_id;
+                let response_tx = envelope.message().response_tx.clone();
+                let store = Arc::clone(&agent.model.store);
+
+                AgentReply::from_async(async move {
+                    let removed = tokio::spawn(async move {
+                        store.delete_for_user(user_id).await.unwrap_or_else(|e| {
+                            tracing::error!(error = %e, user_id, "Failed to delete user sessions");
+                            0
+                        })
+                    })
+                    .await
+                    .unwrap_or_default();
+
+                    if let Some(tx) = response_tx {
+                        let _ = send_response(tx, removed).await;
+                    }
+                })
+            })
+            .mutate_on::<LimitUserSessions>(|agent, envelope| {
+                let user_id = envelope.message().user_id;
+                let keep = envelope.message().keep.clone();
+                let response_tx = envelope.message().response_tx.clone();
+                let store = Arc::clone(&agent.model.store);
+                let max_sessions = agent.model.max_sessions_per_user;
+
+                AgentReply::from_async(async move {
+                    let evicted = match max_sessions {
+                        Some(max_sessions) => tokio::spawn(async move {
+                            Self::evict_excess_sessions(store.as_ref(), user_id, &keep, max_sessions)
+                                .await
+                                .unwrap_or_else(|e| {
+                                    tracing::error!(error = %e, user_id, "Failed to limit user sessions");
+                                    0
+                                })
+                        })
+                        .await
+                        .unwrap_or_default(),
+                        None => 0,
+                    };
+
+                    if let Some(tx) = response_tx {
+                        let _ = send_response(tx, evicted).await;
                     }
-                }
+                })
+            })
+            .act_on::<ListUserSessions>(|agent, envelope| {
+                let user_id = envelope.message().user_id;
+                let response_tx = envelope.message().response_tx.clone();
+                let store = Arc::clone(&agent.model.store);
+                let reply_envelope = envelope.reply_envelope();
+
+                Box::pin(async move {
+                    let sessions =
+                        tokio::spawn(async move { Self::active_sessions(store.as_ref(), user_id).await })
+                            .await
+                            .map_err(anyhow::Error::from)
+                            .and_then(|result| result.map_err(anyhow::Error::from))
+                            .unwrap_or_else(|e| {
+                                tracing::warn!(error = %e, user_id, "Failed to list user sessions");
+                                Vec::new()
+                            });
 
-                for session_id in expired {
-                    agent.model.sessions.remove(&session_id);
-                }
+                    // Send response to web handler if channel provided
+                    if let Some(tx) = response_tx {
+                        let _ = send_response(tx, sessions.clone()).await;
+                    }
 
-                AgentReply::immediate()
+                    // Always send reply envelope for agent-to-agent
+                    let _: () = reply_envelope.send(sessions).await;
+                })
+            })
+            .mutate_on::<RevokeUserSession>(|agent, envelope| {
+                let user_id = envelope.message().user_id;
+                let handle = envelope.message().handle.clone();
+                let response_tx = envelope.message().response_tx.clone();
+                let store = Arc::clone(&agent.model.store);
+
+                AgentReply::from_async(async move {
+                    let revoked = tokio::spawn(async move {
+                        Self::revoke_session(store.as_ref(), user_id, &handle)
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::error!(error = %e, user_id, "Failed to revoke session");
+                                false
+                            })
+                    })
+                    .await
+                    .unwrap_or_default();
+
+                    if let Some(tx) = response_tx {
+                        let _ = send_response(tx, revoked).await;
+                    }
+                })
+            })
+            .mutate_on::<CleanupExpired>(|agent, _envelope| {
+                let store = Arc::clone(&agent.model.store);
+
+                AgentReply::from_async(async move {
+                    let _ = tokio::spawn(async move {
+                        match store.cleanup_expired().await {
+                            Ok(removed) => tracing::debug!("Cleaned up {removed} expired sessions"),
+                            Err(e) => tracing::error!(error = %e, "Failed to clean up sessions"),
+                        }
+                    })
+                    .await;
+                })
             })
             .mutate_on::<AddFlash>(|agent, envelope| {
                 let session_id = envelope.message().session_id.clone();
                 let message = envelope.message().message.clone();
+                let store = Arc::clone(&agent.model.store);
 
-                if let Some(session) = agent.model.sessions.get_mut(&session_id) {
-                    session.flash_messages.push(message);
-                }
-
-                AgentReply::immediate()
+                AgentReply::from_async(async move {
+                    let _ = tokio::spawn(async move {
+                        if let Err(e) = Self::add_flash(store.as_ref(), &session_id, message).await
+                        {
+                            tracing::error!(error = %e, "Failed to add flash message");
+                        }
+                    })
+                    .await;
+                })
             });
 
         Ok(builder.start().await)
     }
+
+    /// Save a session's data under `new_id` and delete `old_id`
+    async fn move_session(
+        store: &dyn SessionStore,
+        old_id: &SessionId,
+        new_id: &SessionId,
+    ) -> Result<(), SessionError> {
+        if let Some(data) = store.load(old_id).await? {
+            store.save(new_id, &data).await?;
+        }
+        store.delete(old_id).await
+    }
+
+    /// Delete a user's least recently active sessions beyond `max_sessions`
+    ///
+    /// `keep` counts toward the limit whether or not it has been saved yet.
+    async fn evict_excess_sessions(
+        store: &dyn SessionStore,
+        user_id: i64,
+        keep: &SessionId,
+        max_sessions: usize,
+    ) -> Result<u64, SessionError> {
+        let mut others: Vec<_> = store
+            .list_for_user(user_id)
+            .await?
+            .into_iter()
+            .filter(|(id, _)| id != keep)
+            .collect();
+        others.sort_by_key(|(_, data)| Reverse(data.last_accessed));
+
+        let mut evicted = 0;
+        for (id, _) in others.iter().skip(max_sessions.saturating_sub(1)) {
+            store.delete(id).await?;
+            evicted += 1;
+        }
+        Ok(evicted)
+    }
+
+    /// A user's unexpired sessions, most recently active first
+    async fn active_sessions(
+        store: &dyn SessionStore,
+        user_id: i64,
+    ) -> Result<Vec<ActiveSession>, SessionError> {
+        let mut sessions: Vec<_> = store
+            .list_for_user(user_id)
+            .await?
+            .into_iter()
+            .filter(|(_, data)| !data.is_expired())
+            .map(|(id, data)| ActiveSession {
+                handle: id.handle(),
+                user_agent: data.user_agent,
+                ip_address: data.ip_address,
+                created_at: data.created_at,
+                last_seen: data.last_accessed,
+            })
+            .collect();
+        sessions.sort_by_key(|session| Reverse(session.last_seen));
+        Ok(sessions)
+    }
+
+    /// Delete the session of `user_id` whose handle is `handle`
+    async fn revoke_session(
+        store: &dyn SessionStore,
+        user_id: i64,
+        handle: &str,
+    ) -> Result<bool, SessionError> {
+        let session = store
+            .list_for_user(user_id)
+            .await?
+            .into_iter()
+            .find(|(id, _)| id.handle() == handle);
+        match session {
+            Some((id, _)) => store.delete(&id).await.map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Take and clear flash messages, persisting the emptied session
+    async fn take_flashes(store: &dyn SessionStore, session_id: &SessionId) -> Vec<FlashMessage> {
+        let mut session = match store.load(session_id).await {
+            Ok(Some(session)) => session,
+            Ok(None) => return Vec::new(),
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to load session for flash messages");
+                return Vec::new();
+            }
+        };
+
+        let messages = std::mem::take(&mut session.flash_messages);
+        if !messages.is_empty() {
+            if let Err(e) = store.save(session_id, &session).await {
+                tracing::error!(error = %e, "Failed to clear flash messages");
+            }
+        }
+        messages
+    }
+
+    /// Append a flash message to an existing session
+    async fn add_flash(
+        store: &dyn SessionStore,
+        session_id: &SessionId,
+        message: FlashMessage,
+    ) -> Result<(), SessionError> {
+        if let Some(mut session) = store.load(session_id).await? {
+            session.flash_messages.push(message);
+            store.save(session_id, &session).await?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Utc;
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_session_manager_creation() {
@@ -435,6 +922,172 @@ mod tests {
         runtime.shutdown_all().await.expect("Failed to shutdown");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_delete_user_sessions() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+
+        let mut user_data = SessionData::new();
+        user_data.user_id = Some(7);
+        let user_session = SessionId::generate();
+        let other_session = SessionId::generate();
+
+        let (request, rx) = SaveSession::with_confirmation(user_session.clone(), user_data);
+        session_manager.send(request).await;
+        assert!(rx.await.unwrap());
+        let (request, rx) = SaveSession::with_confirmation(other_session.clone(), SessionData::new());
+        session_manager.send(request).await;
+        assert!(rx.await.unwrap());
+
+        let (request, rx) = DeleteUserSessions::with_confirmation(7);
+        session_manager.send(request).await;
+        let removed = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx)
+            .await
+            .expect("Timeout")
+            .expect("Channel closed");
+        assert_eq!(removed, 1);
+
+        let (request, rx) = LoadSession::with_response(user_session);
+        session_manager.send(request).await;
+        assert!(rx.await.unwrap().is_none());
+        let (request, rx) = LoadSession::with_response(other_session);
+        session_manager.send(request).await;
+        assert!(rx.await.unwrap().is_some());
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    /// Save a session logged in as `user_id`, last seen `minutes_ago`
+    async fn save_user_session(
+        session_manager: &AgentHandle,
+        user_id: i64,
+        minutes_ago: i64,
+    ) -> SessionId {
+        let mut data = SessionData::new();
+        data.user_id = Some(user_id);
+        data.last_accessed = Utc::now() - Duration::minutes(minutes_ago);
+        let session_id = SessionId::generate();
+        let (request, rx) = SaveSession::with_confirmation(session_id.clone(), data);
+        session_manager.send(request).await;
+        assert!(rx.await.unwrap());
+        session_id
+    }
+
+    async fn session_exists(session_manager: &AgentHandle, session_id: &SessionId) -> bool {
+        let (request, rx) = LoadSession::with_response(session_id.clone());
+        session_manager.send(request).await;
+        rx.await.unwrap().is_some()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_session_limit_evicts_least_recently_active() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn_with_session_limit(
+            &mut runtime,
+            Box::new(InMemorySessionStore::new()),
+            Some(2),
+        )
+        .await
+        .unwrap();
+
+        let oldest = save_user_session(&session_manager, 7, 30).await;
+        let recent = save_user_session(&session_manager, 7, 5).await;
+        let other_user = save_user_session(&session_manager, 8, 60).await;
+        let login = save_user_session(&session_manager, 7, 0).await;
+
+        let (request, rx) = LimitUserSessions::with_confirmation(7, login.clone());
+        session_manager.send(request).await;
+        assert_eq!(rx.await.unwrap(), 1);
+
+        assert!(!session_exists(&session_manager, &oldest).await);
+        assert!(session_exists(&session_manager, &recent).await);
+        assert!(session_exists(&session_manager, &login).await);
+        assert!(session_exists(&session_manager, &other_user).await);
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_session_limit_disabled_by_default() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+
+        let first = save_user_session(&session_manager, 7, 30).await;
+        let login = save_user_session(&session_manager, 7, 0).await;
+
+        let (request, rx) = LimitUserSessions::with_confirmation(7, login);
+        session_manager.send(request).await;
+        assert_eq!(rx.await.unwrap(), 0);
+        assert!(session_exists(&session_manager, &first).await);
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_and_revoke_user_sessions() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+
+        let older = save_user_session(&session_manager, 7, 30).await;
+        let newer = save_user_session(&session_manager, 7, 5).await;
+        let other_user = save_user_session(&session_manager, 8, 0).await;
+
+        let (request, rx) = ListUserSessions::with_response(7);
+        session_manager.send(request).await;
+        let handles: Vec<_> = rx.await.unwrap().into_iter().map(|s| s.handle).collect();
+        assert_eq!(handles, vec![newer.handle(), older.handle()]);
+
+        // Another user's session can't be revoked
+        let (request, rx) = RevokeUserSession::with_confirmation(7, other_user.handle());
+        session_manager.send(request).await;
+        assert!(!rx.await.unwrap());
+        assert!(session_exists(&session_manager, &other_user).await);
+
+        let (request, rx) = RevokeUserSession::with_confirmation(7, older.handle());
+        session_manager.send(request).await;
+        assert!(rx.await.unwrap());
+        assert!(!session_exists(&session_manager, &older).await);
+        assert!(session_exists(&session_manager, &newer).await);
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_regenerate_session_moves_data() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+
+        let old_id = SessionId::generate();
+        let mut data = SessionData::new();
+        data.set("cart".to_string(), vec![1, 2, 3]).unwrap();
+        let (request, rx) = SaveSession::with_confirmation(old_id.clone(), data);
+        session_manager.send(request).await;
+        assert!(rx.await.unwrap());
+
+        let (request, rx) = RegenerateSession::with_response(old_id.clone());
+        session_manager.send(request).await;
+        let new_id = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx)
+            .await
+            .expect("Timeout")
+            .expect("Channel closed");
+        assert_ne!(new_id, old_id);
+
+        let (request, rx) = LoadSession::with_response(old_id);
+        session_manager.send(request).await;
+        assert!(rx.await.unwrap().is_none());
+        let (request, rx) = LoadSession::with_response(new_id);
+        session_manager.send(request).await;
+        let moved = rx.await.unwrap().expect("Session should be moved");
+        assert_eq!(moved.get::<Vec<i32>>("cart"), Some(vec![1, 2, 3]));
+
+        // Unknown sessions still get a fresh ID
+        let (request, rx) = RegenerateSession::with_response(SessionId::generate());
+        session_manager.send(request).await;
+        assert!(rx.await.is_ok());
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_flash_messages_with_verification() {
         let mut runtime = ActonApp::launch();
@@ -650,4 +1303,53 @@ mod tests {
 
         runtime.shutdown_all().await.expect("Failed to shutdown");
     }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sql_session_survives_agent_restart() {
+        use crate::htmx::agents::SqliteSessionStore;
+
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}?mode=rwc", dir.path().join("sessions.db").display());
+        let pool = sqlx::SqlitePool::connect(&url).await.unwrap();
+        sqlx::raw_sql(include_str!("../../../../migrations/005_create_sessions_table.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let session_id = SessionId::generate();
+        let mut data = SessionData::new();
+        data.set("user".to_string(), "alice".to_string()).unwrap();
+
+        // Save through the first agent, then shut it down
+        let mut runtime = ActonApp::launch();
+        let store = Box::new(SqliteSessionStore::new(pool.clone()));
+        let session_manager = SessionManagerAgent::spawn_with_store(&mut runtime, store)
+            .await
+            .unwrap();
+        let (request, rx) = SaveSession::with_confirmation(session_id.clone(), data);
+        session_manager.send(request).await;
+        assert!(rx.await.expect("Channel closed"), "Save should succeed");
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+
+        // Load through a fresh agent on a fresh pool
+        pool.close().await;
+        let pool = sqlx::SqlitePool::connect(&url).await.unwrap();
+        let mut runtime = ActonApp::launch();
+        let store = Box::new(SqliteSessionStore::new(pool));
+        let session_manager = SessionManagerAgent::spawn_with_store(&mut runtime, store)
+            .await
+            .unwrap();
+        let (request, rx) = LoadSession::with_response(session_id);
+        session_manager.send(request).await;
+
+        let loaded = tokio::time::timeout(tokio::time::Duration::from_secs(1), rx)
+            .await
+            .expect("Timeout")
+            .expect("Channel closed")
+            .expect("Session should survive restart");
+        assert_eq!(loaded.get::<String>("user"), Some("alice".to_string()));
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
 }
@@ -0,0 +1,751 @@
+//! Session storage backends
+//!
+//! The [`SessionManagerAgent`](super::SessionManagerAgent) delegates
+//! persistence to a [`SessionStore`]:
+//!
+//! - [`InMemorySessionStore`]: process-local storage (the default)
+//! - [`PostgresSessionStore`]: `sessions` table in PostgreSQL (requires `postgres` feature)
+//! - [`SqliteSessionStore`]: `sessions` table in SQLite (requires `sqlite` feature)
+//! - [`RedisSessionStore`]: `session:{id}` keys expiring via TTL, indexed by
+//!   user in `user_sessions:{user_id}` sets (requires `redis` feature)
+//!
+//! The SQL stores expect the table from
+//! `migrations/005_create_sessions_table.sql` and store [`SessionData`] as
+//! JSON, so sessions survive restarts and are shared between instances.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::agents::{PostgresSessionStore, SessionManagerAgent};
+//!
+//! let store = PostgresSessionStore::new(pool.clone());
+//! let session_manager = SessionManagerAgent::spawn_with_store(&mut runtime, Box::new(store)).await?;
+//! ```
+
+use crate::htmx::auth::session::{SessionData, SessionError, SessionId};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::Debug;
+
+#[cfg(feature = "postgres")]
+use sqlx::PgPool;
+
+#[cfg(feature = "sqlite")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "redis")]
+use deadpool_redis::Pool as RedisPool;
+
+/// Persistence backend for session data
+///
+/// Implementations must be thread-safe; the session manager agent shares a
+/// single store across all requests.
+#[async_trait]
+pub trait SessionStore: Send + Sync + Debug {
+    /// Load a session by ID
+    ///
+    /// Returns the stored data even if it has expired; expiry is checked by
+    /// the session manager.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails or the stored data cannot be decoded
+    async fn load(&self, id: &SessionId) -> Result<Option<SessionData>, SessionError>;
+
+    /// Insert or replace a session
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails or the data cannot be encoded
+    async fn save(&self, id: &SessionId, data: &SessionData) -> Result<(), SessionError>;
+
+    /// Delete a session (no-op if it does not exist)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn delete(&self, id: &SessionId) -> Result<(), SessionError>;
+
+    /// Delete every session logged in as `user_id`
+    ///
+    /// Returns the number of sessions removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError>;
+
+    /// List every session logged in as `user_id`
+    ///
+    /// Like [`load`](Self::load), may include expired sessions.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails or stored data cannot be decoded
+    async fn list_for_user(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<(SessionId, SessionData)>, SessionError>;
+
+    /// Delete all expired sessions
+    ///
+    /// Returns the number of sessions removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn cleanup_expired(&self) -> Result<u64, SessionError>;
+}
+
+/// Process-local session storage
+///
+/// Sessions are lost on restart and are not shared between instances.
+#[derive(Debug, Default)]
+pub struct InMemorySessionStore {
+    inner: Mutex<InMemoryInner>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryInner {
+    /// Session storage
+    sessions: HashMap<SessionId, SessionData>,
+    /// Expiry queue for cleanup (min-heap by expiration time)
+    expiry_queue: BinaryHeap<Reverse<(DateTime<Utc>, SessionId)>>,
+}
+
+impl InMemorySessionStore {
+    /// Create an empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStore for InMemorySessionStore {
+    async fn load(&self, id: &SessionId) -> Result<Option<SessionData>, SessionError> {
+        Ok(self.inner.lock().sessions.get(id).cloned())
+    }
+
+    async fn save(&self, id: &SessionId, data: &SessionData) -> Result<(), SessionError> {
+        let mut inner = self.inner.lock();
+        inner.sessions.insert(id.clone(), data.clone());
+        inner.expiry_queue.push(Reverse((data.expires_at, id.clone())));
+        drop(inner);
+        Ok(())
+    }
+
+    async fn delete(&self, id: &SessionId) -> Result<(), SessionError> {
+        self.inner.lock().sessions.remove(id);
+        Ok(())
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError> {
+        let mut inner = self.inner.lock();
+        let before = inner.sessions.len();
+        inner.sessions.retain(|_, data| data.user_id != Some(user_id));
+        let removed = before - inner.sessions.len();
+        drop(inner);
+        Ok(removed as u64)
+    }
+
+    async fn list_for_user(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<(SessionId, SessionData)>, SessionError> {
+        Ok(self
+            .inner
+            .lock()
+            .sessions
+            .iter()
+            .filter(|(_, data)| data.user_id == Some(user_id))
+            .map(|(id, data)| (id.clone(), data.clone()))
+            .collect())
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, SessionError> {
+        let now = Utc::now();
+        let mut inner = self.inner.lock();
+        let mut removed = 0;
+
+        while inner
+            .expiry_queue
+            .peek()
+            .is_some_and(|Reverse((expiry, _))| *expiry <= now)
+        {
+            let Some(Reverse((_, session_id))) = inner.expiry_queue.pop() else {
+                break;
+            };
+            // Sessions saved again since this entry was queued may not be expired
+            if inner
+                .sessions
+                .get(&session_id)
+                .is_some_and(|data| data.expires_at <= now)
+            {
+                inner.sessions.remove(&session_id);
+                removed += 1;
+            }
+        }
+        drop(inner);
+
+        Ok(removed)
+    }
+}
+
+/// PostgreSQL session storage
+///
+/// Requires the `sessions` table from `migrations/005_create_sessions_table.sql`.
+#[cfg(feature = "postgres")]
+#[derive(Debug, Clone)]
+pub struct PostgresSessionStore {
+    pool: PgPool,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresSessionStore {
+    /// Create a store backed by `pool`
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl SessionStore for PostgresSessionStore {
+    async fn load(&self, id: &SessionId) -> Result<Option<SessionData>, SessionError> {
+        let data: Option<String> = sqlx::query_scalar("SELECT data FROM sessions WHERE id = $1")
+            .bind(id.as_str())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        data.map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(SessionError::from)
+    }
+
+    async fn save(&self, id: &SessionId, data: &SessionData) -> Result<(), SessionError> {
+        sqlx::query(
+            r"
+            INSERT INTO sessions (id, data, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (id) DO UPDATE SET data = EXCLUDED.data, expires_at = EXCLUDED.expires_at
+            ",
+        )
+        .bind(id.as_str())
+        .bind(serde_json::to_string(data)?)
+        .bind(data.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: &SessionId) -> Result<(), SessionError> {
+        sqlx::query("DELETE FROM sessions WHERE id = $1")
+            .bind(id.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError> {
+        let result =
+            sqlx::query("DELETE FROM sessions WHERE (data::jsonb ->> 'user_id')::BIGINT = $1")
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn list_for_user(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<(SessionId, SessionData)>, SessionError> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT id, data FROM sessions WHERE (data::jsonb ->> 'user_id')::BIGINT = $1",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        decode_rows(rows)
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, SessionError> {
+        let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= $1")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// SQLite session storage
+///
+/// Requires the `sessions` table from `migrations/005_create_sessions_table.sql`.
+#[cfg(feature = "sqlite")]
+#[derive(Debug, Clone)]
+pub struct SqliteSessionStore {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteSessionStore {
+    /// Create a store backed by `pool`
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl SessionStore for SqliteSessionStore {
+    async fn load(&self, id: &SessionId) -> Result<Option<SessionData>, SessionError> {
+        let data: Option<String> = sqlx::query_scalar("SELECT data FROM sessions WHERE id = ?")
+            .bind(id.as_str())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        data.map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(SessionError::from)
+    }
+
+    async fn save(&self, id: &SessionId, data: &SessionData) -> Result<(), SessionError> {
+        sqlx::query(
+            r"INSERT INTO sessions (id, data, expires_at) VALUES (?, ?, ?)
+              ON CONFLICT (id) DO UPDATE SET data = excluded.data, expires_at = excluded.expires_at",
+        )
+        .bind(id.as_str())
+        .bind(serde_json::to_string(data)?)
+        .bind(data.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: &SessionId) -> Result<(), SessionError> {
+        sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(id.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError> {
+        let result = sqlx::query("DELETE FROM sessions WHERE json_extract(data, '$.user_id') = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn list_for_user(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<(SessionId, SessionData)>, SessionError> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, data FROM sessions WHERE json_extract(data, '$.user_id') = ?")
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+        decode_rows(rows)
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, SessionError> {
+        let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= ?")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Decode `(id, data)` rows from the SQL stores
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn decode_rows(rows: Vec<(String, String)>) -> Result<Vec<(SessionId, SessionData)>, SessionError> {
+    rows.into_iter()
+        .map(|(id, json)| Ok((SessionId::try_from_string(id)?, serde_json::from_str(&json)?)))
+        .collect()
+}
+
+/// Default Redis session TTL (24 hours, matching `session_max_age_secs`)
+#[cfg(feature = "redis")]
+pub const DEFAULT_REDIS_SESSION_TTL_SECS: u64 = 86400;
+
+/// Redis session storage
+///
+/// Stores each session as JSON under `session:{id}` with a TTL, so Redis
+/// expires sessions itself and [`cleanup_expired`](SessionStore::cleanup_expired)
+/// is a no-op. Saving a session resets its TTL.
+///
+/// Logged-in sessions are also added to a `user_sessions:{user_id}` set.
+/// Members whose session has expired or changed user are pruned when the set
+/// is listed.
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+pub struct RedisSessionStore {
+    pool: RedisPool,
+    ttl_secs: u64,
+}
+
+#[cfg(feature = "redis")]
+impl Debug for RedisSessionStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisSessionStore")
+            .field("ttl_secs", &self.ttl_secs)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "redis")]
+impl RedisSessionStore {
+    /// Create a store backed by `pool` with [`DEFAULT_REDIS_SESSION_TTL_SECS`]
+    #[must_use]
+    pub const fn new(pool: RedisPool) -> Self {
+        Self {
+            pool,
+            ttl_secs: DEFAULT_REDIS_SESSION_TTL_SECS,
+        }
+    }
+
+    /// Set the TTL applied on every save
+    #[must_use]
+    pub const fn with_ttl_secs(mut self, ttl_secs: u64) -> Self {
+        self.ttl_secs = ttl_secs;
+        self
+    }
+
+    /// Redis key for a session
+    #[must_use]
+    pub fn key(id: &SessionId) -> String {
+        format!("session:{}", id.as_str())
+    }
+
+    /// Redis key for the set of a user's session IDs
+    #[must_use]
+    pub fn user_key(user_id: i64) -> String {
+        format!("user_sessions:{user_id}")
+    }
+
+    /// Get a pooled connection
+    async fn connection(&self) -> Result<deadpool_redis::Connection, SessionError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| SessionError::Redis(format!("Failed to get Redis connection: {e}")))
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl SessionStore for RedisSessionStore {
+    async fn load(&self, id: &SessionId) -> Result<Option<SessionData>, SessionError> {
+        let mut conn = self.connection().await?;
+        let data: Option<String> = redis::cmd("GET")
+            .arg(Self::key(id))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| SessionError::Redis(format!("Redis GET failed: {e}")))?;
+
+        data.map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(SessionError::from)
+    }
+
+    async fn save(&self, id: &SessionId, data: &SessionData) -> Result<(), SessionError> {
+        let json = serde_json::to_string(data)?;
+        let mut conn = self.connection().await?;
+        let _: () = redis::cmd("SET")
+            .arg(Self::key(id))
+            .arg(json)
+            .arg("EX")
+            .arg(self.ttl_secs.max(1))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| SessionError::Redis(format!("Redis SET failed: {e}")))?;
+
+        if let Some(user_id) = data.user_id {
+            // The index lives as long as the user's newest session
+            let _: () = redis::pipe()
+                .cmd("SADD")
+                .arg(Self::user_key(user_id))
+                .arg(id.as_str())
+                .ignore()
+                .cmd("EXPIRE")
+                .arg(Self::user_key(user_id))
+                .arg(self.ttl_secs.max(1))
+                .ignore()
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| SessionError::Redis(format!("Redis SADD failed: {e}")))?;
+        }
+
+        Ok(())
+    }
+
+    async fn delete(&self, id: &SessionId) -> Result<(), SessionError> {
+        let mut conn = self.connection().await?;
+        let _: () = redis::cmd("DEL")
+            .arg(Self::key(id))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| SessionError::Redis(format!("Redis DEL failed: {e}")))?;
+
+        Ok(())
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError> {
+        // Sessions aren't indexed by user, so walk every session key
+        let mut conn = self.connection().await?;
+        let mut cursor: u64 = 0;
+        let mut removed = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg("session:*")
+                .arg("COUNT")
+                .arg(100)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| SessionError::Redis(format!("Redis SCAN failed: {e}")))?;
+
+            for key in keys {
+                let data: Option<String> = redis::cmd("GET")
+                    .arg(&key)
+                    .query_async(&mut *conn)
+                    .await
+                    .map_err(|e| SessionError::Redis(format!("Redis GET failed: {e}")))?;
+                let belongs_to_user = data
+                    .and_then(|json| serde_json::from_str::<SessionData>(&json).ok())
+                    .is_some_and(|session| session.user_id == Some(user_id));
+                if belongs_to_user {
+                    let deleted: u64 = redis::cmd("DEL")
+                        .arg(&key)
+                        .query_async(&mut *conn)
+                        .await
+                        .map_err(|e| SessionError::Redis(format!("Redis DEL failed: {e}")))?;
+                    removed += deleted;
+                }
+            }
+
+            if next == 0 {
+                return Ok(removed);
+            }
+            cursor = next;
+        }
+    }
+
+    async fn list_for_user(
+        &self,
+        user_id: i64,
+    ) -> Result<Vec<(SessionId, SessionData)>, SessionError> {
+        let mut conn = self.connection().await?;
+        let members: Vec<String> = redis::cmd("SMEMBERS")
+            .arg(Self::user_key(user_id))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| SessionError::Redis(format!("Redis SMEMBERS failed: {e}")))?;
+
+        let mut sessions = Vec::new();
+        for member in members {
+            let session = match SessionId::try_from_string(member.clone()) {
+                Ok(id) => {
+                    let data: Option<String> = redis::cmd("GET")
+                        .arg(Self::key(&id))
+                        .query_async(&mut *conn)
+                        .await
+                        .map_err(|e| SessionError::Redis(format!("Redis GET failed: {e}")))?;
+                    data.and_then(|json| serde_json::from_str::<SessionData>(&json).ok())
+                        .filter(|data| data.user_id == Some(user_id))
+                        .map(|data| (id, data))
+                }
+                Err(_) => None,
+            };
+
+            match session {
+                Some(session) => sessions.push(session),
+                None => {
+                    let _: () = redis::cmd("SREM")
+                        .arg(Self::user_key(user_id))
+                        .arg(&member)
+                        .query_async(&mut *conn)
+                        .await
+                        .map_err(|e| SessionError::Redis(format!("Redis SREM failed: {e}")))?;
+                }
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, SessionError> {
+        // Redis expires sessions via TTL
+        Ok(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_in_memory_save_load_delete() {
+        let store = InMemorySessionStore::new();
+        let session_id = SessionId::generate();
+        let mut data = SessionData::new();
+        data.set("key".to_string(), "value".to_string()).unwrap();
+
+        store.save(&session_id, &data).await.unwrap();
+        let loaded = store.load(&session_id).await.unwrap().unwrap();
+        assert_eq!(loaded.get::<String>("key"), Some("value".to_string()));
+
+        store.delete(&session_id).await.unwrap();
+        assert!(store.load(&session_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_cleanup_keeps_resaved_sessions() {
+        let store = InMemorySessionStore::new();
+        let expired_id = SessionId::generate();
+        let resaved_id = SessionId::generate();
+
+        let mut expired = SessionData::new();
+        expired.expires_at = Utc::now() - Duration::hours(1);
+        store.save(&expired_id, &expired).await.unwrap();
+        store.save(&resaved_id, &expired).await.unwrap();
+        store.save(&resaved_id, &SessionData::new()).await.unwrap();
+
+        assert_eq!(store.cleanup_expired().await.unwrap(), 1);
+        assert!(store.load(&expired_id).await.unwrap().is_none());
+        assert!(store.load(&resaved_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_delete_for_user() {
+        let store = InMemorySessionStore::new();
+        let mut user_sessions = Vec::new();
+        for user_id in [Some(7), Some(7), Some(8), None] {
+            let id = SessionId::generate();
+            let mut data = SessionData::new();
+            data.user_id = user_id;
+            store.save(&id, &data).await.unwrap();
+            user_sessions.push((id, user_id));
+        }
+
+        let mut listed: Vec<_> = store
+            .list_for_user(7)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        listed.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        let mut expected: Vec<_> = user_sessions
+            .iter()
+            .filter(|(_, user_id)| *user_id == Some(7))
+            .map(|(id, _)| id.clone())
+            .collect();
+        expected.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        assert_eq!(listed, expected);
+
+        assert_eq!(store.delete_for_user(7).await.unwrap(), 2);
+        for (id, user_id) in &user_sessions {
+            let kept = store.load(id).await.unwrap().is_some();
+            assert_eq!(kept, *user_id != Some(7));
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_cleanup_expired() {
+        use crate::htmx::testing::create_sqlite_pool_with_migration;
+
+        let pool = create_sqlite_pool_with_migration(include_str!(
+            "../../../../migrations/005_create_sessions_table.sql"
+        ))
+        .await
+        .unwrap();
+        let store = SqliteSessionStore::new(pool);
+
+        let expired_id = SessionId::generate();
+        let active_id = SessionId::generate();
+        let mut expired = SessionData::new();
+        expired.expires_at = Utc::now() - Duration::hours(1);
+        store.save(&expired_id, &expired).await.unwrap();
+        store.save(&active_id, &SessionData::new()).await.unwrap();
+
+        assert_eq!(store.cleanup_expired().await.unwrap(), 1);
+        assert!(store.load(&expired_id).await.unwrap().is_none());
+        assert!(store.load(&active_id).await.unwrap().is_some());
+
+        let mut logged_in = SessionData::new();
+        logged_in.user_id = Some(7);
+        let logged_in_id = SessionId::generate();
+        store.save(&logged_in_id, &logged_in).await.unwrap();
+
+        let listed = store.list_for_user(7).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].0, logged_in_id);
+        assert!(store.list_for_user(8).await.unwrap().is_empty());
+
+        assert_eq!(store.delete_for_user(7).await.unwrap(), 1);
+        assert!(store.load(&logged_in_id).await.unwrap().is_none());
+        assert!(store.load(&active_id).await.unwrap().is_some());
+    }
+
+    #[cfg(feature = "redis")]
+    fn redis_store() -> RedisSessionStore {
+        let url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+        let pool = deadpool_redis::Config::from_url(url)
+            .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            .unwrap();
+        RedisSessionStore::new(pool).with_ttl_secs(60)
+    }
+
+    #[cfg(feature = "redis")]
+    #[tokio::test]
+    async fn test_redis_key_and_cleanup_noop() {
+        let session_id = SessionId::generate();
+        assert_eq!(
+            RedisSessionStore::key(&session_id),
+            format!("session:{}", session_id.as_str())
+        );
+        assert_eq!(RedisSessionStore::user_key(7), "user_sessions:7");
+        // No connection is needed: expiry is left to Redis
+        assert_eq!(redis_store().cleanup_expired().await.unwrap(), 0);
+    }
+
+    #[cfg(feature = "redis")]
+    #[tokio::test]
+    #[ignore = "requires a running Redis server (REDIS_URL, default redis://127.0.0.1:6379)"]
+    async fn test_redis_set_read_delete() {
+        let store = redis_store();
+        let session_id = SessionId::generate();
+        let mut data = SessionData::new();
+        data.set("user".to_string(), "alice".to_string()).unwrap();
+
+        store.save(&session_id, &data).await.unwrap();
+        let loaded = store.load(&session_id).await.unwrap().unwrap();
+        assert_eq!(loaded.get::<String>("user"), Some("alice".to_string()));
+
+        store.delete(&session_id).await.unwrap();
+        assert!(store.load(&session_id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,215 @@
+//! Extractors for the logged-in user's database record
+//!
+//! [`CurrentUser`] loads the [`User`] row for the session's `user_id` and
+//! rejects with `401 Unauthorized` when there is none;
+//! [`OptionalCurrentUser`] yields `None` instead, for pages that also work
+//! logged out.
+//!
+//! The loaded row is cached in the request extensions, so several
+//! extractors (or middleware and a handler) in one request query the
+//! database once. Requires `SessionLayer` and a database pool on
+//! [`ActonHtmxState`].
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::{CurrentUser, OptionalCurrentUser};
+//!
+//! async fn profile(CurrentUser(user): CurrentUser) -> String {
+//!     format!("Hello, {}!", user.email)
+//! }
+//!
+//! async fn home(OptionalCurrentUser(user): OptionalCurrentUser) -> String {
+//!     user.map_or_else(|| "Hello, guest!".to_string(), |user| format!("Hello, {}!", user.email))
+//! }
+//! ```
+
+use crate::htmx::auth::{SessionData, User, UserError};
+use crate::htmx::state::ActonHtmxState;
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, StatusCode},
+};
+use std::future::Future;
+
+/// The logged-in user's record; rejects with 401 when logged out
+#[derive(Debug, Clone)]
+pub struct CurrentUser(pub User);
+
+/// The logged-in user's record, or `None` when logged out
+#[derive(Debug, Clone)]
+pub struct OptionalCurrentUser(pub Option<User>);
+
+/// User row loaded earlier in this request
+///
+/// A missing row is cached too, so it isn't queried again.
+#[derive(Clone)]
+pub(super) struct CachedUser {
+    pub(super) user_id: i64,
+    pub(super) user: Option<User>,
+}
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+    ActonHtmxState: FromRef<S>,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let OptionalCurrentUser(user) = OptionalCurrentUser::from_request_parts(parts, state).await?;
+        user.map(Self).ok_or(StatusCode::UNAUTHORIZED)
+    }
+}
+
+impl<S> FromRequestParts<S> for OptionalCurrentUser
+where
+    S: Send + Sync,
+    ActonHtmxState: FromRef<S>,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let app_state = ActonHtmxState::from_ref(state);
+        let pool = app_state.database_pool();
+
+        load_current_user(parts, |user_id| User::find_by_id(user_id, pool))
+            .await
+            .map(Self)
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to load current user");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })
+    }
+}
+
+/// Load the session user with `load`, or reuse the row cached in `parts`
+pub(super) async fn load_current_user<F, Fut>(
+    parts: &mut Parts,
+    load: F,
+) -> Result<Option<User>, UserError>
+where
+    F: FnOnce(i64) -> Fut,
+    Fut: Future<Output = Result<User, UserError>>,
+{
+    let Some(user_id) = parts
+        .extensions
+        .get::<SessionData>()
+        .and_then(|session| session.user_id)
+    else {
+        return Ok(None);
+    };
+
+    if let Some(cached) = parts
+        .extensions
+        .get::<CachedUser>()
+        .filter(|cached| cached.user_id == user_id)
+    {
+        return Ok(cached.user.clone());
+    }
+
+    let user = match load(user_id).await {
+        Ok(user) => Some(user),
+        Err(UserError::NotFound) => None,
+        Err(e) => return Err(e),
+    };
+    parts.extensions.insert(CachedUser {
+        user_id,
+        user: user.clone(),
+    });
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::auth::EmailAddress;
+    use axum::http::Request;
+    use chrono::Utc;
+    use std::future::ready;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn parts(user_id: Option<i64>) -> Parts {
+        let mut session = SessionData::new();
+        session.user_id = user_id;
+        let (mut parts, ()) = Request::builder().body(()).unwrap().into_parts();
+        parts.extensions.insert(session);
+        parts
+    }
+
+    /// Loader that knows user 1 and counts queries
+    fn find(user_id: i64, queries: &AtomicUsize) -> Result<User, UserError> {
+        queries.fetch_add(1, Ordering::SeqCst);
+        if user_id != 1 {
+            return Err(UserError::NotFound);
+        }
+        Ok(User {
+            id: 1,
+            email: EmailAddress::parse("ada@example.com").unwrap(),
+            password_hash: String::new(),
+            roles: vec!["user".to_string()],
+            permissions: vec![],
+            email_verified: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_hit_loads_user() {
+        let queries = AtomicUsize::new(0);
+        let mut request = parts(Some(1));
+
+        let user = load_current_user(&mut request, |id| ready(find(id, &queries)))
+            .await
+            .unwrap();
+        assert_eq!(user.map(|user| user.id), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_miss_and_logged_out_yield_none() {
+        let queries = AtomicUsize::new(0);
+
+        let mut unknown = parts(Some(2));
+        let user = load_current_user(&mut unknown, |id| ready(find(id, &queries))).await;
+        assert!(user.unwrap().is_none());
+
+        // No user in the session: nothing to query
+        let mut logged_out = parts(None);
+        let user = load_current_user(&mut logged_out, |id| ready(find(id, &queries))).await;
+        assert!(user.unwrap().is_none());
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cached_within_one_request() {
+        let queries = AtomicUsize::new(0);
+
+        let mut request = parts(Some(1));
+        for _ in 0..3 {
+            let user = load_current_user(&mut request, |id| ready(find(id, &queries))).await;
+            assert!(user.unwrap().is_some());
+        }
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+        // Misses are cached too
+        let mut missing = parts(Some(2));
+        for _ in 0..2 {
+            let user = load_current_user(&mut missing, |id| ready(find(id, &queries))).await;
+            assert!(user.unwrap().is_none());
+        }
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_database_errors_are_not_cached() {
+        let mut request = parts(Some(1));
+
+        let result = load_current_user(&mut request, |_| {
+            ready(Err(UserError::DatabaseError(sqlx::Error::PoolTimedOut)))
+        })
+        .await;
+        assert!(result.is_err());
+        assert!(request.extensions.get::<CachedUser>().is_none());
+    }
+}
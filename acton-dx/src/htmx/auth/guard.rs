@@ -0,0 +1,342 @@
+//! Role and permission guards for routes
+//!
+//! [`RoleLayer`] protects a route or a whole router subtree with a
+//! requirement such as [`RequireRole`] or [`RequirePermission`], checked
+//! against the roles and permissions of the logged-in [`User`].
+//!
+//! - Logged out: `401 Unauthorized`
+//! - Requirement not met: `403 Forbidden`, rendered with `errors/403.html`
+//!   for HTML requests and as JSON for `Accept: application/json`
+//!
+//! The user row is loaded like [`CurrentUser`](super::CurrentUser) and
+//! cached in the request, so a `CurrentUser` extractor in the handler
+//! doesn't query the database again.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::{RequirePermission, RequireRole, RoleLayer};
+//! use axum::{routing::{delete, get}, Router};
+//!
+//! let admin = Router::new()
+//!     .route("/admin/users", get(list_users))
+//!     .layer(RoleLayer::new(&state, RequireRole("admin")));
+//!
+//! let posts = Router::new().route(
+//!     "/posts/{id}",
+//!     delete(delete_post).route_layer(RoleLayer::new(&state, RequirePermission("posts:delete"))),
+//! );
+//! ```
+
+use super::current_user::load_current_user;
+use crate::htmx::auth::User;
+use crate::htmx::state::ActonHtmxState;
+use crate::htmx::template::ResponseFormat;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Something the logged-in user must satisfy to access a route
+pub trait AccessRequirement: Clone + Send + Sync + 'static {
+    /// Whether `user` satisfies the requirement
+    fn is_met(&self, user: &User) -> bool;
+
+    /// Human-readable description used in logs and error messages
+    fn describe(&self) -> String;
+}
+
+/// Require the user to have a role, e.g. `RequireRole("admin")`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequireRole(pub &'static str);
+
+impl AccessRequirement for RequireRole {
+    fn is_met(&self, user: &User) -> bool {
+        user.roles.iter().any(|role| role == self.0)
+    }
+
+    fn describe(&self) -> String {
+        format!("role \"{}\"", self.0)
+    }
+}
+
+/// Require the user to have a permission, e.g. `RequirePermission("posts:delete")`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequirePermission(pub &'static str);
+
+impl AccessRequirement for RequirePermission {
+    fn is_met(&self, user: &User) -> bool {
+        user.permissions.iter().any(|permission| permission == self.0)
+    }
+
+    fn describe(&self) -> String {
+        format!("permission \"{}\"", self.0)
+    }
+}
+
+/// Layer rejecting requests whose user doesn't meet a requirement
+///
+/// Requires `SessionLayer` to run first and a database pool on the state.
+#[derive(Clone)]
+pub struct RoleLayer<R> {
+    state: ActonHtmxState,
+    requirement: R,
+}
+
+impl<R: std::fmt::Debug> std::fmt::Debug for RoleLayer<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoleLayer")
+            .field("requirement", &self.requirement)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: AccessRequirement> RoleLayer<R> {
+    /// Protect routes with `requirement`
+    #[must_use]
+    pub fn new(state: &ActonHtmxState, requirement: R) -> Self {
+        Self {
+            state: state.clone(),
+            requirement,
+        }
+    }
+}
+
+impl<S, R: AccessRequirement> Layer<S> for RoleLayer<R> {
+    type Service = RoleMiddleware<S, R>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RoleMiddleware {
+            inner,
+            state: self.state.clone(),
+            requirement: self.requirement.clone(),
+        }
+    }
+}
+
+/// Middleware checking the user against an [`AccessRequirement`]
+#[derive(Clone)]
+pub struct RoleMiddleware<S, R> {
+    inner: S,
+    state: ActonHtmxState,
+    requirement: R,
+}
+
+impl<S: std::fmt::Debug, R: std::fmt::Debug> std::fmt::Debug for RoleMiddleware<S, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoleMiddleware")
+            .field("inner", &self.inner)
+            .field("requirement", &self.requirement)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, R> Service<Request> for RoleMiddleware<S, R>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    R: AccessRequirement,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let state = self.state.clone();
+        let requirement = self.requirement.clone();
+
+        Box::pin(async move {
+            let format = ResponseFormat::from_headers(req.headers());
+            let (mut parts, body) = req.into_parts();
+            let user = load_current_user(&mut parts, |user_id| {
+                User::find_by_id(user_id, state.database_pool())
+            })
+            .await;
+            let req = Request::from_parts(parts, body);
+
+            match user {
+                Ok(Some(user)) if requirement.is_met(&user) => inner.call(req).await,
+                Ok(Some(user)) => {
+                    tracing::warn!(
+                        user_id = user.id,
+                        path = %req.uri().path(),
+                        "Access denied: missing {}",
+                        requirement.describe()
+                    );
+                    Ok(forbidden(&state, format, &requirement))
+                }
+                Ok(None) => Ok(StatusCode::UNAUTHORIZED.into_response()),
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to load user for access check");
+                    Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+                }
+            }
+        })
+    }
+}
+
+/// 403 response in the format the request asked for
+fn forbidden(
+    state: &ActonHtmxState,
+    format: ResponseFormat,
+    requirement: &impl AccessRequirement,
+) -> Response {
+    let message = format!(
+        "You need the {} to access this page.",
+        requirement.describe()
+    );
+
+    if format == ResponseFormat::Json {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "forbidden",
+                "message": message,
+            })),
+        )
+            .into_response();
+    }
+
+    let html = state
+        .templates()
+        .render(
+            "errors/403.html",
+            minijinja::context! {
+                message => message,
+                home_url => "/",
+            },
+        )
+        .unwrap_or_else(|e| {
+            tracing::error!(error = ?e, "Failed to render error template");
+            format!("<h1>403</h1><p>{message}</p>")
+        });
+
+    (
+        StatusCode::FORBIDDEN,
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        html,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::auth::current_user::CachedUser;
+    use crate::htmx::auth::{EmailAddress, SessionData};
+    use acton_reactive::prelude::ActonApp;
+    use axum::{routing::get, Router};
+    use chrono::Utc;
+    use tower::ServiceExt;
+
+    fn user(roles: &[&str], permissions: &[&str]) -> User {
+        User {
+            id: 1,
+            email: EmailAddress::parse("ada@example.com").unwrap(),
+            password_hash: String::new(),
+            roles: roles.iter().map(ToString::to_string).collect(),
+            permissions: permissions.iter().map(ToString::to_string).collect(),
+            email_verified: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Request as seen after `SessionLayer` and an earlier user lookup
+    fn request(user: Option<User>, accept: &str) -> Request {
+        let mut session = SessionData::new();
+        session.user_id = user.as_ref().map(|user| user.id);
+
+        let mut request = Request::builder()
+            .uri("/admin")
+            .header(header::ACCEPT, accept)
+            .body(Body::empty())
+            .unwrap();
+        if let Some(user) = user {
+            request.extensions_mut().insert(CachedUser {
+                user_id: user.id,
+                user: Some(user),
+            });
+        }
+        request.extensions_mut().insert(session);
+        request
+    }
+
+    fn app<R: AccessRequirement>(state: &ActonHtmxState, requirement: R) -> Router {
+        Router::new()
+            .route("/admin", get(|| async { "secret" }))
+            .layer(RoleLayer::new(state, requirement))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_allowed() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+
+        let admin = user(&["admin"], &[]);
+        let response = app(&state, RequireRole("admin"))
+            .oneshot(request(Some(admin), "text/html"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let editor = user(&["user"], &["posts:delete"]);
+        let response = app(&state, RequirePermission("posts:delete"))
+            .oneshot(request(Some(editor), "text/html"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_denied() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+
+        let response = app(&state, RequireRole("admin"))
+            .oneshot(request(Some(user(&["user"], &[])), "text/html"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+
+        let response = app(&state, RequirePermission("posts:delete"))
+            .oneshot(request(Some(user(&["admin"], &[])), "application/json"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "forbidden");
+        assert!(json["message"].as_str().unwrap().contains("posts:delete"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_anonymous() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+
+        let response = app(&state, RequireRole("admin"))
+            .oneshot(request(None, "text/html"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,4 +1,4 @@
-//! Authentication handlers (login, register, logout)
+//! Authentication handlers (login, register, logout, password reset)
 //!
 //! This module provides basic handler scaffolds for authentication.
 //! Full database integration and template rendering will be added in later phases.
@@ -17,17 +17,58 @@
 //! # }
 //! ```
 
-use crate::htmx::auth::{CreateUser, EmailAddress, FlashMessage, Session, User, UserError};
+use crate::htmx::agents::{ActiveSession, ListUserSessions, RevokeUserSession};
+use crate::htmx::auth::remember::ForgetLogin;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::agents::DeleteUserSessions;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::auth::password_reset::PasswordReset;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::auth::remember::{ForgetAllLogins, RememberLogin};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::auth::totp::{
+    generate_backup_codes, hash_backup_code, take_backup_code, PendingTotpLogin, Totp,
+    TotpCipher, TotpSecret, UserTotp, PENDING_LOGIN_SESSION_KEY,
+};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::auth::verification::{resend_wait, EmailVerification, SENT_AT_SESSION_KEY};
+#[cfg(feature = "webauthn")]
+use crate::htmx::auth::webauthn::PasskeyError;
+#[cfg(all(feature = "webauthn", any(feature = "postgres", feature = "sqlite")))]
+use crate::htmx::auth::webauthn::{
+    encode_credential_id, Passkeys, PendingCeremony, WebauthnCredential,
+    AUTHENTICATION_SESSION_KEY, REGISTRATION_SESSION_KEY,
+};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::middleware::cookie_signer::unix_now;
+use crate::htmx::auth::{
+    CreateUser, EmailAddress, FlashMessage, PasswordError, PwnedPasswordChecker, Session, User,
+    UserError,
+};
+use crate::htmx::middleware::RegenerateSessionId;
+use crate::htmx::responses::PostRedirectGet;
 use crate::htmx::state::ActonHtmxState;
+use acton_reactive::prelude::AgentHandleInterface;
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{Html, IntoResponse, Redirect, Response},
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Response},
     Form,
 };
+use askama::Template;
 use axum_htmx::HxRequest;
 use serde::Deserialize;
+use std::time::Duration;
 use validator::Validate;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::middleware::ConfirmSessionSave;
+#[cfg(all(feature = "webauthn", any(feature = "postgres", feature = "sqlite")))]
+use axum::Json;
+#[cfg(all(feature = "webauthn", any(feature = "postgres", feature = "sqlite")))]
+use webauthn_rs::prelude::{
+    Passkey, PasskeyAuthentication, PasskeyRegistration, PublicKeyCredential,
+    RegisterPublicKeyCredential,
+};
 
 /// Login form data
 #[derive(Debug, Deserialize, Validate)]
@@ -39,6 +80,13 @@ pub struct LoginForm {
     /// User's password (min 8 characters)
     #[validate(length(min = 8))]
     pub password: String,
+
+    /// Keep the user logged in with a remember-me cookie
+    ///
+    /// Only takes effect when the session layer is configured with
+    /// [`with_remember_me`](crate::htmx::middleware::SessionLayer::with_remember_me).
+    #[serde(default)]
+    pub remember: bool,
 }
 
 /// Registration form data
@@ -57,6 +105,71 @@ pub struct RegisterForm {
     pub password_confirm: String,
 }
 
+/// Password reset request form data
+#[derive(Debug, Deserialize, Validate)]
+pub struct PasswordResetRequestForm {
+    /// Address of the account to reset
+    #[validate(email)]
+    pub email: String,
+}
+
+/// New password form data
+#[derive(Debug, Deserialize, Validate)]
+pub struct PasswordResetForm {
+    /// Reset token from the emailed link
+    #[validate(length(min = 1))]
+    pub token: String,
+
+    /// New password (min 8 characters)
+    #[validate(length(min = 8))]
+    pub password: String,
+
+    /// Password confirmation (must match password)
+    #[validate(length(min = 8))]
+    pub password_confirm: String,
+}
+
+/// Authentication code form data
+///
+/// Accepts either a TOTP code or a backup code.
+#[derive(Debug, Deserialize, Validate)]
+pub struct TotpCodeForm {
+    /// Code from the authenticator app, or a backup code
+    #[validate(length(min = 6, max = 32))]
+    pub code: String,
+}
+
+/// Passkey login request
+///
+/// Sent as JSON to start a passkey login.
+#[cfg(feature = "webauthn")]
+#[derive(Debug, Deserialize, Validate)]
+pub struct PasskeyLoginRequest {
+    /// Address of the account to log in to
+    #[validate(email)]
+    pub email: String,
+
+    /// Keep the user logged in with a remember-me cookie
+    #[serde(default)]
+    pub remember: bool,
+}
+
+/// Session revocation form data
+#[derive(Debug, Deserialize, Validate)]
+pub struct RevokeSessionForm {
+    /// Handle of the session to sign out, from [`sessions_list`]
+    #[validate(length(min = 1, max = 64))]
+    pub session: String,
+}
+
+/// Reset link query parameters
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordQuery {
+    /// Reset token from the emailed link
+    #[serde(default)]
+    pub token: String,
+}
+
 /// GET /login - Display login form
 ///
 /// # Example
@@ -88,6 +201,12 @@ pub async fn login_form(
             <label for="password">Password:</label>
             <input type="password" id="password" name="password" required />
         </div>
+        <div>
+            <label>
+                <input type="checkbox" name="remember" value="true" />
+                Remember me
+            </label>
+        </div>
         <button type="submit">Login</button>
     </form>
     <p><a href="/register">Don't have an account? Register</a></p>
@@ -109,6 +228,11 @@ pub async fn login_form(
 /// - User authentication fails (invalid credentials, user not found)
 /// - Database query fails
 ///
+/// On success the session is saved under a new session ID, so an ID known
+/// before login can't be used to ride the logged-in session. If the user has
+/// TOTP enabled, the session is not logged in yet; the response redirects to
+/// `/login/totp` for [`totp_login_post`].
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -136,14 +260,8 @@ pub async fn login_post(
         .await
         .map_err(|_| AuthHandlerError::InvalidCredentials)?;
 
-    // Set user ID in session
-    session.set_user_id(Some(user.id));
-
-    // Add success flash message
-    session.add_flash(FlashMessage::success("Successfully logged in!"));
-
-    // Redirect to dashboard/home
-    Ok(Redirect::to("/").into_response())
+    // Ask for the TOTP code first if enabled, otherwise log in
+    complete_password_login(&state, &mut session, user.id, form.remember).await
 }
 
 /// POST /login - Process login (SQLite)
@@ -163,10 +281,43 @@ pub async fn login_post(
         .await
         .map_err(|_| AuthHandlerError::InvalidCredentials)?;
 
-    session.set_user_id(Some(user.id));
+    complete_password_login(&state, &mut session, user.id, form.remember).await
+}
+
+/// Log the session in after a correct password, or start the TOTP step
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+async fn complete_password_login(
+    state: &ActonHtmxState,
+    session: &mut Session,
+    user_id: i64,
+    remember: bool,
+) -> Result<Response, AuthHandlerError> {
+    if UserTotp::is_enabled(user_id, state.database_pool()).await? {
+        let pending = PendingTotpLogin {
+            user_id,
+            remember,
+            started_at: unix_now(),
+            failed_attempts: 0,
+        };
+        let _ = session.set(PENDING_LOGIN_SESSION_KEY.to_string(), pending);
+        return Ok(PostRedirectGet::new(session, TOTP_LOGIN_PATH).into_response());
+    }
+
+    session.set_user_id(Some(user_id));
     session.add_flash(FlashMessage::success("Successfully logged in!"));
+    Ok(login_redirect(session, user_id, remember))
+}
 
-    Ok(Redirect::to("/").into_response())
+/// Redirect after login, saving the session under a new ID and asking for a
+/// remember-me token if requested
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn login_redirect(session: &Session, user_id: i64, remember: bool) -> Response {
+    let mut response = PostRedirectGet::new(session, "/").into_response();
+    response.extensions_mut().insert(RegenerateSessionId);
+    if remember {
+        response.extensions_mut().insert(RememberLogin { user_id });
+    }
+    response
 }
 
 /// GET /register - Display registration form
@@ -222,9 +373,14 @@ pub async fn register_form(
 /// - Form validation fails (invalid email, weak password, missing fields)
 /// - Email address cannot be parsed
 /// - Password and confirmation password do not match
+/// - Password fails the configured `security.password_policy`
+/// - Password is found in a data breach (when `security.pwned_passwords` is enabled)
 /// - Email address is already registered
 /// - Database query or user creation fails
 ///
+/// When `security.email_verification` is enabled, a verification email is
+/// queued for the new user; failing to queue it doesn't fail registration.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -252,6 +408,9 @@ pub async fn register_post(
         return Err(AuthHandlerError::PasswordMismatch);
     }
 
+    // Enforce the password policy before hashing
+    check_new_password(&state, &form.password).await?;
+
     // Create user in database
     let create_user = CreateUser {
         email,
@@ -262,11 +421,16 @@ pub async fn register_post(
     // Set user ID in session (auto-login after registration)
     session.set_user_id(Some(user.id));
 
+    // Ask the user to confirm their address
+    if state.config().security.email_verification.enabled {
+        queue_verification_email(&state, &mut session, &user).await;
+    }
+
     // Add success flash message
     session.add_flash(FlashMessage::success("Account created successfully! Welcome!"));
 
     // Redirect to dashboard/home
-    Ok(Redirect::to("/").into_response())
+    Ok(login_redirect(&session, user.id, false))
 }
 
 /// POST /register - Process registration (SQLite)
@@ -286,6 +450,8 @@ pub async fn register_post(
         return Err(AuthHandlerError::PasswordMismatch);
     }
 
+    check_new_password(&state, &form.password).await?;
+
     let create_user = CreateUser {
         email,
         password: form.password,
@@ -293,116 +459,1341 @@ pub async fn register_post(
     let user = User::create(create_user, state.database_pool()).await?;
 
     session.set_user_id(Some(user.id));
+
+    if state.config().security.email_verification.enabled {
+        queue_verification_email(&state, &mut session, &user).await;
+    }
     session.add_flash(FlashMessage::success("Account created successfully! Welcome!"));
 
-    Ok(Redirect::to("/").into_response())
+    Ok(login_redirect(&session, user.id, false))
 }
 
-/// POST /logout - Clear session and logout
+/// Check a new password against the configured security settings
+///
+/// Applies `security.password_policy`, then, when enabled,
+/// `security.pwned_passwords`. Use this wherever a user chooses a password,
+/// such as registration and password reset.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError::WeakPassword`] if the password is rejected or
+/// the breach lookup fails closed.
+pub async fn check_new_password(
+    state: &ActonHtmxState,
+    password: &str,
+) -> Result<(), AuthHandlerError> {
+    let security = &state.config().security;
+
+    security
+        .password_policy
+        .validate(password)
+        .map_err(AuthHandlerError::WeakPassword)?;
+
+    if security.pwned_passwords.enabled
+        && PwnedPasswordChecker::from_config(&security.pwned_passwords)
+            .is_pwned(password)
+            .await
+            .map_err(AuthHandlerError::WeakPassword)?
+    {
+        return Err(AuthHandlerError::WeakPassword(PasswordError::Pwned));
+    }
+
+    Ok(())
+}
+
+/// Queue a verification email for `user`, logging failures
+///
+/// Records the send time in the session so
+/// [`resend_verification`] can enforce its cooldown.
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+async fn queue_verification_email(state: &ActonHtmxState, session: &mut Session, user: &User) {
+    let verification = EmailVerification::from_state(state);
+    match verification.send(state, user.id, &user.email).await {
+        Ok(()) => {
+            let _ = session.set(SENT_AT_SESSION_KEY.to_string(), unix_now());
+        }
+        Err(e) => {
+            tracing::warn!(user_id = user.id, error = %e, "Failed to queue verification email");
+        }
+    }
+}
+
+/// Verification link query parameters
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailQuery {
+    /// Signed verification token
+    pub token: String,
+}
+
+/// GET /verify-email - Confirm an email address from a verification link
+///
+/// Works without a session, so the link can be opened in another browser.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError::InvalidVerificationToken`] if the token is
+/// tampered with, expired, or for an address the user no longer has, and
+/// [`AuthHandlerError::UserError`] if the database update fails
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// use acton_htmx::auth::handlers::logout_post;
-/// use axum::{Router, routing::post};
+/// use acton_dx::htmx::auth::handlers::verify_email;
+/// use axum::{Router, routing::get};
 ///
-/// let app = Router::new().route("/logout", post(logout_post));
+/// let app = Router::new().route("/verify-email", get(verify_email));
 /// ```
-pub async fn logout_post(
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub async fn verify_email(
+    State(state): State<ActonHtmxState>,
     mut session: Session,
-) -> Response {
-    // Clear user ID from session
-    session.set_user_id(None);
+    Query(query): Query<VerifyEmailQuery>,
+) -> Result<Response, AuthHandlerError> {
+    let claim = EmailVerification::from_state(&state)
+        .verify(&query.token)
+        .ok_or(AuthHandlerError::InvalidVerificationToken)?;
 
-    // Add info flash message
-    session.add_flash(FlashMessage::info("You have been logged out."));
+    if !User::mark_email_verified(claim.user_id, &claim.email, state.database_pool()).await? {
+        return Err(AuthHandlerError::InvalidVerificationToken);
+    }
 
-    // Redirect to home or login
-    Redirect::to("/login").into_response()
+    session.add_flash(FlashMessage::success("Your email address has been verified."));
+    Ok(PostRedirectGet::new(&session, "/").into_response())
 }
 
-/// Authentication handler errors
-#[derive(Debug)]
-pub enum AuthHandlerError {
-    /// Form validation failed
-    ValidationFailed(String),
-
-    /// Invalid email format
-    InvalidEmail,
+/// POST /verify-email/resend - Send the logged-in user a new verification email
+///
+/// Limited to one email per `security.email_verification.resend_cooldown_secs`
+/// for each session.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - The user is not logged in
+/// - A verification email was sent too recently
+/// - The email cannot be queued
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::resend_verification;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/verify-email/resend", post(resend_verification));
+/// ```
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub async fn resend_verification(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+) -> Result<Response, AuthHandlerError> {
+    let user_id = session.user_id().ok_or(AuthHandlerError::InvalidCredentials)?;
+    let user = User::find_by_id(user_id, state.database_pool()).await?;
 
-    /// Password confirmation doesn't match
-    PasswordMismatch,
+    if user.email_verified {
+        session.add_flash(FlashMessage::info("Your email address is already verified."));
+        return Ok(PostRedirectGet::new(&session, "/").into_response());
+    }
 
-    /// Invalid credentials
-    InvalidCredentials,
+    let verification = EmailVerification::from_state(&state);
+    let now = unix_now();
+    if let Some(retry_after) =
+        resend_wait(session.get(SENT_AT_SESSION_KEY), verification.resend_cooldown(), now)
+    {
+        return Err(AuthHandlerError::RateLimited(retry_after));
+    }
 
-    /// User error
-    UserError(UserError),
+    verification
+        .send(&state, user.id, &user.email)
+        .await
+        .map_err(|e| AuthHandlerError::EmailFailed(e.to_string()))?;
+    let _ = session.set(SENT_AT_SESSION_KEY.to_string(), now);
 
-    /// Database not configured
-    DatabaseNotConfigured,
+    session.add_flash(FlashMessage::info(format!(
+        "We sent a new verification link to {}.",
+        user.email
+    )));
+    Ok(PostRedirectGet::new(&session, "/").into_response())
 }
 
-impl From<UserError> for AuthHandlerError {
-    fn from(err: UserError) -> Self {
-        Self::UserError(err)
-    }
+/// GET /password-reset - Display the password reset request form
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::password_reset_form;
+/// use axum::{Router, routing::get};
+///
+/// let app = Router::new().route("/password-reset", get(password_reset_form));
+/// ```
+pub async fn password_reset_form(
+    HxRequest(_is_htmx): HxRequest,
+) -> Response {
+    let html = r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <title>Reset Password</title>
+    <script src="https://unpkg.com/htmx.org@1.9.10"></script>
+</head>
+<body>
+    <h1>Reset Password</h1>
+    <form hx-post="/password-reset" hx-target="body">
+        <div>
+            <label for="email">Email:</label>
+            <input type="email" id="email" name="email" required />
+        </div>
+        <button type="submit">Send reset link</button>
+    </form>
+    <p><a href="/login">Back to login</a></p>
+</body>
+</html>
+    "#;
+
+    Html(html).into_response()
 }
 
-impl IntoResponse for AuthHandlerError {
-    fn into_response(self) -> Response {
-        let (status, message) = match self {
-            Self::ValidationFailed(msg) => (StatusCode::BAD_REQUEST, msg),
-            Self::InvalidEmail => (StatusCode::BAD_REQUEST, "Invalid email format".to_string()),
-            Self::PasswordMismatch => (
-                StatusCode::BAD_REQUEST,
-                "Passwords do not match".to_string(),
-            ),
-            Self::InvalidCredentials => (
-                StatusCode::UNAUTHORIZED,
-                "Invalid email or password".to_string(),
-            ),
-            Self::UserError(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-            Self::DatabaseNotConfigured => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Database not configured".to_string(),
-            ),
-        };
+/// POST /password-reset - Email a password reset link
+///
+/// Responds identically whether or not the address belongs to an account,
+/// so the form can't be used to find out who has one. Failing to queue the
+/// email is logged rather than reported for the same reason.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - Form validation fails
+/// - Database query or token storage fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::request_password_reset;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/password-reset", post(request_password_reset));
+/// ```
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub async fn request_password_reset(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+    Form(form): Form<PasswordResetRequestForm>,
+) -> Result<Response, AuthHandlerError> {
+    form.validate()
+        .map_err(|e| AuthHandlerError::ValidationFailed(e.to_string()))?;
 
-        (status, message).into_response()
+    let email = EmailAddress::parse(&form.email)
+        .map_err(|_| AuthHandlerError::InvalidEmail)?;
+
+    match User::find_by_email(&email, state.database_pool()).await {
+        Ok(user) => {
+            let reset = PasswordReset::from_state(&state);
+            let token = reset.issue(user.id).await?;
+            if let Err(e) = reset.send(&state, &token, &user.email).await {
+                tracing::warn!(user_id = user.id, error = %e, "Failed to queue password reset email");
+            }
+        }
+        Err(UserError::NotFound) => {}
+        Err(e) => return Err(e.into()),
     }
+
+    session.add_flash(FlashMessage::info(
+        "If an account exists for that address, we've sent a link to reset its password.",
+    ));
+    Ok(PostRedirectGet::new(&session, "/login").into_response())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// GET /password-reset/confirm - Display the new password form
+///
+/// The token from the emailed link is carried in a hidden field; it is
+/// only checked when the form is submitted.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::reset_password_form;
+/// use axum::{Router, routing::get};
+///
+/// let app = Router::new().route("/password-reset/confirm", get(reset_password_form));
+/// ```
+pub async fn reset_password_form(
+    HxRequest(_is_htmx): HxRequest,
+    Query(query): Query<ResetPasswordQuery>,
+) -> Response {
+    // Tokens are URL-safe base64; drop anything else rather than echo it into HTML
+    let token: String = query
+        .token
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        .collect();
 
-    #[test]
-    fn test_login_form_struct() {
-        let form = LoginForm {
-            email: "test@example.com".to_string(),
-            password: "password123".to_string(),
-        };
-        assert!(form.validate().is_ok());
-    }
+    let html = format!(
+        r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <title>Choose a New Password</title>
+    <script src="https://unpkg.com/htmx.org@1.9.10"></script>
+</head>
+<body>
+    <h1>Choose a New Password</h1>
+    <form hx-post="/password-reset/confirm" hx-target="body">
+        <input type="hidden" name="token" value="{token}" />
+        <div>
+            <label for="password">New Password:</label>
+            <input type="password" id="password" name="password" required minlength="8" />
+        </div>
+        <div>
+            <label for="password_confirm">Confirm Password:</label>
+            <input type="password" id="password_confirm" name="password_confirm" required minlength="8" />
+        </div>
+        <button type="submit">Reset password</button>
+    </form>
+</body>
+</html>
+    "#
+    );
 
-    #[test]
-    fn test_register_form_struct() {
-        let form = RegisterForm {
-            email: "test@example.com".to_string(),
-            password: "password123".to_string(),
-            password_confirm: "password123".to_string(),
-        };
-        assert!(form.validate().is_ok());
-    }
+    Html(html).into_response()
+}
 
-    #[test]
-    fn test_invalid_email() {
-        let form = LoginForm {
-            email: "not-an-email".to_string(),
-            password: "password123".to_string(),
-        };
-        assert!(form.validate().is_err());
+/// POST /password-reset/confirm - Set a new password from a reset token
+///
+/// The token is single-use: it is deleted as soon as it is checked. After
+/// the password is changed, every session and remembered device of the
+/// user is signed out, including the current one.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - Form validation fails or the passwords don't match
+/// - The new password fails the password policy
+/// - The token is unknown, expired, or already used
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::reset_password;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/password-reset/confirm", post(reset_password));
+/// ```
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub async fn reset_password(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+    Form(form): Form<PasswordResetForm>,
+) -> Result<Response, AuthHandlerError> {
+    form.validate()
+        .map_err(|e| AuthHandlerError::ValidationFailed(e.to_string()))?;
+
+    if form.password != form.password_confirm {
+        return Err(AuthHandlerError::PasswordMismatch);
+    }
+
+    // Check the password before spending the token, so a rejected password
+    // doesn't require a new email
+    check_new_password(&state, &form.password).await?;
+
+    let reset = PasswordReset::from_state(&state);
+    let user_id = reset
+        .consume(&form.token)
+        .await?
+        .ok_or(AuthHandlerError::InvalidResetToken)?;
+
+    if !User::set_password(user_id, &form.password, state.database_pool()).await? {
+        return Err(AuthHandlerError::InvalidResetToken);
+    }
+    reset.revoke_all(user_id).await?;
+
+    // Sign the user out everywhere
+    let (request, rx) = DeleteUserSessions::with_confirmation(user_id);
+    state.session_manager().send(request).await;
+    if !matches!(tokio::time::timeout(Duration::from_secs(1), rx).await, Ok(Ok(_))) {
+        tracing::warn!(user_id, "Session invalidation was not confirmed after password reset");
+    }
+    session.set_user_id(None);
+
+    session.add_flash(FlashMessage::success(
+        "Your password has been reset. Please log in with your new password.",
+    ));
+    let mut response = PostRedirectGet::new(&session, "/login").into_response();
+    response.extensions_mut().insert(RegenerateSessionId);
+    response.extensions_mut().insert(ForgetAllLogins { user_id });
+    Ok(response)
+}
+
+/// Path of the TOTP step of login
+const TOTP_LOGIN_PATH: &str = "/login/totp";
+
+/// GET /login/totp - Display the authentication code form
+///
+/// Shown after a correct password when the user has TOTP enabled.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::totp_login_form;
+/// use axum::{Router, routing::get};
+///
+/// let app = Router::new().route("/login/totp", get(totp_login_form));
+/// ```
+pub async fn totp_login_form(
+    HxRequest(_is_htmx): HxRequest,
+) -> Response {
+    let html = r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <title>Two-Factor Authentication</title>
+    <script src="https://unpkg.com/htmx.org@1.9.10"></script>
+</head>
+<body>
+    <h1>Two-Factor Authentication</h1>
+    <form hx-post="/login/totp" hx-target="body">
+        <div>
+            <label for="code">Authentication code:</label>
+            <input type="text" id="code" name="code" required autocomplete="one-time-code" inputmode="numeric" />
+        </div>
+        <button type="submit">Verify</button>
+    </form>
+    <p>Lost your device? Enter one of your backup codes instead.</p>
+</body>
+</html>
+    "#;
+
+    Html(html).into_response()
+}
+
+/// POST /login/totp - Finish logging in with a TOTP or backup code
+///
+/// The password step must have been completed within
+/// `security.totp.login_timeout_secs`. After
+/// `security.totp.max_login_attempts` wrong codes the pending login is
+/// dropped and the user has to log in with their password again.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - No password login is waiting for its TOTP step, or it timed out
+/// - The code is wrong, already used, or outside the accepted window
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::totp_login_post;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/login/totp", post(totp_login_post));
+/// ```
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub async fn totp_login_post(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+    Form(form): Form<TotpCodeForm>,
+) -> Result<Response, AuthHandlerError> {
+    form.validate()
+        .map_err(|e| AuthHandlerError::ValidationFailed(e.to_string()))?;
+
+    let config = &state.config().security.totp;
+    let Some(mut pending) = session
+        .get::<PendingTotpLogin>(PENDING_LOGIN_SESSION_KEY)
+        .filter(|pending| pending.is_live(config.login_timeout_secs, unix_now()))
+    else {
+        session.remove(PENDING_LOGIN_SESSION_KEY);
+        return Ok(with_session(&session, AuthHandlerError::InvalidCredentials));
+    };
+
+    // TOTP may have been disabled since the password step
+    if let Some(totp) = UserTotp::find(pending.user_id, state.database_pool())
+        .await?
+        .filter(|totp| totp.enabled)
+    {
+        if !check_second_factor(&state, &totp, &form.code).await? {
+            if pending.record_failure(config.max_login_attempts) {
+                session
+                    .set(PENDING_LOGIN_SESSION_KEY.to_string(), pending)
+                    .map_err(|e| AuthHandlerError::Internal(e.to_string()))?;
+            } else {
+                tracing::warn!(
+                    user_id = pending.user_id,
+                    "Too many wrong TOTP codes, pending login dropped"
+                );
+                session.remove(PENDING_LOGIN_SESSION_KEY);
+            }
+            return Ok(with_session(&session, AuthHandlerError::InvalidTotpCode));
+        }
+    }
+
+    session.remove(PENDING_LOGIN_SESSION_KEY);
+    session.set_user_id(Some(pending.user_id));
+    session.add_flash(FlashMessage::success("Successfully logged in!"));
+    Ok(login_redirect(&session, pending.user_id, pending.remember))
+}
+
+/// Path that starts TOTP enrollment
+const TOTP_SETUP_PATH: &str = "/account/totp/setup";
+
+/// GET /account/totp - Display the two-factor setup page
+///
+/// Only renders a button; the secret is created by [`totp_enroll_start`]
+/// when the form is posted, so prefetching or previewing the link can't
+/// replace an enrollment in progress.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::totp_enroll_form;
+/// use axum::{Router, routing::get};
+///
+/// let app = Router::new().route("/account/totp", get(totp_enroll_form));
+/// ```
+pub async fn totp_enroll_form(
+    HxRequest(_is_htmx): HxRequest,
+) -> Response {
+    let html = format!(
+        r#"
+<!DOCTYPE html>
+<html>
+<head>
+    <title>Set Up Two-Factor Authentication</title>
+    <script src="https://unpkg.com/htmx.org@1.9.10"></script>
+</head>
+<body>
+    <h1>Set Up Two-Factor Authentication</h1>
+    <p>Protect your account with codes from an authenticator app.</p>
+    <form hx-post="{TOTP_SETUP_PATH}" hx-target="body">
+        <button type="submit">Set up</button>
+    </form>
+</body>
+</html>
+    "#
+    );
+
+    Html(html).into_response()
+}
+
+/// Enrollment page showing the new secret
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+#[derive(Template)]
+#[template(
+    source = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Set Up Two-Factor Authentication</title>
+    <script src="https://unpkg.com/htmx.org@1.9.10"></script>
+</head>
+<body>
+    <h1>Set Up Two-Factor Authentication</h1>
+    <p>Scan this link as a QR code with your authenticator app, or enter the key manually.</p>
+    <p><code id="totp-uri">{{ uri }}</code></p>
+    <p>Key: <code>{{ secret }}</code></p>
+    <form hx-post="/account/totp" hx-target="body">
+        <div>
+            <label for="code">Code from the app:</label>
+            <input type="text" id="code" name="code" required autocomplete="one-time-code" inputmode="numeric" />
+        </div>
+        <button type="submit">Enable</button>
+    </form>
+</body>
+</html>"#,
+    ext = "html"
+)]
+struct TotpEnrollPage {
+    uri: String,
+    secret: String,
+}
+
+/// Page showing the backup codes once after enrollment
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+#[derive(Template)]
+#[template(
+    source = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Two-Factor Authentication Enabled</title>
+</head>
+<body>
+    <h1>Two-Factor Authentication Enabled</h1>
+    <p>Store these backup codes somewhere safe. Each can be used once if you lose your device; they won't be shown again.</p>
+    <ul>
+    {% for code in codes %}
+        <li><code>{{ code }}</code></li>
+    {% endfor %}
+    </ul>
+    <p><a href="/">Continue</a></p>
+</body>
+</html>"#,
+    ext = "html"
+)]
+struct TotpBackupCodesPage {
+    codes: Vec<String>,
+}
+
+/// POST /account/totp/setup - Start TOTP enrollment for the logged-in user
+///
+/// Generates a new secret, stores it encrypted as a pending enrollment, and
+/// shows the `otpauth://` URI to scan. TOTP is not required at login until
+/// [`totp_enroll_confirm`] accepts a first code. Posted from
+/// [`totp_enroll_form`]; like every other `POST`, it is covered by
+/// `CsrfLayer`.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - The user is not logged in
+/// - TOTP is already enabled
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::totp_enroll_start;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/account/totp/setup", post(totp_enroll_start));
+/// ```
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub async fn totp_enroll_start(
+    State(state): State<ActonHtmxState>,
+    session: Session,
+) -> Result<Response, AuthHandlerError> {
+    let user_id = session.user_id().ok_or(AuthHandlerError::InvalidCredentials)?;
+    let user = User::find_by_id(user_id, state.database_pool()).await?;
+
+    let secret = TotpSecret::generate();
+    let ciphertext = TotpCipher::from_signer(state.cookie_signer()).encrypt(&secret);
+    if !UserTotp::begin_enrollment(user_id, &ciphertext, state.database_pool()).await? {
+        return Err(AuthHandlerError::TotpConflict(
+            "Two-factor authentication is already enabled",
+        ));
+    }
+
+    let page = TotpEnrollPage {
+        uri: Totp::new(&state.config().security.totp).provisioning_uri(&secret, user.email.as_str()),
+        secret: secret.to_base32(),
+    };
+    let html = page
+        .render()
+        .map_err(|e| AuthHandlerError::Internal(e.to_string()))?;
+    Ok(Html(html).into_response())
+}
+
+/// POST /account/totp - Confirm TOTP enrollment with a first code
+///
+/// Enables TOTP and shows freshly generated backup codes once; only their
+/// hashes are stored.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - The user is not logged in or has no enrollment in progress
+/// - The code is wrong
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::totp_enroll_confirm;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/account/totp", post(totp_enroll_confirm));
+/// ```
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub async fn totp_enroll_confirm(
+    State(state): State<ActonHtmxState>,
+    session: Session,
+    Form(form): Form<TotpCodeForm>,
+) -> Result<Response, AuthHandlerError> {
+    form.validate()
+        .map_err(|e| AuthHandlerError::ValidationFailed(e.to_string()))?;
+
+    let user_id = session.user_id().ok_or(AuthHandlerError::InvalidCredentials)?;
+    let pending = UserTotp::find(user_id, state.database_pool())
+        .await?
+        .filter(|totp| !totp.enabled)
+        .ok_or(AuthHandlerError::TotpConflict(
+            "No two-factor authentication setup is in progress",
+        ))?;
+
+    let config = &state.config().security.totp;
+    let secret = decrypt_totp_secret(&state, &pending)?;
+    let step = Totp::new(config)
+        .verify(&secret, &form.code, None)
+        .ok_or(AuthHandlerError::InvalidTotpCode)?;
+
+    let codes = generate_backup_codes(config.backup_code_count);
+    let hashes: Vec<String> = codes.iter().map(|code| hash_backup_code(code)).collect();
+    if !UserTotp::enable(user_id, step, &hashes, state.database_pool()).await? {
+        return Err(AuthHandlerError::TotpConflict(
+            "No two-factor authentication setup is in progress",
+        ));
+    }
+
+    let html = TotpBackupCodesPage { codes }
+        .render()
+        .map_err(|e| AuthHandlerError::Internal(e.to_string()))?;
+    Ok(Html(html).into_response())
+}
+
+/// POST /account/totp/disable - Turn off TOTP for the logged-in user
+///
+/// Requires a current TOTP code or an unused backup code.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - The user is not logged in or doesn't have TOTP enabled
+/// - The code is wrong
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::totp_disable;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/account/totp/disable", post(totp_disable));
+/// ```
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub async fn totp_disable(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+    Form(form): Form<TotpCodeForm>,
+) -> Result<Response, AuthHandlerError> {
+    form.validate()
+        .map_err(|e| AuthHandlerError::ValidationFailed(e.to_string()))?;
+
+    let user_id = session.user_id().ok_or(AuthHandlerError::InvalidCredentials)?;
+    let totp = UserTotp::find(user_id, state.database_pool())
+        .await?
+        .filter(|totp| totp.enabled)
+        .ok_or(AuthHandlerError::TotpConflict(
+            "Two-factor authentication is not enabled",
+        ))?;
+
+    if !check_second_factor(&state, &totp, &form.code).await? {
+        return Err(AuthHandlerError::InvalidTotpCode);
+    }
+    UserTotp::delete(user_id, state.database_pool()).await?;
+
+    session.add_flash(FlashMessage::info("Two-factor authentication has been disabled."));
+    Ok(PostRedirectGet::new(&session, "/").into_response())
+}
+
+/// Check a TOTP or backup code for an enabled user, consuming it if valid
+///
+/// Returns `false` for wrong codes and for codes used concurrently.
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+async fn check_second_factor(
+    state: &ActonHtmxState,
+    totp: &UserTotp,
+    code: &str,
+) -> Result<bool, AuthHandlerError> {
+    let secret = decrypt_totp_secret(state, totp)?;
+    let pool = state.database_pool();
+
+    if let Some(step) =
+        Totp::new(&state.config().security.totp).verify(&secret, code, totp.last_used_step)
+    {
+        return Ok(UserTotp::record_step(totp.user_id, step, pool).await?);
+    }
+
+    let mut remaining = totp.backup_code_hashes.clone();
+    if take_backup_code(&mut remaining, code) {
+        return Ok(UserTotp::replace_backup_codes(
+            totp.user_id,
+            &totp.backup_code_hashes,
+            &remaining,
+            pool,
+        )
+        .await?);
+    }
+
+    Ok(false)
+}
+
+/// Decrypt a stored TOTP secret with the application key
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn decrypt_totp_secret(
+    state: &ActonHtmxState,
+    totp: &UserTotp,
+) -> Result<TotpSecret, AuthHandlerError> {
+    TotpCipher::from_signer(state.cookie_signer())
+        .decrypt(&totp.secret_ciphertext)
+        .ok_or_else(|| {
+            AuthHandlerError::Internal("Stored two-factor secret cannot be decrypted".to_string())
+        })
+}
+
+/// POST /account/passkeys/register/begin - Start registering a passkey
+///
+/// Returns the `navigator.credentials.create()` options as JSON and keeps
+/// the challenge in the session for `security.webauthn.challenge_timeout_secs`.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - The user is not logged in
+/// - `security.webauthn` is invalid
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::passkey_register_begin;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/account/passkeys/register/begin", post(passkey_register_begin));
+/// ```
+#[cfg(all(feature = "webauthn", any(feature = "postgres", feature = "sqlite")))]
+pub async fn passkey_register_begin(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+) -> Result<Response, AuthHandlerError> {
+    let user_id = session.user_id().ok_or(AuthHandlerError::InvalidCredentials)?;
+    let user = User::find_by_id(user_id, state.database_pool()).await?;
+    let existing: Vec<Passkey> = WebauthnCredential::for_user(user_id, state.database_pool())
+        .await?
+        .into_iter()
+        .map(|credential| credential.passkey)
+        .collect();
+
+    let (challenge, pending) = Passkeys::from_state(&state)?.start_registration(
+        user_id,
+        user.email.as_str(),
+        &existing,
+        unix_now(),
+    )?;
+    session
+        .set(REGISTRATION_SESSION_KEY.to_string(), pending)
+        .map_err(|e| AuthHandlerError::Internal(e.to_string()))?;
+
+    Ok(with_session(&session, Json(challenge)))
+}
+
+/// POST /account/passkeys/register/finish - Store the new passkey
+///
+/// Takes the JSON-encoded result of `navigator.credentials.create()` and
+/// responds `201 Created`. Each challenge can be answered once.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - The user is not logged in
+/// - No registration is in progress, or its challenge expired
+/// - The authenticator's response doesn't verify
+/// - The passkey is already registered
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::passkey_register_finish;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/account/passkeys/register/finish", post(passkey_register_finish));
+/// ```
+#[cfg(all(feature = "webauthn", any(feature = "postgres", feature = "sqlite")))]
+pub async fn passkey_register_finish(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+    Json(credential): Json<RegisterPublicKeyCredential>,
+) -> Result<Response, AuthHandlerError> {
+    let user_id = session.user_id().ok_or(AuthHandlerError::InvalidCredentials)?;
+    let pending = session.get::<PendingCeremony<PasskeyRegistration>>(REGISTRATION_SESSION_KEY);
+    session.remove(REGISTRATION_SESSION_KEY);
+    let pending = pending
+        .filter(|pending| pending.user_id == user_id)
+        .ok_or(PasskeyError::ChallengeExpired)?;
+
+    let passkey =
+        Passkeys::from_state(&state)?.finish_registration(&credential, &pending, unix_now())?;
+    if !WebauthnCredential::insert(user_id, &passkey, state.database_pool()).await? {
+        return Err(PasskeyError::AlreadyRegistered.into());
+    }
+
+    session.add_flash(FlashMessage::success("Passkey added."));
+    Ok(with_session(&session, StatusCode::CREATED))
+}
+
+/// POST /login/passkey/begin - Start logging in with a passkey
+///
+/// Takes a JSON [`PasskeyLoginRequest`] and returns the
+/// `navigator.credentials.get()` options for the account's passkeys.
+/// Unknown accounts and accounts without passkeys get the same error.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - The request is invalid
+/// - The account doesn't exist or has no passkeys
+/// - `security.webauthn` is invalid
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::passkey_login_begin;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/login/passkey/begin", post(passkey_login_begin));
+/// ```
+#[cfg(all(feature = "webauthn", any(feature = "postgres", feature = "sqlite")))]
+pub async fn passkey_login_begin(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+    Json(request): Json<PasskeyLoginRequest>,
+) -> Result<Response, AuthHandlerError> {
+    request
+        .validate()
+        .map_err(|e| AuthHandlerError::ValidationFailed(e.to_string()))?;
+
+    let email = EmailAddress::parse(&request.email)
+        .map_err(|_| AuthHandlerError::InvalidCredentials)?;
+    let user = match User::find_by_email(&email, state.database_pool()).await {
+        Ok(user) => user,
+        Err(UserError::NotFound) => return Err(AuthHandlerError::InvalidCredentials),
+        Err(e) => return Err(e.into()),
+    };
+    let passkeys: Vec<Passkey> = WebauthnCredential::for_user(user.id, state.database_pool())
+        .await?
+        .into_iter()
+        .map(|credential| credential.passkey)
+        .collect();
+    if passkeys.is_empty() {
+        return Err(AuthHandlerError::InvalidCredentials);
+    }
+
+    let (challenge, pending) = Passkeys::from_state(&state)?.start_authentication(
+        user.id,
+        &passkeys,
+        request.remember,
+        unix_now(),
+    )?;
+    session
+        .set(AUTHENTICATION_SESSION_KEY.to_string(), pending)
+        .map_err(|e| AuthHandlerError::Internal(e.to_string()))?;
+
+    Ok(with_session(&session, Json(challenge)))
+}
+
+/// Attach the session to a response so the session middleware saves it
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn with_session(session: &Session, response: impl IntoResponse) -> Response {
+    let mut response = response.into_response();
+    response.extensions_mut().insert(session.data().clone());
+    response.extensions_mut().insert(ConfirmSessionSave);
+    response
+}
+
+/// POST /login/passkey/finish - Finish logging in with a passkey
+///
+/// Takes the JSON-encoded result of `navigator.credentials.get()`. On
+/// success the session is logged in and the response redirects to `/`, the
+/// same as a password login; the TOTP step is skipped because passkeys
+/// require user verification. Each challenge can be answered once.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - No passkey login is in progress, or its challenge expired
+/// - The assertion doesn't verify or is for another account's passkey
+/// - Database query fails
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::passkey_login_finish;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/login/passkey/finish", post(passkey_login_finish));
+/// ```
+#[cfg(all(feature = "webauthn", any(feature = "postgres", feature = "sqlite")))]
+pub async fn passkey_login_finish(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+    Json(credential): Json<PublicKeyCredential>,
+) -> Result<Response, AuthHandlerError> {
+    let pending = session.get::<PendingCeremony<PasskeyAuthentication>>(AUTHENTICATION_SESSION_KEY);
+    session.remove(AUTHENTICATION_SESSION_KEY);
+    let pending = pending.ok_or(PasskeyError::ChallengeExpired)?;
+
+    let result =
+        Passkeys::from_state(&state)?.finish_authentication(&credential, &pending, unix_now())?;
+    let mut stored = WebauthnCredential::find(
+        &encode_credential_id(result.cred_id()),
+        state.database_pool(),
+    )
+    .await?
+    .filter(|stored| stored.user_id == pending.user_id)
+    .ok_or(AuthHandlerError::InvalidCredentials)?;
+
+    // Save the new signature counter
+    stored.passkey.update_credential(&result);
+    WebauthnCredential::record_use(&stored.passkey, state.database_pool()).await?;
+
+    session.set_user_id(Some(pending.user_id));
+    session.add_flash(FlashMessage::success("Successfully logged in!"));
+    Ok(login_redirect(&session, pending.user_id, pending.remember))
+}
+
+/// One row of the active sessions page
+struct SessionRow {
+    handle: String,
+    device: String,
+    ip_address: String,
+    last_seen: String,
+    current: bool,
+}
+
+impl SessionRow {
+    fn new(session: ActiveSession, current_handle: &str) -> Self {
+        Self {
+            current: session.handle == current_handle,
+            handle: session.handle,
+            device: session
+                .user_agent
+                .unwrap_or_else(|| "Unknown device".to_string()),
+            ip_address: session
+                .ip_address
+                .unwrap_or_else(|| "Unknown".to_string()),
+            last_seen: session.last_seen.format("%Y-%m-%d %H:%M UTC").to_string(),
+        }
+    }
+}
+
+/// Page listing the user's active sessions
+#[derive(Template)]
+#[template(
+    source = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Active Sessions</title>
+    <script src="https://unpkg.com/htmx.org@1.9.10"></script>
+</head>
+<body>
+    <h1>Active Sessions</h1>
+    <table>
+        <tr><th>Device</th><th>IP address</th><th>Last seen</th><th></th></tr>
+    {% for row in sessions %}
+        <tr>
+            <td>{{ row.device }}</td>
+            <td>{{ row.ip_address }}</td>
+            <td>{{ row.last_seen }}</td>
+            <td>
+                {% if row.current %}<strong>This device</strong>{% endif %}
+                <form hx-post="/account/sessions/revoke" hx-target="body">
+                    <input type="hidden" name="session" value="{{ row.handle }}" />
+                    <button type="submit">Sign out</button>
+                </form>
+            </td>
+        </tr>
+    {% endfor %}
+    </table>
+</body>
+</html>"#,
+    ext = "html"
+)]
+struct SessionsPage {
+    sessions: Vec<SessionRow>,
+}
+
+/// GET /account/sessions - List the logged-in user's active sessions
+///
+/// Shows each session's device (`User-Agent`), IP address, and last activity,
+/// with a button to sign it out via [`session_revoke`]. Sessions are named by
+/// their [handle](crate::htmx::auth::SessionId::handle), never their ID.
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - The user is not logged in
+/// - The session manager doesn't respond
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::sessions_list;
+/// use axum::{Router, routing::get};
+///
+/// let app = Router::new().route("/account/sessions", get(sessions_list));
+/// ```
+pub async fn sessions_list(
+    State(state): State<ActonHtmxState>,
+    session: Session,
+) -> Result<Response, AuthHandlerError> {
+    let user_id = session.user_id().ok_or(AuthHandlerError::InvalidCredentials)?;
+
+    let (request, rx) = ListUserSessions::with_response(user_id);
+    state.session_manager().send(request).await;
+    let sessions = tokio::time::timeout(Duration::from_secs(1), rx)
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .ok_or_else(|| AuthHandlerError::Internal("Session manager did not respond".to_string()))?;
+
+    let current = session.id().handle();
+    let page = SessionsPage {
+        sessions: sessions
+            .into_iter()
+            .map(|active| SessionRow::new(active, &current))
+            .collect(),
+    };
+    let html = page
+        .render()
+        .map_err(|e| AuthHandlerError::Internal(e.to_string()))?;
+    Ok(Html(html).into_response())
+}
+
+/// POST /account/sessions/revoke - Sign out one of the user's sessions
+///
+/// Deletes the session named by the form's handle, so its cookie no longer
+/// works. Revoking the current session logs out like [`logout_post`].
+///
+/// # Errors
+///
+/// Returns [`AuthHandlerError`] if:
+/// - Form validation fails
+/// - The user is not logged in
+/// - No session of this user has the handle
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::auth::handlers::session_revoke;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/account/sessions/revoke", post(session_revoke));
+/// ```
+pub async fn session_revoke(
+    State(state): State<ActonHtmxState>,
+    mut session: Session,
+    Form(form): Form<RevokeSessionForm>,
+) -> Result<Response, AuthHandlerError> {
+    form.validate()
+        .map_err(|e| AuthHandlerError::ValidationFailed(e.to_string()))?;
+    let user_id = session.user_id().ok_or(AuthHandlerError::InvalidCredentials)?;
+
+    if form.session == session.id().handle() {
+        return Ok(logout_post(session).await);
+    }
+
+    let (request, rx) = RevokeUserSession::with_confirmation(user_id, form.session);
+    state.session_manager().send(request).await;
+    match tokio::time::timeout(Duration::from_secs(1), rx).await {
+        Ok(Ok(true)) => {}
+        Ok(Ok(false)) => return Err(AuthHandlerError::SessionNotFound),
+        _ => {
+            return Err(AuthHandlerError::Internal(
+                "Session manager did not respond".to_string(),
+            ))
+        }
+    }
+
+    session.add_flash(FlashMessage::info("The session has been signed out."));
+    Ok(PostRedirectGet::new(&session, "/account/sessions").into_response())
+}
+
+/// POST /logout - Clear session and logout
+///
+/// Also revokes the remember-me token, if any, and moves the session to a new
+/// ID.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_htmx::auth::handlers::logout_post;
+/// use axum::{Router, routing::post};
+///
+/// let app = Router::new().route("/logout", post(logout_post));
+/// ```
+pub async fn logout_post(
+    mut session: Session,
+) -> Response {
+    // Clear user ID from session
+    session.set_user_id(None);
+
+    // Add info flash message
+    session.add_flash(FlashMessage::info("You have been logged out."));
+
+    // Redirect to login under a new session ID
+    let mut response = PostRedirectGet::new(&session, "/login").into_response();
+    response.extensions_mut().insert(RegenerateSessionId);
+    response.extensions_mut().insert(ForgetLogin);
+    response
+}
+
+/// Authentication handler errors
+#[derive(Debug)]
+pub enum AuthHandlerError {
+    /// Form validation failed
+    ValidationFailed(String),
+
+    /// Invalid email format
+    InvalidEmail,
+
+    /// Password confirmation doesn't match
+    PasswordMismatch,
+
+    /// Password fails the configured password policy
+    WeakPassword(PasswordError),
+
+    /// Invalid credentials
+    InvalidCredentials,
+
+    /// User error
+    UserError(UserError),
+
+    /// Database not configured
+    DatabaseNotConfigured,
+
+    /// Email verification token is tampered, expired, or stale
+    InvalidVerificationToken,
+
+    /// Password reset token is unknown, expired, or already used
+    InvalidResetToken,
+
+    /// TOTP or backup code is wrong or already used
+    InvalidTotpCode,
+
+    /// Two-factor authentication is not in the state the request needs
+    TotpConflict(&'static str),
+
+    /// No active session of the user has the given handle
+    SessionNotFound,
+
+    /// Unexpected server-side failure
+    Internal(String),
+
+    /// Too many requests; retry after this many seconds
+    RateLimited(u64),
+
+    /// Email could not be queued
+    EmailFailed(String),
+
+    /// Passkey registration or login failed
+    #[cfg(feature = "webauthn")]
+    Passkey(PasskeyError),
+}
+
+impl From<UserError> for AuthHandlerError {
+    fn from(err: UserError) -> Self {
+        Self::UserError(err)
+    }
+}
+
+#[cfg(feature = "webauthn")]
+impl From<PasskeyError> for AuthHandlerError {
+    fn from(err: PasskeyError) -> Self {
+        Self::Passkey(err)
+    }
+}
+
+impl IntoResponse for AuthHandlerError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::ValidationFailed(msg) => (StatusCode::BAD_REQUEST, msg),
+            Self::InvalidEmail => (StatusCode::BAD_REQUEST, "Invalid email format".to_string()),
+            Self::PasswordMismatch => (
+                StatusCode::BAD_REQUEST,
+                "Passwords do not match".to_string(),
+            ),
+            Self::WeakPassword(e @ PasswordError::PwnedCheckFailed(_)) => {
+                (StatusCode::SERVICE_UNAVAILABLE, e.to_string())
+            }
+            Self::WeakPassword(e) => (StatusCode::BAD_REQUEST, e.to_string()),
+            Self::InvalidCredentials => (
+                StatusCode::UNAUTHORIZED,
+                "Invalid email or password".to_string(),
+            ),
+            Self::UserError(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            Self::DatabaseNotConfigured => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Database not configured".to_string(),
+            ),
+            Self::InvalidVerificationToken => (
+                StatusCode::BAD_REQUEST,
+                "Invalid or expired verification link".to_string(),
+            ),
+            Self::InvalidResetToken => (
+                StatusCode::BAD_REQUEST,
+                "Invalid or expired password reset link".to_string(),
+            ),
+            Self::InvalidTotpCode => (
+                StatusCode::UNAUTHORIZED,
+                "Invalid authentication code".to_string(),
+            ),
+            Self::TotpConflict(message) => (StatusCode::CONFLICT, message.to_string()),
+            Self::SessionNotFound => (StatusCode::NOT_FOUND, "Session not found".to_string()),
+            Self::Internal(e) => (StatusCode::INTERNAL_SERVER_ERROR, e),
+            Self::RateLimited(retry_after) => {
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, retry_after.to_string())],
+                    format!("Please wait {retry_after} seconds before requesting another email"),
+                )
+                    .into_response();
+            }
+            Self::EmailFailed(e) => (StatusCode::SERVICE_UNAVAILABLE, e),
+            #[cfg(feature = "webauthn")]
+            Self::Passkey(e @ PasskeyError::Configuration(_)) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+            }
+            #[cfg(feature = "webauthn")]
+            Self::Passkey(PasskeyError::ChallengeExpired) => (
+                StatusCode::BAD_REQUEST,
+                "Passkey request expired, please try again".to_string(),
+            ),
+            #[cfg(feature = "webauthn")]
+            Self::Passkey(PasskeyError::Rejected(_)) => (
+                StatusCode::UNAUTHORIZED,
+                "Passkey verification failed".to_string(),
+            ),
+            #[cfg(feature = "webauthn")]
+            Self::Passkey(e @ PasskeyError::AlreadyRegistered) => {
+                (StatusCode::CONFLICT, e.to_string())
+            }
+        };
+
+        (status, message).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_form_struct() {
+        let form = LoginForm {
+            email: "test@example.com".to_string(),
+            password: "password123".to_string(),
+            remember: false,
+        };
+        assert!(form.validate().is_ok());
+    }
+
+    #[test]
+    fn test_register_form_struct() {
+        let form = RegisterForm {
+            email: "test@example.com".to_string(),
+            password: "password123".to_string(),
+            password_confirm: "password123".to_string(),
+        };
+        assert!(form.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_email() {
+        let form = LoginForm {
+            email: "not-an-email".to_string(),
+            password: "password123".to_string(),
+            remember: false,
+        };
+        assert!(form.validate().is_err());
     }
 
     #[test]
@@ -410,7 +1801,262 @@ mod tests {
         let form = LoginForm {
             email: "test@example.com".to_string(),
             password: "short".to_string(),
+            remember: false,
         };
         assert!(form.validate().is_err());
     }
+
+    #[test]
+    fn test_login_form_remember_defaults_to_false() {
+        let form: LoginForm =
+            serde_html_form::from_str("email=test%40example.com&password=password123").unwrap();
+        assert!(!form.remember);
+
+        let form: LoginForm = serde_html_form::from_str(
+            "email=test%40example.com&password=password123&remember=true",
+        )
+        .unwrap();
+        assert!(form.remember);
+    }
+
+    #[tokio::test]
+    async fn test_logout_requests_remember_revocation() {
+        let session = Session::new(
+            crate::htmx::auth::SessionId::generate(),
+            crate::htmx::auth::SessionData::new(),
+        );
+        let response = logout_post(session).await;
+        assert!(response.extensions().get::<ForgetLogin>().is_some());
+        assert!(response.extensions().get::<RegenerateSessionId>().is_some());
+        assert_eq!(
+            response.extensions().get::<crate::htmx::auth::SessionData>().unwrap().user_id,
+            None
+        );
+    }
+
+    #[test]
+    fn test_weak_password_response() {
+        let response = AuthHandlerError::WeakPassword(PasswordError::MissingDigit).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = AuthHandlerError::WeakPassword(PasswordError::Pwned).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response =
+            AuthHandlerError::WeakPassword(PasswordError::PwnedCheckFailed("timeout".into()))
+                .into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_verification_error_responses() {
+        let response = AuthHandlerError::InvalidVerificationToken.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = AuthHandlerError::InvalidResetToken.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = AuthHandlerError::InvalidTotpCode.into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = AuthHandlerError::TotpConflict("already enabled").into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = AuthHandlerError::RateLimited(42).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "42");
+
+        let response = AuthHandlerError::EmailFailed("queue full".into()).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[cfg(feature = "webauthn")]
+    #[test]
+    fn test_passkey_error_responses() {
+        let response = AuthHandlerError::from(PasskeyError::ChallengeExpired).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = AuthHandlerError::from(PasskeyError::Rejected(
+            webauthn_rs::prelude::WebauthnError::MismatchedChallenge,
+        ))
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = AuthHandlerError::from(PasskeyError::AlreadyRegistered).into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response =
+            AuthHandlerError::from(PasskeyError::Configuration("rp_id".into())).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_totp_enroll_form_posts_to_setup() {
+        let response = totp_enroll_form(HxRequest(false)).await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains(r#"hx-post="/account/totp/setup""#));
+        assert!(!body.contains("otpauth://"));
+    }
+
+    #[tokio::test]
+    async fn test_reset_password_form_escapes_token() {
+        let response = reset_password_form(
+            HxRequest(false),
+            Query(ResetPasswordQuery {
+                token: "abc-_123\"><script>".to_string(),
+            }),
+        )
+        .await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains(r#"value="abc-_123script""#));
+        assert!(!body.contains("<script>"));
+    }
+
+    /// Save a session logged in as `user_id` through the state's session manager
+    async fn save_user_session(state: &ActonHtmxState, user_id: i64) -> Session {
+        let mut data = crate::htmx::auth::SessionData::new();
+        data.user_id = Some(user_id);
+        data.user_agent = Some("Firefox <script>".to_string());
+        let id = crate::htmx::auth::SessionId::generate();
+        let (request, rx) =
+            crate::htmx::agents::SaveSession::with_confirmation(id.clone(), data.clone());
+        state.session_manager().send(request).await;
+        assert!(rx.await.unwrap());
+        Session::new(id, data)
+    }
+
+    async fn session_exists(state: &ActonHtmxState, session: &Session) -> bool {
+        let (request, rx) = crate::htmx::agents::LoadSession::with_response(session.id().clone());
+        state.session_manager().send(request).await;
+        rx.await.unwrap().is_some()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sessions_list_and_revoke() {
+        let mut runtime = acton_reactive::prelude::ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+        let current = save_user_session(&state, 7).await;
+        let other = save_user_session(&state, 7).await;
+        let stranger = save_user_session(&state, 8).await;
+
+        let response = sessions_list(State(state.clone()), current.clone()).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains(&current.id().handle()));
+        assert!(body.contains(&other.id().handle()));
+        assert!(!body.contains(&stranger.id().handle()));
+        assert!(!body.contains(current.id().as_str()));
+        assert!(body.contains("This device"));
+        assert!(!body.contains("<script>"));
+
+        // Another user's session is not found
+        let form = RevokeSessionForm {
+            session: stranger.id().handle(),
+        };
+        let err = session_revoke(State(state.clone()), current.clone(), Form(form))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AuthHandlerError::SessionNotFound));
+        assert!(session_exists(&state, &stranger).await);
+
+        let form = RevokeSessionForm {
+            session: other.id().handle(),
+        };
+        let response = session_revoke(State(state.clone()), current.clone(), Form(form))
+            .await
+            .unwrap();
+        assert!(response.status().is_redirection());
+        assert!(!session_exists(&state, &other).await);
+        assert!(session_exists(&state, &current).await);
+
+        // Revoking the current session logs out
+        let form = RevokeSessionForm {
+            session: current.id().handle(),
+        };
+        let response = session_revoke(State(state.clone()), current, Form(form))
+            .await
+            .unwrap();
+        assert!(response.extensions().get::<RegenerateSessionId>().is_some());
+        assert_eq!(
+            response.extensions().get::<crate::htmx::auth::SessionData>().unwrap().user_id,
+            None
+        );
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_totp_login_attempts_are_limited() {
+        let mut runtime = acton_reactive::prelude::ActonApp::launch();
+        let mut state = ActonHtmxState::new(&mut runtime).await.unwrap();
+
+        let pool = crate::htmx::testing::create_sqlite_pool_with_migration(include_str!(
+            "../../../../migrations/009_create_user_totp_table.sql"
+        ))
+        .await
+        .unwrap();
+        let secret = TotpSecret::generate();
+        let ciphertext = TotpCipher::from_signer(state.cookie_signer()).encrypt(&secret);
+        assert!(UserTotp::begin_enrollment(7, &ciphertext, &pool).await.unwrap());
+        assert!(UserTotp::enable(7, 0, &[], &pool).await.unwrap());
+        state.set_database_pool(pool);
+
+        let config = state.config().security.totp.clone();
+        let mut session = Session::new(
+            crate::htmx::auth::SessionId::generate(),
+            crate::htmx::auth::SessionData::new(),
+        );
+        let pending = PendingTotpLogin {
+            user_id: 7,
+            remember: false,
+            started_at: unix_now(),
+            failed_attempts: 0,
+        };
+        session
+            .set(PENDING_LOGIN_SESSION_KEY.to_string(), pending)
+            .unwrap();
+
+        // Each wrong code is saved with the session, until the login is dropped
+        let attempt = |session: Session, code: String| {
+            let state = state.clone();
+            async move {
+                let id = session.id().clone();
+                let response = totp_login_post(State(state), session, Form(TotpCodeForm { code }))
+                    .await
+                    .unwrap();
+                let data = response
+                    .extensions()
+                    .get::<crate::htmx::auth::SessionData>()
+                    .unwrap()
+                    .clone();
+                (response.status(), Session::new(id, data))
+            }
+        };
+        for _ in 0..config.max_login_attempts {
+            let (status, next) = attempt(session, "not-a-code".to_string()).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            session = next;
+        }
+        assert!(session
+            .get::<PendingTotpLogin>(PENDING_LOGIN_SESSION_KEY)
+            .is_none());
+
+        let code = Totp::new(&config).code_at(&secret, unix_now());
+        let (status, session) = attempt(session, code).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(session.data().user_id, None);
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
 }
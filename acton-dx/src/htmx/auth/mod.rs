@@ -2,26 +2,85 @@
 //!
 //! This module provides session-based authentication with secure HTTP-only cookies.
 
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub mod current_user;
 pub mod extractors;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub mod guard;
 pub mod handlers;
 pub mod password;
+pub mod password_reset;
+pub mod pwned;
+pub mod realtime;
+pub mod remember;
 pub mod session;
+pub mod totp;
 pub mod user;
+pub mod verification;
+#[cfg(feature = "webauthn")]
+pub mod webauthn;
 
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub use current_user::{CurrentUser, OptionalCurrentUser};
 pub use extractors::{Authenticated, AuthenticationError, OptionalAuth};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub use guard::{AccessRequirement, RequirePermission, RequireRole, RoleLayer, RoleMiddleware};
 pub use handlers::{
-    login_form, logout_post, register_form, AuthHandlerError, LoginForm, RegisterForm,
+    check_new_password, login_form, logout_post, password_reset_form, register_form,
+    reset_password_form, session_revoke, sessions_list, totp_enroll_form, totp_login_form,
+    AuthHandlerError, LoginForm, PasswordResetForm, PasswordResetRequestForm, RegisterForm,
+    RevokeSessionForm, TotpCodeForm,
 };
 
 // Database-dependent handlers are only available with postgres or sqlite
 #[cfg(any(feature = "postgres", feature = "sqlite"))]
-pub use handlers::{login_post, register_post};
+pub use handlers::{
+    login_post, register_post, request_password_reset, resend_verification, reset_password,
+    totp_disable, totp_enroll_confirm, totp_enroll_start, totp_login_post, verify_email,
+};
+#[cfg(all(feature = "webauthn", any(feature = "postgres", feature = "sqlite")))]
+pub use handlers::{
+    passkey_login_begin, passkey_login_finish, passkey_register_begin, passkey_register_finish,
+    PasskeyLoginRequest,
+};
 pub use password::{
     hash_password, verify_password, PasswordError, PasswordHashConfig, PasswordHasher,
+    PasswordPolicy,
+};
+pub use password_reset::{
+    InMemoryPasswordResetTokenStore, PasswordReset, PasswordResetConfig,
+    PasswordResetTokenRecord, PasswordResetTokenStore,
+};
+#[cfg(feature = "postgres")]
+pub use password_reset::PostgresPasswordResetTokenStore;
+#[cfg(feature = "sqlite")]
+pub use password_reset::SqlitePasswordResetTokenStore;
+pub use pwned::{PwnedPasswordChecker, PwnedPasswordConfig};
+pub use remember::{
+    ForgetAllLogins, ForgetLogin, InMemoryRememberTokenStore, RememberLogin, RememberMe, RememberTokenRecord,
+    RememberTokenStore,
 };
+#[cfg(feature = "postgres")]
+pub use remember::PostgresRememberTokenStore;
+#[cfg(feature = "sqlite")]
+pub use remember::SqliteRememberTokenStore;
+pub use realtime::{RealtimeAuth, RealtimeAuthError, SessionWatch, WS_CLOSE_POLICY_VIOLATION};
 pub use session::{FlashLevel, FlashMessage, SessionData, SessionError, SessionId};
+pub use totp::{
+    generate_backup_codes, hash_backup_code, take_backup_code, PendingTotpLogin, Totp,
+    TotpCipher, TotpConfig, TotpSecret, UserTotp,
+};
 pub use user::{CreateUser, EmailAddress, User, UserError};
+pub use verification::{EmailVerification, EmailVerificationConfig, VerificationClaim};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub use verification::RequireVerifiedEmail;
+#[cfg(feature = "webauthn")]
+pub use webauthn::{
+    encode_credential_id, user_handle, PasskeyError, Passkeys, PendingCeremony, WebauthnConfig,
+    WebauthnCredential,
+};
 
+use crate::htmx::middleware::FlashJar;
 use serde::{Deserialize, Serialize};
 
 /// Session wrapper for handler extractors
@@ -31,13 +90,47 @@ use serde::{Deserialize, Serialize};
 pub struct Session {
     id: SessionId,
     data: SessionData,
+    htmx: bool,
+    flash_jar: Option<FlashJar>,
 }
 
 impl Session {
     /// Create a new session wrapper
     #[must_use]
     pub const fn new(id: SessionId, data: SessionData) -> Self {
-        Self { id, data }
+        Self {
+            id,
+            data,
+            htmx: false,
+            flash_jar: None,
+        }
+    }
+
+    /// Keep flash messages in the flash cookie instead of the session
+    ///
+    /// Set by the `Session` extractor when
+    /// [`FlashCookieLayer`](crate::htmx::middleware::FlashCookieLayer) is
+    /// installed.
+    #[must_use]
+    pub fn with_flash_jar(mut self, flash_jar: FlashJar) -> Self {
+        self.flash_jar = Some(flash_jar);
+        self
+    }
+
+    /// Record whether the current request was made by HTMX
+    ///
+    /// Set by the `Session` extractor; used by [`prg`](crate::htmx::responses::prg)
+    /// to choose between `HX-Redirect` and `303 See Other`.
+    #[must_use]
+    pub const fn with_htmx_request(mut self, htmx: bool) -> Self {
+        self.htmx = htmx;
+        self
+    }
+
+    /// Whether the current request was made by HTMX
+    #[must_use]
+    pub const fn is_htmx_request(&self) -> bool {
+        self.htmx
     }
 
     /// Get the session ID
@@ -89,18 +182,30 @@ impl Session {
     }
 
     /// Add a flash message
+    ///
+    /// Stored in the flash cookie when flash cookies are enabled, otherwise
+    /// in the session.
     pub fn add_flash(&mut self, message: FlashMessage) {
-        self.data.flash_messages.push(message);
+        match &self.flash_jar {
+            Some(jar) => jar.add(message),
+            None => self.data.flash_messages.push(message),
+        }
     }
 
     /// Take all flash messages (clears them from session)
     pub fn take_flashes(&mut self) -> Vec<FlashMessage> {
-        std::mem::take(&mut self.data.flash_messages)
+        match &self.flash_jar {
+            Some(jar) => jar.take(),
+            None => std::mem::take(&mut self.data.flash_messages),
+        }
     }
 
     /// Check if there are any flash messages
     #[must_use]
     pub fn has_flashes(&self) -> bool {
-        !self.data.flash_messages.is_empty()
+        self.flash_jar.as_ref().map_or_else(
+            || !self.data.flash_messages.is_empty(),
+            FlashJar::has_flashes,
+        )
     }
 }
@@ -10,6 +10,7 @@
 //! - Cryptographically secure random salt generation
 //! - Constant-time password verification
 //! - Follows OWASP recommendations for password storage
+//! - [`PasswordPolicy`] enforces strength rules before a password is hashed
 //!
 //! # Example
 //!
@@ -63,6 +64,161 @@ pub enum PasswordError {
     /// Invalid parameters for Argon2
     #[error("Invalid Argon2 parameters: {0}")]
     InvalidParams(String),
+
+    /// Password is shorter than the policy minimum
+    #[error("Password must be at least {0} characters")]
+    TooShort(usize),
+
+    /// Password is longer than the policy maximum
+    #[error("Password must be at most {0} characters")]
+    TooLong(usize),
+
+    /// Password has no uppercase letter
+    #[error("Password must contain at least one uppercase letter")]
+    MissingUppercase,
+
+    /// Password has no lowercase letter
+    #[error("Password must contain at least one lowercase letter")]
+    MissingLowercase,
+
+    /// Password has no digit
+    #[error("Password must contain at least one digit")]
+    MissingDigit,
+
+    /// Password has no symbol
+    #[error("Password must contain at least one symbol")]
+    MissingSymbol,
+
+    /// Password appears in the common password blocklist
+    #[error("Password is too common")]
+    CommonPassword,
+
+    /// Password appears in a known data breach
+    #[error("Password has appeared in a data breach, please choose another")]
+    Pwned,
+
+    /// Breached password lookup failed
+    #[error("Failed to check password against breach data: {0}")]
+    PwnedCheckFailed(String),
+}
+
+/// Embedded list of commonly used passwords, one per line, lowercase
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+/// Password strength rules checked at registration
+///
+/// Character counts are in Unicode scalar values, not bytes. Symbols are any
+/// characters that are neither alphanumeric nor whitespace.
+///
+/// # Defaults
+///
+/// - Length: 8 to 128 characters
+/// - Uppercase, lowercase, and digit required; symbol optional
+/// - Common password blocklist enabled
+///
+/// # Example
+///
+/// ```rust
+/// use acton_htmx::auth::password::{PasswordError, PasswordPolicy};
+///
+/// let policy = PasswordPolicy {
+///     min_length: 12,
+///     require_symbol: true,
+///     ..PasswordPolicy::default()
+/// };
+///
+/// assert!(policy.validate("Correct-Horse-9").is_ok());
+/// assert!(matches!(policy.validate("Short1!a"), Err(PasswordError::TooShort(12))));
+/// ```
+#[allow(clippy::struct_excessive_bools)] // Independent on/off rules mirrored from config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    /// Minimum length in characters (default: 8)
+    pub min_length: usize,
+
+    /// Maximum length in characters (default: 128)
+    ///
+    /// Bounds the work done by Argon2 for attacker-supplied input.
+    pub max_length: usize,
+
+    /// Require at least one uppercase letter (default: true)
+    pub require_uppercase: bool,
+
+    /// Require at least one lowercase letter (default: true)
+    pub require_lowercase: bool,
+
+    /// Require at least one digit (default: true)
+    pub require_digit: bool,
+
+    /// Require at least one symbol (default: false)
+    pub require_symbol: bool,
+
+    /// Reject passwords from the embedded common password list (default: true)
+    ///
+    /// The comparison is case-insensitive.
+    pub block_common_passwords: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: false,
+            block_common_passwords: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Check a password against every rule of the policy
+    ///
+    /// # Errors
+    ///
+    /// Returns the `PasswordError` for the first rule the password fails,
+    /// checked in order: length, common password, then character classes.
+    pub fn validate(&self, password: &str) -> Result<(), PasswordError> {
+        let length = password.chars().count();
+        if length < self.min_length {
+            return Err(PasswordError::TooShort(self.min_length));
+        }
+        if length > self.max_length {
+            return Err(PasswordError::TooLong(self.max_length));
+        }
+
+        if self.block_common_passwords && is_common_password(password) {
+            return Err(PasswordError::CommonPassword);
+        }
+
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            return Err(PasswordError::MissingUppercase);
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            return Err(PasswordError::MissingLowercase);
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Err(PasswordError::MissingDigit);
+        }
+        if self.require_symbol
+            && !password
+                .chars()
+                .any(|c| !c.is_alphanumeric() && !c.is_whitespace())
+        {
+            return Err(PasswordError::MissingSymbol);
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether a password appears in the embedded common password list
+fn is_common_password(password: &str) -> bool {
+    let password = password.to_lowercase();
+    COMMON_PASSWORDS.lines().any(|common| common == password)
 }
 
 /// Configuration for Argon2id password hashing
@@ -511,4 +667,86 @@ mod tests {
         let _ = hasher.verify("test", &hash);
         let _ = hasher.verify("wrong", &hash);
     }
+
+    #[test]
+    fn test_policy_default_accepts_strong_password() {
+        assert!(PasswordPolicy::default().validate("Tr0ub4dor&3x").is_ok());
+    }
+
+    #[test]
+    fn test_policy_min_length() {
+        let policy = PasswordPolicy::default();
+        assert!(matches!(policy.validate("Ab1cdef"), Err(PasswordError::TooShort(8))));
+        // Length is counted in characters, not bytes
+        assert!(matches!(policy.validate("Äb1cdéf"), Err(PasswordError::TooShort(8))));
+    }
+
+    #[test]
+    fn test_policy_max_length() {
+        let policy = PasswordPolicy {
+            max_length: 10,
+            ..PasswordPolicy::default()
+        };
+        assert!(matches!(policy.validate("Abcdefgh1234"), Err(PasswordError::TooLong(10))));
+    }
+
+    #[test]
+    fn test_policy_requires_uppercase() {
+        assert!(matches!(
+            PasswordPolicy::default().validate("lowercase1only"),
+            Err(PasswordError::MissingUppercase)
+        ));
+    }
+
+    #[test]
+    fn test_policy_requires_lowercase() {
+        assert!(matches!(
+            PasswordPolicy::default().validate("UPPERCASE1ONLY"),
+            Err(PasswordError::MissingLowercase)
+        ));
+    }
+
+    #[test]
+    fn test_policy_requires_digit() {
+        assert!(matches!(
+            PasswordPolicy::default().validate("NoDigitsHere"),
+            Err(PasswordError::MissingDigit)
+        ));
+    }
+
+    #[test]
+    fn test_policy_requires_symbol_when_enabled() {
+        let policy = PasswordPolicy {
+            require_symbol: true,
+            ..PasswordPolicy::default()
+        };
+        assert!(matches!(policy.validate("NoSymbols123"), Err(PasswordError::MissingSymbol)));
+        assert!(matches!(policy.validate("Space Only1"), Err(PasswordError::MissingSymbol)));
+        assert!(policy.validate("Symbol#123a").is_ok());
+    }
+
+    #[test]
+    fn test_policy_disabled_rules() {
+        let policy = PasswordPolicy {
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            ..PasswordPolicy::default()
+        };
+        assert!(policy.validate("correcthorsebattery").is_ok());
+    }
+
+    #[test]
+    fn test_policy_blocks_common_passwords_case_insensitively() {
+        let policy = PasswordPolicy::default();
+        assert!(matches!(policy.validate("Password123"), Err(PasswordError::CommonPassword)));
+        assert!(matches!(policy.validate("WELCOME123"), Err(PasswordError::CommonPassword)));
+        assert!(matches!(policy.validate("P@ssw0rd"), Err(PasswordError::CommonPassword)));
+
+        let permissive = PasswordPolicy {
+            block_common_passwords: false,
+            ..PasswordPolicy::default()
+        };
+        assert!(permissive.validate("Password123").is_ok());
+    }
 }
@@ -0,0 +1,597 @@
+//! Password reset with single-use tokens
+//!
+//! [`PasswordReset`] issues random, expiring reset tokens and emails them as
+//! a link through [`SendEmailJob`]. Only a SHA-256 hash of each token is
+//! stored, and a token is deleted the first time it is presented, so it can
+//! never be used twice.
+//!
+//! The [`request_password_reset`](crate::htmx::auth::handlers::request_password_reset)
+//! handler sends the email and responds identically whether or not the
+//! address belongs to an account, so it can't be used to discover users.
+//! [`reset_password`](crate::htmx::auth::handlers::reset_password) checks
+//! the token, enforces the password policy, stores the new hash, and signs
+//! the user out of every session and remembered device.
+//!
+//! The SQL stores expect the table from
+//! `migrations/008_create_password_reset_tokens_table.sql`.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [security.password_reset]
+//! from = "noreply@example.com"
+//! base_url = "https://example.com"
+//! token_ttl_secs = 3600
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::handlers::{
+//!     password_reset_form, request_password_reset, reset_password, reset_password_form,
+//! };
+//! use axum::{routing::get, Router};
+//!
+//! let app = Router::new()
+//!     .route("/password-reset", get(password_reset_form).post(request_password_reset))
+//!     .route("/password-reset/confirm", get(reset_password_form).post(reset_password));
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use askama::Template;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::htmx::auth::remember::{hash_validator, random_token};
+use crate::htmx::auth::{EmailAddress, UserError};
+use crate::htmx::email::{Email, EmailError, SendEmailJob, SimpleEmailTemplate};
+use crate::htmx::jobs::JobError;
+use crate::htmx::state::ActonHtmxState;
+
+#[cfg(feature = "postgres")]
+use sqlx::PgPool;
+
+#[cfg(feature = "sqlite")]
+use sqlx::SqlitePool;
+
+/// Path of the reset link, relative to `base_url`
+pub const RESET_PASSWORD_PATH: &str = "/password-reset/confirm";
+
+/// Configuration for password reset emails
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [security.password_reset]
+/// from = "noreply@example.com"
+/// base_url = "https://example.com"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PasswordResetConfig {
+    /// Sender address of reset emails
+    pub from: String,
+
+    /// Public URL of the application, used to build the reset link
+    pub base_url: String,
+
+    /// How long a reset link stays valid, in seconds (default: 1 hour)
+    pub token_ttl_secs: u64,
+}
+
+impl Default for PasswordResetConfig {
+    fn default() -> Self {
+        Self {
+            from: "noreply@localhost".to_string(),
+            base_url: "http://localhost:3000".to_string(),
+            token_ttl_secs: 3600,
+        }
+    }
+}
+
+/// A stored password reset token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordResetTokenRecord {
+    /// Hex-encoded SHA-256 hash of the token
+    pub token_hash: String,
+    /// User whose password the token resets
+    pub user_id: i64,
+    /// When the token stops being accepted
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Persistence backend for password reset tokens
+#[async_trait]
+pub trait PasswordResetTokenStore: Send + Sync + Debug {
+    /// Store a new token
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn insert(&self, record: &PasswordResetTokenRecord) -> Result<(), UserError>;
+
+    /// Delete a token by hash, returning it if it existed
+    ///
+    /// Returns the token even if it has expired. Only one caller can take a
+    /// given token.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn take(&self, token_hash: &str) -> Result<Option<PasswordResetTokenRecord>, UserError>;
+
+    /// Delete every token belonging to a user
+    ///
+    /// Returns the number of tokens removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, UserError>;
+
+    /// Delete all expired tokens
+    ///
+    /// Returns the number of tokens removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn cleanup_expired(&self) -> Result<u64, UserError>;
+}
+
+/// Process-local password reset token storage
+///
+/// Tokens are lost on restart; intended for tests and single-process
+/// development.
+#[derive(Debug, Default)]
+pub struct InMemoryPasswordResetTokenStore {
+    tokens: Mutex<HashMap<String, PasswordResetTokenRecord>>,
+}
+
+impl InMemoryPasswordResetTokenStore {
+    /// Create an empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl PasswordResetTokenStore for InMemoryPasswordResetTokenStore {
+    async fn insert(&self, record: &PasswordResetTokenRecord) -> Result<(), UserError> {
+        self.tokens
+            .lock()
+            .insert(record.token_hash.clone(), record.clone());
+        Ok(())
+    }
+
+    async fn take(&self, token_hash: &str) -> Result<Option<PasswordResetTokenRecord>, UserError> {
+        Ok(self.tokens.lock().remove(token_hash))
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, UserError> {
+        let mut tokens = self.tokens.lock();
+        let before = tokens.len();
+        tokens.retain(|_, record| record.user_id != user_id);
+        let removed = before - tokens.len();
+        drop(tokens);
+        Ok(removed as u64)
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, UserError> {
+        let now = Utc::now();
+        let mut tokens = self.tokens.lock();
+        let before = tokens.len();
+        tokens.retain(|_, record| record.expires_at > now);
+        let removed = before - tokens.len();
+        drop(tokens);
+        Ok(removed as u64)
+    }
+}
+
+/// PostgreSQL password reset token storage
+///
+/// Requires the `password_reset_tokens` table from
+/// `migrations/008_create_password_reset_tokens_table.sql`.
+#[cfg(feature = "postgres")]
+#[derive(Debug, Clone)]
+pub struct PostgresPasswordResetTokenStore {
+    pool: PgPool,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresPasswordResetTokenStore {
+    /// Create a store backed by `pool`
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl PasswordResetTokenStore for PostgresPasswordResetTokenStore {
+    async fn insert(&self, record: &PasswordResetTokenRecord) -> Result<(), UserError> {
+        sqlx::query(
+            r"
+            INSERT INTO password_reset_tokens (token_hash, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            ",
+        )
+        .bind(&record.token_hash)
+        .bind(record.user_id)
+        .bind(record.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn take(&self, token_hash: &str) -> Result<Option<PasswordResetTokenRecord>, UserError> {
+        let row: Option<(i64, DateTime<Utc>)> = sqlx::query_as(
+            "DELETE FROM password_reset_tokens WHERE token_hash = $1 RETURNING user_id, expires_at",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(user_id, expires_at)| PasswordResetTokenRecord {
+            token_hash: token_hash.to_string(),
+            user_id,
+            expires_at,
+        }))
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, UserError> {
+        let result = sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, UserError> {
+        let result = sqlx::query("DELETE FROM password_reset_tokens WHERE expires_at <= $1")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// SQLite password reset token storage
+///
+/// Requires the `password_reset_tokens` table from
+/// `migrations/008_create_password_reset_tokens_table.sql`.
+#[cfg(feature = "sqlite")]
+#[derive(Debug, Clone)]
+pub struct SqlitePasswordResetTokenStore {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqlitePasswordResetTokenStore {
+    /// Create a store backed by `pool`
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl PasswordResetTokenStore for SqlitePasswordResetTokenStore {
+    async fn insert(&self, record: &PasswordResetTokenRecord) -> Result<(), UserError> {
+        sqlx::query(
+            "INSERT INTO password_reset_tokens (token_hash, user_id, expires_at) VALUES (?, ?, ?)",
+        )
+        .bind(&record.token_hash)
+        .bind(record.user_id)
+        .bind(record.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn take(&self, token_hash: &str) -> Result<Option<PasswordResetTokenRecord>, UserError> {
+        let row: Option<(i64, DateTime<Utc>)> = sqlx::query_as(
+            "DELETE FROM password_reset_tokens WHERE token_hash = ? RETURNING user_id, expires_at",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(user_id, expires_at)| PasswordResetTokenRecord {
+            token_hash: token_hash.to_string(),
+            user_id,
+            expires_at,
+        }))
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, UserError> {
+        let result = sqlx::query("DELETE FROM password_reset_tokens WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, UserError> {
+        let result = sqlx::query("DELETE FROM password_reset_tokens WHERE expires_at <= ?")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Issues, emails, and redeems password reset tokens
+#[derive(Debug, Clone)]
+pub struct PasswordReset {
+    store: Arc<dyn PasswordResetTokenStore>,
+    config: PasswordResetConfig,
+}
+
+impl PasswordReset {
+    /// Create from a token store and configuration
+    #[must_use]
+    pub fn new(store: Arc<dyn PasswordResetTokenStore>, config: &PasswordResetConfig) -> Self {
+        Self {
+            store,
+            config: config.clone(),
+        }
+    }
+
+    /// Create from the application database and `security.password_reset`
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    #[must_use]
+    pub fn from_state(state: &ActonHtmxState) -> Self {
+        #[cfg(feature = "postgres")]
+        let store = Arc::new(PostgresPasswordResetTokenStore::new(
+            state.database_pool().clone(),
+        ));
+        #[cfg(all(feature = "sqlite", not(feature = "postgres")))]
+        let store = Arc::new(SqlitePasswordResetTokenStore::new(
+            state.database_pool().clone(),
+        ));
+
+        Self::new(store, &state.config().security.password_reset)
+    }
+
+    /// Underlying token store
+    #[must_use]
+    pub fn store(&self) -> &Arc<dyn PasswordResetTokenStore> {
+        &self.store
+    }
+
+    /// Issue a new reset token for `user_id`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the token cannot be stored
+    pub async fn issue(&self, user_id: i64) -> Result<String, UserError> {
+        let token = random_token();
+        let ttl = Duration::seconds(i64::try_from(self.config.token_ttl_secs).unwrap_or(i64::MAX));
+
+        self.store
+            .insert(&PasswordResetTokenRecord {
+                token_hash: hash_validator(&token),
+                user_id,
+                expires_at: Utc::now()
+                    .checked_add_signed(ttl)
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            })
+            .await?;
+
+        Ok(token)
+    }
+
+    /// Redeem `token`, returning the user whose password it resets
+    ///
+    /// Returns `None` for unknown, already used, or expired tokens. The
+    /// token is deleted in every case it can be found, so it is never
+    /// accepted twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store fails
+    pub async fn consume(&self, token: &str) -> Result<Option<i64>, UserError> {
+        let record = self.store.take(&hash_validator(token)).await?;
+        Ok(record
+            .filter(|record| record.expires_at > Utc::now())
+            .map(|record| record.user_id))
+    }
+
+    /// Revoke every outstanding reset token of `user_id`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store fails
+    pub async fn revoke_all(&self, user_id: i64) -> Result<u64, UserError> {
+        self.store.delete_for_user(user_id).await
+    }
+
+    /// Reset link for `token`
+    #[must_use]
+    pub fn link(&self, token: &str) -> String {
+        format!(
+            "{}{RESET_PASSWORD_PATH}?token={token}",
+            self.config.base_url.trim_end_matches('/')
+        )
+    }
+
+    /// Build the reset email carrying `token` to `email`
+    ///
+    /// # Errors
+    ///
+    /// Returns `EmailError::TemplateError` if the template fails to render
+    pub fn email(&self, token: &str, email: &EmailAddress) -> Result<Email, EmailError> {
+        let template = PasswordResetEmailTemplate {
+            link: self.link(token),
+            valid_minutes: self.config.token_ttl_secs.div_ceil(60),
+        };
+
+        Ok(Email::from_template(&template)?
+            .to(email.as_str())
+            .from(&self.config.from)
+            .subject("Reset your password"))
+    }
+
+    /// Queue the reset email carrying `token` to `email`
+    ///
+    /// # Errors
+    ///
+    /// Returns `JobError` if the email cannot be built or queued
+    pub async fn send(
+        &self,
+        state: &ActonHtmxState,
+        token: &str,
+        email: &EmailAddress,
+    ) -> Result<(), JobError> {
+        let message = self
+            .email(token, email)
+            .map_err(|e| JobError::ExecutionFailed(format!("Failed to build password reset email: {e}")))?;
+        state.enqueue(SendEmailJob::new(message)).await?;
+        Ok(())
+    }
+}
+
+/// Password reset email body
+#[derive(Debug, Clone, Template)]
+#[template(
+    source = r#"<p>We received a request to reset your password. Follow this link to choose a new one:</p>
+<p><a href="{{ link }}">Reset password</a></p>
+<p>The link expires in {{ valid_minutes }} minutes and can only be used once. If you didn't ask to reset your password, you can ignore this email.</p>"#,
+    ext = "html"
+)]
+pub struct PasswordResetEmailTemplate {
+    /// Reset link
+    pub link: String,
+    /// Minutes until the link expires
+    pub valid_minutes: u64,
+}
+
+impl SimpleEmailTemplate for PasswordResetEmailTemplate {
+    fn render_text(&self) -> Result<Option<String>, EmailError> {
+        Ok(Some(format!(
+            "We received a request to reset your password. Follow this link to choose a new one:\n\n{}\n\n\
+             The link expires in {} minutes and can only be used once. If you didn't ask to reset \
+             your password, you can ignore this email.\n",
+            self.link, self.valid_minutes
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn password_reset(token_ttl_secs: u64) -> (PasswordReset, Arc<InMemoryPasswordResetTokenStore>) {
+        let store = Arc::new(InMemoryPasswordResetTokenStore::new());
+        let config = PasswordResetConfig {
+            base_url: "https://example.com/".to_string(),
+            token_ttl_secs,
+            ..PasswordResetConfig::default()
+        };
+        (PasswordReset::new(store.clone(), &config), store)
+    }
+
+    #[tokio::test]
+    async fn test_reset_happy_path() {
+        let (reset, store) = password_reset(3600);
+        let token = reset.issue(42).await.unwrap();
+
+        // Only the hash is stored
+        let stored = store.tokens.lock().keys().cloned().collect::<Vec<_>>();
+        assert_eq!(stored, vec![hash_validator(&token)]);
+
+        let email = reset
+            .email(&token, &EmailAddress::parse("ada@example.com").unwrap())
+            .unwrap();
+        let link = format!("https://example.com/password-reset/confirm?token={token}");
+        assert_eq!(email.subject.as_deref(), Some("Reset your password"));
+        assert!(email.html.as_deref().unwrap().contains(&link));
+        assert!(email.text.as_deref().unwrap().contains(&link));
+
+        assert_eq!(reset.consume(&token).await.unwrap(), Some(42));
+        assert!(store.tokens.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reused_token_rejected() {
+        let (reset, _) = password_reset(3600);
+        let token = reset.issue(42).await.unwrap();
+
+        assert_eq!(reset.consume(&token).await.unwrap(), Some(42));
+        assert_eq!(reset.consume(&token).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_rejected_and_deleted() {
+        let (reset, store) = password_reset(0);
+        let token = reset.issue(42).await.unwrap();
+
+        assert_eq!(reset.consume(&token).await.unwrap(), None);
+        assert!(store.tokens.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_token_rejected() {
+        let (reset, _) = password_reset(3600);
+        reset.issue(42).await.unwrap();
+
+        assert_eq!(reset.consume("not-a-token").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_revoke_all() {
+        let (reset, _) = password_reset(3600);
+        let first = reset.issue(42).await.unwrap();
+        let second = reset.issue(42).await.unwrap();
+        let other = reset.issue(7).await.unwrap();
+
+        assert_eq!(reset.revoke_all(42).await.unwrap(), 2);
+        assert_eq!(reset.consume(&first).await.unwrap(), None);
+        assert_eq!(reset.consume(&second).await.unwrap(), None);
+        assert_eq!(reset.consume(&other).await.unwrap(), Some(7));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_store() {
+        let pool = crate::htmx::testing::create_sqlite_pool_with_migration(include_str!(
+            "../../../../migrations/008_create_password_reset_tokens_table.sql"
+        ))
+        .await
+        .unwrap();
+        let reset = PasswordReset::new(
+            Arc::new(SqlitePasswordResetTokenStore::new(pool)),
+            &PasswordResetConfig::default(),
+        );
+
+        let token = reset.issue(42).await.unwrap();
+        assert_eq!(reset.consume(&token).await.unwrap(), Some(42));
+        assert_eq!(reset.consume(&token).await.unwrap(), None);
+
+        let expired = reset.store().as_ref();
+        expired
+            .insert(&PasswordResetTokenRecord {
+                token_hash: hash_validator("expired"),
+                user_id: 42,
+                expires_at: Utc::now() - Duration::hours(1),
+            })
+            .await
+            .unwrap();
+        assert_eq!(expired.cleanup_expired().await.unwrap(), 1);
+    }
+}
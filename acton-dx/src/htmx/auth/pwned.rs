@@ -0,0 +1,247 @@
+//! Breached password detection via the Have I Been Pwned range API
+//!
+//! Uses k-anonymity: only the first 5 hex characters of the password's SHA-1
+//! hash leave the server. The API answers with every known breached hash
+//! suffix sharing that prefix, and the match is done locally.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_htmx::auth::pwned::PwnedPasswordChecker;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let checker = PwnedPasswordChecker::new();
+//!
+//! if checker.is_pwned("P@ssw0rd").await? {
+//!     println!("This password has appeared in a data breach");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use super::password::PasswordError;
+
+/// Default Have I Been Pwned password range API
+pub const DEFAULT_PWNED_API_URL: &str = "https://api.pwnedpasswords.com";
+
+/// Configuration for breached password checks
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [security.pwned_passwords]
+/// enabled = true
+/// fail_open = false
+/// timeout_ms = 3000
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PwnedPasswordConfig {
+    /// Reject breached passwords at registration (default: false)
+    pub enabled: bool,
+
+    /// Accept the password when the API cannot be reached (default: true)
+    ///
+    /// When false, registration fails while the API is unavailable.
+    pub fail_open: bool,
+
+    /// Base URL of the range API (default: `https://api.pwnedpasswords.com`)
+    pub api_url: String,
+
+    /// Request timeout in milliseconds (default: 5000)
+    pub timeout_ms: u64,
+}
+
+impl Default for PwnedPasswordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            fail_open: true,
+            api_url: DEFAULT_PWNED_API_URL.to_string(),
+            timeout_ms: 5000,
+        }
+    }
+}
+
+/// Checks passwords against the Have I Been Pwned breach corpus
+#[derive(Debug, Clone)]
+pub struct PwnedPasswordChecker {
+    client: reqwest::Client,
+    api_url: String,
+    fail_open: bool,
+}
+
+impl Default for PwnedPasswordChecker {
+    fn default() -> Self {
+        Self::from_config(&PwnedPasswordConfig::default())
+    }
+}
+
+impl PwnedPasswordChecker {
+    /// Create a checker for the public API that fails open
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a checker from configuration
+    #[must_use]
+    pub fn from_config(config: &PwnedPasswordConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            api_url: config.api_url.trim_end_matches('/').to_string(),
+            fail_open: config.fail_open,
+        }
+    }
+
+    /// Set whether API failures accept (`true`) or reject (`false`) the password
+    #[must_use]
+    pub const fn fail_open(mut self, fail_open: bool) -> Self {
+        self.fail_open = fail_open;
+        self
+    }
+
+    /// Check whether a password appears in a known data breach
+    ///
+    /// Only the first 5 characters of the password's SHA-1 hash are sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PasswordError::PwnedCheckFailed` if the API cannot be queried
+    /// and the checker fails closed. A failing-open checker returns
+    /// `Ok(false)` instead.
+    pub async fn is_pwned(&self, password: &str) -> Result<bool, PasswordError> {
+        let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+        let (prefix, suffix) = hash.split_at(5);
+
+        match self.fetch_range(prefix).await {
+            Ok(range) => Ok(range_contains(&range, suffix)),
+            Err(e) if self.fail_open => {
+                tracing::warn!(error = %e, "Pwned password check failed, accepting password");
+                Ok(false)
+            }
+            Err(e) => Err(PasswordError::PwnedCheckFailed(e)),
+        }
+    }
+
+    /// Fetch all breached hash suffixes for a prefix
+    async fn fetch_range(&self, prefix: &str) -> Result<String, String> {
+        let response = self
+            .client
+            .get(format!("{}/range/{prefix}", self.api_url))
+            // Pad responses so their size does not reveal the prefix
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("range API responded with {status}"));
+        }
+
+        response.text().await.map_err(|e| e.to_string())
+    }
+}
+
+/// Whether a range response lists `suffix` with a non-zero breach count
+///
+/// Padding entries have a count of 0 and never match.
+fn range_contains(range: &str, suffix: &str) -> bool {
+    range.lines().any(|line| {
+        line.trim().split_once(':').is_some_and(|(candidate, count)| {
+            candidate.eq_ignore_ascii_case(suffix) && count.trim() != "0"
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Path, routing::get, Router};
+
+    /// SHA-1 of "password" is 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+    const PASSWORD_RANGE: &str = "\
+        1E2AAA439972480CEC7F16C795BBB429372:1\r\n\
+        1E4C9B93F3F0682250B6CF8331B7EE68FD8:10434004\r\n\
+        1F2B668E8AABEF1C59E9EC6F82E3F3CD786:0\r\n";
+
+    /// Serve `PASSWORD_RANGE` for the "password" prefix and nothing otherwise
+    async fn spawn_range_api() -> String {
+        async fn range(Path(prefix): Path<String>) -> &'static str {
+            if prefix == "5BAA6" {
+                PASSWORD_RANGE
+            } else {
+                ""
+            }
+        }
+
+        let app = Router::new().route("/range/{prefix}", get(range));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{addr}")
+    }
+
+    /// URL of a port with nothing listening on it
+    async fn unreachable_api() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        format!("http://{addr}")
+    }
+
+    fn checker(api_url: String, fail_open: bool) -> PwnedPasswordChecker {
+        PwnedPasswordChecker::from_config(&PwnedPasswordConfig {
+            enabled: true,
+            fail_open,
+            api_url,
+            timeout_ms: 1000,
+        })
+    }
+
+    #[test]
+    fn test_range_contains() {
+        assert!(range_contains(PASSWORD_RANGE, "1E4C9B93F3F0682250B6CF8331B7EE68FD8"));
+        assert!(range_contains(PASSWORD_RANGE, "1e4c9b93f3f0682250b6cf8331b7ee68fd8"));
+        // Padding entry
+        assert!(!range_contains(PASSWORD_RANGE, "1F2B668E8AABEF1C59E9EC6F82E3F3CD786"));
+        assert!(!range_contains(PASSWORD_RANGE, "0000000000000000000000000000000000"));
+    }
+
+    #[tokio::test]
+    async fn test_is_pwned_with_mocked_range() {
+        let checker = checker(spawn_range_api().await, false);
+
+        assert!(checker.is_pwned("password").await.unwrap());
+        assert!(!checker.is_pwned("a-much-less-common-Passw0rd!").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_network_failure_fail_open() {
+        let checker = checker(unreachable_api().await, true);
+
+        assert!(!checker.is_pwned("password").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_network_failure_fail_closed() {
+        let checker = checker(unreachable_api().await, false);
+
+        assert!(matches!(
+            checker.is_pwned("password").await,
+            Err(PasswordError::PwnedCheckFailed(_))
+        ));
+    }
+}
@@ -0,0 +1,376 @@
+//! Authentication for long-lived real-time connections
+//!
+//! Server-Sent Events streams and WebSocket connections authenticate once, on
+//! the upgrade request, and then stay open for minutes or hours. The
+//! [`RealtimeAuth`] extractor runs the regular [`Authenticated`] extractor
+//! against the upgrade request and keeps a [`SessionWatch`] for the life of
+//! the connection, so the stream can be closed as soon as the session expires
+//! or is deleted (e.g. on logout).
+//!
+//! Unauthenticated upgrades are rejected with `401 Unauthorized` before the
+//! connection is established. Unlike [`AuthenticationError`], the rejection
+//! never redirects: `EventSource` and WebSocket clients cannot follow one.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::{RealtimeAuth, User};
+//! use axum::response::sse::{Event, Sse};
+//! use futures_util::stream::Stream;
+//!
+//! async fn notifications(
+//!     auth: RealtimeAuth<User>,
+//! ) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+//!     let user_id = auth.user().id;
+//!     let events = notification_stream(user_id);
+//!
+//!     // The stream ends when the session expires or is deleted
+//!     Sse::new(auth.guard(events))
+//! }
+//! ```
+//!
+//! WebSocket handlers select on [`SessionWatch::expired`] and close the
+//! socket with [`WS_CLOSE_POLICY_VIOLATION`].
+
+use crate::htmx::agents::LoadSession;
+use crate::htmx::auth::session::{SessionData, SessionId};
+use crate::htmx::auth::{Authenticated, AuthenticationError};
+use crate::htmx::state::ActonHtmxState;
+use acton_reactive::prelude::{AgentHandle, AgentHandleInterface};
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use futures_util::stream::{Stream, StreamExt};
+use std::time::Duration;
+
+/// WebSocket close code for connections whose session ended (RFC 6455)
+pub const WS_CLOSE_POLICY_VIOLATION: u16 = 1008;
+
+/// Default interval between session checks
+const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Timeout for a single session lookup
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Rejection for unauthenticated real-time upgrade requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum RealtimeAuthError {
+    /// No session on the upgrade request
+    #[error("No session on real-time connection request")]
+    MissingSession,
+
+    /// Session exists but no user is signed in
+    #[error("Real-time connection requires authentication")]
+    NotAuthenticated,
+
+    /// The user could not be loaded
+    #[error("Failed to authenticate real-time connection")]
+    Internal,
+}
+
+impl From<AuthenticationError> for RealtimeAuthError {
+    fn from(error: AuthenticationError) -> Self {
+        match error {
+            AuthenticationError::MissingSession | AuthenticationError::MissingSessionHtmx => {
+                Self::MissingSession
+            }
+            AuthenticationError::NotAuthenticated
+            | AuthenticationError::NotAuthenticatedHtmx => Self::NotAuthenticated,
+            AuthenticationError::DatabaseNotConfigured | AuthenticationError::DatabaseError(_) => {
+                Self::Internal
+            }
+        }
+    }
+}
+
+impl IntoResponse for RealtimeAuthError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::MissingSession | Self::NotAuthenticated => {
+                (StatusCode::UNAUTHORIZED, "Unauthorized").into_response()
+            }
+            Self::Internal => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load user").into_response()
+            }
+        }
+    }
+}
+
+/// Tracks whether the session behind a connection is still valid
+///
+/// Cheap to clone; every clone watches the same session.
+#[derive(Clone)]
+pub struct SessionWatch {
+    session_id: SessionId,
+    expires_at: DateTime<Utc>,
+    session_manager: AgentHandle,
+    check_interval: Duration,
+}
+
+impl std::fmt::Debug for SessionWatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionWatch")
+            .field("session_id", &self.session_id)
+            .field("expires_at", &self.expires_at)
+            .field("check_interval", &self.check_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SessionWatch {
+    /// Watch a session known to expire at `expires_at`
+    #[must_use]
+    pub const fn new(
+        session_id: SessionId,
+        expires_at: DateTime<Utc>,
+        session_manager: AgentHandle,
+    ) -> Self {
+        Self {
+            session_id,
+            expires_at,
+            session_manager,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+
+    /// Set how often the session store is re-checked (default: 30 seconds)
+    ///
+    /// Re-checks catch sessions deleted before their expiry, such as on
+    /// logout.
+    #[must_use]
+    pub const fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// The watched session
+    #[must_use]
+    pub const fn session_id(&self) -> &SessionId {
+        &self.session_id
+    }
+
+    /// Resolve once the session has expired or been deleted
+    ///
+    /// Sleeps until the next check (or the known expiry, if sooner), then
+    /// reloads the session. Activity on other requests may extend the
+    /// session, in which case watching continues. A lookup that times out
+    /// keeps the connection open until the next check.
+    pub async fn expired(&self) {
+        let mut expires_at = self.expires_at;
+
+        loop {
+            let until_expiry = (expires_at - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(until_expiry.min(self.check_interval)).await;
+
+            let (request, rx) = LoadSession::with_response(self.session_id.clone());
+            self.session_manager.send(request).await;
+
+            match tokio::time::timeout(LOOKUP_TIMEOUT, rx).await {
+                Ok(Ok(Some(data))) if !data.is_expired() => expires_at = data.expires_at,
+                Ok(Ok(_) | Err(_)) => {
+                    tracing::debug!(session_id = %self.session_id.as_str(), "Real-time session ended");
+                    return;
+                }
+                Err(_) => {
+                    tracing::warn!(session_id = %self.session_id.as_str(), "Session check timed out");
+                }
+            }
+        }
+    }
+
+    /// End `stream` once the session has expired or been deleted
+    pub fn guard<St: Stream>(&self, stream: St) -> impl Stream<Item = St::Item> {
+        let watch = self.clone();
+        stream.take_until(async move { watch.expired().await })
+    }
+}
+
+/// Authenticated user of a real-time connection
+///
+/// Extract in SSE and WebSocket upgrade handlers. Runs [`Authenticated<T>`]
+/// on the upgrade request and keeps a [`SessionWatch`] for the connection.
+#[derive(Debug, Clone)]
+pub struct RealtimeAuth<T> {
+    user: T,
+    watch: SessionWatch,
+}
+
+impl<T> RealtimeAuth<T> {
+    /// The authenticated user
+    pub const fn user(&self) -> &T {
+        &self.user
+    }
+
+    /// Take the authenticated user, dropping the session watch
+    pub fn into_user(self) -> T {
+        self.user
+    }
+
+    /// The session watch for this connection
+    pub const fn watch(&self) -> &SessionWatch {
+        &self.watch
+    }
+
+    /// Split into the user and the session watch
+    pub fn into_parts(self) -> (T, SessionWatch) {
+        (self.user, self.watch)
+    }
+
+    /// End `stream` once the session has expired or been deleted
+    pub fn guard<St: Stream>(&self, stream: St) -> impl Stream<Item = St::Item> {
+        self.watch.guard(stream)
+    }
+}
+
+impl<S, T> FromRequestParts<S> for RealtimeAuth<T>
+where
+    S: Send + Sync,
+    T: Send,
+    Authenticated<T>: FromRequestParts<S, Rejection = AuthenticationError>,
+    ActonHtmxState: FromRef<S>,
+{
+    type Rejection = RealtimeAuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Authenticated(user) = Authenticated::<T>::from_request_parts(parts, state).await?;
+
+        let session_id = parts
+            .extensions
+            .get::<SessionId>()
+            .cloned()
+            .ok_or(RealtimeAuthError::MissingSession)?;
+        let expires_at = parts
+            .extensions
+            .get::<SessionData>()
+            .map_or_else(Utc::now, |data| data.expires_at);
+
+        let app_state = ActonHtmxState::from_ref(state);
+        let watch = SessionWatch::new(
+            session_id,
+            expires_at,
+            app_state.session_manager().clone(),
+        );
+
+        Ok(Self { user, watch })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::agents::{DeleteSession, SaveSession, SessionManagerAgent};
+    use crate::htmx::auth::Session;
+    use acton_reactive::prelude::ActonApp;
+    use axum::{body::Body, http::Request};
+
+    #[derive(Debug)]
+    struct TestUser(i64);
+
+    impl FromRequestParts<ActonHtmxState> for Authenticated<TestUser> {
+        type Rejection = AuthenticationError;
+
+        async fn from_request_parts(
+            parts: &mut Parts,
+            _state: &ActonHtmxState,
+        ) -> Result<Self, Self::Rejection> {
+            let session = parts
+                .extensions
+                .get::<Session>()
+                .ok_or(AuthenticationError::MissingSession)?;
+            let user_id = session
+                .user_id()
+                .ok_or(AuthenticationError::NotAuthenticated)?;
+            Ok(Self(TestUser(user_id)))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unauthenticated_upgrade_is_rejected() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+
+        let (mut parts, ()) = Request::builder().uri("/events").body(()).unwrap().into_parts();
+        let error = RealtimeAuth::<TestUser>::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(error, RealtimeAuthError::MissingSession);
+
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().get("HX-Redirect").is_none());
+
+        let mut data = SessionData::new();
+        data.user_id = None;
+        let id = SessionId::generate();
+        let (mut parts, _) = Request::builder().body(Body::empty()).unwrap().into_parts();
+        parts.extensions.insert(Session::new(id, data));
+        let error = RealtimeAuth::<TestUser>::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap_err();
+        assert_eq!(error, RealtimeAuthError::NotAuthenticated);
+
+        runtime.shutdown_all().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stream_ends_when_session_deleted() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+        let session_manager = state.session_manager().clone();
+
+        let id = SessionId::generate();
+        let mut data = SessionData::new();
+        data.user_id = Some(42);
+        session_manager
+            .send(SaveSession::new(id.clone(), data.clone()))
+            .await;
+
+        let (mut parts, ()) = Request::builder().body(()).unwrap().into_parts();
+        parts.extensions.insert(Session::new(id.clone(), data.clone()));
+        parts.extensions.insert(id.clone());
+        parts.extensions.insert(data);
+
+        let auth = RealtimeAuth::<TestUser>::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap();
+        assert_eq!(auth.user().0, 42);
+
+        let watch = auth.watch().clone().with_check_interval(Duration::from_millis(20));
+        let mut stream = Box::pin(watch.guard(futures_util::stream::repeat(()).then(
+            |()| async { tokio::time::sleep(Duration::from_millis(5)).await },
+        )));
+
+        // Session is valid: the stream keeps producing
+        for _ in 0..10 {
+            assert!(stream.next().await.is_some());
+        }
+
+        session_manager.send(DeleteSession { session_id: id }).await;
+        let ended = tokio::time::timeout(Duration::from_secs(2), async {
+            while stream.next().await.is_some() {}
+        })
+        .await;
+        assert!(ended.is_ok(), "stream should end after the session is deleted");
+
+        runtime.shutdown_all().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_watch_resolves_for_expired_session() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+
+        let watch = SessionWatch::new(
+            SessionId::generate(),
+            Utc::now() - chrono::Duration::seconds(1),
+            session_manager,
+        );
+        let expired = tokio::time::timeout(Duration::from_secs(2), watch.expired()).await;
+        assert!(expired.is_ok());
+
+        runtime.shutdown_all().await.unwrap();
+    }
+}
@@ -0,0 +1,666 @@
+//! Persistent "remember me" logins
+//!
+//! A remember-me token is a selector/validator pair. The selector looks the
+//! token up; only a SHA-256 hash of the validator is stored, and the pair is
+//! sent to the browser in a long-lived cookie signed with a [`CookieSigner`].
+//!
+//! When [`SessionLayer::with_remember_me`] is configured and a request arrives
+//! without a live session, a valid remember cookie transparently logs the
+//! user back in. Each automatic login consumes the token and issues a new one,
+//! so a stolen cookie stops working as soon as either party uses it. A valid
+//! selector presented with the wrong validator is treated as theft and every
+//! token of that user is revoked.
+//!
+//! Handlers request a token with the [`RememberLogin`] response extension and
+//! revoke it with [`ForgetLogin`]; `login_post` and `logout_post` do this.
+//!
+//! The SQL stores expect the table from
+//! `migrations/006_create_remember_tokens_table.sql`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::remember::{PostgresRememberTokenStore, RememberMe};
+//! use acton_dx::htmx::middleware::SessionLayer;
+//! use std::sync::Arc;
+//!
+//! let remember_me = RememberMe::from_signer(
+//!     Arc::new(PostgresRememberTokenStore::new(pool.clone())),
+//!     state.cookie_signer(),
+//! );
+//! let app = app.layer(SessionLayer::new(&state).with_remember_me(remember_me));
+//! ```
+//!
+//! [`SessionLayer::with_remember_me`]: crate::htmx::middleware::SessionLayer::with_remember_me
+
+use crate::htmx::auth::session::SessionError;
+use crate::htmx::middleware::CookieSigner;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+#[cfg(feature = "postgres")]
+use sqlx::PgPool;
+
+#[cfg(feature = "sqlite")]
+use sqlx::SqlitePool;
+
+/// Remember-me cookie name
+pub const REMEMBER_COOKIE_NAME: &str = "acton_remember";
+
+/// Default remember-me token lifetime (30 days)
+pub const DEFAULT_REMEMBER_MAX_AGE_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// [`CookieSigner::for_purpose`] key of the remember-me cookie
+const SIGNING_PURPOSE: &str = "remember-me";
+
+/// Response extension asking the session middleware to issue a remember-me token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RememberLogin {
+    /// User the token logs back in
+    pub user_id: i64,
+}
+
+/// Response extension asking the session middleware to revoke the remember-me token
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForgetLogin;
+
+/// Response extension asking the session middleware to revoke every
+/// remember-me token of a user, signing out all remembered devices
+///
+/// Also clears the request's remember-me cookie, like [`ForgetLogin`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForgetAllLogins {
+    /// User whose tokens to revoke
+    pub user_id: i64,
+}
+
+/// A stored remember-me token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RememberTokenRecord {
+    /// Public lookup key
+    pub selector: String,
+    /// Hex-encoded SHA-256 hash of the secret validator
+    pub validator_hash: String,
+    /// User the token logs back in
+    pub user_id: i64,
+    /// When the token stops being accepted
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Persistence backend for remember-me tokens
+#[async_trait]
+pub trait RememberTokenStore: Send + Sync + Debug {
+    /// Store a new token
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn insert(&self, record: &RememberTokenRecord) -> Result<(), SessionError>;
+
+    /// Find a token by selector
+    ///
+    /// Returns the token even if it has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn find(&self, selector: &str) -> Result<Option<RememberTokenRecord>, SessionError>;
+
+    /// Delete a token (no-op if it does not exist)
+    ///
+    /// Returns whether a token was deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn delete(&self, selector: &str) -> Result<bool, SessionError>;
+
+    /// Delete every token belonging to a user
+    ///
+    /// Returns the number of tokens removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError>;
+
+    /// Delete all expired tokens
+    ///
+    /// Returns the number of tokens removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the backend fails
+    async fn cleanup_expired(&self) -> Result<u64, SessionError>;
+}
+
+/// Process-local remember-me token storage
+///
+/// Tokens are lost on restart, which defeats the purpose outside of tests
+/// and single-process development.
+#[derive(Debug, Default)]
+pub struct InMemoryRememberTokenStore {
+    tokens: Mutex<HashMap<String, RememberTokenRecord>>,
+}
+
+impl InMemoryRememberTokenStore {
+    /// Create an empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RememberTokenStore for InMemoryRememberTokenStore {
+    async fn insert(&self, record: &RememberTokenRecord) -> Result<(), SessionError> {
+        self.tokens
+            .lock()
+            .insert(record.selector.clone(), record.clone());
+        Ok(())
+    }
+
+    async fn find(&self, selector: &str) -> Result<Option<RememberTokenRecord>, SessionError> {
+        Ok(self.tokens.lock().get(selector).cloned())
+    }
+
+    async fn delete(&self, selector: &str) -> Result<bool, SessionError> {
+        Ok(self.tokens.lock().remove(selector).is_some())
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError> {
+        let mut tokens = self.tokens.lock();
+        let before = tokens.len();
+        tokens.retain(|_, record| record.user_id != user_id);
+        let removed = before - tokens.len();
+        drop(tokens);
+        Ok(removed as u64)
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, SessionError> {
+        let now = Utc::now();
+        let mut tokens = self.tokens.lock();
+        let before = tokens.len();
+        tokens.retain(|_, record| record.expires_at > now);
+        let removed = before - tokens.len();
+        drop(tokens);
+        Ok(removed as u64)
+    }
+}
+
+/// PostgreSQL remember-me token storage
+///
+/// Requires the `remember_tokens` table from
+/// `migrations/006_create_remember_tokens_table.sql`.
+#[cfg(feature = "postgres")]
+#[derive(Debug, Clone)]
+pub struct PostgresRememberTokenStore {
+    pool: PgPool,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresRememberTokenStore {
+    /// Create a store backed by `pool`
+    #[must_use]
+    pub const fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl RememberTokenStore for PostgresRememberTokenStore {
+    async fn insert(&self, record: &RememberTokenRecord) -> Result<(), SessionError> {
+        sqlx::query(
+            r"
+            INSERT INTO remember_tokens (selector, validator_hash, user_id, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ",
+        )
+        .bind(&record.selector)
+        .bind(&record.validator_hash)
+        .bind(record.user_id)
+        .bind(record.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find(&self, selector: &str) -> Result<Option<RememberTokenRecord>, SessionError> {
+        let row: Option<(String, i64, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT validator_hash, user_id, expires_at FROM remember_tokens WHERE selector = $1",
+        )
+        .bind(selector)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(validator_hash, user_id, expires_at)| RememberTokenRecord {
+            selector: selector.to_string(),
+            validator_hash,
+            user_id,
+            expires_at,
+        }))
+    }
+
+    async fn delete(&self, selector: &str) -> Result<bool, SessionError> {
+        let result = sqlx::query("DELETE FROM remember_tokens WHERE selector = $1")
+            .bind(selector)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError> {
+        let result = sqlx::query("DELETE FROM remember_tokens WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, SessionError> {
+        let result = sqlx::query("DELETE FROM remember_tokens WHERE expires_at <= $1")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// SQLite remember-me token storage
+///
+/// Requires the `remember_tokens` table from
+/// `migrations/006_create_remember_tokens_table.sql`.
+#[cfg(feature = "sqlite")]
+#[derive(Debug, Clone)]
+pub struct SqliteRememberTokenStore {
+    pool: SqlitePool,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteRememberTokenStore {
+    /// Create a store backed by `pool`
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl RememberTokenStore for SqliteRememberTokenStore {
+    async fn insert(&self, record: &RememberTokenRecord) -> Result<(), SessionError> {
+        sqlx::query(
+            r"INSERT INTO remember_tokens (selector, validator_hash, user_id, expires_at)
+              VALUES (?, ?, ?, ?)",
+        )
+        .bind(&record.selector)
+        .bind(&record.validator_hash)
+        .bind(record.user_id)
+        .bind(record.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find(&self, selector: &str) -> Result<Option<RememberTokenRecord>, SessionError> {
+        let row: Option<(String, i64, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT validator_hash, user_id, expires_at FROM remember_tokens WHERE selector = ?",
+        )
+        .bind(selector)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|(validator_hash, user_id, expires_at)| RememberTokenRecord {
+            selector: selector.to_string(),
+            validator_hash,
+            user_id,
+            expires_at,
+        }))
+    }
+
+    async fn delete(&self, selector: &str) -> Result<bool, SessionError> {
+        let result = sqlx::query("DELETE FROM remember_tokens WHERE selector = ?")
+            .bind(selector)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_for_user(&self, user_id: i64) -> Result<u64, SessionError> {
+        let result = sqlx::query("DELETE FROM remember_tokens WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn cleanup_expired(&self) -> Result<u64, SessionError> {
+        let result = sqlx::query("DELETE FROM remember_tokens WHERE expires_at <= ?")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Issues, rotates, and revokes remember-me tokens
+///
+/// Cookie values have the form `selector.validator.timestamp.signature`.
+#[derive(Debug, Clone)]
+pub struct RememberMe {
+    store: Arc<dyn RememberTokenStore>,
+    signer: CookieSigner,
+    cookie_name: String,
+    max_age_secs: u64,
+}
+
+impl RememberMe {
+    /// Create a remember-me service signing cookies with `secret_key`
+    ///
+    /// Use `security.secret_key` so cookies survive restarts and are accepted
+    /// by every instance.
+    #[must_use]
+    pub fn new(store: Arc<dyn RememberTokenStore>, secret_key: impl AsRef<[u8]>) -> Self {
+        Self::from_signer(store, &CookieSigner::new(secret_key))
+    }
+
+    /// Create a remember-me service signing cookies with `signer`
+    ///
+    /// Pass the application's cookie signer so cookies survive restarts and
+    /// are accepted by every instance.
+    #[must_use]
+    pub fn from_signer(store: Arc<dyn RememberTokenStore>, signer: &CookieSigner) -> Self {
+        Self {
+            store,
+            signer: signer.for_purpose(SIGNING_PURPOSE),
+            cookie_name: REMEMBER_COOKIE_NAME.to_string(),
+            max_age_secs: DEFAULT_REMEMBER_MAX_AGE_SECS,
+        }
+    }
+
+    /// Set the cookie name (default: `acton_remember`)
+    #[must_use]
+    pub fn with_cookie_name(mut self, cookie_name: impl Into<String>) -> Self {
+        self.cookie_name = cookie_name.into();
+        self
+    }
+
+    /// Set the token lifetime in seconds (default: 30 days)
+    #[must_use]
+    pub const fn with_max_age_secs(mut self, max_age_secs: u64) -> Self {
+        self.max_age_secs = max_age_secs;
+        self
+    }
+
+    /// Cookie name
+    #[must_use]
+    pub fn cookie_name(&self) -> &str {
+        &self.cookie_name
+    }
+
+    /// Token lifetime in seconds
+    #[must_use]
+    pub const fn max_age_secs(&self) -> u64 {
+        self.max_age_secs
+    }
+
+    /// Underlying token store
+    #[must_use]
+    pub fn store(&self) -> &Arc<dyn RememberTokenStore> {
+        &self.store
+    }
+
+    /// Issue a new token for `user_id`, returning the cookie value
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the token cannot be stored
+    pub async fn issue(&self, user_id: i64) -> Result<String, SessionError> {
+        let selector = random_token();
+        let validator = random_token();
+        let max_age = Duration::seconds(i64::try_from(self.max_age_secs).unwrap_or(i64::MAX));
+
+        self.store
+            .insert(&RememberTokenRecord {
+                selector: selector.clone(),
+                validator_hash: hash_validator(&validator),
+                user_id,
+                expires_at: Utc::now()
+                    .checked_add_signed(max_age)
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            })
+            .await?;
+
+        Ok(self.sign(&selector, &validator))
+    }
+
+    /// Consume a cookie value and rotate it
+    ///
+    /// Returns the user ID and the replacement cookie value, or `None` if the
+    /// cookie is forged, unknown, or expired. The presented token is deleted
+    /// in every case it can be found, so it is never accepted twice.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store fails
+    pub async fn rotate(&self, cookie_value: &str) -> Result<Option<(i64, String)>, SessionError> {
+        let Some((selector, validator)) = self.verify(cookie_value) else {
+            return Ok(None);
+        };
+        let Some(record) = self.store.find(&selector).await? else {
+            return Ok(None);
+        };
+
+        if !self
+            .signer
+            .constant_time_eq(&hash_validator(&validator), &record.validator_hash)
+        {
+            // Valid selector, wrong validator: the cookie was copied and used
+            tracing::warn!(
+                user_id = record.user_id,
+                "Remember-me validator mismatch, revoking all of the user's tokens"
+            );
+            self.store.delete_for_user(record.user_id).await?;
+            return Ok(None);
+        }
+
+        // Only the request that actually deletes the token may use it
+        if !self.store.delete(&selector).await? || record.expires_at <= Utc::now() {
+            return Ok(None);
+        }
+
+        let cookie_value = self.issue(record.user_id).await?;
+        Ok(Some((record.user_id, cookie_value)))
+    }
+
+    /// Revoke the token behind a cookie value
+    ///
+    /// Forged or unknown cookies are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store fails
+    pub async fn revoke(&self, cookie_value: &str) -> Result<(), SessionError> {
+        if let Some((selector, _)) = self.verify(cookie_value) {
+            self.store.delete(&selector).await?;
+        }
+        Ok(())
+    }
+
+    /// Revoke every token belonging to `user_id`
+    ///
+    /// Use after a password change to sign out remembered devices.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the store fails
+    pub async fn revoke_all(&self, user_id: i64) -> Result<u64, SessionError> {
+        self.store.delete_for_user(user_id).await
+    }
+
+    /// Sign a selector/validator pair as a cookie value
+    fn sign(&self, selector: &str, validator: &str) -> String {
+        self.signer.sign(&format!("{selector}.{validator}"))
+    }
+
+    /// Split a cookie value into selector and validator if its signature is valid
+    fn verify(&self, cookie_value: &str) -> Option<(String, String)> {
+        let payload = self.signer.verify(cookie_value)?;
+        let (selector, validator) = payload.split_once('.')?;
+        Some((selector.to_string(), validator.to_string()))
+    }
+}
+
+/// 32 random bytes, base64url-encoded
+pub(crate) fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Hex-encoded SHA-256 hash of a validator
+pub(crate) fn hash_validator(validator: &str) -> String {
+    hex::encode(Sha256::digest(validator.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remember_me() -> (RememberMe, Arc<InMemoryRememberTokenStore>) {
+        let store = Arc::new(InMemoryRememberTokenStore::new());
+        (RememberMe::new(store.clone(), b"test-secret"), store)
+    }
+
+    #[tokio::test]
+    async fn test_rotation_consumes_token() {
+        let (remember_me, _) = remember_me();
+        let cookie = remember_me.issue(42).await.unwrap();
+
+        let (user_id, rotated) = remember_me.rotate(&cookie).await.unwrap().unwrap();
+        assert_eq!(user_id, 42);
+        assert_ne!(rotated, cookie);
+
+        // The original token is single-use
+        assert!(remember_me.rotate(&cookie).await.unwrap().is_none());
+
+        let (user_id, _) = remember_me.rotate(&rotated).await.unwrap().unwrap();
+        assert_eq!(user_id, 42);
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_rejected_and_deleted() {
+        let (remember_me, store) = remember_me();
+        let remember_me = remember_me.with_max_age_secs(0);
+        let cookie = remember_me.issue(42).await.unwrap();
+
+        assert!(remember_me.rotate(&cookie).await.unwrap().is_none());
+        assert_eq!(store.tokens.lock().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_revoke() {
+        let (remember_me, _) = remember_me();
+        let cookie = remember_me.issue(42).await.unwrap();
+
+        remember_me.revoke(&cookie).await.unwrap();
+        assert!(remember_me.rotate(&cookie).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_forged_signature_rejected() {
+        let (remember_me, _) = remember_me();
+        let cookie = remember_me.issue(42).await.unwrap();
+        let (payload, _) = cookie.rsplit_once('.').unwrap();
+        let forged = format!("{payload}.{}", URL_SAFE_NO_PAD.encode([0u8; 32]));
+
+        assert!(remember_me.rotate(&forged).await.unwrap().is_none());
+        assert!(remember_me.rotate(&cookie).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cookie_signed_for_another_purpose_rejected() {
+        let (remember_me, _) = remember_me();
+        let cookie = remember_me.issue(42).await.unwrap();
+        let (selector, validator) = remember_me.verify(&cookie).unwrap();
+
+        let session = CookieSigner::new(b"test-secret").for_purpose("session");
+        let forged = session.sign(&format!("{selector}.{validator}"));
+        assert!(remember_me.rotate(&forged).await.unwrap().is_none());
+        assert!(remember_me.rotate(&cookie).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_validator_mismatch_revokes_all_user_tokens() {
+        let (remember_me, store) = remember_me();
+        let cookie = remember_me.issue(42).await.unwrap();
+        let other_device = remember_me.issue(42).await.unwrap();
+        let unrelated = remember_me.issue(7).await.unwrap();
+
+        // Correctly signed, but with a validator that does not match
+        let (selector, _) = remember_me.verify(&cookie).unwrap();
+        let tampered = remember_me.sign(&selector, "guessed-validator");
+
+        assert!(remember_me.rotate(&tampered).await.unwrap().is_none());
+        assert!(remember_me.rotate(&other_device).await.unwrap().is_none());
+        assert!(remember_me.rotate(&unrelated).await.unwrap().is_some());
+        assert_eq!(store.tokens.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_cleanup_expired() {
+        let store = InMemoryRememberTokenStore::new();
+        for (selector, offset) in [("old", -1), ("new", 1)] {
+            store
+                .insert(&RememberTokenRecord {
+                    selector: selector.to_string(),
+                    validator_hash: String::new(),
+                    user_id: 1,
+                    expires_at: Utc::now() + Duration::hours(offset),
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(store.cleanup_expired().await.unwrap(), 1);
+        assert!(store.find("new").await.unwrap().is_some());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_rotation_and_revocation() {
+        let pool = crate::htmx::testing::create_sqlite_pool_with_migration(include_str!(
+            "../../../../migrations/006_create_remember_tokens_table.sql"
+        ))
+        .await
+        .unwrap();
+        let remember_me = RememberMe::new(
+            Arc::new(SqliteRememberTokenStore::new(pool)),
+            b"test-secret",
+        );
+
+        let cookie = remember_me.issue(42).await.unwrap();
+        let (user_id, rotated) = remember_me.rotate(&cookie).await.unwrap().unwrap();
+        assert_eq!(user_id, 42);
+        assert!(remember_me.rotate(&cookie).await.unwrap().is_none());
+
+        remember_me.revoke(&rotated).await.unwrap();
+        assert!(remember_me.rotate(&rotated).await.unwrap().is_none());
+    }
+}
@@ -4,6 +4,7 @@
 
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -34,6 +35,15 @@ impl SessionId {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Short, non-secret name for this session
+    ///
+    /// Derived from the ID by hashing, so it can be shown to the user (e.g.
+    /// in a list of their active sessions) without revealing the ID itself.
+    #[must_use]
+    pub fn handle(&self) -> String {
+        hex::encode(&Sha256::digest(self.0.as_bytes())[..8])
+    }
 }
 
 impl std::fmt::Display for SessionId {
@@ -67,6 +77,12 @@ pub struct SessionData {
     pub data: HashMap<String, serde_json::Value>,
     /// Flash messages queued for next request
     pub flash_messages: Vec<FlashMessage>,
+    /// Client `User-Agent` from the most recent request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Client IP address from the most recent request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
 }
 
 impl SessionData {
@@ -82,6 +98,8 @@ impl SessionData {
             user_name: None,
             data: HashMap::new(),
             flash_messages: Vec::new(),
+            user_agent: None,
+            ip_address: None,
         }
     }
 
@@ -97,6 +115,8 @@ impl SessionData {
             user_name: None,
             data: HashMap::new(),
             flash_messages: Vec::new(),
+            user_agent: None,
+            ip_address: None,
         }
     }
 
@@ -191,6 +211,11 @@ pub struct FlashMessage {
     pub message: String,
     /// Optional title
     pub title: Option<String>,
+    /// Milliseconds after which the message should be dismissed
+    ///
+    /// Rendered as a `data-dismiss-after` attribute for client-side scripts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dismiss_ms: Option<u64>,
 }
 
 impl FlashMessage {
@@ -201,6 +226,7 @@ impl FlashMessage {
             level: FlashLevel::Success,
             message: message.into(),
             title: None,
+            dismiss_ms: None,
         }
     }
 
@@ -211,6 +237,7 @@ impl FlashMessage {
             level: FlashLevel::Info,
             message: message.into(),
             title: None,
+            dismiss_ms: None,
         }
     }
 
@@ -221,6 +248,7 @@ impl FlashMessage {
             level: FlashLevel::Warning,
             message: message.into(),
             title: None,
+            dismiss_ms: None,
         }
     }
 
@@ -231,6 +259,7 @@ impl FlashMessage {
             level: FlashLevel::Error,
             message: message.into(),
             title: None,
+            dismiss_ms: None,
         }
     }
 
@@ -241,6 +270,15 @@ impl FlashMessage {
         self
     }
 
+    /// Dismiss this flash message automatically after `duration`
+    ///
+    /// Durations beyond `u64::MAX` milliseconds saturate.
+    #[must_use]
+    pub fn dismiss_after(mut self, duration: std::time::Duration) -> Self {
+        self.dismiss_ms = Some(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX));
+        self
+    }
+
     /// Get CSS class for this flash level
     #[must_use]
     pub const fn css_class(&self) -> &'static str {
@@ -310,11 +348,23 @@ pub enum SessionError {
     #[error("Redis error: {0}")]
     Redis(String),
 
+    /// Database error
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    #[error("Database error: {0}")]
+    Database(String),
+
     /// Agent communication error
     #[error("Agent error: {0}")]
     Agent(String),
 }
 
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+impl From<sqlx::Error> for SessionError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Database(error.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -339,6 +389,24 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_session_id_handle() {
+        let id = SessionId::generate();
+        let same_id = SessionId::try_from_string(id.as_str().to_string()).unwrap();
+        assert_eq!(id.handle(), same_id.handle());
+        assert_eq!(id.handle().len(), 16);
+        assert!(!id.as_str().contains(&id.handle()));
+        assert_ne!(id.handle(), SessionId::generate().handle());
+    }
+
+    #[test]
+    fn test_session_data_without_client_fields_deserializes() {
+        let mut json = serde_json::to_value(SessionData::new()).unwrap();
+        json.as_object_mut().unwrap().remove("user_agent");
+        let data: SessionData = serde_json::from_value(json).unwrap();
+        assert!(data.user_agent.is_none() && data.ip_address.is_none());
+    }
+
     #[test]
     fn test_session_data_new() {
         let data = SessionData::new();
@@ -416,4 +484,19 @@ mod tests {
         assert_eq!(FlashLevel::Warning.css_class(), "flash-warning");
         assert_eq!(FlashLevel::Error.css_class(), "flash-error");
     }
+
+    #[test]
+    fn test_flash_message_dismiss_after() {
+        let flash = FlashMessage::info("Saved").dismiss_after(std::time::Duration::from_secs(5));
+        assert_eq!(flash.dismiss_ms, Some(5000));
+        assert_eq!(FlashMessage::info("Saved").dismiss_ms, None);
+    }
+
+    #[test]
+    fn test_flash_message_deserializes_without_dismiss_ms() {
+        let flash: FlashMessage =
+            serde_json::from_str(r#"{"level":"warning","message":"Careful","title":null}"#)
+                .unwrap();
+        assert_eq!(flash, FlashMessage::warning("Careful"));
+    }
 }
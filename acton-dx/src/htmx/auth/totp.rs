@@ -0,0 +1,963 @@
+//! TOTP two-factor authentication
+//!
+//! Implements time-based one-time passwords (RFC 6238, HMAC-SHA1) as used by
+//! authenticator apps, plus single-use backup codes for when the device is
+//! lost.
+//!
+//! - [`TotpSecret`] generates the shared secret; [`Totp::provisioning_uri`]
+//!   builds the `otpauth://` URI that authenticator apps import, which is also
+//!   the payload to render as a QR code.
+//! - [`Totp::verify`] accepts codes from neighbouring time steps to tolerate
+//!   clock skew, and never accepts a time step that was already used.
+//! - Backup codes are stored as SHA-256 hashes and removed when used.
+//! - [`TotpCipher`] encrypts secrets with AES-256-GCM under a key derived
+//!   from `security.secret_key` before they are stored in the `user_totp`
+//!   table ([`UserTotp`]).
+//!
+//! With TOTP enabled, `login_post` no longer logs the user in after the
+//! password check; it redirects to the `/login/totp` step, and only
+//! [`totp_login_post`](crate::htmx::auth::handlers::totp_login_post)
+//! establishes the session. The pending login is dropped after
+//! `max_login_attempts` wrong codes, so the password has to be entered again.
+//!
+//! The SQL functions expect the table from
+//! `migrations/009_create_user_totp_table.sql`.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [security.totp]
+//! issuer = "Example"
+//! skew_steps = 1
+//! backup_code_count = 10
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::handlers::{
+//!     totp_disable, totp_enroll_confirm, totp_enroll_form, totp_enroll_start, totp_login_form,
+//!     totp_login_post,
+//! };
+//! use axum::{routing::{get, post}, Router};
+//!
+//! let app = Router::new()
+//!     .route("/login/totp", get(totp_login_form).post(totp_login_post))
+//!     .route("/account/totp", get(totp_enroll_form).post(totp_enroll_confirm))
+//!     .route("/account/totp/setup", post(totp_enroll_start))
+//!     .route("/account/totp/disable", post(totp_disable));
+//! ```
+
+use std::fmt::Debug;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+
+use crate::htmx::auth::remember::hash_validator;
+use crate::htmx::middleware::cookie_signer::unix_now;
+use crate::htmx::middleware::CookieSigner;
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::auth::UserError;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// Session key holding a login that still needs its TOTP step
+pub(crate) const PENDING_LOGIN_SESSION_KEY: &str = "totp_pending_login";
+
+/// Purpose label for the key that encrypts stored secrets
+const CIPHER_KEY_PURPOSE: &str = "totp-secret";
+
+/// Secret length in bytes (160 bits, as recommended by RFC 4226)
+const SECRET_LEN: usize = 20;
+
+/// Configuration for TOTP two-factor authentication
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [security.totp]
+/// issuer = "Example"
+/// skew_steps = 1
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TotpConfig {
+    /// Name shown next to the account in authenticator apps
+    pub issuer: String,
+
+    /// Number of digits in a code (default: 6)
+    pub digits: u32,
+
+    /// Length of a time step in seconds (default: 30)
+    pub step_secs: u64,
+
+    /// Time steps accepted on either side of the current one, to tolerate
+    /// clock skew (default: 1)
+    pub skew_steps: u64,
+
+    /// Backup codes issued at enrollment (default: 10)
+    pub backup_code_count: usize,
+
+    /// How long the TOTP step of a login may take, in seconds (default: 300)
+    pub login_timeout_secs: u64,
+
+    /// Wrong codes accepted before the login has to start over (default: 5)
+    pub max_login_attempts: u32,
+}
+
+impl Default for TotpConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "acton-htmx".to_string(),
+            digits: 6,
+            step_secs: 30,
+            skew_steps: 1,
+            backup_code_count: 10,
+            login_timeout_secs: 300,
+            max_login_attempts: 5,
+        }
+    }
+}
+
+/// A TOTP shared secret
+#[derive(Clone, PartialEq, Eq)]
+pub struct TotpSecret(Vec<u8>);
+
+impl Debug for TotpSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TotpSecret([redacted])")
+    }
+}
+
+impl TotpSecret {
+    /// Generate a new random secret
+    #[must_use]
+    pub fn generate() -> Self {
+        let mut bytes = vec![0u8; SECRET_LEN];
+        rand::rng().fill(bytes.as_mut_slice());
+        Self(bytes)
+    }
+
+    /// Create from raw bytes
+    #[must_use]
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        Self(bytes.into())
+    }
+
+    /// Parse the base32 form shown to users (spaces and case are ignored)
+    #[must_use]
+    pub fn from_base32(encoded: &str) -> Option<Self> {
+        let normalized: String = encoded
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '=')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        BASE32_NOPAD.decode(normalized.as_bytes()).ok().map(Self)
+    }
+
+    /// Base32 form for manual entry into an authenticator app
+    #[must_use]
+    pub fn to_base32(&self) -> String {
+        BASE32_NOPAD.encode(&self.0)
+    }
+
+    /// Raw secret bytes
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Generates and checks TOTP codes
+#[derive(Debug, Clone)]
+pub struct Totp {
+    config: TotpConfig,
+}
+
+impl Totp {
+    /// Create from configuration
+    #[must_use]
+    pub fn new(config: &TotpConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// Time step containing `unix_time`
+    #[must_use]
+    pub const fn step(&self, unix_time: u64) -> u64 {
+        unix_time / self.step_secs()
+    }
+
+    const fn step_secs(&self) -> u64 {
+        if self.config.step_secs == 0 {
+            30
+        } else {
+            self.config.step_secs
+        }
+    }
+
+    /// Code for `secret` at `unix_time`
+    #[must_use]
+    pub fn code_at(&self, secret: &TotpSecret, unix_time: u64) -> String {
+        self.code_for_step(secret, self.step(unix_time))
+    }
+
+    /// HOTP value (RFC 4226) for a counter
+    fn code_for_step(&self, secret: &TotpSecret, step: u64) -> String {
+        // HMAC accepts keys of any length, so this never falls back
+        let Ok(mut mac) = HmacSha1::new_from_slice(secret.as_bytes()) else {
+            return String::new();
+        };
+        mac.update(&step.to_be_bytes());
+        let hash = mac.finalize().into_bytes();
+
+        let offset = usize::from(hash[hash.len() - 1] & 0x0f);
+        let binary = u32::from_be_bytes([
+            hash[offset] & 0x7f,
+            hash[offset + 1],
+            hash[offset + 2],
+            hash[offset + 3],
+        ]);
+        let digits = self.config.digits.clamp(6, 9);
+        let code = binary % 10u32.pow(digits);
+        format!("{code:0width$}", width = digits as usize)
+    }
+
+    /// Check `code` against `secret` now
+    ///
+    /// Returns the matched time step, which the caller must store and pass as
+    /// `last_used_step` next time so a code can't be replayed. Steps at or
+    /// before `last_used_step` are never accepted.
+    #[must_use]
+    pub fn verify(&self, secret: &TotpSecret, code: &str, last_used_step: Option<u64>) -> Option<u64> {
+        self.verify_at(secret, code, unix_now(), last_used_step)
+    }
+
+    /// Check `code` against `secret` at `unix_time`
+    ///
+    /// See [`verify`](Self::verify).
+    #[must_use]
+    pub fn verify_at(
+        &self,
+        secret: &TotpSecret,
+        code: &str,
+        unix_time: u64,
+        last_used_step: Option<u64>,
+    ) -> Option<u64> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let current = self.step(unix_time);
+        let first = current.saturating_sub(self.config.skew_steps);
+        let last = current.saturating_add(self.config.skew_steps);
+
+        // Check every step so timing doesn't reveal which one matched
+        let mut matched = None;
+        for step in first..=last {
+            if last_used_step.is_some_and(|used| step <= used) {
+                continue;
+            }
+            if constant_time_eq(&self.code_for_step(secret, step), &code) && matched.is_none() {
+                matched = Some(step);
+            }
+        }
+        matched
+    }
+
+    /// `otpauth://` URI for enrolling `secret` in an authenticator app
+    ///
+    /// Render this as a QR code for scanning; `account` is usually the
+    /// user's email address.
+    #[must_use]
+    pub fn provisioning_uri(&self, secret: &TotpSecret, account: &str) -> String {
+        let issuer = percent_encode(&self.config.issuer);
+        format!(
+            "otpauth://totp/{issuer}:{}?secret={}&issuer={issuer}&algorithm=SHA1&digits={}&period={}",
+            percent_encode(account),
+            secret.to_base32(),
+            self.config.digits.clamp(6, 9),
+            self.step_secs(),
+        )
+    }
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    use std::fmt::Write;
+
+    value.bytes().fold(String::new(), |mut encoded, byte| {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+        encoded
+    })
+}
+
+/// Compare two strings without short-circuiting on the first difference
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a
+            .bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// Generate `count` backup codes formatted as `xxxxx-xxxxx`
+///
+/// Show them to the user once and store only [`hash_backup_code`] hashes.
+#[must_use]
+pub fn generate_backup_codes(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let mut bytes = [0u8; 7];
+            rand::rng().fill(&mut bytes);
+            let encoded = BASE32_NOPAD.encode(&bytes).to_ascii_lowercase();
+            format!("{}-{}", &encoded[..5], &encoded[5..10])
+        })
+        .collect()
+}
+
+/// Hex-encoded SHA-256 hash of a backup code
+///
+/// Case, spaces, and dashes are ignored, so codes can be typed loosely.
+#[must_use]
+pub fn hash_backup_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hash_validator(&normalized)
+}
+
+/// Remove the hash matching `code` from `hashes`
+///
+/// Returns whether the code was valid. Persist the shortened list to make
+/// the code single-use.
+pub fn take_backup_code(hashes: &mut Vec<String>, code: &str) -> bool {
+    let hash = hash_backup_code(code);
+    let before = hashes.len();
+    hashes.retain(|stored| !constant_time_eq(stored, &hash));
+    hashes.len() < before
+}
+
+/// Encrypts TOTP secrets for storage
+///
+/// Uses AES-256-GCM with a random nonce per secret. The stored form is the
+/// URL-safe base64 of `nonce || ciphertext || tag`.
+pub struct TotpCipher {
+    key: LessSafeKey,
+}
+
+impl Debug for TotpCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TotpCipher").finish_non_exhaustive()
+    }
+}
+
+impl TotpCipher {
+    /// Create from a 256-bit key
+    ///
+    /// # Panics
+    ///
+    /// Never in practice: AES-256-GCM accepts any 32-byte key.
+    #[must_use]
+    pub fn new(key: &[u8; 32]) -> Self {
+        let key = UnboundKey::new(&AES_256_GCM, key)
+            .expect("AES-256-GCM accepts 32-byte keys");
+        Self {
+            key: LessSafeKey::new(key),
+        }
+    }
+
+    /// Create with a key derived from the application's cookie signer
+    ///
+    /// The key follows `security.secret_key`; changing it makes stored
+    /// secrets unreadable, so users would have to enroll again.
+    #[must_use]
+    pub fn from_signer(signer: &CookieSigner) -> Self {
+        Self::new(&signer.derive_key(CIPHER_KEY_PURPOSE))
+    }
+
+    /// Encrypt `secret` for storage
+    #[must_use]
+    pub fn encrypt(&self, secret: &TotpSecret) -> String {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rng().fill(&mut nonce);
+
+        let mut sealed = secret.as_bytes().to_vec();
+        // Sealing only fails for inputs far larger than a TOTP secret
+        let _ = self.key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut sealed,
+        );
+
+        let mut stored = nonce.to_vec();
+        stored.extend_from_slice(&sealed);
+        URL_SAFE_NO_PAD.encode(stored)
+    }
+
+    /// Decrypt a stored secret
+    ///
+    /// Returns `None` if the value was tampered with or encrypted under a
+    /// different key.
+    #[must_use]
+    pub fn decrypt(&self, stored: &str) -> Option<TotpSecret> {
+        let mut bytes = URL_SAFE_NO_PAD.decode(stored).ok()?;
+        if bytes.len() < NONCE_LEN {
+            return None;
+        }
+        let mut sealed = bytes.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&bytes).ok()?;
+        let secret = self.key.open_in_place(nonce, Aad::empty(), &mut sealed).ok()?;
+        Some(TotpSecret::from_bytes(secret.to_vec()))
+    }
+}
+
+/// A login that passed the password check and awaits its TOTP code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTotpLogin {
+    /// User logging in
+    pub user_id: i64,
+    /// Whether the login form asked to be remembered
+    pub remember: bool,
+    /// When the password was checked (seconds since the Unix epoch)
+    pub started_at: u64,
+    /// Wrong codes entered so far
+    #[serde(default)]
+    pub failed_attempts: u32,
+}
+
+impl PendingTotpLogin {
+    /// Whether the TOTP step may still be completed at `now`
+    #[must_use]
+    pub const fn is_live(&self, timeout_secs: u64, now: u64) -> bool {
+        now.saturating_sub(self.started_at) <= timeout_secs
+    }
+
+    /// Count a wrong code, returning whether another attempt is allowed
+    pub const fn record_failure(&mut self, max_attempts: u32) -> bool {
+        self.failed_attempts = self.failed_attempts.saturating_add(1);
+        self.failed_attempts < max_attempts
+    }
+}
+
+/// A user's row in the `user_totp` table
+///
+/// A row with `enabled = false` is an enrollment waiting for its first code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserTotp {
+    /// User the secret belongs to
+    pub user_id: i64,
+    /// Secret encrypted with [`TotpCipher`]
+    pub secret_ciphertext: String,
+    /// Whether login requires a code
+    pub enabled: bool,
+    /// Last accepted time step, to reject replayed codes
+    pub last_used_step: Option<u64>,
+    /// Hashes of the unused backup codes
+    pub backup_code_hashes: Vec<String>,
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+type UserTotpRow = (i64, String, bool, Option<i64>, String);
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+impl UserTotp {
+    fn from_row((user_id, secret_ciphertext, enabled, last_used_step, backup_codes): UserTotpRow) -> Self {
+        Self {
+            user_id,
+            secret_ciphertext,
+            enabled,
+            last_used_step: last_used_step.and_then(|step| u64::try_from(step).ok()),
+            backup_code_hashes: serde_json::from_str(&backup_codes).unwrap_or_default(),
+        }
+    }
+
+    fn backup_codes_json(hashes: &[String]) -> String {
+        serde_json::to_string(hashes).unwrap_or_else(|_| "[]".to_string())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl UserTotp {
+    /// Find a user's TOTP row
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn find(user_id: i64, pool: &sqlx::PgPool) -> Result<Option<Self>, UserError> {
+        let row: Option<UserTotpRow> = sqlx::query_as(
+            r"
+            SELECT user_id, secret_ciphertext, enabled, last_used_step, backup_codes
+            FROM user_totp
+            WHERE user_id = $1
+            ",
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Self::from_row))
+    }
+
+    /// Whether login for `user_id` requires a TOTP code
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn is_enabled(user_id: i64, pool: &sqlx::PgPool) -> Result<bool, UserError> {
+        Ok(Self::find(user_id, pool).await?.is_some_and(|totp| totp.enabled))
+    }
+
+    /// Start (or restart) enrollment with a new encrypted secret
+    ///
+    /// Does nothing if TOTP is already enabled; returns whether the secret
+    /// was stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn begin_enrollment(
+        user_id: i64,
+        secret_ciphertext: &str,
+        pool: &sqlx::PgPool,
+    ) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"
+            INSERT INTO user_totp (user_id, secret_ciphertext, enabled, last_used_step, backup_codes)
+            VALUES ($1, $2, FALSE, NULL, '[]')
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret_ciphertext = EXCLUDED.secret_ciphertext, last_used_step = NULL, backup_codes = '[]'
+            WHERE user_totp.enabled = FALSE
+            ",
+        )
+        .bind(user_id)
+        .bind(secret_ciphertext)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Finish enrollment, storing the first accepted step and backup code hashes
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn enable(
+        user_id: i64,
+        step: u64,
+        backup_code_hashes: &[String],
+        pool: &sqlx::PgPool,
+    ) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"
+            UPDATE user_totp
+            SET enabled = TRUE, last_used_step = $2, backup_codes = $3
+            WHERE user_id = $1 AND enabled = FALSE
+            ",
+        )
+        .bind(user_id)
+        .bind(i64::try_from(step).unwrap_or(i64::MAX))
+        .bind(Self::backup_codes_json(backup_code_hashes))
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record an accepted time step
+    ///
+    /// Returns `false` if a later or equal step was recorded concurrently,
+    /// i.e. the code was replayed.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn record_step(user_id: i64, step: u64, pool: &sqlx::PgPool) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"
+            UPDATE user_totp
+            SET last_used_step = $2
+            WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+            ",
+        )
+        .bind(user_id)
+        .bind(i64::try_from(step).unwrap_or(i64::MAX))
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace the backup code hashes if they are still `expected`
+    ///
+    /// Returns `false` if another request used a code first.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn replace_backup_codes(
+        user_id: i64,
+        expected: &[String],
+        remaining: &[String],
+        pool: &sqlx::PgPool,
+    ) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            "UPDATE user_totp SET backup_codes = $3 WHERE user_id = $1 AND backup_codes = $2",
+        )
+        .bind(user_id)
+        .bind(Self::backup_codes_json(expected))
+        .bind(Self::backup_codes_json(remaining))
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove TOTP for a user
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn delete(user_id: i64, pool: &sqlx::PgPool) -> Result<bool, UserError> {
+        let result = sqlx::query("DELETE FROM user_totp WHERE user_id = $1")
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl UserTotp {
+    /// Find a user's TOTP row (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn find(user_id: i64, pool: &sqlx::SqlitePool) -> Result<Option<Self>, UserError> {
+        let row: Option<UserTotpRow> = sqlx::query_as(
+            r"SELECT user_id, secret_ciphertext, enabled, last_used_step, backup_codes
+              FROM user_totp WHERE user_id = ?",
+        )
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Self::from_row))
+    }
+
+    /// Whether login for `user_id` requires a TOTP code (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn is_enabled(user_id: i64, pool: &sqlx::SqlitePool) -> Result<bool, UserError> {
+        Ok(Self::find(user_id, pool).await?.is_some_and(|totp| totp.enabled))
+    }
+
+    /// Start (or restart) enrollment with a new encrypted secret (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn begin_enrollment(
+        user_id: i64,
+        secret_ciphertext: &str,
+        pool: &sqlx::SqlitePool,
+    ) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"INSERT INTO user_totp (user_id, secret_ciphertext, enabled, last_used_step, backup_codes)
+              VALUES (?, ?, 0, NULL, '[]')
+              ON CONFLICT (user_id) DO UPDATE
+              SET secret_ciphertext = excluded.secret_ciphertext, last_used_step = NULL, backup_codes = '[]'
+              WHERE user_totp.enabled = 0",
+        )
+        .bind(user_id)
+        .bind(secret_ciphertext)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Finish enrollment (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn enable(
+        user_id: i64,
+        step: u64,
+        backup_code_hashes: &[String],
+        pool: &sqlx::SqlitePool,
+    ) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"UPDATE user_totp SET enabled = 1, last_used_step = ?, backup_codes = ?
+              WHERE user_id = ? AND enabled = 0",
+        )
+        .bind(i64::try_from(step).unwrap_or(i64::MAX))
+        .bind(Self::backup_codes_json(backup_code_hashes))
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record an accepted time step (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn record_step(
+        user_id: i64,
+        step: u64,
+        pool: &sqlx::SqlitePool,
+    ) -> Result<bool, UserError> {
+        let step = i64::try_from(step).unwrap_or(i64::MAX);
+        let result = sqlx::query(
+            r"UPDATE user_totp SET last_used_step = ?
+              WHERE user_id = ? AND (last_used_step IS NULL OR last_used_step < ?)",
+        )
+        .bind(step)
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace the backup code hashes if they are still `expected` (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn replace_backup_codes(
+        user_id: i64,
+        expected: &[String],
+        remaining: &[String],
+        pool: &sqlx::SqlitePool,
+    ) -> Result<bool, UserError> {
+        let result =
+            sqlx::query("UPDATE user_totp SET backup_codes = ? WHERE user_id = ? AND backup_codes = ?")
+                .bind(Self::backup_codes_json(remaining))
+                .bind(user_id)
+                .bind(Self::backup_codes_json(expected))
+                .execute(pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove TOTP for a user (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn delete(user_id: i64, pool: &sqlx::SqlitePool) -> Result<bool, UserError> {
+        let result = sqlx::query("DELETE FROM user_totp WHERE user_id = ?")
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totp() -> Totp {
+        Totp::new(&TotpConfig::default())
+    }
+
+    #[test]
+    fn test_rfc6238_vector() {
+        // RFC 6238 appendix B, SHA-1, truncated to 8 and 6 digits
+        let secret = TotpSecret::from_bytes(b"12345678901234567890".to_vec());
+        let eight = Totp::new(&TotpConfig {
+            digits: 8,
+            ..TotpConfig::default()
+        });
+
+        assert_eq!(eight.code_at(&secret, 59), "94287082");
+        assert_eq!(eight.code_at(&secret, 1_111_111_109), "07081804");
+        assert_eq!(totp().code_at(&secret, 59), "287082");
+    }
+
+    #[test]
+    fn test_generated_code_validates_within_window() {
+        let totp = totp();
+        let secret = TotpSecret::generate();
+        let now = 1_700_000_000;
+        let step = totp.step(now);
+
+        let code = totp.code_at(&secret, now);
+        assert_eq!(totp.verify_at(&secret, &code, now, None), Some(step));
+
+        // One step of clock skew either way is tolerated
+        let behind = totp.code_at(&secret, now - 30);
+        assert_eq!(totp.verify_at(&secret, &behind, now, None), Some(step - 1));
+        let ahead = totp.code_at(&secret, now + 30);
+        assert_eq!(totp.verify_at(&secret, &ahead, now, None), Some(step + 1));
+
+        // Outside the window
+        let stale = totp.code_at(&secret, now - 90);
+        assert_eq!(totp.verify_at(&secret, &stale, now, None), None);
+        assert_eq!(totp.verify_at(&TotpSecret::generate(), &code, now, None), None);
+
+        assert!(totp.verify(&secret, &totp.code_at(&secret, unix_now()), None).is_some());
+    }
+
+    #[test]
+    fn test_used_step_rejected() {
+        let totp = totp();
+        let secret = TotpSecret::generate();
+        let now = 1_700_000_000;
+        let code = totp.code_at(&secret, now);
+
+        let step = totp.verify_at(&secret, &code, now, None).unwrap();
+        assert_eq!(totp.verify_at(&secret, &code, now + 5, Some(step)), None);
+    }
+
+    #[test]
+    fn test_secret_base32_roundtrip() {
+        let secret = TotpSecret::generate();
+        let encoded = secret.to_base32();
+        assert_eq!(encoded.len(), 32);
+        assert_eq!(TotpSecret::from_base32(&encoded.to_lowercase()), Some(secret));
+        assert!(TotpSecret::from_base32("not base32!").is_none());
+    }
+
+    #[test]
+    fn test_provisioning_uri() {
+        let secret = TotpSecret::from_bytes(b"12345678901234567890".to_vec());
+        let totp = Totp::new(&TotpConfig {
+            issuer: "Acme Corp".to_string(),
+            ..TotpConfig::default()
+        });
+
+        assert_eq!(
+            totp.provisioning_uri(&secret, "ada@example.com"),
+            "otpauth://totp/Acme%20Corp:ada%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+             &issuer=Acme%20Corp&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn test_used_backup_code_cannot_be_reused() {
+        let codes = generate_backup_codes(10);
+        assert_eq!(codes.len(), 10);
+        assert!(codes.iter().all(|code| code.len() == 11 && code.as_bytes()[5] == b'-'));
+
+        let mut hashes: Vec<String> = codes.iter().map(|code| hash_backup_code(code)).collect();
+        assert!(!hashes.contains(&codes[0]));
+
+        assert!(take_backup_code(&mut hashes, &codes[0].to_uppercase().replace('-', " ")));
+        assert_eq!(hashes.len(), 9);
+        assert!(!take_backup_code(&mut hashes, &codes[0]));
+        assert!(!take_backup_code(&mut hashes, "wrong-code0"));
+        assert!(take_backup_code(&mut hashes, &codes[1]));
+    }
+
+    #[test]
+    fn test_cipher_roundtrip_and_tamper() {
+        let cipher = TotpCipher::from_signer(&CookieSigner::new(b"test-secret"));
+        let secret = TotpSecret::generate();
+
+        let stored = cipher.encrypt(&secret);
+        assert_ne!(stored, cipher.encrypt(&secret));
+        assert_eq!(cipher.decrypt(&stored), Some(secret));
+
+        let mut tampered = URL_SAFE_NO_PAD.decode(&stored).unwrap();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(cipher.decrypt(&URL_SAFE_NO_PAD.encode(tampered)).is_none());
+
+        let other = TotpCipher::from_signer(&CookieSigner::new(b"other-secret"));
+        assert!(other.decrypt(&stored).is_none());
+        assert!(cipher.decrypt("short").is_none());
+    }
+
+    #[test]
+    fn test_pending_login_timeout() {
+        let pending = PendingTotpLogin {
+            user_id: 1,
+            remember: false,
+            started_at: 1_000,
+            failed_attempts: 0,
+        };
+        assert!(pending.is_live(300, 1_300));
+        assert!(!pending.is_live(300, 1_301));
+    }
+
+    #[test]
+    fn test_pending_login_attempts() {
+        let mut pending = PendingTotpLogin {
+            user_id: 1,
+            remember: false,
+            started_at: 1_000,
+            failed_attempts: 0,
+        };
+        assert!(pending.record_failure(3));
+        assert!(pending.record_failure(3));
+        assert!(!pending.record_failure(3));
+        assert_eq!(pending.failed_attempts, 3);
+
+        // Logins pending before the counter existed start at zero
+        let old: PendingTotpLogin =
+            serde_json::from_str(r#"{"user_id":1,"remember":false,"started_at":1000}"#).unwrap();
+        assert_eq!(old.failed_attempts, 0);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_user_totp() {
+        let pool = crate::htmx::testing::create_sqlite_pool_with_migration(include_str!(
+            "../../../../migrations/009_create_user_totp_table.sql"
+        ))
+        .await
+        .unwrap();
+
+        assert!(UserTotp::begin_enrollment(7, "first", &pool).await.unwrap());
+        assert!(UserTotp::begin_enrollment(7, "second", &pool).await.unwrap());
+        assert!(!UserTotp::is_enabled(7, &pool).await.unwrap());
+
+        let hashes = vec![hash_backup_code("aaaaa-bbbbb"), hash_backup_code("ccccc-ddddd")];
+        assert!(UserTotp::enable(7, 100, &hashes, &pool).await.unwrap());
+        assert!(!UserTotp::begin_enrollment(7, "third", &pool).await.unwrap());
+
+        let stored = UserTotp::find(7, &pool).await.unwrap().unwrap();
+        assert!(stored.enabled);
+        assert_eq!(stored.secret_ciphertext, "second");
+        assert_eq!(stored.last_used_step, Some(100));
+        assert_eq!(stored.backup_code_hashes, hashes);
+
+        assert!(!UserTotp::record_step(7, 100, &pool).await.unwrap());
+        assert!(UserTotp::record_step(7, 101, &pool).await.unwrap());
+
+        let mut remaining = hashes.clone();
+        assert!(take_backup_code(&mut remaining, "aaaaa-bbbbb"));
+        assert!(UserTotp::replace_backup_codes(7, &hashes, &remaining, &pool).await.unwrap());
+        // A second use of the same code races against a stale list
+        assert!(!UserTotp::replace_backup_codes(7, &hashes, &remaining, &pool).await.unwrap());
+
+        assert!(UserTotp::delete(7, &pool).await.unwrap());
+        assert!(UserTotp::find(7, &pool).await.unwrap().is_none());
+    }
+}
@@ -379,6 +379,67 @@ impl User {
         Ok(user)
     }
 
+    /// Mark the user's email as verified, if it is still `email`
+    ///
+    /// Sets `email_verified` and records the time in `email_verified_at`.
+    /// Returns `false` when no user has this ID and address, e.g. because
+    /// the address changed after the verification link was sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    #[cfg(feature = "postgres")]
+    pub async fn mark_email_verified(
+        id: i64,
+        email: &str,
+        pool: &sqlx::PgPool,
+    ) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"
+            UPDATE users
+            SET email_verified = TRUE, email_verified_at = COALESCE(email_verified_at, NOW())
+            WHERE id = $1 AND email = $2
+            ",
+        )
+        .bind(id)
+        .bind(email)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace the user's password
+    ///
+    /// Hashes `password`; callers are responsible for checking it against the
+    /// password policy first. Returns `false` when no user has this ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if password hashing or the database operation fails
+    #[cfg(feature = "postgres")]
+    pub async fn set_password(
+        id: i64,
+        password: &str,
+        pool: &sqlx::PgPool,
+    ) -> Result<bool, UserError> {
+        let password_hash = hash_password(password)?;
+
+        let result = sqlx::query(
+            r"
+            UPDATE users
+            SET password_hash = $1, updated_at = NOW()
+            WHERE id = $2
+            ",
+        )
+        .bind(&password_hash)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Authenticate a user with email and password
     ///
     /// # Errors
@@ -506,6 +567,54 @@ impl User {
         row.into_user()
     }
 
+    /// Mark the user's email as verified, if it is still `email` (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    #[cfg(feature = "sqlite")]
+    pub async fn mark_email_verified(
+        id: i64,
+        email: &str,
+        pool: &sqlx::SqlitePool,
+    ) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"UPDATE users
+              SET email_verified = 1, email_verified_at = COALESCE(email_verified_at, CURRENT_TIMESTAMP)
+              WHERE id = ? AND email = ?",
+        )
+        .bind(id)
+        .bind(email)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace the user's password (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if password hashing or the database operation fails.
+    #[cfg(feature = "sqlite")]
+    pub async fn set_password(
+        id: i64,
+        password: &str,
+        pool: &sqlx::SqlitePool,
+    ) -> Result<bool, UserError> {
+        let password_hash = hash_password(password)?;
+
+        let result = sqlx::query(
+            "UPDATE users SET password_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )
+        .bind(&password_hash)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Authenticate a user with email and password (SQLite)
     ///
     /// # Errors
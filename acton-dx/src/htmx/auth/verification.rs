@@ -0,0 +1,389 @@
+//! Email address verification
+//!
+//! [`EmailVerification`] issues signed, expiring tokens for a user's email
+//! address and emails them as a link through [`SendEmailJob`]. The
+//! [`verify_email`](crate::htmx::auth::handlers::verify_email) handler checks
+//! the token and records the verification in `users.email_verified_at`;
+//! [`RequireVerifiedEmail`] then guards routes that need a confirmed address.
+//!
+//! Tokens are signed with the application [`CookieSigner`] and bound to both
+//! the user ID and the address, so changing the email invalidates links sent
+//! to the old one.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [security.email_verification]
+//! enabled = true
+//! from = "noreply@example.com"
+//! base_url = "https://example.com"
+//! token_ttl_secs = 86400
+//! resend_cooldown_secs = 60
+//! ```
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::handlers::{resend_verification, verify_email};
+//! use acton_dx::htmx::auth::RequireVerifiedEmail;
+//! use axum::{routing::{get, post}, Router};
+//!
+//! async fn new_post(RequireVerifiedEmail(user): RequireVerifiedEmail) -> String {
+//!     format!("{} may post", user.email)
+//! }
+//!
+//! let app = Router::new()
+//!     .route("/verify-email", get(verify_email))
+//!     .route("/verify-email/resend", post(resend_verification))
+//!     .route("/posts/new", get(new_post));
+//! ```
+
+use std::time::Duration;
+
+use askama::Template;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::htmx::auth::EmailAddress;
+use crate::htmx::email::{Email, EmailError, SendEmailJob, SimpleEmailTemplate};
+use crate::htmx::jobs::JobError;
+use crate::htmx::middleware::cookie_signer::unix_now;
+use crate::htmx::middleware::CookieSigner;
+use crate::htmx::state::ActonHtmxState;
+
+/// Path of the verification link, relative to `base_url`
+pub const VERIFY_EMAIL_PATH: &str = "/verify-email";
+
+/// Session key holding when the last verification email was sent
+pub(crate) const SENT_AT_SESSION_KEY: &str = "email_verification_sent_at";
+
+/// [`CookieSigner::for_purpose`] key of verification tokens, also prefixed
+/// to their payload
+const TOKEN_PURPOSE: &str = "email-verification";
+
+/// Configuration for email verification
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [security.email_verification]
+/// enabled = true
+/// from = "noreply@example.com"
+/// base_url = "https://example.com"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailVerificationConfig {
+    /// Send a verification email at registration (default: false)
+    pub enabled: bool,
+
+    /// Sender address of verification emails
+    pub from: String,
+
+    /// Public URL of the application, used to build the verification link
+    pub base_url: String,
+
+    /// How long a verification link stays valid, in seconds (default: 24 hours)
+    pub token_ttl_secs: u64,
+
+    /// Minimum time between verification emails for a session, in seconds
+    /// (default: 60)
+    pub resend_cooldown_secs: u64,
+}
+
+impl Default for EmailVerificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            from: "noreply@localhost".to_string(),
+            base_url: "http://localhost:3000".to_string(),
+            token_ttl_secs: 86400,
+            resend_cooldown_secs: 60,
+        }
+    }
+}
+
+/// A verified token's user and address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationClaim {
+    /// User the token was issued for
+    pub user_id: i64,
+    /// Address the token confirms
+    pub email: String,
+}
+
+/// Issues and checks email verification tokens
+#[derive(Debug, Clone)]
+pub struct EmailVerification {
+    signer: CookieSigner,
+    config: EmailVerificationConfig,
+}
+
+impl EmailVerification {
+    /// Create from a signer and configuration
+    #[must_use]
+    pub fn new(signer: &CookieSigner, config: &EmailVerificationConfig) -> Self {
+        Self {
+            signer: signer
+                .for_purpose(TOKEN_PURPOSE)
+                .with_max_age(Duration::from_secs(config.token_ttl_secs)),
+            config: config.clone(),
+        }
+    }
+
+    /// Create from the application's cookie signer and `security.email_verification`
+    #[must_use]
+    pub fn from_state(state: &ActonHtmxState) -> Self {
+        Self::new(state.cookie_signer(), &state.config().security.email_verification)
+    }
+
+    /// Minimum time between verification emails
+    #[must_use]
+    pub const fn resend_cooldown(&self) -> Duration {
+        Duration::from_secs(self.config.resend_cooldown_secs)
+    }
+
+    /// Issue a token confirming `email` for `user_id`
+    #[must_use]
+    pub fn token(&self, user_id: i64, email: &EmailAddress) -> String {
+        self.token_at(user_id, email, unix_now())
+    }
+
+    fn token_at(&self, user_id: i64, email: &EmailAddress, timestamp: u64) -> String {
+        let value = format!("{TOKEN_PURPOSE}:{user_id}:{}", email.as_str());
+        self.signer.sign_at(&URL_SAFE_NO_PAD.encode(value), timestamp)
+    }
+
+    /// Return the user and address if `token` is authentic and not expired
+    #[must_use]
+    pub fn verify(&self, token: &str) -> Option<VerificationClaim> {
+        self.verify_at(token, unix_now())
+    }
+
+    fn verify_at(&self, token: &str, now: u64) -> Option<VerificationClaim> {
+        let value = self.signer.verify_at(token, now)?;
+        let value = String::from_utf8(URL_SAFE_NO_PAD.decode(value).ok()?).ok()?;
+
+        let rest = value.strip_prefix(TOKEN_PURPOSE)?.strip_prefix(':')?;
+        let (user_id, email) = rest.split_once(':')?;
+        Some(VerificationClaim {
+            user_id: user_id.parse().ok()?,
+            email: email.to_string(),
+        })
+    }
+
+    /// Verification link for `token`
+    #[must_use]
+    pub fn link(&self, token: &str) -> String {
+        format!(
+            "{}{VERIFY_EMAIL_PATH}?token={token}",
+            self.config.base_url.trim_end_matches('/')
+        )
+    }
+
+    /// Build the verification email for `user_id` at `email`
+    ///
+    /// # Errors
+    ///
+    /// Returns `EmailError::TemplateError` if the template fails to render
+    pub fn email(&self, user_id: i64, email: &EmailAddress) -> Result<Email, EmailError> {
+        let template = VerificationEmailTemplate {
+            link: self.link(&self.token(user_id, email)),
+            valid_hours: self.config.token_ttl_secs.div_ceil(3600),
+        };
+
+        Ok(Email::from_template(&template)?
+            .to(email.as_str())
+            .from(&self.config.from)
+            .subject("Confirm your email address"))
+    }
+
+    /// Queue the verification email for `user_id` at `email`
+    ///
+    /// # Errors
+    ///
+    /// Returns `JobError` if the email cannot be built or queued
+    pub async fn send(
+        &self,
+        state: &ActonHtmxState,
+        user_id: i64,
+        email: &EmailAddress,
+    ) -> Result<(), JobError> {
+        let message = self
+            .email(user_id, email)
+            .map_err(|e| JobError::ExecutionFailed(format!("Failed to build verification email: {e}")))?;
+        state.enqueue(SendEmailJob::new(message)).await?;
+        Ok(())
+    }
+}
+
+/// Verification email body
+#[derive(Debug, Clone, Template)]
+#[template(
+    source = r#"<p>Please confirm your email address by following this link:</p>
+<p><a href="{{ link }}">Confirm email address</a></p>
+<p>The link expires in {{ valid_hours }} hours. If you didn't create an account, you can ignore this email.</p>"#,
+    ext = "html"
+)]
+pub struct VerificationEmailTemplate {
+    /// Verification link
+    pub link: String,
+    /// Hours until the link expires
+    pub valid_hours: u64,
+}
+
+impl SimpleEmailTemplate for VerificationEmailTemplate {
+    fn render_text(&self) -> Result<Option<String>, EmailError> {
+        Ok(Some(format!(
+            "Please confirm your email address by following this link:\n\n{}\n\n\
+             The link expires in {} hours. If you didn't create an account, you can ignore this email.\n",
+            self.link, self.valid_hours
+        )))
+    }
+}
+
+/// Seconds until another verification email may be sent, if any
+///
+/// `sent_at` is when the last one was sent (seconds since the Unix epoch).
+pub(crate) const fn resend_wait(sent_at: Option<u64>, cooldown: Duration, now: u64) -> Option<u64> {
+    let Some(sent_at) = sent_at else {
+        return None;
+    };
+    let ready_at = sent_at.saturating_add(cooldown.as_secs());
+    if now < ready_at {
+        Some(ready_at - now)
+    } else {
+        None
+    }
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub use guard::RequireVerifiedEmail;
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+mod guard {
+    use crate::htmx::auth::{CurrentUser, User};
+    use crate::htmx::state::ActonHtmxState;
+    use axum::{
+        extract::{FromRef, FromRequestParts},
+        http::{request::Parts, StatusCode},
+    };
+
+    /// The logged-in user's record, only if their email is verified
+    ///
+    /// Rejects with `401 Unauthorized` when logged out and `403 Forbidden`
+    /// when the address hasn't been confirmed.
+    #[derive(Debug, Clone)]
+    pub struct RequireVerifiedEmail(pub User);
+
+    impl<S> FromRequestParts<S> for RequireVerifiedEmail
+    where
+        S: Send + Sync,
+        ActonHtmxState: FromRef<S>,
+    {
+        type Rejection = StatusCode;
+
+        async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+            let CurrentUser(user) = CurrentUser::from_request_parts(parts, state).await?;
+            if user.email_verified {
+                Ok(Self(user))
+            } else {
+                Err(StatusCode::FORBIDDEN)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verification() -> EmailVerification {
+        EmailVerification::new(
+            &CookieSigner::new(b"test-secret"),
+            &EmailVerificationConfig {
+                token_ttl_secs: 3600,
+                base_url: "https://example.com/".to_string(),
+                ..EmailVerificationConfig::default()
+            },
+        )
+    }
+
+    fn email() -> EmailAddress {
+        EmailAddress::parse("ada@example.com").unwrap()
+    }
+
+    #[test]
+    fn test_valid_token() {
+        let verification = verification();
+        let token = verification.token_at(42, &email(), 1_000);
+
+        assert_eq!(
+            verification.verify_at(&token, 1_100),
+            Some(VerificationClaim {
+                user_id: 42,
+                email: "ada@example.com".to_string(),
+            })
+        );
+        assert!(verification.verify(&verification.token(42, &email())).is_some());
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let verification = verification();
+        let token = verification.token_at(42, &email(), 1_000);
+
+        assert!(verification.verify_at(&token, 4_600).is_some());
+        assert_eq!(verification.verify_at(&token, 4_601), None);
+    }
+
+    #[test]
+    fn test_tampered_token() {
+        let verification = verification();
+        let token = verification.token_at(42, &email(), 1_000);
+
+        // Swap the payload for another user's
+        let other = verification.token_at(43, &email(), 1_000);
+        let (payload, _) = other.split_once('.').unwrap();
+        let (_, rest) = token.split_once('.').unwrap();
+        assert_eq!(verification.verify_at(&format!("{payload}.{rest}"), 1_000), None);
+
+        // Extended timestamp, truncated signature, other key
+        assert_eq!(verification.verify_at(&token.replace(".1000.", ".9000."), 1_000), None);
+        assert_eq!(verification.verify_at(&token[..token.len() - 1], 1_000), None);
+        let other_key = EmailVerification::new(
+            &CookieSigner::new(b"other-secret"),
+            &EmailVerificationConfig::default(),
+        );
+        assert_eq!(other_key.verify_at(&token, 1_000), None);
+    }
+
+    #[test]
+    fn test_other_signed_values_are_not_tokens() {
+        let signer = CookieSigner::new(b"test-secret");
+        let verification = EmailVerification::new(&signer, &EmailVerificationConfig::default());
+
+        assert_eq!(verification.verify(&signer.sign("42:ada@example.com")), None);
+    }
+
+    #[test]
+    fn test_verification_email() {
+        let verification = verification();
+        let message = verification.email(42, &email()).unwrap();
+
+        assert_eq!(message.to, vec!["ada@example.com".to_string()]);
+        assert_eq!(message.subject.as_deref(), Some("Confirm your email address"));
+        let html = message.html.unwrap();
+        assert!(html.contains("https://example.com/verify-email?token="));
+        assert!(message.text.unwrap().contains("expires in 1 hours"));
+    }
+
+    #[test]
+    fn test_resend_wait() {
+        let cooldown = Duration::from_secs(60);
+
+        assert_eq!(resend_wait(None, cooldown, 1_000), None);
+        assert_eq!(resend_wait(Some(1_000), cooldown, 1_015), Some(45));
+        assert_eq!(resend_wait(Some(1_000), cooldown, 1_060), None);
+    }
+}
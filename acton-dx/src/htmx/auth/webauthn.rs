@@ -0,0 +1,757 @@
+//! WebAuthn passkey registration and login (`webauthn` feature)
+//!
+//! Wraps the [`webauthn_rs`] crate so users can register passkeys (platform
+//! authenticators, security keys) and later log in with one instead of a
+//! password.
+//!
+//! - [`Passkeys`] runs both ceremonies for the relying party configured in
+//!   `security.webauthn`. Each ceremony's challenge state is kept in the
+//!   session as a [`PendingCeremony`] and rejected once it is older than
+//!   `challenge_timeout_secs`, or if it is used twice.
+//! - [`WebauthnCredential`] stores credentials in the `webauthn_credentials`
+//!   table, keyed by credential ID and linked to `user_id`. The stored
+//!   passkey is updated after each login so the signature counter keeps
+//!   moving forward.
+//!
+//! Passkeys always require user verification (PIN or biometric), so a passkey
+//! login establishes the session directly and skips the TOTP step.
+//!
+//! The SQL functions expect the table from
+//! `migrations/010_create_webauthn_credentials_table.sql`.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [security.webauthn]
+//! rp_id = "example.com"
+//! rp_origin = "https://example.com"
+//! rp_name = "Example"
+//! challenge_timeout_secs = 300
+//! ```
+//!
+//! # Example
+//!
+//! The handlers exchange JSON with `navigator.credentials.create()` and
+//! `navigator.credentials.get()` in the browser.
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::handlers::{
+//!     passkey_login_begin, passkey_login_finish, passkey_register_begin, passkey_register_finish,
+//! };
+//! use axum::{routing::post, Router};
+//!
+//! let app = Router::new()
+//!     .route("/account/passkeys/register/begin", post(passkey_register_begin))
+//!     .route("/account/passkeys/register/finish", post(passkey_register_finish))
+//!     .route("/login/passkey/begin", post(passkey_login_begin))
+//!     .route("/login/passkey/finish", post(passkey_login_finish));
+//! ```
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use webauthn_rs::prelude::{
+    AuthenticationResult, CreationChallengeResponse, Passkey, PasskeyAuthentication,
+    PasskeyRegistration, PublicKeyCredential, RegisterPublicKeyCredential,
+    RequestChallengeResponse, Url, Uuid, Webauthn, WebauthnBuilder, WebauthnError,
+};
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::auth::UserError;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use crate::htmx::state::ActonHtmxState;
+
+/// Session key holding the registration ceremony in progress
+pub(crate) const REGISTRATION_SESSION_KEY: &str = "webauthn_registration";
+
+/// Session key holding the authentication ceremony in progress
+pub(crate) const AUTHENTICATION_SESSION_KEY: &str = "webauthn_authentication";
+
+/// WebAuthn relying party configuration
+///
+/// `rp_id` must be the host of `rp_origin` or a parent domain of it.
+/// Changing `rp_id` invalidates every registered passkey.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [security.webauthn]
+/// rp_id = "example.com"
+/// rp_origin = "https://example.com"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebauthnConfig {
+    /// Relying party ID, normally the site's domain (default: `localhost`)
+    pub rp_id: String,
+
+    /// Origin the browser reports, including scheme and port (default:
+    /// `http://localhost:3000`)
+    pub rp_origin: String,
+
+    /// Name shown by the browser when creating a passkey
+    pub rp_name: String,
+
+    /// How long a registration or login challenge stays valid, in seconds
+    /// (default: 300)
+    pub challenge_timeout_secs: u64,
+}
+
+impl Default for WebauthnConfig {
+    fn default() -> Self {
+        Self {
+            rp_id: "localhost".to_string(),
+            rp_origin: "http://localhost:3000".to_string(),
+            rp_name: "acton-htmx".to_string(),
+            challenge_timeout_secs: 300,
+        }
+    }
+}
+
+/// Passkey ceremony failures
+#[derive(Debug, Error)]
+pub enum PasskeyError {
+    /// `security.webauthn` is not a valid relying party
+    #[error("Invalid WebAuthn configuration: {0}")]
+    Configuration(String),
+
+    /// The challenge is missing, already used, or older than the timeout
+    #[error("Passkey challenge expired")]
+    ChallengeExpired,
+
+    /// The authenticator's response failed verification
+    #[error("Passkey verification failed: {0}")]
+    Rejected(WebauthnError),
+
+    /// The credential is already registered
+    #[error("Passkey is already registered")]
+    AlreadyRegistered,
+}
+
+/// Challenge state of a ceremony waiting for the browser's response
+///
+/// Stored in the session between the begin and finish requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingCeremony<S> {
+    /// User the ceremony is for
+    pub user_id: i64,
+    /// Whether to remember the login (authentication only)
+    #[serde(default)]
+    pub remember: bool,
+    /// When the challenge was issued (Unix seconds)
+    pub issued_at: u64,
+    /// `webauthn-rs` state holding the challenge
+    pub state: S,
+}
+
+impl<S> PendingCeremony<S> {
+    /// Whether the challenge may still be answered at `now`
+    #[must_use]
+    pub const fn is_live(&self, timeout_secs: u64, now: u64) -> bool {
+        now.saturating_sub(self.issued_at) <= timeout_secs
+    }
+}
+
+/// Runs passkey ceremonies for the configured relying party
+#[derive(Debug)]
+pub struct Passkeys {
+    webauthn: Webauthn,
+    challenge_timeout_secs: u64,
+}
+
+impl Passkeys {
+    /// Create from a relying party configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasskeyError::Configuration`] if `rp_origin` is not a URL or
+    /// `rp_id` is not its domain or a parent of it.
+    pub fn new(config: &WebauthnConfig) -> Result<Self, PasskeyError> {
+        let origin = Url::parse(&config.rp_origin)
+            .map_err(|e| PasskeyError::Configuration(format!("rp_origin: {e}")))?;
+        let webauthn = WebauthnBuilder::new(&config.rp_id, &origin)
+            .and_then(|builder| builder.rp_name(&config.rp_name).build())
+            .map_err(|e| PasskeyError::Configuration(e.to_string()))?;
+
+        Ok(Self {
+            webauthn,
+            challenge_timeout_secs: config.challenge_timeout_secs,
+        })
+    }
+
+    /// Create from `security.webauthn`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasskeyError::Configuration`] if the configuration is invalid
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub fn from_state(state: &ActonHtmxState) -> Result<Self, PasskeyError> {
+        Self::new(&state.config().security.webauthn)
+    }
+
+    /// Start registering a new passkey for `user_id`
+    ///
+    /// `existing` credentials are excluded so an authenticator can't be
+    /// registered twice. Send the challenge to the browser and keep the
+    /// pending ceremony in the session.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasskeyError::Rejected`] if the challenge cannot be created
+    pub fn start_registration(
+        &self,
+        user_id: i64,
+        user_name: &str,
+        existing: &[Passkey],
+        now: u64,
+    ) -> Result<(CreationChallengeResponse, PendingCeremony<PasskeyRegistration>), PasskeyError> {
+        let exclude = existing.iter().map(|passkey| passkey.cred_id().clone()).collect();
+        let (challenge, state) = self
+            .webauthn
+            .start_passkey_registration(user_handle(user_id), user_name, user_name, Some(exclude))
+            .map_err(PasskeyError::Rejected)?;
+
+        Ok((
+            challenge,
+            PendingCeremony {
+                user_id,
+                remember: false,
+                issued_at: now,
+                state,
+            },
+        ))
+    }
+
+    /// Verify the browser's response to a registration challenge
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasskeyError::ChallengeExpired`] if the challenge timed out,
+    /// or [`PasskeyError::Rejected`] if the response doesn't verify.
+    pub fn finish_registration(
+        &self,
+        credential: &RegisterPublicKeyCredential,
+        pending: &PendingCeremony<PasskeyRegistration>,
+        now: u64,
+    ) -> Result<Passkey, PasskeyError> {
+        if !pending.is_live(self.challenge_timeout_secs, now) {
+            return Err(PasskeyError::ChallengeExpired);
+        }
+
+        self.webauthn
+            .finish_passkey_registration(credential, &pending.state)
+            .map_err(PasskeyError::Rejected)
+    }
+
+    /// Start a login for `user_id` with any of their `passkeys`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasskeyError::Rejected`] if the challenge cannot be created
+    pub fn start_authentication(
+        &self,
+        user_id: i64,
+        passkeys: &[Passkey],
+        remember: bool,
+        now: u64,
+    ) -> Result<(RequestChallengeResponse, PendingCeremony<PasskeyAuthentication>), PasskeyError> {
+        let (challenge, state) = self
+            .webauthn
+            .start_passkey_authentication(passkeys)
+            .map_err(PasskeyError::Rejected)?;
+
+        Ok((
+            challenge,
+            PendingCeremony {
+                user_id,
+                remember,
+                issued_at: now,
+                state,
+            },
+        ))
+    }
+
+    /// Verify the browser's response to a login challenge
+    ///
+    /// Pass the result to [`Passkey::update_credential`] for the matching
+    /// stored credential and save it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PasskeyError::ChallengeExpired`] if the challenge timed out,
+    /// or [`PasskeyError::Rejected`] if the assertion doesn't verify.
+    pub fn finish_authentication(
+        &self,
+        credential: &PublicKeyCredential,
+        pending: &PendingCeremony<PasskeyAuthentication>,
+        now: u64,
+    ) -> Result<AuthenticationResult, PasskeyError> {
+        if !pending.is_live(self.challenge_timeout_secs, now) {
+            return Err(PasskeyError::ChallengeExpired);
+        }
+
+        self.webauthn
+            .finish_passkey_authentication(credential, &pending.state)
+            .map_err(PasskeyError::Rejected)
+    }
+}
+
+/// WebAuthn user handle for a user ID
+///
+/// Stable per user and free of personal data, as the specification asks.
+#[must_use]
+pub const fn user_handle(user_id: i64) -> Uuid {
+    Uuid::from_u64_pair(0, u64::from_be_bytes(user_id.to_be_bytes()))
+}
+
+/// Encode a credential ID the way it is stored (unpadded base64url)
+#[must_use]
+pub fn encode_credential_id(credential_id: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(credential_id)
+}
+
+/// A passkey registered to a user
+#[derive(Debug, Clone)]
+pub struct WebauthnCredential {
+    /// Credential ID, unpadded base64url
+    pub credential_id: String,
+    /// User the passkey logs in as
+    pub user_id: i64,
+    /// Public key, counter, and flags
+    pub passkey: Passkey,
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+type WebauthnCredentialRow = (String, i64, String);
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+impl WebauthnCredential {
+    fn from_row((credential_id, user_id, passkey): WebauthnCredentialRow) -> Result<Self, UserError> {
+        let passkey = serde_json::from_str(&passkey).map_err(|e| {
+            UserError::DatabaseError(sqlx::Error::Decode(Box::new(e)))
+        })?;
+
+        Ok(Self {
+            credential_id,
+            user_id,
+            passkey,
+        })
+    }
+
+    fn passkey_json(passkey: &Passkey) -> Result<String, UserError> {
+        serde_json::to_string(passkey)
+            .map_err(|e| UserError::DatabaseError(sqlx::Error::Encode(Box::new(e))))
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl WebauthnCredential {
+    /// Store a newly registered passkey for `user_id`
+    ///
+    /// Returns `false` if the credential is already registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn insert(user_id: i64, passkey: &Passkey, pool: &sqlx::PgPool) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"
+            INSERT INTO webauthn_credentials (credential_id, user_id, passkey)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (credential_id) DO NOTHING
+            ",
+        )
+        .bind(encode_credential_id(passkey.cred_id()))
+        .bind(user_id)
+        .bind(Self::passkey_json(passkey)?)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// All passkeys registered to a user
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn for_user(user_id: i64, pool: &sqlx::PgPool) -> Result<Vec<Self>, UserError> {
+        let rows: Vec<WebauthnCredentialRow> = sqlx::query_as(
+            r"
+            SELECT credential_id, user_id, passkey
+            FROM webauthn_credentials
+            WHERE user_id = $1
+            ORDER BY created_at
+            ",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.into_iter().map(Self::from_row).collect()
+    }
+
+    /// Find a passkey by credential ID
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn find(credential_id: &str, pool: &sqlx::PgPool) -> Result<Option<Self>, UserError> {
+        let row: Option<WebauthnCredentialRow> = sqlx::query_as(
+            r"
+            SELECT credential_id, user_id, passkey
+            FROM webauthn_credentials
+            WHERE credential_id = $1
+            ",
+        )
+        .bind(credential_id)
+        .fetch_optional(pool)
+        .await?;
+
+        row.map(Self::from_row).transpose()
+    }
+
+    /// Save a passkey after a login and record when it was used
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn record_use(passkey: &Passkey, pool: &sqlx::PgPool) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"
+            UPDATE webauthn_credentials
+            SET passkey = $2, last_used_at = NOW()
+            WHERE credential_id = $1
+            ",
+        )
+        .bind(encode_credential_id(passkey.cred_id()))
+        .bind(Self::passkey_json(passkey)?)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove one of a user's passkeys
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails
+    pub async fn delete(user_id: i64, credential_id: &str, pool: &sqlx::PgPool) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            "DELETE FROM webauthn_credentials WHERE user_id = $1 AND credential_id = $2",
+        )
+        .bind(user_id)
+        .bind(credential_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl WebauthnCredential {
+    /// Store a newly registered passkey for `user_id` (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn insert(
+        user_id: i64,
+        passkey: &Passkey,
+        pool: &sqlx::SqlitePool,
+    ) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"INSERT INTO webauthn_credentials (credential_id, user_id, passkey)
+              VALUES (?, ?, ?)
+              ON CONFLICT (credential_id) DO NOTHING",
+        )
+        .bind(encode_credential_id(passkey.cred_id()))
+        .bind(user_id)
+        .bind(Self::passkey_json(passkey)?)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// All passkeys registered to a user (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn for_user(user_id: i64, pool: &sqlx::SqlitePool) -> Result<Vec<Self>, UserError> {
+        let rows: Vec<WebauthnCredentialRow> = sqlx::query_as(
+            r"SELECT credential_id, user_id, passkey FROM webauthn_credentials
+              WHERE user_id = ? ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        rows.into_iter().map(Self::from_row).collect()
+    }
+
+    /// Find a passkey by credential ID (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn find(
+        credential_id: &str,
+        pool: &sqlx::SqlitePool,
+    ) -> Result<Option<Self>, UserError> {
+        let row: Option<WebauthnCredentialRow> = sqlx::query_as(
+            r"SELECT credential_id, user_id, passkey FROM webauthn_credentials
+              WHERE credential_id = ?",
+        )
+        .bind(credential_id)
+        .fetch_optional(pool)
+        .await?;
+
+        row.map(Self::from_row).transpose()
+    }
+
+    /// Save a passkey after a login and record when it was used (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn record_use(passkey: &Passkey, pool: &sqlx::SqlitePool) -> Result<bool, UserError> {
+        let result = sqlx::query(
+            r"UPDATE webauthn_credentials SET passkey = ?, last_used_at = CURRENT_TIMESTAMP
+              WHERE credential_id = ?",
+        )
+        .bind(Self::passkey_json(passkey)?)
+        .bind(encode_credential_id(passkey.cred_id()))
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove one of a user's passkeys (SQLite)
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the database operation fails.
+    pub async fn delete(
+        user_id: i64,
+        credential_id: &str,
+        pool: &sqlx::SqlitePool,
+    ) -> Result<bool, UserError> {
+        let result =
+            sqlx::query("DELETE FROM webauthn_credentials WHERE user_id = ? AND credential_id = ?")
+                .bind(user_id)
+                .bind(credential_id)
+                .execute(pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Registration vector from webauthn-rs-core (`test_edge_touchid_rk_verified`):
+    // a Touch ID passkey created in Edge for http://localhost:8080
+    const VECTOR_CHALLENGE: &str = "bCE-p6LqJD-w56E6Kel1ndL0exzCZCJEIAG38GThtjA";
+    const VECTOR_CREDENTIAL_ID: &str = "AWtT-NSYHNmZjP2R9JAbBmwf3sWMxs_L4_O2XoIvI8HY-rGPjA";
+    const VECTOR_RESPONSE: &str = r#"{
+        "id": "AWtT-NSYHNmZjP2R9JAbBmwf3sWMxs_L4_O2XoIvI8HY-rGPjA",
+        "rawId": "AWtT-NSYHNmZjP2R9JAbBmwf3sWMxs_L4_O2XoIvI8HY-rGPjA",
+        "response": {
+            "attestationObject": "o2NmbXRmcGFja2VkZ2F0dFN0bXSiY2FsZyZjc2lnWEgwRgIhAOpCgJUKTloGtzqjcnCSL8ywG1baTYd5WChecwfd-A0lAiEAuz9KEXKBM--RgNh1J7-CBu9PD1A6NBIYOa59xvguirFoYXV0aERhdGFYqUmWDeWIDoxodDQXD2R2YFuP5K65ooYyx5lc87qDHZdjRWJM2x-tzgACNbzGCmSLCyXx8FUDACUBa1P41Jgc2ZmM_ZH0kBsGbB_exYzGz8vj87Zegi8jwdj6sY-MpQECAyYgASFYII__M-4cJoL1GDCkdTFmjmcZLv2J5BDcgxHlNKVL4NrtIlggc5greCirh25w_RyOmgkJlV7-k-smBNca2TP1l5TAjak",
+            "clientDataJSON": "eyJ0eXBlIjoid2ViYXV0aG4uY3JlYXRlIiwiY2hhbGxlbmdlIjoiYkNFLXA2THFKRC13NTZFNktlbDFuZEwwZXh6Q1pDSkVJQUczOEdUaHRqQSIsIm9yaWdpbiI6Imh0dHA6Ly9sb2NhbGhvc3Q6ODA4MCIsImNyb3NzT3JpZ2luIjpmYWxzZX0"
+        },
+        "type": "public-key"
+    }"#;
+
+    fn passkeys() -> Passkeys {
+        Passkeys::new(&WebauthnConfig {
+            rp_origin: "http://localhost:8080".to_string(),
+            ..WebauthnConfig::default()
+        })
+        .unwrap()
+    }
+
+    /// Start a registration, then swap in the vector's challenge
+    fn vector_registration(passkeys: &Passkeys, now: u64) -> PendingCeremony<PasskeyRegistration> {
+        let (_, pending) = passkeys.start_registration(1, "ada@example.com", &[], now).unwrap();
+        let mut state = serde_json::to_value(&pending.state).unwrap();
+        state["rs"]["challenge"] = VECTOR_CHALLENGE.into();
+
+        PendingCeremony {
+            state: serde_json::from_value(state).unwrap(),
+            ..pending
+        }
+    }
+
+    fn vector_passkey() -> Passkey {
+        let passkeys = passkeys();
+        let pending = vector_registration(&passkeys, 1_000);
+        let response = serde_json::from_str(VECTOR_RESPONSE).unwrap();
+        passkeys.finish_registration(&response, &pending, 1_000).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_relying_party_rejected() {
+        let mismatched = WebauthnConfig {
+            rp_id: "example.com".to_string(),
+            ..WebauthnConfig::default()
+        };
+        assert!(matches!(
+            Passkeys::new(&mismatched),
+            Err(PasskeyError::Configuration(_))
+        ));
+
+        let not_a_url = WebauthnConfig {
+            rp_origin: "localhost".to_string(),
+            ..WebauthnConfig::default()
+        };
+        assert!(matches!(
+            Passkeys::new(&not_a_url),
+            Err(PasskeyError::Configuration(_))
+        ));
+    }
+
+    #[test]
+    fn test_registration_challenge_issued() {
+        let (challenge, pending) = passkeys()
+            .start_registration(42, "ada@example.com", &[], 1_000)
+            .unwrap();
+
+        assert_eq!(pending.user_id, 42);
+        assert_eq!(pending.issued_at, 1_000);
+        let challenge = serde_json::to_value(&challenge).unwrap();
+        assert_eq!(challenge["publicKey"]["rp"]["id"], "localhost");
+        assert_eq!(challenge["publicKey"]["user"]["name"], "ada@example.com");
+        assert_eq!(
+            challenge["publicKey"]["authenticatorSelection"]["userVerification"],
+            "required"
+        );
+
+        // Each ceremony gets a fresh challenge
+        let (other, _) = passkeys()
+            .start_registration(42, "ada@example.com", &[], 1_000)
+            .unwrap();
+        assert_ne!(
+            challenge["publicKey"]["challenge"],
+            serde_json::to_value(&other).unwrap()["publicKey"]["challenge"]
+        );
+    }
+
+    #[test]
+    fn test_pending_ceremony_session_roundtrip() {
+        let (_, pending) = passkeys()
+            .start_registration(42, "ada@example.com", &[], 1_000)
+            .unwrap();
+
+        let json = serde_json::to_string(&pending).unwrap();
+        let restored: PendingCeremony<PasskeyRegistration> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.user_id, 42);
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+    }
+
+    #[test]
+    fn test_finish_registration_with_vector() {
+        let passkey = vector_passkey();
+        assert_eq!(encode_credential_id(passkey.cred_id()), VECTOR_CREDENTIAL_ID);
+
+        // An authenticator registered once is excluded from the next challenge
+        let (challenge, _) = passkeys()
+            .start_registration(1, "ada@example.com", &[passkey], 2_000)
+            .unwrap();
+        let challenge = serde_json::to_value(&challenge).unwrap();
+        assert_eq!(
+            challenge["publicKey"]["excludeCredentials"][0]["id"],
+            VECTOR_CREDENTIAL_ID
+        );
+    }
+
+    #[test]
+    fn test_expired_registration_challenge_rejected() {
+        let passkeys = passkeys();
+        let pending = vector_registration(&passkeys, 1_000);
+        let response = serde_json::from_str(VECTOR_RESPONSE).unwrap();
+
+        assert!(matches!(
+            passkeys.finish_registration(&response, &pending, 1_301),
+            Err(PasskeyError::ChallengeExpired)
+        ));
+        assert!(passkeys.finish_registration(&response, &pending, 1_300).is_ok());
+    }
+
+    #[test]
+    fn test_wrong_registration_challenge_rejected() {
+        let passkeys = passkeys();
+        let (_, pending) = passkeys.start_registration(1, "ada@example.com", &[], 1_000).unwrap();
+        let response = serde_json::from_str(VECTOR_RESPONSE).unwrap();
+
+        assert!(matches!(
+            passkeys.finish_registration(&response, &pending, 1_000),
+            Err(PasskeyError::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_authentication_challenge_issued_and_expires() {
+        let (challenge, pending) = passkeys()
+            .start_authentication(1, &[vector_passkey()], true, 1_000)
+            .unwrap();
+        assert!(pending.remember);
+        let challenge = serde_json::to_value(&challenge).unwrap();
+        assert_eq!(
+            challenge["publicKey"]["allowCredentials"][0]["id"],
+            VECTOR_CREDENTIAL_ID
+        );
+
+        assert!(pending.is_live(300, 1_300));
+        assert!(!pending.is_live(300, 1_301));
+    }
+
+    #[test]
+    fn test_user_handle_is_stable() {
+        assert_eq!(user_handle(42), user_handle(42));
+        assert_ne!(user_handle(42), user_handle(43));
+        assert_ne!(user_handle(-1), user_handle(1));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_credential_persistence() {
+        let pool = crate::htmx::testing::create_sqlite_pool_with_migration(include_str!(
+            "../../../../migrations/010_create_webauthn_credentials_table.sql"
+        ))
+        .await
+        .unwrap();
+
+        let passkey = vector_passkey();
+        assert!(WebauthnCredential::insert(7, &passkey, &pool).await.unwrap());
+        assert!(!WebauthnCredential::insert(8, &passkey, &pool).await.unwrap());
+
+        let stored = WebauthnCredential::find(VECTOR_CREDENTIAL_ID, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.user_id, 7);
+        assert_eq!(stored.passkey.cred_id(), passkey.cred_id());
+        assert_eq!(
+            serde_json::to_value(&stored.passkey).unwrap(),
+            serde_json::to_value(&passkey).unwrap()
+        );
+
+        let for_user = WebauthnCredential::for_user(7, &pool).await.unwrap();
+        assert_eq!(for_user.len(), 1);
+        assert!(WebauthnCredential::for_user(8, &pool).await.unwrap().is_empty());
+
+        assert!(WebauthnCredential::record_use(&stored.passkey, &pool).await.unwrap());
+
+        assert!(!WebauthnCredential::delete(8, VECTOR_CREDENTIAL_ID, &pool).await.unwrap());
+        assert!(WebauthnCredential::delete(7, VECTOR_CREDENTIAL_ID, &pool).await.unwrap());
+        assert!(WebauthnCredential::find(VECTOR_CREDENTIAL_ID, &pool).await.unwrap().is_none());
+    }
+}
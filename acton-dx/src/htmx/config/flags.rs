@@ -0,0 +1,211 @@
+//! Runtime feature flags
+//!
+//! [`FeatureFlags`] starts from the `[features]` section of the configuration
+//! and can be changed while the application runs. Unknown flags are off.
+//! Code that needs to react to a change subscribes with
+//! [`FeatureFlags::watch`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use acton_dx::htmx::config::FeatureFlags;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let flags = FeatureFlags::default();
+//! let mut new_checkout = flags.watch("new_checkout");
+//!
+//! flags.set("new_checkout", true);
+//! new_checkout.changed().await.unwrap();
+//! assert!(*new_checkout.borrow());
+//! assert!(flags.is_enabled("new_checkout"));
+//! # }
+//! ```
+
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::fs;
+use tokio::sync::watch;
+
+use crate::htmx::middleware::MAINTENANCE_FLAG;
+
+/// Shared, mutable set of feature flags
+///
+/// Cloning is cheap; clones share the same flags.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureFlags {
+    /// Each flag's current value lives in its watch channel
+    flags: Arc<RwLock<HashMap<String, watch::Sender<bool>>>>,
+}
+
+impl FeatureFlags {
+    /// Create flags with the given initial values
+    #[must_use]
+    pub fn new(initial: &HashMap<String, bool>) -> Self {
+        let flags = initial
+            .iter()
+            .map(|(key, &enabled)| (key.clone(), watch::Sender::new(enabled)))
+            .collect();
+        Self {
+            flags: Arc::new(RwLock::new(flags)),
+        }
+    }
+
+    /// Whether `key` is enabled; unknown flags are off
+    #[must_use]
+    pub fn is_enabled(&self, key: &str) -> bool {
+        self.flags
+            .read()
+            .get(key)
+            .is_some_and(|flag| *flag.borrow())
+    }
+
+    /// Enable or disable `key`, notifying watchers if the value changed
+    pub fn set(&self, key: &str, enabled: bool) {
+        let mut flags = self.flags.write();
+        if let Some(flag) = flags.get(key) {
+            flag.send_if_modified(|current| std::mem::replace(current, enabled) != enabled);
+        } else {
+            flags.insert(key.to_string(), watch::Sender::new(enabled));
+        }
+        drop(flags);
+    }
+
+    /// Subscribe to changes of `key`
+    ///
+    /// The receiver starts with the current value and is notified each time
+    /// [`set`](Self::set) changes it.
+    #[must_use]
+    pub fn watch(&self, key: &str) -> watch::Receiver<bool> {
+        if let Some(flag) = self.flags.read().get(key) {
+            return flag.subscribe();
+        }
+        self.flags
+            .write()
+            .entry(key.to_string())
+            .or_insert_with(|| watch::Sender::new(false))
+            .subscribe()
+    }
+
+    /// Current value of every known flag
+    #[must_use]
+    pub fn snapshot(&self) -> HashMap<String, bool> {
+        self.flags
+            .read()
+            .iter()
+            .map(|(key, flag)| (key.clone(), *flag.borrow()))
+            .collect()
+    }
+
+    /// Write the current flags to the `[features]` section of a TOML file
+    ///
+    /// Other sections of an existing file are kept. The file and its parent
+    /// directory are created if missing. The
+    /// [`MAINTENANCE_FLAG`](crate::htmx::middleware::MAINTENANCE_FLAG) is
+    /// left out so a restart never boots into maintenance mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be read, parsed, or written
+    pub async fn persist(&self, path: &Path) -> anyhow::Result<()> {
+        let mut document = match fs::read_to_string(path).await {
+            Ok(contents) => contents.parse::<toml::Table>()?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml::Table::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let features = self
+            .snapshot()
+            .into_iter()
+            .filter(|(key, _)| key != MAINTENANCE_FLAG)
+            .map(|(key, enabled)| (key, toml::Value::Boolean(enabled)))
+            .collect();
+        document.insert("features".to_string(), toml::Value::Table(features));
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(path, toml::to_string_pretty(&document)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_flags_are_off() {
+        let flags = FeatureFlags::new(&HashMap::from([("beta".to_string(), true)]));
+        assert!(flags.is_enabled("beta"));
+        assert!(!flags.is_enabled("missing"));
+
+        // Watching an unknown flag doesn't enable it
+        let receiver = flags.watch("missing");
+        assert!(!*receiver.borrow());
+        assert!(!flags.is_enabled("missing"));
+    }
+
+    #[test]
+    fn test_runtime_toggling_is_shared_by_clones() {
+        let flags = FeatureFlags::default();
+        let clone = flags.clone();
+
+        clone.set("beta", true);
+        assert!(flags.is_enabled("beta"));
+
+        flags.set("beta", false);
+        assert!(!clone.is_enabled("beta"));
+    }
+
+    #[tokio::test]
+    async fn test_watchers_are_notified() {
+        let flags = FeatureFlags::default();
+        let mut receiver = flags.watch("beta");
+
+        flags.set("beta", true);
+        receiver.changed().await.unwrap();
+        assert!(*receiver.borrow_and_update());
+
+        // Setting the same value again is not a change
+        flags.set("beta", true);
+        assert!(!receiver.has_changed().unwrap());
+
+        flags.set("beta", false);
+        receiver.changed().await.unwrap();
+        assert!(!*receiver.borrow_and_update());
+    }
+
+    #[tokio::test]
+    async fn test_persist_keeps_other_sections() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app/config.toml");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "[htmx]\nrequest_timeout_ms = 1000\n").unwrap();
+
+        let flags = FeatureFlags::default();
+        flags.set("beta", true);
+        flags.persist(&path).await.unwrap();
+
+        let document: toml::Table = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(document["htmx"]["request_timeout_ms"].as_integer(), Some(1000));
+        assert_eq!(document["features"]["beta"].as_bool(), Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_persist_skips_maintenance_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let flags = FeatureFlags::default();
+        flags.set("beta", true);
+        flags.set(MAINTENANCE_FLAG, true);
+        flags.persist(&path).await.unwrap();
+
+        let document: toml::Table = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+        assert_eq!(document["features"]["beta"].as_bool(), Some(true));
+        assert!(document["features"].get(MAINTENANCE_FLAG).is_none());
+    }
+}
@@ -55,16 +55,30 @@
 //! let csrf_enabled = config.security.csrf_enabled;
 //! ```
 
+pub mod flags;
+
+pub use flags::FeatureFlags;
+
 use figment::{
     providers::{Env, Format, Toml},
     Figment,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 #[cfg(feature = "cedar")]
 use std::time::Duration;
 
+use crate::htmx::auth::password::PasswordPolicy;
+use crate::htmx::middleware::body_limit::DEFAULT_MAX_BODY_BYTES;
+use crate::htmx::middleware::cookie_signer::DEV_SECRET_KEY;
+use crate::htmx::middleware::cors::CorsConfig;
+use crate::htmx::auth::pwned::PwnedPasswordConfig;
+use crate::htmx::auth::password_reset::PasswordResetConfig;
+use crate::htmx::auth::totp::TotpConfig;
+use crate::htmx::auth::verification::EmailVerificationConfig;
+#[cfg(feature = "webauthn")]
+use crate::htmx::auth::webauthn::WebauthnConfig;
 use crate::htmx::oauth2::types::OAuthConfig;
 
 /// HTMX-specific configuration
@@ -133,6 +147,12 @@ pub struct SecuritySettings {
     /// Session maximum age in seconds
     pub session_max_age_secs: u64,
 
+    /// Most sessions a user may be logged in with at once (default: unlimited)
+    ///
+    /// Logging in beyond the limit signs out the user's least recently active
+    /// sessions.
+    pub max_sessions_per_user: Option<usize>,
+
     /// Enable secure cookies (HTTPS only)
     pub secure_cookies: bool,
 
@@ -144,6 +164,46 @@ pub struct SecuritySettings {
 
     /// Rate limiting configuration
     pub rate_limit: RateLimitConfig,
+
+    /// Cross-origin resource sharing; no other origins are allowed by default
+    pub cors: CorsConfig,
+
+    /// Largest request body accepted, in bytes (default: 2MB)
+    ///
+    /// Routes that accept uploads can allow more with
+    /// [`BodyLimitLayer::with_route_limit`](crate::htmx::middleware::BodyLimitLayer::with_route_limit).
+    pub max_body_bytes: usize,
+
+    /// Secret key used to sign cookies (session IDs, flash messages,
+    /// double-submit CSRF tokens, remember-me tokens) and verification links
+    ///
+    /// Each use signs with its own key derived from this one (see
+    /// [`CookieSigner::for_purpose`](crate::htmx::middleware::CookieSigner::for_purpose)),
+    /// so a value signed for one can't be replayed as another.
+    ///
+    /// Must be identical on every instance serving the application and is
+    /// required in release builds. Debug builds fall back to a fixed
+    /// development key when unset.
+    pub secret_key: Option<String>,
+
+    /// Password strength rules enforced at registration and password reset
+    pub password_policy: PasswordPolicy,
+
+    /// Reject passwords found in known data breaches at registration
+    pub pwned_passwords: PwnedPasswordConfig,
+
+    /// Email new users a link confirming their address
+    pub email_verification: EmailVerificationConfig,
+
+    /// Password reset emails and token lifetime
+    pub password_reset: PasswordResetConfig,
+
+    /// TOTP two-factor authentication
+    pub totp: TotpConfig,
+
+    /// WebAuthn relying party for passkey login (requires webauthn feature)
+    #[cfg(feature = "webauthn")]
+    pub webauthn: WebauthnConfig,
 }
 
 impl Default for SecuritySettings {
@@ -151,10 +211,89 @@ impl Default for SecuritySettings {
         Self {
             csrf_enabled: true,
             session_max_age_secs: 86400, // 24 hours
+            max_sessions_per_user: None,
             secure_cookies: !cfg!(debug_assertions),
             same_site: SameSitePolicy::Lax,
             security_headers_enabled: true,
             rate_limit: RateLimitConfig::default(),
+            cors: CorsConfig::default(),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            secret_key: None,
+            password_policy: PasswordPolicy::default(),
+            pwned_passwords: PwnedPasswordConfig::default(),
+            email_verification: EmailVerificationConfig::default(),
+            password_reset: PasswordResetConfig::default(),
+            totp: TotpConfig::default(),
+            #[cfg(feature = "webauthn")]
+            webauthn: WebauthnConfig::default(),
+        }
+    }
+}
+
+/// Request logging configuration
+///
+/// Header and field names are matched case-insensitively. Matching fields
+/// are masked wherever they appear: query strings, form bodies, and JSON
+/// bodies at any nesting depth.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [logging]
+/// log_bodies = true
+/// max_body_bytes = 4096
+/// redact_headers = ["authorization", "cookie", "x-api-key"]
+/// redact_fields = ["password", "ssn"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingSettings {
+    /// Include request bodies (form and JSON only) in request logs
+    pub log_bodies: bool,
+
+    /// Largest request body that is logged; larger bodies are omitted
+    pub max_body_bytes: usize,
+
+    /// Header names whose values are masked
+    pub redact_headers: Vec<String>,
+
+    /// Query, form, and JSON field names whose values are masked
+    pub redact_fields: Vec<String>,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            log_bodies: false,
+            max_body_bytes: 4096,
+            redact_headers: [
+                "authorization",
+                "proxy-authorization",
+                "cookie",
+                "set-cookie",
+                "x-csrf-token",
+                "x-api-key",
+            ]
+            .map(String::from)
+            .to_vec(),
+            redact_fields: [
+                "password",
+                "password_confirmation",
+                "current_password",
+                "new_password",
+                "_csrf_token",
+                "token",
+                "access_token",
+                "refresh_token",
+                "id_token",
+                "client_secret",
+                "secret",
+                "api_key",
+                "code",
+                "otp",
+            ]
+            .map(String::from)
+            .to_vec(),
         }
     }
 }
@@ -357,6 +496,140 @@ impl CedarConfig {
     }
 }
 
+/// Redis connection configuration
+///
+/// When present, [`ActonHtmxState`](crate::htmx::state::ActonHtmxState)
+/// creates a Redis pool and stores sessions in Redis so they are shared
+/// between instances.
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [redis]
+/// url = "redis://localhost:6379"
+/// ```
+#[cfg(feature = "redis")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisSettings {
+    /// Redis connection URL
+    pub url: String,
+}
+
+/// Background job settings
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [jobs]
+/// compress_threshold_bytes = 4096
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobsSettings {
+    /// Serialized jobs larger than this are gzip-compressed when persisted
+    /// to Redis (requires redis feature)
+    pub compress_threshold_bytes: usize,
+}
+
+impl Default for JobsSettings {
+    fn default() -> Self {
+        Self {
+            compress_threshold_bytes: 4096,
+        }
+    }
+}
+
+/// Environment the application is deployed to
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeployEnv {
+    /// Local development; no settings are rejected
+    #[default]
+    Development,
+    /// Production; insecure settings are rejected
+    Production,
+}
+
+/// What [`ActonHtmxState::with_config`] does with validation problems
+///
+/// [`ActonHtmxState::with_config`]: crate::htmx::state::ActonHtmxState::with_config
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigValidation {
+    /// Skip validation
+    Off,
+    /// Log every problem and start anyway
+    Warn,
+    /// Log every problem and refuse to start on errors
+    #[default]
+    Enforce,
+}
+
+/// Deployment settings
+///
+/// # Example Configuration
+///
+/// ```toml
+/// [deploy]
+/// env = "production"
+/// validation = "enforce"  # or "warn", "off"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeploySettings {
+    /// Environment the application runs in
+    pub env: DeployEnv,
+
+    /// How configuration problems are handled at startup
+    pub validation: ConfigValidation,
+}
+
+/// Problem found by [`ActonHtmxConfig::validate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigWarning {
+    /// `security.csrf_enabled` is false
+    CsrfDisabled,
+    /// `security.secure_cookies` is false
+    InsecureCookies,
+    /// `security.same_site` is `none` without secure cookies
+    SameSiteNoneWithoutSecure,
+    /// `security.rate_limit.enabled` is false
+    RateLimitDisabled,
+    /// `security.secret_key` is unset or the development key
+    DefaultSecretKey,
+}
+
+impl ConfigWarning {
+    /// Whether the problem prevents a production deployment
+    ///
+    /// Everything except [`Self::DefaultSecretKey`] is an error; a missing key
+    /// already stops release builds when the cookie signer is created.
+    #[must_use]
+    pub const fn is_error(self) -> bool {
+        !matches!(self, Self::DefaultSecretKey)
+    }
+}
+
+impl std::fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::CsrfDisabled => "CSRF protection is disabled (security.csrf_enabled = false)",
+            Self::InsecureCookies => {
+                "cookies are sent over plain HTTP (security.secure_cookies = false)"
+            }
+            Self::SameSiteNoneWithoutSecure => {
+                "security.same_site = \"none\" requires security.secure_cookies = true"
+            }
+            Self::RateLimitDisabled => {
+                "rate limiting is disabled (security.rate_limit.enabled = false)"
+            }
+            Self::DefaultSecretKey => {
+                "security.secret_key is unset or the development key; cookies can be forged"
+            }
+        })
+    }
+}
+
 /// Complete acton-dx configuration
 ///
 /// Combines framework configuration with HTMX-specific settings.
@@ -375,6 +648,10 @@ pub struct ActonHtmxConfig {
     #[serde(default)]
     pub security: SecuritySettings,
 
+    /// Request logging settings
+    #[serde(default)]
+    pub logging: LoggingSettings,
+
     /// OAuth2 configuration
     #[serde(default)]
     pub oauth2: OAuthConfig,
@@ -384,12 +661,85 @@ pub struct ActonHtmxConfig {
     #[serde(default)]
     pub cedar: Option<CedarConfig>,
 
+    /// Redis configuration (optional, requires redis feature)
+    #[cfg(feature = "redis")]
+    #[serde(default)]
+    pub redis: Option<RedisSettings>,
+
+    /// Background job settings
+    #[serde(default)]
+    pub jobs: JobsSettings,
+
+    /// Deployment environment and startup validation
+    #[serde(default)]
+    pub deploy: DeploySettings,
+
     /// Feature flags
+    ///
+    /// Initial values for the runtime [`FeatureFlags`] in application state
     #[serde(default)]
     pub features: HashMap<String, bool>,
 }
 
 impl ActonHtmxConfig {
+    /// Check the configuration for settings that are unsafe in `env`
+    ///
+    /// Production rejects disabled CSRF protection, insecure cookies,
+    /// `SameSite=None` without secure cookies, and disabled rate limiting,
+    /// and warns about a missing or development signing key. Development
+    /// accepts everything.
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found; use [`ConfigWarning::is_error`] to tell
+    /// errors from warnings
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use acton_dx::htmx::config::{ActonHtmxConfig, ConfigWarning, DeployEnv};
+    ///
+    /// let mut config = ActonHtmxConfig::default();
+    /// config.security.csrf_enabled = false;
+    ///
+    /// assert!(config.validate(DeployEnv::Development).is_ok());
+    /// let problems = config.validate(DeployEnv::Production).unwrap_err();
+    /// assert!(problems.contains(&ConfigWarning::CsrfDisabled));
+    /// ```
+    pub fn validate(&self, env: DeployEnv) -> Result<(), Vec<ConfigWarning>> {
+        if env == DeployEnv::Development {
+            return Ok(());
+        }
+
+        let security = &self.security;
+        let mut problems = Vec::new();
+        if !security.csrf_enabled {
+            problems.push(ConfigWarning::CsrfDisabled);
+        }
+        if !security.secure_cookies {
+            problems.push(ConfigWarning::InsecureCookies);
+            if matches!(security.same_site, SameSitePolicy::None) {
+                problems.push(ConfigWarning::SameSiteNoneWithoutSecure);
+            }
+        }
+        if !security.rate_limit.enabled {
+            problems.push(ConfigWarning::RateLimitDisabled);
+        }
+        let default_key = security
+            .secret_key
+            .as_deref()
+            .is_none_or(|key| key.is_empty() || key == DEV_SECRET_KEY);
+        if default_key {
+            problems.push(ConfigWarning::DefaultSecretKey);
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
     /// Load configuration for a specific service
     ///
     /// Searches for configuration in XDG-compliant locations with precedence:
@@ -418,11 +768,81 @@ impl ActonHtmxConfig {
     /// # }
     /// ```
     pub fn load_for_service(service_name: &str) -> anyhow::Result<Self> {
+        let figment = Self::base_figment(service_name, Path::new("."))?
+            // 1. Environment variables (highest priority, double underscore for nesting)
+            .merge(Env::prefixed("ACTON_").split("__").lowercase(true));
+
+        let config = figment.extract()?;
+        Ok(config)
+    }
+
+    /// Load configuration for a service in a named environment
+    ///
+    /// Like [`load_for_service`](Self::load_for_service), with
+    /// `./config/{env}.toml` (e.g. `config/production.toml`) merged above the
+    /// base files:
+    /// 1. Environment variables (`ACTON_*`, use `__` for nesting)
+    /// 2. `./config/{env}.toml`
+    /// 3. `./config.toml`
+    /// 4. `~/.config/acton-dx/{service_name}/config.toml`
+    /// 5. `/etc/acton-dx/{service_name}/config.toml`
+    /// 6. Defaults
+    ///
+    /// A missing environment file is skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a configuration file cannot be read or parsed, or
+    /// if the merged values cannot be converted
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use acton_htmx::config::ActonHtmxConfig;
+    ///
+    /// # fn example() -> anyhow::Result<()> {
+    /// // `ACTON_ENV=production` selects config/production.toml
+    /// let env = ActonHtmxConfig::current_env();
+    /// let config = ActonHtmxConfig::load_for_env("my-app", &env)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_for_env(service_name: &str, env: &str) -> anyhow::Result<Self> {
+        Self::load_layered(service_name, Path::new("."), env)
+    }
+
+    /// Environment named by `ACTON_ENV`, or `development` when unset
+    #[must_use]
+    pub fn current_env() -> String {
+        std::env::var("ACTON_ENV")
+            .ok()
+            .filter(|env| !env.is_empty())
+            .unwrap_or_else(|| "development".to_string())
+    }
+
+    /// Merge `{project_dir}/config/{env}.toml` and environment variables
+    /// over the base files
+    fn load_layered(service_name: &str, project_dir: &Path, env: &str) -> anyhow::Result<Self> {
+        let mut figment = Self::base_figment(service_name, project_dir)?;
+
+        let env_config = project_dir.join("config").join(format!("{env}.toml"));
+        if env_config.exists() {
+            figment = figment.merge(Toml::file(&env_config));
+        }
+
+        let config = figment
+            .merge(Env::prefixed("ACTON_").split("__").lowercase(true))
+            .extract()?;
+        Ok(config)
+    }
+
+    /// Defaults merged with the system, user, and project config files
+    fn base_figment(service_name: &str, project_dir: &Path) -> anyhow::Result<Figment> {
         let mut figment = Figment::new()
-            // 5. Start with defaults (lowest priority)
+            // Start with defaults (lowest priority)
             .merge(Toml::string(&toml::to_string(&Self::default())?));
 
-        // 4. System config: /etc/acton-dx/{service_name}/config.toml
+        // System config: /etc/acton-dx/{service_name}/config.toml
         let system_config = PathBuf::from("/etc/acton-dx")
             .join(service_name)
             .join("config.toml");
@@ -430,23 +850,19 @@ impl ActonHtmxConfig {
             figment = figment.merge(Toml::file(&system_config));
         }
 
-        // 3. User config: ~/.config/acton-dx/{service_name}/config.toml
+        // User config: ~/.config/acton-dx/{service_name}/config.toml
         let user_config = Self::recommended_path(service_name);
         if user_config.exists() {
             figment = figment.merge(Toml::file(&user_config));
         }
 
-        // 2. Local config: ./config.toml
-        let local_config = PathBuf::from("./config.toml");
+        // Project config: ./config.toml
+        let local_config = project_dir.join("config.toml");
         if local_config.exists() {
             figment = figment.merge(Toml::file(&local_config));
         }
 
-        // 1. Environment variables (highest priority, double underscore for nesting)
-        figment = figment.merge(Env::prefixed("ACTON_").split("__").lowercase(true));
-
-        let config = figment.extract()?;
-        Ok(config)
+        Ok(figment)
     }
 
     /// Load configuration from a specific file
@@ -546,6 +962,7 @@ mod tests {
         assert!(config.htmx.auto_vary);
         assert!(config.security.csrf_enabled);
         assert_eq!(config.security.session_max_age_secs, 86400);
+        assert!(config.security.max_sessions_per_user.is_none());
     }
 
     #[test]
@@ -561,6 +978,9 @@ mod tests {
         let security = SecuritySettings::default();
         assert!(security.csrf_enabled);
         assert!(security.security_headers_enabled);
+        assert!(security.secret_key.is_none());
+        assert!(security.cors.allowed_origins.is_empty());
+        assert_eq!(security.max_body_bytes, 2 * 1024 * 1024);
 
         // secure_cookies should be true in release, false in debug
         #[cfg(debug_assertions)]
@@ -622,6 +1042,7 @@ history_enabled = false
 [security]
 csrf_enabled = false
 session_max_age_secs = 3600
+max_sessions_per_user = 3
 ";
 
         let mut file = fs::File::create(&config_path).unwrap();
@@ -636,11 +1057,65 @@ session_max_age_secs = 3600
         assert!(!config.htmx.history_enabled);
         assert!(!config.security.csrf_enabled);
         assert_eq!(config.security.session_max_age_secs, 3600);
+        assert_eq!(config.security.max_sessions_per_user, Some(3));
 
         // Cleanup
         fs::remove_file(config_path).ok();
     }
 
+    #[test]
+    fn test_load_password_policy() {
+        use std::fs;
+
+        let config_path = std::env::temp_dir().join("test_password_policy_config.toml");
+        fs::write(
+            &config_path,
+            "[security.password_policy]\nmin_length = 12\nrequire_symbol = true\n",
+        )
+        .unwrap();
+
+        let config = ActonHtmxConfig::load_from(config_path.to_str().unwrap()).unwrap();
+        let policy = &config.security.password_policy;
+        assert_eq!(policy.min_length, 12);
+        assert!(policy.require_symbol);
+        // Unset rules keep their defaults
+        assert_eq!(policy.max_length, 128);
+        assert!(policy.block_common_passwords);
+        assert!(!config.security.pwned_passwords.enabled);
+
+        fs::remove_file(config_path).ok();
+    }
+
+    #[cfg(feature = "redis")]
+    #[test]
+    fn test_load_redis_settings() {
+        use std::fs;
+
+        let config_path = std::env::temp_dir().join("test_redis_config.toml");
+        fs::write(&config_path, "[redis]\nurl = \"redis://cache:6379\"\n").unwrap();
+
+        let config = ActonHtmxConfig::load_from(config_path.to_str().unwrap()).unwrap();
+        assert_eq!(config.redis.unwrap().url, "redis://cache:6379");
+        assert!(ActonHtmxConfig::default().redis.is_none());
+
+        fs::remove_file(config_path).ok();
+    }
+
+    #[test]
+    fn test_load_jobs_settings() {
+        use std::fs;
+
+        assert_eq!(JobsSettings::default().compress_threshold_bytes, 4096);
+
+        let config_path = std::env::temp_dir().join("test_jobs_config.toml");
+        fs::write(&config_path, "[jobs]\ncompress_threshold_bytes = 1024\n").unwrap();
+
+        let config = ActonHtmxConfig::load_from(config_path.to_str().unwrap()).unwrap();
+        assert_eq!(config.jobs.compress_threshold_bytes, 1024);
+
+        fs::remove_file(config_path).ok();
+    }
+
     #[test]
     fn test_load_for_service_with_defaults() {
         use std::env;
@@ -657,6 +1132,123 @@ session_max_age_secs = 3600
         assert!(config.htmx.history_enabled);
     }
 
+    fn production_config() -> ActonHtmxConfig {
+        let mut config = ActonHtmxConfig::default();
+        config.security.secure_cookies = true;
+        config.security.secret_key = Some("a-long-random-production-key".to_string());
+        config
+    }
+
+    #[test]
+    fn test_validate_passing_production_config() {
+        assert_eq!(production_config().validate(DeployEnv::Production), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_each_insecure_setting() {
+        type Case = (fn(&mut ActonHtmxConfig), &'static [ConfigWarning]);
+        let cases: [Case; 4] = [
+            (|c| c.security.csrf_enabled = false, &[ConfigWarning::CsrfDisabled]),
+            (|c| c.security.secure_cookies = false, &[ConfigWarning::InsecureCookies]),
+            (
+                |c| {
+                    c.security.secure_cookies = false;
+                    c.security.same_site = SameSitePolicy::None;
+                },
+                &[
+                    ConfigWarning::InsecureCookies,
+                    ConfigWarning::SameSiteNoneWithoutSecure,
+                ],
+            ),
+            (|c| c.security.rate_limit.enabled = false, &[ConfigWarning::RateLimitDisabled]),
+        ];
+
+        for (change, expected) in cases {
+            let mut config = production_config();
+            change(&mut config);
+            let problems = config.validate(DeployEnv::Production).unwrap_err();
+            assert_eq!(problems, expected);
+            assert!(problems.iter().all(|problem| problem.is_error()));
+
+            // Development accepts anything
+            assert_eq!(config.validate(DeployEnv::Development), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_validate_warns_on_default_secret_key() {
+        for key in [None, Some(String::new()), Some(DEV_SECRET_KEY.to_string())] {
+            let mut config = production_config();
+            config.security.secret_key = key;
+            let problems = config.validate(DeployEnv::Production).unwrap_err();
+            assert_eq!(problems, [ConfigWarning::DefaultSecretKey]);
+            assert!(!problems[0].is_error());
+        }
+    }
+
+    #[test]
+    fn test_deploy_settings_from_toml() {
+        let config: ActonHtmxConfig =
+            toml::from_str("[deploy]\nenv = \"production\"\nvalidation = \"warn\"\n").unwrap();
+        assert_eq!(config.deploy.env, DeployEnv::Production);
+        assert_eq!(config.deploy.validation, ConfigValidation::Warn);
+
+        let defaults = DeploySettings::default();
+        assert_eq!(defaults.env, DeployEnv::Development);
+        assert_eq!(defaults.validation, ConfigValidation::Enforce);
+    }
+
+    /// Project directory with a base config and per-environment overrides
+    fn layered_project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("config")).unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            "[htmx]\nrequest_timeout_ms = 1000\nhistory_enabled = false\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("config/development.toml"),
+            "[htmx]\nrequest_timeout_ms = 2000\n[jobs]\ncompress_threshold_bytes = 10\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("config/production.toml"),
+            "[htmx]\nrequest_timeout_ms = 3000\n[jobs]\ncompress_threshold_bytes = 20\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_layered_env_file_overrides_base() {
+        let dir = layered_project();
+        let service = "layered-test-service";
+
+        let development = ActonHtmxConfig::load_layered(service, dir.path(), "development").unwrap();
+        assert_eq!(development.htmx.request_timeout_ms, 2000);
+
+        let production = ActonHtmxConfig::load_layered(service, dir.path(), "production").unwrap();
+        assert_eq!(production.htmx.request_timeout_ms, 3000);
+        // Values the environment file doesn't set come from the base file
+        assert!(!production.htmx.history_enabled);
+
+        // A missing environment file leaves the base values
+        let staging = ActonHtmxConfig::load_layered(service, dir.path(), "staging").unwrap();
+        assert_eq!(staging.htmx.request_timeout_ms, 1000);
+    }
+
+    #[test]
+    fn test_load_layered_env_vars_win() {
+        let dir = layered_project();
+
+        std::env::set_var("ACTON_JOBS__COMPRESS_THRESHOLD_BYTES", "99");
+        let config = ActonHtmxConfig::load_layered("layered-test-service", dir.path(), "production");
+        std::env::remove_var("ACTON_JOBS__COMPRESS_THRESHOLD_BYTES");
+
+        assert_eq!(config.unwrap().jobs.compress_threshold_bytes, 99);
+    }
+
     #[test]
     fn test_create_config_dir() {
         use std::fs;
@@ -0,0 +1,607 @@
+//! Generic CRUD repositories
+//!
+//! [`Repository`] is the data access interface handlers program against:
+//! find, list a [`Page`], count, create, update and delete. [`SqlxRepository`]
+//! implements it for any type that describes its table with [`Record`], on
+//! either the PostgreSQL or SQLite pool from
+//! [`ActonHtmxState`](crate::htmx::state::ActonHtmxState).
+//!
+//! Tables with a `deleted_at` column can opt into soft deletes by setting
+//! [`Record::SOFT_DELETE_COLUMN`]. Soft-deleted rows are then hidden from
+//! `find`, `list` and `count`, and left untouched by `update`, unless the repository is switched to
+//! [`SqlxRepository::with_deleted`].
+//!
+//! Errors are plain [`sqlx::Error`]s, so the
+//! [`RecordExt`](crate::htmx::error::RecordExt) and
+//! [`OptionalRecordExt`](crate::htmx::error::OptionalRecordExt) helpers turn
+//! missing records into 404 responses.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::db::{Record, Repository, SqlxRepository};
+//! use acton_dx::htmx::prelude::*;
+//! use sqlx::{error::BoxDynError, Arguments, Postgres};
+//!
+//! #[derive(Debug, Clone, sqlx::FromRow)]
+//! struct Post {
+//!     id: i64,
+//!     title: String,
+//!     body: String,
+//! }
+//!
+//! impl Record<Postgres> for Post {
+//!     type Id = i64;
+//!     const TABLE: &'static str = "posts";
+//!     const COLUMNS: &'static [&'static str] = &["title", "body"];
+//!
+//!     fn bind_columns<'q>(
+//!         &'q self,
+//!         args: &mut <Postgres as sqlx::Database>::Arguments<'q>,
+//!     ) -> Result<(), BoxDynError> {
+//!         args.add(&self.title)?;
+//!         args.add(&self.body)
+//!     }
+//! }
+//!
+//! async fn show_post(
+//!     State(state): State<ActonHtmxState>,
+//!     Path(id): Path<i64>,
+//! ) -> Result<Json<Post>, ActonHtmxError> {
+//!     let posts = SqlxRepository::<Post, Postgres>::new(state.database_pool().clone());
+//!     let post = posts.find(id).await.found_or_404("Post")?;
+//!     Ok(Json(post))
+//! }
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use sqlx::{
+    error::BoxDynError, query::QueryAs, ColumnIndex, Database, Decode, Encode, Executor, FromRow,
+    IntoArguments, Pool, Type,
+};
+
+use crate::htmx::pagination::Page;
+
+/// CRUD access to records of type `T` identified by `Id`
+#[async_trait]
+pub trait Repository<T, Id = i64>: Send + Sync {
+    /// Find a record by id
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails
+    async fn find(&self, id: Id) -> Result<Option<T>, sqlx::Error>;
+
+    /// List one page of records ordered by id (`page` is 1-indexed)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails
+    async fn list(&self, page: usize, page_size: usize) -> Result<Page<T>, sqlx::Error>;
+
+    /// Count all records
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails
+    async fn count(&self) -> Result<usize, sqlx::Error>;
+
+    /// Insert a record and return it as stored
+    ///
+    /// The id and any database-generated columns of `record` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the insert fails
+    async fn create(&self, record: &T) -> Result<T, sqlx::Error>;
+
+    /// Replace the record with `id`, returning it as stored
+    ///
+    /// Returns `None` if there is no record with `id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the update fails
+    async fn update(&self, id: Id, record: &T) -> Result<Option<T>, sqlx::Error>;
+
+    /// Delete the record with `id`, returning whether it existed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delete fails
+    async fn delete(&self, id: Id) -> Result<bool, sqlx::Error>;
+
+    /// Mark the record with `id` deleted, returning whether it was live
+    ///
+    /// The row is kept with its soft-delete column set to the current time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails or the records do not support
+    /// soft deletes
+    async fn soft_delete(&self, id: Id) -> Result<bool, sqlx::Error>;
+
+    /// Undo a soft delete, returning whether the record was deleted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails or the records do not support
+    /// soft deletes
+    async fn restore(&self, id: Id) -> Result<bool, sqlx::Error>;
+}
+
+/// A row type stored in one table, usable with [`SqlxRepository`]
+///
+/// Only the columns in [`COLUMNS`](Self::COLUMNS) are written; the id and
+/// anything else (such as timestamps with database defaults) are left to the
+/// database and read back with `RETURNING *`.
+pub trait Record<DB: Database>: for<'r> FromRow<'r, DB::Row> + Send + Sync + Unpin {
+    /// Primary key type
+    type Id: for<'q> Encode<'q, DB> + Type<DB> + Send + Sync;
+
+    /// Table name
+    const TABLE: &'static str;
+
+    /// Primary key column (default: `id`)
+    const ID_COLUMN: &'static str = "id";
+
+    /// Columns written by create and update, in [`bind_columns`](Self::bind_columns) order
+    const COLUMNS: &'static [&'static str];
+
+    /// Nullable timestamp column marking soft-deleted rows (default: none)
+    ///
+    /// Set to `Some("deleted_at")` to enable soft deletes.
+    const SOFT_DELETE_COLUMN: Option<&'static str> = None;
+
+    /// Add the values of [`COLUMNS`](Self::COLUMNS) to `args`, in order
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a value cannot be encoded
+    fn bind_columns<'q>(&'q self, args: &mut DB::Arguments<'q>) -> Result<(), BoxDynError>;
+}
+
+/// [`Repository`] for a [`Record`] type on a sqlx pool
+///
+/// Works with PostgreSQL and SQLite (`$N` placeholders and `RETURNING`).
+/// Table and column names come from the [`Record`] impl and are not quoted.
+pub struct SqlxRepository<T, DB: Database> {
+    pool: Pool<DB>,
+    with_deleted: bool,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<T, DB: Database> SqlxRepository<T, DB> {
+    /// Create a repository on `pool`
+    #[must_use]
+    pub const fn new(pool: Pool<DB>) -> Self {
+        Self {
+            pool,
+            with_deleted: false,
+            _record: PhantomData,
+        }
+    }
+
+    /// Include soft-deleted records in `find`, `list` and `count`
+    #[must_use]
+    pub const fn with_deleted(mut self) -> Self {
+        self.with_deleted = true;
+        self
+    }
+
+    /// The underlying pool, for queries the repository does not cover
+    #[must_use]
+    pub const fn pool(&self) -> &Pool<DB> {
+        &self.pool
+    }
+}
+
+impl<T, DB: Database> Clone for SqlxRepository<T, DB> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            with_deleted: self.with_deleted,
+            _record: PhantomData,
+        }
+    }
+}
+
+impl<T, DB: Database> fmt::Debug for SqlxRepository<T, DB> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqlxRepository")
+            .field("table", &std::any::type_name::<T>())
+            .field("database", &DB::NAME)
+            .field("with_deleted", &self.with_deleted)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, DB> SqlxRepository<T, DB>
+where
+    T: Record<DB>,
+    DB: Database,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+{
+    /// `$1, $2, ...` for the columns, plus the next placeholder number
+    fn column_placeholders() -> (String, usize) {
+        let placeholders = (1..=T::COLUMNS.len())
+            .map(|n| format!("${n}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (placeholders, T::COLUMNS.len() + 1)
+    }
+
+    /// `AND`-able condition hiding soft-deleted rows, if they are hidden
+    fn live_condition(&self) -> Option<String> {
+        T::SOFT_DELETE_COLUMN
+            .filter(|_| !self.with_deleted)
+            .map(|column| format!("{column} IS NULL"))
+    }
+
+    /// The soft-delete column, or an error if the records have none
+    fn soft_delete_column() -> Result<&'static str, sqlx::Error> {
+        T::SOFT_DELETE_COLUMN.ok_or_else(|| {
+            sqlx::Error::Configuration(
+                format!("{} does not support soft deletes", T::TABLE).into(),
+            )
+        })
+    }
+
+    /// Query returning `T` with the record's column values bound
+    fn query_with_columns<'q>(
+        sql: &'q str,
+        record: &'q T,
+    ) -> Result<QueryAs<'q, DB, T, DB::Arguments<'q>>, sqlx::Error> {
+        let mut args = DB::Arguments::default();
+        record
+            .bind_columns(&mut args)
+            .map_err(sqlx::Error::Encode)?;
+        Ok(sqlx::query_as_with(sql, args))
+    }
+}
+
+#[async_trait]
+impl<T, DB> Repository<T, T::Id> for SqlxRepository<T, DB>
+where
+    T: Record<DB> + 'static,
+    T::Id: 'static,
+    DB: Database,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    i64: for<'q> Encode<'q, DB> + for<'r> Decode<'r, DB> + Type<DB>,
+    usize: ColumnIndex<DB::Row>,
+{
+    async fn find(&self, id: T::Id) -> Result<Option<T>, sqlx::Error> {
+        let mut sql = format!("SELECT * FROM {} WHERE {} = $1", T::TABLE, T::ID_COLUMN);
+        if let Some(condition) = self.live_condition() {
+            sql = format!("{sql} AND {condition}");
+        }
+        sqlx::query_as::<DB, T>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    async fn list(&self, page: usize, page_size: usize) -> Result<Page<T>, sqlx::Error> {
+        let page = page.max(1);
+        let total = self.count().await?;
+
+        let filter = self
+            .live_condition()
+            .map(|condition| format!(" WHERE {condition}"))
+            .unwrap_or_default();
+        let sql = format!(
+            "SELECT * FROM {}{filter} ORDER BY {} LIMIT $1 OFFSET $2",
+            T::TABLE,
+            T::ID_COLUMN
+        );
+        let items = sqlx::query_as::<DB, T>(&sql)
+            .bind(i64::try_from(page_size).unwrap_or(i64::MAX))
+            .bind(i64::try_from(Page::<T>::offset(page, page_size)).unwrap_or(i64::MAX))
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(Page::new(items, page, page_size, total))
+    }
+
+    async fn count(&self) -> Result<usize, sqlx::Error> {
+        let filter = self
+            .live_condition()
+            .map(|condition| format!(" WHERE {condition}"))
+            .unwrap_or_default();
+        let sql = format!("SELECT COUNT(*) FROM {}{filter}", T::TABLE);
+        let count: i64 = sqlx::query_scalar(&sql).fetch_one(&self.pool).await?;
+        Ok(usize::try_from(count).unwrap_or_default())
+    }
+
+    async fn create(&self, record: &T) -> Result<T, sqlx::Error> {
+        let (placeholders, _) = Self::column_placeholders();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES ({placeholders}) RETURNING *",
+            T::TABLE,
+            T::COLUMNS.join(", ")
+        );
+        let query = Self::query_with_columns(&sql, record)?;
+        query.fetch_one(&self.pool).await
+    }
+
+    async fn update(&self, id: T::Id, record: &T) -> Result<Option<T>, sqlx::Error> {
+        let assignments = T::COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(i, column)| format!("{column} = ${}", i + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (_, id_placeholder) = Self::column_placeholders();
+        let mut sql = format!(
+            "UPDATE {} SET {assignments} WHERE {} = ${id_placeholder}",
+            T::TABLE,
+            T::ID_COLUMN
+        );
+        if let Some(condition) = self.live_condition() {
+            sql = format!("{sql} AND {condition}");
+        }
+        sql.push_str(" RETURNING *");
+        let query = Self::query_with_columns(&sql, record)?;
+        query.bind(id).fetch_optional(&self.pool).await
+    }
+
+    async fn delete(&self, id: T::Id) -> Result<bool, sqlx::Error> {
+        let sql = format!(
+            "DELETE FROM {} WHERE {} = $1 RETURNING {}",
+            T::TABLE,
+            T::ID_COLUMN,
+            T::ID_COLUMN
+        );
+        let deleted = sqlx::query(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(deleted.is_some())
+    }
+
+    async fn soft_delete(&self, id: T::Id) -> Result<bool, sqlx::Error> {
+        let column = Self::soft_delete_column()?;
+        let sql = format!(
+            "UPDATE {} SET {column} = CURRENT_TIMESTAMP WHERE {} = $1 AND {column} IS NULL RETURNING {}",
+            T::TABLE,
+            T::ID_COLUMN,
+            T::ID_COLUMN
+        );
+        let deleted = sqlx::query(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(deleted.is_some())
+    }
+
+    async fn restore(&self, id: T::Id) -> Result<bool, sqlx::Error> {
+        let column = Self::soft_delete_column()?;
+        let sql = format!(
+            "UPDATE {} SET {column} = NULL WHERE {} = $1 AND {column} IS NOT NULL RETURNING {}",
+            T::TABLE,
+            T::ID_COLUMN,
+            T::ID_COLUMN
+        );
+        let restored = sqlx::query(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(restored.is_some())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+    use crate::htmx::testing::create_sqlite_pool_with_migration;
+    use sqlx::{Arguments, Sqlite};
+
+    #[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+    struct Note {
+        id: i64,
+        title: String,
+        pinned: bool,
+    }
+
+    impl Note {
+        fn new(title: &str, pinned: bool) -> Self {
+            Self {
+                id: 0,
+                title: title.to_string(),
+                pinned,
+            }
+        }
+    }
+
+    impl Record<Sqlite> for Note {
+        type Id = i64;
+        const TABLE: &'static str = "notes";
+        const COLUMNS: &'static [&'static str] = &["title", "pinned"];
+
+        fn bind_columns<'q>(
+            &'q self,
+            args: &mut <Sqlite as Database>::Arguments<'q>,
+        ) -> Result<(), BoxDynError> {
+            args.add(&self.title)?;
+            args.add(self.pinned)
+        }
+    }
+
+    /// The same table as [`Note`], with soft deletes enabled
+    #[derive(Debug, Clone, sqlx::FromRow)]
+    struct ArchivableNote {
+        id: i64,
+        title: String,
+        deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+    }
+
+    impl Record<Sqlite> for ArchivableNote {
+        type Id = i64;
+        const TABLE: &'static str = "notes";
+        const COLUMNS: &'static [&'static str] = &["title", "pinned"];
+        const SOFT_DELETE_COLUMN: Option<&'static str> = Some("deleted_at");
+
+        fn bind_columns<'q>(
+            &'q self,
+            args: &mut <Sqlite as Database>::Arguments<'q>,
+        ) -> Result<(), BoxDynError> {
+            args.add(&self.title)?;
+            args.add(false)
+        }
+    }
+
+    fn archivable(title: &str) -> ArchivableNote {
+        ArchivableNote {
+            id: 0,
+            title: title.to_string(),
+            deleted_at: None,
+        }
+    }
+
+    async fn repository<T>() -> SqlxRepository<T, Sqlite> {
+        let pool = create_sqlite_pool_with_migration(
+            "CREATE TABLE notes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL,
+                pinned BOOLEAN NOT NULL,
+                deleted_at TIMESTAMPTZ
+            )",
+        )
+        .await
+        .unwrap();
+        SqlxRepository::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_crud_cycle() {
+        let notes = repository::<Note>().await;
+
+        let created = notes.create(&Note::new("first", false)).await.unwrap();
+        assert_eq!(created.title, "first");
+        assert!(created.id > 0);
+        assert_eq!(notes.count().await.unwrap(), 1);
+
+        let found = notes.find(created.id).await.unwrap();
+        assert_eq!(found.as_ref(), Some(&created));
+
+        let updated = notes
+            .update(created.id, &Note::new("renamed", true))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.id, created.id);
+        assert_eq!(updated.title, "renamed");
+        assert!(updated.pinned);
+
+        assert!(notes.delete(created.id).await.unwrap());
+        assert!(!notes.delete(created.id).await.unwrap());
+        assert!(notes.find(created.id).await.unwrap().is_none());
+        assert_eq!(notes.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_missing_record() {
+        let notes = repository::<Note>().await;
+
+        let updated = notes.update(42, &Note::new("ghost", false)).await.unwrap();
+        assert!(updated.is_none());
+        assert_eq!(notes.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_pages() {
+        let notes = repository::<Note>().await;
+        for i in 1..=5 {
+            notes.create(&Note::new(&format!("note {i}"), false)).await.unwrap();
+        }
+
+        let first = notes.list(1, 2).await.unwrap();
+        let titles: Vec<_> = first.items.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(titles, ["note 1", "note 2"]);
+        assert_eq!(first.total, 5);
+        assert_eq!(first.total_pages(), 3);
+        assert!(first.has_next());
+
+        let last = notes.list(3, 2).await.unwrap();
+        assert_eq!(last.items.len(), 1);
+        assert_eq!(last.items[0].title, "note 5");
+        assert!(!last.has_next());
+
+        let beyond = notes.list(4, 2).await.unwrap();
+        assert!(beyond.items.is_empty());
+        assert_eq!(beyond.total, 5);
+
+        // Page 0 is treated as the first page
+        assert_eq!(notes.list(0, 2).await.unwrap().page, 1);
+    }
+
+    #[tokio::test]
+    async fn test_soft_deleted_rows_hidden_by_default() {
+        let notes = repository::<ArchivableNote>().await;
+        let kept = notes.create(&archivable("kept")).await.unwrap();
+        let gone = notes.create(&archivable("gone")).await.unwrap();
+        assert!(kept.deleted_at.is_none());
+
+        assert!(notes.soft_delete(gone.id).await.unwrap());
+        assert!(!notes.soft_delete(gone.id).await.unwrap());
+
+        assert!(notes.find(gone.id).await.unwrap().is_none());
+        assert_eq!(notes.count().await.unwrap(), 1);
+        let page = notes.list(1, 10).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, kept.id);
+
+        let all = notes.clone().with_deleted();
+        assert_eq!(all.count().await.unwrap(), 2);
+        assert_eq!(all.list(1, 10).await.unwrap().items.len(), 2);
+        let found = all.find(gone.id).await.unwrap().unwrap();
+        assert!(found.deleted_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_update_skips_soft_deleted_rows() {
+        let notes = repository::<ArchivableNote>().await;
+        let note = notes.create(&archivable("original")).await.unwrap();
+        notes.soft_delete(note.id).await.unwrap();
+
+        let updated = notes.update(note.id, &archivable("edited")).await.unwrap();
+        assert!(updated.is_none());
+
+        let all = notes.clone().with_deleted();
+        let stored = all.find(note.id).await.unwrap().unwrap();
+        assert_eq!(stored.title, "original");
+        assert!(stored.deleted_at.is_some());
+
+        let updated = all.update(note.id, &archivable("edited")).await.unwrap().unwrap();
+        assert_eq!(updated.title, "edited");
+    }
+
+    #[tokio::test]
+    async fn test_restore() {
+        let notes = repository::<ArchivableNote>().await;
+        let note = notes.create(&archivable("back")).await.unwrap();
+
+        assert!(!notes.restore(note.id).await.unwrap());
+        notes.soft_delete(note.id).await.unwrap();
+        assert!(notes.restore(note.id).await.unwrap());
+
+        let restored = notes.find(note.id).await.unwrap().unwrap();
+        assert!(restored.deleted_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete_requires_column() {
+        let notes = repository::<Note>().await;
+        let note = notes.create(&Note::new("plain", false)).await.unwrap();
+
+        assert!(matches!(
+            notes.soft_delete(note.id).await,
+            Err(sqlx::Error::Configuration(_))
+        ));
+        assert!(notes.find(note.id).await.unwrap().is_some());
+    }
+}
@@ -1,16 +1,21 @@
 //! AWS SES backend for sending emails
 //!
 //! Uses AWS Simple Email Service (SES) v2 API for sending emails.
+//! Emails with attachments are sent as raw MIME messages.
 //! Requires the `aws-sdk-sesv2` feature to be enabled.
 
 #[cfg(feature = "aws-ses")]
 use async_trait::async_trait;
 #[cfg(feature = "aws-ses")]
 use aws_sdk_sesv2::{
-    types::{Body, Content, Destination, EmailContent, Message},
+    primitives::Blob,
+    types::{Body, Content, Destination, EmailContent, Message, RawMessage},
     Client,
 };
 
+#[cfg(feature = "aws-ses")]
+use super::smtp::SmtpBackend;
+
 use crate::htmx::email::{Email, EmailError, EmailSender};
 
 /// AWS SES email backend
@@ -72,12 +77,8 @@ impl AwsSesBackend {
         Ok(Self::new(client))
     }
 
-    /// Build AWS SES message from Email
-    fn build_message(email: &Email) -> Result<EmailContent, EmailError> {
-        // Validate email first
-        email.validate()?;
-
-        // Build destination
+    /// Build AWS SES destination from Email recipients
+    fn build_destination(email: &Email) -> Destination {
         let mut destination = Destination::builder();
         for to_addr in &email.to {
             destination = destination.to_addresses(to_addr);
@@ -88,6 +89,18 @@ impl AwsSesBackend {
         for bcc_addr in &email.bcc {
             destination = destination.bcc_addresses(bcc_addr);
         }
+        destination.build()
+    }
+
+    /// Build AWS SES message from Email
+    fn build_message(email: &Email) -> Result<EmailContent, EmailError> {
+        // Validate email first
+        email.validate()?;
+
+        // Simple content has no room for attachments, so send raw MIME
+        if !email.attachments.is_empty() {
+            return Self::build_raw_message(email);
+        }
 
         // Build subject
         let subject = email.subject.as_ref().ok_or(EmailError::NoSubject)?;
@@ -132,6 +145,17 @@ impl AwsSesBackend {
 
         Ok(EmailContent::builder().simple(message).build())
     }
+
+    /// Build a raw MIME message, used for emails with attachments
+    fn build_raw_message(email: &Email) -> Result<EmailContent, EmailError> {
+        let mime = SmtpBackend::build_message(email)?.formatted();
+        let raw = RawMessage::builder()
+            .data(Blob::new(mime))
+            .build()
+            .map_err(|e| EmailError::aws_ses(format!("Failed to build raw message: {e}")))?;
+
+        Ok(EmailContent::builder().raw(raw).build())
+    }
 }
 
 #[cfg(feature = "aws-ses")]
@@ -145,6 +169,7 @@ impl EmailSender for AwsSesBackend {
             .client
             .send_email()
             .from_email_address(from_addr)
+            .destination(Self::build_destination(&email))
             .content(content);
 
         // Add reply-to if present
@@ -222,4 +247,37 @@ mod tests {
         let content = AwsSesBackend::build_message(&email);
         assert!(content.is_ok());
     }
+
+    #[test]
+    fn test_build_message_with_attachments_is_raw_mime() {
+        let email = Email::new()
+            .to("recipient@example.com")
+            .from("sender@example.com")
+            .subject("Your receipt")
+            .html(r#"<img src="cid:logo">"#)
+            .attachment("receipt.pdf", "application/pdf", b"%PDF-1.7".to_vec())
+            .inline_attachment("logo", "image/png", vec![1, 2, 3]);
+
+        let content = AwsSesBackend::build_message(&email).unwrap();
+        assert!(content.simple().is_none());
+
+        let mime = std::str::from_utf8(content.raw().unwrap().data().as_ref()).unwrap();
+        assert!(mime.contains("Content-Type: multipart/mixed"));
+        assert!(mime.contains("Content-Type: multipart/related"));
+        assert!(mime.contains(r#"Content-Disposition: attachment; filename="receipt.pdf""#));
+        assert!(mime.contains("Content-ID: <logo>"));
+    }
+
+    #[test]
+    fn test_build_destination() {
+        let email = Email::new()
+            .to("to@example.com")
+            .cc("cc@example.com")
+            .bcc("bcc@example.com");
+
+        let destination = AwsSesBackend::build_destination(&email);
+        assert_eq!(destination.to_addresses(), ["to@example.com"]);
+        assert_eq!(destination.cc_addresses(), ["cc@example.com"]);
+        assert_eq!(destination.bcc_addresses(), ["bcc@example.com"]);
+    }
 }
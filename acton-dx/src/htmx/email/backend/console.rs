@@ -76,6 +76,7 @@ impl EmailSender for ConsoleBackend {
                 has_html = email.html.is_some(),
                 has_text = email.text.is_some(),
                 headers = ?email.headers,
+                attachments = email.attachments.len(),
                 "Email details"
             );
 
@@ -137,6 +138,22 @@ impl EmailSender for ConsoleBackend {
             println!("├─────────────────────────────────────────────────────┤");
         }
 
+        if !email.attachments.is_empty() {
+            println!("│ Attachments:                                        │");
+            println!("├─────────────────────────────────────────────────────┤");
+            for attachment in &email.attachments {
+                let kind = if attachment.is_inline() { "inline" } else { "file" };
+                let line = format!(
+                    "{} ({}, {} bytes, {kind})",
+                    attachment.filename,
+                    attachment.content_type,
+                    attachment.data.len()
+                );
+                println!("│ {line:<51} │");
+            }
+            println!("├─────────────────────────────────────────────────────┤");
+        }
+
         println!("╰─────────────────────────────────────────────────────╯\n");
 
         Ok(())
@@ -191,4 +208,19 @@ mod tests {
         let result = backend.send(email).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_console_backend_with_attachments() {
+        let backend = ConsoleBackend::new();
+
+        let email = Email::new()
+            .to("user@example.com")
+            .from("noreply@myapp.com")
+            .subject("Test Email")
+            .text("Test content")
+            .attachment("receipt.pdf", "application/pdf", vec![0; 1024]);
+
+        let result = backend.send(email).await;
+        assert!(result.is_ok());
+    }
 }
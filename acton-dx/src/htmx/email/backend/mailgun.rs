@@ -0,0 +1,381 @@
+//! Mailgun backend for sending emails
+//!
+//! Uses the Mailgun HTTP API (`/v3/{domain}/messages`) with API key
+//! authentication. Requires the `mailgun` feature to be enabled.
+
+use crate::htmx::email::{Email, EmailError, EmailSender};
+
+/// Mailgun API region
+///
+/// Domains are created in either the US or EU region and must be sent
+/// through that region's API host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MailgunRegion {
+    /// `https://api.mailgun.net`
+    #[default]
+    Us,
+    /// `https://api.eu.mailgun.net`
+    Eu,
+}
+
+impl MailgunRegion {
+    /// Base URL of the region's API
+    #[must_use]
+    pub const fn base_url(self) -> &'static str {
+        match self {
+            Self::Us => "https://api.mailgun.net",
+            Self::Eu => "https://api.eu.mailgun.net",
+        }
+    }
+}
+
+impl std::str::FromStr for MailgunRegion {
+    type Err = EmailError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "us" => Ok(Self::Us),
+            "eu" => Ok(Self::Eu),
+            _ => Err(EmailError::config("MAILGUN_REGION must be 'us' or 'eu'")),
+        }
+    }
+}
+
+/// Mailgun email backend
+///
+/// Sends emails via the Mailgun HTTP API.
+///
+/// Requires the `mailgun` feature to be enabled.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "mailgun")]
+/// # {
+/// use acton_htmx::email::{Email, EmailSender, MailgunBackend};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// // Reads MAILGUN_API_KEY, MAILGUN_DOMAIN, and MAILGUN_REGION
+/// let backend = MailgunBackend::from_env()?;
+///
+/// let email = Email::new()
+///     .to("user@example.com")
+///     .from("noreply@myapp.com")
+///     .subject("Hello!")
+///     .text("Hello, World!");
+///
+/// backend.send(email).await?;
+/// # Ok(())
+/// # }
+/// # }
+/// ```
+#[cfg(feature = "mailgun")]
+#[derive(Debug, Clone)]
+pub struct MailgunBackend {
+    client: reqwest::Client,
+    api_key: String,
+    domain: String,
+    base_url: String,
+}
+
+#[cfg(feature = "mailgun")]
+impl MailgunBackend {
+    /// Create a new Mailgun backend for a sending domain
+    #[must_use]
+    pub fn new(api_key: impl Into<String>, domain: impl Into<String>, region: MailgunRegion) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.into(),
+            domain: domain.into(),
+            base_url: region.base_url().to_string(),
+        }
+    }
+
+    /// Create a new Mailgun backend from environment variables
+    ///
+    /// Reads:
+    /// - `MAILGUN_API_KEY`: Private API key (required)
+    /// - `MAILGUN_DOMAIN`: Sending domain (required)
+    /// - `MAILGUN_REGION`: `us` or `eu` (default: `us`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `EmailError::ConfigError` if a required variable is missing or
+    /// the region is invalid
+    pub fn from_env() -> Result<Self, EmailError> {
+        let api_key = std::env::var("MAILGUN_API_KEY")
+            .map_err(|_| EmailError::config("MAILGUN_API_KEY environment variable not set"))?;
+
+        let domain = std::env::var("MAILGUN_DOMAIN")
+            .map_err(|_| EmailError::config("MAILGUN_DOMAIN environment variable not set"))?;
+
+        let region = std::env::var("MAILGUN_REGION")
+            .map_or(Ok(MailgunRegion::default()), |region| region.parse())?;
+
+        Ok(Self::new(api_key, domain, region))
+    }
+
+    /// Override the API base URL (e.g. for a proxy or a test server)
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// URL of the domain's messages endpoint
+    fn messages_url(&self) -> String {
+        format!(
+            "{}/v3/{}/messages",
+            self.base_url.trim_end_matches('/'),
+            self.domain
+        )
+    }
+
+    /// Build the multipart form Mailgun expects from an Email
+    fn build_form(email: &Email) -> Result<reqwest::multipart::Form, EmailError> {
+        email.validate()?;
+
+        let from = email.from.clone().ok_or(EmailError::NoSender)?;
+        let subject = email.subject.clone().ok_or(EmailError::NoSubject)?;
+
+        let mut form = reqwest::multipart::Form::new()
+            .text("from", from)
+            .text("subject", subject);
+
+        for to_addr in &email.to {
+            form = form.text("to", to_addr.clone());
+        }
+        for cc_addr in &email.cc {
+            form = form.text("cc", cc_addr.clone());
+        }
+        for bcc_addr in &email.bcc {
+            form = form.text("bcc", bcc_addr.clone());
+        }
+        if let Some(text) = &email.text {
+            form = form.text("text", text.clone());
+        }
+        if let Some(html) = &email.html {
+            form = form.text("html", html.clone());
+        }
+        if let Some(reply_to) = &email.reply_to {
+            form = form.text("h:Reply-To", reply_to.clone());
+        }
+        for (name, value) in &email.headers {
+            form = form.text(format!("h:{name}"), value.clone());
+        }
+        for attachment in &email.attachments {
+            // Mailgun derives the Content-ID of inline files from their filename
+            let field = if attachment.is_inline() { "inline" } else { "attachment" };
+            let part = reqwest::multipart::Part::bytes(attachment.data.clone())
+                .file_name(attachment.filename.clone())
+                .mime_str(&attachment.content_type)
+                .map_err(|e| {
+                    EmailError::InvalidAttachment(format!(
+                        "{}: invalid content type '{}': {e}",
+                        attachment.filename, attachment.content_type
+                    ))
+                })?;
+            form = form.part(field, part);
+        }
+
+        Ok(form)
+    }
+}
+
+#[cfg(feature = "mailgun")]
+#[async_trait::async_trait]
+impl EmailSender for MailgunBackend {
+    async fn send(&self, email: Email) -> Result<(), EmailError> {
+        let form = Self::build_form(&email)?;
+
+        let response = self
+            .client
+            .post(self.messages_url())
+            .basic_auth("api", Some(&self.api_key))
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| EmailError::mailgun(format!("Failed to send email: {e}")))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(EmailError::mailgun(format!(
+                "Mailgun responded with {status}: {body}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Stub implementation when the Mailgun feature is not enabled
+///
+/// This struct is only available when the `mailgun` feature is disabled.
+/// Enable the feature to use the full Mailgun backend implementation.
+#[cfg(not(feature = "mailgun"))]
+pub struct MailgunBackend;
+
+#[cfg(not(feature = "mailgun"))]
+impl MailgunBackend {
+    /// Mailgun backend is not available without the `mailgun` feature
+    ///
+    /// # Errors
+    ///
+    /// Always returns an error indicating the feature is not enabled
+    pub fn from_env() -> Result<Self, EmailError> {
+        Err(EmailError::config(
+            "Mailgun backend requires the 'mailgun' feature to be enabled",
+        ))
+    }
+}
+
+#[cfg(not(feature = "mailgun"))]
+#[async_trait::async_trait]
+impl EmailSender for MailgunBackend {
+    async fn send(&self, _email: Email) -> Result<(), EmailError> {
+        Err(EmailError::config(
+            "Mailgun backend requires the 'mailgun' feature to be enabled",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_base_urls() {
+        assert_eq!(MailgunRegion::Us.base_url(), "https://api.mailgun.net");
+        assert_eq!(MailgunRegion::Eu.base_url(), "https://api.eu.mailgun.net");
+        assert_eq!("EU".parse::<MailgunRegion>().unwrap(), MailgunRegion::Eu);
+        assert!("asia".parse::<MailgunRegion>().is_err());
+    }
+}
+
+#[cfg(all(test, feature = "mailgun"))]
+mod mock_server_tests {
+    use super::*;
+    use axum::{
+        extract::{Multipart, Path, State},
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct Captured {
+        domain: String,
+        authorization: String,
+        fields: Vec<(String, String)>,
+    }
+
+    /// Serve a fake messages endpoint that records requests and replies with `status`
+    async fn spawn_mock_server(status: StatusCode, reply: &'static str) -> (String, Arc<Mutex<Captured>>) {
+        async fn messages(
+            State((captured, status, reply)): State<(Arc<Mutex<Captured>>, StatusCode, &'static str)>,
+            Path(domain): Path<String>,
+            headers: HeaderMap,
+            mut multipart: Multipart,
+        ) -> (StatusCode, &'static str) {
+            let mut fields = Vec::new();
+            while let Some(field) = multipart.next_field().await.unwrap() {
+                let name = field.name().unwrap().to_string();
+                fields.push((name, field.text().await.unwrap()));
+            }
+            *captured.lock().await = Captured {
+                domain,
+                authorization: headers["authorization"].to_str().unwrap().to_string(),
+                fields,
+            };
+            (status, reply)
+        }
+
+        let captured = Arc::new(Mutex::new(Captured::default()));
+        let app = Router::new()
+            .route("/v3/{domain}/messages", post(messages))
+            .with_state((captured.clone(), status, reply));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{addr}"), captured)
+    }
+
+    fn field<'a>(captured: &'a Captured, name: &str) -> Vec<&'a str> {
+        captured
+            .fields
+            .iter()
+            .filter(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_multipart_form() {
+        let (base_url, captured) = spawn_mock_server(StatusCode::OK, r#"{"message":"Queued"}"#).await;
+        let backend = MailgunBackend::new("key-123", "mg.example.com", MailgunRegion::Eu)
+            .with_base_url(base_url);
+
+        let email = Email::new()
+            .to("alice@example.com")
+            .to("bob@example.com")
+            .from("noreply@example.com")
+            .subject("Welcome!")
+            .text("Hello in text")
+            .html("<h1>Hello in HTML</h1>");
+        backend.send(email).await.unwrap();
+
+        let captured = std::mem::take(&mut *captured.lock().await);
+        assert_eq!(captured.domain, "mg.example.com");
+        // base64("api:key-123")
+        assert_eq!(captured.authorization, "Basic YXBpOmtleS0xMjM=");
+        assert_eq!(field(&captured, "to"), ["alice@example.com", "bob@example.com"]);
+        assert_eq!(field(&captured, "from"), ["noreply@example.com"]);
+        assert_eq!(field(&captured, "subject"), ["Welcome!"]);
+        assert_eq!(field(&captured, "text"), ["Hello in text"]);
+        assert_eq!(field(&captured, "html"), ["<h1>Hello in HTML</h1>"]);
+    }
+
+    #[tokio::test]
+    async fn test_send_includes_attachments() {
+        let (base_url, captured) = spawn_mock_server(StatusCode::OK, r#"{"message":"Queued"}"#).await;
+        let backend = MailgunBackend::new("key-123", "mg.example.com", MailgunRegion::Us)
+            .with_base_url(base_url);
+
+        let email = Email::new()
+            .to("alice@example.com")
+            .from("noreply@example.com")
+            .subject("Receipt")
+            .html(r#"<img src="cid:logo">"#)
+            .attachment("receipt.pdf", "application/pdf", b"%PDF-1.7".to_vec())
+            .inline_attachment("logo", "image/png", b"PNG".to_vec());
+        backend.send(email).await.unwrap();
+
+        let captured = std::mem::take(&mut *captured.lock().await);
+        assert_eq!(field(&captured, "attachment"), ["%PDF-1.7"]);
+        assert_eq!(field(&captured, "inline"), ["PNG"]);
+    }
+
+    #[tokio::test]
+    async fn test_send_maps_error_response() {
+        let (base_url, _captured) =
+            spawn_mock_server(StatusCode::UNAUTHORIZED, r#"{"message":"Invalid private key"}"#).await;
+        let backend = MailgunBackend::new("wrong", "mg.example.com", MailgunRegion::Us)
+            .with_base_url(base_url);
+
+        let email = Email::new()
+            .to("alice@example.com")
+            .from("noreply@example.com")
+            .subject("Hi")
+            .text("Hi");
+        let err = backend.send(email).await.unwrap_err();
+
+        assert!(matches!(err, EmailError::MailgunError(_)));
+        let message = err.to_string();
+        assert!(message.contains("401"));
+        assert!(message.contains("Invalid private key"));
+    }
+}
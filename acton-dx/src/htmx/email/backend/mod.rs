@@ -3,8 +3,10 @@
 //! This module provides different backend implementations for sending emails:
 //! - **SMTP**: Send emails via SMTP server (production)
 //! - **AWS SES**: Send emails via Amazon SES (production, AWS environments)
+//! - **Mailgun**: Send emails via the Mailgun HTTP API (production)
 //! - **Console**: Print emails to console (development)
 
 pub mod smtp;
 pub mod aws_ses;
 pub mod console;
+pub mod mailgun;
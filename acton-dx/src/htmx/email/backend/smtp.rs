@@ -4,7 +4,7 @@
 
 use async_trait::async_trait;
 use lettre::{
-    message::{header, Mailbox, MultiPart, SinglePart},
+    message::{self, header, Mailbox, MultiPart, SinglePart},
     transport::smtp::{
         authentication::Credentials,
         client::{Tls, TlsParameters},
@@ -12,7 +12,7 @@ use lettre::{
     AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
 
-use crate::htmx::email::{Email, EmailError, EmailSender};
+use crate::htmx::email::{Attachment, Email, EmailError, EmailSender};
 
 /// SMTP email backend configuration
 #[derive(Debug, Clone)]
@@ -121,7 +121,10 @@ impl SmtpBackend {
     }
 
     /// Build lettre Message from Email
-    fn build_message(email: &Email) -> Result<Message, EmailError> {
+    ///
+    /// Also used by the AWS SES backend to produce raw MIME for emails with
+    /// attachments.
+    pub(crate) fn build_message(email: &Email) -> Result<Message, EmailError> {
         // Validate email first
         email.validate()?;
 
@@ -174,6 +177,13 @@ impl SmtpBackend {
         // See docs/guides/09-email.md "Custom Headers" section for workarounds.
         // Planned for Phase 3 if there's sufficient user demand.
 
+        // Attachments need a multipart/mixed envelope around the body
+        if !email.attachments.is_empty() {
+            return builder
+                .multipart(Self::build_multipart_with_attachments(email)?)
+                .map_err(|e| EmailError::smtp(e.to_string()));
+        }
+
         // Build multipart message if we have both HTML and text
         let message = if let (Some(html), Some(text)) = (&email.html, &email.text) {
             builder
@@ -208,6 +218,69 @@ impl SmtpBackend {
         Ok(message)
     }
 
+    /// Build a multipart/mixed body holding the content and its attachments
+    ///
+    /// Inline attachments are grouped with the HTML part in a
+    /// multipart/related so `cid:` references in the HTML resolve. Without an
+    /// HTML body they are attached like regular files.
+    fn build_multipart_with_attachments(email: &Email) -> Result<MultiPart, EmailError> {
+        let (inline, attached): (Vec<_>, Vec<_>) = email
+            .attachments
+            .iter()
+            .partition(|attachment| attachment.is_inline() && email.html.is_some());
+
+        let mixed = MultiPart::mixed();
+        let mut mixed = match (&email.text, &email.html) {
+            (Some(text), Some(html)) => {
+                let alternative =
+                    MultiPart::alternative().singlepart(SinglePart::plain(text.clone()));
+                if inline.is_empty() {
+                    mixed.multipart(alternative.singlepart(SinglePart::html(html.clone())))
+                } else {
+                    mixed.multipart(alternative.multipart(Self::build_related_html(html, &inline)?))
+                }
+            }
+            (None, Some(html)) if inline.is_empty() => {
+                mixed.singlepart(SinglePart::html(html.clone()))
+            }
+            (None, Some(html)) => mixed.multipart(Self::build_related_html(html, &inline)?),
+            (Some(text), None) => mixed.singlepart(SinglePart::plain(text.clone())),
+            (None, None) => return Err(EmailError::NoContent),
+        };
+
+        for attachment in attached {
+            mixed = mixed.singlepart(Self::build_attachment_part(attachment)?);
+        }
+
+        Ok(mixed)
+    }
+
+    /// Build a multipart/related holding the HTML body and its inline attachments
+    fn build_related_html(html: &str, inline: &[&Attachment]) -> Result<MultiPart, EmailError> {
+        let mut related = MultiPart::related().singlepart(SinglePart::html(html.to_string()));
+        for attachment in inline {
+            related = related.singlepart(Self::build_attachment_part(attachment)?);
+        }
+        Ok(related)
+    }
+
+    /// Build the MIME part for a single attachment
+    fn build_attachment_part(attachment: &Attachment) -> Result<SinglePart, EmailError> {
+        let content_type = header::ContentType::parse(&attachment.content_type).map_err(|e| {
+            EmailError::InvalidAttachment(format!(
+                "{}: invalid content type '{}': {e}",
+                attachment.filename, attachment.content_type
+            ))
+        })?;
+
+        let part = attachment.content_id.as_ref().map_or_else(
+            || message::Attachment::new(attachment.filename.clone()),
+            |cid| message::Attachment::new_inline(cid.clone()),
+        );
+
+        Ok(part.body(attachment.data.clone(), content_type))
+    }
+
     /// Create SMTP transport from config
     fn create_transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>, EmailError> {
         let credentials = Credentials::new(
@@ -322,4 +395,64 @@ mod tests {
         let message = SmtpBackend::build_message(&email);
         assert!(message.is_ok());
     }
+
+    #[test]
+    fn test_build_message_with_attachments() {
+        let email = Email::new()
+            .to("recipient@example.com")
+            .from("sender@example.com")
+            .subject("Your receipt")
+            .text("Receipt attached")
+            .html(r#"<img src="cid:logo"><p>Receipt attached</p>"#)
+            .attachment("receipt.pdf", "application/pdf", b"%PDF-1.7".to_vec())
+            .inline_attachment("logo", "image/png", vec![0x89, b'P', b'N', b'G']);
+
+        let message = SmtpBackend::build_message(&email).unwrap();
+        let mime = String::from_utf8(message.formatted()).unwrap();
+
+        assert!(mime.contains("Content-Type: multipart/mixed"));
+        assert!(mime.contains("Content-Type: multipart/alternative"));
+        assert!(mime.contains("Content-Type: multipart/related"));
+        assert!(mime.contains("Content-Type: application/pdf"));
+        assert!(mime.contains(r#"Content-Disposition: attachment; filename="receipt.pdf""#));
+        assert!(mime.contains("Content-Type: image/png"));
+        assert!(mime.contains("Content-ID: <logo>"));
+        assert!(mime.contains("Content-Disposition: inline"));
+
+        // The inline image sits next to the HTML it belongs to, before the PDF
+        let related = mime.find("multipart/related").unwrap();
+        assert!(related < mime.find("Content-ID: <logo>").unwrap());
+        assert!(mime.find("Content-ID: <logo>").unwrap() < mime.find("receipt.pdf").unwrap());
+    }
+
+    #[test]
+    fn test_build_message_inline_attachment_without_html() {
+        let email = Email::new()
+            .to("recipient@example.com")
+            .from("sender@example.com")
+            .subject("Logo")
+            .text("See attached")
+            .inline_attachment("logo", "image/png", vec![1, 2, 3]);
+
+        let mime = String::from_utf8(SmtpBackend::build_message(&email).unwrap().formatted()).unwrap();
+
+        assert!(mime.contains("Content-Type: multipart/mixed"));
+        assert!(!mime.contains("multipart/related"));
+        assert!(mime.contains("Content-ID: <logo>"));
+    }
+
+    #[test]
+    fn test_build_message_rejects_invalid_attachment_content_type() {
+        let email = Email::new()
+            .to("recipient@example.com")
+            .from("sender@example.com")
+            .subject("Bad")
+            .text("Bad")
+            .attachment("file.bin", "not a mime type", vec![0]);
+
+        assert!(matches!(
+            SmtpBackend::build_message(&email),
+            Err(EmailError::InvalidAttachment(_))
+        ));
+    }
 }
@@ -48,6 +48,37 @@ pub struct Email {
 
     /// Custom headers
     pub headers: Vec<(String, String)>,
+
+    /// File attachments, including inline attachments referenced by CID
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+/// A file attached to an email
+///
+/// Regular attachments are offered for download; inline attachments carry a
+/// Content-ID so the HTML body can reference them (e.g. `<img src="cid:logo">`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    /// File name shown to the recipient (the CID for inline attachments)
+    pub filename: String,
+
+    /// MIME type of the content (e.g. `application/pdf`)
+    pub content_type: String,
+
+    /// Raw file content
+    pub data: Vec<u8>,
+
+    /// Content-ID for inline attachments, `None` for regular attachments
+    pub content_id: Option<String>,
+}
+
+impl Attachment {
+    /// Whether this attachment is displayed inline in the HTML body
+    #[must_use]
+    pub const fn is_inline(&self) -> bool {
+        self.content_id.is_some()
+    }
 }
 
 impl Email {
@@ -268,6 +299,51 @@ impl Email {
         self
     }
 
+    /// Attach a file
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use acton_htmx::email::Email;
+    ///
+    /// let pdf = b"%PDF-1.7 ...".to_vec();
+    /// let email = Email::new()
+    ///     .attachment("receipt.pdf", "application/pdf", pdf);
+    /// ```
+    #[must_use]
+    pub fn attachment(mut self, filename: &str, content_type: &str, data: impl Into<Vec<u8>>) -> Self {
+        self.attachments.push(Attachment {
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            data: data.into(),
+            content_id: None,
+        });
+        self
+    }
+
+    /// Attach a file inline, referenceable from the HTML body as `cid:<cid>`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use acton_htmx::email::Email;
+    ///
+    /// let logo = std::fs::read("static/logo.png").unwrap_or_default();
+    /// let email = Email::new()
+    ///     .html(r#"<img src="cid:logo">"#)
+    ///     .inline_attachment("logo", "image/png", logo);
+    /// ```
+    #[must_use]
+    pub fn inline_attachment(mut self, cid: &str, content_type: &str, data: impl Into<Vec<u8>>) -> Self {
+        self.attachments.push(Attachment {
+            filename: cid.to_string(),
+            content_type: content_type.to_string(),
+            data: data.into(),
+            content_id: Some(cid.to_string()),
+        });
+        self
+    }
+
     /// Validate the email
     ///
     /// Checks that all required fields are present
@@ -423,4 +499,29 @@ mod tests {
         assert_eq!(email.text, Some("Plain text content".to_string()));
         assert_eq!(email.html, Some("<h1>HTML content</h1>".to_string()));
     }
+
+    #[test]
+    fn test_attachments() {
+        let email = Email::new()
+            .html(r#"<img src="cid:logo">"#)
+            .attachment("receipt.pdf", "application/pdf", b"%PDF".to_vec())
+            .inline_attachment("logo", "image/png", vec![0x89, b'P', b'N', b'G']);
+
+        assert_eq!(email.attachments.len(), 2);
+        assert_eq!(email.attachments[0].filename, "receipt.pdf");
+        assert!(!email.attachments[0].is_inline());
+        assert_eq!(email.attachments[1].content_id.as_deref(), Some("logo"));
+        assert!(email.attachments[1].is_inline());
+    }
+
+    #[test]
+    fn test_deserialize_without_attachments() {
+        let email: Email = serde_json::from_str(
+            r#"{"to":["user@example.com"],"from":null,"reply_to":null,"cc":[],"bcc":[],
+                "subject":null,"text":"Hi","html":null,"headers":[]}"#,
+        )
+        .unwrap();
+
+        assert!(email.attachments.is_empty());
+    }
 }
@@ -25,6 +25,10 @@ pub enum EmailError {
     #[error("invalid email address: {0}")]
     InvalidAddress(String),
 
+    /// Attachment that cannot be encoded (e.g. an invalid content type)
+    #[error("invalid attachment: {0}")]
+    InvalidAttachment(String),
+
     /// Template rendering error
     #[error("failed to render email template: {0}")]
     TemplateError(#[from] askama::Error),
@@ -37,6 +41,10 @@ pub enum EmailError {
     #[error("AWS SES error: {0}")]
     AwsSesError(String),
 
+    /// Mailgun API error
+    #[error("Mailgun error: {0}")]
+    MailgunError(String),
+
     /// Email configuration error
     #[error("email configuration error: {0}")]
     ConfigError(String),
@@ -63,6 +71,12 @@ impl EmailError {
         Self::AwsSesError(msg.into())
     }
 
+    /// Create a Mailgun error from a string message
+    #[must_use]
+    pub fn mailgun<T: Into<String>>(msg: T) -> Self {
+        Self::MailgunError(msg.into())
+    }
+
     /// Create a configuration error from a string message
     #[must_use]
     pub fn config<T: Into<String>>(msg: T) -> Self {
@@ -1,8 +1,10 @@
 //! Email sending with multiple backends and template support
 //!
 //! This module provides a flexible email system with:
-//! - Multiple backends (SMTP, AWS SES, console/development)
+//! - Multiple backends (SMTP, AWS SES, Mailgun, console/development)
+//! - File and inline (CID) attachments
 //! - Askama template integration for HTML and plain text emails
+//! - Optional open/click tracking for HTML emails
 //! - Background job integration for async sending
 //! - Common email flows (welcome, verification, password reset)
 //!
@@ -62,17 +64,23 @@ mod error;
 mod job;
 mod sender;
 mod template;
+mod tracking;
 
 pub use backend::{
     aws_ses::AwsSesBackend,
     console::ConsoleBackend,
+    mailgun::{MailgunBackend, MailgunRegion},
     smtp::SmtpBackend,
 };
-pub use builder::Email;
+pub use builder::{Attachment, Email};
 pub use error::EmailError;
 pub use job::SendEmailJob;
 pub use sender::EmailSender;
 pub use template::{EmailTemplate, SimpleEmailTemplate};
+pub use tracking::{
+    EmailTracking, TrackingEvent, TrackingEventKind, TrackingSender, TrackingSink,
+    TRACKING_ID_HEADER,
+};
 
 // Test utilities are now in the testing module
 // Re-export for backward compatibility
@@ -0,0 +1,441 @@
+//! Open and click tracking for HTML emails
+//!
+//! [`EmailTracking`] rewrites an email's HTML body so that opens and clicks
+//! can be observed:
+//! - a 1x1 pixel (`{base_url}/t/open/{id}`) is appended to the body
+//! - every `<a href="http(s)://...">` is routed through
+//!   `{base_url}/t/click/{id}?u=<url>&s=<signature>`
+//!
+//! Plain-text bodies are never modified. Click URLs are signed with
+//! HMAC-SHA256 so the redirect endpoint cannot be abused as an open redirect.
+//!
+//! Wrap any backend in a [`TrackingSender`] to apply tracking on send, and
+//! mount the `track_open`/`track_click` handlers from
+//! [`crate::htmx::handlers::email_tracking`] to record events.
+
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use regex::{Captures, Regex};
+use sha2::Sha256;
+
+use super::{Email, EmailError, EmailSender};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header carrying the tracking ID assigned by [`TrackingSender`]
+pub const TRACKING_ID_HEADER: &str = "X-Tracking-Id";
+
+/// Opening `<a ...>` tags
+static ANCHOR_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<a\s[^>]*>").expect("Invalid regex"));
+
+/// `href` attribute inside a tag, single or double quoted
+static HREF_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)(\shref\s*=\s*)(?:"([^"]*)"|'([^']*)')"#).expect("Invalid regex")
+});
+
+/// Email open/click tracking configuration
+///
+/// # Examples
+///
+/// ```rust
+/// use acton_htmx::email::{Email, EmailTracking};
+///
+/// let tracking = EmailTracking::new("https://app.example.com", b"secret-key".to_vec());
+///
+/// let mut email = Email::new().html(r#"<a href="https://example.com/docs">Docs</a>"#);
+/// tracking.apply(&mut email, "msg-123");
+///
+/// let html = email.html.unwrap();
+/// assert!(html.contains("/t/click/msg-123?u="));
+/// assert!(html.contains("/t/open/msg-123"));
+/// ```
+#[derive(Clone)]
+pub struct EmailTracking {
+    base_url: String,
+    secret_key: Arc<[u8]>,
+    track_opens: bool,
+    track_clicks: bool,
+}
+
+impl fmt::Debug for EmailTracking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailTracking")
+            .field("base_url", &self.base_url)
+            .field("track_opens", &self.track_opens)
+            .field("track_clicks", &self.track_clicks)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EmailTracking {
+    /// Create a tracking configuration with open and click tracking enabled
+    ///
+    /// `base_url` is the public origin the tracking handlers are mounted on
+    /// (e.g. `https://app.example.com`). `secret_key` signs click URLs.
+    #[must_use]
+    pub fn new(base_url: impl Into<String>, secret_key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            secret_key: Arc::from(secret_key.into()),
+            track_opens: true,
+            track_clicks: true,
+        }
+    }
+
+    /// Enable or disable the open tracking pixel
+    #[must_use]
+    pub const fn track_opens(mut self, enabled: bool) -> Self {
+        self.track_opens = enabled;
+        self
+    }
+
+    /// Enable or disable click tracking
+    #[must_use]
+    pub const fn track_clicks(mut self, enabled: bool) -> Self {
+        self.track_clicks = enabled;
+        self
+    }
+
+    /// Whether any tracking is enabled
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.track_opens || self.track_clicks
+    }
+
+    /// Apply tracking to an email's HTML body
+    ///
+    /// Emails without an HTML body are left untouched.
+    pub fn apply(&self, email: &mut Email, id: &str) {
+        let Some(html) = email.html.take() else {
+            return;
+        };
+
+        let html = if self.track_clicks {
+            self.rewrite_links(&html, id)
+        } else {
+            html
+        };
+
+        email.html = Some(if self.track_opens {
+            self.inject_pixel(&html, id)
+        } else {
+            html
+        });
+    }
+
+    /// URL of the open tracking pixel for an email
+    #[must_use]
+    pub fn open_url(&self, id: &str) -> String {
+        format!("{}/t/open/{id}", self.base_url)
+    }
+
+    /// Signed redirect URL that records a click on `url`
+    #[must_use]
+    pub fn click_url(&self, id: &str, url: &str) -> String {
+        let signature = URL_SAFE_NO_PAD.encode(self.sign(id, url));
+        let query = serde_html_form::to_string([("u", url), ("s", signature.as_str())])
+            .unwrap_or_default();
+        format!("{}/t/click/{id}?{query}", self.base_url)
+    }
+
+    /// Check that a click URL was produced by [`Self::click_url`] for this email
+    #[must_use]
+    pub fn verify_click(&self, id: &str, url: &str, signature: &str) -> bool {
+        let Ok(signature) = URL_SAFE_NO_PAD.decode(signature) else {
+            return false;
+        };
+        let Ok(mut mac) = HmacSha256::new_from_slice(&self.secret_key) else {
+            return false;
+        };
+        mac.update(id.as_bytes());
+        mac.update(b"\n");
+        mac.update(url.as_bytes());
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    /// Route every absolute `http(s)` link in `<a>` tags through the click endpoint
+    ///
+    /// Relative links, anchors, and other schemes (`mailto:`, `tel:`) are kept
+    /// as-is. The original URL is carried whole, including its query string
+    /// and fragment.
+    #[must_use]
+    pub fn rewrite_links(&self, html: &str, id: &str) -> String {
+        ANCHOR_TAG
+            .replace_all(html, |tag: &Captures| {
+                HREF_ATTR
+                    .replace(&tag[0], |attr: &Captures| {
+                        let (quote, href) = attr
+                            .get(2)
+                            .map_or_else(|| ('\'', &attr[3]), |href| ('"', href.as_str()));
+                        let url = href.trim().replace("&amp;", "&");
+
+                        if !is_trackable(&url) {
+                            return attr[0].to_string();
+                        }
+
+                        let tracked = self.click_url(id, &url).replace('&', "&amp;");
+                        format!("{}{quote}{tracked}{quote}", &attr[1])
+                    })
+                    .into_owned()
+            })
+            .into_owned()
+    }
+
+    /// Append the open tracking pixel, inside `</body>` when present
+    #[must_use]
+    pub fn inject_pixel(&self, html: &str, id: &str) -> String {
+        let pixel = format!(
+            r#"<img src="{}" width="1" height="1" alt="" style="border:0">"#,
+            self.open_url(id)
+        );
+
+        html.to_ascii_lowercase().rfind("</body>").map_or_else(
+            || format!("{html}{pixel}"),
+            |index| format!("{}{pixel}{}", &html[..index], &html[index..]),
+        )
+    }
+
+    /// HMAC-SHA256 over the email ID and target URL
+    fn sign(&self, id: &str, url: &str) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(&self.secret_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(id.as_bytes());
+        mac.update(b"\n");
+        mac.update(url.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+/// Only absolute web links are worth tracking
+fn is_trackable(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Kind of tracked email interaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrackingEventKind {
+    /// The tracking pixel was loaded
+    Open,
+    /// A tracked link was followed
+    Click {
+        /// The original link target
+        url: String,
+    },
+}
+
+/// A recorded email open or click
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackingEvent {
+    /// Tracking ID of the email
+    pub email_id: String,
+    /// What happened
+    pub kind: TrackingEventKind,
+    /// When the event was received
+    pub occurred_at: DateTime<Utc>,
+    /// User agent of the client, if sent
+    pub user_agent: Option<String>,
+}
+
+/// Destination for email tracking events
+///
+/// Implement this to store events in a database or forward them to an
+/// analytics service.
+#[async_trait]
+pub trait TrackingSink: Send + Sync {
+    /// Record a tracking event
+    async fn record(&self, event: TrackingEvent);
+}
+
+/// Email sender that applies [`EmailTracking`] before delegating
+///
+/// Each email gets a fresh tracking ID, also set as the
+/// [`TRACKING_ID_HEADER`] header so it can be correlated with the message.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use acton_htmx::email::{EmailTracking, SmtpBackend, TrackingSender};
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let tracking = EmailTracking::new("https://app.example.com", b"secret-key".to_vec());
+/// let sender = TrackingSender::new(SmtpBackend::from_env()?, tracking);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrackingSender<S> {
+    inner: S,
+    tracking: EmailTracking,
+}
+
+impl<S: EmailSender> TrackingSender<S> {
+    /// Wrap a backend with tracking
+    #[must_use]
+    pub const fn new(inner: S, tracking: EmailTracking) -> Self {
+        Self { inner, tracking }
+    }
+}
+
+#[async_trait]
+impl<S: EmailSender> EmailSender for TrackingSender<S> {
+    async fn send(&self, mut email: Email) -> Result<(), EmailError> {
+        if self.tracking.is_enabled() && email.html.is_some() {
+            let id = uuid::Uuid::new_v4().to_string();
+            self.tracking.apply(&mut email, &id);
+            email.headers.push((TRACKING_ID_HEADER.to_string(), id));
+        }
+
+        self.inner.send(email).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::testing::MockEmailSender;
+
+    fn tracking() -> EmailTracking {
+        EmailTracking::new("https://app.example.com/", b"test-secret".to_vec())
+    }
+
+    /// Extract the `u` and `s` query values from a rewritten href
+    fn click_params(html: &str) -> (String, String) {
+        let start = html.find("?u=").unwrap() + 1;
+        let end = start + html[start..].find('"').unwrap();
+        let query = html[start..end].replace("&amp;", "&");
+        let params: Vec<(String, String)> = serde_html_form::from_str(&query).unwrap();
+        (params[0].1.clone(), params[1].1.clone())
+    }
+
+    #[test]
+    fn test_rewrite_links_preserves_query_and_fragment() {
+        let html = r#"<p><a class="btn" href="https://example.com/a?x=1&amp;y=2#frag">Go</a></p>"#;
+        let rewritten = tracking().rewrite_links(html, "msg-1");
+
+        assert!(rewritten.starts_with(r#"<p><a class="btn" href="https://app.example.com/t/click/msg-1?u="#));
+        assert!(rewritten.ends_with(r#"">Go</a></p>"#));
+
+        let (url, signature) = click_params(&rewritten);
+        assert_eq!(url, "https://example.com/a?x=1&y=2#frag");
+        assert!(tracking().verify_click("msg-1", &url, &signature));
+    }
+
+    #[test]
+    fn test_rewrite_links_skips_untrackable_links() {
+        let html = concat!(
+            r#"<a href="mailto:help@example.com">Mail</a>"#,
+            r##"<a href="#top">Top</a>"##,
+            r#"<a href="/relative">Rel</a>"#,
+            r#"<link href="https://example.com/style.css" rel="stylesheet">"#,
+        );
+
+        assert_eq!(tracking().rewrite_links(html, "msg-1"), html);
+    }
+
+    #[test]
+    fn test_rewrite_links_single_quotes() {
+        let html = "<A HREF='http://example.com/page'>Page</A>";
+        let rewritten = tracking().rewrite_links(html, "msg-1");
+
+        assert!(rewritten.starts_with("<A HREF='https://app.example.com/t/click/msg-1?u="));
+        assert!(rewritten.ends_with("'>Page</A>"));
+    }
+
+    #[test]
+    fn test_verify_click_rejects_tampered_url() {
+        let tracking = tracking();
+        let url = tracking.click_url("msg-1", "https://example.com");
+        let (_, signature) = click_params(&format!(r#"href="{url}""#));
+
+        assert!(tracking.verify_click("msg-1", "https://example.com", &signature));
+        assert!(!tracking.verify_click("msg-1", "https://evil.example", &signature));
+        assert!(!tracking.verify_click("msg-2", "https://example.com", &signature));
+        assert!(!tracking.verify_click("msg-1", "https://example.com", "not-base64!"));
+    }
+
+    #[test]
+    fn test_inject_pixel_before_body_close() {
+        let html = "<html><body><p>Hi</p></BODY></html>";
+        let tracked = tracking().inject_pixel(html, "msg-1");
+
+        assert_eq!(
+            tracked,
+            r#"<html><body><p>Hi</p><img src="https://app.example.com/t/open/msg-1" width="1" height="1" alt="" style="border:0"></BODY></html>"#
+        );
+    }
+
+    #[test]
+    fn test_inject_pixel_appends_without_body() {
+        let tracked = tracking().inject_pixel("<p>Hi</p>", "msg-1");
+
+        assert!(tracked.starts_with("<p>Hi</p><img src=\"https://app.example.com/t/open/msg-1\""));
+    }
+
+    #[test]
+    fn test_apply_leaves_text_untouched() {
+        let mut email = Email::new()
+            .text("Visit https://example.com")
+            .html(r#"<a href="https://example.com">Visit</a>"#);
+        tracking().apply(&mut email, "msg-1");
+
+        assert_eq!(email.text.as_deref(), Some("Visit https://example.com"));
+        let html = email.html.unwrap();
+        assert!(html.contains("/t/click/msg-1"));
+        assert!(html.contains("/t/open/msg-1"));
+    }
+
+    #[test]
+    fn test_apply_respects_disabled_features() {
+        let mut email = Email::new().html(r#"<a href="https://example.com">Visit</a>"#);
+        tracking().track_clicks(false).apply(&mut email, "msg-1");
+
+        let html = email.html.unwrap();
+        assert!(!html.contains("/t/click/"));
+        assert!(html.contains("/t/open/msg-1"));
+    }
+
+    #[tokio::test]
+    async fn test_tracking_sender_tags_html_emails() {
+        let inner = MockEmailSender::new();
+        let sender = TrackingSender::new(inner.clone(), tracking());
+        let email = Email::new()
+            .to("user@example.com")
+            .from("noreply@example.com")
+            .subject("Hi")
+            .html("<p>Hi</p>");
+
+        sender.send(email).await.unwrap();
+
+        let sent = inner.last_sent().unwrap();
+        let (_, id) = sent
+            .headers
+            .iter()
+            .find(|(name, _)| name == TRACKING_ID_HEADER)
+            .unwrap();
+        assert!(sent.html.unwrap().contains(&format!("/t/open/{id}")));
+    }
+
+    #[tokio::test]
+    async fn test_tracking_sender_skips_text_only_emails() {
+        let inner = MockEmailSender::new();
+        let sender = TrackingSender::new(inner.clone(), tracking());
+        let email = Email::new()
+            .to("user@example.com")
+            .from("noreply@example.com")
+            .subject("Hi")
+            .text("Hi");
+
+        sender.send(email).await.unwrap();
+
+        let sent = inner.last_sent().unwrap();
+        assert!(sent.headers.is_empty());
+        assert_eq!(sent.text.as_deref(), Some("Hi"));
+    }
+}
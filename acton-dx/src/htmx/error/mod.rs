@@ -1,9 +1,36 @@
 //! Error types and error handling
+//!
+//! [`ActonHtmxError`] renders itself in the format the request asked for
+//! (see [`ResponseFormat`]):
+//!
+//! - API requests (`Accept: application/json`) get `{ "error": "..." }`
+//! - Full-page requests get the matching framework template
+//!   (`errors/404.html`, `errors/413.html`, ...)
+//! - HTMX requests get the same template, retargeted with `HX-Retarget` to
+//!   [`DEFAULT_ERROR_TARGET`] so the error doesn't replace the element that
+//!   triggered the request
+//!
+//! Server errors are logged and shown with a generic message.
 
 #![allow(dead_code)]
 
+mod not_found;
+
+pub use not_found::{find_one_or_404, find_or_404, OptionalRecordExt, RecordExt};
+
+use crate::htmx::middleware::DEFAULT_ERROR_TARGET;
+use crate::htmx::responses::SwapStrategy;
+use crate::htmx::template::{escape_html, helpers::try_templates, ResponseFormat};
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
 use thiserror::Error;
 
+/// Message shown in place of server error details
+const SERVER_ERROR_MESSAGE: &str = "Internal server error";
+
 /// Framework error type
 #[derive(Debug, Error)]
 pub enum ActonHtmxError {
@@ -42,4 +69,195 @@ pub enum ActonHtmxError {
     /// Not Found (404)
     #[error("Not found: {0}")]
     NotFound(String),
+
+    /// Payload too large (413)
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    /// Unprocessable entity (422)
+    #[error("Unprocessable entity: {0}")]
+    UnprocessableEntity(String),
+}
+
+impl ActonHtmxError {
+    /// HTTP status code for this error
+    #[must_use]
+    pub const fn status_code(&self) -> StatusCode {
+        match self {
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Config(_)
+            | Self::ServerError(_)
+            | Self::Database(_)
+            | Self::OAuth(_)
+            | Self::SessionError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Message safe to show to the user
+    ///
+    /// Client errors return their message; server errors return a generic
+    /// message so internal details don't leak.
+    #[must_use]
+    pub fn public_message(&self) -> String {
+        match self {
+            Self::BadRequest(message)
+            | Self::Unauthorized(message)
+            | Self::Forbidden(message)
+            | Self::NotFound(message)
+            | Self::PayloadTooLarge(message)
+            | Self::UnprocessableEntity(message) => message.clone(),
+            Self::Config(_)
+            | Self::ServerError(_)
+            | Self::Database(_)
+            | Self::OAuth(_)
+            | Self::SessionError(_) => SERVER_ERROR_MESSAGE.to_string(),
+        }
+    }
+
+    /// Build the response for a request in `format`
+    ///
+    /// [`IntoResponse`] uses the format of the current request, set by
+    /// [`ResponseFormatLayer`](crate::htmx::template::ResponseFormatLayer).
+    #[must_use]
+    pub fn into_response_for(self, format: ResponseFormat) -> Response {
+        let status = self.status_code();
+        if status.is_server_error() {
+            tracing::error!(error = %self, "Request failed");
+        }
+        let message = self.public_message();
+
+        if format == ResponseFormat::Json {
+            return (status, Json(serde_json::json!({ "error": message }))).into_response();
+        }
+
+        let mut response = (
+            status,
+            [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+            render_error_page(status, &message),
+        )
+            .into_response();
+
+        if format.is_htmx() {
+            let headers = response.headers_mut();
+            headers.insert(
+                "HX-Retarget",
+                header::HeaderValue::from_static(DEFAULT_ERROR_TARGET),
+            );
+            headers.insert(
+                "HX-Reswap",
+                header::HeaderValue::from_static(SwapStrategy::InnerHTML.as_str()),
+            );
+        }
+
+        response
+    }
+}
+
+impl IntoResponse for ActonHtmxError {
+    fn into_response(self) -> Response {
+        let format = ResponseFormat::current().unwrap_or(ResponseFormat::FullPage);
+        self.into_response_for(format)
+    }
+}
+
+/// Render the framework error template for `status`
+pub(crate) fn render_error_page(status: StatusCode, message: &str) -> String {
+    let code = match status.as_u16() {
+        code @ (400 | 401 | 403 | 404 | 413 | 422 | 503) => code,
+        _ => 500,
+    };
+
+    try_templates()
+        .and_then(|templates| {
+            templates
+                .render(
+                    &format!("errors/{code}.html"),
+                    minijinja::context! {
+                        message => message,
+                        home_url => "/",
+                    },
+                )
+                .map_err(|e| tracing::error!(error = ?e, "Failed to render error template"))
+                .ok()
+        })
+        .unwrap_or_else(|| format!("<h1>{code}</h1><p>{}</p>", escape_html(message)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_status_codes() {
+        let cases = [
+            (ActonHtmxError::BadRequest(String::new()), 400),
+            (ActonHtmxError::Unauthorized(String::new()), 401),
+            (ActonHtmxError::Forbidden(String::new()), 403),
+            (ActonHtmxError::NotFound(String::new()), 404),
+            (ActonHtmxError::PayloadTooLarge(String::new()), 413),
+            (ActonHtmxError::UnprocessableEntity(String::new()), 422),
+            (ActonHtmxError::ServerError(String::new()), 500),
+            (ActonHtmxError::Config(String::new()), 500),
+            (ActonHtmxError::Database(sqlx::Error::PoolTimedOut), 500),
+        ];
+
+        for (error, code) in cases {
+            assert_eq!(error.into_response().status().as_u16(), code);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_json_for_api_requests() {
+        let response = ResponseFormat::Json
+            .scope(async { ActonHtmxError::NotFound("Post not found".into()).into_response() })
+            .await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let json: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+        assert_eq!(json["error"], "Post not found");
+    }
+
+    #[tokio::test]
+    async fn test_server_error_details_are_hidden() {
+        let response = ActonHtmxError::ServerError("connection string leaked".into())
+            .into_response_for(ResponseFormat::Json);
+
+        let json: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+        assert_eq!(json["error"], SERVER_ERROR_MESSAGE);
+    }
+
+    #[tokio::test]
+    async fn test_htmx_requests_are_retargeted() {
+        let response = ResponseFormat::HtmxPartial
+            .scope(async { ActonHtmxError::Forbidden("Admins only".into()).into_response() })
+            .await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(response.headers()["HX-Retarget"], DEFAULT_ERROR_TARGET);
+        assert_eq!(response.headers()["HX-Reswap"], "innerHTML");
+    }
+
+    #[tokio::test]
+    async fn test_full_page_html_by_default() {
+        let response = ActonHtmxError::NotFound("Post not found".into()).into_response();
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        assert!(response.headers().get("HX-Retarget").is_none());
+        assert!(body(response).await.contains("Post not found"));
+    }
 }
@@ -0,0 +1,157 @@
+//! Not-found helpers for database lookups
+//!
+//! Show, edit, and delete handlers all fetch a record by id and turn a
+//! missing row into a 404. These helpers do that mapping once:
+//! - `Ok(None)` from `fetch_optional` becomes [`ActonHtmxError::NotFound`]
+//! - [`sqlx::Error::RowNotFound`] from `fetch_one` becomes
+//!   [`ActonHtmxError::NotFound`]
+//! - Every other database error stays [`ActonHtmxError::Database`], so real
+//!   failures still respond with 500
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::error::{find_or_404, ActonHtmxError, RecordExt};
+//!
+//! async fn show_post(
+//!     State(state): State<ActonHtmxState>,
+//!     Path(id): Path<i64>,
+//! ) -> Result<Html<String>, ActonHtmxError> {
+//!     let post: Post = find_or_404(
+//!         sqlx::query_as("SELECT * FROM posts WHERE id = $1")
+//!             .bind(id)
+//!             .fetch_optional(state.database_pool()),
+//!         "Post",
+//!     )
+//!     .await?;
+//!
+//!     // Or, with fetch_one:
+//!     let author: User = sqlx::query_as("SELECT * FROM users WHERE id = $1")
+//!         .bind(post.author_id)
+//!         .fetch_one(state.database_pool())
+//!         .await
+//!         .or_404("User")?;
+//!
+//!     Ok(Html(render(&post, &author)))
+//! }
+//! ```
+
+use super::ActonHtmxError;
+use std::future::Future;
+
+/// Map the result of a `fetch_one` query, treating a missing row as a 404
+pub trait RecordExt<T> {
+    /// Convert [`sqlx::Error::RowNotFound`] into [`ActonHtmxError::NotFound`]
+    ///
+    /// `resource` names the record in the error message (e.g. `"Post"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ActonHtmxError::NotFound`] if no row matched, or
+    /// [`ActonHtmxError::Database`] for any other database error.
+    fn or_404(self, resource: &str) -> Result<T, ActonHtmxError>;
+}
+
+impl<T> RecordExt<T> for Result<T, sqlx::Error> {
+    fn or_404(self, resource: &str) -> Result<T, ActonHtmxError> {
+        self.map_err(|error| match error {
+            sqlx::Error::RowNotFound => not_found(resource),
+            other => ActonHtmxError::Database(other),
+        })
+    }
+}
+
+/// Map the result of a `fetch_optional` query, treating `None` as a 404
+pub trait OptionalRecordExt<T> {
+    /// Convert `Ok(None)` into [`ActonHtmxError::NotFound`]
+    ///
+    /// `resource` names the record in the error message (e.g. `"Post"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ActonHtmxError::NotFound`] if no row matched, or
+    /// [`ActonHtmxError::Database`] for any other database error.
+    fn found_or_404(self, resource: &str) -> Result<T, ActonHtmxError>;
+}
+
+impl<T> OptionalRecordExt<T> for Result<Option<T>, sqlx::Error> {
+    fn found_or_404(self, resource: &str) -> Result<T, ActonHtmxError> {
+        self.or_404(resource)?.ok_or_else(|| not_found(resource))
+    }
+}
+
+/// Await a `fetch_optional` query and return the record or a 404
+///
+/// # Errors
+///
+/// Returns [`ActonHtmxError::NotFound`] if no row matched, or
+/// [`ActonHtmxError::Database`] for any other database error.
+pub async fn find_or_404<T, F>(query: F, resource: &str) -> Result<T, ActonHtmxError>
+where
+    F: Future<Output = Result<Option<T>, sqlx::Error>>,
+{
+    query.await.found_or_404(resource)
+}
+
+/// Await a `fetch_one` query and return the record or a 404
+///
+/// # Errors
+///
+/// Returns [`ActonHtmxError::NotFound`] if no row matched, or
+/// [`ActonHtmxError::Database`] for any other database error.
+pub async fn find_one_or_404<T, F>(query: F, resource: &str) -> Result<T, ActonHtmxError>
+where
+    F: Future<Output = Result<T, sqlx::Error>>,
+{
+    query.await.or_404(resource)
+}
+
+fn not_found(resource: &str) -> ActonHtmxError {
+    ActonHtmxError::NotFound(format!("{resource} not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    #[tokio::test]
+    async fn test_missing_optional_record_is_not_found() {
+        let error = find_or_404(async { Ok::<Option<i64>, _>(None) }, "Post")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(&error, ActonHtmxError::NotFound(msg) if msg == "Post not found"));
+        assert_eq!(error.into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_found_records_pass_through() {
+        assert_eq!(find_or_404(async { Ok(Some(7)) }, "Post").await.unwrap(), 7);
+        assert_eq!(find_one_or_404(async { Ok(7) }, "Post").await.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_row_not_found_is_not_found() {
+        let error = find_one_or_404(async { Err::<i64, _>(sqlx::Error::RowNotFound) }, "User")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ActonHtmxError::NotFound(_)));
+
+        let error = Err::<Option<i64>, _>(sqlx::Error::RowNotFound)
+            .found_or_404("User")
+            .unwrap_err();
+        assert!(matches!(error, ActonHtmxError::NotFound(_)));
+    }
+
+    #[test]
+    fn test_other_database_errors_stay_server_errors() {
+        let error = Err::<i64, _>(sqlx::Error::PoolTimedOut)
+            .or_404("Post")
+            .unwrap_err();
+
+        assert!(matches!(error, ActonHtmxError::Database(_)));
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,374 @@
+//! Application-level domain event bus
+//!
+//! Decouples producers of domain events (user registered, order placed) from
+//! the code that reacts to them (send email, update metrics, audit log).
+//!
+//! Built on the acton-reactive broker: [`EventBus::publish`] broadcasts an
+//! event, and every subscriber agent registered for that event type receives
+//! a copy.
+//!
+//! # Delivery Guarantees
+//!
+//! Each subscriber chooses its guarantee explicitly via [`Delivery`]:
+//!
+//! - [`Delivery::FireAndForget`] - The handler runs inside the subscriber
+//!   agent. If it fails or the process stops, the event is lost. Suitable for
+//!   metrics, cache invalidation, and logging.
+//! - [`Delivery::AtLeastOnce`] - The event is mapped to a [`Job`] and enqueued
+//!   on the [`JobAgent`](crate::htmx::jobs::JobAgent), so it inherits the job
+//!   system's retries, dead letter queue, and (with Redis) persistence. Job
+//!   handlers must be idempotent because they may run more than once.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::email::{Email, SendEmailJob};
+//! use acton_dx::htmx::events::{DomainEvent, EventBus, EventSubscriber};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Clone, Serialize, Deserialize)]
+//! struct UserRegistered { email: String }
+//!
+//! impl DomainEvent for UserRegistered {}
+//!
+//! let bus = EventBus::new(&runtime);
+//!
+//! // Audit every registration (fire-and-forget)
+//! bus.subscribe(&mut runtime, EventSubscriber::<UserRegistered>::audit_log("audit")).await?;
+//!
+//! // Send a welcome email through the job queue (at-least-once)
+//! bus.subscribe(
+//!     &mut runtime,
+//!     EventSubscriber::at_least_once("welcome_email", job_agent, |event: &UserRegistered| {
+//!         Some(SendEmailJob::new(Email::new().to(&event.email).subject("Welcome!").text("Hi!")))
+//!     }),
+//! ).await?;
+//!
+//! bus.publish(UserRegistered { email: "alice@example.com".into() }).await;
+//! ```
+
+use crate::htmx::agents::default_agent_config;
+use crate::htmx::jobs::{agent::EnqueueJob, Job};
+use acton_reactive::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Marker trait for events published on the [`EventBus`]
+///
+/// Events must be cloneable (each subscriber receives its own copy) and
+/// serializable (so they can be logged and carried into jobs).
+pub trait DomainEvent:
+    Clone + fmt::Debug + Send + Sync + Serialize + DeserializeOwned + 'static
+{
+    /// Event name used in logs and audit records
+    ///
+    /// Default: the Rust type name.
+    fn event_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
+
+/// Delivery guarantee chosen by a subscriber
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Run the handler in the subscriber agent; failures are logged and the
+    /// event is dropped.
+    FireAndForget,
+    /// Enqueue a job for the event; the job system handles retries.
+    AtLeastOnce,
+}
+
+type EventHandler<E> = Arc<dyn Fn(E) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// A subscriber registration for events of type `E`
+///
+/// Build with [`fire_and_forget`](Self::fire_and_forget),
+/// [`at_least_once`](Self::at_least_once), or
+/// [`audit_log`](Self::audit_log), then register with
+/// [`EventBus::subscribe`].
+pub struct EventSubscriber<E> {
+    name: String,
+    delivery: Delivery,
+    handler: EventHandler<E>,
+}
+
+impl<E> fmt::Debug for EventSubscriber<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSubscriber")
+            .field("name", &self.name)
+            .field("delivery", &self.delivery)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<E> EventSubscriber<E> {
+    /// Subscriber name (used for the agent name and logs)
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Delivery guarantee of this subscriber
+    #[must_use]
+    pub const fn delivery(&self) -> Delivery {
+        self.delivery
+    }
+}
+
+impl<E: DomainEvent> EventSubscriber<E> {
+    /// Subscriber that runs `handler` for each event
+    pub fn fire_and_forget<F, Fut>(name: impl Into<String>, handler: F) -> Self
+    where
+        F: Fn(E) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self {
+            name: name.into(),
+            delivery: Delivery::FireAndForget,
+            handler: Arc::new(move |event| Box::pin(handler(event))),
+        }
+    }
+
+    /// Subscriber that enqueues the job returned by `to_job` for each event
+    ///
+    /// Return `None` to skip an event.
+    pub fn at_least_once<J, F>(name: impl Into<String>, job_agent: AgentHandle, to_job: F) -> Self
+    where
+        J: Job,
+        F: Fn(&E) -> Option<J> + Send + Sync + 'static,
+    {
+        let name = name.into();
+        let subscriber = name.clone();
+        let handler: EventHandler<E> = Arc::new(move |event| {
+            let job = to_job(&event).and_then(|job| {
+                EnqueueJob::from_job(&job)
+                    .inspect_err(|e| {
+                        warn!(subscriber = %subscriber, error = %e, "Failed to serialize event job");
+                    })
+                    .ok()
+            });
+            let job_agent = job_agent.clone();
+            let subscriber = subscriber.clone();
+            Box::pin(async move {
+                if let Some(job) = job {
+                    debug!(subscriber = %subscriber, job_id = %job.id, "Enqueueing job for event");
+                    job_agent.send(job).await;
+                }
+            })
+        });
+
+        Self {
+            name,
+            delivery: Delivery::AtLeastOnce,
+            handler,
+        }
+    }
+
+    /// Fire-and-forget subscriber that writes each event to the `audit`
+    /// tracing target as JSON
+    pub fn audit_log(name: impl Into<String>) -> Self {
+        Self::fire_and_forget(name, |event: E| async move {
+            let payload = serde_json::to_string(&event).unwrap_or_default();
+            info!(target: "audit", event = event.event_name(), payload = %payload, "Domain event");
+        })
+    }
+}
+
+/// Agent state for an event subscriber
+#[derive(Debug, Default, Clone)]
+pub struct EventSubscriberAgent {
+    name: String,
+}
+
+/// Publishes domain events to subscriber agents
+///
+/// Cheap to clone; store it in application state and call
+/// [`publish`](Self::publish) from handlers.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    broker: AgentHandle,
+}
+
+impl EventBus {
+    /// Create an event bus on the runtime's broker
+    #[must_use]
+    pub fn new(runtime: &AgentRuntime) -> Self {
+        Self {
+            broker: runtime.broker(),
+        }
+    }
+
+    /// Publish an event to all subscribers of its type
+    ///
+    /// Returns once the event is handed to the broker; it does not wait for
+    /// subscribers to process it.
+    pub async fn publish<E: DomainEvent>(&self, event: E) {
+        debug!(event = event.event_name(), "Publishing domain event");
+        self.broker.broadcast(event).await;
+    }
+
+    /// Spawn a subscriber agent for events of type `E`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the subscriber agent cannot be created (e.g. the
+    /// subscriber name is not a valid agent name).
+    pub async fn subscribe<E: DomainEvent>(
+        &self,
+        runtime: &mut AgentRuntime,
+        subscriber: EventSubscriber<E>,
+    ) -> anyhow::Result<AgentHandle> {
+        let config = default_agent_config(&subscriber.name)?;
+        let mut builder = runtime
+            .new_agent_with_config::<EventSubscriberAgent>(config)
+            .await;
+        builder.model.name.clone_from(&subscriber.name);
+
+        let handler = subscriber.handler;
+        builder.act_on::<E>(move |agent, envelope| {
+            let event = envelope.message().clone();
+            let name = agent.model.name.clone();
+            // Run on a task so handler futures need not be `Sync` and a
+            // panicking handler cannot take down the agent.
+            let task = tokio::spawn(handler(event));
+            Box::pin(async move {
+                if let Err(e) = task.await {
+                    warn!(subscriber = %name, error = %e, "Event handler failed");
+                }
+            })
+        });
+
+        builder.handle().subscribe::<E>().await;
+        Ok(builder.start().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::jobs::{JobContext, JobResult};
+    use async_trait::async_trait;
+    use serde::Deserialize;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct UserRegistered {
+        user_id: i64,
+    }
+
+    impl DomainEvent for UserRegistered {}
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct WelcomeJob {
+        user_id: i64,
+    }
+
+    #[async_trait]
+    impl Job for WelcomeJob {
+        type Result = ();
+
+        async fn execute(&self, _ctx: &JobContext) -> JobResult<Self::Result> {
+            Ok(())
+        }
+
+        fn max_retries(&self) -> u32 {
+            5
+        }
+    }
+
+    #[derive(Debug, Default, Clone)]
+    struct EnqueueRecorder;
+
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    #[test]
+    fn test_subscriber_delivery() {
+        let subscriber = EventSubscriber::<UserRegistered>::audit_log("audit");
+        assert_eq!(subscriber.delivery(), Delivery::FireAndForget);
+        assert_eq!(subscriber.name(), "audit");
+    }
+
+    #[test]
+    fn test_enqueue_job_from_job() {
+        let msg = EnqueueJob::from_job(&WelcomeJob { user_id: 7 }).unwrap();
+        assert_eq!(msg.max_retries, 5);
+        assert!(msg.job_type.ends_with("WelcomeJob"));
+        let job: WelcomeJob = serde_json::from_slice(&msg.payload).unwrap();
+        assert_eq!(job.user_id, 7);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_publish_fans_out_to_all_subscribers() {
+        let mut runtime = ActonApp::launch();
+        let bus = EventBus::new(&runtime);
+        let count = Arc::new(AtomicUsize::new(0));
+
+        for name in ["first_subscriber", "second_subscriber"] {
+            let count = count.clone();
+            bus.subscribe(
+                &mut runtime,
+                EventSubscriber::fire_and_forget(name, move |event: UserRegistered| {
+                    let count = count.clone();
+                    async move {
+                        assert_eq!(event.user_id, 42);
+                        count.fetch_add(1, Ordering::SeqCst);
+                    }
+                }),
+            )
+            .await
+            .unwrap();
+        }
+        settle().await;
+
+        bus.publish(UserRegistered { user_id: 42 }).await;
+        settle().await;
+
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+        runtime.shutdown_all().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_at_least_once_enqueues_job() {
+        let mut runtime = ActonApp::launch();
+        let bus = EventBus::new(&runtime);
+        let enqueued = Arc::new(AtomicUsize::new(0));
+
+        let mut recorder = runtime
+            .new_agent_with_config::<EnqueueRecorder>(default_agent_config("recorder").unwrap())
+            .await;
+        let counter = enqueued.clone();
+        recorder.act_on::<EnqueueJob>(move |_agent, envelope| {
+            let job: WelcomeJob = serde_json::from_slice(&envelope.message().payload).unwrap();
+            assert_eq!(job.user_id, 9);
+            counter.fetch_add(1, Ordering::SeqCst);
+            AgentReply::immediate()
+        });
+        let job_agent = recorder.start().await;
+
+        bus.subscribe(
+            &mut runtime,
+            EventSubscriber::at_least_once("welcome_email", job_agent, |event: &UserRegistered| {
+                (event.user_id > 0).then_some(WelcomeJob {
+                    user_id: event.user_id,
+                })
+            }),
+        )
+        .await
+        .unwrap();
+        settle().await;
+
+        bus.publish(UserRegistered { user_id: 9 }).await;
+        bus.publish(UserRegistered { user_id: 0 }).await;
+        settle().await;
+
+        assert_eq!(enqueued.load(Ordering::SeqCst), 1);
+        runtime.shutdown_all().await.unwrap();
+    }
+}
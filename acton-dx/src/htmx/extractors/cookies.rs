@@ -0,0 +1,340 @@
+//! Cookie jar extractor
+//!
+//! [`Cookies`] reads the cookies sent with the request and collects changes
+//! to send back, so handlers don't parse `Cookie` or build `Set-Cookie`
+//! headers by hand. Return the jar as part of the response to apply the
+//! changes:
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::extractors::Cookies;
+//!
+//! async fn set_theme(mut cookies: Cookies) -> (Cookies, &'static str) {
+//!     let previous = cookies.get("theme").map(ToString::to_string);
+//!     cookies.set("theme", "dark");
+//!     cookies.set_signed("last_theme", previous.as_deref().unwrap_or("light"));
+//!     (cookies, "Theme saved")
+//! }
+//! ```
+//!
+//! New cookies use the defaults from `[security]`: `Secure` follows
+//! `secure_cookies`, `SameSite` follows `same_site`, and cookies are
+//! `HttpOnly`. Signed cookies use the application's [`CookieSigner`].
+//!
+//! Cookie values are sent as-is, so they must be valid cookie values (no
+//! spaces, commas, semicolons, or quotes); encode other values first.
+
+use crate::htmx::config::SecuritySettings;
+use crate::htmx::middleware::{CookieSigner, SameSite};
+use crate::htmx::state::ActonHtmxState;
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{
+        header::{COOKIE, SET_COOKIE},
+        request::Parts,
+        HeaderMap, HeaderValue,
+    },
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::time::Duration;
+
+/// Attributes of a cookie set through [`Cookies`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CookieOptions {
+    /// Cookie path (default: `/`)
+    pub path: String,
+    /// Lifetime; `None` makes a session cookie (default: `None`)
+    pub max_age: Option<Duration>,
+    /// Hide the cookie from JavaScript (default: true)
+    pub http_only: bool,
+    /// Send over HTTPS only (default: `security.secure_cookies`)
+    pub secure: bool,
+    /// `SameSite` policy (default: `security.same_site`)
+    pub same_site: SameSite,
+}
+
+impl CookieOptions {
+    /// Defaults from the security settings
+    #[must_use]
+    pub fn from_settings(settings: &SecuritySettings) -> Self {
+        Self {
+            secure: settings.secure_cookies,
+            same_site: settings.same_site.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Keep the cookie for `max_age`
+    #[must_use]
+    pub const fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+}
+
+impl Default for CookieOptions {
+    fn default() -> Self {
+        Self {
+            path: "/".to_string(),
+            max_age: None,
+            http_only: true,
+            secure: !cfg!(debug_assertions),
+            same_site: SameSite::Lax,
+        }
+    }
+}
+
+/// Request cookies plus pending changes for the response
+///
+/// Requires the router state to provide [`ActonHtmxState`].
+#[derive(Debug, Clone)]
+pub struct Cookies {
+    request: HashMap<String, String>,
+    pending: Vec<String>,
+    signer: CookieSigner,
+    defaults: CookieOptions,
+}
+
+impl Cookies {
+    /// Read the cookies in `headers`
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap, signer: CookieSigner, defaults: CookieOptions) -> Self {
+        let mut request = HashMap::new();
+        for header in headers.get_all(COOKIE) {
+            let Ok(header) = header.to_str() else {
+                continue;
+            };
+            for (name, value) in header
+                .split(';')
+                .filter_map(|cookie| cookie.trim().split_once('='))
+            {
+                request
+                    .entry(name.trim().to_string())
+                    .or_insert_with(|| value.trim().to_string());
+            }
+        }
+
+        Self {
+            request,
+            pending: Vec::new(),
+            signer,
+            defaults,
+        }
+    }
+
+    /// Value of a cookie sent with the request
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.request.get(name).map(String::as_str)
+    }
+
+    /// Value of a signed cookie sent with the request
+    ///
+    /// Returns `None` if the cookie is missing or its signature is invalid.
+    /// Signatures are bound to the cookie name, so a value signed for one
+    /// cookie isn't accepted under another.
+    #[must_use]
+    pub fn get_signed(&self, name: &str) -> Option<String> {
+        signer_for(&self.signer, name).verify(self.get(name)?)
+    }
+
+    /// Default attributes for new cookies
+    #[must_use]
+    pub const fn defaults(&self) -> &CookieOptions {
+        &self.defaults
+    }
+
+    /// Set a cookie with the default attributes
+    pub fn set(&mut self, name: &str, value: &str) {
+        let options = self.defaults.clone();
+        self.set_with(name, value, &options);
+    }
+
+    /// Set a signed cookie with the default attributes
+    pub fn set_signed(&mut self, name: &str, value: &str) {
+        let signed = signer_for(&self.signer, name).sign(value);
+        self.set(name, &signed);
+    }
+
+    /// Set a cookie with custom attributes
+    pub fn set_with(&mut self, name: &str, value: &str, options: &CookieOptions) {
+        let mut cookie = format!(
+            "{name}={value}; Path={}; SameSite={}",
+            options.path,
+            options.same_site.as_str()
+        );
+        if let Some(max_age) = options.max_age {
+            cookie = format!("{cookie}; Max-Age={}", max_age.as_secs());
+        }
+        if options.http_only {
+            cookie.push_str("; HttpOnly");
+        }
+        if options.secure {
+            cookie.push_str("; Secure");
+        }
+        self.pending.push(cookie);
+    }
+
+    /// Remove a cookie from the browser
+    pub fn remove(&mut self, name: &str) {
+        let options = self.defaults.clone().with_max_age(Duration::ZERO);
+        self.set_with(name, "", &options);
+    }
+
+    /// `Set-Cookie` values that will be sent with the response
+    #[must_use]
+    pub fn pending(&self) -> &[String] {
+        &self.pending
+    }
+}
+
+impl<S> FromRequestParts<S> for Cookies
+where
+    S: Send + Sync,
+    ActonHtmxState: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = ActonHtmxState::from_ref(state);
+        Ok(Self::from_headers(
+            &parts.headers,
+            state.cookie_signer().clone(),
+            CookieOptions::from_settings(&state.config().security),
+        ))
+    }
+}
+
+impl IntoResponseParts for Cookies {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        for cookie in self.pending {
+            match HeaderValue::try_from(cookie) {
+                Ok(value) => {
+                    res.headers_mut().append(SET_COOKIE, value);
+                }
+                Err(e) => tracing::warn!(error = %e, "Skipping invalid Set-Cookie value"),
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl IntoResponse for Cookies {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
+
+/// Signer for the cookie called `name`
+fn signer_for(signer: &CookieSigner, name: &str) -> CookieSigner {
+    signer.for_purpose(&format!("cookie:{name}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jar(cookie: Option<&str>) -> Cookies {
+        let mut headers = HeaderMap::new();
+        if let Some(cookie) = cookie {
+            headers.insert(COOKIE, HeaderValue::from_str(cookie).unwrap());
+        }
+        Cookies::from_headers(
+            &headers,
+            CookieSigner::new(b"test-secret"),
+            CookieOptions::default(),
+        )
+    }
+
+    fn set_cookies(cookies: Cookies) -> Vec<String> {
+        cookies
+            .into_response()
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect()
+    }
+
+    /// Value of a `Set-Cookie` header
+    fn value(set_cookie: &str) -> &str {
+        set_cookie.split(';').next().unwrap().split_once('=').unwrap().1
+    }
+
+    #[test]
+    fn test_get() {
+        let cookies = jar(Some("theme=dark; lang=en"));
+        assert_eq!(cookies.get("theme"), Some("dark"));
+        assert_eq!(cookies.get("lang"), Some("en"));
+        assert_eq!(cookies.get("missing"), None);
+    }
+
+    #[test]
+    fn test_signed_round_trip() {
+        let mut cookies = jar(None);
+        cookies.set_signed("user", "42");
+        let headers = set_cookies(cookies);
+
+        let next = jar(Some(&format!("user={}", value(&headers[0]))));
+        assert_eq!(next.get_signed("user").as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_tampered_signed_cookie_is_rejected() {
+        let mut cookies = jar(None);
+        cookies.set_signed("user", "42");
+        let signed = value(&set_cookies(cookies)[0]).to_string();
+
+        let tampered = jar(Some(&format!("user={}", signed.replacen("42", "43", 1))));
+        assert_eq!(tampered.get_signed("user"), None);
+        assert_eq!(jar(Some("user=42")).get_signed("user"), None);
+    }
+
+    #[test]
+    fn test_signed_cookie_is_bound_to_its_name() {
+        let mut cookies = jar(None);
+        cookies.set_signed("theme", "admin");
+        let signed = value(&set_cookies(cookies)[0]).to_string();
+
+        let renamed = jar(Some(&format!("role={signed}")));
+        assert_eq!(renamed.get_signed("role"), None);
+
+        // Nor is a value signed by another feature
+        let session = CookieSigner::new(b"test-secret").for_purpose("session").sign("admin");
+        assert_eq!(jar(Some(&format!("role={session}"))).get_signed("role"), None);
+    }
+
+    #[test]
+    fn test_multiple_sets_produce_multiple_headers() {
+        let mut cookies = jar(Some("old=1"));
+        cookies.set("theme", "dark");
+        cookies.set("lang", "en");
+        cookies.remove("old");
+
+        let headers = set_cookies(cookies);
+        assert_eq!(headers.len(), 3);
+        assert!(headers[0].starts_with("theme=dark; Path=/; SameSite=Lax"));
+        assert!(headers[1].starts_with("lang=en;"));
+        assert!(headers[2].starts_with("old=;") && headers[2].contains("Max-Age=0"));
+    }
+
+    #[test]
+    fn test_defaults_follow_security_settings() {
+        let settings = SecuritySettings {
+            secure_cookies: true,
+            same_site: crate::htmx::config::SameSitePolicy::Strict,
+            ..SecuritySettings::default()
+        };
+        let mut cookies = jar(None);
+        cookies.defaults = CookieOptions::from_settings(&settings);
+        cookies.set("theme", "dark");
+
+        assert_eq!(
+            set_cookies(cookies)[0],
+            "theme=dark; Path=/; SameSite=Strict; HttpOnly; Secure"
+        );
+    }
+}
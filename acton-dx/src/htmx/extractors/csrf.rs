@@ -15,7 +15,8 @@ use std::time::Duration;
 /// Extractor for CSRF token
 ///
 /// Retrieves or creates a CSRF token for the current session.
-/// Requires SessionMiddleware to be applied first.
+/// Requires SessionMiddleware to be applied first, unless `CsrfMiddleware`
+/// runs in double-submit mode and has already provided the token.
 ///
 /// # Example
 ///
@@ -60,6 +61,11 @@ where
     type Rejection = (StatusCode, String);
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Double-submit mode: the middleware supplies the cookie's token
+        if let Some(token) = parts.extensions.get::<CsrfToken>().cloned() {
+            return Ok(Self { token });
+        }
+
         // Extract state
         let state = ActonHtmxState::from_ref(state);
 
@@ -64,14 +64,52 @@
 //!     Ok(format!("Uploaded {} files", stored_ids.len()))
 //! }
 //! ```
+//!
+//! ## Streaming Large Files
+//!
+//! [`FileUpload`] and [`MultiFileUpload`] buffer each file in memory.
+//! [`StreamingFileUpload`] instead yields files as chunk streams that can be
+//! piped straight into [`FileStorage::store_stream`], enforcing the size
+//! limit as bytes arrive:
+//!
+//! ```rust,no_run
+//! use acton_htmx::extractors::StreamingFileUpload;
+//! use acton_htmx::storage::LocalFileStorage;
+//! use axum::{extract::State, response::IntoResponse};
+//!
+//! async fn upload_video(
+//!     State(storage): State<LocalFileStorage>,
+//!     upload: StreamingFileUpload,
+//! ) -> Result<impl IntoResponse, String> {
+//!     let mut upload = upload.max_size(500 * 1024 * 1024); // 500MB
+//!
+//!     let file = upload.next_file().await
+//!         .map_err(|e| e.to_string())?
+//!         .ok_or("No file uploaded")?;
+//!     let stored = file.store(&storage).await
+//!         .map_err(|e| e.to_string())?;
+//!
+//!     Ok(format!("File uploaded: {}", stored.id))
+//! }
+//! ```
+//!
+//! Axum's `DefaultBodyLimit` (2MB) still applies to the whole request body;
+//! raise or disable it on routes that accept large uploads.
+//!
+//! [`FileStorage::store_stream`]: crate::htmx::storage::FileStorage::store_stream
 
-use crate::htmx::storage::UploadedFile;
+use crate::htmx::storage::{FileStorage, StorageError, StoredFile, UploadedFile};
 use axum::{
+    body::Bytes,
     extract::{multipart::Field, FromRequest, Multipart, Request},
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
+    RequestExt,
 };
+use futures_util::{Stream, StreamExt};
 use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 /// Default maximum file size (10MB)
 pub const DEFAULT_MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
@@ -109,6 +147,9 @@ pub enum FileUploadError {
 
     /// Missing required field (filename or content-type)
     MissingField(String),
+
+    /// Storage backend failed while storing a streamed upload
+    Storage(StorageError),
 }
 
 impl fmt::Display for FileUploadError {
@@ -124,6 +165,7 @@ impl fmt::Display for FileUploadError {
                 write!(f, "Upload contains {actual} files, maximum is {max}")
             }
             Self::MissingField(field) => write!(f, "Missing required field: {field}"),
+            Self::Storage(e) => write!(f, "Failed to store upload: {e}"),
         }
     }
 }
@@ -137,6 +179,7 @@ impl IntoResponse for FileUploadError {
             Self::MissingFile | Self::MissingField(_) | Self::MultipleFiles | Self::TooManyFiles { .. } | Self::MultipartError(_) => {
                 StatusCode::BAD_REQUEST
             }
+            Self::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
 
         (status, self.to_string()).into_response()
@@ -307,6 +350,210 @@ where
     }
 }
 
+/// Extractor for streaming file uploads
+///
+/// Unlike [`FileUpload`], nothing is read when the extractor runs: files are
+/// pulled one at a time with [`next_file`](Self::next_file), and each
+/// [`StreamingFile`] yields its data in chunks. The per-file size limit
+/// (default [`DEFAULT_MAX_FILE_SIZE`]) is checked as chunks arrive, so an
+/// oversized upload fails with [`FileUploadError::FileTooLarge`] without
+/// reading the rest of the body.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use acton_htmx::extractors::StreamingFileUpload;
+/// use futures_util::StreamExt;
+///
+/// async fn handler(upload: StreamingFileUpload) -> Result<String, String> {
+///     let mut upload = upload.max_size(1024 * 1024);
+///     let mut total = 0;
+///
+///     while let Some(mut file) = upload.next_file().await.map_err(|e| e.to_string())? {
+///         while let Some(chunk) = file.next().await {
+///             total += chunk.map_err(|e| e.to_string())?.len();
+///         }
+///     }
+///
+///     Ok(format!("Received {total} bytes"))
+/// }
+/// ```
+#[derive(Debug)]
+pub struct StreamingFileUpload {
+    multipart: multer::Multipart<'static>,
+    max_size: usize,
+}
+
+impl StreamingFileUpload {
+    /// Set the maximum size of each file in bytes
+    #[must_use]
+    pub const fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Advance to the next file in the upload
+    ///
+    /// Non-file fields are skipped. Returns `Ok(None)` once the multipart body
+    /// is exhausted. The previous [`StreamingFile`] must be dropped first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileUploadError::MultipartError`] if the multipart body is
+    /// malformed or the previous file is still alive.
+    pub async fn next_file(&mut self) -> Result<Option<StreamingFile>, FileUploadError> {
+        let max_size = self.max_size;
+
+        while let Some(field) = self
+            .multipart
+            .next_field()
+            .await
+            .map_err(|e| FileUploadError::MultipartError(e.to_string()))?
+        {
+            let Some(filename) = field.file_name().map(ToString::to_string) else {
+                continue;
+            };
+
+            let content_type = field
+                .content_type()
+                .map_or_else(|| "application/octet-stream".to_string(), ToString::to_string);
+
+            return Ok(Some(StreamingFile {
+                filename,
+                content_type,
+                field,
+                max_size,
+                read: 0,
+                done: false,
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+impl<S> FromRequest<S> for StreamingFileUpload
+where
+    S: Send + Sync,
+{
+    type Rejection = FileUploadError;
+
+    async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
+        let boundary = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| FileUploadError::MultipartError("Missing Content-Type header".into()))
+            .and_then(|value| {
+                multer::parse_boundary(value)
+                    .map_err(|e| FileUploadError::MultipartError(e.to_string()))
+            })?;
+
+        // Multer fields own their data, so files can outlive the borrow of
+        // the upload; the body still honors `DefaultBodyLimit` like `Multipart`
+        let body = req.with_limited_body().into_body().into_data_stream();
+
+        Ok(Self {
+            multipart: multer::Multipart::new(body, boundary),
+            max_size: DEFAULT_MAX_FILE_SIZE,
+        })
+    }
+}
+
+/// A single file from a [`StreamingFileUpload`]
+///
+/// Yields the file data as a stream of chunks. Once more than the configured
+/// maximum has been read the stream yields [`FileUploadError::FileTooLarge`]
+/// and ends.
+#[derive(Debug)]
+pub struct StreamingFile {
+    /// Original filename from the upload
+    pub filename: String,
+    /// MIME type reported by the client
+    pub content_type: String,
+    field: multer::Field<'static>,
+    max_size: usize,
+    read: usize,
+    done: bool,
+}
+
+impl StreamingFile {
+    /// Stream this file into a storage backend
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileUploadError::FileTooLarge`] if the file exceeds the size
+    /// limit, [`FileUploadError::MultipartError`] if reading the upload fails,
+    /// or [`FileUploadError::Storage`] if the backend fails.
+    pub async fn store<S>(self, storage: &S) -> Result<StoredFile, FileUploadError>
+    where
+        S: FileStorage + ?Sized,
+    {
+        let filename = self.filename.clone();
+        let content_type = self.content_type.clone();
+        let max_size = self.max_size;
+
+        let stream = self
+            .map(|chunk| {
+                chunk.map_err(|e| match e {
+                    FileUploadError::FileTooLarge { actual, max } => {
+                        StorageError::FileSizeExceeded {
+                            actual: actual as u64,
+                            limit: max as u64,
+                        }
+                    }
+                    other => StorageError::Other(other.to_string()),
+                })
+            })
+            .boxed();
+
+        storage
+            .store_stream(&filename, &content_type, stream)
+            .await
+            .map_err(|e| match e {
+                StorageError::FileSizeExceeded { actual, .. } => FileUploadError::FileTooLarge {
+                    actual: usize::try_from(actual).unwrap_or(usize::MAX),
+                    max: max_size,
+                },
+                other => FileUploadError::Storage(other),
+            })
+    }
+}
+
+impl Stream for StreamingFile {
+    type Item = Result<Bytes, FileUploadError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(&mut self.field).poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                self.read += chunk.len();
+                if self.read > self.max_size {
+                    // Stop pulling from the body; the remainder is never read
+                    self.done = true;
+                    return Poll::Ready(Some(Err(FileUploadError::FileTooLarge {
+                        actual: self.read,
+                        max: self.max_size,
+                    })));
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(Some(Err(e))) => {
+                self.done = true;
+                Poll::Ready(Some(Err(FileUploadError::MultipartError(e.to_string()))))
+            }
+            Poll::Ready(None) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
 /// Reads field data with size limit enforcement
 ///
 /// This function reads the field data and enforces the maximum size limit
@@ -334,8 +581,11 @@ async fn read_field_data(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::htmx::storage::LocalFileStorage;
     use axum::http::{header, Request};
     use axum::body::Body;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
 
     fn create_multipart_request(files: Vec<(&str, &str, &[u8])>) -> Request<Body> {
         use std::fmt::Write;
@@ -425,6 +675,130 @@ mod tests {
         assert!(matches!(result.unwrap_err(), FileUploadError::MissingFile));
     }
 
+    const BOUNDARY: &str = "----WebKitFormBoundary7MA4YWxkTrZu0gW";
+
+    /// Multipart request streaming `size` bytes of file data in 64KB chunks,
+    /// counting how many bytes the body has handed out
+    ///
+    /// Each chunk yields to the runtime first, like data arriving over a
+    /// socket; otherwise the multipart parser would buffer the whole body.
+    fn streaming_multipart_request(size: usize, pulled: Arc<AtomicUsize>) -> Request<Body> {
+        const CHUNK: usize = 64 * 1024;
+
+        let head = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"big.bin\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\n"
+        );
+        let tail = format!("\r\n--{BOUNDARY}--\r\n");
+
+        let chunks = std::iter::once(Bytes::from(head))
+            .chain((0..size.div_ceil(CHUNK)).map(move |i| {
+                Bytes::from(vec![b'x'; CHUNK.min(size - i * CHUNK)])
+            }))
+            .chain(std::iter::once(Bytes::from(tail)));
+        let stream = futures_util::stream::iter(chunks).then(move |chunk| {
+            let pulled = pulled.clone();
+            async move {
+                tokio::task::yield_now().await;
+                pulled.fetch_add(chunk.len(), Ordering::SeqCst);
+                Ok::<_, std::convert::Infallible>(chunk)
+            }
+        });
+
+        Request::builder()
+            .method("POST")
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={BOUNDARY}"),
+            )
+            .body(Body::from_stream(stream))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_streaming_upload_yields_chunks() {
+        let req = streaming_multipart_request(200 * 1024, Arc::default());
+
+        let mut upload = StreamingFileUpload::from_request(req, &()).await.unwrap();
+        let mut file = upload.next_file().await.unwrap().unwrap();
+        assert_eq!(file.filename, "big.bin");
+        assert_eq!(file.content_type, "application/octet-stream");
+
+        let mut total = 0;
+        while let Some(chunk) = file.next().await {
+            total += chunk.unwrap().len();
+        }
+        assert_eq!(total, 200 * 1024);
+
+        drop(file);
+        assert!(upload.next_file().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_streaming_upload_rejects_oversized_file_mid_stream() {
+        const MAX: usize = 1024 * 1024;
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let req = streaming_multipart_request(10 * 1024 * 1024, pulled.clone());
+
+        let upload = StreamingFileUpload::from_request(req, &()).await.unwrap();
+        let mut upload = upload.max_size(MAX);
+        let mut file = upload.next_file().await.unwrap().unwrap();
+
+        let mut error = None;
+        while let Some(chunk) = file.next().await {
+            if let Err(e) = chunk {
+                error = Some(e);
+                break;
+            }
+        }
+
+        assert!(matches!(
+            error,
+            Some(FileUploadError::FileTooLarge { actual, max: MAX }) if actual > MAX
+        ));
+        assert!(file.next().await.is_none());
+        assert!(pulled.load(Ordering::SeqCst) < 2 * MAX);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_store_aborts_at_limit() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = LocalFileStorage::new(temp.path().to_path_buf()).unwrap();
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let req = streaming_multipart_request(10 * 1024 * 1024, pulled.clone());
+
+        let upload = StreamingFileUpload::from_request(req, &()).await.unwrap();
+        let mut upload = upload.max_size(1024 * 1024);
+        let file = upload.next_file().await.unwrap().unwrap();
+
+        let result = file.store(&storage).await;
+        assert!(matches!(
+            result,
+            Err(FileUploadError::FileTooLarge { max, .. }) if max == 1024 * 1024
+        ));
+        assert!(pulled.load(Ordering::SeqCst) < 2 * 1024 * 1024);
+
+        // The partially written file is cleaned up
+        let mut entries = std::fs::read_dir(temp.path()).unwrap();
+        assert!(entries.all(|entry| {
+            std::fs::read_dir(entry.unwrap().path()).unwrap().next().is_none()
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_store_writes_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let storage = LocalFileStorage::new(temp.path().to_path_buf()).unwrap();
+        let req = streaming_multipart_request(300 * 1024, Arc::default());
+
+        let mut upload = StreamingFileUpload::from_request(req, &()).await.unwrap();
+        let file = upload.next_file().await.unwrap().unwrap();
+
+        let stored = file.store(&storage).await.unwrap();
+        assert_eq!(stored.size, 300 * 1024);
+        assert_eq!(storage.retrieve(&stored.id).await.unwrap().len(), 300 * 1024);
+    }
+
     // Note: Testing file size limits with mock multipart requests is complex because
     // creating large binary multipart bodies requires proper encoding. The size validation
     // logic in read_field_data() works correctly, but testing it would require a more
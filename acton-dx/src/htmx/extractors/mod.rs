@@ -1,16 +1,27 @@
 //! Axum extractors for acton-dx
 //!
 //! Provides extractors for accessing session data, flash messages,
-//! CSRF tokens, validation, file uploads, and other request context within handlers.
+//! cookies, CSRF tokens, validation, file uploads, signed webhooks, and other
+//! request context within handlers.
 
+mod cookies;
 mod csrf;
 mod file_upload;
 mod session;
 mod validated;
+mod webhook;
 
+pub use cookies::{CookieOptions, Cookies};
 pub use csrf::CsrfTokenExtractor;
-pub use file_upload::{FileUpload, FileUploadError, MultiFileUpload};
+pub use file_upload::{
+    FileUpload, FileUploadError, MultiFileUpload, StreamingFile, StreamingFileUpload,
+};
 pub use session::{FlashExtractor, OptionalSession, SessionExtractor};
 pub use validated::{
-    format_validation_errors, validation_errors_json, ValidatedForm, ValidationError,
+    format_validation_errors, multiselect, validation_errors_json, ValidatedForm, ValidatedQuery,
+    ValidationError,
+};
+pub use webhook::{
+    SignatureScheme, SignedWebhook, WebhookError, WebhookVerifier, DEFAULT_STRIPE_TOLERANCE_SECS,
+    GITHUB_SIGNATURE_HEADER, STRIPE_SIGNATURE_HEADER,
 };
@@ -7,6 +7,8 @@
 //! Flash messages can be consumed (cleared after read) via `FlashExtractor`.
 
 use crate::htmx::auth::session::{FlashMessage, SessionData, SessionId};
+use crate::htmx::auth::Session;
+use crate::htmx::middleware::{is_htmx_request, FlashJar};
 use axum::{
     extract::FromRequestParts,
     http::{request::Parts, StatusCode},
@@ -55,6 +57,30 @@ where
     }
 }
 
+/// Requires `SessionMiddleware` to be applied to the router.
+///
+/// Changes made through the extracted [`Session`] are only saved when the
+/// session data is returned in the response extensions, as
+/// [`prg`](crate::htmx::responses::prg) does.
+impl<S> FromRequestParts<S> for Session
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let SessionExtractor(session_id, session_data) =
+            SessionExtractor::from_request_parts(parts, state).await?;
+
+        let session =
+            Self::new(session_id, session_data).with_htmx_request(is_htmx_request(&parts.headers));
+        Ok(match parts.extensions.get::<FlashJar>() {
+            Some(jar) => session.with_flash_jar(jar.clone()),
+            None => session,
+        })
+    }
+}
+
 /// Extractor for flash messages
 ///
 /// Extracts flash messages from the session and clears them from the session data.
@@ -66,6 +92,10 @@ where
 /// clearing them so they won't be persisted back. The middleware will save the
 /// modified session data (without the flashes) on response.
 ///
+/// With [`FlashCookieLayer`](crate::htmx::middleware::FlashCookieLayer)
+/// installed, the messages are taken from the flash cookie instead, which is
+/// cleared on response.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -87,6 +117,10 @@ where
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(jar) = parts.extensions.get::<FlashJar>() {
+            return Ok(Self(jar.take()));
+        }
+
         // Take flash messages from session data in extensions (clears them)
         let messages = parts
             .extensions
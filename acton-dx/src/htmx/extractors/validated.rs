@@ -23,16 +23,41 @@
 //!     Html(format!("Logged in as {}", form.email))
 //! }
 //! ```
+//!
+//! # Multi-Value Fields and Checkboxes
+//!
+//! Form data is parsed with HTML form semantics:
+//! - Repeated keys (`tags=1&tags=2`, from multi-selects and checkbox groups)
+//!   deserialize into `Vec<T>`.
+//! - A checkbox deserializes into `bool`: `on`/`true` is `true`. Browsers
+//!   omit unchecked checkboxes entirely, so mark the field `#[serde(default)]`
+//!   to read absent as `false`.
+//! - Browsers also omit multi-selects with nothing selected. Use
+//!   `#[serde(default)] Vec<T>` when "none selected" and "not submitted" mean
+//!   the same thing, or [`multiselect`] with a hidden empty sentinel input to
+//!   tell them apart.
+//!
+//! # Query Strings
+//!
+//! [`ValidatedQuery`] applies the same parsing and validation to the URL
+//! query of any request, for live-search and filter endpoints. Validation
+//! failures on HTMX requests render the field errors as an HTML partial so
+//! they can be swapped straight into the page.
 
 use axum::{
-    extract::{Form, FromRequest, Request},
-    http::StatusCode,
-    response::{IntoResponse, Response},
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Request},
+    http::{header, request::Parts, Method, StatusCode},
+    response::{Html, IntoResponse, Response},
 };
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
 use std::fmt;
+use std::str::FromStr;
 use validator::Validate;
 
+use crate::htmx::middleware::is_htmx_request;
+use crate::htmx::template::helpers::escape_html;
+
 /// Validated form extractor
 ///
 /// Automatically deserializes and validates form data using the validator crate.
@@ -76,12 +101,9 @@ where
     type Rejection = ValidationError;
 
     async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
-        // Extract form data using standard Form extractor
-        let Form(data) = Form::<T>::from_request(req, state)
-            .await
-            .map_err(|err| {
-                ValidationError::FormRejection(format!("Failed to parse form data: {err}"))
-            })?;
+        let data: T = parse_form(req, state).await.map_err(|err| {
+            ValidationError::FormRejection(format!("Failed to parse form data: {err}"))
+        })?;
 
         // Validate the data
         data.validate()
@@ -91,6 +113,129 @@ where
     }
 }
 
+/// Validated query string extractor
+///
+/// Deserializes the URL query string with the same semantics as
+/// [`ValidatedForm`] (repeated keys as `Vec<T>`) and validates it with the
+/// validator crate. Works with any HTTP method and never reads the body.
+///
+/// Validation failures return 422 Unprocessable Entity. For HTMX requests
+/// the body is an HTML partial listing each field error; otherwise it is the
+/// same plain-text summary [`ValidatedForm`] returns.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use acton_dx::htmx::extractors::ValidatedQuery;
+/// use axum::response::Html;
+/// use serde::Deserialize;
+/// use validator::Validate;
+///
+/// #[derive(Debug, Deserialize, Validate)]
+/// struct SearchQuery {
+///     #[validate(length(min = 2, max = 100))]
+///     q: String,
+///     #[serde(default)]
+///     page: u32,
+/// }
+///
+/// async fn search(ValidatedQuery(query): ValidatedQuery<SearchQuery>) -> Html<String> {
+///     Html(format!("Results for {} (page {})", query.q, query.page))
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ValidationError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+        let data: T = serde_html_form::from_str(query).map_err(|err| {
+            ValidationError::FormRejection(format!("Failed to parse query string: {err}"))
+        })?;
+
+        data.validate().map_err(|errors| {
+            ValidationError::validation(errors, is_htmx_request(&parts.headers))
+        })?;
+
+        Ok(Self(data))
+    }
+}
+
+/// Deserialize form data from the query string (GET/HEAD) or an
+/// `application/x-www-form-urlencoded` body, with repeated keys as sequences
+async fn parse_form<T, S>(req: Request, state: &S) -> Result<T, String>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    if matches!(*req.method(), Method::GET | Method::HEAD) {
+        let query = req.uri().query().unwrap_or_default();
+        return serde_html_form::from_str(query).map_err(|e| e.to_string());
+    }
+
+    let is_form = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"));
+
+    if !is_form {
+        return Err("Expected request with `Content-Type: application/x-www-form-urlencoded`".into());
+    }
+
+    let body = Bytes::from_request(req, state)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    serde_html_form::from_bytes(&body).map_err(|e| e.to_string())
+}
+
+/// Deserialize a multi-select that distinguishes "none selected" from "not
+/// submitted"
+///
+/// Render a hidden input with the same name and an empty value before the
+/// select (`<input type="hidden" name="tags" value="">`). Empty values are
+/// dropped, so:
+/// - field absent → `None` (requires `#[serde(default)]`)
+/// - only the sentinel → `Some(vec![])`
+/// - `tags=&tags=1&tags=2` → `Some(vec![1, 2])`
+///
+/// # Errors
+///
+/// Returns a deserialization error if a non-empty value fails to parse as `T`.
+///
+/// # Example
+///
+/// ```rust
+/// use acton_dx::htmx::extractors::multiselect;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct TagsForm {
+///     #[serde(default, deserialize_with = "multiselect")]
+///     tags: Option<Vec<u32>>,
+/// }
+/// ```
+pub fn multiselect<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .collect::<Result<Vec<T>, _>>()
+        .map(Some)
+}
+
 /// Validation error response
 ///
 /// Returned when form validation fails. Contains detailed error information
@@ -101,13 +246,32 @@ pub enum ValidationError {
     FormRejection(String),
     /// Validation failed (data parsed but invalid)
     Validation(validator::ValidationErrors),
+    /// Validation failed on an HTMX request; responds with an HTML partial
+    HtmxValidation(validator::ValidationErrors),
+}
+
+impl ValidationError {
+    /// Create a validation failure appropriate for the request type.
+    ///
+    /// # Returns
+    ///
+    /// * [`HtmxValidation`](Self::HtmxValidation) for HTMX requests
+    /// * [`Validation`](Self::Validation) for regular requests
+    #[must_use]
+    pub const fn validation(errors: validator::ValidationErrors, is_htmx: bool) -> Self {
+        if is_htmx {
+            Self::HtmxValidation(errors)
+        } else {
+            Self::Validation(errors)
+        }
+    }
 }
 
 impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::FormRejection(msg) => write!(f, "Form parsing error: {msg}"),
-            Self::Validation(errors) => {
+            Self::Validation(errors) | Self::HtmxValidation(errors) => {
                 write!(f, "Validation failed: ")?;
                 for (field, errors) in errors.field_errors() {
                     write!(f, "{field}: ")?;
@@ -141,10 +305,44 @@ impl IntoResponse for ValidationError {
                 )
                     .into_response()
             }
+            Self::HtmxValidation(errors) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Html(validation_errors_html(&errors)),
+            )
+                .into_response(),
         }
     }
 }
 
+/// Render validation errors as an HTML list for HTMX swaps
+///
+/// Each item carries a `data-field` attribute naming the invalid field.
+fn validation_errors_html(errors: &validator::ValidationErrors) -> String {
+    use std::fmt::Write;
+
+    let mut fields: Vec<_> = errors.field_errors().into_iter().collect();
+    fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut html = String::from(r#"<ul class="validation-errors">"#);
+    for (field, field_errors) in fields {
+        for error in field_errors {
+            let message = error.message.as_ref().map_or_else(
+                || format!("{field}: {}", error.code),
+                ToString::to_string,
+            );
+            write!(
+                html,
+                r#"<li data-field="{}">{}</li>"#,
+                escape_html(&field),
+                escape_html(&message)
+            )
+            .unwrap();
+        }
+    }
+    html.push_str("</ul>");
+    html
+}
+
 /// Format validation errors for display
 ///
 /// Converts validator::ValidationErrors into a human-readable format.
@@ -302,6 +500,163 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
+    #[derive(Debug, Deserialize, Validate)]
+    struct PreferencesForm {
+        #[serde(default)]
+        newsletter: bool,
+        #[serde(default)]
+        tags: Vec<u32>,
+        #[serde(default, deserialize_with = "multiselect")]
+        roles: Option<Vec<String>>,
+    }
+
+    async fn preferences_handler(ValidatedForm(form): ValidatedForm<PreferencesForm>) -> String {
+        format!("{}|{:?}|{:?}", form.newsletter, form.tags, form.roles)
+    }
+
+    async fn post_preferences(body: &'static str) -> String {
+        let app = Router::new().route("/", post(preferences_handler));
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_repeated_keys_into_vec() {
+        let body = post_preferences("newsletter=on&tags=1&tags=2&roles=admin&roles=editor").await;
+        assert_eq!(body, r#"true|[1, 2]|Some(["admin", "editor"])"#);
+    }
+
+    #[tokio::test]
+    async fn test_unchecked_checkbox_is_false() {
+        // Unchecked checkboxes and empty multi-selects are absent entirely
+        let body = post_preferences("").await;
+        assert_eq!(body, "false|[]|None");
+    }
+
+    #[tokio::test]
+    async fn test_multiselect_sentinel_is_empty_not_missing() {
+        let body = post_preferences("roles=").await;
+        assert_eq!(body, "false|[]|Some([])");
+    }
+
+    #[tokio::test]
+    async fn test_get_form_reads_query() {
+        async fn handler(ValidatedForm(form): ValidatedForm<PreferencesForm>) -> String {
+            format!("{:?}", form.tags)
+        }
+
+        let app = Router::new().route("/", axum::routing::get(handler));
+        let request = Request::builder()
+            .uri("/?tags=3&tags=4")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"[3, 4]");
+    }
+
+    #[tokio::test]
+    async fn test_wrong_content_type_rejected() {
+        let app = Router::new().route("/", post(test_handler));
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header("content-type", "application/json")
+            .body(Body::from("{}"))
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[derive(Debug, Deserialize, Validate)]
+    struct SearchQuery {
+        #[validate(length(min = 2))]
+        q: String,
+        #[serde(default)]
+        page: u32,
+    }
+
+    async fn search_handler(ValidatedQuery(query): ValidatedQuery<SearchQuery>) -> String {
+        format!("{}|{}", query.q, query.page)
+    }
+
+    async fn get_search(uri: &str, htmx: bool) -> (StatusCode, Option<String>, String) {
+        let app = Router::new().route("/search", axum::routing::get(search_handler));
+
+        let mut request = Request::builder().uri(uri);
+        if htmx {
+            request = request.header("HX-Request", "true");
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .map(|value| value.to_str().unwrap().to_string());
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, content_type, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_valid_query() {
+        let (status, _, body) = get_search("/search?q=rust&page=2", false).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "rust|2");
+    }
+
+    #[tokio::test]
+    async fn test_query_missing_required_field() {
+        let (status, _, body) = get_search("/search?page=2", false).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("missing field `q`"));
+    }
+
+    #[tokio::test]
+    async fn test_query_validation_error_htmx_renders_partial() {
+        let (status, content_type, body) = get_search("/search?q=r", true).await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(content_type.unwrap().starts_with("text/html"));
+        assert_eq!(
+            body,
+            r#"<ul class="validation-errors"><li data-field="q">q: length</li></ul>"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_validation_error_non_htmx_is_text() {
+        let (status, content_type, body) = get_search("/search?q=r", false).await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(content_type.unwrap().starts_with("text/plain"));
+        assert_eq!(body, "Validation failed:\nq: length");
+    }
+
     #[test]
     fn test_format_validation_errors() {
         let mut errors = validator::ValidationErrors::new();
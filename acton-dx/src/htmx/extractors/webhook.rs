@@ -0,0 +1,482 @@
+//! Signed webhook extractor
+//!
+//! [`SignedWebhook<T>`] authenticates webhook deliveries by their HMAC-SHA256
+//! signature before deserializing the JSON body into `T`. The signature is
+//! computed over the raw body bytes exactly as received, so the body is read
+//! in full and verified before any parsing happens.
+//!
+//! Each route supplies its provider's secret and signature scheme as a
+//! [`WebhookVerifier`] extension. Webhook routes usually can't send a CSRF
+//! token, so exempt them with
+//! [`CsrfConfig::skip_path`](crate::htmx::middleware::CsrfConfig::skip_path):
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::extractors::{SignedWebhook, WebhookVerifier};
+//! use axum::{routing::post, Extension, Router};
+//!
+//! async fn github_push(SignedWebhook(event): SignedWebhook<serde_json::Value>) -> &'static str {
+//!     tracing::info!(?event, "push received");
+//!     "ok"
+//! }
+//!
+//! let app = Router::new().route(
+//!     "/webhooks/github",
+//!     post(github_push).layer(Extension(WebhookVerifier::github(github_secret))),
+//! );
+//! ```
+//!
+//! # Schemes
+//!
+//! - [`SignatureScheme::Hex`]: the header holds the hex digest of the body,
+//!   after an optional prefix. [`WebhookVerifier::github`] reads
+//!   `X-Hub-Signature-256: sha256=<hex>`.
+//! - [`SignatureScheme::Stripe`]: the header holds `t=<unix time>,v1=<hex>`
+//!   and the digest covers `"{t}.{body}"`. Deliveries whose timestamp is
+//!   outside the tolerance are rejected, so a captured request can't be
+//!   replayed later. [`WebhookVerifier::stripe`] reads `Stripe-Signature`.
+
+use crate::htmx::middleware::cookie_signer::unix_now;
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{HeaderMap, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
+};
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+use std::sync::Arc;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// GitHub's signature header
+pub const GITHUB_SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+/// Stripe's signature header
+pub const STRIPE_SIGNATURE_HEADER: &str = "stripe-signature";
+
+/// Default tolerance for Stripe signature timestamps (5 minutes, as in
+/// Stripe's libraries)
+pub const DEFAULT_STRIPE_TOLERANCE_SECS: u64 = 300;
+
+/// How a provider encodes the signature header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureScheme {
+    /// Hex HMAC-SHA256 of the body following `prefix` (e.g. `sha256=`)
+    Hex {
+        /// Text preceding the hex digest; may be empty
+        prefix: String,
+    },
+    /// Stripe's timestamped `t=<unix time>,v1=<hex>` scheme
+    ///
+    /// The digest covers `"{t}.{body}"`. Any of several `v1` signatures may
+    /// match, which allows secret rotation.
+    Stripe {
+        /// Largest accepted difference between the timestamp and now
+        tolerance_secs: u64,
+    },
+}
+
+/// Webhook signature verification failures
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    /// No [`WebhookVerifier`] extension on the route
+    #[error("No webhook verifier is configured for this route")]
+    MissingVerifier,
+
+    /// The signature header is absent
+    #[error("Missing webhook signature")]
+    MissingSignature,
+
+    /// The signature header can't be parsed
+    #[error("Malformed webhook signature")]
+    MalformedSignature,
+
+    /// The signature doesn't match the body
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+
+    /// The signed timestamp is outside the tolerance
+    #[error("Webhook timestamp is outside the tolerance")]
+    StaleTimestamp,
+
+    /// The body couldn't be read
+    #[error("Failed to read webhook body: {0}")]
+    Body(String),
+
+    /// The verified body isn't valid JSON for the target type
+    #[error("Invalid webhook payload: {0}")]
+    Payload(String),
+}
+
+impl IntoResponse for WebhookError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Self::MissingVerifier => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::MissingSignature
+            | Self::MalformedSignature
+            | Self::InvalidSignature
+            | Self::StaleTimestamp => StatusCode::UNAUTHORIZED,
+            Self::Body(_) => StatusCode::BAD_REQUEST,
+            Self::Payload(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+
+        (status, self.to_string()).into_response()
+    }
+}
+
+/// A provider's webhook secret and signature scheme
+///
+/// Add to a route as an [`Extension`](axum::Extension) for
+/// [`SignedWebhook`] to find.
+#[derive(Clone)]
+pub struct WebhookVerifier {
+    header: HeaderName,
+    secret: Arc<[u8]>,
+    scheme: SignatureScheme,
+}
+
+impl std::fmt::Debug for WebhookVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookVerifier")
+            .field("header", &self.header)
+            .field("secret", &"[redacted]")
+            .field("scheme", &self.scheme)
+            .finish()
+    }
+}
+
+impl WebhookVerifier {
+    /// Verify signatures in `header` with `secret` and `scheme`
+    #[must_use]
+    pub fn new(header: HeaderName, secret: impl AsRef<[u8]>, scheme: SignatureScheme) -> Self {
+        Self {
+            header,
+            secret: Arc::from(secret.as_ref()),
+            scheme,
+        }
+    }
+
+    /// GitHub: `X-Hub-Signature-256: sha256=<hex>`
+    #[must_use]
+    pub fn github(secret: impl AsRef<[u8]>) -> Self {
+        Self::new(
+            HeaderName::from_static(GITHUB_SIGNATURE_HEADER),
+            secret,
+            SignatureScheme::Hex {
+                prefix: "sha256=".to_string(),
+            },
+        )
+    }
+
+    /// Stripe: `Stripe-Signature: t=<unix time>,v1=<hex>` with
+    /// [`DEFAULT_STRIPE_TOLERANCE_SECS`]
+    #[must_use]
+    pub fn stripe(secret: impl AsRef<[u8]>) -> Self {
+        Self::new(
+            HeaderName::from_static(STRIPE_SIGNATURE_HEADER),
+            secret,
+            SignatureScheme::Stripe {
+                tolerance_secs: DEFAULT_STRIPE_TOLERANCE_SECS,
+            },
+        )
+    }
+
+    /// Check the signature in `headers` against `body`
+    ///
+    /// `now` is the current Unix time, used by timestamped schemes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WebhookError`] if the signature is missing, malformed, stale,
+    /// or doesn't match
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8], now: u64) -> Result<(), WebhookError> {
+        let header = headers
+            .get(&self.header)
+            .ok_or(WebhookError::MissingSignature)?
+            .to_str()
+            .map_err(|_| WebhookError::MalformedSignature)?;
+
+        match &self.scheme {
+            SignatureScheme::Hex { prefix } => {
+                let signature = header
+                    .strip_prefix(prefix.as_str())
+                    .and_then(|hex| hex::decode(hex).ok())
+                    .ok_or(WebhookError::MalformedSignature)?;
+                self.check(&[body], &signature)
+            }
+            SignatureScheme::Stripe { tolerance_secs } => {
+                let (timestamp, signatures) = parse_stripe_header(header)?;
+                if timestamp.abs_diff(now) > *tolerance_secs {
+                    return Err(WebhookError::StaleTimestamp);
+                }
+                let signed_prefix = format!("{timestamp}.");
+                signatures
+                    .iter()
+                    .find_map(|signature| {
+                        self.check(&[signed_prefix.as_bytes(), body], signature).ok()
+                    })
+                    .ok_or(WebhookError::InvalidSignature)
+            }
+        }
+    }
+
+    /// Compare the HMAC of `parts` with `signature` in constant time
+    fn check(&self, parts: &[&[u8]], signature: &[u8]) -> Result<(), WebhookError> {
+        let mut mac = HmacSha256::new_from_slice(&self.secret)
+            .map_err(|_| WebhookError::InvalidSignature)?;
+        for part in parts {
+            mac.update(part);
+        }
+        mac.verify_slice(signature)
+            .map_err(|_| WebhookError::InvalidSignature)
+    }
+}
+
+/// Split a Stripe signature header into its timestamp and `v1` signatures
+fn parse_stripe_header(header: &str) -> Result<(u64, Vec<Vec<u8>>), WebhookError> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for (key, value) in header.split(',').filter_map(|item| item.trim().split_once('=')) {
+        match key {
+            "t" => timestamp = value.parse().ok(),
+            "v1" => signatures
+                .push(hex::decode(value).map_err(|_| WebhookError::MalformedSignature)?),
+            // Other schemes (e.g. v0 test signatures) are ignored
+            _ => {}
+        }
+    }
+
+    match timestamp {
+        Some(timestamp) if !signatures.is_empty() => Ok((timestamp, signatures)),
+        _ => Err(WebhookError::MalformedSignature),
+    }
+}
+
+/// Extractor for a webhook whose signature has been verified
+///
+/// Reads the raw body, verifies it with the route's [`WebhookVerifier`]
+/// extension, and only then deserializes it as JSON into `T`. Requests with
+/// a missing or wrong signature are rejected with 401 before `T` is parsed.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::extractors::{SignedWebhook, WebhookVerifier};
+/// use axum::{routing::post, Extension, Router};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct StripeEvent {
+///     id: String,
+///     r#type: String,
+/// }
+///
+/// async fn stripe_event(SignedWebhook(event): SignedWebhook<StripeEvent>) -> &'static str {
+///     tracing::info!(id = %event.id, kind = %event.r#type, "Stripe event");
+///     "ok"
+/// }
+///
+/// let app = Router::new().route(
+///     "/webhooks/stripe",
+///     post(stripe_event).layer(Extension(WebhookVerifier::stripe(stripe_secret))),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SignedWebhook<T>(pub T);
+
+impl<T, S> FromRequest<S> for SignedWebhook<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = WebhookError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let verifier = req
+            .extensions()
+            .get::<WebhookVerifier>()
+            .cloned()
+            .ok_or(WebhookError::MissingVerifier)?;
+        let headers = req.headers().clone();
+        let body = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| WebhookError::Body(e.to_string()))?;
+
+        verifier.verify(&headers, &body, unix_now())?;
+
+        serde_json::from_slice(&body)
+            .map(Self)
+            .map_err(|e| WebhookError::Payload(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::post, Extension, Router};
+    use serde::Deserialize;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &str = r#"{"action":"opened","number":7}"#;
+
+    fn hex_hmac(secret: &str, message: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(message);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn headers(name: &str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            value.parse().unwrap(),
+        );
+        headers
+    }
+
+    fn stripe_header(secret: &str, timestamp: u64, body: &str) -> String {
+        let signature = hex_hmac(secret, format!("{timestamp}.{body}").as_bytes());
+        format!("t={timestamp},v1={signature}")
+    }
+
+    #[test]
+    fn test_github_signature() {
+        let verifier = WebhookVerifier::github(SECRET);
+        let valid = format!("sha256={}", hex_hmac(SECRET, BODY.as_bytes()));
+
+        let result =
+            verifier.verify(&headers(GITHUB_SIGNATURE_HEADER, &valid), BODY.as_bytes(), 0);
+        assert!(result.is_ok());
+
+        let result = verifier.verify(&headers(GITHUB_SIGNATURE_HEADER, &valid), b"{}", 0);
+        assert!(matches!(result, Err(WebhookError::InvalidSignature)));
+
+        let wrong_key = format!("sha256={}", hex_hmac("other", BODY.as_bytes()));
+        let result =
+            verifier.verify(&headers(GITHUB_SIGNATURE_HEADER, &wrong_key), BODY.as_bytes(), 0);
+        assert!(matches!(result, Err(WebhookError::InvalidSignature)));
+
+        let unprefixed = hex_hmac(SECRET, BODY.as_bytes());
+        let result =
+            verifier.verify(&headers(GITHUB_SIGNATURE_HEADER, &unprefixed), BODY.as_bytes(), 0);
+        assert!(matches!(result, Err(WebhookError::MalformedSignature)));
+
+        let result = verifier.verify(&HeaderMap::new(), BODY.as_bytes(), 0);
+        assert!(matches!(result, Err(WebhookError::MissingSignature)));
+    }
+
+    #[test]
+    fn test_stripe_signature() {
+        let verifier = WebhookVerifier::stripe(SECRET);
+        let now = 1_700_000_000;
+        let header = stripe_header(SECRET, now, BODY);
+        let verify = |header: &str, body: &str| {
+            verifier.verify(&headers(STRIPE_SIGNATURE_HEADER, header), body.as_bytes(), now + 10)
+        };
+
+        assert!(verify(&header, BODY).is_ok());
+
+        // Any v1 signature may match, so rotated secrets keep working
+        let (_, current) = header.split_once(',').unwrap();
+        let rotated = format!("{},{current}", stripe_header("old", now, BODY));
+        assert!(verify(&rotated, BODY).is_ok());
+
+        assert!(matches!(verify(&header, "{}"), Err(WebhookError::InvalidSignature)));
+
+        // Moving the timestamp invalidates the signature
+        let moved = header.replace(&format!("t={now}"), &format!("t={}", now + 1));
+        assert!(matches!(verify(&moved, BODY), Err(WebhookError::InvalidSignature)));
+
+        assert!(matches!(verify("v1=abcd", BODY), Err(WebhookError::MalformedSignature)));
+    }
+
+    #[test]
+    fn test_stripe_replay_with_stale_timestamp() {
+        let verifier = WebhookVerifier::stripe(SECRET);
+        let signed_at = 1_700_000_000;
+        let header = headers(STRIPE_SIGNATURE_HEADER, &stripe_header(SECRET, signed_at, BODY));
+
+        let replayed_at = signed_at + DEFAULT_STRIPE_TOLERANCE_SECS + 1;
+        let result = verifier.verify(&header, BODY.as_bytes(), replayed_at);
+        assert!(matches!(result, Err(WebhookError::StaleTimestamp)));
+
+        let just_in_time = signed_at + DEFAULT_STRIPE_TOLERANCE_SECS;
+        assert!(verifier.verify(&header, BODY.as_bytes(), just_in_time).is_ok());
+    }
+
+    #[derive(Deserialize)]
+    struct PullRequestEvent {
+        action: String,
+        number: u32,
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/webhooks/github",
+                post(|SignedWebhook(event): SignedWebhook<PullRequestEvent>| async move {
+                    format!("{} #{}", event.action, event.number)
+                })
+                .layer(Extension(WebhookVerifier::github(SECRET))),
+            )
+            .route(
+                "/webhooks/unconfigured",
+                post(|SignedWebhook(_): SignedWebhook<serde_json::Value>| async { "ok" }),
+            )
+    }
+
+    async fn deliver(path: &str, signature: Option<String>, body: &str) -> (StatusCode, String) {
+        let mut request = Request::post(path);
+        if let Some(signature) = signature {
+            request = request.header(GITHUB_SIGNATURE_HEADER, signature);
+        }
+        let response = app()
+            .oneshot(request.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_extractor_accepts_valid_signature() {
+        let signature = format!("sha256={}", hex_hmac(SECRET, BODY.as_bytes()));
+        let (status, body) = deliver("/webhooks/github", Some(signature), BODY).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "opened #7");
+    }
+
+    #[tokio::test]
+    async fn test_extractor_rejects_bad_signature() {
+        let signature = format!("sha256={}", hex_hmac("attacker", BODY.as_bytes()));
+        let (status, _) = deliver("/webhooks/github", Some(signature), BODY).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = deliver("/webhooks/github", None, BODY).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_extractor_verifies_before_parsing() {
+        // A correctly signed body of the wrong shape fails parsing afterwards
+        let body = r#"{"action":"opened"}"#;
+        let signature = format!("sha256={}", hex_hmac(SECRET, body.as_bytes()));
+        let (status, _) = deliver("/webhooks/github", Some(signature), body).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // An unsigned body is rejected even if it isn't JSON
+        let (status, _) = deliver("/webhooks/github", None, "not json").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_extractor_requires_verifier() {
+        let (status, _) = deliver("/webhooks/unconfigured", None, BODY).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -3,7 +3,9 @@
 //! Provides error types for form validation that integrate with
 //! the `validator` crate and support HTMX partial updates.
 
-use std::collections::HashMap;
+use crate::htmx::template::helpers::{escape_html, templates};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
 
 /// A single validation error for a field
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -58,6 +60,7 @@ impl std::fmt::Display for FieldError {
 #[derive(Debug, Clone, Default)]
 pub struct ValidationErrors {
     errors: HashMap<String, Vec<FieldError>>,
+    known_fields: BTreeSet<String>,
 }
 
 impl ValidationErrors {
@@ -133,6 +136,7 @@ impl ValidationErrors {
                 .or_default()
                 .extend(errors.iter().cloned());
         }
+        self.known_fields.extend(other.known_fields.iter().cloned());
     }
 
     /// Iterate over all errors
@@ -141,6 +145,100 @@ impl ValidationErrors {
             .iter()
             .map(|(k, v)| (k.as_str(), v.as_slice()))
     }
+
+    /// Register the form's fields so [`to_oob_swaps`](Self::to_oob_swaps)
+    /// clears the error containers of fields that now pass
+    #[must_use]
+    pub fn with_known_fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.known_fields.extend(fields.into_iter().map(Into::into));
+        self
+    }
+
+    /// Render errors as HTMX out-of-band swaps
+    ///
+    /// Produces one `<div id="{prefix}-{field}-error" hx-swap-oob="true">`
+    /// per field with errors, filled from the `validation/field-errors.html`
+    /// framework template. Known fields without errors (see
+    /// [`with_known_fields`](Self::with_known_fields)) get an empty container
+    /// so previously shown errors disappear.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use acton_htmx::forms::ValidationErrors;
+    ///
+    /// let errors = ValidationErrors::from(validation_errors)
+    ///     .with_known_fields(["email", "password"]);
+    ///
+    /// // Return alongside (or instead of) the main swap content
+    /// Html(errors.to_oob_swaps("signup"))
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the framework templates cannot be loaded or rendered. Ensure
+    /// templates are initialized via `acton-dx templates init`.
+    #[must_use]
+    pub fn to_oob_swaps(&self, field_id_prefix: &str) -> String {
+        self.render_oob_swaps(field_id_prefix, |messages| {
+            templates()
+                .render(
+                    "validation/field-errors.html",
+                    field_errors_context(messages),
+                )
+                .expect("Failed to render field errors template - run `acton-dx templates init`")
+        })
+    }
+
+    /// Build the OOB swaps, rendering each field's messages with `render`
+    fn render_oob_swaps(
+        &self,
+        field_id_prefix: &str,
+        render: impl Fn(&[&str]) -> String,
+    ) -> String {
+        let fields: BTreeSet<&str> = self
+            .known_fields
+            .iter()
+            .chain(self.errors.keys())
+            .map(String::as_str)
+            .collect();
+
+        let mut html = String::new();
+        for field in fields {
+            let messages: Vec<&str> = self
+                .for_field(field)
+                .iter()
+                .map(|error| error.message.as_str())
+                .collect();
+            let content = if messages.is_empty() {
+                String::new()
+            } else {
+                render(&messages)
+            };
+            let _ = write!(
+                html,
+                r#"<div id="{}-{}-error" hx-swap-oob="true">{content}</div>"#,
+                escape_html(field_id_prefix),
+                escape_html(field),
+            );
+        }
+        html
+    }
+}
+
+/// Template context for `validation/field-errors.html`
+///
+/// Classes match [`FormRenderOptions`](super::FormRenderOptions) defaults.
+fn field_errors_context(messages: &[&str]) -> minijinja::Value {
+    minijinja::context! {
+        container_class => "form-error",
+        error_class => "error",
+        errors => messages,
+    }
 }
 
 /// Convert from validator crate's `ValidationErrors`
@@ -226,6 +324,52 @@ mod tests {
         assert!(!errors.has_errors());
     }
 
+    /// Render with the bundled default template, bypassing the XDG loader
+    fn render_default(messages: &[&str]) -> String {
+        let mut env = minijinja::Environment::new();
+        env.set_trim_blocks(true);
+        env.set_lstrip_blocks(true);
+        env.add_template(
+            "validation/field-errors.html",
+            include_str!("../template/framework/defaults/validation/field-errors.html"),
+        )
+        .unwrap();
+        env.get_template("validation/field-errors.html")
+            .unwrap()
+            .render(field_errors_context(messages))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_oob_swaps_ids_and_escaping() {
+        let mut errors = ValidationErrors::new();
+        errors.add("email", "must look like <user@example.com>");
+        errors.add("password", "too short");
+
+        let html = errors.render_oob_swaps("signup", render_default);
+
+        assert!(html.contains(r#"<div id="signup-email-error" hx-swap-oob="true">"#));
+        assert!(html.contains(r#"<div id="signup-password-error" hx-swap-oob="true">"#));
+        assert!(html.contains("must look like &lt;user@example.com&gt;"));
+        assert!(!html.contains("<user@example.com>"));
+        assert!(html.contains(r#"<span class="error">too short</span>"#));
+        // Deterministic field order
+        assert!(html.find("signup-email-error") < html.find("signup-password-error"));
+    }
+
+    #[test]
+    fn test_oob_swaps_clear_known_fields_without_errors() {
+        let mut errors = ValidationErrors::new();
+        errors.add("email", "is required");
+        let errors = errors.with_known_fields(["email", "name"]);
+
+        let html = errors.render_oob_swaps("f", render_default);
+
+        assert!(html.contains(r#"<div id="f-name-error" hx-swap-oob="true"></div>"#));
+        assert_eq!(html.matches("hx-swap-oob").count(), 2);
+        assert!(ValidationErrors::new().render_oob_swaps("f", render_default).is_empty());
+    }
+
     #[test]
     fn test_fields_with_errors() {
         let mut errors = ValidationErrors::new();
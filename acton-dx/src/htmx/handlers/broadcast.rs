@@ -0,0 +1,225 @@
+//! WebSocket broadcast handler
+//!
+//! Upgrades requests to WebSocket connections registered with the
+//! [`BroadcastAgent`]. Each connection joins the channel named by the
+//! `channel` query parameter and, when `SessionMiddleware` is applied, the
+//! session's own channel ([`BroadcastAgent::session_channel`]).
+//!
+//! # Security
+//!
+//! Browsers attach cookies to WebSocket handshakes from any page, so the
+//! upgrade is only accepted when its `Origin` is this site or one of
+//! `security.cors.allowed_origins`. Requested channels must be allowed by
+//! the [`ChannelAuthorizer`](crate::htmx::agents::ChannelAuthorizer) set with
+//! [`ActonHtmxState::set_channel_authorizer`]; without one, only the session
+//! channel is available. Connections of signed-in users are closed once
+//! their session ends, as with [`RealtimeAuth`](crate::htmx::auth::RealtimeAuth).
+//!
+//! # Example Usage
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::handlers::ws_handler;
+//! use axum::{routing::get, Router};
+//!
+//! state.set_channel_authorizer(|channel, _session| channel == "dashboard");
+//!
+//! let app = Router::new()
+//!     .route("/ws", get(ws_handler))
+//!     .with_state(state);
+//! ```
+//!
+//! ```html
+//! <div hx-ext="ws" ws-connect="/ws?channel=dashboard"></div>
+//! ```
+
+use acton_reactive::prelude::AgentHandleInterface;
+use axum::{
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{
+        header::{HOST, ORIGIN},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::htmx::agents::broadcast::SUBSCRIBER_BUFFER;
+use crate::htmx::agents::{BroadcastAgent, Subscribe, Unsubscribe};
+use crate::htmx::auth::realtime::{SessionWatch, WS_CLOSE_POLICY_VIOLATION};
+use crate::htmx::extractors::OptionalSession;
+use crate::htmx::state::ActonHtmxState;
+
+/// Query parameters for [`ws_handler`]
+#[derive(Debug, Default, Deserialize)]
+pub struct BroadcastQuery {
+    /// Channel to subscribe to
+    pub channel: Option<String>,
+}
+
+/// Upgrade to a WebSocket connection receiving broadcast fragments
+///
+/// Subscriptions are registered before the upgrade completes, so fragments
+/// published once the client is connected are never missed.
+///
+/// Returns `403 Forbidden` when the `Origin` header is missing or foreign,
+/// or the requested channel is not authorized, and `400 Bad Request` when
+/// there is neither a `channel` parameter nor a session to subscribe to.
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<ActonHtmxState>,
+    Query(query): Query<BroadcastQuery>,
+    OptionalSession(session): OptionalSession,
+    headers: HeaderMap,
+) -> Response {
+    if !is_allowed_origin(&headers, &state.config().security.cors.allowed_origins) {
+        tracing::warn!(origin = ?headers.get(ORIGIN), "Rejected cross-origin WebSocket upgrade");
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+
+    if let Some(channel) = &query.channel {
+        let session_data = session.as_ref().map(|(_, data)| data);
+        let authorized = state
+            .channel_authorizer()
+            .is_some_and(|authorize| authorize(channel, session_data));
+        if !authorized {
+            tracing::warn!(channel = %channel, "Rejected unauthorized channel subscription");
+            return (StatusCode::FORBIDDEN, "Channel not allowed").into_response();
+        }
+    }
+
+    // Signed-in connections end with their session, as `RealtimeAuth` does
+    let watch = session
+        .as_ref()
+        .filter(|(_, data)| data.user_id.is_some())
+        .map(|(id, data)| {
+            SessionWatch::new(id.clone(), data.expires_at, state.session_manager().clone())
+        });
+
+    let channels: Vec<String> = query
+        .channel
+        .into_iter()
+        .chain(session.map(|(id, _)| BroadcastAgent::session_channel(&id)))
+        .collect();
+
+    if channels.is_empty() {
+        return (StatusCode::BAD_REQUEST, "No broadcast channel requested").into_response();
+    }
+
+    let (sink, fragments) = mpsc::channel(SUBSCRIBER_BUFFER);
+    let broadcast = state.broadcast().clone();
+    for channel in &channels {
+        broadcast
+            .send(Subscribe::new(channel.clone(), sink.clone()))
+            .await;
+    }
+
+    ws.on_upgrade(move |socket| async move {
+        forward_fragments(socket, fragments, watch).await;
+        for channel in channels {
+            broadcast.send(Unsubscribe::new(channel, sink.clone())).await;
+        }
+    })
+}
+
+/// Whether the upgrade request was made by this site or an allowed origin
+///
+/// Browsers always send `Origin` on WebSocket handshakes, so a missing one
+/// is rejected too.
+fn is_allowed_origin(headers: &HeaderMap, allowed_origins: &[String]) -> bool {
+    let Some(origin) = headers.get(ORIGIN).and_then(|value| value.to_str().ok()) else {
+        return false;
+    };
+    if allowed_origins.iter().any(|allowed| allowed == origin) {
+        return true;
+    }
+
+    let authority = origin.split_once("://").map(|(_, authority)| authority);
+    let host = headers.get(HOST).and_then(|value| value.to_str().ok());
+    authority
+        .zip(host)
+        .is_some_and(|(authority, host)| authority.eq_ignore_ascii_case(host))
+}
+
+/// Push fragments to the socket until either side closes or the watched
+/// session ends
+async fn forward_fragments(
+    mut socket: WebSocket,
+    mut fragments: mpsc::Receiver<Arc<str>>,
+    watch: Option<SessionWatch>,
+) {
+    let session_ended = async {
+        match &watch {
+            Some(watch) => watch.expired().await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(session_ended);
+
+    loop {
+        tokio::select! {
+            fragment = fragments.recv() => {
+                let Some(fragment) = fragment else { break };
+                if socket.send(Message::Text(fragment.as_ref().into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                // Client messages are ignored; only a close ends the connection
+                match incoming {
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                }
+            }
+            () = &mut session_ended => {
+                let close = CloseFrame {
+                    code: WS_CLOSE_POLICY_VIOLATION,
+                    reason: "Session ended".into(),
+                };
+                // The client may already be gone
+                let _ = socket.send(Message::Close(Some(close))).await;
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(origin: Option<&str>, host: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(HOST, host.parse().unwrap());
+        if let Some(origin) = origin {
+            headers.insert(ORIGIN, origin.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_same_origin_is_allowed() {
+        let headers = headers(Some("https://app.example.com"), "app.example.com");
+        assert!(is_allowed_origin(&headers, &[]));
+    }
+
+    #[test]
+    fn test_missing_or_foreign_origin_is_rejected() {
+        assert!(!is_allowed_origin(&headers(None, "app.example.com"), &[]));
+        let foreign = headers(Some("https://evil.example.com"), "app.example.com");
+        assert!(!is_allowed_origin(&foreign, &[]));
+        let sibling = headers(Some("https://app.example.com.evil.com"), "app.example.com");
+        assert!(!is_allowed_origin(&sibling, &[]));
+    }
+
+    #[test]
+    fn test_configured_origin_is_allowed() {
+        let headers = headers(Some("https://admin.example.com"), "app.example.com");
+        let allowed = ["https://admin.example.com".to_string()];
+        assert!(is_allowed_origin(&headers, &allowed));
+    }
+}
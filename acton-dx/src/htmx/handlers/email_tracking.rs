@@ -0,0 +1,263 @@
+//! Email open/click tracking handlers
+//!
+//! Endpoints targeted by the pixel and links that
+//! [`EmailTracking`](crate::htmx::email::EmailTracking) writes into HTML
+//! emails. Events are handed to a [`TrackingSink`].
+//!
+//! # Example Usage
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::handlers::{track_click, track_open, EmailTrackingState};
+//! use axum::{routing::get, Router};
+//!
+//! let tracking_routes = Router::new()
+//!     .route("/t/open/{id}", get(track_open))
+//!     .route("/t/click/{id}", get(track_click))
+//!     .with_state(EmailTrackingState::new(tracking, Arc::new(MySink)));
+//! ```
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, LOCATION, USER_AGENT},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use crate::htmx::email::{EmailTracking, TrackingEvent, TrackingEventKind, TrackingSink};
+
+/// Transparent 1x1 GIF served by [`track_open`]
+const TRANSPARENT_GIF: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00,
+    0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b,
+];
+
+/// State for the tracking handlers
+#[derive(Clone)]
+pub struct EmailTrackingState {
+    tracking: EmailTracking,
+    sink: Arc<dyn TrackingSink>,
+}
+
+impl EmailTrackingState {
+    /// Create tracking handler state
+    ///
+    /// `tracking` must use the same secret key as the sender so click
+    /// signatures verify.
+    #[must_use]
+    pub fn new(tracking: EmailTracking, sink: Arc<dyn TrackingSink>) -> Self {
+        Self { tracking, sink }
+    }
+}
+
+/// Query parameters for [`track_click`]
+#[derive(Debug, Deserialize)]
+pub struct TrackClickQuery {
+    /// Original link target
+    pub u: String,
+    /// Signature of the email ID and target
+    pub s: String,
+}
+
+/// Record an email open and serve a transparent pixel
+pub async fn track_open(
+    State(state): State<EmailTrackingState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Response {
+    state
+        .sink
+        .record(tracking_event(id, TrackingEventKind::Open, &headers))
+        .await;
+
+    (
+        [
+            (CONTENT_TYPE, "image/gif"),
+            (CACHE_CONTROL, "no-store, no-cache, must-revalidate"),
+        ],
+        TRANSPARENT_GIF,
+    )
+        .into_response()
+}
+
+/// Record a link click and redirect (302) to the original URL
+///
+/// Returns `400 Bad Request` when the signature does not match, so the
+/// endpoint cannot redirect to arbitrary URLs. Non-ASCII characters and
+/// spaces in the target are percent-encoded, as browsers do.
+pub async fn track_click(
+    State(state): State<EmailTrackingState>,
+    Path(id): Path<String>,
+    Query(query): Query<TrackClickQuery>,
+    headers: HeaderMap,
+) -> Response {
+    if !state.tracking.verify_click(&id, &query.u, &query.s) {
+        return (StatusCode::BAD_REQUEST, "Invalid tracking link").into_response();
+    }
+    let Ok(location) = HeaderValue::from_str(&percent_encode_url(&query.u)) else {
+        return (StatusCode::BAD_REQUEST, "Invalid link target").into_response();
+    };
+
+    let kind = TrackingEventKind::Click {
+        url: query.u.clone(),
+    };
+    state.sink.record(tracking_event(id, kind, &headers)).await;
+
+    (StatusCode::FOUND, [(LOCATION, location)]).into_response()
+}
+
+/// Percent-encode the bytes of `url` that can't appear in a header
+fn percent_encode_url(url: &str) -> String {
+    let mut encoded = String::with_capacity(url.len());
+    for byte in url.bytes() {
+        if byte.is_ascii_graphic() {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// Build an event stamped with the current time and client user agent
+fn tracking_event(email_id: String, kind: TrackingEventKind, headers: &HeaderMap) -> TrackingEvent {
+    TrackingEvent {
+        email_id,
+        kind,
+        occurred_at: Utc::now(),
+        user_agent: headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tokio::sync::Mutex;
+    use tower::ServiceExt;
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<TrackingEvent>>);
+
+    impl RecordingSink {
+        async fn events(&self) -> Vec<TrackingEvent> {
+            self.0.lock().await.clone()
+        }
+    }
+
+    #[async_trait]
+    impl TrackingSink for RecordingSink {
+        async fn record(&self, event: TrackingEvent) {
+            self.0.lock().await.push(event);
+        }
+    }
+
+    fn tracking() -> EmailTracking {
+        EmailTracking::new("https://app.example.com", b"test-secret".to_vec())
+    }
+
+    fn app(sink: Arc<RecordingSink>) -> Router {
+        Router::new()
+            .route("/t/open/{id}", get(track_open))
+            .route("/t/click/{id}", get(track_click))
+            .with_state(EmailTrackingState::new(tracking(), sink))
+    }
+
+    #[tokio::test]
+    async fn test_track_open_records_and_serves_pixel() {
+        let sink = Arc::new(RecordingSink::default());
+        let response = app(sink.clone())
+            .oneshot(
+                Request::get("/t/open/msg-1")
+                    .header(USER_AGENT, "MailClient/1.0")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "image/gif");
+
+        let events = sink.events().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].email_id, "msg-1");
+        assert_eq!(events[0].kind, TrackingEventKind::Open);
+        assert_eq!(events[0].user_agent.as_deref(), Some("MailClient/1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_track_click_redirects_to_original_url() {
+        let sink = Arc::new(RecordingSink::default());
+        let target = "https://example.com/a?x=1&y=2#frag";
+        let click_url = tracking().click_url("msg-1", target);
+        let path = click_url.trim_start_matches("https://app.example.com");
+
+        let response = app(sink.clone())
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(response.headers()[LOCATION], target);
+
+        let events = sink.events().await;
+        assert_eq!(
+            events[0].kind,
+            TrackingEventKind::Click {
+                url: target.to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_track_click_encodes_non_ascii_target() {
+        let sink = Arc::new(RecordingSink::default());
+        let target = "https://example.com/café menu?q=ü";
+        let click_url = tracking().click_url("msg-1", target);
+        let path = click_url.trim_start_matches("https://app.example.com");
+
+        let response = app(sink.clone())
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FOUND);
+        assert_eq!(
+            response.headers()[LOCATION],
+            "https://example.com/caf%C3%A9%20menu?q=%C3%BC"
+        );
+        assert_eq!(
+            sink.events().await[0].kind,
+            TrackingEventKind::Click {
+                url: target.to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_track_click_rejects_bad_signature() {
+        let sink = Arc::new(RecordingSink::default());
+        let response = app(sink.clone())
+            .oneshot(
+                Request::get("/t/click/msg-1?u=https%3A%2F%2Fevil.example&s=AAAA")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(sink.events().await.is_empty());
+    }
+}
@@ -0,0 +1,194 @@
+//! Feature flag admin handlers
+//!
+//! Toggles runtime [`FeatureFlags`](crate::htmx::config::FeatureFlags)
+//! without a restart. These handlers require the "admin" role.
+//!
+//! # Example Usage
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::handlers::feature_flags;
+//! use axum::{routing::post, Router};
+//!
+//! let admin_routes = Router::new()
+//!     .route("/admin/flags/{key}", post(feature_flags::toggle_flag));
+//! ```
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::htmx::auth::{user::User, Authenticated};
+use crate::htmx::config::ActonHtmxConfig;
+use crate::htmx::state::ActonHtmxState;
+
+/// Request body for [`toggle_flag`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToggleFlagRequest {
+    /// New value of the flag
+    pub enabled: bool,
+    /// Also write all flags to the service's user config file
+    #[serde(default)]
+    pub persist: bool,
+}
+
+/// Response for [`toggle_flag`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToggleFlagResponse {
+    /// Flag name
+    pub key: String,
+    /// Value after the update
+    pub enabled: bool,
+    /// Whether the flags were written to the user config file
+    pub persisted: bool,
+}
+
+/// Enable or disable a feature flag at runtime
+///
+/// Watchers of the flag are notified immediately. With `persist`, every
+/// flag except maintenance mode is written to the `[features]` section of
+/// `~/.config/acton-dx/{service_name}/config.toml` so the change survives
+/// restarts. Requires admin role.
+///
+/// # Example
+///
+/// ```bash
+/// POST /admin/flags/new_checkout
+/// Content-Type: application/json
+///
+/// {"enabled": true, "persist": true}
+/// ```
+///
+/// Response:
+/// ```json
+/// {
+///   "key": "new_checkout",
+///   "enabled": true,
+///   "persisted": true
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns:
+/// - `403 FORBIDDEN` if user is not an admin
+/// - `500 INTERNAL_SERVER_ERROR` if the flags cannot be persisted (the
+///   runtime change still applies)
+pub async fn toggle_flag(
+    State(state): State<ActonHtmxState>,
+    Authenticated(admin): Authenticated<User>,
+    Path(key): Path<String>,
+    Json(request): Json<ToggleFlagRequest>,
+) -> Result<Response, StatusCode> {
+    // Verify admin role
+    if !admin.roles.contains(&"admin".to_string()) {
+        tracing::warn!(
+            admin_id = admin.id,
+            flag = %key,
+            "Non-admin attempted to toggle feature flag"
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let flags = state.feature_flags();
+    flags.set(&key, request.enabled);
+
+    tracing::info!(
+        admin_id = admin.id,
+        flag = %key,
+        enabled = request.enabled,
+        "Feature flag toggled"
+    );
+
+    if request.persist {
+        let path = ActonHtmxConfig::recommended_path(&state.observability().service_name);
+        flags.persist(&path).await.map_err(|e| {
+            tracing::error!(error = %e, path = %path.display(), "Failed to persist feature flags");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    }
+
+    let response = ToggleFlagResponse {
+        key,
+        enabled: request.enabled,
+        persisted: request.persist,
+    };
+
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::auth::user::EmailAddress;
+    use acton_reactive::prelude::ActonApp;
+    use chrono::Utc;
+
+    fn user(roles: &[&str]) -> User {
+        User {
+            id: 1,
+            email: EmailAddress::parse("admin@example.com").unwrap(),
+            password_hash: String::new(),
+            roles: roles.iter().map(ToString::to_string).collect(),
+            permissions: vec![],
+            email_verified: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn toggle(enabled: bool) -> Json<ToggleFlagRequest> {
+        Json(ToggleFlagRequest {
+            enabled,
+            persist: false,
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_toggle_flag_updates_state() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+        let mut watcher = state.feature_flags().watch("beta");
+
+        let response = toggle_flag(
+            State(state.clone()),
+            Authenticated(user(&["admin"])),
+            Path("beta".to_string()),
+            toggle(true),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.feature_flags().is_enabled("beta"));
+        watcher.changed().await.unwrap();
+        assert!(*watcher.borrow());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_toggle_flag_requires_admin() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+
+        let result = toggle_flag(
+            State(state.clone()),
+            Authenticated(user(&["user"])),
+            Path("beta".to_string()),
+            toggle(true),
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err(), StatusCode::FORBIDDEN);
+        assert!(!state.feature_flags().is_enabled("beta"));
+    }
+
+    #[test]
+    fn test_toggle_request_persist_defaults_off() {
+        let request: ToggleFlagRequest = serde_json::from_str(r#"{"enabled":true}"#).unwrap();
+        assert!(request.enabled);
+        assert!(!request.persist);
+    }
+}
@@ -19,12 +19,14 @@
 //!
 //! let admin_routes = Router::new()
 //!     .route("/admin/jobs/list", get(job_admin::list_jobs))
-//!     .route("/admin/jobs/stats", get(job_admin::job_stats));
+//!     .route("/admin/jobs/stats", get(job_admin::job_stats))
+//!     .route("/admin/jobs/dead-letter", get(job_admin::list_dead_letter_jobs))
+//!     .route("/admin/jobs/dead-letter/clear", post(job_admin::clear_dead_letter_queue));
 //! ```
 
 use acton_reactive::prelude::AgentHandleInterface;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
@@ -36,7 +38,7 @@ use crate::htmx::auth::{user::User, Authenticated};
 use crate::htmx::jobs::{
     agent::{
         CancelJobRequest, ClearDeadLetterQueueRequest, GetMetricsRequest, RetryAllFailedRequest,
-        RetryJobRequest,
+        RetryFailedRequest, RetryJobRequest,
     },
     JobId,
 };
@@ -412,6 +414,102 @@ pub async fn retry_all_jobs(
         .into_response())
 }
 
+/// Filter for replaying failed jobs
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RetryFailedParams {
+    /// Only retry jobs of this type
+    pub job_type: Option<String>,
+    /// Only retry jobs enqueued at least this many seconds ago
+    pub older_than_secs: Option<u64>,
+    /// Priority to assign to retried jobs
+    pub priority: Option<i32>,
+}
+
+/// Retry failed jobs matching a filter
+///
+/// Re-queues jobs from the dead letter queue filtered by job type and/or
+/// age, resetting their attempt counters. Requires admin role.
+///
+/// # Example
+///
+/// ```bash
+/// POST /admin/jobs/retry-failed
+/// Content-Type: application/json
+///
+/// { "job_type": "send_email", "older_than_secs": 3600, "priority": 10 }
+/// ```
+///
+/// Response:
+/// ```json
+/// {
+///   "retried": 3,
+///   "message": "3 jobs queued for retry"
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns:
+/// - `403 FORBIDDEN` if user is not an admin
+/// - `408 REQUEST_TIMEOUT` if agent doesn't respond within 500ms
+/// - `500 INTERNAL_SERVER_ERROR` if agent response channel fails
+pub async fn retry_failed_jobs(
+    State(state): State<ActonHtmxState>,
+    Authenticated(admin): Authenticated<User>,
+    Json(params): Json<RetryFailedParams>,
+) -> Result<Response, StatusCode> {
+    // Verify admin role
+    if !admin.roles.contains(&"admin".to_string()) {
+        tracing::warn!(
+            admin_id = admin.id,
+            "Non-admin attempted to retry failed jobs"
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // Create request with response channel
+    let (mut request, rx) = RetryFailedRequest::new(
+        params.job_type.clone(),
+        params.older_than_secs.map(Duration::from_secs),
+    );
+    if let Some(priority) = params.priority {
+        request = request.with_priority(priority);
+    }
+
+    // Send message to JobAgent
+    state.job_agent().send(request).await;
+
+    // Await response with 500ms timeout (may need to requeue many jobs)
+    let timeout = Duration::from_millis(500);
+    let retried = tokio::time::timeout(timeout, rx)
+        .await
+        .map_err(|_| {
+            tracing::error!("Retry failed jobs timeout");
+            StatusCode::REQUEST_TIMEOUT
+        })?
+        .map_err(|_| {
+            tracing::error!("Retry failed jobs channel error");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    tracing::info!(
+        admin_id = admin.id,
+        job_type = params.job_type.as_deref(),
+        older_than_secs = params.older_than_secs,
+        retried,
+        "Filtered failed jobs queued for retry"
+    );
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "retried": retried,
+            "message": format!("{retried} jobs queued for retry")
+        })),
+    )
+        .into_response())
+}
+
 /// Cancel a running or pending job
 ///
 /// Attempts to cancel a job. If the job is pending, it's removed from the queue.
@@ -498,6 +596,83 @@ pub async fn cancel_job(
     }
 }
 
+/// Pagination for the dead letter queue listing
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeadLetterParams {
+    /// Page number (1-indexed, default 1)
+    pub page: Option<usize>,
+    /// Jobs per page (default 20, max 100)
+    pub page_size: Option<usize>,
+}
+
+/// List jobs in the dead letter queue
+///
+/// Returns a page of permanently failed jobs with their type, last error,
+/// attempt count, and enqueue time, so the admin UI can render a table with
+/// retry and clear actions. Requires admin role.
+///
+/// # Example
+///
+/// ```bash
+/// GET /admin/jobs/dead-letter?page=1&page_size=20
+/// ```
+///
+/// Response:
+/// ```json
+/// {
+///   "jobs": [
+///     {
+///       "id": "550e8400-e29b-41d4-a716-446655440000",
+///       "job_type": "send_email",
+///       "last_error": "SMTP connection refused",
+///       "attempts": 4,
+///       "enqueued_at": "2025-11-22T10:00:00Z"
+///     }
+///   ],
+///   "page": 1,
+///   "page_size": 20,
+///   "total_count": 1,
+///   "has_prev": false,
+///   "has_next": false
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns:
+/// - `403 FORBIDDEN` if user is not an admin
+/// - `500 INTERNAL_SERVER_ERROR` if the agent doesn't respond within 100ms
+pub async fn list_dead_letter_jobs(
+    State(state): State<ActonHtmxState>,
+    Authenticated(admin): Authenticated<User>,
+    Query(params): Query<DeadLetterParams>,
+) -> Result<Response, StatusCode> {
+    // Verify admin role
+    if !admin.roles.contains(&"admin".to_string()) {
+        tracing::warn!(
+            admin_id = admin.id,
+            "Non-admin attempted to list dead letter queue"
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let page = state
+        .get_dead_letter_jobs(params.page.unwrap_or(1), params.page_size.unwrap_or(20))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Dead letter queue listing failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    tracing::info!(
+        admin_id = admin.id,
+        total = page.total_count,
+        "Admin listed dead letter queue"
+    );
+
+    Ok((StatusCode::OK, Json(page)).into_response())
+}
+
 /// Clear the dead letter queue
 ///
 /// Permanently removes all jobs from the dead letter queue.
@@ -590,6 +765,22 @@ mod tests {
         assert!(json.contains("WelcomeEmail"));
     }
 
+    #[test]
+    fn test_retry_failed_params_optional_fields() {
+        let params: RetryFailedParams =
+            serde_json::from_str(r#"{"job_type":"send_email"}"#).unwrap();
+        assert_eq!(params.job_type.as_deref(), Some("send_email"));
+        assert_eq!(params.older_than_secs, None);
+        assert_eq!(params.priority, None);
+    }
+
+    #[test]
+    fn test_dead_letter_params_defaults() {
+        let params: DeadLetterParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params.page, None);
+        assert_eq!(params.page_size, None);
+    }
+
     #[test]
     fn test_job_stats_response_serialization() {
         let stats = JobStatsResponse {
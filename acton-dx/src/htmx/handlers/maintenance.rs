@@ -0,0 +1,155 @@
+//! Maintenance mode admin handler
+//!
+//! Turns [`MaintenanceLayer`](crate::htmx::middleware::MaintenanceLayer) on
+//! and off at runtime by setting the
+//! [`MAINTENANCE_FLAG`] feature flag. Requires the "admin" role.
+//!
+//! Keep the route under an allowlisted path (`/admin` by default) so it
+//! stays reachable while maintenance is on.
+//!
+//! # Example Usage
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::handlers::maintenance;
+//! use axum::{routing::post, Router};
+//!
+//! let admin_routes = Router::new()
+//!     .route("/admin/maintenance", post(maintenance::set_maintenance));
+//! ```
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::htmx::auth::{user::User, Authenticated};
+use crate::htmx::middleware::MAINTENANCE_FLAG;
+use crate::htmx::state::ActonHtmxState;
+
+/// Request body for [`set_maintenance`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRequest {
+    /// Whether maintenance mode should be on
+    pub enabled: bool,
+}
+
+/// Response for [`set_maintenance`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceResponse {
+    /// Whether maintenance mode is on after the update
+    pub enabled: bool,
+}
+
+/// Turn maintenance mode on or off
+///
+/// Takes effect for the next request. Requires admin role.
+///
+/// # Example
+///
+/// ```bash
+/// POST /admin/maintenance
+/// Content-Type: application/json
+///
+/// {"enabled": true}
+/// ```
+///
+/// Response:
+/// ```json
+/// {
+///   "enabled": true
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns:
+/// - `403 FORBIDDEN` if user is not an admin
+pub async fn set_maintenance(
+    State(state): State<ActonHtmxState>,
+    Authenticated(admin): Authenticated<User>,
+    Json(request): Json<MaintenanceRequest>,
+) -> Result<Response, StatusCode> {
+    // Verify admin role
+    if !admin.roles.contains(&"admin".to_string()) {
+        tracing::warn!(
+            admin_id = admin.id,
+            "Non-admin attempted to toggle maintenance mode"
+        );
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    state
+        .feature_flags()
+        .set(MAINTENANCE_FLAG, request.enabled);
+
+    tracing::info!(
+        admin_id = admin.id,
+        enabled = request.enabled,
+        "Maintenance mode toggled"
+    );
+
+    let response = MaintenanceResponse {
+        enabled: request.enabled,
+    };
+
+    Ok((StatusCode::OK, Json(response)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::auth::user::EmailAddress;
+    use acton_reactive::prelude::ActonApp;
+    use chrono::Utc;
+
+    fn user(roles: &[&str]) -> User {
+        User {
+            id: 1,
+            email: EmailAddress::parse("admin@example.com").unwrap(),
+            password_hash: String::new(),
+            roles: roles.iter().map(ToString::to_string).collect(),
+            permissions: vec![],
+            email_verified: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_set_maintenance_toggles_flag() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+
+        for enabled in [true, false] {
+            let response = set_maintenance(
+                State(state.clone()),
+                Authenticated(user(&["admin"])),
+                Json(MaintenanceRequest { enabled }),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(state.feature_flags().is_enabled(MAINTENANCE_FLAG), enabled);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_set_maintenance_requires_admin() {
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime).await.unwrap();
+
+        let result = set_maintenance(
+            State(state.clone()),
+            Authenticated(user(&["user"])),
+            Json(MaintenanceRequest { enabled: true }),
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err(), StatusCode::FORBIDDEN);
+        assert!(!state.feature_flags().is_enabled(MAINTENANCE_FLAG));
+    }
+}
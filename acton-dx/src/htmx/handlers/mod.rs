@@ -4,21 +4,38 @@
 //! - Cedar policy administration (admin-only endpoints)
 //! - Role management (admin-only endpoints, requires postgres)
 //! - Job management (admin-only endpoints)
+//! - Feature flag toggling (admin-only endpoints)
+//! - Maintenance mode toggling (admin-only endpoints)
+//! - WebSocket broadcast connections
+//! - Email open/click tracking
 
+pub mod broadcast;
 #[cfg(feature = "cedar")]
 pub mod cedar_admin;
+pub mod email_tracking;
+pub mod feature_flags;
 pub mod job_admin;
+pub mod maintenance;
 #[cfg(feature = "postgres")]
 pub mod role_admin;
 
 // Re-exports
+pub use broadcast::{ws_handler, BroadcastQuery};
+pub use email_tracking::{track_click, track_open, EmailTrackingState, TrackClickQuery};
+
 #[cfg(feature = "cedar")]
 #[allow(unused_imports)]
 pub use cedar_admin::{policy_status, reload_policies, PolicyStatusResponse, ReloadPolicyResponse};
 
+#[allow(unused_imports)]
+pub use feature_flags::{toggle_flag, ToggleFlagRequest, ToggleFlagResponse};
+
 #[allow(unused_imports)]
 pub use job_admin::{job_stats, list_jobs, JobListResponse, JobStatsResponse};
 
+#[allow(unused_imports)]
+pub use maintenance::{set_maintenance, MaintenanceRequest, MaintenanceResponse};
+
 #[cfg(feature = "postgres")]
 #[allow(unused_imports)]
 pub use role_admin::{
@@ -0,0 +1,602 @@
+//! Internationalization
+//!
+//! [`Translations`] loads `locales/{lang}.toml` files mapping keys to
+//! strings. Nested tables become dotted keys, and `{name}` placeholders are
+//! filled in from arguments:
+//!
+//! ```toml
+//! # locales/en.toml
+//! [nav]
+//! home = "Home"
+//!
+//! greeting = "Hello, {name}!"
+//! ```
+//!
+//! [`I18nLayer`] picks a locale for each request: the `acton_locale` cookie
+//! if it names a supported locale, otherwise the best match from the
+//! `Accept-Language` header, otherwise the default locale. The chosen
+//! [`Locale`] is stored in the request extensions, and handlers can use the
+//! [`AcceptLanguage`] extractor to translate strings.
+//!
+//! Templates rendered while handling the request, including [`HxTemplate`]
+//! templates, translate with the [`t`](crate::htmx::template::t) helper; the
+//! framework templates get a `t(key, name=value)` function.
+//!
+//! Missing keys fall back to the default locale, then to the key itself.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::i18n::{AcceptLanguage, I18nLayer, Translations};
+//! use axum::{routing::get, Router};
+//!
+//! # fn example() -> Result<(), acton_dx::htmx::i18n::I18nError> {
+//! let translations = Translations::load("locales", "en")?;
+//!
+//! let app: Router = Router::new()
+//!     .route(
+//!         "/",
+//!         get(|lang: AcceptLanguage| async move { lang.t("greeting", &[("name", "Ada")]) }),
+//!     )
+//!     .layer(I18nLayer::new(translations));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`HxTemplate`]: crate::htmx::template::HxTemplate
+
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, Request},
+    http::{
+        header::{ACCEPT_LANGUAGE, COOKIE},
+        request::Parts,
+        StatusCode,
+    },
+    response::Response,
+};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Cookie overriding the `Accept-Language` header
+pub const LOCALE_COOKIE_NAME: &str = "acton_locale";
+
+tokio::task_local! {
+    static CURRENT: AcceptLanguage;
+}
+
+/// Errors loading translations
+#[derive(Debug, thiserror::Error)]
+pub enum I18nError {
+    /// A locale file or directory could not be read
+    #[error("Failed to read {path}: {source}")]
+    Read {
+        /// Path that failed
+        path: PathBuf,
+        /// Underlying error
+        source: std::io::Error,
+    },
+
+    /// A locale file is not valid TOML
+    #[error("Failed to parse translations for {locale}: {source}")]
+    Parse {
+        /// Locale of the file
+        locale: String,
+        /// Underlying error
+        source: toml::de::Error,
+    },
+
+    /// There is no file for the default locale
+    #[error("No translations for default locale {0}")]
+    MissingDefault(String),
+}
+
+/// A language tag such as `en` or `pt-BR`
+///
+/// Tags are compared case-insensitively and `_` is accepted for `-`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Locale(String);
+
+impl Locale {
+    /// Create a locale from a language tag
+    #[must_use]
+    pub fn new(tag: &str) -> Self {
+        Self(tag.trim().replace('_', "-").to_lowercase())
+    }
+
+    /// The normalized tag, e.g. `pt-br`
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The primary language, e.g. `pt` for `pt-br`
+    #[must_use]
+    pub fn language(&self) -> &str {
+        self.0.split('-').next().unwrap_or(&self.0)
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Translated strings for each supported locale
+#[derive(Debug, Clone)]
+pub struct Translations {
+    default_locale: Locale,
+    locales: Arc<HashMap<Locale, HashMap<String, String>>>,
+}
+
+impl Translations {
+    /// Create an empty set of translations
+    #[must_use]
+    pub fn new(default_locale: &str) -> Self {
+        Self {
+            default_locale: Locale::new(default_locale),
+            locales: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Load every `{lang}.toml` file in `dir`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a file cannot be read or parsed, or if there is no
+    /// file for `default_locale`
+    pub fn load(dir: impl AsRef<Path>, default_locale: &str) -> Result<Self, I18nError> {
+        let dir = dir.as_ref();
+        let read_error = |source| I18nError::Read {
+            path: dir.to_path_buf(),
+            source,
+        };
+
+        let mut translations = Self::new(default_locale);
+        for entry in std::fs::read_dir(dir).map_err(read_error)? {
+            let path = entry.map_err(read_error)?.path();
+            if path.extension().is_none_or(|extension| extension != "toml") {
+                continue;
+            }
+            let Some(locale) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let source = std::fs::read_to_string(&path).map_err(|source| I18nError::Read {
+                path: path.clone(),
+                source,
+            })?;
+            translations = translations.with_toml(locale, &source)?;
+        }
+
+        if !translations.locales.contains_key(&translations.default_locale) {
+            return Err(I18nError::MissingDefault(
+                translations.default_locale.to_string(),
+            ));
+        }
+        Ok(translations)
+    }
+
+    /// Add the strings in a TOML document for `locale`
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `source` is not valid TOML
+    pub fn with_toml(mut self, locale: &str, source: &str) -> Result<Self, I18nError> {
+        let table: toml::Table = toml::from_str(source).map_err(|source| I18nError::Parse {
+            locale: locale.to_string(),
+            source,
+        })?;
+
+        let strings = Arc::make_mut(&mut self.locales)
+            .entry(Locale::new(locale))
+            .or_default();
+        flatten("", &table, strings);
+        Ok(self)
+    }
+
+    /// Locale used when nothing better matches
+    #[must_use]
+    pub const fn default_locale(&self) -> &Locale {
+        &self.default_locale
+    }
+
+    /// The supported locale that best serves `tag`, if any
+    ///
+    /// Matches the exact tag first, then the primary language, so `en-GB`
+    /// is served by `en` and `pt` by `pt-BR`.
+    #[must_use]
+    pub fn supported(&self, tag: &str) -> Option<Locale> {
+        let wanted = Locale::new(tag);
+        if self.locales.contains_key(&wanted) {
+            return Some(wanted);
+        }
+
+        let language = wanted.language();
+        self.locales
+            .keys()
+            .filter(|locale| locale.language() == language)
+            .min_by_key(|locale| (locale.as_str() != language, locale.as_str().to_string()))
+            .cloned()
+    }
+
+    /// Choose the locale for a request
+    ///
+    /// A cookie naming a supported locale wins over the `Accept-Language`
+    /// header, which is tried in order of preference.
+    #[must_use]
+    pub fn negotiate(&self, accept_language: Option<&str>, cookie: Option<&str>) -> Locale {
+        if let Some(locale) = cookie.and_then(|tag| self.supported(tag)) {
+            return locale;
+        }
+
+        accept_language
+            .map(parse_accept_language)
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|tag| self.supported(tag))
+            .unwrap_or_else(|| self.default_locale.clone())
+    }
+
+    /// Translate `key` into `locale`, filling in `{name}` placeholders
+    ///
+    /// Falls back to the default locale, then to the key itself.
+    #[must_use]
+    pub fn translate(&self, locale: &Locale, key: &str, args: &[(&str, &str)]) -> String {
+        let template = [locale, &self.default_locale]
+            .into_iter()
+            .find_map(|locale| self.locales.get(locale)?.get(key))
+            .map_or(key, String::as_str);
+        interpolate(template, args)
+    }
+}
+
+/// Add the strings in `table` to `strings`, joining nested keys with dots
+fn flatten(prefix: &str, table: &toml::Table, strings: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            toml::Value::Table(table) => flatten(&key, table, strings),
+            toml::Value::String(text) => {
+                strings.insert(key, text.clone());
+            }
+            other => {
+                strings.insert(key, other.to_string());
+            }
+        }
+    }
+}
+
+/// Language tags from an `Accept-Language` header, most preferred first
+fn parse_accept_language(header: &str) -> Vec<&str> {
+    let mut tags: Vec<(&str, f32)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    // Stable sort keeps header order for equal weights
+    tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+    tags.into_iter().map(|(tag, _)| tag).collect()
+}
+
+/// Replace `{name}` placeholders with matching arguments
+///
+/// Placeholders without a matching argument are left as they are.
+fn interpolate(template: &str, args: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &after[..end];
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => output.push_str(value),
+            None => output.push_str(&rest[start..=start + end + 1]),
+        }
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Translations in the locale chosen for the current request
+///
+/// Requires [`I18nLayer`] to be applied to the router.
+#[derive(Debug, Clone)]
+pub struct AcceptLanguage {
+    locale: Locale,
+    translations: Translations,
+}
+
+impl AcceptLanguage {
+    /// The chosen locale
+    #[must_use]
+    pub const fn locale(&self) -> &Locale {
+        &self.locale
+    }
+
+    /// Translate `key` into the chosen locale
+    #[must_use]
+    pub fn t(&self, key: &str, args: &[(&str, &str)]) -> String {
+        self.translations.translate(&self.locale, key, args)
+    }
+
+    /// Run `future` with this as the current request's language
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+
+    /// Language of the request being handled, if [`I18nLayer`] is installed
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+}
+
+impl<S> FromRequestParts<S> for AcceptLanguage
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Self>()
+            .cloned()
+            .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "I18nLayer not installed"))
+    }
+}
+
+/// Layer choosing the locale of each request
+#[derive(Debug, Clone)]
+pub struct I18nLayer {
+    translations: Translations,
+}
+
+impl I18nLayer {
+    /// Translate requests with `translations`
+    #[must_use]
+    pub const fn new(translations: Translations) -> Self {
+        Self { translations }
+    }
+}
+
+impl<S> Layer<S> for I18nLayer {
+    type Service = I18nMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        I18nMiddleware {
+            inner,
+            translations: self.translations.clone(),
+        }
+    }
+}
+
+/// Middleware storing the request's [`Locale`] and [`AcceptLanguage`]
+#[derive(Debug, Clone)]
+pub struct I18nMiddleware<S> {
+    inner: S,
+    translations: Translations,
+}
+
+impl<S> Service<Request> for I18nMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let headers = req.headers();
+        let accept_language = headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok());
+        let locale = self
+            .translations
+            .negotiate(accept_language, locale_cookie(headers));
+
+        let language = AcceptLanguage {
+            locale: locale.clone(),
+            translations: self.translations.clone(),
+        };
+        req.extensions_mut().insert(locale);
+        req.extensions_mut().insert(language.clone());
+
+        Box::pin(language.scope(self.inner.call(req)))
+    }
+}
+
+/// Value of the locale cookie
+fn locale_cookie(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| name.trim() == LOCALE_COOKIE_NAME)
+        .map(|(_, value)| value.trim())
+}
+
+/// `t(key, name=value, ...)` for framework templates
+// minijinja passes keyword arguments by value
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn template_function(key: &str, kwargs: minijinja::value::Kwargs) -> String {
+    let values: Vec<(String, String)> = kwargs
+        .args()
+        .map(|name| {
+            let value = kwargs
+                .get::<minijinja::Value>(name)
+                .map(|value| value.to_string())
+                .unwrap_or_default();
+            (name.to_string(), value)
+        })
+        .collect();
+    let args: Vec<(&str, &str)> = values
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+
+    AcceptLanguage::current().map_or_else(|| key.to_string(), |language| language.t(key, &args))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    fn translations() -> Translations {
+        Translations::new("en")
+            .with_toml(
+                "en",
+                r#"
+                greeting = "Hello, {name}!"
+                farewell = "Goodbye"
+
+                [nav]
+                home = "Home"
+                "#,
+            )
+            .unwrap()
+            .with_toml("fr", "greeting = \"Bonjour, {name} !\"")
+            .unwrap()
+            .with_toml("pt-BR", "farewell = \"Tchau\"")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_fallback() {
+        let translations = translations();
+        let fr = Locale::new("fr");
+
+        assert_eq!(translations.translate(&fr, "greeting", &[]), "Bonjour, {name} !");
+        // Missing in fr, present in the default locale
+        assert_eq!(translations.translate(&fr, "farewell", &[]), "Goodbye");
+        assert_eq!(translations.translate(&fr, "nav.home", &[]), "Home");
+        // Missing everywhere
+        assert_eq!(translations.translate(&fr, "nav.missing", &[]), "nav.missing");
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let translations = translations();
+        let en = Locale::new("en");
+
+        assert_eq!(
+            translations.translate(&en, "greeting", &[("name", "Ada")]),
+            "Hello, Ada!"
+        );
+        assert_eq!(interpolate("{a} and {b}", &[("a", "1")]), "1 and {b}");
+        assert_eq!(interpolate("{a}{a} {unclosed", &[("a", "x")]), "xx {unclosed");
+    }
+
+    #[test]
+    fn test_negotiation() {
+        let translations = translations();
+
+        assert_eq!(translations.negotiate(None, None).as_str(), "en");
+        assert_eq!(
+            translations.negotiate(Some("de, fr;q=0.8, en;q=0.5"), None).as_str(),
+            "fr"
+        );
+        assert_eq!(translations.negotiate(Some("en-GB"), None).as_str(), "en");
+        assert_eq!(translations.negotiate(Some("pt"), None).as_str(), "pt-br");
+        assert_eq!(translations.negotiate(Some("fr;q=0, de"), None).as_str(), "en");
+    }
+
+    #[test]
+    fn test_cookie_overrides_header() {
+        let translations = translations();
+
+        assert_eq!(
+            translations.negotiate(Some("en"), Some("fr")).as_str(),
+            "fr"
+        );
+        // Unsupported cookie values are ignored
+        assert_eq!(
+            translations.negotiate(Some("fr"), Some("xx")).as_str(),
+            "fr"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_layer_sets_locale() {
+        let app = Router::new()
+            .route(
+                "/",
+                get(
+                    |Extension(locale): Extension<Locale>, lang: AcceptLanguage| async move {
+                        format!(
+                            "{} {} {}",
+                            locale,
+                            lang.t("greeting", &[("name", "Ada")]),
+                            crate::htmx::template::t("farewell", &[])
+                        )
+                    },
+                ),
+            )
+            .layer(I18nLayer::new(translations()));
+
+        let request = Request::builder()
+            .uri("/")
+            .header(ACCEPT_LANGUAGE, "en-US,en;q=0.9")
+            .header(COOKIE, format!("theme=dark; {LOCALE_COOKIE_NAME}=fr"))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        assert_eq!(body, "fr Bonjour, Ada ! Goodbye");
+    }
+
+    #[tokio::test]
+    async fn test_framework_template_function() {
+        let mut env = minijinja::Environment::new();
+        env.add_function("t", template_function);
+        env.add_template("greet", r#"{{ t("greeting", name="Ada") }}"#)
+            .unwrap();
+        let render = || env.get_template("greet").unwrap().render(()).unwrap();
+
+        // Without a request the key itself is shown
+        assert_eq!(render(), "greeting");
+
+        let language = AcceptLanguage {
+            locale: Locale::new("fr"),
+            translations: translations(),
+        };
+        assert_eq!(language.scope(async { render() }).await, "Bonjour, Ada !");
+    }
+}
@@ -1,6 +1,8 @@
 //! Messages for the job agent.
 
-use crate::htmx::jobs::{JobId, JobStatus};
+use super::queue::QueuedJob;
+use crate::htmx::jobs::{BackoffConfig, Job, JobError, JobId, JobProgress, JobStatus};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
@@ -25,8 +27,69 @@ pub struct EnqueueJob {
     pub priority: i32,
     /// Maximum number of retry attempts.
     pub max_retries: u32,
+    /// Backoff policy between retry attempts.
+    #[serde(default)]
+    pub backoff: BackoffConfig,
     /// Job execution timeout.
     pub timeout: Duration,
+    /// Deduplication key (see [`Job::dedup_key`]).
+    #[serde(default)]
+    pub dedup_key: Option<String>,
+}
+
+impl EnqueueJob {
+    /// Build an enqueue message from a typed job.
+    ///
+    /// Serializes the job as JSON and copies its priority, retry, backoff,
+    /// timeout, and dedup key settings from the [`Job`] implementation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JobError::SerializationError`] if the job cannot be serialized.
+    pub fn from_job<J: Job>(job: &J) -> Result<Self, JobError> {
+        Ok(Self {
+            id: JobId::new(),
+            job_type: job.job_type().to_string(),
+            payload: serde_json::to_vec(job)?,
+            priority: job.priority(),
+            max_retries: job.max_retries(),
+            backoff: job.backoff(),
+            timeout: job.timeout(),
+            dedup_key: job.dedup_key(),
+        })
+    }
+}
+
+/// Enqueue a job and wait for the outcome (web handler pattern).
+///
+/// Unlike [`EnqueueJob`], which only replies to agents on success, this
+/// reports rejections (queue full, shutting down) through the oneshot
+/// channel, including [`JobError::Duplicate`] when the job collapsed into
+/// one with the same dedup key. Prefer
+/// [`ActonHtmxState::enqueue`](crate::htmx::state::ActonHtmxState::enqueue)
+/// in handlers.
+#[derive(Clone, Debug)]
+pub struct EnqueueJobRequest {
+    /// The job to enqueue.
+    pub job: EnqueueJob,
+    /// Response channel with the job ID or the rejection.
+    pub response_tx: ResponseChannel<Result<JobId, JobError>>,
+}
+
+impl EnqueueJobRequest {
+    /// Create a new enqueue request with response channel.
+    ///
+    /// Returns a tuple of (request, receiver) where the request should be
+    /// sent to the agent and the receiver awaited for the response.
+    #[must_use]
+    pub fn new(job: EnqueueJob) -> (Self, oneshot::Receiver<Result<JobId, JobError>>) {
+        let (tx, rx) = oneshot::channel();
+        let request = Self {
+            job,
+            response_tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (request, rx)
+    }
 }
 
 /// Response to job enqueue request.
@@ -34,6 +97,68 @@ pub struct EnqueueJob {
 pub struct JobEnqueued {
     /// The enqueued job ID.
     pub id: JobId,
+    /// Whether the job collapsed into an already queued or running job with
+    /// the same dedup key; `id` is then that job's ID.
+    #[serde(default)]
+    pub deduplicated: bool,
+}
+
+/// Report that a job has finished for good (fire-and-forget).
+///
+/// Sent by job runners once a job completes or fails out with no retries
+/// left. Removes the job from the queue and running set, updates the
+/// completed/failed metrics, and releases its dedup key so an identical job
+/// can be enqueued again. A failed job moves to the dead letter queue with
+/// its error.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobFinished {
+    /// Job ID.
+    pub id: JobId,
+    /// Whether the job completed successfully.
+    pub success: bool,
+    /// Error message of the final failed attempt.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Record progress of a running job (fire-and-forget).
+///
+/// Sent by [`JobContext::report_progress`](crate::htmx::jobs::JobContext::report_progress).
+/// Marks the job as running and stores the progress in its status. Reports
+/// for jobs that already finished are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportProgress {
+    /// Job ID.
+    pub id: JobId,
+    /// Latest progress.
+    pub progress: JobProgress,
+}
+
+/// Get the latest progress of a running job (web handler pattern).
+///
+/// Replies `None` when the job is not running or hasn't reported progress.
+#[derive(Clone, Debug)]
+pub struct GetJobProgressRequest {
+    /// Job ID to query.
+    pub id: JobId,
+    /// Response channel for the progress.
+    pub response_tx: ResponseChannel<Option<JobProgress>>,
+}
+
+impl GetJobProgressRequest {
+    /// Create a new job progress request with response channel.
+    ///
+    /// Returns a tuple of (request, receiver) where the request should be
+    /// sent to the agent and the receiver awaited for the response.
+    #[must_use]
+    pub fn new(id: JobId) -> (Self, oneshot::Receiver<Option<JobProgress>>) {
+        let (tx, rx) = oneshot::channel();
+        let request = Self {
+            id,
+            response_tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (request, rx)
+    }
 }
 
 /// Get the status of a job (agent-to-agent pattern).
@@ -114,8 +239,8 @@ impl JobMetrics {
         }
 
         // Update average
-        if self.jobs_completed > 0 {
-            self.avg_execution_time_ms = self.total_execution_time_ms / self.jobs_completed;
+        if let Some(avg) = self.total_execution_time_ms.checked_div(self.jobs_completed) {
+            self.avg_execution_time_ms = avg;
         }
 
         // Simple percentile estimation (will be replaced with histogram in production)
@@ -331,6 +456,87 @@ impl RetryAllFailedRequest {
     }
 }
 
+/// Retry failed jobs matching a filter (web handler pattern).
+///
+/// Re-queues dead letter queue jobs whose type and age match, resetting their
+/// attempt counter and optionally overriding their priority. Jobs that cannot
+/// be re-queued (e.g. the queue is full) stay in the dead letter queue.
+/// Returns the number of jobs re-queued.
+///
+/// Age is measured from the job's original enqueue time.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_htmx::jobs::agent::messages::RetryFailedRequest;
+///
+/// async fn handler(State(state): State<ActonHtmxState>) -> Result<Response> {
+///     let (request, rx) = RetryFailedRequest::new(
+///         Some("send_email".to_string()),
+///         Some(Duration::from_secs(3600)),
+///     );
+///     state.job_agent().send(request.with_priority(10)).await;
+///
+///     let count = tokio::time::timeout(Duration::from_millis(500), rx).await??;
+///     Ok(Json(json!({ "retried": count })).into_response())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryFailedRequest {
+    /// Only retry jobs of this type.
+    pub job_type: Option<String>,
+    /// Only retry jobs enqueued at least this long ago.
+    pub older_than: Option<Duration>,
+    /// Priority to assign to retried jobs (keeps the original if `None`).
+    pub priority: Option<i32>,
+    /// Response channel with count of retried jobs.
+    pub response_tx: ResponseChannel<usize>,
+}
+
+impl RetryFailedRequest {
+    /// Create a new filtered retry request with response channel.
+    ///
+    /// Returns a tuple of (request, receiver) where the request should be
+    /// sent to the agent and the receiver awaited for the response.
+    #[must_use]
+    pub fn new(
+        job_type: Option<String>,
+        older_than: Option<Duration>,
+    ) -> (Self, oneshot::Receiver<usize>) {
+        let (tx, rx) = oneshot::channel();
+        let request = Self {
+            job_type,
+            older_than,
+            priority: None,
+            response_tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (request, rx)
+    }
+
+    /// Assign a new priority to retried jobs.
+    #[must_use]
+    pub const fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Check whether a dead letter queue job matches this filter.
+    pub(crate) fn matches(&self, job: &QueuedJob, now: DateTime<Utc>) -> bool {
+        let type_matches = self
+            .job_type
+            .as_ref()
+            .is_none_or(|job_type| *job_type == job.job_type);
+
+        let age_matches = self.older_than.is_none_or(|older_than| {
+            (now - job.enqueued_at)
+                .to_std()
+                .is_ok_and(|age| age >= older_than)
+        });
+
+        type_matches && age_matches
+    }
+}
+
 /// Cancel a running or pending job (web handler pattern).
 ///
 /// Attempts to cancel a job. If the job is pending, it's removed from the queue.
@@ -492,6 +698,152 @@ impl JobHistoryPage {
     }
 }
 
+/// A job in the dead letter queue, as shown to admins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterJob {
+    /// Job ID.
+    pub id: JobId,
+    /// Job type name.
+    pub job_type: String,
+    /// Error message from the last failed attempt.
+    pub last_error: Option<String>,
+    /// Number of attempts made.
+    pub attempts: u32,
+    /// When the job was enqueued.
+    pub enqueued_at: DateTime<Utc>,
+}
+
+impl From<&QueuedJob> for DeadLetterJob {
+    fn from(job: &QueuedJob) -> Self {
+        Self {
+            id: job.id,
+            job_type: job.job_type.clone(),
+            last_error: job.last_error.clone(),
+            attempts: job.attempt + 1,
+            enqueued_at: job.enqueued_at,
+        }
+    }
+}
+
+/// Page of dead letter queue entries with pagination info.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterPage {
+    /// Dead letter jobs for this page, most recently enqueued first.
+    pub jobs: Vec<DeadLetterJob>,
+    /// Current page number (1-indexed).
+    pub page: usize,
+    /// Number of jobs per page.
+    pub page_size: usize,
+    /// Total number of jobs in the dead letter queue.
+    pub total_count: usize,
+    /// Whether there is a previous page.
+    pub has_prev: bool,
+    /// Whether there is a next page.
+    pub has_next: bool,
+}
+
+impl DeadLetterPage {
+    /// Create a new dead letter page from jobs and pagination info.
+    #[must_use]
+    pub const fn new(
+        jobs: Vec<DeadLetterJob>,
+        page: usize,
+        page_size: usize,
+        total_count: usize,
+    ) -> Self {
+        let has_prev = page > 1;
+        let total_pages = total_count.div_ceil(page_size);
+        let has_next = page < total_pages;
+
+        Self {
+            jobs,
+            page,
+            page_size,
+            total_count,
+            has_prev,
+            has_next,
+        }
+    }
+}
+
+/// List dead letter queue entries (web handler pattern).
+///
+/// Lets admins inspect failed jobs before retrying or clearing them.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let (request, rx) = GetDeadLetterJobsRequest::new(1, 20);
+/// state.job_agent().send(request).await;
+/// let page = rx.await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct GetDeadLetterJobsRequest {
+    /// Page number (1-indexed).
+    pub page: usize,
+    /// Number of jobs per page.
+    pub page_size: usize,
+    /// Response channel for the page.
+    pub response_tx: ResponseChannel<DeadLetterPage>,
+}
+
+impl GetDeadLetterJobsRequest {
+    /// Create a new dead letter listing request with response channel.
+    ///
+    /// The page is at least 1 and the page size is clamped to 1-100.
+    ///
+    /// Returns a tuple of (request, receiver) where the request should be
+    /// sent to the agent and the receiver awaited for the response.
+    #[must_use]
+    pub fn new(page: usize, page_size: usize) -> (Self, oneshot::Receiver<DeadLetterPage>) {
+        let (tx, rx) = oneshot::channel();
+        let request = Self {
+            page: page.max(1),
+            page_size: page_size.clamp(1, 100),
+            response_tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (request, rx)
+    }
+}
+
+/// Stop accepting new jobs (web handler pattern).
+///
+/// Sent at shutdown. The agent rejects every later [`EnqueueJob`] (counted
+/// in `jobs_rejected`) and, with Redis persistence enabled, persists the
+/// jobs still queued so they survive the restart. Responds with the number
+/// of jobs still queued.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_htmx::jobs::agent::StopAcceptingJobsRequest;
+///
+/// let (request, rx) = StopAcceptingJobsRequest::new();
+/// state.job_agent().send(request).await;
+///
+/// let queued = tokio::time::timeout(Duration::from_millis(500), rx).await??;
+/// ```
+#[derive(Clone, Debug)]
+pub struct StopAcceptingJobsRequest {
+    /// Response channel with count of jobs still queued.
+    pub response_tx: ResponseChannel<usize>,
+}
+
+impl StopAcceptingJobsRequest {
+    /// Create a new stop accepting request with response channel.
+    ///
+    /// Returns a tuple of (request, receiver) where the request should be
+    /// sent to the agent and the receiver awaited for the response.
+    #[must_use]
+    pub fn new() -> (Self, oneshot::Receiver<usize>) {
+        let (tx, rx) = oneshot::channel();
+        let request = Self {
+            response_tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (request, rx)
+    }
+}
+
 /// Request job history with pagination and search (web handler pattern).
 ///
 /// Retrieves completed job history with optional search filtering
@@ -558,3 +910,50 @@ impl GetJobHistoryRequest {
         (request, rx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed_job(job_type: &str, age: Duration) -> QueuedJob {
+        QueuedJob {
+            id: JobId::new(),
+            job_type: job_type.to_string(),
+            payload: Vec::new(),
+            priority: 0,
+            max_retries: 3,
+            backoff: BackoffConfig::default(),
+            timeout: Duration::from_secs(30),
+            enqueued_at: Utc::now() - chrono::Duration::from_std(age).unwrap(),
+            attempt: 3,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn test_retry_failed_filter_by_job_type() {
+        let (request, _rx) = RetryFailedRequest::new(Some("send_email".to_string()), None);
+        let now = Utc::now();
+
+        assert!(request.matches(&failed_job("send_email", Duration::ZERO), now));
+        assert!(!request.matches(&failed_job("resize_image", Duration::ZERO), now));
+    }
+
+    #[test]
+    fn test_retry_failed_filter_by_age() {
+        let (request, _rx) = RetryFailedRequest::new(None, Some(Duration::from_secs(3600)));
+        let now = Utc::now();
+
+        assert!(request.matches(&failed_job("send_email", Duration::from_secs(7200)), now));
+        assert!(!request.matches(&failed_job("send_email", Duration::from_secs(60)), now));
+    }
+
+    #[test]
+    fn test_retry_failed_without_filter_matches_all() {
+        let (request, _rx) = RetryFailedRequest::new(None, None);
+        let request = request.with_priority(5);
+
+        assert_eq!(request.priority, Some(5));
+        assert!(request.matches(&failed_job("anything", Duration::ZERO), Utc::now()));
+    }
+}
@@ -10,25 +10,31 @@ pub mod scheduled;
 
 pub use history::JobHistoryRecord;
 pub use messages::{
-    CancelJobRequest, ClearDeadLetterQueueRequest, EnqueueJob, GetJobHistoryRequest,
-    GetJobStatusRequest, GetMetricsRequest, JobEnqueued, JobHistoryPage, JobMetrics,
-    ResponseChannel, RetryAllFailedRequest, RetryJobRequest,
+    CancelJobRequest, ClearDeadLetterQueueRequest, DeadLetterJob, DeadLetterPage, EnqueueJob,
+    EnqueueJobRequest, GetDeadLetterJobsRequest, GetJobHistoryRequest, GetJobProgressRequest,
+    GetJobStatusRequest, GetMetricsRequest, JobEnqueued, JobFinished, JobHistoryPage, JobMetrics,
+    ReportProgress, ResponseChannel, RetryAllFailedRequest, RetryFailedRequest, RetryJobRequest,
+    StopAcceptingJobsRequest,
 };
 #[cfg(feature = "redis")]
+pub use persistence::{LoadJobRequest, LoadSchedulesRequest};
+pub use queue::QueuedJob;
+#[cfg(feature = "redis")]
 pub use redis_agent::RedisPersistenceAgent;
-pub use scheduled::{ScheduledJobAgent, ScheduledJobEntry, ScheduledJobMessage, ScheduledJobResponse, start_scheduler_loop};
+pub use scheduled::{RegisterScheduledJobRequest, ScheduledJobAgent, ScheduledJobEntry, ScheduledJobMessage, ScheduledJobResponse, start_scheduler_loop};
 
-use super::{JobContext, JobId, JobStatus};
+use super::{JobContext, JobError, JobId, JobStatus};
 use acton_reactive::prelude::*;
 use chrono::Utc;
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tracing::{debug, warn};
 
 use history::JobHistory;
 use messages::{GetJobStatus, GetMetrics, JobStatusResponse};
-use queue::{JobQueue, QueuedJob};
+use queue::JobQueue;
 
 // Type alias for the ManagedAgent builder type
 type JobAgentBuilder = ManagedAgent<Idle, JobAgent>;
@@ -55,6 +61,10 @@ pub struct JobAgent {
     history: Arc<RwLock<JobHistory>>,
     /// Job metrics.
     metrics: Arc<RwLock<JobMetrics>>,
+    /// Dedup keys of queued and running jobs.
+    dedup_keys: Arc<RwLock<HashMap<String, JobId>>>,
+    /// Whether new jobs are accepted (cleared at shutdown).
+    accepting: Arc<AtomicBool>,
     /// Job execution context with services.
     ///
     /// Provides jobs with access to email sender, database pool, file storage, etc.
@@ -73,6 +83,8 @@ impl std::fmt::Debug for JobAgent {
             .field("dead_letter", &self.dead_letter.read().len())
             .field("history", &self.history.read().len())
             .field("metrics", &self.metrics.read())
+            .field("dedup_keys", &self.dedup_keys.read().len())
+            .field("accepting", &self.accepting.load(Ordering::Relaxed))
             .field("context", &self.context);
 
         #[cfg(feature = "redis")]
@@ -101,6 +113,8 @@ impl JobAgent {
             dead_letter: Arc::new(RwLock::new(HashMap::new())),
             history: Arc::new(RwLock::new(JobHistory::new(1000))), // Keep last 1000 jobs
             metrics: Arc::new(RwLock::new(JobMetrics::default())),
+            dedup_keys: Arc::new(RwLock::new(HashMap::new())),
+            accepting: Arc::new(AtomicBool::new(true)),
             context: Arc::new(JobContext::new()),
             #[cfg(feature = "redis")]
             redis_persistence: None,
@@ -119,6 +133,8 @@ impl JobAgent {
             dead_letter: Arc::new(RwLock::new(HashMap::new())),
             history: Arc::new(RwLock::new(JobHistory::new(1000))), // Keep last 1000 jobs
             metrics: Arc::new(RwLock::new(JobMetrics::default())),
+            dedup_keys: Arc::new(RwLock::new(HashMap::new())),
+            accepting: Arc::new(AtomicBool::new(true)),
             context: Arc::new(context),
             #[cfg(feature = "redis")]
             redis_persistence: None,
@@ -163,6 +179,8 @@ impl JobAgent {
             dead_letter: Arc::new(RwLock::new(HashMap::new())),
             history: Arc::new(RwLock::new(JobHistory::new(1000))), // Keep last 1000 jobs
             metrics: Arc::new(RwLock::new(JobMetrics::default())),
+            dedup_keys: Arc::new(RwLock::new(HashMap::new())),
+            accepting: Arc::new(AtomicBool::new(true)),
             context: Arc::new(context),
             redis_persistence: Some(redis_persistence),
         }
@@ -201,50 +219,60 @@ impl JobAgent {
                 let msg = envelope.message().clone();
                 let reply_envelope = envelope.reply_envelope();
 
-                debug!("Enqueueing job {} with priority {}", msg.id, msg.priority);
-
-                let queued_job = QueuedJob {
-                    id: msg.id,
-                    job_type: msg.job_type,
-                    payload: msg.payload,
-                    priority: msg.priority,
-                    max_retries: msg.max_retries,
-                    timeout: msg.timeout,
-                    enqueued_at: Utc::now(),
-                    attempt: 0,
-                };
-
-                // Add to in-memory queue
-                let result = agent.model.queue.write().enqueue(queued_job.clone());
-
-                // Clone Redis persistence handle if available
-                #[cfg(feature = "redis")]
-                let redis_handle = agent.model.redis_persistence.clone();
-
-                match result {
-                    Ok(()) => {
-                        agent.model.metrics.write().jobs_enqueued += 1;
+                match agent.model.enqueue(msg) {
+                    Ok(queued_job) => {
+                        #[cfg(feature = "redis")]
+                        let redis_handle = agent.model.redis_persistence.clone();
 
                         // Send response via reply_envelope
-                        let response = JobEnqueued { id: msg.id };
+                        let response = JobEnqueued {
+                            id: queued_job.id,
+                            deduplicated: false,
+                        };
                         AgentReply::from_async(async move {
                             // Persist to Redis if enabled (fire-and-forget)
                             #[cfg(feature = "redis")]
-                            if let Some(redis) = redis_handle {
-                                use persistence::PersistJob;
-                                redis.send(PersistJob { job: queued_job }).await;
-                            }
+                            Self::persist(redis_handle, queued_job).await;
 
                             let _: () = reply_envelope.send(response).await;
                         })
                     }
-                    Err(e) => {
-                        warn!("Failed to enqueue job {}: {:?}", msg.id, e);
-                        agent.model.metrics.write().jobs_rejected += 1;
-                        AgentReply::immediate()
-                    }
+                    Err(JobError::Duplicate(id)) => AgentReply::from_async(async move {
+                        let response = JobEnqueued {
+                            id,
+                            deduplicated: true,
+                        };
+                        let _: () = reply_envelope.send(response).await;
+                    }),
+                    Err(_) => AgentReply::immediate(),
                 }
             })
+            // Enqueue a job (web handler pattern with oneshot channel)
+            .mutate_on::<EnqueueJobRequest>(|agent, envelope| {
+                let msg = envelope.message().clone();
+                let response_tx = msg.response_tx;
+
+                let result = agent.model.enqueue(msg.job);
+                #[cfg(feature = "redis")]
+                let redis_handle = agent.model.redis_persistence.clone();
+
+                AgentReply::from_async(async move {
+                    let result = match result {
+                        Ok(queued_job) => {
+                            let id = queued_job.id;
+                            #[cfg(feature = "redis")]
+                            Self::persist(redis_handle, queued_job).await;
+                            Ok(id)
+                        }
+                        Err(e) => Err(e),
+                    };
+
+                    let mut guard = response_tx.lock().await;
+                    if let Some(tx) = guard.take() {
+                        let _ = tx.send(result);
+                    }
+                })
+            })
             // Get job status (read-only with reply_envelope)
             .act_on::<GetJobStatus>(|agent, envelope| {
                 let msg = envelope.message().clone();
@@ -307,6 +335,48 @@ impl JobAgent {
                     Self::send_status_response(response_tx, status).await;
                 })
             })
+            // Record progress of a running job
+            .mutate_on::<ReportProgress>(|agent, envelope| {
+                let msg = envelope.message().clone();
+
+                let mut running = agent.model.running.write();
+                let started_at = match running.get(&msg.id) {
+                    Some(JobStatus::Running { started_at, .. }) => Some(*started_at),
+                    // First report: a queued job has started running
+                    None if agent.model.queue.read().contains(&msg.id) => Some(Utc::now()),
+                    // Finished or unknown job
+                    _ => None,
+                };
+                if let Some(started_at) = started_at {
+                    running.insert(
+                        msg.id,
+                        JobStatus::Running {
+                            started_at,
+                            progress: Some(msg.progress),
+                        },
+                    );
+                }
+                drop(running);
+
+                AgentReply::immediate()
+            })
+            // Get job progress (web handler pattern with oneshot channel)
+            .act_on::<GetJobProgressRequest>(|agent, envelope| {
+                let msg = envelope.message();
+                let response_tx = msg.response_tx.clone();
+
+                let progress = match agent.model.running.read().get(&msg.id) {
+                    Some(JobStatus::Running { progress, .. }) => progress.clone(),
+                    _ => None,
+                };
+
+                Box::pin(async move {
+                    let mut guard = response_tx.lock().await;
+                    if let Some(tx) = guard.take() {
+                        let _ = tx.send(progress);
+                    }
+                })
+            })
             // Retry a failed job from dead letter queue
             .mutate_on::<RetryJobRequest>(|agent, envelope| {
                 let msg = envelope.message();
@@ -353,6 +423,46 @@ impl JobAgent {
                     Self::send_usize_response(response_tx, retried).await;
                 })
             })
+            // Retry failed jobs matching a filter
+            .mutate_on::<RetryFailedRequest>(|agent, envelope| {
+                let msg = envelope.message().clone();
+                let now = Utc::now();
+
+                let mut dlq = agent.model.dead_letter.write();
+                let ids: Vec<JobId> = dlq
+                    .values()
+                    .filter(|job| msg.matches(job, now))
+                    .map(|job| job.id)
+                    .collect();
+
+                let mut queue = agent.model.queue.write();
+                let mut retried = 0;
+                for id in ids {
+                    let Some(mut job) = dlq.remove(&id) else {
+                        continue;
+                    };
+                    let original = job.clone();
+
+                    job.attempt = 0;
+                    if let Some(priority) = msg.priority {
+                        job.priority = priority;
+                    }
+
+                    if queue.enqueue(job).is_ok() {
+                        retried += 1;
+                    } else {
+                        // Queue full: keep the job in the DLQ for a later replay
+                        dlq.insert(id, original);
+                    }
+                }
+                drop(queue);
+                drop(dlq);
+
+                let response_tx = msg.response_tx;
+                AgentReply::from_async(async move {
+                    Self::send_usize_response(response_tx, retried).await;
+                })
+            })
             // Cancel a running or pending job
             .mutate_on::<CancelJobRequest>(|agent, envelope| {
                 let msg = envelope.message();
@@ -366,11 +476,70 @@ impl JobAgent {
                     // If not in queue, check if it's running and mark for cancellation
                     agent.model.running.write().remove(&job_id).is_some()
                 };
+                if success {
+                    agent.model.release_dedup_key(&job_id);
+                }
 
                 AgentReply::from_async(async move {
                     Self::send_bool_response(response_tx, success).await;
                 })
             })
+            // A job completed or failed out
+            .mutate_on::<JobFinished>(|agent, envelope| {
+                let msg = envelope.message();
+
+                let job = agent.model.queue.write().remove(&msg.id);
+                agent.model.running.write().remove(&msg.id);
+                agent.model.release_dedup_key(&msg.id);
+
+                let mut metrics = agent.model.metrics.write();
+                if msg.success {
+                    metrics.jobs_completed += 1;
+                } else {
+                    metrics.jobs_failed += 1;
+                    if let Some(mut job) = job {
+                        // Failed out: every retry was used
+                        job.attempt = job.attempt.max(job.max_retries);
+                        job.last_error.clone_from(&msg.error);
+                        agent.model.dead_letter.write().insert(job.id, job);
+                        metrics.jobs_in_dlq += 1;
+                    }
+                }
+                drop(metrics);
+                debug!("Job {} finished (success: {})", msg.id, msg.success);
+
+                AgentReply::immediate()
+            })
+            // List dead letter queue entries
+            .act_on::<GetDeadLetterJobsRequest>(|agent, envelope| {
+                let msg = envelope.message();
+                let response_tx = msg.response_tx.clone();
+
+                let mut jobs: Vec<DeadLetterJob> = agent
+                    .model
+                    .dead_letter
+                    .read()
+                    .values()
+                    .map(DeadLetterJob::from)
+                    .collect();
+                // Most recent first
+                jobs.sort_by_key(|job| std::cmp::Reverse(job.enqueued_at));
+
+                let total_count = jobs.len();
+                let page_jobs = jobs
+                    .into_iter()
+                    .skip((msg.page - 1) * msg.page_size)
+                    .take(msg.page_size)
+                    .collect();
+                let page = DeadLetterPage::new(page_jobs, msg.page, msg.page_size, total_count);
+
+                Box::pin(async move {
+                    let mut guard = response_tx.lock().await;
+                    if let Some(tx) = guard.take() {
+                        let _ = tx.send(page);
+                    }
+                })
+            })
             // Clear the dead letter queue
             .mutate_on::<ClearDeadLetterQueueRequest>(|agent, envelope| {
                 let response_tx = envelope.message().response_tx.clone();
@@ -390,6 +559,28 @@ impl JobAgent {
                     Self::send_usize_response(response_tx, count).await;
                 })
             })
+            // Stop accepting jobs and persist the remaining queue
+            .mutate_on::<StopAcceptingJobsRequest>(|agent, envelope| {
+                let response_tx = envelope.message().response_tx.clone();
+
+                agent.model.accepting.store(false, Ordering::Relaxed);
+                let queued: Vec<QueuedJob> = agent.model.queue.read().jobs().cloned().collect();
+                debug!("Stopped accepting jobs with {} still queued", queued.len());
+
+                #[cfg(feature = "redis")]
+                let redis_handle = agent.model.redis_persistence.clone();
+
+                AgentReply::from_async(async move {
+                    let count = queued.len();
+
+                    #[cfg(feature = "redis")]
+                    for job in queued {
+                        Self::persist(redis_handle.clone(), job).await;
+                    }
+
+                    Self::send_usize_response(response_tx, count).await;
+                })
+            })
             // Get job history with pagination and search
             .act_on::<GetJobHistoryRequest>(|agent, envelope| {
                 let msg = envelope.message();
@@ -417,6 +608,79 @@ impl JobAgent {
         Ok(builder.start().await)
     }
 
+    /// Add a job to the in-memory queue, updating metrics.
+    ///
+    /// Rejects the job while shutting down or when the queue is full, and
+    /// returns [`JobError::Duplicate`] when a job with the same dedup key is
+    /// already queued or running.
+    fn enqueue(&self, msg: EnqueueJob) -> Result<QueuedJob, JobError> {
+        if !self.accepting.load(Ordering::Relaxed) {
+            warn!("Rejecting job {}: job system is shutting down", msg.id);
+            self.metrics.write().jobs_rejected += 1;
+            return Err(JobError::AgentUnavailable);
+        }
+
+        if let Some(key) = &msg.dedup_key {
+            let existing = self.dedup_keys.read().get(key).copied();
+            if let Some(existing) = existing {
+                debug!("Job {} deduplicated into {} (key: {})", msg.id, existing, key);
+                return Err(JobError::Duplicate(existing));
+            }
+        }
+
+        debug!("Enqueueing job {} with priority {}", msg.id, msg.priority);
+
+        let queued_job = QueuedJob {
+            id: msg.id,
+            job_type: msg.job_type,
+            payload: msg.payload,
+            priority: msg.priority,
+            max_retries: msg.max_retries,
+            backoff: msg.backoff,
+            timeout: msg.timeout,
+            enqueued_at: Utc::now(),
+            attempt: 0,
+            last_error: None,
+        };
+
+        let mut queue = self.queue.write();
+        let result = if queue.len() >= queue.max_size() {
+            Err(JobError::QueueFull(queue.max_size()))
+        } else {
+            queue.enqueue(queued_job.clone()).map_err(JobError::Other)
+        };
+        drop(queue);
+
+        match result {
+            Ok(()) => {
+                if let Some(key) = msg.dedup_key {
+                    self.dedup_keys.write().insert(key, queued_job.id);
+                }
+                self.metrics.write().jobs_enqueued += 1;
+                Ok(queued_job)
+            }
+            Err(e) => {
+                warn!("Failed to enqueue job {}: {}", queued_job.id, e);
+                self.metrics.write().jobs_rejected += 1;
+                Err(e)
+            }
+        }
+    }
+
+    /// Release the dedup key held by a job, if any.
+    fn release_dedup_key(&self, id: &JobId) {
+        self.dedup_keys.write().retain(|_, job| job != id);
+    }
+
+    /// Persist a queued job to Redis if enabled (fire-and-forget).
+    #[cfg(feature = "redis")]
+    async fn persist(redis_handle: Option<AgentHandle>, job: QueuedJob) {
+        if let Some(redis) = redis_handle {
+            use persistence::PersistJob;
+            redis.send(PersistJob { job }).await;
+        }
+    }
+
     /// Send metrics response via oneshot channel.
     ///
     /// Helper method for web handler pattern responses.
@@ -477,3 +741,171 @@ impl JobAgent {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn enqueue() -> EnqueueJob {
+        EnqueueJob {
+            id: JobId::new(),
+            job_type: "slow_job".to_string(),
+            payload: Vec::new(),
+            priority: 0,
+            max_retries: 0,
+            backoff: crate::htmx::jobs::BackoffConfig::default(),
+            timeout: Duration::from_secs(30),
+            dedup_key: None,
+        }
+    }
+
+    fn reminder(key: &str) -> EnqueueJob {
+        EnqueueJob {
+            dedup_key: Some(key.to_string()),
+            ..enqueue()
+        }
+    }
+
+    async fn request(handle: &AgentHandle, job: EnqueueJob) -> Result<JobId, JobError> {
+        let (request, rx) = EnqueueJobRequest::new(job);
+        handle.send(request).await;
+        rx.await.unwrap()
+    }
+
+    async fn metrics(handle: &AgentHandle) -> JobMetrics {
+        let (request, rx) = GetMetricsRequest::new();
+        handle.send(request).await;
+        rx.await.unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stop_accepting_rejects_new_jobs() {
+        let mut runtime = ActonApp::launch();
+        let handle = JobAgent::spawn(&mut runtime).await.unwrap();
+
+        handle.send(enqueue()).await;
+        let (request, rx) = StopAcceptingJobsRequest::new();
+        handle.send(request).await;
+        assert_eq!(rx.await.unwrap(), 1);
+
+        handle.send(enqueue()).await;
+        let metrics = metrics(&handle).await;
+        assert_eq!(metrics.jobs_enqueued, 1);
+        assert_eq!(metrics.jobs_rejected, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dedup_collapses_duplicates() {
+        let mut runtime = ActonApp::launch();
+        let handle = JobAgent::spawn(&mut runtime).await.unwrap();
+
+        let first = request(&handle, reminder("reminder:42")).await.unwrap();
+        let duplicate = request(&handle, reminder("reminder:42")).await;
+        assert!(matches!(duplicate, Err(JobError::Duplicate(id)) if id == first));
+
+        let other = request(&handle, reminder("reminder:43")).await.unwrap();
+        assert_ne!(other, first);
+        assert!(request(&handle, enqueue()).await.is_ok());
+
+        let metrics = metrics(&handle).await;
+        assert_eq!(metrics.jobs_enqueued, 3);
+        assert_eq!(metrics.jobs_rejected, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dedup_allows_reenqueue_after_finish() {
+        let mut runtime = ActonApp::launch();
+        let handle = JobAgent::spawn(&mut runtime).await.unwrap();
+
+        let first = request(&handle, reminder("reminder:42")).await.unwrap();
+        handle.send(JobFinished {
+            id: first,
+            success: true,
+            error: None,
+        }).await;
+        let second = request(&handle, reminder("reminder:42")).await.unwrap();
+        assert_ne!(second, first);
+
+        handle.send(JobFinished {
+            id: second,
+            success: false,
+            error: None,
+        }).await;
+        assert!(request(&handle, reminder("reminder:42")).await.is_ok());
+
+        let metrics = metrics(&handle).await;
+        assert_eq!(metrics.jobs_completed, 1);
+        assert_eq!(metrics.jobs_failed, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dedup_released_on_cancel() {
+        let mut runtime = ActonApp::launch();
+        let handle = JobAgent::spawn(&mut runtime).await.unwrap();
+
+        let first = request(&handle, reminder("reminder:42")).await.unwrap();
+        let (cancel, rx) = CancelJobRequest::new(first);
+        handle.send(cancel).await;
+        assert!(rx.await.unwrap());
+
+        assert!(request(&handle, reminder("reminder:42")).await.is_ok());
+    }
+
+    async fn dead_letter_page(handle: &AgentHandle, page: usize, page_size: usize) -> DeadLetterPage {
+        let (request, rx) = GetDeadLetterJobsRequest::new(page, page_size);
+        handle.send(request).await;
+        rx.await.unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_failed_job_in_dead_letter_queue() {
+        let mut runtime = ActonApp::launch();
+        let handle = JobAgent::spawn(&mut runtime).await.unwrap();
+
+        let id = request(&handle, EnqueueJob { max_retries: 2, ..enqueue() }).await.unwrap();
+        handle
+            .send(JobFinished {
+                id,
+                success: false,
+                error: Some("SMTP connection refused".to_string()),
+            })
+            .await;
+
+        let page = dead_letter_page(&handle, 1, 20).await;
+        assert_eq!(page.total_count, 1);
+        let job = &page.jobs[0];
+        assert_eq!(job.id, id);
+        assert_eq!(job.job_type, "slow_job");
+        assert_eq!(job.last_error.as_deref(), Some("SMTP connection refused"));
+        assert_eq!(job.attempts, 3);
+        assert_eq!(metrics(&handle).await.jobs_in_dlq, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dead_letter_pagination() {
+        let mut runtime = ActonApp::launch();
+        let handle = JobAgent::spawn(&mut runtime).await.unwrap();
+
+        for _ in 0..3 {
+            let id = request(&handle, enqueue()).await.unwrap();
+            handle
+                .send(JobFinished {
+                    id,
+                    success: false,
+                    error: None,
+                })
+                .await;
+        }
+
+        let first = dead_letter_page(&handle, 1, 2).await;
+        assert_eq!(first.jobs.len(), 2);
+        assert_eq!(first.total_count, 3);
+        assert!(first.has_next);
+
+        let second = dead_letter_page(&handle, 2, 2).await;
+        assert_eq!(second.jobs.len(), 1);
+        assert!(second.has_prev);
+        assert!(!second.has_next);
+    }
+}
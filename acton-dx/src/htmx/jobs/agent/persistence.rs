@@ -13,10 +13,22 @@ use crate::htmx::jobs::JobId;
 use crate::htmx::jobs::JobId;
 
 #[cfg(feature = "redis")]
-use crate::htmx::jobs::JobStatus;
+use super::messages::ResponseChannel;
+#[cfg(feature = "redis")]
+use super::scheduled::ScheduledJobEntry;
+#[cfg(feature = "redis")]
+use crate::htmx::jobs::{JobError, JobStatus};
+#[cfg(feature = "redis")]
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 #[cfg(feature = "redis")]
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "redis")]
+use std::io::{Read, Write};
+#[cfg(feature = "redis")]
+use std::sync::Arc;
+#[cfg(feature = "redis")]
+use tokio::sync::{oneshot, Mutex};
+#[cfg(feature = "redis")]
 use tracing::{debug, error};
 
 #[cfg(feature = "redis")]
@@ -30,6 +42,32 @@ pub struct PersistJob {
     pub job: QueuedJob,
 }
 
+/// Request a persisted job from Redis (web handler pattern).
+///
+/// Responds with `None` if the job is not stored or cannot be decoded.
+#[cfg(feature = "redis")]
+#[derive(Debug, Clone)]
+pub struct LoadJobRequest {
+    /// Job ID.
+    pub id: JobId,
+    /// Response channel for the job.
+    pub response_tx: ResponseChannel<Option<QueuedJob>>,
+}
+
+#[cfg(feature = "redis")]
+impl LoadJobRequest {
+    /// Create a new load request with response channel.
+    #[must_use]
+    pub fn new(id: JobId) -> (Self, oneshot::Receiver<Option<QueuedJob>>) {
+        let (tx, rx) = oneshot::channel();
+        let request = Self {
+            id,
+            response_tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (request, rx)
+    }
+}
+
 /// Message to mark a job as completed in Redis.
 #[cfg(feature = "redis")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,24 +102,142 @@ pub struct MoveToDeadLetterQueue {
     pub error: String,
 }
 
+/// Message to save a scheduled job definition to Redis.
+#[cfg(feature = "redis")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistSchedule {
+    /// Scheduled job with its current execution state.
+    pub entry: ScheduledJobEntry,
+}
+
+/// Message to delete a scheduled job definition from Redis.
+#[cfg(feature = "redis")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveSchedule {
+    /// Scheduled job ID.
+    pub id: JobId,
+}
+
+/// Load all saved scheduled jobs (web handler pattern).
+///
+/// Sent by [`ScheduledJobAgent::spawn_with_persistence`](super::ScheduledJobAgent::spawn_with_persistence)
+/// at startup.
+#[cfg(feature = "redis")]
+#[derive(Clone, Debug)]
+pub struct LoadSchedulesRequest {
+    /// Response channel for the saved schedules.
+    pub response_tx: ResponseChannel<Vec<ScheduledJobEntry>>,
+}
+
+#[cfg(feature = "redis")]
+impl LoadSchedulesRequest {
+    /// Create a new load request with response channel.
+    #[must_use]
+    pub fn new() -> (Self, oneshot::Receiver<Vec<ScheduledJobEntry>>) {
+        let (tx, rx) = oneshot::channel();
+        let request = Self {
+            response_tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (request, rx)
+    }
+}
+
+/// Redis hash holding scheduled job definitions keyed by ID.
+#[cfg(feature = "redis")]
+pub(super) const SCHEDULES_KEY: &str = "schedules";
+
+/// `encoding` hash field value for plain JSON jobs.
+#[cfg(feature = "redis")]
+pub(super) const ENCODING_JSON: &str = "json";
+
+/// `encoding` hash field value for gzip-compressed JSON jobs.
+#[cfg(feature = "redis")]
+pub(super) const ENCODING_GZIP: &str = "gzip";
+
+/// A job serialized for storage in Redis.
+#[cfg(feature = "redis")]
+#[derive(Debug)]
+pub(super) struct EncodedJob {
+    /// Serialized (and possibly compressed) job.
+    pub data: Vec<u8>,
+    /// How `data` is encoded ([`ENCODING_JSON`] or [`ENCODING_GZIP`]).
+    pub encoding: &'static str,
+}
+
+/// Serialize a job, gzip-compressing it if larger than `compress_threshold_bytes`.
+///
+/// # Errors
+///
+/// Returns error if serialization or compression fails.
+#[cfg(feature = "redis")]
+pub(super) fn encode_job(
+    job: &QueuedJob,
+    compress_threshold_bytes: usize,
+) -> Result<EncodedJob, JobError> {
+    let json = serde_json::to_vec(job)?;
+    if json.len() <= compress_threshold_bytes {
+        return Ok(EncodedJob {
+            data: json,
+            encoding: ENCODING_JSON,
+        });
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&json)
+        .and_then(|()| encoder.finish())
+        .map(|data| EncodedJob {
+            data,
+            encoding: ENCODING_GZIP,
+        })
+        .map_err(|e| JobError::Other(format!("failed to compress job {}: {e}", job.id)))
+}
+
+/// Deserialize a job stored by [`encode_job`].
+///
+/// # Errors
+///
+/// Returns error if the encoding is unknown or the data is corrupt.
+#[cfg(feature = "redis")]
+pub(super) fn decode_job(data: &[u8], encoding: &str) -> Result<QueuedJob, JobError> {
+    match encoding {
+        ENCODING_JSON => Ok(serde_json::from_slice(data)?),
+        ENCODING_GZIP => {
+            let mut json = Vec::new();
+            GzDecoder::new(data)
+                .read_to_end(&mut json)
+                .map_err(|e| JobError::Other(format!("failed to decompress job: {e}")))?;
+            Ok(serde_json::from_slice(&json)?)
+        }
+        other => Err(JobError::Other(format!("unknown job encoding: {other}"))),
+    }
+}
+
+/// Convert a job encoding error into a Redis error.
+#[cfg(feature = "redis")]
+pub(super) fn encoding_error(error: &JobError) -> redis::RedisError {
+    redis::RedisError::from((
+        redis::ErrorKind::TypeError,
+        "job encoding error",
+        error.to_string(),
+    ))
+}
+
 #[cfg(feature = "redis")]
 /// Persist a job to Redis (called from act_on handler).
-#[allow(dead_code)] // Will be used when Redis handlers are enabled
 pub(super) async fn persist_job_to_redis(
     redis: &mut redis::aio::MultiplexedConnection,
     job: &QueuedJob,
+    compress_threshold_bytes: usize,
 ) -> Result<(), redis::RedisError> {
     let key = format!("job:{}", job.id);
-    let json = serde_json::to_string(job).map_err(|e| {
-        redis::RedisError::from((
-            redis::ErrorKind::TypeError,
-            "serialization error",
-            e.to_string(),
-        ))
-    })?;
+    let encoded = encode_job(job, compress_threshold_bytes).map_err(|e| encoding_error(&e))?;
 
     // Store job data with 7 day expiry
-    let _: () = redis.set_ex(&key, json, 604_800).await?;
+    let _: () = redis
+        .hset_multiple(&key, &[("job", encoded.data.as_slice()), ("encoding", encoded.encoding.as_bytes())])
+        .await?;
+    let _: () = redis.expire(&key, 604_800).await?;
 
     // Add to pending queue
     let _: usize = redis.lpush("queue:pending", job.id.to_string()).await?;
@@ -92,7 +248,6 @@ pub(super) async fn persist_job_to_redis(
 
 #[cfg(feature = "redis")]
 /// Mark job as completed in Redis.
-#[allow(dead_code)] // Will be used when Redis handlers are enabled
 pub(super) async fn mark_completed_in_redis(
     redis: &mut redis::aio::MultiplexedConnection,
     id: JobId,
@@ -125,7 +280,6 @@ pub(super) async fn mark_completed_in_redis(
 
 #[cfg(feature = "redis")]
 /// Mark job as failed in Redis.
-#[allow(dead_code)] // Will be used when Redis handlers are enabled
 pub(super) async fn mark_failed_in_redis(
     redis: &mut redis::aio::MultiplexedConnection,
     id: JobId,
@@ -157,24 +311,19 @@ pub(super) async fn mark_failed_in_redis(
 
 #[cfg(feature = "redis")]
 /// Move job to dead letter queue.
-#[allow(dead_code)] // Will be used when Redis handlers are enabled
 pub(super) async fn move_to_dlq_in_redis(
     redis: &mut redis::aio::MultiplexedConnection,
     id: JobId,
     job: &QueuedJob,
     error: &str,
+    compress_threshold_bytes: usize,
 ) -> Result<(), redis::RedisError> {
     let dlq_key = format!("dlq:{id}");
-    let json = serde_json::to_string(job).map_err(|e| {
-        redis::RedisError::from((
-            redis::ErrorKind::TypeError,
-            "serialization error",
-            e.to_string(),
-        ))
-    })?;
+    let encoded = encode_job(job, compress_threshold_bytes).map_err(|e| encoding_error(&e))?;
 
     // Store in DLQ with permanent retention
-    let _: () = redis.hset(&dlq_key, "job", json).await?;
+    let _: () = redis.hset(&dlq_key, "job", encoded.data).await?;
+    let _: () = redis.hset(&dlq_key, "encoding", encoded.encoding).await?;
     let _: () = redis.hset(&dlq_key, "error", error).await?;
     let _: () = redis.hset(&dlq_key, "moved_at", chrono::Utc::now().to_rfc3339()).await?;
 
@@ -188,6 +337,79 @@ pub(super) async fn move_to_dlq_in_redis(
     Ok(())
 }
 
+#[cfg(feature = "redis")]
+/// Load a persisted job from Redis, decompressing it if needed.
+///
+/// Returns `None` if the job is not stored.
+pub(super) async fn load_job_from_redis(
+    redis: &mut redis::aio::MultiplexedConnection,
+    id: JobId,
+) -> Result<Option<QueuedJob>, redis::RedisError> {
+    let key = format!("job:{id}");
+    let (data, encoding): (Option<Vec<u8>>, Option<String>) = redis::cmd("HMGET")
+        .arg(&key)
+        .arg("job")
+        .arg("encoding")
+        .query_async(redis)
+        .await?;
+
+    let Some(data) = data else {
+        return Ok(None);
+    };
+    let encoding = encoding.as_deref().unwrap_or(ENCODING_JSON);
+    decode_job(&data, encoding)
+        .map(Some)
+        .map_err(|e| encoding_error(&e))
+}
+
+#[cfg(feature = "redis")]
+/// Save a scheduled job definition to Redis.
+pub(super) async fn persist_schedule_to_redis(
+    redis: &mut redis::aio::MultiplexedConnection,
+    entry: &ScheduledJobEntry,
+) -> Result<(), redis::RedisError> {
+    let json = serde_json::to_string(entry).map_err(|e| {
+        redis::RedisError::from((
+            redis::ErrorKind::TypeError,
+            "serialization error",
+            e.to_string(),
+        ))
+    })?;
+    let _: () = redis.hset(SCHEDULES_KEY, entry.id.to_string(), json).await?;
+
+    debug!("Persisted scheduled job {} to Redis", entry.id);
+    Ok(())
+}
+
+#[cfg(feature = "redis")]
+/// Delete a scheduled job definition from Redis.
+pub(super) async fn remove_schedule_from_redis(
+    redis: &mut redis::aio::MultiplexedConnection,
+    id: JobId,
+) -> Result<(), redis::RedisError> {
+    let _: usize = redis.hdel(SCHEDULES_KEY, id.to_string()).await?;
+
+    debug!("Removed scheduled job {} from Redis", id);
+    Ok(())
+}
+
+#[cfg(feature = "redis")]
+/// Load all saved scheduled job definitions, skipping corrupt entries.
+pub(super) async fn load_schedules_from_redis(
+    redis: &mut redis::aio::MultiplexedConnection,
+) -> Result<Vec<ScheduledJobEntry>, redis::RedisError> {
+    let saved: std::collections::HashMap<String, String> = redis.hgetall(SCHEDULES_KEY).await?;
+
+    Ok(saved
+        .into_iter()
+        .filter_map(|(id, json)| {
+            serde_json::from_str(&json)
+                .inspect_err(|e| error!("Skipping corrupt scheduled job {}: {}", id, e))
+                .ok()
+        })
+        .collect())
+}
+
 #[cfg(not(feature = "redis"))]
 /// Stub implementation when Redis feature is disabled.
 #[allow(dead_code)]
@@ -215,3 +437,80 @@ pub(super) async fn mark_failed_in_redis(_id: JobId, _error: &str, _attempt: u32
 pub(super) async fn move_to_dlq_in_redis(_id: JobId, _job: &QueuedJob, _error: &str) -> Result<(), String> {
     Ok(())
 }
+
+#[cfg(all(test, feature = "redis"))]
+mod tests {
+    use super::*;
+    use crate::htmx::jobs::BackoffConfig;
+    use std::time::Duration;
+
+    fn job_with_payload(size: usize) -> QueuedJob {
+        QueuedJob {
+            id: JobId::new(),
+            job_type: "ReportJob".to_string(),
+            payload: serde_json::to_vec(&serde_json::json!({ "rows": "x".repeat(size) })).unwrap(),
+            priority: 0,
+            max_retries: 3,
+            backoff: BackoffConfig::default(),
+            timeout: Duration::from_secs(60),
+            enqueued_at: chrono::Utc::now(),
+            attempt: 0,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn test_large_job_round_trips_compressed() {
+        let job = job_with_payload(64 * 1024);
+        let json_len = serde_json::to_vec(&job).unwrap().len();
+
+        let encoded = encode_job(&job, 4096).unwrap();
+        assert_eq!(encoded.encoding, ENCODING_GZIP);
+        assert!(encoded.data.len() < json_len / 10);
+
+        let decoded = decode_job(&encoded.data, encoded.encoding).unwrap();
+        assert_eq!(decoded.id, job.id);
+        assert_eq!(decoded.payload, job.payload);
+    }
+
+    #[test]
+    fn test_job_below_threshold_round_trips_uncompressed() {
+        let job = job_with_payload(64 * 1024);
+
+        let encoded = encode_job(&job, usize::MAX).unwrap();
+        assert_eq!(encoded.encoding, ENCODING_JSON);
+        assert_eq!(encoded.data, serde_json::to_vec(&job).unwrap());
+
+        let decoded = decode_job(&encoded.data, encoded.encoding).unwrap();
+        assert_eq!(decoded.id, job.id);
+        assert_eq!(decoded.payload, job.payload);
+    }
+
+    #[test]
+    fn test_decode_rejects_corrupt_data() {
+        assert!(decode_job(b"not gzip", ENCODING_GZIP).is_err());
+        assert!(decode_job(b"{}", "zstd").is_err());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a running Redis server (REDIS_URL, default redis://127.0.0.1:6379)"]
+    async fn test_redis_persist_and_load() {
+        let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+        let client = redis::Client::open(url).unwrap();
+        let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+
+        for threshold in [4096, usize::MAX] {
+            let job = job_with_payload(64 * 1024);
+            persist_job_to_redis(&mut conn, &job, threshold).await.unwrap();
+
+            let loaded = load_job_from_redis(&mut conn, job.id).await.unwrap().unwrap();
+            assert_eq!(loaded.payload, job.payload);
+
+            // Status updates share the job hash
+            mark_completed_in_redis(&mut conn, job.id, 5).await.unwrap();
+            assert!(load_job_from_redis(&mut conn, job.id).await.unwrap().is_some());
+        }
+
+        assert!(load_job_from_redis(&mut conn, JobId::new()).await.unwrap().is_none());
+    }
+}
@@ -1,6 +1,6 @@
 //! Priority queue for jobs.
 
-use crate::htmx::jobs::JobId;
+use crate::htmx::jobs::{BackoffConfig, JobId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -20,12 +20,29 @@ pub struct QueuedJob {
     pub priority: i32,
     /// Maximum number of retry attempts.
     pub max_retries: u32,
+    /// Backoff policy between retry attempts.
+    #[serde(default)]
+    pub backoff: BackoffConfig,
     /// Job execution timeout.
     pub timeout: Duration,
     /// When the job was enqueued.
     pub enqueued_at: DateTime<Utc>,
     /// Current attempt number (0 = first attempt).
     pub attempt: u32,
+    /// Error message from the last failed attempt.
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+impl QueuedJob {
+    /// Delay before retrying this job after its current attempt failed.
+    ///
+    /// Returns `None` once `max_retries` is exhausted and the job belongs in
+    /// the dead letter queue.
+    #[must_use]
+    pub fn next_retry_delay(&self) -> Option<Duration> {
+        (self.attempt < self.max_retries).then(|| self.backoff.delay(self.attempt))
+    }
 }
 
 /// Wrapper for priority queue ordering.
@@ -135,9 +152,19 @@ impl JobQueue {
         removed.into_iter().next().map(|entry| entry.job)
     }
 
+    /// Maximum number of queued jobs.
+    #[must_use]
+    pub(super) const fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Iterate over the queued jobs in no particular order.
+    pub(super) fn jobs(&self) -> impl Iterator<Item = &QueuedJob> {
+        self.heap.iter().map(|entry| &entry.job)
+    }
+
     /// Get current queue size.
     #[must_use]
-    #[allow(dead_code)] // May be used in future features
     pub(super) fn len(&self) -> usize {
         self.heap.len()
     }
@@ -7,12 +7,13 @@
 //! only modify external state (the Redis database), not agent state.
 
 use super::persistence::{
-    MarkJobCompleted, MarkJobFailed, MoveToDeadLetterQueue, PersistJob,
+    load_job_from_redis, load_schedules_from_redis, mark_completed_in_redis, mark_failed_in_redis,
+    move_to_dlq_in_redis, persist_job_to_redis, persist_schedule_to_redis,
+    remove_schedule_from_redis, LoadJobRequest, LoadSchedulesRequest, MarkJobCompleted,
+    MarkJobFailed, MoveToDeadLetterQueue, PersistJob, PersistSchedule, RemoveSchedule,
 };
-use super::queue::QueuedJob;
-use crate::htmx::jobs::{JobId, JobStatus};
+use crate::htmx::config::JobsSettings;
 use acton_reactive::prelude::*;
-use redis::AsyncCommands;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tracing::{debug, error, warn};
@@ -36,12 +37,26 @@ use tracing::{debug, error, warn};
 /// - `MarkJobCompleted` - Mark job as completed
 /// - `MarkJobFailed` - Mark job as failed
 /// - `MoveToDeadLetterQueue` - Move job to DLQ
+///
+/// Web handlers load persisted jobs with `LoadJobRequest`.
+///
+/// `ScheduledJobAgent` saves schedule definitions with `PersistSchedule` and
+/// `RemoveSchedule`, and restores them with `LoadSchedulesRequest`.
+///
+/// # Storage
+///
+/// Each job is stored in a `job:{id}` hash with a `job` field holding the
+/// serialized job and an `encoding` field (`json` or `gzip`). Jobs larger
+/// than `jobs.compress_threshold_bytes` are gzip-compressed; loading
+/// decompresses them transparently.
 #[derive(Clone, Default)]
 pub struct RedisPersistenceAgent {
     /// Redis connection (cloneable via Arc internally).
     ///
     /// None in Default impl - always set via spawn().
     redis_conn: Option<redis::aio::MultiplexedConnection>,
+    /// Serialized jobs larger than this are gzip-compressed.
+    compress_threshold_bytes: usize,
     /// Count of operations performed (for metrics).
     operations_count: Arc<AtomicUsize>,
 }
@@ -50,6 +65,7 @@ impl std::fmt::Debug for RedisPersistenceAgent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RedisPersistenceAgent")
             .field("redis_conn", &self.redis_conn.is_some())
+            .field("compress_threshold_bytes", &self.compress_threshold_bytes)
             .field("operations_count", &self.operations_count.load(Ordering::Relaxed))
             .finish()
     }
@@ -58,6 +74,8 @@ impl std::fmt::Debug for RedisPersistenceAgent {
 impl RedisPersistenceAgent {
     /// Create and spawn a new Redis persistence agent.
     ///
+    /// Uses the default [`JobsSettings`]; see [`Self::spawn_with_settings`].
+    ///
     /// # Arguments
     ///
     /// * `redis_url` - Redis connection URL (e.g., "redis://localhost:6379")
@@ -92,6 +110,27 @@ impl RedisPersistenceAgent {
         redis_url: &str,
         runtime: &mut AgentRuntime,
     ) -> anyhow::Result<AgentHandle> {
+        Self::spawn_with_settings(redis_url, &JobsSettings::default(), runtime).await
+    }
+
+    /// Create and spawn a new Redis persistence agent with job settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - Redis connection fails
+    /// - Agent spawning fails
+    ///
+    /// # Panics
+    ///
+    /// Panics if handler configuration fails, which should not occur in normal operation.
+    pub async fn spawn_with_settings(
+        redis_url: &str,
+        settings: &JobsSettings,
+        runtime: &mut AgentRuntime,
+    ) -> anyhow::Result<AgentHandle> {
+        let compress_threshold_bytes = settings.compress_threshold_bytes;
+
         // Create Redis connection
         let client = redis::Client::open(redis_url)?;
         let conn = client.get_multiplexed_async_connection().await?;
@@ -104,6 +143,7 @@ impl RedisPersistenceAgent {
                 // Set model with Redis connection
                 agent.model = Self {
                     redis_conn: Some(conn),
+                    compress_threshold_bytes,
                     operations_count: Arc::new(AtomicUsize::new(0)),
                 };
 
@@ -118,6 +158,7 @@ impl RedisPersistenceAgent {
     ///
     /// All handlers use `act_on` for concurrent execution since they only
     /// perform external IO without modifying agent state.
+    #[allow(clippy::too_many_lines)]
     async fn configure_handlers(
         mut builder: ManagedAgent<Idle, Self>,
     ) -> anyhow::Result<AgentHandle> {
@@ -126,13 +167,14 @@ impl RedisPersistenceAgent {
             .act_on::<PersistJob>(|agent, envelope| {
                 let conn_opt = agent.model.redis_conn.clone();
                 let job = envelope.message().job.clone();
+                let threshold = agent.model.compress_threshold_bytes;
                 let ops_count = agent.model.operations_count.clone();
 
                 // Spawn as tokio task to satisfy Sync bound
                 Box::pin(async move {
                     tokio::spawn(async move {
                         if let Some(mut conn) = conn_opt {
-                            match persist_job_impl(&mut conn, &job).await {
+                            match persist_job_to_redis(&mut conn, &job, threshold).await {
                                 Ok(()) => {
                                     ops_count.fetch_add(1, Ordering::Relaxed);
                                     debug!("Successfully persisted job {}", job.id);
@@ -156,7 +198,7 @@ impl RedisPersistenceAgent {
                 Box::pin(async move {
                     tokio::spawn(async move {
                         if let Some(mut conn) = conn_opt {
-                            match mark_completed_impl(&mut conn, msg.id, msg.execution_time_ms).await {
+                            match mark_completed_in_redis(&mut conn, msg.id, msg.execution_time_ms).await {
                                 Ok(()) => {
                                     ops_count.fetch_add(1, Ordering::Relaxed);
                                     debug!("Successfully marked job {} as completed", msg.id);
@@ -180,7 +222,7 @@ impl RedisPersistenceAgent {
                 Box::pin(async move {
                     tokio::spawn(async move {
                         if let Some(mut conn) = conn_opt {
-                            match mark_failed_impl(&mut conn, msg.id, &msg.error, msg.attempt).await {
+                            match mark_failed_in_redis(&mut conn, msg.id, &msg.error, msg.attempt).await {
                                 Ok(()) => {
                                     ops_count.fetch_add(1, Ordering::Relaxed);
                                     debug!("Successfully marked job {} as failed", msg.id);
@@ -198,13 +240,14 @@ impl RedisPersistenceAgent {
             .act_on::<MoveToDeadLetterQueue>(|agent, envelope| {
                 let conn_opt = agent.model.redis_conn.clone();
                 let msg = envelope.message().clone();
+                let threshold = agent.model.compress_threshold_bytes;
                 let ops_count = agent.model.operations_count.clone();
 
                 // Spawn as tokio task to satisfy Sync bound
                 Box::pin(async move {
                     tokio::spawn(async move {
                         if let Some(mut conn) = conn_opt {
-                            match move_to_dlq_impl(&mut conn, msg.id, &msg.job, &msg.error).await {
+                            match move_to_dlq_in_redis(&mut conn, msg.id, &msg.job, &msg.error, threshold).await {
                                 Ok(()) => {
                                     ops_count.fetch_add(1, Ordering::Relaxed);
                                     warn!("Moved job {} to DLQ: {}", msg.id, msg.error);
@@ -216,120 +259,104 @@ impl RedisPersistenceAgent {
                         }
                     });
                 })
-            });
-
-        Ok(builder.start().await)
-    }
-}
-
-/// Implementation function for persisting a job to Redis.
-async fn persist_job_impl(
-    redis: &mut redis::aio::MultiplexedConnection,
-    job: &QueuedJob,
-) -> Result<(), redis::RedisError> {
-    let key = format!("job:{}", job.id);
-    let json = serde_json::to_string(job).map_err(|e| {
-        redis::RedisError::from((
-            redis::ErrorKind::TypeError,
-            "serialization error",
-            e.to_string(),
-        ))
-    })?;
-
-    // Store job data with 7 day expiry
-    let _: () = redis.set_ex(&key, json, 604_800).await?;
-
-    // Add to pending queue
-    let _: usize = redis.lpush("queue:pending", job.id.to_string()).await?;
-
-    Ok(())
-}
-
-/// Implementation function for marking job as completed in Redis.
-async fn mark_completed_impl(
-    redis: &mut redis::aio::MultiplexedConnection,
-    id: JobId,
-    execution_time_ms: u64,
-) -> Result<(), redis::RedisError> {
-    let key = format!("job:{id}");
-
-    // Update status
-    let status = JobStatus::Completed {
-        completed_at: chrono::Utc::now(),
-    };
-    let status_json = serde_json::to_string(&status).map_err(|e| {
-        redis::RedisError::from((
-            redis::ErrorKind::TypeError,
-            "serialization error",
-            e.to_string(),
-        ))
-    })?;
-
-    let _: () = redis.hset(&key, "status", status_json).await?;
-    let _: () = redis.hset(&key, "execution_time_ms", execution_time_ms).await?;
-
-    // Remove from pending, add to completed
-    let _: usize = redis.lrem("queue:pending", 1, id.to_string()).await?;
-    let _: usize = redis.lpush("queue:completed", id.to_string()).await?;
-
-    Ok(())
-}
+            })
 
-/// Implementation function for marking job as failed in Redis.
-async fn mark_failed_impl(
-    redis: &mut redis::aio::MultiplexedConnection,
-    id: JobId,
-    error: &str,
-    attempt: u32,
-) -> Result<(), redis::RedisError> {
-    let key = format!("job:{id}");
+            // Load a persisted job (web handler pattern with oneshot channel)
+            .act_on::<LoadJobRequest>(|agent, envelope| {
+                let conn_opt = agent.model.redis_conn.clone();
+                let msg = envelope.message().clone();
 
-    // Update status
-    let status = JobStatus::Failed {
-        failed_at: chrono::Utc::now(),
-        attempts: attempt,
-        error: error.to_string(),
-    };
-    let status_json = serde_json::to_string(&status).map_err(|e| {
-        redis::RedisError::from((
-            redis::ErrorKind::TypeError,
-            "serialization error",
-            e.to_string(),
-        ))
-    })?;
+                // Spawn as tokio task to satisfy Sync bound
+                Box::pin(async move {
+                    tokio::spawn(async move {
+                        let job = match conn_opt {
+                            Some(mut conn) => load_job_from_redis(&mut conn, msg.id)
+                                .await
+                                .unwrap_or_else(|e| {
+                                    error!("Failed to load job {}: {:?}", msg.id, e);
+                                    None
+                                }),
+                            None => None,
+                        };
+                        let mut guard = msg.response_tx.lock().await;
+                        if let Some(tx) = guard.take() {
+                            let _ = tx.send(job);
+                        }
+                    });
+                })
+            })
 
-    let _: () = redis.hset(&key, "status", status_json).await?;
-    let _: () = redis.hset(&key, "attempts", attempt).await?;
+            // Save a scheduled job definition (fire-and-forget)
+            .act_on::<PersistSchedule>(|agent, envelope| {
+                let conn_opt = agent.model.redis_conn.clone();
+                let entry = envelope.message().entry.clone();
+                let ops_count = agent.model.operations_count.clone();
 
-    Ok(())
-}
+                // Spawn as tokio task to satisfy Sync bound
+                Box::pin(async move {
+                    tokio::spawn(async move {
+                        if let Some(mut conn) = conn_opt {
+                            match persist_schedule_to_redis(&mut conn, &entry).await {
+                                Ok(()) => {
+                                    ops_count.fetch_add(1, Ordering::Relaxed);
+                                }
+                                Err(e) => {
+                                    error!("Failed to persist scheduled job {}: {:?}", entry.id, e);
+                                }
+                            }
+                        }
+                    });
+                })
+            })
 
-/// Implementation function for moving job to dead letter queue.
-async fn move_to_dlq_impl(
-    redis: &mut redis::aio::MultiplexedConnection,
-    id: JobId,
-    job: &QueuedJob,
-    error: &str,
-) -> Result<(), redis::RedisError> {
-    let dlq_key = format!("dlq:{id}");
-    let json = serde_json::to_string(job).map_err(|e| {
-        redis::RedisError::from((
-            redis::ErrorKind::TypeError,
-            "serialization error",
-            e.to_string(),
-        ))
-    })?;
+            // Delete a scheduled job definition (fire-and-forget)
+            .act_on::<RemoveSchedule>(|agent, envelope| {
+                let conn_opt = agent.model.redis_conn.clone();
+                let id = envelope.message().id;
+                let ops_count = agent.model.operations_count.clone();
 
-    // Store in DLQ with permanent retention
-    let _: () = redis.hset(&dlq_key, "job", json).await?;
-    let _: () = redis.hset(&dlq_key, "error", error).await?;
-    let _: () = redis.hset(&dlq_key, "moved_at", chrono::Utc::now().to_rfc3339()).await?;
+                // Spawn as tokio task to satisfy Sync bound
+                Box::pin(async move {
+                    tokio::spawn(async move {
+                        if let Some(mut conn) = conn_opt {
+                            match remove_schedule_from_redis(&mut conn, id).await {
+                                Ok(()) => {
+                                    ops_count.fetch_add(1, Ordering::Relaxed);
+                                }
+                                Err(e) => {
+                                    error!("Failed to remove scheduled job {}: {:?}", id, e);
+                                }
+                            }
+                        }
+                    });
+                })
+            })
 
-    // Add to DLQ list
-    let _: usize = redis.lpush("queue:dlq", id.to_string()).await?;
+            // Load saved scheduled jobs (web handler pattern with oneshot channel)
+            .act_on::<LoadSchedulesRequest>(|agent, envelope| {
+                let conn_opt = agent.model.redis_conn.clone();
+                let response_tx = envelope.message().response_tx.clone();
 
-    // Remove from pending
-    let _: usize = redis.lrem("queue:pending", 1, id.to_string()).await?;
+                // Spawn as tokio task to satisfy Sync bound
+                Box::pin(async move {
+                    tokio::spawn(async move {
+                        let entries = match conn_opt {
+                            Some(mut conn) => load_schedules_from_redis(&mut conn)
+                                .await
+                                .unwrap_or_else(|e| {
+                                    error!("Failed to load scheduled jobs: {:?}", e);
+                                    Vec::new()
+                                }),
+                            None => Vec::new(),
+                        };
+                        let mut guard = response_tx.lock().await;
+                        if let Some(tx) = guard.take() {
+                            let _ = tx.send(entries);
+                        }
+                    });
+                })
+            });
 
-    Ok(())
+        Ok(builder.start().await)
+    }
 }
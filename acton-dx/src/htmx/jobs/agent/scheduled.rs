@@ -1,15 +1,26 @@
 //! Scheduled job management agent.
-
-use super::messages::EnqueueJob;
-use crate::htmx::jobs::{JobError, JobId, JobSchedule};
+//!
+//! With Redis persistence ([`ScheduledJobAgent::spawn_with_persistence`]),
+//! schedule definitions are saved in the `schedules` hash and restored at
+//! startup, so recurring jobs survive restarts. Registering a schedule
+//! identical to an existing one (same job type, payload, and schedule)
+//! returns the existing ID, which keeps boot-time registration idempotent.
+
+use super::messages::{EnqueueJob, GetJobStatusRequest, ResponseChannel};
+#[cfg(feature = "redis")]
+use super::persistence::{LoadSchedulesRequest, PersistSchedule, RemoveSchedule};
+use crate::htmx::jobs::{BackoffConfig, JobError, JobId, JobSchedule};
 use acton_reactive::prelude::*;
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::{oneshot, Mutex};
 use tracing::{debug, error, info};
+#[cfg(feature = "redis")]
+use tracing::warn;
 
 /// A scheduled job entry.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +45,12 @@ pub struct ScheduledJobEntry {
     pub execution_count: u64,
     /// Whether this scheduled job is enabled.
     pub enabled: bool,
+    /// Skip a fire while the previous run is still queued or running.
+    #[serde(default)]
+    pub skip_if_running: bool,
+    /// ID of the job enqueued by the most recent fire.
+    #[serde(default)]
+    pub last_job_id: Option<JobId>,
 }
 
 /// Messages for the scheduled job agent.
@@ -76,6 +93,52 @@ pub enum ScheduledJobMessage {
     GetScheduledJobs,
 }
 
+/// Register a scheduled job and wait for the outcome (web handler pattern).
+///
+/// Equivalent to [`ScheduledJobMessage::RegisterScheduledJob`], replying
+/// through a oneshot channel so HTTP handlers can await it. Prefer
+/// [`ActonHtmxState::enqueue_scheduled`](crate::htmx::state::ActonHtmxState::enqueue_scheduled)
+/// in handlers.
+#[derive(Clone, Debug)]
+pub struct RegisterScheduledJobRequest {
+    /// The job to run; its ID is replaced by a fresh one per execution.
+    pub job: EnqueueJob,
+    /// Job schedule.
+    pub schedule: JobSchedule,
+    /// Skip a fire while the previous run is still queued or running.
+    pub skip_if_running: bool,
+    /// Response channel with the scheduled job ID or the rejection.
+    pub response_tx: ResponseChannel<Result<JobId, JobError>>,
+}
+
+impl RegisterScheduledJobRequest {
+    /// Create a new registration request with response channel.
+    ///
+    /// Returns a tuple of (request, receiver) where the request should be
+    /// sent to the agent and the receiver awaited for the response.
+    #[must_use]
+    pub fn new(
+        job: EnqueueJob,
+        schedule: JobSchedule,
+    ) -> (Self, oneshot::Receiver<Result<JobId, JobError>>) {
+        let (tx, rx) = oneshot::channel();
+        let request = Self {
+            job,
+            schedule,
+            skip_if_running: false,
+            response_tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (request, rx)
+    }
+
+    /// Skip overlapping runs of this schedule.
+    #[must_use]
+    pub const fn with_skip_if_running(mut self, skip_if_running: bool) -> Self {
+        self.skip_if_running = skip_if_running;
+        self
+    }
+}
+
 /// Response messages from scheduled job agent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ScheduledJobResponse {
@@ -85,6 +148,12 @@ pub enum ScheduledJobResponse {
         id: JobId,
     },
 
+    /// Scheduled job was rejected because its schedule is invalid.
+    ScheduleRejected {
+        /// Why the schedule was rejected.
+        reason: String,
+    },
+
     /// Scheduled job was unregistered.
     JobUnregistered,
 
@@ -108,6 +177,9 @@ pub struct ScheduledJobAgent {
     scheduled_jobs: Arc<RwLock<HashMap<JobId, ScheduledJobEntry>>>,
     /// Handle to the job queue agent.
     job_agent_handle: Option<AgentHandle>,
+    /// Handle to Redis persistence agent (optional, for persistence).
+    #[cfg(feature = "redis")]
+    redis_persistence: Option<AgentHandle>,
 }
 
 impl Default for ScheduledJobAgent {
@@ -123,6 +195,8 @@ impl ScheduledJobAgent {
         Self {
             scheduled_jobs: Arc::new(RwLock::new(HashMap::new())),
             job_agent_handle: None,
+            #[cfg(feature = "redis")]
+            redis_persistence: None,
         }
     }
 
@@ -135,6 +209,49 @@ impl ScheduledJobAgent {
         runtime: &mut AgentRuntime,
         job_agent_handle: AgentHandle,
     ) -> anyhow::Result<AgentHandle> {
+        let builder = Self::builder(runtime, job_agent_handle).await?;
+        Self::configure_handlers(builder).await
+    }
+
+    /// Spawn the scheduled job agent with Redis persistence.
+    ///
+    /// Restores the schedules saved by a previous run before accepting
+    /// messages, and saves every later change.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if agent initialization fails. Failing to restore
+    /// schedules is logged, not returned.
+    #[cfg(feature = "redis")]
+    pub async fn spawn_with_persistence(
+        runtime: &mut AgentRuntime,
+        job_agent_handle: AgentHandle,
+        redis_persistence: AgentHandle,
+    ) -> anyhow::Result<AgentHandle> {
+        let mut builder = Self::builder(runtime, job_agent_handle).await?;
+
+        let (request, rx) = LoadSchedulesRequest::new();
+        redis_persistence.send(request).await;
+        if let Ok(Ok(entries)) = tokio::time::timeout(Duration::from_secs(5), rx).await {
+            info!("Restored {} scheduled jobs from Redis", entries.len());
+            builder
+                .model
+                .scheduled_jobs
+                .write()
+                .extend(entries.into_iter().map(|entry| (entry.id, entry)));
+        } else {
+            warn!("Could not restore scheduled jobs from Redis");
+        }
+
+        builder.model.redis_persistence = Some(redis_persistence);
+        Self::configure_handlers(builder).await
+    }
+
+    /// Create the agent builder with the job agent handle set.
+    async fn builder(
+        runtime: &mut AgentRuntime,
+        job_agent_handle: AgentHandle,
+    ) -> anyhow::Result<ManagedAgent<Idle, Self>> {
         let agent_config =
             AgentConfig::new(Ern::with_root("scheduled_job_manager")?, None, None)?;
         let mut builder = runtime
@@ -143,7 +260,14 @@ impl ScheduledJobAgent {
 
         // Set the job agent handle
         builder.model.job_agent_handle = Some(job_agent_handle);
+        Ok(builder)
+    }
 
+    /// Configure all message handlers for the scheduled job agent.
+    #[allow(clippy::too_many_lines)]
+    async fn configure_handlers(
+        mut builder: ManagedAgent<Idle, Self>,
+    ) -> anyhow::Result<AgentHandle> {
         // Configure message handlers
         builder.mutate_on::<ScheduledJobMessage>(|agent, envelope| {
             let msg = envelope.message().clone();
@@ -158,37 +282,49 @@ impl ScheduledJobAgent {
                     max_retries,
                     timeout,
                 } => {
-                    let id = JobId::new();
-                    let next_execution = schedule
-                        .next_execution(Utc::now())
-                        .unwrap_or_else(Utc::now);
-
-                    let entry = ScheduledJobEntry {
-                        id,
+                    let job = EnqueueJob {
+                        id: JobId::new(),
                         job_type,
                         payload,
-                        schedule,
                         priority,
                         max_retries,
+                        backoff: BackoffConfig::default(),
                         timeout,
-                        next_execution,
-                        execution_count: 0,
-                        enabled: true,
+                        dedup_key: None,
+                    };
+                    let result = agent.model.register(job, schedule, false);
+                    #[cfg(feature = "redis")]
+                    let (redis, entries) = (
+                        agent.model.redis_persistence.clone(),
+                        agent.model.entries(result.as_ref().ok()),
+                    );
+
+                    let response = match result {
+                        Ok(id) => ScheduledJobResponse::JobRegistered { id },
+                        Err(e) => ScheduledJobResponse::ScheduleRejected {
+                            reason: e.to_string(),
+                        },
                     };
-
-                    agent.model.scheduled_jobs.write().insert(id, entry);
-                    info!("Registered scheduled job: {}", id);
 
                     AgentReply::from_async(async move {
-                        let response = ScheduledJobResponse::JobRegistered { id };
+                        #[cfg(feature = "redis")]
+                        Self::persist(redis, entries).await;
+
                         let _: () = reply_envelope.send(response).await;
                     })
                 }
                 ScheduledJobMessage::UnregisterScheduledJob { id } => {
                     agent.model.scheduled_jobs.write().remove(&id);
                     info!("Unregistered scheduled job: {}", id);
+                    #[cfg(feature = "redis")]
+                    let redis = agent.model.redis_persistence.clone();
 
                     AgentReply::from_async(async move {
+                        #[cfg(feature = "redis")]
+                        if let Some(redis) = redis {
+                            redis.send(RemoveSchedule { id }).await;
+                        }
+
                         let response = ScheduledJobResponse::JobUnregistered;
                         let _: () = reply_envelope.send(response).await;
                     })
@@ -198,8 +334,16 @@ impl ScheduledJobAgent {
                         entry.enabled = enabled;
                         info!("Set scheduled job {} enabled={}", id, enabled);
                     }
+                    #[cfg(feature = "redis")]
+                    let (redis, entries) = (
+                        agent.model.redis_persistence.clone(),
+                        agent.model.entries(Some(&id)),
+                    );
 
                     AgentReply::from_async(async move {
+                        #[cfg(feature = "redis")]
+                        Self::persist(redis, entries).await;
+
                         let response = ScheduledJobResponse::EnabledUpdated;
                         let _: () = reply_envelope.send(response).await;
                     })
@@ -208,10 +352,18 @@ impl ScheduledJobAgent {
                     // Clone what we need for processing
                     let scheduled_jobs = agent.model.scheduled_jobs.clone();
                     let job_handle = agent.model.job_agent_handle.clone();
+                    #[cfg(feature = "redis")]
+                    let redis = agent.model.redis_persistence.clone();
 
                     // Process in async block
                     AgentReply::from_async(async move {
-                        Self::process_scheduled_jobs_async(scheduled_jobs, job_handle).await;
+                        let updated =
+                            Self::process_scheduled_jobs_async(scheduled_jobs, job_handle).await;
+
+                        #[cfg(feature = "redis")]
+                        Self::persist(redis, updated).await;
+                        #[cfg(not(feature = "redis"))]
+                        drop(updated);
                     })
                 }
                 ScheduledJobMessage::GetScheduledJobs => {
@@ -231,69 +383,207 @@ impl ScheduledJobAgent {
             }
         });
 
+        builder.mutate_on::<RegisterScheduledJobRequest>(|agent, envelope| {
+            let msg = envelope.message().clone();
+            let result = agent.model.register(msg.job, msg.schedule, msg.skip_if_running);
+            #[cfg(feature = "redis")]
+            let (redis, entries) = (
+                agent.model.redis_persistence.clone(),
+                agent.model.entries(result.as_ref().ok()),
+            );
+
+            let response_tx = msg.response_tx;
+            AgentReply::from_async(async move {
+                #[cfg(feature = "redis")]
+                Self::persist(redis, entries).await;
+
+                let mut guard = response_tx.lock().await;
+                if let Some(tx) = guard.take() {
+                    let _ = tx.send(result);
+                }
+            })
+        });
+
         Ok(builder.start().await)
     }
 
+    /// Store a scheduled job, computing its first execution time.
+    ///
+    /// Returns the existing ID, updating its settings, when an identical
+    /// schedule (same job type, payload, and schedule) is already stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JobError::InvalidSchedule`] if the schedule has no next run.
+    fn register(
+        &self,
+        job: EnqueueJob,
+        schedule: JobSchedule,
+        skip_if_running: bool,
+    ) -> Result<JobId, JobError> {
+        let next_execution = schedule.next_run(Utc::now()).inspect_err(|e| {
+            error!("Rejected scheduled job {}: {}", job.job_type, e);
+        })?;
+
+        let mut jobs = self.scheduled_jobs.write();
+        let description = schedule.description();
+        if let Some(entry) = jobs.values_mut().find(|entry| {
+            entry.job_type == job.job_type
+                && entry.payload == job.payload
+                && entry.schedule.description() == description
+        }) {
+            entry.priority = job.priority;
+            entry.max_retries = job.max_retries;
+            entry.timeout = job.timeout;
+            entry.skip_if_running = skip_if_running;
+            info!("Scheduled job {} is already registered", entry.id);
+            return Ok(entry.id);
+        }
+
+        let id = JobId::new();
+        let entry = ScheduledJobEntry {
+            id,
+            job_type: job.job_type,
+            payload: job.payload,
+            schedule,
+            priority: job.priority,
+            max_retries: job.max_retries,
+            timeout: job.timeout,
+            next_execution,
+            execution_count: 0,
+            enabled: true,
+            skip_if_running,
+            last_job_id: None,
+        };
+
+        jobs.insert(id, entry);
+        drop(jobs);
+        info!("Registered scheduled job: {}", id);
+        Ok(id)
+    }
+
+    /// Snapshot the scheduled job with the given ID, if any.
+    #[cfg(feature = "redis")]
+    fn entries(&self, id: Option<&JobId>) -> Vec<ScheduledJobEntry> {
+        let jobs = self.scheduled_jobs.read();
+        id.and_then(|id| jobs.get(id)).cloned().into_iter().collect()
+    }
+
+    /// Save scheduled jobs to Redis if enabled (fire-and-forget).
+    #[cfg(feature = "redis")]
+    async fn persist(redis_handle: Option<AgentHandle>, entries: Vec<ScheduledJobEntry>) {
+        if let Some(redis) = redis_handle {
+            for entry in entries {
+                redis.send(PersistSchedule { entry }).await;
+            }
+        }
+    }
+
     /// Process all scheduled jobs and enqueue those that are ready (async).
+    ///
+    /// Returns the scheduled jobs that fired or were skipped, with their
+    /// updated execution state.
     #[allow(clippy::cognitive_complexity)]
     async fn process_scheduled_jobs_async(
         scheduled_jobs: Arc<RwLock<HashMap<JobId, ScheduledJobEntry>>>,
         job_handle: Option<AgentHandle>,
-    ) {
+    ) -> Vec<ScheduledJobEntry> {
+        let Some(job_agent) = job_handle else {
+            error!("Job agent handle not set - cannot enqueue scheduled jobs");
+            return Vec::new();
+        };
         let now = Utc::now();
-        let mut jobs_to_enqueue = Vec::new();
 
         // Find jobs that need to be executed
+        let mut due = Vec::new();
         {
             let mut jobs = scheduled_jobs.write();
             for entry in jobs.values_mut() {
-                if !entry.enabled {
+                if !entry.enabled || entry.next_execution > now {
                     continue;
                 }
 
-                if entry.next_execution <= now {
-                    // Check if schedule allows more executions
-                    if !entry.schedule.has_more_executions(entry.execution_count) {
-                        debug!("Scheduled job {} has no more executions", entry.id);
-                        entry.enabled = false;
-                        continue;
-                    }
+                // Check if schedule allows more executions
+                if !entry.schedule.has_more_executions(entry.execution_count) {
+                    debug!("Scheduled job {} has no more executions", entry.id);
+                    entry.enabled = false;
+                    continue;
+                }
 
-                    jobs_to_enqueue.push(entry.clone());
+                let previous_run = entry.last_job_id.filter(|_| entry.skip_if_running);
+                due.push((entry.id, previous_run));
+            }
+        }
 
-                    // Update execution count and next execution time
-                    entry.execution_count += 1;
-                    if let Some(next) = entry.schedule.next_execution(now) {
-                        entry.next_execution = next;
-                    } else {
-                        // No more executions
-                        entry.enabled = false;
-                    }
+        // Skip schedules whose previous run is still queued or running
+        let mut skipped = HashSet::new();
+        for (id, previous_run) in &due {
+            if let Some(previous_run) = previous_run {
+                if Self::is_active(&job_agent, *previous_run).await {
+                    debug!("Skipping scheduled job {}: run {} still active", id, previous_run);
+                    skipped.insert(*id);
                 }
             }
         }
 
-        // Enqueue jobs
-        if let Some(job_agent) = job_handle {
-            for entry in jobs_to_enqueue {
-                debug!("Enqueueing scheduled job: {}", entry.id);
-
-                let enqueue_msg = EnqueueJob {
-                    id: JobId::new(), // New ID for each execution
-                    job_type: entry.job_type.clone(),
-                    payload: entry.payload.clone(),
-                    priority: entry.priority,
-                    max_retries: entry.max_retries,
-                    timeout: entry.timeout,
+        // Update execution state and build the jobs to enqueue
+        let mut jobs_to_enqueue = Vec::new();
+        let mut updated = Vec::new();
+        {
+            let mut jobs = scheduled_jobs.write();
+            for (id, _) in due {
+                let Some(entry) = jobs.get_mut(&id) else {
+                    continue;
                 };
 
-                // Send message to job agent using handle
-                job_agent.send(enqueue_msg).await;
-                debug!("Successfully enqueued scheduled job");
+                if !skipped.contains(&id) {
+                    let job_id = JobId::new(); // New ID for each execution
+                    entry.execution_count += 1;
+                    entry.last_job_id = Some(job_id);
+                    jobs_to_enqueue.push(EnqueueJob {
+                        id: job_id,
+                        job_type: entry.job_type.clone(),
+                        payload: entry.payload.clone(),
+                        priority: entry.priority,
+                        max_retries: entry.max_retries,
+                        backoff: BackoffConfig::default(),
+                        timeout: entry.timeout,
+                        dedup_key: None,
+                    });
+                }
+
+                if let Some(next) = entry.schedule.next_execution(now) {
+                    entry.next_execution = next;
+                } else {
+                    // No more executions
+                    entry.enabled = false;
+                }
+                updated.push(entry.clone());
             }
-        } else {
-            error!("Job agent handle not set - cannot enqueue scheduled jobs");
+            drop(jobs);
+        }
+
+        // Enqueue jobs
+        for enqueue_msg in jobs_to_enqueue {
+            debug!("Enqueueing scheduled job: {}", enqueue_msg.id);
+
+            // Send message to job agent using handle
+            job_agent.send(enqueue_msg).await;
+            debug!("Successfully enqueued scheduled job");
         }
+
+        updated
+    }
+
+    /// Whether a job is still queued or running in the job agent.
+    async fn is_active(job_agent: &AgentHandle, id: JobId) -> bool {
+        let (request, rx) = GetJobStatusRequest::new(id);
+        job_agent.send(request).await;
+
+        matches!(
+            tokio::time::timeout(Duration::from_millis(100), rx).await,
+            Ok(Ok(Some(status))) if !status.is_terminal()
+        )
     }
 }
 
@@ -336,6 +626,8 @@ mod tests {
             next_execution: Utc::now(),
             execution_count: 0,
             enabled: true,
+            skip_if_running: false,
+            last_job_id: None,
         };
 
         assert_eq!(entry.job_type, "TestJob");
@@ -356,6 +648,8 @@ mod tests {
             next_execution: Utc::now(),
             execution_count: 5,
             enabled: true,
+            skip_if_running: true,
+            last_job_id: Some(JobId::new()),
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -363,5 +657,86 @@ mod tests {
 
         assert_eq!(entry.job_type, deserialized.job_type);
         assert_eq!(entry.execution_count, deserialized.execution_count);
+        assert_eq!(entry.last_job_id, deserialized.last_job_id);
+        assert!(deserialized.skip_if_running);
+    }
+
+    fn tick_job() -> EnqueueJob {
+        EnqueueJob {
+            id: JobId::new(),
+            job_type: "CleanupJob".to_string(),
+            payload: Vec::new(),
+            priority: 0,
+            max_retries: 0,
+            backoff: BackoffConfig::default(),
+            timeout: Duration::from_secs(30),
+            dedup_key: None,
+        }
+    }
+
+    async fn due_now(agent: &ScheduledJobAgent, id: JobId) {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        agent.scheduled_jobs.write().get_mut(&id).unwrap().next_execution = Utc::now();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_skip_if_running_waits_for_previous_run() {
+        use super::super::{JobAgent, JobFinished};
+
+        let mut runtime = ActonApp::launch();
+        let job_agent = JobAgent::spawn(&mut runtime).await.unwrap();
+        let agent = ScheduledJobAgent::new();
+        let schedule = JobSchedule::every(Duration::from_secs(60));
+        let id = agent.register(tick_job(), schedule, true).unwrap();
+
+        due_now(&agent, id).await;
+        let fired = ScheduledJobAgent::process_scheduled_jobs_async(
+            agent.scheduled_jobs.clone(),
+            Some(job_agent.clone()),
+        )
+        .await;
+        let first_run = fired[0].last_job_id.unwrap();
+        assert_eq!(fired[0].execution_count, 1);
+
+        // Previous run still queued: skipped, but the schedule advances
+        due_now(&agent, id).await;
+        let skipped = ScheduledJobAgent::process_scheduled_jobs_async(
+            agent.scheduled_jobs.clone(),
+            Some(job_agent.clone()),
+        )
+        .await;
+        assert_eq!(skipped[0].execution_count, 1);
+        assert_eq!(skipped[0].last_job_id, Some(first_run));
+        assert!(skipped[0].next_execution > Utc::now());
+
+        job_agent
+            .send(JobFinished {
+                id: first_run,
+                success: true,
+                error: None,
+            })
+            .await;
+        due_now(&agent, id).await;
+        let fired = ScheduledJobAgent::process_scheduled_jobs_async(
+            agent.scheduled_jobs.clone(),
+            Some(job_agent),
+        )
+        .await;
+        assert_eq!(fired[0].execution_count, 2);
+        assert_ne!(fired[0].last_job_id, Some(first_run));
+    }
+
+    #[test]
+    fn test_register_identical_schedule_returns_existing_id() {
+        let agent = ScheduledJobAgent::new();
+        let schedule = JobSchedule::every(Duration::from_secs(60));
+
+        let id = agent.register(tick_job(), schedule.clone(), false).unwrap();
+        assert_eq!(agent.register(tick_job(), schedule, true).unwrap(), id);
+        assert!(agent.scheduled_jobs.read()[&id].skip_if_running);
+
+        let hourly = JobSchedule::every(Duration::from_secs(3600));
+        assert_ne!(agent.register(tick_job(), hourly, false).unwrap(), id);
+        assert_eq!(agent.scheduled_jobs.read().len(), 2);
     }
 }
@@ -0,0 +1,170 @@
+//! Retry backoff policies.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Randomization applied to an exponential backoff delay.
+///
+/// Jitter spreads retries of jobs that failed together (e.g. during an
+/// outage) so they do not hammer the recovering service in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum JitterKind {
+    /// Use the exponential delay as-is.
+    None,
+    /// Pick a delay uniformly from `[0, delay]`.
+    #[default]
+    Full,
+    /// Keep half the delay and randomize the other half: `[delay / 2, delay]`.
+    Equal,
+}
+
+/// Exponential backoff policy for job retries.
+///
+/// The delay window for retry `attempt` (0 = first retry) is
+/// `base * 2^attempt`, capped at `max`. Jitter is then applied within
+/// that window, so a computed delay never exceeds `max`.
+///
+/// # Example
+///
+/// ```rust
+/// use acton_dx::htmx::jobs::{BackoffConfig, JitterKind};
+/// use std::time::Duration;
+///
+/// let backoff = BackoffConfig::new(Duration::from_millis(500), Duration::from_secs(60))
+///     .with_jitter(JitterKind::Equal);
+///
+/// assert!(backoff.delay(3) <= Duration::from_secs(4));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackoffConfig {
+    /// Delay window before the first retry.
+    pub base: Duration,
+    /// Upper bound for any computed delay.
+    pub max: Duration,
+    /// Randomization applied within the delay window.
+    pub jitter: JitterKind,
+}
+
+impl Default for BackoffConfig {
+    /// 1 second base, 5 minute cap, full jitter.
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(1),
+            max: Duration::from_secs(300),
+            jitter: JitterKind::Full,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// Create a backoff policy with full jitter.
+    #[must_use]
+    pub const fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            jitter: JitterKind::Full,
+        }
+    }
+
+    /// Set the jitter applied within the delay window.
+    #[must_use]
+    pub const fn with_jitter(mut self, jitter: JitterKind) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Delay window for retry `attempt`: `base * 2^attempt`, capped at `max`.
+    #[must_use]
+    pub fn window(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt);
+        self.base.saturating_mul(factor).min(self.max)
+    }
+
+    /// Delay to wait before retry `attempt`, with jitter applied.
+    ///
+    /// Always within `[0, max]`.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let window = self.window(attempt);
+        match self.jitter {
+            JitterKind::None => window,
+            JitterKind::Full => random_up_to(window),
+            JitterKind::Equal => {
+                let half = window / 2;
+                half + random_up_to(window.saturating_sub(half))
+            }
+        }
+    }
+}
+
+/// Uniformly random duration in `[0, upper]`, at nanosecond resolution.
+fn random_up_to(upper: Duration) -> Duration {
+    let nanos = u64::try_from(upper.as_nanos()).unwrap_or(u64::MAX);
+    Duration::from_nanos(rand::rng().random_range(0..=nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 1_000;
+
+    #[test]
+    fn test_window_grows_exponentially_and_caps() {
+        let backoff = BackoffConfig::new(Duration::from_millis(100), Duration::from_secs(1));
+
+        assert_eq!(backoff.window(0), Duration::from_millis(100));
+        assert_eq!(backoff.window(1), Duration::from_millis(200));
+        assert_eq!(backoff.window(3), Duration::from_millis(800));
+        assert_eq!(backoff.window(4), Duration::from_secs(1));
+        assert_eq!(backoff.window(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_no_jitter_is_deterministic() {
+        let backoff = BackoffConfig::new(Duration::from_millis(100), Duration::from_secs(1))
+            .with_jitter(JitterKind::None);
+
+        assert_eq!(backoff.delay(2), Duration::from_millis(400));
+        assert_eq!(backoff.delay(10), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_full_jitter_stays_within_window_and_randomizes() {
+        let backoff = BackoffConfig::new(Duration::from_millis(100), Duration::from_secs(2));
+
+        for attempt in 0..8 {
+            let window = backoff.window(attempt);
+            let delays: Vec<Duration> = (0..SAMPLES).map(|_| backoff.delay(attempt)).collect();
+
+            assert!(delays.iter().all(|d| *d <= window && *d <= backoff.max));
+            assert!(delays.iter().any(|d| *d < window / 2));
+            assert!(delays.iter().any(|d| *d > window / 2));
+        }
+    }
+
+    #[test]
+    fn test_equal_jitter_keeps_lower_half() {
+        let backoff = BackoffConfig::new(Duration::from_millis(100), Duration::from_secs(2))
+            .with_jitter(JitterKind::Equal);
+
+        for attempt in 0..8 {
+            let window = backoff.window(attempt);
+            for _ in 0..SAMPLES {
+                let delay = backoff.delay(attempt);
+                assert!(delay >= window / 2 && delay <= window);
+            }
+        }
+    }
+
+    #[test]
+    fn test_base_above_max_is_capped() {
+        let backoff = BackoffConfig::new(Duration::from_secs(10), Duration::from_secs(1));
+
+        for _ in 0..SAMPLES {
+            assert!(backoff.delay(0) <= Duration::from_secs(1));
+        }
+    }
+}
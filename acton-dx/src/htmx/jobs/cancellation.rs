@@ -263,6 +263,7 @@ impl JobShutdownCoordinator {
 
         // Signal global shutdown
         self.shutdown_token.cancel();
+        let in_flight = self.cancellation_manager.active_count();
 
         // Cancel all running jobs
         self.cancellation_manager.cancel_all();
@@ -275,11 +276,53 @@ impl JobShutdownCoordinator {
 
         if graceful {
             info!("Job system shutdown completed gracefully");
-            ShutdownResult::Graceful
         } else {
             warn!("Job system forced shutdown after timeout");
+        }
+        self.result(in_flight)
+    }
+
+    /// Drain running jobs before shutting down.
+    ///
+    /// Unlike [`shutdown`](Self::shutdown), running jobs are left to finish
+    /// undisturbed:
+    /// 1. Signal global shutdown (prevent new jobs)
+    /// 2. Wait for running jobs to complete (up to timeout)
+    /// 3. Cancel the jobs still running after the timeout
+    ///
+    /// # Returns
+    ///
+    /// `ShutdownResult` with the number of jobs that completed and the number
+    /// abandoned at the timeout.
+    pub async fn drain(&self, graceful_timeout: Duration) -> ShutdownResult {
+        info!("Draining job system");
+
+        self.shutdown_token.cancel();
+        let in_flight = self.cancellation_manager.active_count();
+
+        let graceful = self
+            .cancellation_manager
+            .wait_for_completion(graceful_timeout)
+            .await;
+
+        if !graceful {
+            warn!("Cancelling jobs still running after drain timeout");
+            self.cancellation_manager.cancel_all();
+        }
+        self.result(in_flight)
+    }
+
+    /// Summarize shutdown given the number of jobs running when it began.
+    fn result(&self, in_flight: usize) -> ShutdownResult {
+        let jobs_remaining = self.cancellation_manager.active_count();
+        let jobs_completed = in_flight.saturating_sub(jobs_remaining);
+
+        if jobs_remaining == 0 {
+            ShutdownResult::Graceful { jobs_completed }
+        } else {
             ShutdownResult::Forced {
-                jobs_remaining: self.cancellation_manager.active_count(),
+                jobs_completed,
+                jobs_remaining,
             }
         }
     }
@@ -316,9 +359,14 @@ impl Default for JobShutdownCoordinator {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ShutdownResult {
     /// All jobs completed gracefully within timeout.
-    Graceful,
+    Graceful {
+        /// Number of running jobs that completed during shutdown.
+        jobs_completed: usize,
+    },
     /// Some jobs did not complete within timeout and were force-stopped.
     Forced {
+        /// Number of running jobs that completed during shutdown.
+        jobs_completed: usize,
         /// Number of jobs that were still running.
         jobs_remaining: usize,
     },
@@ -328,15 +376,25 @@ impl ShutdownResult {
     /// Check if shutdown was graceful.
     #[must_use]
     pub const fn is_graceful(&self) -> bool {
-        matches!(self, Self::Graceful)
+        matches!(self, Self::Graceful { .. })
+    }
+
+    /// Get the number of running jobs that completed during shutdown.
+    #[must_use]
+    pub const fn jobs_completed(&self) -> usize {
+        match self {
+            Self::Graceful { jobs_completed } | Self::Forced { jobs_completed, .. } => {
+                *jobs_completed
+            }
+        }
     }
 
     /// Get the number of jobs that didn't complete (0 if graceful).
     #[must_use]
     pub const fn jobs_remaining(&self) -> usize {
         match self {
-            Self::Graceful => 0,
-            Self::Forced { jobs_remaining } => *jobs_remaining,
+            Self::Graceful { .. } => 0,
+            Self::Forced { jobs_remaining, .. } => *jobs_remaining,
         }
     }
 }
@@ -458,12 +516,17 @@ mod tests {
 
     #[test]
     fn test_shutdown_result() {
-        let graceful = ShutdownResult::Graceful;
+        let graceful = ShutdownResult::Graceful { jobs_completed: 2 };
         assert!(graceful.is_graceful());
+        assert_eq!(graceful.jobs_completed(), 2);
         assert_eq!(graceful.jobs_remaining(), 0);
 
-        let forced = ShutdownResult::Forced { jobs_remaining: 5 };
+        let forced = ShutdownResult::Forced {
+            jobs_completed: 1,
+            jobs_remaining: 5,
+        };
         assert!(!forced.is_graceful());
+        assert_eq!(forced.jobs_completed(), 1);
         assert_eq!(forced.jobs_remaining(), 5);
     }
 
@@ -491,4 +554,49 @@ mod tests {
         let result = coordinator.shutdown(Duration::from_secs(1)).await;
         assert!(result.is_graceful());
     }
+
+    /// Run a job that takes `duration`, registered with the coordinator
+    fn spawn_slow_job(coordinator: &JobShutdownCoordinator, duration: Duration) -> CancellationToken {
+        let job_id = JobId::new();
+        let token = CancellationToken::new();
+        let manager = coordinator.cancellation_manager().clone();
+        manager.register(job_id, token.clone());
+
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            manager.unregister(&job_id);
+        });
+        token
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_slow_job() {
+        let coordinator = JobShutdownCoordinator::new();
+        let token = spawn_slow_job(&coordinator, Duration::from_millis(250));
+
+        let result = coordinator.drain(Duration::from_secs(2)).await;
+
+        assert_eq!(result, ShutdownResult::Graceful { jobs_completed: 1 });
+        assert!(coordinator.shutdown_token().is_cancelled());
+        // The job was allowed to finish rather than being cancelled
+        assert!(!token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_drain_cancels_jobs_after_timeout() {
+        let coordinator = JobShutdownCoordinator::new();
+        spawn_slow_job(&coordinator, Duration::from_millis(50));
+        let stuck = spawn_slow_job(&coordinator, Duration::from_secs(60));
+
+        let result = coordinator.drain(Duration::from_millis(300)).await;
+
+        assert_eq!(
+            result,
+            ShutdownResult::Forced {
+                jobs_completed: 1,
+                jobs_remaining: 1,
+            }
+        );
+        assert!(stuck.is_cancelled());
+    }
 }
@@ -45,9 +45,30 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Progress Reporting
+//!
+//! Job runners attach the job agent with [`JobContext::with_progress`];
+//! long-running jobs then call [`JobContext::report_progress`] and handlers
+//! poll [`ActonHtmxState::get_job_progress`](crate::htmx::state::ActonHtmxState::get_job_progress):
+//!
+//! ```rust,ignore
+//! async fn execute(&self, ctx: &JobContext) -> JobResult<Self::Result> {
+//!     for (i, chunk) in self.rows.chunks(100).enumerate() {
+//!         import(chunk).await?;
+//!         let percent = (i + 1) * 100 / self.rows.len().div_ceil(100);
+//!         ctx.report_progress(percent as u8, format!("Imported {} rows", (i + 1) * 100)).await;
+//!     }
+//!     Ok(())
+//! }
+//! ```
 
+use super::agent::ReportProgress;
+use super::{JobId, JobProgress};
 use crate::htmx::email::EmailSender;
 use crate::htmx::storage::FileStorage;
+use crate::htmx::tenant::Tenant;
+use acton_reactive::prelude::{AgentHandle, AgentHandleInterface};
 use sqlx::PgPool;
 use std::sync::Arc;
 
@@ -78,6 +99,12 @@ pub struct JobContext {
     /// Redis connection pool (optional, for caching and distributed operations)
     #[cfg(feature = "redis")]
     redis_pool: Option<RedisPool>,
+
+    /// Tenant the current job runs on behalf of (multi-tenant deployments)
+    tenant: Option<Tenant>,
+
+    /// Job agent receiving progress reports for the current job
+    progress: Option<(JobId, AgentHandle)>,
 }
 
 impl JobContext {
@@ -92,6 +119,8 @@ impl JobContext {
             file_storage: None,
             #[cfg(feature = "redis")]
             redis_pool: None,
+            tenant: None,
+            progress: None,
         }
     }
 
@@ -124,6 +153,37 @@ impl JobContext {
         self
     }
 
+    /// Set the tenant this job runs on behalf of.
+    ///
+    /// Usually set by [`TenantJob`](crate::htmx::tenant::TenantJob) from the
+    /// tenant captured at enqueue time.
+    #[must_use]
+    pub fn with_tenant(mut self, tenant: Tenant) -> Self {
+        self.tenant = Some(tenant);
+        self
+    }
+
+    /// Send progress reports for `job_id` to the job agent.
+    ///
+    /// Set by job runners before executing a job so that
+    /// [`report_progress`](Self::report_progress) reaches the agent.
+    #[must_use]
+    pub fn with_progress(mut self, job_id: JobId, job_agent: AgentHandle) -> Self {
+        self.progress = Some((job_id, job_agent));
+        self
+    }
+
+    /// Report progress of the running job.
+    ///
+    /// Stores the latest progress in the job agent, where handlers can
+    /// query it. Does nothing when no job agent is attached.
+    pub async fn report_progress(&self, percent: u8, message: impl Into<String>) {
+        if let Some((id, job_agent)) = &self.progress {
+            let progress = JobProgress::new(percent, message);
+            job_agent.send(ReportProgress { id: *id, progress }).await;
+        }
+    }
+
     /// Get the email sender if available.
     #[must_use]
     pub fn email_sender(&self) -> Option<&Arc<dyn EmailSender>> {
@@ -148,6 +208,12 @@ impl JobContext {
     pub const fn redis_pool(&self) -> Option<&RedisPool> {
         self.redis_pool.as_ref()
     }
+
+    /// Get the tenant this job runs on behalf of, if any.
+    #[must_use]
+    pub const fn tenant(&self) -> Option<&Tenant> {
+        self.tenant.as_ref()
+    }
 }
 
 impl Default for JobContext {
@@ -167,6 +233,10 @@ impl std::fmt::Debug for JobContext {
         #[cfg(feature = "redis")]
         debug_struct.field("redis_pool", &self.redis_pool.is_some());
 
+        debug_struct
+            .field("tenant", &self.tenant)
+            .field("progress", &self.progress.is_some());
+
         debug_struct.finish()
     }
 }
@@ -181,6 +251,14 @@ mod tests {
         assert!(ctx.email_sender().is_none());
         assert!(ctx.database_pool().is_none());
         assert!(ctx.file_storage().is_none());
+        assert!(ctx.tenant().is_none());
+    }
+
+    #[test]
+    fn test_job_context_with_tenant() {
+        let tenant = Tenant::new("acme").unwrap();
+        let ctx = JobContext::new().with_tenant(tenant.clone());
+        assert_eq!(ctx.tenant(), Some(&tenant));
     }
 
     #[test]
@@ -1,5 +1,6 @@
 //! Job-related error types.
 
+use super::JobId;
 use thiserror::Error;
 
 /// Result type for job operations.
@@ -41,6 +42,14 @@ pub enum JobError {
     #[error("job agent not available")]
     AgentUnavailable,
 
+    /// A job with the same dedup key is already queued or running.
+    #[error("duplicate of job {0}")]
+    Duplicate(JobId),
+
+    /// Job schedule is invalid (e.g. malformed cron expression).
+    #[error("invalid schedule: {0}")]
+    InvalidSchedule(String),
+
     /// Other error.
     #[error("{0}")]
     Other(String),
@@ -1,6 +1,6 @@
 //! Core job trait and types.
 
-use super::JobResult;
+use super::{BackoffConfig, JobResult};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -106,7 +106,7 @@ pub trait Job: Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static {
     /// # Errors
     ///
     /// Returns an error if the job execution fails. The job will be retried
-    /// according to `max_retries()` with exponential backoff.
+    /// according to `max_retries()` with the delays given by `backoff()`.
     async fn execute(&self, ctx: &super::JobContext) -> JobResult<Self::Result>;
 
     /// Maximum number of retry attempts.
@@ -116,6 +116,13 @@ pub trait Job: Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static {
         3
     }
 
+    /// Backoff policy for delays between retry attempts.
+    ///
+    /// Default: 1 second base, 5 minute cap, full jitter
+    fn backoff(&self) -> BackoffConfig {
+        BackoffConfig::default()
+    }
+
     /// Timeout for job execution.
     ///
     /// If the job takes longer than this duration, it will be cancelled
@@ -136,6 +143,18 @@ pub trait Job: Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static {
         0
     }
 
+    /// Deduplication key for collapsing identical jobs.
+    ///
+    /// While a job with the same key is queued or running, enqueueing another
+    /// one is rejected with [`JobError::Duplicate`](super::JobError::Duplicate)
+    /// carrying the existing job's ID. The key is released when that job
+    /// completes or fails out.
+    ///
+    /// Default: `None` (no deduplication)
+    fn dedup_key(&self) -> Option<String> {
+        None
+    }
+
     /// Job type name for logging and debugging.
     ///
     /// Default: Returns the type name
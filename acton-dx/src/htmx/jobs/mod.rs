@@ -5,7 +5,7 @@
 //! - **Actor-based architecture** using acton-reactive v5
 //! - **In-memory priority queue** with fast synchronous operations (`mutate_on`)
 //! - **Concurrent Redis persistence** using async I/O (`act_on`)
-//! - Automatic retry with exponential backoff
+//! - Automatic retry with exponential backoff and jitter ([`BackoffConfig`])
 //! - Dead letter queue for failed jobs
 //! - Priority-based execution
 //! - Graceful shutdown support
@@ -62,6 +62,7 @@
 //! }
 //! ```
 
+mod backoff;
 mod cancellation;
 mod context;
 mod error;
@@ -71,6 +72,7 @@ mod observability;
 mod schedule;
 mod status;
 
+pub use backoff::{BackoffConfig, JitterKind};
 pub use cancellation::{
     CancellationToken, JobCancellationManager, JobShutdownCoordinator, ShutdownResult,
 };
@@ -81,7 +83,7 @@ pub use observability::{JobExecutionContext, JobPerformanceRecorder, JobQueueObs
 #[cfg(feature = "otel-metrics")]
 pub use observability::JobMetricsCollector;
 pub use schedule::JobSchedule;
-pub use status::JobStatus;
+pub use status::{JobProgress, JobStatus};
 
 // Re-export agent components
 pub mod agent;
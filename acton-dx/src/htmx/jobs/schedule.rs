@@ -3,6 +3,7 @@
 use chrono::{DateTime, Duration, Utc};
 use cron::Schedule as CronSchedule;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::str::FromStr;
 
 use super::JobError;
@@ -10,7 +11,7 @@ use super::JobError;
 /// Job execution schedule.
 ///
 /// Supports three types of scheduling:
-/// - **Cron**: Execute on a cron schedule (e.g., "0 0 0 * * *" for daily at midnight)
+/// - **Cron**: Execute on a cron schedule (e.g., "0 0 * * *" for daily at midnight)
 /// - **Delayed**: Execute once after a delay
 /// - **Recurring**: Execute repeatedly with a fixed interval
 ///
@@ -21,7 +22,7 @@ use super::JobError;
 /// use std::time::Duration;
 ///
 /// // Cron schedule: daily at midnight
-/// let daily = JobSchedule::cron("0 0 0 * * *").unwrap();
+/// let daily = JobSchedule::cron("0 0 * * *").unwrap();
 ///
 /// // Delayed: run once after 1 hour
 /// let delayed = JobSchedule::after(Duration::from_secs(3600));
@@ -34,14 +35,17 @@ use super::JobError;
 pub enum JobSchedule {
     /// Execute on a cron schedule.
     ///
-    /// Uses standard cron syntax with 6 fields:
-    /// - `sec min hour day_of_month month day_of_week`
+    /// Uses standard cron syntax with 5 fields:
+    /// - `min hour day_of_month month day_of_week` (day of week `0`-`7`, `0` and `7` = Sunday)
     ///
     /// Examples:
-    /// - `"0 0 0 * * *"` - Daily at midnight
-    /// - `"0 0 */2 * * *"` - Every 2 hours
-    /// - `"0 0 9 * * 1-5"` - Weekdays at 9 AM
-    /// - `"0 */15 * * * *"` - Every 15 minutes
+    /// - `"0 0 * * *"` - Daily at midnight
+    /// - `"0 */2 * * *"` - Every 2 hours
+    /// - `"0 9 * * 1-5"` - Weekdays at 9 AM
+    /// - `"*/15 * * * *"` - Every 15 minutes
+    ///
+    /// Expressions with a leading seconds field (6 or 7 fields) are passed to
+    /// the `cron` crate unchanged; there, day of week `1` is Sunday.
     Cron {
         /// Cron expression string.
         expression: String,
@@ -74,9 +78,12 @@ pub enum JobSchedule {
 impl JobSchedule {
     /// Create a cron-based schedule.
     ///
+    /// Accepts a standard 5-field expression (`min hour day_of_month month
+    /// day_of_week`) or the `cron` crate's 6/7-field syntax with seconds.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the cron expression is invalid.
+    /// Returns [`JobError::InvalidSchedule`] if the cron expression is invalid.
     ///
     /// # Examples
     ///
@@ -90,8 +97,7 @@ impl JobSchedule {
     /// let schedule = JobSchedule::cron("*/15 * * * *").unwrap();
     /// ```
     pub fn cron(expression: &str) -> Result<Self, JobError> {
-        let schedule = CronSchedule::from_str(expression)
-            .map_err(|e| JobError::Other(format!("Invalid cron expression: {e}")))?;
+        let schedule = parse_cron(expression)?;
 
         Ok(Self::Cron {
             expression: expression.to_string(),
@@ -159,7 +165,8 @@ impl JobSchedule {
     ///
     /// # Returns
     ///
-    /// - For cron schedules: Returns the next scheduled time according to the cron expression
+    /// - For cron schedules: Returns the next scheduled time according to the cron expression,
+    ///   or `None` if the expression is invalid
     /// - For delayed schedules: Returns reference + delay
     /// - For recurring schedules: Returns reference + interval
     ///
@@ -180,12 +187,13 @@ impl JobSchedule {
     pub fn next_execution(&self, from: DateTime<Utc>) -> Option<DateTime<Utc>> {
         match self {
             Self::Cron {
-                expression: _,
+                expression,
                 schedule,
-            } => {
-                let sched = schedule.as_ref()?;
-                sched.after(&from).next()
-            }
+            } => match schedule {
+                Some(sched) => sched.after(&from).next(),
+                // Deserialized schedules carry only the expression
+                None => parse_cron(expression).ok()?.after(&from).next(),
+            },
             Self::Delayed { delay } => {
                 let duration = Duration::from_std(*delay).ok()?;
                 Some(from + duration)
@@ -200,6 +208,25 @@ impl JobSchedule {
         }
     }
 
+    /// Validate the schedule and calculate its next execution time.
+    ///
+    /// Unlike [`next_execution`](Self::next_execution), reports why no
+    /// execution time could be calculated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`JobError::InvalidSchedule`] if the cron expression is
+    /// invalid, never fires again, or the delay/interval is out of range.
+    pub fn next_run(&self, from: DateTime<Utc>) -> Result<DateTime<Utc>, JobError> {
+        if let Self::Cron { expression, .. } = self {
+            parse_cron(expression)?;
+        }
+
+        self.next_execution(from).ok_or_else(|| {
+            JobError::InvalidSchedule(format!("{} has no execution after {from}", self.description()))
+        })
+    }
+
     /// Check if the schedule has more executions remaining.
     ///
     /// For delayed schedules, this returns `false` after the first execution.
@@ -242,6 +269,91 @@ impl JobSchedule {
     }
 }
 
+/// Parse a cron expression into a `cron` crate schedule.
+///
+/// Standard 5-field expressions are converted to the crate's syntax first.
+fn parse_cron(expression: &str) -> Result<CronSchedule, JobError> {
+    let converted = to_cron_crate_syntax(expression)?;
+    CronSchedule::from_str(&converted).map_err(|e| {
+        JobError::InvalidSchedule(format!("invalid cron expression '{expression}': {e}"))
+    })
+}
+
+/// Convert a standard 5-field expression to the `cron` crate's syntax.
+///
+/// Prepends a zero seconds field and shifts numeric days of week from
+/// 0-based (`0` = Sunday) to the crate's 1-based numbering. Expressions that
+/// already have 6 or 7 fields, and `@daily`-style shorthands, are returned
+/// unchanged.
+fn to_cron_crate_syntax(expression: &str) -> Result<String, JobError> {
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    match fields.as_slice() {
+        [minute, hour, day_of_month, month, day_of_week] => {
+            let day_of_week = translate_day_of_week(day_of_week)
+                .map_err(|e| JobError::InvalidSchedule(format!("invalid cron expression '{expression}': {e}")))?;
+            Ok(format!("0 {minute} {hour} {day_of_month} {month} {day_of_week}"))
+        }
+        [shorthand] if shorthand.starts_with('@') => Ok(expression.to_string()),
+        _ if fields.len() == 6 || fields.len() == 7 => Ok(expression.to_string()),
+        _ => Err(JobError::InvalidSchedule(format!(
+            "invalid cron expression '{expression}': expected 5 fields (minute hour day-of-month month day-of-week), found {}",
+            fields.len()
+        ))),
+    }
+}
+
+/// Convert a numeric day-of-week field to an explicit 1-based list.
+///
+/// Named days (`MON-FRI`) and wildcards mean the same in both syntaxes and
+/// are returned unchanged.
+fn translate_day_of_week(field: &str) -> Result<String, String> {
+    if field == "*" || field == "?" || field.chars().any(|c| c.is_ascii_alphabetic()) {
+        return Ok(field.to_string());
+    }
+
+    let mut days = BTreeSet::new();
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid step '{step}' in day of week"))?;
+                (range, Some(step))
+            }
+            None => (item, None),
+        };
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (0, 6),
+            Some((start, end)) => (parse_day_of_week(start)?, parse_day_of_week(end)?),
+            // `n/step` runs from n to the end of the week
+            None if step.is_some() => (parse_day_of_week(range)?, 6),
+            None => {
+                let day = parse_day_of_week(range)?;
+                (day, day)
+            }
+        };
+        if start > end {
+            return Err(format!("day of week range '{range}' is reversed"));
+        }
+
+        days.extend((start..=end).step_by(step.unwrap_or(1)).map(|day| day % 7 + 1));
+    }
+
+    Ok(days.iter().map(ToString::to_string).collect::<Vec<_>>().join(","))
+}
+
+/// Parse a 0-based day of week (`0`-`7`, both `0` and `7` are Sunday)
+fn parse_day_of_week(value: &str) -> Result<u8, String> {
+    value
+        .parse::<u8>()
+        .ok()
+        .filter(|day| *day <= 7)
+        .ok_or_else(|| format!("invalid day of week '{value}' (expected 0-7)"))
+}
+
 /// Serde helper for serializing Duration as milliseconds.
 mod duration_ms {
     use serde::{Deserialize, Deserializer, Serializer};
@@ -366,4 +478,88 @@ mod tests {
             _ => panic!("Expected Recurring schedule"),
         }
     }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_cron_every_five_minutes() {
+        let schedule = JobSchedule::cron("*/5 * * * *").unwrap();
+
+        let next = schedule.next_execution(at("2024-01-01T00:02:30Z")).unwrap();
+        assert_eq!(next, at("2024-01-01T00:05:00Z"));
+        let next = schedule.next_execution(next).unwrap();
+        assert_eq!(next, at("2024-01-01T00:10:00Z"));
+    }
+
+    #[test]
+    fn test_cron_standard_day_of_week() {
+        // 2024-01-03 is a Wednesday
+        let from = at("2024-01-03T12:00:00Z");
+
+        let monday = JobSchedule::cron("0 0 * * 1").unwrap();
+        assert_eq!(monday.next_execution(from).unwrap(), at("2024-01-08T00:00:00Z"));
+
+        for sunday in ["0 0 * * 0", "0 0 * * 7", "0 0 * * SUN"] {
+            let schedule = JobSchedule::cron(sunday).unwrap();
+            assert_eq!(schedule.next_execution(from).unwrap(), at("2024-01-07T00:00:00Z"), "{sunday}");
+        }
+
+        let weekdays = JobSchedule::cron("30 9 * * 1-5").unwrap();
+        assert_eq!(
+            weekdays.next_execution(at("2024-01-06T10:00:00Z")).unwrap(),
+            at("2024-01-08T09:30:00Z")
+        );
+
+        let weekend = JobSchedule::cron("0 0 * * 6,0").unwrap();
+        assert_eq!(weekend.next_execution(from).unwrap(), at("2024-01-06T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_cron_rejects_malformed_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "* * * * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * * * 8",
+            "* * * * 5-1",
+            "*/0 * * * *",
+            "* * * * */0",
+            "a b c d e",
+        ] {
+            let result = JobSchedule::cron(expression);
+            assert!(
+                matches!(result, Err(JobError::InvalidSchedule(_))),
+                "{expression:?} should be rejected"
+            );
+        }
+
+        let message = JobSchedule::cron("* * * *").unwrap_err().to_string();
+        assert!(message.contains("expected 5 fields"), "{message}");
+    }
+
+    #[test]
+    fn test_deserialized_cron_computes_next_execution() {
+        let schedule = JobSchedule::cron("0 0 * * 1").unwrap();
+        let json = serde_json::to_string(&schedule).unwrap();
+        let deserialized: JobSchedule = serde_json::from_str(&json).unwrap();
+
+        let from = at("2024-01-03T12:00:00Z");
+        assert_eq!(deserialized.next_run(from).unwrap(), at("2024-01-08T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_next_run_rejects_invalid_deserialized_cron() {
+        let schedule: JobSchedule =
+            serde_json::from_str(r#"{"type":"cron","expression":"not cron"}"#).unwrap();
+
+        assert!(schedule.next_execution(Utc::now()).is_none());
+        assert!(matches!(
+            schedule.next_run(Utc::now()),
+            Err(JobError::InvalidSchedule(_))
+        ));
+    }
 }
@@ -4,15 +4,19 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Status of a background job.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum JobStatus {
     /// Job is queued and waiting to be executed.
+    #[default]
     Pending,
 
     /// Job is currently being executed.
     Running {
         /// When the job started executing.
         started_at: DateTime<Utc>,
+        /// Latest progress reported by the job, if any.
+        #[serde(default)]
+        progress: Option<JobProgress>,
     },
 
     /// Job completed successfully.
@@ -50,6 +54,36 @@ pub enum JobStatus {
     },
 }
 
+/// Progress reported by a running job.
+///
+/// Jobs report progress with
+/// [`JobContext::report_progress`](super::JobContext::report_progress);
+/// handlers read it with
+/// [`ActonHtmxState::get_job_progress`](crate::htmx::state::ActonHtmxState::get_job_progress).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobProgress {
+    /// Completion percentage (0-100).
+    pub percent: u8,
+    /// Human-readable description of the current step.
+    pub message: String,
+    /// When the progress was reported.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JobProgress {
+    /// Create a progress report timestamped now.
+    ///
+    /// Percentages above 100 are clamped to 100.
+    #[must_use]
+    pub fn new(percent: u8, message: impl Into<String>) -> Self {
+        Self {
+            percent: percent.min(100),
+            message: message.into(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
 impl JobStatus {
     /// Check if the job is in a terminal state (completed, failed, or cancelled).
     #[must_use]
@@ -92,12 +126,6 @@ impl JobStatus {
     }
 }
 
-impl Default for JobStatus {
-    fn default() -> Self {
-        Self::Pending
-    }
-}
-
 impl std::fmt::Display for JobStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name())
@@ -112,7 +140,8 @@ mod tests {
     fn test_status_is_terminal() {
         assert!(!JobStatus::Pending.is_terminal());
         assert!(!JobStatus::Running {
-            started_at: Utc::now()
+            started_at: Utc::now(),
+            progress: None,
         }
         .is_terminal());
         assert!(JobStatus::Completed {
@@ -136,7 +165,8 @@ mod tests {
         assert_eq!(JobStatus::Pending.name(), "pending");
         assert_eq!(
             JobStatus::Running {
-                started_at: Utc::now()
+                started_at: Utc::now(),
+                progress: None,
             }
             .name(),
             "running"
@@ -155,4 +185,11 @@ mod tests {
         let status = JobStatus::Pending;
         assert_eq!(format!("{status}"), "pending");
     }
+
+    #[test]
+    fn test_progress_clamped() {
+        let progress = JobProgress::new(150, "Almost");
+        assert_eq!(progress.percent, 100);
+        assert_eq!(progress.message, "Almost");
+    }
 }
@@ -0,0 +1,333 @@
+//! Request body size limits
+//!
+//! [`BodyLimitLayer`] caps request bodies at `security.max_body_bytes`, with
+//! larger limits for selected routes such as upload endpoints.
+//!
+//! - Requests whose `Content-Length` exceeds the limit are rejected with
+//!   `413 Payload Too Large` before the handler runs, rendered with
+//!   `errors/413.html` for HTML requests and as JSON for API requests
+//! - The limit also replaces axum's `DefaultBodyLimit` for the route, so
+//!   body extractors (`Form`, `Json`, `Bytes`, `Multipart`) stop reading
+//!   chunked bodies at the same size
+//!
+//! The upload extractors keep their own per-file limits:
+//! [`FileUpload`](crate::htmx::extractors::FileUpload) still rejects files
+//! over [`DEFAULT_MAX_FILE_SIZE`](crate::htmx::extractors::DEFAULT_MAX_FILE_SIZE)
+//! and [`StreamingFileUpload`](crate::htmx::extractors::StreamingFileUpload)
+//! still enforces its `max_size` as chunks arrive, within the route limit.
+//!
+//! Route limits are looked up by the matched route pattern, so apply the
+//! layer with `Router::layer`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::BodyLimitLayer;
+//! use axum::{routing::post, Router};
+//!
+//! let app: Router = Router::new()
+//!     .route("/posts", post(|| async { "created" }))
+//!     .route("/uploads", post(|| async { "uploaded" }))
+//!     .layer(
+//!         BodyLimitLayer::new(2 * 1024 * 1024)
+//!             .with_route_limit("/uploads", 100 * 1024 * 1024),
+//!     );
+//! ```
+
+use crate::htmx::config::SecuritySettings;
+use crate::htmx::error::ActonHtmxError;
+use crate::htmx::template::ResponseFormat;
+use axum::{
+    body::Body,
+    extract::{DefaultBodyLimit, MatchedPath, Request},
+    http::header,
+    response::Response,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Default maximum request body size (2MB, matching axum's `DefaultBodyLimit`)
+pub const DEFAULT_MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Layer limiting request body sizes
+#[derive(Debug, Clone)]
+pub struct BodyLimitLayer {
+    default_limit: usize,
+    route_limits: Arc<HashMap<String, usize>>,
+}
+
+impl Default for BodyLimitLayer {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BODY_BYTES)
+    }
+}
+
+impl BodyLimitLayer {
+    /// Limit request bodies to `max_bytes`
+    #[must_use]
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            default_limit: max_bytes,
+            route_limits: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Limit request bodies to `security.max_body_bytes`
+    #[must_use]
+    pub fn from_config(config: &SecuritySettings) -> Self {
+        Self::new(config.max_body_bytes)
+    }
+
+    /// Use a different limit for a route
+    ///
+    /// `route` is the pattern the route was registered with, e.g.
+    /// `"/posts/{id}/attachments"`.
+    #[must_use]
+    pub fn with_route_limit(mut self, route: impl Into<String>, max_bytes: usize) -> Self {
+        Arc::make_mut(&mut self.route_limits).insert(route.into(), max_bytes);
+        self
+    }
+
+    /// Limit applied to requests for `route`
+    #[must_use]
+    pub fn limit_for(&self, route: &str) -> usize {
+        self.route_limits
+            .get(route)
+            .copied()
+            .unwrap_or(self.default_limit)
+    }
+}
+
+impl<S> Layer<S> for BodyLimitLayer {
+    type Service = BodyLimitMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BodyLimitMiddleware {
+            inner,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Middleware enforcing [`BodyLimitLayer`] limits
+#[derive(Debug, Clone)]
+pub struct BodyLimitMiddleware<S> {
+    inner: S,
+    config: BodyLimitLayer,
+}
+
+impl<S> Service<Request> for BodyLimitMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let route = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map_or_else(|| req.uri().path(), MatchedPath::as_str);
+        let limit = self.config.limit_for(route);
+
+        let content_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+
+        if content_length.is_some_and(|length| length > limit as u64) {
+            tracing::warn!(
+                path = %req.uri().path(),
+                content_length,
+                limit,
+                "Request body too large"
+            );
+            let format = ResponseFormat::from_headers(req.headers());
+            let response = ActonHtmxError::PayloadTooLarge(format!(
+                "The request body is larger than the {} limit.",
+                format_size(limit)
+            ))
+            .into_response_for(format);
+            return Box::pin(async move { Ok(response) });
+        }
+
+        let mut inner = DefaultBodyLimit::max(limit).layer(self.inner.clone());
+        Box::pin(async move { inner.call(req).await })
+    }
+}
+
+/// Human-readable size, e.g. `2MB`
+#[allow(clippy::cast_precision_loss)]
+fn format_size(bytes: usize) -> String {
+    const KB: usize = 1024;
+    const MB: usize = 1024 * KB;
+
+    if bytes >= MB {
+        format!("{:.0}MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.0}KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{bytes} bytes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::extractors::{FileUpload, FileUploadError, StreamingFileUpload};
+    use axum::{
+        body::Bytes,
+        http::StatusCode,
+        routing::post,
+        Router,
+    };
+    use futures_util::StreamExt;
+    use tower::ServiceExt;
+
+    const BOUNDARY: &str = "X-BOUNDARY";
+
+    fn app() -> Router {
+        Router::new()
+            .route("/posts", post(|body: Bytes| async move { body.len().to_string() }))
+            .route(
+                "/uploads",
+                post(|FileUpload(file): FileUpload| async move { file.size().to_string() }),
+            )
+            .route(
+                "/streams",
+                post(|upload: StreamingFileUpload| async move {
+                    let mut upload = upload.max_size(1024);
+                    let mut file = upload.next_file().await?.unwrap();
+                    let mut size = 0;
+                    while let Some(chunk) = file.next().await {
+                        size += chunk?.len();
+                    }
+                    Ok::<_, FileUploadError>(size.to_string())
+                }),
+            )
+            .route(
+                "/forms",
+                post(|FileUpload(file): FileUpload| async move { file.size().to_string() }),
+            )
+            .layer(
+                BodyLimitLayer::new(1024)
+                    .with_route_limit("/uploads", 64 * 1024)
+                    .with_route_limit("/streams", 64 * 1024),
+            )
+    }
+
+    fn request(path: &str, body: Vec<u8>, accept: &str) -> Request {
+        Request::builder()
+            .method("POST")
+            .uri(path)
+            .header(header::ACCEPT, accept)
+            .header(header::CONTENT_LENGTH, body.len())
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    fn upload(path: &str, size: usize) -> Request {
+        let mut body = format!(
+            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.bin\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend(std::iter::repeat_n(b'a', size));
+        body.extend(format!("\r\n--{BOUNDARY}--\r\n").into_bytes());
+
+        let mut request = request(path, body, "text/html");
+        request.headers_mut().insert(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={BOUNDARY}")
+                .parse()
+                .unwrap(),
+        );
+        request
+    }
+
+    #[tokio::test]
+    async fn test_under_limit() {
+        let response = app()
+            .oneshot(request("/posts", vec![b'a'; 1024], "text/html"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_over_limit() {
+        let response = app()
+            .oneshot(request("/posts", vec![b'a'; 1025], "text/html"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+
+        let response = app()
+            .oneshot(request("/posts", vec![b'a'; 1025], "application/json"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["error"].as_str().unwrap().contains("1KB"));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_body_over_limit() {
+        let chunks = vec![Ok::<_, std::io::Error>(Bytes::from(vec![b'a'; 2048]))];
+        let request = Request::builder()
+            .method("POST")
+            .uri("/posts")
+            .body(Body::from_stream(futures_util::stream::iter(chunks)))
+            .unwrap();
+
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_route_override() {
+        let response = app().oneshot(upload("/uploads", 4096)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app().oneshot(upload("/forms", 4096)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_upload_keeps_its_own_limit() {
+        let response = app().oneshot(upload("/streams", 512)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app().oneshot(upload("/streams", 4096)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("exceeds maximum of 1024 bytes"));
+    }
+
+    #[test]
+    fn test_limit_lookup() {
+        let layer = BodyLimitLayer::default().with_route_limit("/uploads", 10);
+        assert_eq!(layer.limit_for("/uploads"), 10);
+        assert_eq!(layer.limit_for("/posts"), DEFAULT_MAX_BODY_BYTES);
+        assert_eq!(format_size(DEFAULT_MAX_BODY_BYTES), "2MB");
+    }
+}
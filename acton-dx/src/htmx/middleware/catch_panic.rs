@@ -0,0 +1,294 @@
+//! Panic recovery
+//!
+//! A panicking handler normally drops the connection without a response.
+//! [`CatchPanicLayer`] catches the panic and answers `500 Internal Server
+//! Error` instead. Each panic is logged with a generated error id, which is
+//! also returned in the [`ERROR_ID_HEADER`] header and shown to the user so
+//! reports can be matched to logs.
+//!
+//! - Full-page requests get the `errors/500.html` template
+//! - HTMX requests get an alert fragment retargeted to
+//!   [`DEFAULT_ERROR_TARGET`](super::DEFAULT_ERROR_TARGET)
+//! - API requests get `{ "error": "...", "error_id": "..." }`
+//!
+//! The panic message is only shown to clients in debug builds; release
+//! builds log it and return a generic message.
+//!
+//! [`ActonServer`](crate::htmx::server::ActonServer) installs this layer
+//! automatically.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::CatchPanicLayer;
+//! use axum::{routing::get, Router};
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "Hello" }))
+//!     .layer(CatchPanicLayer);
+//! ```
+
+use super::DEFAULT_ERROR_TARGET;
+use crate::htmx::error::render_error_page;
+use crate::htmx::responses::SwapStrategy;
+use crate::htmx::template::{escape_html, ResponseFormat};
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures_util::FutureExt;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use uuid::Uuid;
+
+/// Response header carrying the id of a logged panic
+pub const ERROR_ID_HEADER: &str = "x-error-id";
+
+/// Layer converting handler panics into `500` responses
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CatchPanicLayer;
+
+impl<S> Layer<S> for CatchPanicLayer {
+    type Service = CatchPanicMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CatchPanicMiddleware { inner }
+    }
+}
+
+/// Middleware catching panics in the inner service
+#[derive(Debug, Clone)]
+pub struct CatchPanicMiddleware<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for CatchPanicMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let format = ResponseFormat::from_headers(req.headers());
+        let method = req.method().clone();
+        let path = req.uri().path().to_owned();
+
+        match panic::catch_unwind(AssertUnwindSafe(|| self.inner.call(req))) {
+            Ok(future) => Box::pin(async move {
+                match AssertUnwindSafe(future).catch_unwind().await {
+                    Ok(result) => result,
+                    Err(panic) => Ok(panic_response(&*panic, format, &method, &path)),
+                }
+            }),
+            Err(panic) => {
+                let response = panic_response(&*panic, format, &method, &path);
+                Box::pin(async move { Ok(response) })
+            }
+        }
+    }
+}
+
+/// Log the panic and build the response in the format the request asked for
+fn panic_response(
+    panic: &(dyn Any + Send),
+    format: ResponseFormat,
+    method: &Method,
+    path: &str,
+) -> Response {
+    let error_id = Uuid::new_v4().to_string();
+    let details = panic_message(panic);
+    tracing::error!(%error_id, %method, path, panic = details, "Request handler panicked");
+
+    let message = if cfg!(debug_assertions) {
+        format!("Something went wrong (error id {error_id}): {details}")
+    } else {
+        format!("Something went wrong. Please try again later (error id {error_id}).")
+    };
+    let status = StatusCode::INTERNAL_SERVER_ERROR;
+
+    let mut response = match format {
+        ResponseFormat::Json => (
+            status,
+            Json(serde_json::json!({
+                "error": message,
+                "error_id": error_id,
+            })),
+        )
+            .into_response(),
+        ResponseFormat::HtmxPartial => (
+            status,
+            [
+                (header::CONTENT_TYPE, "text/html; charset=utf-8"),
+                (
+                    header::HeaderName::from_static("hx-retarget"),
+                    DEFAULT_ERROR_TARGET,
+                ),
+                (
+                    header::HeaderName::from_static("hx-reswap"),
+                    SwapStrategy::InnerHTML.as_str(),
+                ),
+            ],
+            format!(
+                r#"<div class="alert alert-error" role="alert" data-status="500">{}</div>"#,
+                escape_html(&message)
+            ),
+        )
+            .into_response(),
+        ResponseFormat::FullPage => (
+            status,
+            [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+            render_error_page(status, &message),
+        )
+            .into_response(),
+    };
+
+    if let Ok(value) = HeaderValue::from_str(&error_id) {
+        response.headers_mut().insert(ERROR_ID_HEADER, value);
+    }
+    response
+}
+
+/// Message of a panic payload from `panic!` or `unwrap`/`expect`
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic payload")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+
+    /// Log output captured from a tracing subscriber
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn boom() -> &'static str {
+        panic!("boom")
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/boom", get(boom))
+            .route("/ok", get(|| async { "ok" }))
+            .layer(CatchPanicLayer)
+    }
+
+    fn request(path: &str, headers: &[(&str, &str)]) -> Request {
+        let mut builder = Request::builder().uri(path);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_panic_becomes_500_with_logged_error_id() {
+        let logs = LogBuffer::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let response = app().oneshot(request("/boom", &[])).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        let error_id = response.headers()[ERROR_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        assert!(Uuid::parse_str(&error_id).is_ok());
+
+        let page = body(response).await;
+        assert!(page.contains(&error_id));
+        assert_eq!(page.contains("boom"), cfg!(debug_assertions));
+
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("Request handler panicked"));
+        assert!(logs.contains(&error_id));
+        assert!(logs.contains("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_htmx_panic_returns_retargeted_partial() {
+        let response = app()
+            .oneshot(request("/boom", &[("HX-Request", "true")]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.headers()["HX-Retarget"], DEFAULT_ERROR_TARGET);
+        assert_eq!(response.headers()["HX-Reswap"], "innerHTML");
+
+        let fragment = body(response).await;
+        assert!(fragment.starts_with("<div class=\"alert alert-error\""));
+        assert!(!fragment.contains("<html"));
+    }
+
+    #[tokio::test]
+    async fn test_api_panic_returns_json() {
+        let response = app()
+            .oneshot(request("/boom", &[("Accept", "application/json")]))
+            .await
+            .unwrap();
+
+        let error_id = response.headers()[ERROR_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let json: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+        assert_eq!(json["error_id"], error_id);
+    }
+
+    #[tokio::test]
+    async fn test_normal_responses_pass_through() {
+        let response = app().oneshot(request("/ok", &[])).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(ERROR_ID_HEADER).is_none());
+    }
+}
@@ -0,0 +1,260 @@
+//! Response compression
+//!
+//! [`CompressionLayer`] wraps `tower_http`'s compression with defaults for
+//! HTML-heavy HTMX applications:
+//!
+//! - Only text-like responses are compressed (`text/html`, `text/css`,
+//!   `application/json`, ...); images, archives, and other already-compressed
+//!   types are left alone
+//! - Responses smaller than [`DEFAULT_MIN_COMPRESS_BYTES`] are sent as-is,
+//!   since compressing tiny fragments costs more than it saves
+//! - Responses that already have a `Content-Encoding` (such as precompressed
+//!   files from [`FileServingMiddleware`](super::FileServingMiddleware)) or a
+//!   `Content-Range` are never recompressed
+//!
+//! The encoding is chosen from the request's `Accept-Encoding` (brotli,
+//! gzip, deflate, or zstd). Compressed responses get `Vary: Accept-Encoding`
+//! appended, alongside the `Vary` values added by
+//! [`AutoVaryLayer`](crate::htmx::responses::AutoVaryLayer).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::CompressionLayer;
+//! use axum::{routing::get, Router};
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "Hello" }))
+//!     .layer(
+//!         CompressionLayer::new()
+//!             .with_min_size(512)
+//!             .with_content_type("application/xml"),
+//!     );
+//! ```
+
+use axum::{
+    body::HttpBody,
+    http::{header, Response},
+};
+use std::sync::Arc;
+use tower::Layer;
+use tower_http::compression::{predicate::SizeAbove, Compression, Predicate};
+
+/// Default minimum response size to compress (1KB)
+pub const DEFAULT_MIN_COMPRESS_BYTES: u16 = 1024;
+
+/// Content types compressed by default
+const DEFAULT_CONTENT_TYPES: &[&str] = &[
+    "text/html",
+    "text/css",
+    "text/plain",
+    "text/javascript",
+    "application/javascript",
+    "application/json",
+    "application/xml",
+    "image/svg+xml",
+];
+
+/// Layer compressing responses according to `Accept-Encoding`
+#[derive(Debug, Clone)]
+pub struct CompressionLayer {
+    predicate: CompressionPredicate,
+}
+
+impl Default for CompressionLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompressionLayer {
+    /// Compress the default content types above [`DEFAULT_MIN_COMPRESS_BYTES`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            predicate: CompressionPredicate {
+                min_size: SizeAbove::new(DEFAULT_MIN_COMPRESS_BYTES),
+                content_types: Arc::new(
+                    DEFAULT_CONTENT_TYPES.iter().map(ToString::to_string).collect(),
+                ),
+            },
+        }
+    }
+
+    /// Only compress responses of at least `bytes`
+    ///
+    /// Streaming responses of unknown size are always compressed.
+    #[must_use]
+    pub const fn with_min_size(mut self, bytes: u16) -> Self {
+        self.predicate.min_size = SizeAbove::new(bytes);
+        self
+    }
+
+    /// Also compress responses of `content_type`
+    #[must_use]
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.predicate.content_types)
+            .push(content_type.into().to_ascii_lowercase());
+        self
+    }
+}
+
+impl<S> Layer<S> for CompressionLayer {
+    type Service = Compression<S, CompressionPredicate>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Compression::new(inner).compress_when(self.predicate.clone())
+    }
+}
+
+/// Decides which responses [`CompressionLayer`] compresses
+#[derive(Debug, Clone)]
+pub struct CompressionPredicate {
+    min_size: SizeAbove,
+    content_types: Arc<Vec<String>>,
+}
+
+impl Predicate for CompressionPredicate {
+    fn should_compress<B>(&self, response: &Response<B>) -> bool
+    where
+        B: HttpBody,
+    {
+        let Some(content_type) = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+        else {
+            return false;
+        };
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        self.content_types.contains(&essence)
+            && self.min_size.should_compress(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::responses::AutoVaryLayer;
+    use axum::{body::Body, extract::Request, http::StatusCode, routing::get, Router};
+    use axum_htmx::HxRequest;
+    use tower::ServiceExt;
+
+    fn large_html() -> String {
+        "<li class=\"item\">Item</li>".repeat(200)
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/page",
+                get(|| async { axum::response::Html(large_html()) }),
+            )
+            .route(
+                "/fragment",
+                get(|| async { axum::response::Html("<p>ok</p>\n") }),
+            )
+            .route(
+                "/image",
+                get(|| async { ([(header::CONTENT_TYPE, "image/png")], vec![0u8; 4096]) }),
+            )
+            .route(
+                "/items",
+                get(|HxRequest(htmx): HxRequest| async move {
+                    if htmx {
+                        axum::response::Html(large_html())
+                    } else {
+                        axum::response::Html(format!("<main>{}</main>", large_html()))
+                    }
+                }),
+            )
+            .layer(AutoVaryLayer)
+            .layer(CompressionLayer::new())
+    }
+
+    fn request(path: &str, accept_encoding: &str) -> Request {
+        Request::builder()
+            .uri(path)
+            .header(header::ACCEPT_ENCODING, accept_encoding)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn body_len(response: Response<Body>) -> usize {
+        axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .len()
+    }
+
+    #[tokio::test]
+    async fn test_large_html_is_compressed() {
+        let response = app().oneshot(request("/page", "gzip")).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+        assert!(body_len(response).await < large_html().len());
+
+        let response = app().oneshot(request("/page", "br, gzip")).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+    }
+
+    #[tokio::test]
+    async fn test_small_fragment_is_not_compressed() {
+        let response = app().oneshot(request("/fragment", "gzip, br")).await.unwrap();
+
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(body_len(response).await, 10);
+    }
+
+    #[tokio::test]
+    async fn test_respects_accept_encoding() {
+        let response = app().oneshot(request("/page", "identity")).await.unwrap();
+
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(body_len(response).await, large_html().len());
+    }
+
+    #[tokio::test]
+    async fn test_skips_other_content_types() {
+        let response = app().oneshot(request("/image", "gzip")).await.unwrap();
+
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_vary_combines_with_auto_vary() {
+        let response = app().oneshot(request("/items", "gzip")).await.unwrap();
+
+        let vary: Vec<_> = response
+            .headers()
+            .get_all(header::VARY)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_ascii_lowercase())
+            .collect();
+        assert!(vary.iter().any(|value| value.contains("hx-request")));
+        assert!(vary.iter().any(|value| value.contains("accept-encoding")));
+    }
+
+    #[test]
+    fn test_content_type_matching_ignores_parameters() {
+        let layer = CompressionLayer::new().with_min_size(0);
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, "Text/HTML; charset=utf-8")
+            .body(Body::from("<p>hi</p>"))
+            .unwrap();
+        assert!(layer.predicate.should_compress(&response));
+
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, "application/zip")
+            .body(Body::from("PK"))
+            .unwrap();
+        assert!(!layer.predicate.should_compress(&response));
+    }
+}
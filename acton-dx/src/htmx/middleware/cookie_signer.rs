@@ -0,0 +1,294 @@
+//! HMAC-signed cookie values
+//!
+//! [`CookieSigner`] signs a value together with the time it was signed, so a
+//! cookie can be checked for tampering and, with
+//! [`with_max_age`](CookieSigner::with_max_age), rejected once it is too old.
+//! Signed values have the form `value.timestamp.signature`, where the
+//! signature is a base64url HMAC-SHA256 of `value.timestamp`.
+//!
+//! The key comes from `security.secret_key`. Debug builds fall back to a
+//! fixed development key when it is unset; release builds refuse to start.
+//!
+//! Each feature signs with its own [`for_purpose`](CookieSigner::for_purpose)
+//! signer (sessions, flash messages, CSRF and remember-me cookies, email
+//! verification links, and each [`Cookies::set_signed`] cookie name), so a
+//! value signed for one can't be replayed as another.
+//!
+//! [`Cookies::set_signed`]: crate::htmx::extractors::Cookies::set_signed
+//!
+//! # Example
+//!
+//! ```rust
+//! use acton_dx::htmx::middleware::CookieSigner;
+//! use std::time::Duration;
+//!
+//! let signer = CookieSigner::new(b"secret").with_max_age(Duration::from_secs(3600));
+//! let signed = signer.sign("user-42");
+//! assert_eq!(signer.verify(&signed).as_deref(), Some("user-42"));
+//! assert_eq!(signer.verify(&signed.replace("42", "43")), None);
+//! ```
+
+use crate::htmx::config::SecuritySettings;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Key used in debug builds when `security.secret_key` is unset
+pub(crate) const DEV_SECRET_KEY: &str = "acton-dx-insecure-development-secret-key";
+
+/// Errors creating a [`CookieSigner`]
+#[derive(Debug, thiserror::Error)]
+pub enum CookieSignerError {
+    /// `security.secret_key` is unset in a release build
+    #[error("security.secret_key must be set in release builds")]
+    MissingSecretKey,
+}
+
+/// Signs and verifies cookie values with HMAC-SHA256
+#[derive(Clone)]
+pub struct CookieSigner {
+    secret_key: Arc<[u8]>,
+    max_age: Option<Duration>,
+}
+
+impl std::fmt::Debug for CookieSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CookieSigner")
+            .field("secret_key", &"[redacted]")
+            .field("max_age", &self.max_age)
+            .finish()
+    }
+}
+
+impl CookieSigner {
+    /// Create a signer using `secret_key`
+    ///
+    /// Signed values never expire unless [`with_max_age`](Self::with_max_age)
+    /// is called.
+    #[must_use]
+    pub fn new(secret_key: impl AsRef<[u8]>) -> Self {
+        Self {
+            secret_key: Arc::from(secret_key.as_ref()),
+            max_age: None,
+        }
+    }
+
+    /// Create a signer from `security.secret_key`
+    ///
+    /// Debug builds fall back to a fixed development key (with a warning)
+    /// when the key is unset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CookieSignerError::MissingSecretKey`] if the key is unset in
+    /// a release build
+    pub fn from_settings(settings: &SecuritySettings) -> Result<Self, CookieSignerError> {
+        match settings.secret_key.as_deref() {
+            Some(key) => Ok(Self::new(key)),
+            None if cfg!(debug_assertions) => {
+                tracing::warn!(
+                    "security.secret_key is not set; cookies are signed with a development key"
+                );
+                Ok(Self::new(DEV_SECRET_KEY))
+            }
+            None => Err(CookieSignerError::MissingSecretKey),
+        }
+    }
+
+    /// Create a signer with a random key
+    ///
+    /// Values signed with it do not survive restarts and are rejected by
+    /// other instances.
+    #[must_use]
+    pub fn random() -> Self {
+        let mut key = [0u8; 32];
+        rand::rng().fill(&mut key);
+        Self::new(key)
+    }
+
+    /// Signer keyed for `purpose` alone
+    ///
+    /// Uses a key derived from this signer's key, so values signed for one
+    /// purpose don't verify for any other.
+    #[must_use]
+    pub fn for_purpose(&self, purpose: &str) -> Self {
+        Self {
+            secret_key: Arc::from(self.derive_key(&format!("sign:{purpose}")).as_slice()),
+            max_age: self.max_age,
+        }
+    }
+
+    /// Reject values signed more than `max_age` ago
+    #[must_use]
+    pub const fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sign `value` with the current time
+    #[must_use]
+    pub fn sign(&self, value: &str) -> String {
+        self.sign_at(value, unix_now())
+    }
+
+    /// Return the original value if `signed` is authentic and not expired
+    #[must_use]
+    pub fn verify(&self, signed: &str) -> Option<String> {
+        self.verify_at(signed, unix_now())
+    }
+
+    pub(crate) fn sign_at(&self, value: &str, timestamp: u64) -> String {
+        let payload = format!("{value}.{timestamp}");
+        // HMAC accepts keys of any length, so this never falls back
+        let signature = self
+            .mac(&payload)
+            .map(|mac| URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+            .unwrap_or_default();
+        format!("{payload}.{signature}")
+    }
+
+    pub(crate) fn verify_at(&self, signed: &str, now: u64) -> Option<String> {
+        let (payload, signature) = signed.rsplit_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        // `verify_slice` compares in constant time
+        self.mac(payload)?.verify_slice(&signature).ok()?;
+
+        let (value, timestamp) = payload.rsplit_once('.')?;
+        let timestamp: u64 = timestamp.parse().ok()?;
+        if let Some(max_age) = self.max_age {
+            if now.saturating_sub(timestamp) > max_age.as_secs() {
+                return None;
+            }
+        }
+        Some(value.to_string())
+    }
+
+    /// Derive a 256-bit key for `purpose` from the secret key
+    ///
+    /// Lets other features (e.g. encrypting TOTP secrets) key off
+    /// `security.secret_key` without reusing the signing key directly.
+    pub(crate) fn derive_key(&self, purpose: &str) -> [u8; 32] {
+        self.mac(&format!("derive-key:{purpose}"))
+            .map(|mac| mac.finalize().into_bytes().into())
+            .unwrap_or_default()
+    }
+
+    /// Compare two secrets in constant time
+    ///
+    /// Compares their MACs rather than the values, so timing reveals nothing
+    /// about where they differ.
+    pub(crate) fn constant_time_eq(&self, a: &str, b: &str) -> bool {
+        let Some(expected) = self.mac(b).map(|mac| mac.finalize().into_bytes()) else {
+            return false;
+        };
+        self.mac(a).is_some_and(|mac| mac.verify_slice(&expected).is_ok())
+    }
+
+    fn mac(&self, payload: &str) -> Option<HmacSha256> {
+        let mut mac = HmacSha256::new_from_slice(&self.secret_key).ok()?;
+        mac.update(payload.as_bytes());
+        Some(mac)
+    }
+}
+
+/// Seconds since the Unix epoch
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = CookieSigner::new(b"test-secret");
+        let cookie = signer.sign("session-id");
+
+        assert!(cookie.starts_with("session-id."));
+        assert_eq!(signer.verify(&cookie).as_deref(), Some("session-id"));
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let signer = CookieSigner::new(b"test-secret");
+        let cookie = signer.sign_at("user-1", 1_000);
+
+        // Changed value, changed timestamp, truncated or missing signature
+        assert_eq!(signer.verify_at(&cookie.replace("user-1", "user-2"), 1_000), None);
+        assert_eq!(signer.verify_at(&cookie.replace(".1000.", ".9999."), 1_000), None);
+        assert_eq!(signer.verify_at(&cookie[..cookie.len() - 1], 1_000), None);
+        assert_eq!(signer.verify_at("user-1.1000", 1_000), None);
+        assert_eq!(signer.verify_at("user-1", 1_000), None);
+
+        // Signed with another key
+        let other = CookieSigner::new(b"other-secret");
+        assert_eq!(other.verify_at(&cookie, 1_000), None);
+    }
+
+    #[test]
+    fn test_expiry() {
+        let signer = CookieSigner::new(b"test-secret").with_max_age(Duration::from_secs(60));
+        let cookie = signer.sign_at("user-1", 1_000);
+
+        assert_eq!(signer.verify_at(&cookie, 1_060).as_deref(), Some("user-1"));
+        assert_eq!(signer.verify_at(&cookie, 1_061), None);
+
+        // Without a max age the value never expires
+        let forever = CookieSigner::new(b"test-secret");
+        assert_eq!(forever.verify_at(&cookie, u64::MAX).as_deref(), Some("user-1"));
+    }
+
+    #[test]
+    fn test_purposes_are_separate() {
+        let signer = CookieSigner::new(b"test-secret");
+        let session = signer.for_purpose("session");
+        let cookie = session.sign("user-1");
+
+        assert_eq!(session.verify(&cookie).as_deref(), Some("user-1"));
+        assert_eq!(signer.for_purpose("flash").verify(&cookie), None);
+        assert_eq!(signer.verify(&cookie), None);
+        assert_eq!(
+            CookieSigner::new(b"test-secret").for_purpose("session").verify(&cookie).as_deref(),
+            Some("user-1")
+        );
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        let signer = CookieSigner::new(b"test-secret");
+        assert!(signer.constant_time_eq("abc", "abc"));
+        assert!(!signer.constant_time_eq("abc", "abd"));
+        assert!(!signer.constant_time_eq("abc", "abcd"));
+    }
+
+    #[test]
+    fn test_values_containing_dots() {
+        let signer = CookieSigner::new(b"test-secret");
+        let cookie = signer.sign("a.b.c");
+        assert_eq!(signer.verify(&cookie).as_deref(), Some("a.b.c"));
+    }
+
+    #[test]
+    fn test_from_settings() {
+        let mut settings = SecuritySettings {
+            secret_key: Some("configured".to_string()),
+            ..SecuritySettings::default()
+        };
+        let cookie = CookieSigner::from_settings(&settings).unwrap().sign("v");
+        assert_eq!(CookieSigner::new("configured").verify(&cookie).as_deref(), Some("v"));
+
+        settings.secret_key = None;
+        // Tests run as debug builds, which use the development key
+        let signer = CookieSigner::from_settings(&settings).unwrap();
+        let cookie = signer.sign("v");
+        assert_eq!(CookieSigner::new(DEV_SECRET_KEY).verify(&cookie).as_deref(), Some("v"));
+    }
+}
@@ -0,0 +1,344 @@
+//! Cross-origin resource sharing (CORS) middleware
+//!
+//! Wraps [`tower_http::cors`] with configuration from `[security.cors]`.
+//! The default configuration allows no cross-origin requests: browsers
+//! only let other origins call the application once they are listed in
+//! `allowed_origins`.
+//!
+//! # Example Configuration
+//!
+//! ```toml
+//! [security.cors]
+//! allowed_origins = ["https://app.example.com"]
+//! allowed_methods = ["GET", "POST", "DELETE"]
+//! allowed_headers = ["content-type", "x-csrf-token"]
+//! allow_credentials = true
+//! max_age_secs = 3600
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! use acton_dx::htmx::middleware::{CorsConfig, CorsLayer};
+//! use axum::{routing::get, Router};
+//!
+//! let config = CorsConfig {
+//!     allowed_origins: vec!["https://app.example.com".to_string()],
+//!     ..CorsConfig::default()
+//! };
+//! let app: Router = Router::new()
+//!     .route("/api/items", get(|| async { "[]" }))
+//!     .layer(CorsLayer::from_config(&config).unwrap());
+//! ```
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tower::Layer;
+use tower_http::cors::{self, AllowHeaders, AllowMethods, AllowOrigin, Cors};
+
+/// Value allowing any origin, method, or header
+const WILDCARD: &str = "*";
+
+/// CORS configuration
+///
+/// An entry of `"*"` in `allowed_origins`, `allowed_methods`, or
+/// `allowed_headers` allows any value, but cannot be combined with
+/// `allow_credentials`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// Origins allowed to make cross-origin requests, e.g.
+    /// `"https://app.example.com"` (default: none)
+    pub allowed_origins: Vec<String>,
+
+    /// Methods allowed in cross-origin requests (default: GET, POST)
+    pub allowed_methods: Vec<String>,
+
+    /// Request headers allowed in cross-origin requests
+    /// (default: `content-type`)
+    pub allowed_headers: Vec<String>,
+
+    /// Allow cookies and HTTP authentication on cross-origin requests
+    /// (default: false)
+    pub allow_credentials: bool,
+
+    /// How long browsers may cache preflight responses, in seconds
+    /// (default: 3600)
+    pub max_age_secs: u64,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec!["GET".to_string(), "POST".to_string()],
+            allowed_headers: vec!["content-type".to_string()],
+            allow_credentials: false,
+            max_age_secs: 3600,
+        }
+    }
+}
+
+/// Errors building a [`CorsLayer`]
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CorsError {
+    /// A wildcard was combined with `allow_credentials`, which browsers reject
+    #[error("security.cors.{0} cannot contain \"*\" when allow_credentials is true")]
+    WildcardWithCredentials(&'static str),
+
+    /// An origin is not a valid header value
+    #[error("invalid CORS origin: {0}")]
+    InvalidOrigin(String),
+
+    /// A method name is invalid
+    #[error("invalid CORS method: {0}")]
+    InvalidMethod(String),
+
+    /// A header name is invalid
+    #[error("invalid CORS header: {0}")]
+    InvalidHeader(String),
+}
+
+/// Layer adding CORS headers and answering preflight requests
+#[derive(Debug, Clone)]
+pub struct CorsLayer {
+    inner: cors::CorsLayer,
+}
+
+impl CorsLayer {
+    /// Build the layer from configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns error if a wildcard is combined with `allow_credentials`, or
+    /// if an origin, method, or header is invalid
+    pub fn from_config(config: &CorsConfig) -> Result<Self, CorsError> {
+        let any_origin = is_wildcard(&config.allowed_origins);
+        let any_method = is_wildcard(&config.allowed_methods);
+        let any_header = is_wildcard(&config.allowed_headers);
+
+        if config.allow_credentials {
+            if any_origin {
+                return Err(CorsError::WildcardWithCredentials("allowed_origins"));
+            }
+            if any_method {
+                return Err(CorsError::WildcardWithCredentials("allowed_methods"));
+            }
+            if any_header {
+                return Err(CorsError::WildcardWithCredentials("allowed_headers"));
+            }
+        }
+
+        let origins = if any_origin {
+            AllowOrigin::any()
+        } else {
+            let origins = config
+                .allowed_origins
+                .iter()
+                .map(|origin| {
+                    HeaderValue::from_str(origin)
+                        .map_err(|_| CorsError::InvalidOrigin(origin.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            AllowOrigin::list(origins)
+        };
+
+        let methods = if any_method {
+            AllowMethods::any()
+        } else {
+            let methods = config
+                .allowed_methods
+                .iter()
+                .map(|method| {
+                    Method::from_bytes(method.to_uppercase().as_bytes())
+                        .map_err(|_| CorsError::InvalidMethod(method.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            AllowMethods::list(methods)
+        };
+
+        let headers = if any_header {
+            AllowHeaders::any()
+        } else {
+            let headers = config
+                .allowed_headers
+                .iter()
+                .map(|header| {
+                    HeaderName::try_from(header.as_str())
+                        .map_err(|_| CorsError::InvalidHeader(header.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            AllowHeaders::list(headers)
+        };
+
+        Ok(Self {
+            inner: cors::CorsLayer::new()
+                .allow_origin(origins)
+                .allow_methods(methods)
+                .allow_headers(headers)
+                .allow_credentials(config.allow_credentials)
+                .max_age(Duration::from_secs(config.max_age_secs)),
+        })
+    }
+}
+
+impl<S> Layer<S> for CorsLayer {
+    type Service = Cors<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        self.inner.layer(inner)
+    }
+}
+
+fn is_wildcard(values: &[String]) -> bool {
+    values.iter().any(|value| value == WILDCARD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    fn app(config: &CorsConfig) -> Router {
+        Router::new()
+            .route("/api", get(|| async { "ok" }))
+            .layer(CorsLayer::from_config(config).unwrap())
+    }
+
+    fn preflight(origin: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn app_origin() -> CorsConfig {
+        CorsConfig {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            allow_credentials: true,
+            max_age_secs: 600,
+            ..CorsConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_preflight_for_allowed_origin() {
+        let response = app(&app_origin())
+            .oneshot(preflight("https://app.example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_METHODS], "GET,POST");
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+    }
+
+    #[tokio::test]
+    async fn test_disallowed_origin_gets_no_cors_headers() {
+        let response = app(&app_origin())
+            .oneshot(preflight("https://evil.example.com"))
+            .await
+            .unwrap();
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+
+        let request = Request::builder()
+            .uri("/api")
+            .header(header::ORIGIN, "https://evil.example.com")
+            .body(Body::empty())
+            .unwrap();
+        let response = app(&app_origin()).oneshot(request).await.unwrap();
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_default_allows_no_cross_origin_requests() {
+        let response = app(&CorsConfig::default())
+            .oneshot(preflight("https://app.example.com"))
+            .await
+            .unwrap();
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_origin() {
+        let config = CorsConfig {
+            allowed_origins: vec!["*".to_string()],
+            ..CorsConfig::default()
+        };
+        let response = app(&config)
+            .oneshot(preflight("https://anywhere.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    }
+
+    #[test]
+    fn test_wildcard_with_credentials_is_rejected() {
+        let config = CorsConfig {
+            allowed_origins: vec!["*".to_string()],
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+        assert_eq!(
+            CorsLayer::from_config(&config).unwrap_err(),
+            CorsError::WildcardWithCredentials("allowed_origins")
+        );
+
+        let config = CorsConfig {
+            allowed_headers: vec!["*".to_string()],
+            ..app_origin()
+        };
+        assert_eq!(
+            CorsLayer::from_config(&config).unwrap_err(),
+            CorsError::WildcardWithCredentials("allowed_headers")
+        );
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        let config = CorsConfig {
+            allowed_methods: vec!["GET POST".to_string()],
+            ..CorsConfig::default()
+        };
+        assert_eq!(
+            CorsLayer::from_config(&config).unwrap_err(),
+            CorsError::InvalidMethod("GET POST".to_string())
+        );
+
+        let config = CorsConfig {
+            allowed_headers: vec!["bad header".to_string()],
+            ..CorsConfig::default()
+        };
+        assert!(matches!(
+            CorsLayer::from_config(&config),
+            Err(CorsError::InvalidHeader(_))
+        ));
+    }
+}
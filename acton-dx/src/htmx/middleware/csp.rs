@@ -0,0 +1,407 @@
+//! Content-Security-Policy builder and per-request nonces
+//!
+//! [`CspBuilder`] assembles a `Content-Security-Policy` value from typed
+//! directives. Policies that use a nonce ([`CspBuilder::script_nonce`],
+//! [`CspBuilder::style_nonce`]) contain [`CSP_NONCE_SOURCE`], which
+//! `SecurityHeadersMiddleware` replaces with a fresh nonce on every request.
+//! The same nonce is available to handlers through the [`CspNonce`]
+//! extractor, and to templates through the `csp_nonce()` and `script()`
+//! helpers, so `<script>` and `<style>` tags can carry it.
+//!
+//! # Example
+//!
+//! ```rust
+//! use acton_dx::htmx::middleware::csp::{self, CspBuilder};
+//! use acton_dx::htmx::middleware::{SecurityHeadersConfig, SecurityHeadersLayer};
+//!
+//! let policy = CspBuilder::new()
+//!     .default_src([csp::SELF])
+//!     .script_src([csp::SELF, "https://unpkg.com"])
+//!     .script_nonce()
+//!     .img_src([csp::SELF, csp::DATA])
+//!     .build();
+//! assert_eq!(
+//!     policy,
+//!     "default-src 'self'; script-src 'self' https://unpkg.com 'nonce-{nonce}'; img-src 'self' data:"
+//! );
+//!
+//! let layer = SecurityHeadersLayer::new(SecurityHeadersConfig::strict().with_csp(policy));
+//! ```
+
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    http::{request::Parts, StatusCode},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::Rng;
+use std::fmt;
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT_NONCE: CspNonce;
+}
+
+/// Source replaced with the request's nonce, e.g. `'nonce-k2Zq…'`
+pub const CSP_NONCE_SOURCE: &str = "'nonce-{nonce}'";
+
+/// Placeholder inside [`CSP_NONCE_SOURCE`] that receives the nonce
+pub(crate) const NONCE_PLACEHOLDER: &str = "{nonce}";
+
+/// The document's own origin
+pub const SELF: &str = "'self'";
+/// Nothing is allowed
+pub const NONE: &str = "'none'";
+/// `data:` URLs, e.g. inline images
+pub const DATA: &str = "data:";
+/// Inline scripts or styles; prefer a nonce
+pub const UNSAFE_INLINE: &str = "'unsafe-inline'";
+/// `eval()` and similar; required by `hx-on` attributes
+pub const UNSAFE_EVAL: &str = "'unsafe-eval'";
+
+/// Builder for `Content-Security-Policy` header values
+///
+/// Directives are written in the order they are first set; setting a
+/// directive again adds to its sources.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CspBuilder {
+    directives: Vec<(&'static str, Vec<String>)>,
+}
+
+impl CspBuilder {
+    /// Start an empty policy
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            directives: Vec::new(),
+        }
+    }
+
+    /// Policy suited to HTMX applications
+    ///
+    /// Everything is same-origin, and inline `<script>` and `<style>` tags
+    /// must carry the request's nonce instead of relying on
+    /// `'unsafe-inline'`. Pass the nonce to HTMX with
+    /// [`CspNonce::htmx_config_meta`] so the scripts and indicator styles it
+    /// inserts are allowed too.
+    ///
+    /// ```text
+    /// default-src 'self'; script-src 'self' 'nonce-…'; style-src 'self' 'nonce-…';
+    /// img-src 'self' data:; connect-src 'self'; object-src 'none';
+    /// base-uri 'self'; form-action 'self'; frame-ancestors 'none'
+    /// ```
+    #[must_use]
+    pub fn htmx() -> Self {
+        Self::new()
+            .default_src([SELF])
+            .script_src([SELF])
+            .script_nonce()
+            .style_src([SELF])
+            .style_nonce()
+            .img_src([SELF, DATA])
+            .connect_src([SELF])
+            .object_src([NONE])
+            .base_uri([SELF])
+            .form_action([SELF])
+            .frame_ancestors([NONE])
+    }
+
+    /// Fallback for fetch directives that are not set (`default-src`)
+    #[must_use]
+    pub fn default_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("default-src", sources)
+    }
+
+    /// Allowed script sources (`script-src`)
+    #[must_use]
+    pub fn script_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("script-src", sources)
+    }
+
+    /// Allowed stylesheet sources (`style-src`)
+    #[must_use]
+    pub fn style_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("style-src", sources)
+    }
+
+    /// Allowed image sources (`img-src`)
+    #[must_use]
+    pub fn img_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("img-src", sources)
+    }
+
+    /// Allowed targets of fetch, XHR, `WebSocket`, and SSE (`connect-src`)
+    #[must_use]
+    pub fn connect_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("connect-src", sources)
+    }
+
+    /// Allowed font sources (`font-src`)
+    #[must_use]
+    pub fn font_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("font-src", sources)
+    }
+
+    /// Allowed audio and video sources (`media-src`)
+    #[must_use]
+    pub fn media_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("media-src", sources)
+    }
+
+    /// Allowed `<object>` and `<embed>` sources (`object-src`)
+    #[must_use]
+    pub fn object_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("object-src", sources)
+    }
+
+    /// Allowed sources of nested frames (`frame-src`)
+    #[must_use]
+    pub fn frame_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("frame-src", sources)
+    }
+
+    /// Allowed worker script sources (`worker-src`)
+    #[must_use]
+    pub fn worker_src<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("worker-src", sources)
+    }
+
+    /// Allowed `<base href>` values (`base-uri`)
+    #[must_use]
+    pub fn base_uri<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("base-uri", sources)
+    }
+
+    /// Allowed form submission targets (`form-action`)
+    #[must_use]
+    pub fn form_action<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("form-action", sources)
+    }
+
+    /// Pages allowed to embed this one in a frame (`frame-ancestors`)
+    #[must_use]
+    pub fn frame_ancestors<I: IntoIterator<Item = S>, S: Into<String>>(self, sources: I) -> Self {
+        self.directive("frame-ancestors", sources)
+    }
+
+    /// Allow inline scripts carrying the request's nonce
+    #[must_use]
+    pub fn script_nonce(self) -> Self {
+        self.directive("script-src", [CSP_NONCE_SOURCE])
+    }
+
+    /// Allow inline styles carrying the request's nonce
+    #[must_use]
+    pub fn style_nonce(self) -> Self {
+        self.directive("style-src", [CSP_NONCE_SOURCE])
+    }
+
+    /// Make browsers load `http:` resources over HTTPS
+    /// (`upgrade-insecure-requests`)
+    #[must_use]
+    pub fn upgrade_insecure_requests(self) -> Self {
+        self.directive("upgrade-insecure-requests", std::iter::empty::<String>())
+    }
+
+    /// Serialize the policy
+    #[must_use]
+    pub fn build(&self) -> String {
+        self.to_string()
+    }
+
+    fn directive<I: IntoIterator<Item = S>, S: Into<String>>(
+        mut self,
+        name: &'static str,
+        sources: I,
+    ) -> Self {
+        let index = self
+            .directives
+            .iter()
+            .position(|(existing, _)| *existing == name)
+            .unwrap_or_else(|| {
+                self.directives.push((name, Vec::new()));
+                self.directives.len() - 1
+            });
+        let values = &mut self.directives[index].1;
+        for source in sources {
+            let source = source.into();
+            if !values.contains(&source) {
+                values.push(source);
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for CspBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, sources)) in self.directives.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{name}")?;
+            for source in sources {
+                write!(f, " {source}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The CSP nonce of the current request
+///
+/// Inserted into request extensions by `SecurityHeadersMiddleware` when the
+/// configured policy contains [`CSP_NONCE_SOURCE`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::middleware::CspNonce;
+/// use axum::response::Html;
+///
+/// async fn page(nonce: CspNonce) -> Html<String> {
+///     Html(format!(
+///         r#"<head>{}<script nonce="{nonce}" src="/app.js"></script></head>"#,
+///         nonce.htmx_config_meta()
+///     ))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CspNonce(String);
+
+impl CspNonce {
+    /// Generate a random nonce (128 bits, base64)
+    #[must_use]
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 16];
+        rand::rng().fill(&mut bytes);
+        Self(STANDARD.encode(bytes))
+    }
+
+    /// The nonce value, for `nonce="…"` attributes
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// `<meta name="htmx-config">` tag making HTMX use this nonce for the
+    /// scripts and styles it inserts
+    #[must_use]
+    pub fn htmx_config_meta(&self) -> String {
+        format!(
+            r#"<meta name="htmx-config" content='{{"inlineScriptNonce":"{0}","inlineStyleNonce":"{0}"}}'>"#,
+            self.0
+        )
+    }
+
+    /// Run `future` with this nonce as the current request's nonce
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT_NONCE.scope(self.clone(), future).await
+    }
+
+    /// The nonce of the current request, if inside [`scope`](Self::scope)
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CURRENT_NONCE.try_with(Clone::clone).ok()
+    }
+
+    /// Substitute this nonce into a policy built with [`CSP_NONCE_SOURCE`]
+    pub(crate) fn apply(&self, policy: &str) -> String {
+        policy.replace(NONCE_PLACEHOLDER, &self.0)
+    }
+}
+
+impl fmt::Display for CspNonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CspNonce {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Self>().cloned().ok_or_else(|| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "CSP nonce not found - ensure SecurityHeadersLayer is applied with a nonce policy"
+                    .to_string(),
+            )
+        })
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for CspNonce {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<Self>().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directive_serialization() {
+        let policy = CspBuilder::new()
+            .default_src([SELF])
+            .img_src([SELF, DATA])
+            .script_src(["https://unpkg.com"])
+            .img_src(["https://images.example.com", SELF])
+            .upgrade_insecure_requests()
+            .build();
+
+        assert_eq!(
+            policy,
+            "default-src 'self'; img-src 'self' data: https://images.example.com; \
+             script-src https://unpkg.com; upgrade-insecure-requests"
+        );
+        assert_eq!(CspBuilder::new().build(), "");
+    }
+
+    #[test]
+    fn test_htmx_preset_uses_nonces() {
+        let policy = CspBuilder::htmx().build();
+
+        assert!(policy.starts_with("default-src 'self'; "));
+        assert!(policy.contains("script-src 'self' 'nonce-{nonce}'"));
+        assert!(policy.contains("style-src 'self' 'nonce-{nonce}'"));
+        assert!(policy.contains("frame-ancestors 'none'"));
+        assert!(!policy.contains(UNSAFE_INLINE));
+    }
+
+    #[test]
+    fn test_nonce_applied_to_policy() {
+        let nonce = CspNonce::generate();
+        let policy = nonce.apply(&CspBuilder::new().script_nonce().style_nonce().build());
+
+        assert_eq!(
+            policy,
+            format!("script-src 'nonce-{nonce}'; style-src 'nonce-{nonce}'")
+        );
+        assert_ne!(CspNonce::generate(), nonce);
+        assert_eq!(STANDARD.decode(nonce.as_str()).unwrap().len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_current_nonce_is_scoped() {
+        let nonce = CspNonce::generate();
+        let current = nonce.scope(async { CspNonce::current() }).await;
+
+        assert_eq!(current, Some(nonce));
+        assert_eq!(CspNonce::current(), None);
+    }
+
+    #[test]
+    fn test_htmx_config_meta() {
+        let nonce = CspNonce("abc".to_string());
+        assert_eq!(
+            nonce.htmx_config_meta(),
+            r#"<meta name="htmx-config" content='{"inlineScriptNonce":"abc","inlineStyleNonce":"abc"}'>"#
+        );
+    }
+}
@@ -1,25 +1,59 @@
 //! CSRF middleware for protection against Cross-Site Request Forgery attacks
 //!
 //! Provides middleware that validates CSRF tokens on state-changing requests
-//! (POST, PUT, DELETE, PATCH). Integrates with the `CsrfManagerAgent` for
-//! token storage and validation.
+//! (POST, PUT, DELETE, PATCH). Two strategies are available, selected with
+//! [`CsrfMode`]:
+//!
+//! - [`CsrfMode::Synchronizer`] (default): tokens are stored per session by
+//!   the `CsrfManagerAgent` and rotated after each successful validation.
+//! - [`CsrfMode::DoubleSubmit`]: the token lives in an HMAC-signed cookie and
+//!   is compared with the submitted token, without a session or agent
+//!   round-trip. The cookie is signed with the application's
+//!   [`CookieSigner`], so the key comes from `security.secret_key`. When
+//!   `SessionMiddleware` runs first, the signature also covers the session
+//!   ID, so a cookie planted from a sibling subdomain doesn't validate.
 //!
 //! # Security Features
 //!
 //! - Automatic token validation on non-idempotent methods
-//! - Token rotation after successful validation
 //! - 403 Forbidden response on validation failure
-//! - Support for both form data and custom headers
-//! - Session-based token storage
+//! - Support for both urlencoded form data and custom headers
+//! - Session-based or signed-cookie token storage
+//!
+//! # Exempt Routes
+//!
+//! Routes called by third parties that can't obtain a token, such as Stripe
+//! or GitHub webhooks, are exempted with [`CsrfConfig::skip_path`]. Patterns
+//! use axum's route syntax, so the route definition can be reused:
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::middleware::{CsrfConfig, CsrfLayer};
+//!
+//! let config = CsrfConfig::new()
+//!     .skip_path("/webhooks/stripe")
+//!     .skip_path("/webhooks/{provider}")
+//!     .skip_path("/api/v1/{*rest}");
+//! let app = router.layer(CsrfLayer::with_config(&state, config));
+//! ```
+//!
+//! An exempt route has no CSRF protection at all: any site can make a
+//! logged-in browser POST to it. Exempt routes must verify authenticity
+//! another way, e.g. the provider's request signature (see
+//! [`SignedWebhook`](crate::htmx::extractors::SignedWebhook)) or an API key,
+//! and must not rely on the session cookie.
 
 use crate::htmx::agents::{CsrfToken, ValidateToken};
 use crate::htmx::auth::session::SessionId;
+use crate::htmx::middleware::CookieSigner;
 use crate::htmx::state::ActonHtmxState;
 use acton_reactive::prelude::{AgentHandle, AgentHandleInterface};
 use axum::{
     body::Body,
     extract::Request,
-    http::{Method, StatusCode},
+    http::{
+        header::{CONTENT_TYPE, COOKIE, SET_COOKIE},
+        Method, StatusCode,
+    },
     response::{IntoResponse, Response},
 };
 use std::sync::Arc;
@@ -33,6 +67,31 @@ pub const CSRF_HEADER_NAME: &str = "x-csrf-token";
 /// CSRF token form field name
 pub const CSRF_FORM_FIELD: &str = "_csrf_token";
 
+/// CSRF cookie name used in [`CsrfMode::DoubleSubmit`] mode
+pub const CSRF_COOKIE_NAME: &str = "acton_csrf";
+
+/// Largest form body buffered while looking for the CSRF form field
+const MAX_FORM_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// [`CookieSigner::for_purpose`] key of the double-submit cookie
+const SIGNING_PURPOSE: &str = "csrf";
+
+/// Strategy used to store and validate CSRF tokens
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CsrfMode {
+    /// Session-bound tokens held by the `CsrfManagerAgent`
+    ///
+    /// Requires `SessionMiddleware` to be applied first.
+    #[default]
+    Synchronizer,
+    /// Stateless tokens held in a signed cookie
+    ///
+    /// The submitted token must match the cookie's token. No session is
+    /// required and the agent is never consulted, but when a `SessionId`
+    /// is present the cookie is only accepted for that session.
+    DoubleSubmit,
+}
+
 /// CSRF configuration for middleware
 #[derive(Clone, Debug)]
 pub struct CsrfConfig {
@@ -42,8 +101,17 @@ pub struct CsrfConfig {
     pub form_field: String,
     /// Timeout for agent communication in milliseconds
     pub agent_timeout_ms: u64,
-    /// Skip CSRF validation for these paths (e.g., webhooks, health checks)
+    /// Skip CSRF validation for paths matching these patterns (e.g.,
+    /// webhooks)
+    ///
+    /// See [`skip_path`](Self::skip_path) for the pattern syntax.
     pub skip_paths: Vec<String>,
+    /// Token storage and validation strategy
+    pub mode: CsrfMode,
+    /// Cookie name for [`CsrfMode::DoubleSubmit`] (default: "acton_csrf")
+    pub cookie_name: String,
+    /// Mark the double-submit cookie `Secure` (HTTPS only)
+    pub cookie_secure: bool,
 }
 
 impl Default for CsrfConfig {
@@ -53,6 +121,9 @@ impl Default for CsrfConfig {
             form_field: CSRF_FORM_FIELD.to_string(),
             agent_timeout_ms: 100,
             skip_paths: vec![],
+            mode: CsrfMode::default(),
+            cookie_name: CSRF_COOKIE_NAME.to_string(),
+            cookie_secure: !cfg!(debug_assertions),
         }
     }
 }
@@ -64,28 +135,51 @@ impl CsrfConfig {
         Self::default()
     }
 
-    /// Add a path to skip CSRF validation
+    /// Add a path pattern to skip CSRF validation
+    ///
+    /// Patterns follow axum route syntax and match the same paths as the
+    /// route would:
+    ///
+    /// - `/webhooks/stripe` matches exactly that path
+    /// - `/webhooks/{provider}` matches one non-empty segment in place of
+    ///   `{provider}`
+    /// - `/api/{*rest}` matches one or more trailing segments
+    /// - `{{` and `}}` match literal braces
+    ///
+    /// Matching requests are not checked at all, so the route must verify
+    /// authenticity another way (see the [module docs](self#exempt-routes)).
     #[must_use]
     pub fn skip_path(mut self, path: impl Into<String>) -> Self {
         self.skip_paths.push(path.into());
         self
     }
 
-    /// Add multiple paths to skip CSRF validation
+    /// Add multiple path patterns to skip CSRF validation
+    ///
+    /// See [`skip_path`](Self::skip_path) for the pattern syntax.
     #[must_use]
     pub fn skip_paths(mut self, paths: Vec<String>) -> Self {
         self.skip_paths.extend(paths);
         self
     }
+
+    /// Set the token storage and validation strategy
+    #[must_use]
+    pub const fn mode(mut self, mode: CsrfMode) -> Self {
+        self.mode = mode;
+        self
+    }
 }
 
 /// Layer for CSRF middleware
 ///
-/// Requires both `SessionId` and CSRF manager to be available.
+/// In [`CsrfMode::Synchronizer`] mode, requires both `SessionId` and CSRF
+/// manager to be available.
 #[derive(Clone)]
 pub struct CsrfLayer {
     config: CsrfConfig,
     csrf_manager: AgentHandle,
+    signer: CookieSigner,
 }
 
 impl std::fmt::Debug for CsrfLayer {
@@ -93,6 +187,7 @@ impl std::fmt::Debug for CsrfLayer {
         f.debug_struct("CsrfLayer")
             .field("config", &self.config)
             .field("csrf_manager", &"AgentHandle")
+            .field("signer", &self.signer)
             .finish()
     }
 }
@@ -101,38 +196,52 @@ impl CsrfLayer {
     /// Create new CSRF layer with CSRF manager from state
     #[must_use]
     pub fn new(state: &ActonHtmxState) -> Self {
-        Self {
-            config: CsrfConfig::default(),
-            csrf_manager: state.csrf_manager().clone(),
-        }
+        Self::with_config(state, CsrfConfig::default())
     }
 
     /// Create CSRF layer with custom configuration
+    ///
+    /// Double-submit cookies are signed with the state's cookie signer.
     #[must_use]
     pub fn with_config(state: &ActonHtmxState, config: CsrfConfig) -> Self {
         Self {
             config,
             csrf_manager: state.csrf_manager().clone(),
+            signer: state.cookie_signer().clone(),
         }
     }
 
     /// Create CSRF layer from an existing agent handle
     #[must_use]
     pub fn from_handle(csrf_manager: AgentHandle) -> Self {
-        Self {
-            config: CsrfConfig::default(),
-            csrf_manager,
-        }
+        Self::from_handle_with_config(csrf_manager, CsrfConfig::default())
     }
 
     /// Create CSRF layer from handle with custom configuration
+    ///
+    /// Double-submit cookies are signed with a random key unless
+    /// [`with_signer`](Self::with_signer) is called.
     #[must_use]
-    pub const fn from_handle_with_config(csrf_manager: AgentHandle, config: CsrfConfig) -> Self {
+    pub fn from_handle_with_config(csrf_manager: AgentHandle, config: CsrfConfig) -> Self {
         Self {
             config,
             csrf_manager,
+            signer: CookieSigner::random(),
         }
     }
+
+    /// Sign double-submit cookies with `signer`
+    #[must_use]
+    pub fn with_signer(mut self, signer: CookieSigner) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// Sign double-submit cookies with `secret_key`
+    #[must_use]
+    pub fn with_secret_key(self, secret_key: impl AsRef<[u8]>) -> Self {
+        self.with_signer(CookieSigner::new(secret_key))
+    }
 }
 
 impl<S> Layer<S> for CsrfLayer {
@@ -143,19 +252,22 @@ impl<S> Layer<S> for CsrfLayer {
             inner,
             config: Arc::new(self.config.clone()),
             csrf_manager: self.csrf_manager.clone(),
+            signer: self.signer.for_purpose(SIGNING_PURPOSE),
         }
     }
 }
 
 /// CSRF middleware that validates tokens on state-changing requests
 ///
-/// Automatically validates CSRF tokens from the `CsrfManagerAgent` on
-/// POST, PUT, DELETE, and PATCH requests.
+/// Validates CSRF tokens on POST, PUT, DELETE, and PATCH requests, either
+/// with the `CsrfManagerAgent` or against the signed CSRF cookie depending
+/// on [`CsrfConfig::mode`].
 #[derive(Clone)]
 pub struct CsrfMiddleware<S> {
     inner: S,
     config: Arc<CsrfConfig>,
     csrf_manager: AgentHandle,
+    signer: CookieSigner,
 }
 
 impl<S: std::fmt::Debug> std::fmt::Debug for CsrfMiddleware<S> {
@@ -164,6 +276,7 @@ impl<S: std::fmt::Debug> std::fmt::Debug for CsrfMiddleware<S> {
             .field("inner", &self.inner)
             .field("config", &self.config)
             .field("csrf_manager", &"AgentHandle")
+            .field("signer", &self.signer)
             .finish()
     }
 }
@@ -185,64 +298,142 @@ where
 
     fn call(&mut self, req: Request) -> Self::Future {
         let config = self.config.clone();
-        let csrf_manager = self.csrf_manager.clone();
-        let mut inner = self.inner.clone();
-        let timeout = Duration::from_millis(config.agent_timeout_ms);
-
-        // Skip CSRF validation for idempotent methods
-        if is_method_safe(req.method()) {
-            return Box::pin(inner.call(req));
+        let inner = self.inner.clone();
+
+        match config.mode {
+            CsrfMode::Synchronizer => Box::pin(validate_synchronizer(
+                req,
+                inner,
+                config,
+                self.csrf_manager.clone(),
+            )),
+            CsrfMode::DoubleSubmit => Box::pin(validate_double_submit(
+                req,
+                inner,
+                config,
+                self.signer.clone(),
+            )),
         }
+    }
+}
+
+/// Validate the request against the session's token in the `CsrfManagerAgent`
+async fn validate_synchronizer<S>(
+    req: Request,
+    mut inner: S,
+    config: Arc<CsrfConfig>,
+    csrf_manager: AgentHandle,
+) -> Result<Response<Body>, S::Error>
+where
+    S: Service<Request, Response = Response<Body>>,
+{
+    // Skip CSRF validation for idempotent methods and configured paths
+    if is_method_safe(req.method()) || is_path_skipped(&req, &config) {
+        return inner.call(req).await;
+    }
 
-        // Skip CSRF validation for configured paths
-        let path = req.uri().path().to_string();
-        if config.skip_paths.iter().any(|skip| skip == &path) {
-            return Box::pin(inner.call(req));
+    // Get session ID from request extensions (set by SessionMiddleware)
+    let Some(session_id) = req.extensions().get::<SessionId>().cloned() else {
+        tracing::warn!("CSRF middleware requires SessionMiddleware to be applied first");
+        return Ok(csrf_validation_error(
+            "Session not found - ensure SessionMiddleware is applied",
+        ));
+    };
+
+    let (req, token) = extract_csrf_token(req, &config).await;
+    let Some(token) = token else {
+        tracing::warn!(
+            "CSRF token missing for {} {}",
+            req.method(),
+            req.uri().path()
+        );
+        return Ok(csrf_validation_error("CSRF token missing"));
+    };
+
+    // Validate token with CSRF manager
+    let timeout = Duration::from_millis(config.agent_timeout_ms);
+    let (validate_request, rx) = ValidateToken::new(session_id, token);
+    csrf_manager.send(validate_request).await;
+
+    let is_valid = match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(valid)) => valid,
+        Ok(Err(_)) => {
+            tracing::error!("CSRF validation channel error");
+            false
         }
+        Err(_) => {
+            tracing::error!("CSRF validation timeout");
+            false
+        }
+    };
 
-        // Get session ID from request extensions (set by SessionMiddleware)
-        let Some(session_id) = req.extensions().get::<SessionId>().cloned() else {
-            tracing::warn!("CSRF middleware requires SessionMiddleware to be applied first");
-            return Box::pin(async move {
-                Ok(csrf_validation_error(
-                    "Session not found - ensure SessionMiddleware is applied",
-                ))
-            });
-        };
+    if !is_valid {
+        tracing::warn!("CSRF token validation failed");
+        return Ok(csrf_validation_error("CSRF token validation failed"));
+    }
 
-        // Extract CSRF token from request header
-        let Some(token) = extract_csrf_token(&req, &config) else {
-            let method = req.method().clone();
-            tracing::warn!("CSRF token missing for {} {}", method, path);
-            return Box::pin(async move { Ok(csrf_validation_error("CSRF token missing")) });
-        };
+    // Token validated - proceed with request
+    inner.call(req).await
+}
 
-        Box::pin(async move {
-            // Validate token with CSRF manager
-            let (validate_request, rx) = ValidateToken::new(session_id, token);
-            csrf_manager.send(validate_request).await;
+/// Validate the request against the token in the signed CSRF cookie
+///
+/// Requests without a valid cookie that are not validated (safe methods,
+/// skipped paths) are issued a fresh token. The current token is inserted
+/// into request extensions for `CsrfTokenExtractor`.
+///
+/// Cookies are signed for the request's session, if any, so a cookie
+/// issued to another session is treated as missing.
+async fn validate_double_submit<S>(
+    mut req: Request,
+    mut inner: S,
+    config: Arc<CsrfConfig>,
+    signer: CookieSigner,
+) -> Result<Response<Body>, S::Error>
+where
+    S: Service<Request, Response = Response<Body>>,
+{
+    let signer = session_signer(&req, &signer);
+    let cookie_token = extract_cookie_token(&req, &config.cookie_name, &signer);
 
-            let is_valid = match tokio::time::timeout(timeout, rx).await {
-                Ok(Ok(valid)) => valid,
-                Ok(Err(_)) => {
-                    tracing::error!("CSRF validation channel error");
-                    false
-                }
-                Err(_) => {
-                    tracing::error!("CSRF validation timeout");
-                    false
-                }
-            };
+    if is_method_safe(req.method()) || is_path_skipped(&req, &config) {
+        let (token, issued) =
+            cookie_token.map_or_else(|| (CsrfToken::generate(), true), |token| (token, false));
+        req.extensions_mut().insert(token.clone());
 
-            if !is_valid {
-                tracing::warn!("CSRF token validation failed");
-                return Ok(csrf_validation_error("CSRF token validation failed"));
-            }
+        let mut response = inner.call(req).await?;
+        if issued {
+            set_csrf_cookie(&mut response, &token, &signer, &config);
+        }
+        return Ok(response);
+    }
+
+    let Some(expected) = cookie_token else {
+        tracing::warn!(
+            "CSRF cookie missing for {} {}",
+            req.method(),
+            req.uri().path()
+        );
+        return Ok(csrf_validation_error("CSRF cookie missing"));
+    };
+
+    let (mut req, token) = extract_csrf_token(req, &config).await;
+    let Some(token) = token else {
+        tracing::warn!(
+            "CSRF token missing for {} {}",
+            req.method(),
+            req.uri().path()
+        );
+        return Ok(csrf_validation_error("CSRF token missing"));
+    };
 
-            // Token validated - proceed with request
-            inner.call(req).await
-        })
+    if !signer.constant_time_eq(token.as_str(), expected.as_str()) {
+        tracing::warn!("CSRF token does not match CSRF cookie");
+        return Ok(csrf_validation_error("CSRF token validation failed"));
     }
+
+    req.extensions_mut().insert(expected);
+    inner.call(req).await
 }
 
 /// Check if HTTP method is considered safe (doesn't modify state)
@@ -253,21 +444,142 @@ const fn is_method_safe(method: &Method) -> bool {
     )
 }
 
+/// Check if the request path is configured to skip CSRF validation
+fn is_path_skipped(req: &Request, config: &CsrfConfig) -> bool {
+    let path = req.uri().path();
+    config
+        .skip_paths
+        .iter()
+        .any(|pattern| route_pattern_matches(pattern, path))
+}
+
+/// Match a path against an axum route pattern
+///
+/// Like axum, compares the raw (still percent-encoded) path segment by
+/// segment: `{name}` matches any non-empty segment and `{*name}` matches the
+/// non-empty remainder of the path.
+fn route_pattern_matches(pattern: &str, path: &str) -> bool {
+    let mut path_segments = path.split('/');
+    for pattern_segment in pattern.split('/') {
+        if pattern_segment.starts_with("{*") && pattern_segment.ends_with('}') {
+            let rest: Vec<_> = path_segments.collect();
+            return rest.iter().any(|segment| !segment.is_empty());
+        }
+
+        let Some(path_segment) = path_segments.next() else {
+            return false;
+        };
+        let is_param = pattern_segment.len() > 2
+            && pattern_segment.starts_with('{')
+            && pattern_segment.ends_with('}')
+            && !pattern_segment.starts_with("{{");
+        let matches = if is_param {
+            !path_segment.is_empty()
+        } else {
+            pattern_segment.replace("{{", "{").replace("}}", "}") == path_segment
+        };
+        if !matches {
+            return false;
+        }
+    }
+    path_segments.next().is_none()
+}
+
 /// Extract CSRF token from request (header or form data)
-fn extract_csrf_token(req: &Request, config: &CsrfConfig) -> Option<CsrfToken> {
-    // First, try to get token from header
-    if let Some(token_value) = req.headers().get(&config.header_name) {
-        if let Ok(token_str) = token_value.to_str() {
-            return Some(CsrfToken::from_string(token_str.to_string()));
+///
+/// The header takes precedence. Otherwise an urlencoded form body is
+/// buffered and searched for the form field; the returned request carries
+/// the same body for the handler.
+async fn extract_csrf_token(req: Request, config: &CsrfConfig) -> (Request, Option<CsrfToken>) {
+    let header_token = req
+        .headers()
+        .get(&config.header_name)
+        .and_then(|value| value.to_str().ok())
+        .map(|token| CsrfToken::from_string(token.to_string()));
+    if header_token.is_some() {
+        return (req, header_token);
+    }
+
+    if !is_form_request(&req) {
+        return (req, None);
+    }
+
+    let (parts, body) = req.into_parts();
+    match axum::body::to_bytes(body, MAX_FORM_BODY_BYTES).await {
+        Ok(bytes) => {
+            let token = form_field_token(&bytes, &config.form_field);
+            (Request::from_parts(parts, Body::from(bytes)), token)
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to buffer form body for CSRF validation");
+            (Request::from_parts(parts, Body::empty()), None)
         }
     }
+}
+
+/// Check whether the request carries an urlencoded form body
+fn is_form_request(req: &Request) -> bool {
+    req.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"))
+}
 
-    // If not in header, check if it's form data
-    // Note: This is a simplified implementation. In production, you'd want to
-    // properly parse form data without consuming the body.
-    // For now, we'll just check the header.
+/// Find the CSRF form field in an urlencoded body
+fn form_field_token(body: &[u8], form_field: &str) -> Option<CsrfToken> {
+    serde_html_form::from_bytes::<Vec<(String, String)>>(body)
+        .ok()?
+        .into_iter()
+        .find(|(name, _)| name == form_field)
+        .map(|(_, value)| CsrfToken::from_string(value))
+}
 
-    None
+/// Signer for the double-submit cookie of the request's session
+///
+/// Requests without a `SessionId` use `signer` unchanged.
+fn session_signer(req: &Request, signer: &CookieSigner) -> CookieSigner {
+    req.extensions().get::<SessionId>().map_or_else(
+        || signer.clone(),
+        |session_id| signer.for_purpose(&format!("session:{}", session_id.as_str())),
+    )
+}
+
+/// Extract the token from a correctly signed CSRF cookie
+fn extract_cookie_token(
+    req: &Request,
+    cookie_name: &str,
+    signer: &CookieSigner,
+) -> Option<CsrfToken> {
+    let cookie_str = req.headers().get(COOKIE)?.to_str().ok()?;
+
+    cookie_str
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| name.trim() == cookie_name)
+        .and_then(|(_, value)| signer.verify(value.trim()))
+        .map(CsrfToken::from_string)
+}
+
+/// Set the signed CSRF cookie on response
+fn set_csrf_cookie(
+    response: &mut Response<Body>,
+    token: &CsrfToken,
+    signer: &CookieSigner,
+    config: &CsrfConfig,
+) {
+    let value = signer.sign(token.as_str());
+    let mut cookie_value = format!(
+        "{}={value}; Path=/; SameSite=Lax; HttpOnly",
+        config.cookie_name
+    );
+
+    if config.cookie_secure {
+        cookie_value.push_str("; Secure");
+    }
+
+    if let Ok(header_value) = cookie_value.parse() {
+        response.headers_mut().append(SET_COOKIE, header_value);
+    }
 }
 
 /// Create a 403 Forbidden response for CSRF validation failure
@@ -294,6 +606,8 @@ mod tests {
         assert_eq!(config.form_field, CSRF_FORM_FIELD);
         assert_eq!(config.agent_timeout_ms, 100);
         assert!(config.skip_paths.is_empty());
+        assert_eq!(config.mode, CsrfMode::Synchronizer);
+        assert_eq!(config.cookie_name, CSRF_COOKIE_NAME);
     }
 
     #[test]
@@ -314,6 +628,29 @@ mod tests {
         assert!(config.skip_paths.contains(&"/metrics".to_string()));
     }
 
+    #[test]
+    fn test_route_pattern_matches() {
+        assert!(route_pattern_matches("/webhooks/stripe", "/webhooks/stripe"));
+        assert!(!route_pattern_matches("/webhooks/stripe", "/webhooks/stripe/"));
+        assert!(!route_pattern_matches("/webhooks/stripe", "/webhooks/github"));
+        assert!(!route_pattern_matches("/webhooks", "/webhooks/stripe"));
+
+        assert!(route_pattern_matches("/webhooks/{provider}", "/webhooks/github"));
+        assert!(route_pattern_matches("/hooks/{id}/events", "/hooks/42/events"));
+        assert!(!route_pattern_matches("/webhooks/{provider}", "/webhooks/"));
+        assert!(!route_pattern_matches("/webhooks/{provider}", "/webhooks/a/b"));
+        assert!(!route_pattern_matches("/webhooks/{provider}", "/webhooks"));
+
+        assert!(route_pattern_matches("/api/{*rest}", "/api/v1"));
+        assert!(route_pattern_matches("/api/{*rest}", "/api/v1/users/7"));
+        assert!(!route_pattern_matches("/api/{*rest}", "/api/"));
+        assert!(!route_pattern_matches("/api/{*rest}", "/api"));
+        assert!(!route_pattern_matches("/api/{*rest}", "/apis/v1"));
+
+        assert!(route_pattern_matches("/literal/{{id}}", "/literal/{id}"));
+        assert!(!route_pattern_matches("/literal/{{id}}", "/literal/7"));
+    }
+
     #[test]
     fn test_is_method_safe() {
         assert!(is_method_safe(&Method::GET));
@@ -326,4 +663,245 @@ mod tests {
         assert!(!is_method_safe(&Method::DELETE));
         assert!(!is_method_safe(&Method::PATCH));
     }
+
+    #[test]
+    fn test_cookie_token_round_trip_and_tampering() {
+        let signer = CookieSigner::new(b"secret").for_purpose(SIGNING_PURPOSE);
+        let token = CsrfToken::generate();
+        let mut response = Response::new(Body::empty());
+        set_csrf_cookie(&mut response, &token, &signer, &CsrfConfig::default());
+        let cookie = response.headers()[SET_COOKIE].to_str().unwrap();
+        let cookie = cookie.split(';').next().unwrap().to_string();
+
+        let extract = |signer: &CookieSigner, cookie: &str| {
+            let request = Request::get("/").header(COOKIE, cookie).body(Body::empty()).unwrap();
+            extract_cookie_token(&request, CSRF_COOKIE_NAME, signer)
+        };
+        assert_eq!(extract(&signer, &cookie), Some(token));
+
+        let other_key = CookieSigner::new(b"other-secret").for_purpose(SIGNING_PURPOSE);
+        assert_eq!(extract(&other_key, &cookie), None);
+        // Signed with the right key for another purpose
+        let session = CookieSigner::new(b"secret").for_purpose("session");
+        assert_eq!(extract(&session, &cookie), None);
+        assert_eq!(extract(&signer, &format!("{CSRF_COOKIE_NAME}=forged.1.c2lnbmF0dXJl")), None);
+        assert_eq!(extract(&signer, &format!("{CSRF_COOKIE_NAME}=no-signature")), None);
+    }
+
+    #[test]
+    fn test_form_field_token() {
+        let token = form_field_token(b"name=Ada&_csrf_token=abc%2D123", CSRF_FORM_FIELD);
+        assert_eq!(token.unwrap().as_str(), "abc-123");
+        assert!(form_field_token(b"name=Ada", CSRF_FORM_FIELD).is_none());
+    }
+
+    mod exempt_paths {
+        use super::*;
+        use crate::htmx::agents::CsrfManagerAgent;
+        use acton_reactive::prelude::ActonApp;
+        use axum::{routing::post, Router};
+        use tower::ServiceExt;
+
+        async fn app() -> Router {
+            let mut runtime = ActonApp::launch();
+            let csrf_manager = CsrfManagerAgent::spawn(&mut runtime).await.unwrap();
+            let config = CsrfConfig::new().skip_path("/webhooks/{provider}");
+
+            Router::new()
+                .route("/webhooks/{provider}", post(|| async { "received" }))
+                .route("/account", post(|| async { "updated" }))
+                .layer(CsrfLayer::from_handle_with_config(csrf_manager, config))
+                .layer(axum::Extension(SessionId::generate()))
+        }
+
+        async fn post_status(app: &Router, path: &str) -> StatusCode {
+            let request = Request::post(path).body(Body::from("{}")).unwrap();
+            app.clone().oneshot(request).await.unwrap().status()
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_exempt_path_bypasses_csrf() {
+            let app = app().await;
+            assert_eq!(post_status(&app, "/webhooks/stripe").await, StatusCode::OK);
+            assert_eq!(post_status(&app, "/webhooks/github").await, StatusCode::OK);
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_non_exempt_post_is_checked() {
+            let app = app().await;
+            assert_eq!(post_status(&app, "/account").await, StatusCode::FORBIDDEN);
+        }
+    }
+
+    mod double_submit {
+        use super::*;
+        use crate::htmx::agents::CsrfManagerAgent;
+        use acton_reactive::prelude::ActonApp;
+        use axum::{extract::Extension, routing::get, Router};
+        use tower::ServiceExt;
+
+        const SECRET: &str = "test-secret-key";
+
+        async fn app() -> Router {
+            let mut runtime = ActonApp::launch();
+            let csrf_manager = CsrfManagerAgent::spawn(&mut runtime).await.unwrap();
+            let config = CsrfConfig::new().mode(CsrfMode::DoubleSubmit);
+
+            Router::new()
+                .route(
+                    "/form",
+                    get(|Extension(token): Extension<CsrfToken>| async move { token.to_string() })
+                        .post(|body: String| async move { body }),
+                )
+                .layer(
+                    CsrfLayer::from_handle_with_config(csrf_manager, config)
+                        .with_secret_key(SECRET),
+                )
+        }
+
+        /// Fetch the form, returning the rendered token and the CSRF cookie
+        async fn issue_token(app: &Router) -> (String, String) {
+            let response = app
+                .clone()
+                .oneshot(Request::get("/form").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let set_cookie = response.headers()[SET_COOKIE].to_str().unwrap().to_string();
+            assert!(set_cookie.contains("HttpOnly"));
+            let cookie = set_cookie.split(';').next().unwrap().to_string();
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (String::from_utf8(body.to_vec()).unwrap(), cookie)
+        }
+
+        fn post_form(cookie: Option<&str>, body: String) -> Request {
+            let mut request =
+                Request::post("/form").header(CONTENT_TYPE, "application/x-www-form-urlencoded");
+            if let Some(cookie) = cookie {
+                request = request.header(COOKIE, cookie);
+            }
+            request.body(Body::from(body)).unwrap()
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_valid_round_trip() {
+            let app = app().await;
+            let (token, cookie) = issue_token(&app).await;
+
+            // Form field, with the body still readable by the handler
+            let form = format!("name=Ada&{CSRF_FORM_FIELD}={token}");
+            let response = app
+                .clone()
+                .oneshot(post_form(Some(&cookie), form.clone()))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().get(SET_COOKIE).is_none());
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(body, form.as_bytes());
+
+            // Header
+            let request = Request::post("/form")
+                .header(COOKIE, &cookie)
+                .header(CSRF_HEADER_NAME, &token)
+                .body(Body::empty())
+                .unwrap();
+            let response = app.oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_token_mismatch_is_rejected() {
+            let app = app().await;
+            let (_, cookie) = issue_token(&app).await;
+            let other = CsrfToken::generate();
+
+            let form = format!("{CSRF_FORM_FIELD}={other}");
+            let response = app.oneshot(post_form(Some(&cookie), form)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_missing_cookie_is_rejected() {
+            let app = app().await;
+            let (token, cookie) = issue_token(&app).await;
+
+            let form = format!("{CSRF_FORM_FIELD}={token}");
+            let response = app
+                .clone()
+                .oneshot(post_form(None, form.clone()))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+            // A cookie signed with another key counts as missing
+            let (name, value) = cookie.split_once('=').unwrap();
+            let cookie_token = CookieSigner::new(SECRET)
+                .for_purpose(SIGNING_PURPOSE)
+                .verify(value)
+                .unwrap();
+            assert_eq!(cookie_token, token);
+            let forged = format!(
+                "{name}={}",
+                CookieSigner::new(b"attacker-key")
+                    .for_purpose(SIGNING_PURPOSE)
+                    .sign(&cookie_token)
+            );
+            let response = app.oneshot(post_form(Some(&forged), form)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_cookie_is_bound_to_session() {
+            let app = app().await;
+            let session = SessionId::generate();
+
+            let mut request = Request::get("/form").body(Body::empty()).unwrap();
+            request.extensions_mut().insert(session.clone());
+            let response = app.clone().oneshot(request).await.unwrap();
+            let set_cookie = response.headers()[SET_COOKIE].to_str().unwrap();
+            let cookie = set_cookie.split(';').next().unwrap().to_string();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let token = String::from_utf8(body.to_vec()).unwrap();
+            let form = format!("{CSRF_FORM_FIELD}={token}");
+
+            let post = |session: Option<SessionId>| {
+                let mut request = post_form(Some(&cookie), form.clone());
+                if let Some(session) = session {
+                    request.extensions_mut().insert(session);
+                }
+                app.clone().oneshot(request)
+            };
+
+            let response = post(Some(session)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            // Another session, or no session, can't use the cookie
+            let response = post(Some(SessionId::generate())).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            let response = post(None).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn test_sessionless_cookie_is_rejected_for_session() {
+            let app = app().await;
+            // Issued without a session, e.g. to an attacker
+            let (token, cookie) = issue_token(&app).await;
+
+            let form = format!("{CSRF_FORM_FIELD}={token}");
+            let mut request = post_form(Some(&cookie), form);
+            request.extensions_mut().insert(SessionId::generate());
+            let response = app.oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+    }
 }
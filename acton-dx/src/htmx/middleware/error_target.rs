@@ -0,0 +1,417 @@
+//! HTMX error response targeting
+//!
+//! HTMX does not swap `4xx`/`5xx` responses by default, so a failing request
+//! silently does nothing. This middleware makes error responses to HTMX
+//! requests land somewhere visible:
+//! - `422 Unprocessable Entity` (validation) responses are retargeted to the
+//!   element that triggered the request, normally the form, and swapped with
+//!   `outerHTML` so the re-rendered form with errors replaces it
+//! - Other `4xx`/`5xx` responses are retargeted to a global error region
+//!   (default `#error-region`) and swapped with `innerHTML`
+//!
+//! Error bodies that are not already HTML (e.g. `"Internal Server Error"`)
+//! are wrapped in an alert fragment. Responses that already set
+//! `HX-Retarget` and non-HTMX requests are left untouched.
+//!
+//! # Client Configuration
+//!
+//! The browser must also be told to swap error responses. Either load the
+//! `response-targets` extension, or set `htmx.config.responseHandling`, e.g.
+//! by including [`ERROR_SWAP_META`] in the page `<head>`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::ErrorTargetExt;
+//! use axum::{routing::post, Router};
+//!
+//! let app: Router = Router::new()
+//!     .route("/posts", post(|| async { "created" }))
+//!     .with_error_target("#flash-errors");
+//! ```
+
+use crate::htmx::middleware::is_htmx_request;
+use crate::htmx::responses::SwapStrategy;
+use crate::htmx::template::escape_html;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    response::Response,
+    Router,
+};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Default selector of the global error region
+pub const DEFAULT_ERROR_TARGET: &str = "#error-region";
+
+/// `htmx-config` meta tag enabling swaps of `4xx`/`5xx` responses
+pub const ERROR_SWAP_META: &str = r#"<meta name="htmx-config" content='{"responseHandling":[{"code":"204","swap":false},{"code":"[23]..","swap":true},{"code":"[45]..","swap":true,"error":true}]}'>"#;
+
+/// Largest error body that is wrapped into a fragment
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// Renders an error fragment from the status and the original plain-text body
+pub type ErrorFragmentRenderer = Arc<dyn Fn(StatusCode, &str) -> String + Send + Sync>;
+
+/// Configuration for HTMX error targeting
+#[derive(Clone)]
+pub struct ErrorTargetConfig {
+    /// Selector for non-validation errors
+    pub error_target: String,
+    /// Swap strategy for non-validation errors
+    pub error_swap: SwapStrategy,
+    /// Retarget validation (`422`) errors to the triggering element
+    pub validation_to_trigger: bool,
+    /// Swap strategy for validation errors
+    pub validation_swap: SwapStrategy,
+    renderer: ErrorFragmentRenderer,
+}
+
+impl std::fmt::Debug for ErrorTargetConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ErrorTargetConfig")
+            .field("error_target", &self.error_target)
+            .field("error_swap", &self.error_swap)
+            .field("validation_to_trigger", &self.validation_to_trigger)
+            .field("validation_swap", &self.validation_swap)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for ErrorTargetConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_ERROR_TARGET)
+    }
+}
+
+impl ErrorTargetConfig {
+    /// Target non-validation errors at `error_target`
+    #[must_use]
+    pub fn new(error_target: impl Into<String>) -> Self {
+        Self {
+            error_target: error_target.into(),
+            error_swap: SwapStrategy::InnerHTML,
+            validation_to_trigger: true,
+            validation_swap: SwapStrategy::OuterHTML,
+            renderer: Arc::new(default_fragment),
+        }
+    }
+
+    /// Set the swap strategy for non-validation errors
+    #[must_use]
+    pub const fn with_error_swap(mut self, swap: SwapStrategy) -> Self {
+        self.error_swap = swap;
+        self
+    }
+
+    /// Enable or disable retargeting `422` responses to the triggering element
+    #[must_use]
+    pub const fn with_validation_to_trigger(mut self, enabled: bool) -> Self {
+        self.validation_to_trigger = enabled;
+        self
+    }
+
+    /// Set the swap strategy for validation errors
+    #[must_use]
+    pub const fn with_validation_swap(mut self, swap: SwapStrategy) -> Self {
+        self.validation_swap = swap;
+        self
+    }
+
+    /// Render non-HTML error bodies with a custom fragment
+    #[must_use]
+    pub fn with_renderer<F>(mut self, renderer: F) -> Self
+    where
+        F: Fn(StatusCode, &str) -> String + Send + Sync + 'static,
+    {
+        self.renderer = Arc::new(renderer);
+        self
+    }
+}
+
+/// Default error fragment: an escaped message in an alert
+fn default_fragment(status: StatusCode, message: &str) -> String {
+    let message = if message.trim().is_empty() {
+        status.canonical_reason().unwrap_or("Error")
+    } else {
+        message
+    };
+    format!(
+        r#"<div class="alert alert-error" role="alert" data-status="{}">{}</div>"#,
+        status.as_u16(),
+        escape_html(message)
+    )
+}
+
+/// Layer that retargets HTMX error responses
+#[derive(Debug, Clone, Default)]
+pub struct ErrorTargetLayer {
+    config: Arc<ErrorTargetConfig>,
+}
+
+impl ErrorTargetLayer {
+    /// Create an error targeting layer
+    #[must_use]
+    pub fn new(config: ErrorTargetConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<S> Layer<S> for ErrorTargetLayer {
+    type Service = ErrorTargetMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ErrorTargetMiddleware {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+/// HTMX error targeting middleware service
+#[derive(Debug, Clone)]
+pub struct ErrorTargetMiddleware<S> {
+    inner: S,
+    config: Arc<ErrorTargetConfig>,
+}
+
+impl<S> Service<Request> for ErrorTargetMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if !is_htmx_request(req.headers()) {
+            return Box::pin(self.inner.call(req));
+        }
+
+        let trigger = req
+            .headers()
+            .get("HX-Trigger")
+            .and_then(|v| v.to_str().ok())
+            .filter(|id| !id.is_empty())
+            .map(|id| format!("#{id}"));
+        let config = self.config.clone();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = future.await?;
+            let status = response.status();
+
+            if !(status.is_client_error() || status.is_server_error())
+                || response.headers().contains_key("HX-Retarget")
+            {
+                return Ok(response);
+            }
+
+            Ok(retarget(response, &config, trigger).await)
+        })
+    }
+}
+
+/// Add retarget headers and wrap non-HTML bodies in an error fragment
+async fn retarget(
+    response: Response,
+    config: &ErrorTargetConfig,
+    trigger: Option<String>,
+) -> Response {
+    let status = response.status();
+    let (mut parts, body) = response.into_parts();
+
+    let (target, swap) = if status == StatusCode::UNPROCESSABLE_ENTITY {
+        let target = trigger.filter(|_| config.validation_to_trigger);
+        (target, config.validation_swap)
+    } else {
+        (Some(config.error_target.clone()), config.error_swap)
+    };
+
+    if let Some(value) = target.and_then(|t| HeaderValue::from_str(&t).ok()) {
+        parts.headers.insert("HX-Retarget", value);
+    }
+    parts
+        .headers
+        .insert("HX-Reswap", HeaderValue::from_static(swap.as_str()));
+
+    let is_html = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    if is_html {
+        return Response::from_parts(parts, body);
+    }
+
+    let message = axum::body::to_bytes(body, MAX_ERROR_BODY)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .unwrap_or_default();
+    let fragment = (config.renderer)(status, &message);
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    Response::from_parts(parts, Body::from(fragment))
+}
+
+/// Router helper for configuring HTMX error targeting
+pub trait ErrorTargetExt {
+    /// Retarget HTMX error responses to `selector` (validation errors still
+    /// go to the triggering form)
+    #[must_use]
+    fn with_error_target(self, selector: impl Into<String>) -> Self;
+
+    /// Retarget HTMX error responses using a full configuration
+    #[must_use]
+    fn with_error_target_config(self, config: ErrorTargetConfig) -> Self;
+}
+
+impl<S> ErrorTargetExt for Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn with_error_target(self, selector: impl Into<String>) -> Self {
+        self.with_error_target_config(ErrorTargetConfig::new(selector))
+    }
+
+    fn with_error_target_config(self, config: ErrorTargetConfig) -> Self {
+        self.layer(ErrorTargetLayer::new(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        response::{Html, IntoResponse},
+        routing::post,
+    };
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/validate",
+                post(|| async {
+                    (StatusCode::UNPROCESSABLE_ENTITY, Html("<form id=\"post-form\">errors</form>"))
+                }),
+            )
+            .route(
+                "/crash",
+                post(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "db <down>") }),
+            )
+            .route(
+                "/handled",
+                post(|| async {
+                    (
+                        StatusCode::NOT_FOUND,
+                        [("HX-Retarget", "#custom")],
+                        "missing",
+                    )
+                        .into_response()
+                }),
+            )
+            .with_error_target("#errors")
+    }
+
+    fn htmx_post(uri: &str) -> Request {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("HX-Request", "true")
+            .header("HX-Trigger", "post-form")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_validation_error_targets_form() {
+        let response = app().oneshot(htmx_post("/validate")).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(response.headers().get("HX-Retarget").unwrap(), "#post-form");
+        assert_eq!(response.headers().get("HX-Reswap").unwrap(), "outerHTML");
+        assert_eq!(
+            body_string(response).await,
+            "<form id=\"post-form\">errors</form>"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_error_targets_global_region() {
+        let response = app().oneshot(htmx_post("/crash")).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.headers().get("HX-Retarget").unwrap(), "#errors");
+        assert_eq!(response.headers().get("HX-Reswap").unwrap(), "innerHTML");
+        assert!(response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+
+        let body = body_string(response).await;
+        assert!(body.contains("role=\"alert\""));
+        assert!(body.contains("db &lt;down&gt;"));
+    }
+
+    #[tokio::test]
+    async fn test_existing_retarget_and_non_htmx_untouched() {
+        let response = app().oneshot(htmx_post("/handled")).await.unwrap();
+        assert_eq!(response.headers().get("HX-Retarget").unwrap(), "#custom");
+        assert!(response.headers().get("HX-Reswap").is_none());
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/crash")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert!(response.headers().get("HX-Retarget").is_none());
+        assert_eq!(body_string(response).await, "db <down>");
+    }
+
+    #[tokio::test]
+    async fn test_custom_renderer() {
+        let app = Router::new()
+            .route(
+                "/crash",
+                post(|| async { StatusCode::SERVICE_UNAVAILABLE }),
+            )
+            .with_error_target_config(ErrorTargetConfig::default().with_renderer(
+                |status, _| format!("<p>{}</p>", status.as_u16()),
+            ));
+
+        let response = app.oneshot(htmx_post("/crash")).await.unwrap();
+        assert_eq!(
+            response.headers().get("HX-Retarget").unwrap(),
+            DEFAULT_ERROR_TARGET
+        );
+        assert_eq!(body_string(response).await, "<p>503</p>");
+    }
+}
@@ -3,8 +3,11 @@
 //! This module provides middleware for serving uploaded files with:
 //! - Range request support for streaming and resumable downloads
 //! - Proper cache headers (ETag, Last-Modified, Cache-Control)
+//! - Conditional requests (`If-None-Match`, `If-Modified-Since`, `If-Range`)
 //! - CDN integration hints
 //! - Access control for private files
+//! - Precompressed `.br`/`.gz` variants selected by `Accept-Encoding`
+//! - Redirects to presigned backend URLs (e.g., S3) instead of proxying bytes
 //!
 //! # Examples
 //!
@@ -52,25 +55,99 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! ## Conditional requests
+//!
+//! Responses carry a strong ETag derived from a hash of the served bytes,
+//! and `Last-Modified` when the storage backend reports modification times
+//! ([`FileStorage::last_modified`]). A request whose `If-None-Match` matches
+//! the ETag, or (without `If-None-Match`) whose `If-Modified-Since` is not
+//! older than the file, gets `304 Not Modified` with no body. `If-Range`
+//! accepts either validator; a range is only served when it matches,
+//! otherwise the full file is returned.
+//!
+//! Use [`FileServingMiddleware::serve_for`] to run the access control
+//! function first, so a `304` is never returned for a file the user may not
+//! read.
+//!
+//! ## With precompressed variants
+//!
+//! Assets compressed at build time are stored next to the original under the
+//! original ID plus an extension (`app.css` → `app.css.br`, `app.css.gz`).
+//! The first configured encoding that the client accepts and that exists in
+//! storage is served with `Content-Encoding`; otherwise the raw file is
+//! served. Raw responses are left for an outer compression layer to
+//! compress on the fly, if one is installed.
+//!
+//! Each variant gets its own ETag (its bytes differ), and range requests are disabled for
+//! compressed responses (`Accept-Ranges: none`) since byte offsets into the
+//! encoded body are not meaningful to clients.
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::{FileServingMiddleware, PrecompressedEncoding};
+//! use acton_dx::htmx::storage::LocalFileStorage;
+//! use axum::http::HeaderMap;
+//! use std::path::PathBuf;
+//! use std::sync::Arc;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let storage = Arc::new(LocalFileStorage::new(PathBuf::from("/var/assets"))?);
+//! let files = FileServingMiddleware::new(storage)
+//!     .with_precompressed([PrecompressedEncoding::Brotli, PrecompressedEncoding::Gzip]);
+//!
+//! let response = files.serve("app.css", &HeaderMap::new()).await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## With presigned URL redirects
+//!
+//! When the storage backend can sign URLs ([`FileStorage::presigned_url`]
+//! returns `Some`), the client is redirected to the signed URL instead:
+//! HTMX requests get an `HX-Redirect`, other requests a `307 Temporary
+//! Redirect`. Backends without signing support are served as usual.
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::FileServingMiddleware;
+//! use acton_dx::htmx::storage::LocalFileStorage;
+//! use axum::http::HeaderMap;
+//! use std::path::PathBuf;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let storage = Arc::new(LocalFileStorage::new(PathBuf::from("/var/uploads"))?);
+//! let files = FileServingMiddleware::new(storage)
+//!     .with_presigned_urls(Duration::from_secs(300));
+//!
+//! let response = files.serve("550e8400-e29b-41d4-a716-446655440000", &HeaderMap::new()).await;
+//! # Ok(())
+//! # }
+//! ```
 
+use crate::htmx::middleware::is_htmx_request;
+use crate::htmx::responses::HxRedirect;
 use crate::htmx::storage::{FileStorage, StorageError, StorageResult};
 use axum::{
     body::Body,
     extract::{Path, State},
     http::{
         header::{
-            ACCEPT_RANGES, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, ETAG,
-            IF_NONE_MATCH, IF_RANGE, LAST_MODIFIED, RANGE,
+            ACCEPT_ENCODING, ACCEPT_RANGES, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH,
+            CONTENT_RANGE, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE,
+            LAST_MODIFIED, RANGE, VARY,
         },
         HeaderMap, HeaderValue, StatusCode,
     },
-    response::{IntoResponse, Response},
+    response::{IntoResponse, Redirect, Response},
 };
+use httpdate::HttpDate;
+use sha2::{Digest, Sha256};
 use std::{
     future::Future,
     pin::Pin,
     sync::Arc,
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 /// Access control function type for file serving
@@ -82,17 +159,51 @@ pub type FileAccessControl = Arc<
         + Sync,
 >;
 
+/// Content encoding of a precompressed file variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrecompressedEncoding {
+    /// Brotli (`.br`, `Content-Encoding: br`)
+    Brotli,
+    /// Gzip (`.gz`, `Content-Encoding: gzip`)
+    Gzip,
+}
+
+impl PrecompressedEncoding {
+    /// `Content-Encoding` token for this encoding
+    #[must_use]
+    pub const fn content_encoding(self) -> &'static str {
+        match self {
+            Self::Brotli => "br",
+            Self::Gzip => "gzip",
+        }
+    }
+
+    /// File extension of the precompressed variant
+    #[must_use]
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Brotli => "br",
+            Self::Gzip => "gz",
+        }
+    }
+
+    /// Storage ID of the variant of `file_id` in this encoding
+    #[must_use]
+    pub fn variant_id(self, file_id: &str) -> String {
+        format!("{file_id}.{}", self.extension())
+    }
+}
+
 /// Middleware for serving files with range requests, caching, and access control
 #[derive(Clone)]
 pub struct FileServingMiddleware<S: FileStorage> {
-    #[allow(dead_code)] // Used in future layer implementation
     storage: Arc<S>,
-    #[allow(dead_code)] // Used in future layer implementation
     access_control: Option<FileAccessControl>,
-    #[allow(dead_code)] // Used in future layer implementation
     cache_max_age: u32,
     #[allow(dead_code)] // Used in future layer implementation
     enable_cdn_headers: bool,
+    precompressed: Vec<PrecompressedEncoding>,
+    presigned_expiry: Option<Duration>,
 }
 
 impl<S: FileStorage> FileServingMiddleware<S> {
@@ -104,6 +215,8 @@ impl<S: FileStorage> FileServingMiddleware<S> {
             access_control: None,
             cache_max_age: 86400, // 1 day default
             enable_cdn_headers: false,
+            precompressed: Vec::new(),
+            presigned_expiry: None,
         }
     }
 
@@ -127,6 +240,279 @@ impl<S: FileStorage> FileServingMiddleware<S> {
         self.enable_cdn_headers = true;
         self
     }
+
+    /// Serve precompressed variants, in order of preference
+    ///
+    /// For example `[Brotli, Gzip]` serves `{id}.br` to clients accepting
+    /// `br`, else `{id}.gz` to clients accepting `gzip`, else `{id}`.
+    #[must_use]
+    pub fn with_precompressed(
+        mut self,
+        encodings: impl IntoIterator<Item = PrecompressedEncoding>,
+    ) -> Self {
+        self.precompressed = encodings.into_iter().collect();
+        self
+    }
+
+    /// Redirect to presigned backend URLs valid for `expires_in`
+    ///
+    /// Only takes effect for backends whose [`FileStorage::presigned_url`]
+    /// returns `Some`; other files are served through the application.
+    #[must_use]
+    pub const fn with_presigned_urls(mut self, expires_in: Duration) -> Self {
+        self.presigned_expiry = Some(expires_in);
+        self
+    }
+
+    /// Serve a file to `user_id` if the access control function allows it
+    ///
+    /// Access is checked before any conditional or range header is
+    /// evaluated. Without an access control function this is the same as
+    /// [`serve`](Self::serve).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileServingError::AccessDenied`] if access is refused, and
+    /// the errors of [`serve`](Self::serve) otherwise.
+    pub async fn serve_for(
+        &self,
+        user_id: Option<String>,
+        file_id: &str,
+        headers: &HeaderMap,
+    ) -> Result<Response, FileServingError> {
+        if let Some(access_control) = &self.access_control {
+            let allowed = access_control(user_id, file_id.to_string())
+                .await
+                .map_err(FileServingError::Storage)?;
+            if !allowed {
+                return Err(FileServingError::AccessDenied);
+            }
+        }
+
+        self.serve(file_id, headers).await
+    }
+
+    /// Serve a file, honoring conditional, range, and encoding headers
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FileServingError`] if the file cannot be read from storage
+    /// or the range request is invalid.
+    pub async fn serve(
+        &self,
+        file_id: &str,
+        headers: &HeaderMap,
+    ) -> Result<Response, FileServingError> {
+        if let Some(expires_in) = self.presigned_expiry {
+            if let Some(url) = self
+                .storage
+                .presigned_url(file_id, expires_in)
+                .await
+                .map_err(FileServingError::Storage)?
+            {
+                return Ok(presigned_redirect(url, headers));
+            }
+        }
+
+        // Retrieve file metadata for content type and other info
+        let metadata = self
+            .storage
+            .get_metadata(file_id)
+            .await
+            .map_err(FileServingError::Storage)?;
+
+        // Use content type from metadata, with mime_guess fallback
+        let content_type = if !metadata.content_type.is_empty()
+            && metadata.content_type != "application/octet-stream"
+        {
+            metadata.content_type
+        } else {
+            // Fallback to MIME type detection from filename
+            mime_guess::from_path(&metadata.filename)
+                .first_or_octet_stream()
+                .to_string()
+        };
+
+        let encoding = self.select_precompressed(file_id, headers).await;
+        let data_id = encoding.map_or_else(|| file_id.to_string(), |e| e.variant_id(file_id));
+
+        // Retrieve file data
+        let data = self
+            .storage
+            .retrieve(&data_id)
+            .await
+            .map_err(FileServingError::Storage)?;
+        let last_modified = self
+            .storage
+            .last_modified(&data_id)
+            .await
+            .map_err(FileServingError::Storage)?;
+
+        let etag = content_etag(&data);
+
+        let mut response = if is_not_modified(headers, &etag, last_modified) {
+            not_modified_response(&etag, last_modified)
+        } else if let Some(encoding) = encoding {
+            // Byte ranges are not offered on encoded bodies
+            let mut response =
+                build_file_response(data, &etag, last_modified, &content_type, None);
+            let response_headers = response.headers_mut();
+            response_headers.insert(
+                CONTENT_ENCODING,
+                HeaderValue::from_static(encoding.content_encoding()),
+            );
+            response_headers.insert(ACCEPT_RANGES, HeaderValue::from_static("none"));
+            response
+        } else if let Some(range_header) = headers.get(RANGE) {
+            serve_range_request(
+                &data,
+                range_header,
+                &etag,
+                last_modified,
+                &content_type,
+                headers,
+            )?
+        } else {
+            build_file_response(data, &etag, last_modified, &content_type, None)
+        };
+
+        // Sent with 304 too, so caches refresh their freshness lifetime
+        if let Ok(cache_control) =
+            HeaderValue::from_str(&format!("public, max-age={}", self.cache_max_age))
+        {
+            response.headers_mut().insert(CACHE_CONTROL, cache_control);
+        }
+
+        if !self.precompressed.is_empty() {
+            response
+                .headers_mut()
+                .insert(VARY, HeaderValue::from_static("accept-encoding"));
+        }
+
+        Ok(response)
+    }
+
+    /// Pick the first configured encoding the client accepts and storage has
+    async fn select_precompressed(
+        &self,
+        file_id: &str,
+        headers: &HeaderMap,
+    ) -> Option<PrecompressedEncoding> {
+        for &encoding in &self.precompressed {
+            if accepts_encoding(headers, encoding.content_encoding())
+                && self
+                    .storage
+                    .exists(&encoding.variant_id(file_id))
+                    .await
+                    .unwrap_or(false)
+            {
+                return Some(encoding);
+            }
+        }
+        None
+    }
+}
+
+/// Redirect the client to a presigned URL
+///
+/// HTMX requests get `HX-Redirect` so the browser navigates to the download
+/// instead of swapping the file into the page.
+fn presigned_redirect(url: String, headers: &HeaderMap) -> Response {
+    if is_htmx_request(headers) {
+        (HxRedirect(url), ()).into_response()
+    } else {
+        Redirect::temporary(&url).into_response()
+    }
+}
+
+/// Check whether `Accept-Encoding` allows `token` (explicitly or via `*`)
+fn accepts_encoding(headers: &HeaderMap, token: &str) -> bool {
+    let mut wildcard = None;
+
+    for value in headers
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+    {
+        for entry in value.split(',') {
+            let mut params = entry.split(';');
+            let coding = params.next().unwrap_or_default().trim();
+            let quality = params
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            if coding.eq_ignore_ascii_case(token) {
+                return quality > 0.0;
+            }
+            if coding == "*" {
+                wildcard = Some(quality > 0.0);
+            }
+        }
+    }
+
+    wildcard.unwrap_or(false)
+}
+
+/// Strong ETag for `data`: a truncated SHA-256 of the bytes being served
+fn content_etag(data: &[u8]) -> String {
+    let digest = Sha256::digest(data);
+    format!(r#""{}""#, hex::encode(&digest[..16]))
+}
+
+/// Whether the client's cached copy is current
+///
+/// `If-None-Match` takes precedence; `If-Modified-Since` is only consulted
+/// without it, and only when the modification time is known.
+fn is_not_modified(headers: &HeaderMap, etag: &str, last_modified: Option<SystemTime>) -> bool {
+    if let Some(if_none_match) = headers.get(IF_NONE_MATCH) {
+        return if_none_match
+            .to_str()
+            .is_ok_and(|value| etag_list_matches(value, etag));
+    }
+
+    let Some(last_modified) = last_modified else {
+        return false;
+    };
+    headers
+        .get(IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<HttpDate>().ok())
+        .is_some_and(|since| HttpDate::from(last_modified) <= since)
+}
+
+/// Weak comparison of `etag` against an `If-None-Match` list
+fn etag_list_matches(list: &str, etag: &str) -> bool {
+    list.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+/// Whether an `If-Range` validator still matches the file
+///
+/// ETags use strong comparison, so weak ETags never match; dates must equal
+/// the modification time exactly.
+fn if_range_matches(value: &str, etag: &str, last_modified: Option<SystemTime>) -> bool {
+    if value.starts_with('"') || value.starts_with("W/") {
+        return value == etag;
+    }
+    value
+        .parse::<HttpDate>()
+        .is_ok_and(|date| last_modified.is_some_and(|modified| HttpDate::from(modified) == date))
+}
+
+/// `304 Not Modified` carrying the validators of the current file
+fn not_modified_response(etag: &str, last_modified: Option<SystemTime>) -> Response {
+    let mut response = Response::builder()
+        .status(StatusCode::NOT_MODIFIED)
+        .header(ETAG, etag);
+    if let Some(last_modified) = last_modified {
+        response = response.header(LAST_MODIFIED, httpdate::fmt_http_date(last_modified));
+    }
+
+    response
+        .body(Body::empty())
+        .unwrap_or_else(|_| Response::new(Body::empty()))
 }
 
 /// Handler for serving a single file with range request support
@@ -164,47 +550,9 @@ pub async fn serve_file<S: FileStorage>(
     Path(file_id): Path<String>,
     headers: HeaderMap,
 ) -> Result<Response, FileServingError> {
-    // Retrieve file metadata for content type and other info
-    let metadata = storage
-        .get_metadata(&file_id)
-        .await
-        .map_err(FileServingError::Storage)?;
-
-    // Retrieve file data
-    let data = storage
-        .retrieve(&file_id)
+    FileServingMiddleware::new(storage)
+        .serve(&file_id, &headers)
         .await
-        .map_err(FileServingError::Storage)?;
-
-    // Generate ETag from file ID and size
-    let etag = format!(r#""{}-{}""#, file_id, data.len());
-
-    // Use content type from metadata, with mime_guess fallback
-    let content_type = if !metadata.content_type.is_empty()
-        && metadata.content_type != "application/octet-stream"
-    {
-        metadata.content_type
-    } else {
-        // Fallback to MIME type detection from filename
-        mime_guess::from_path(&metadata.filename)
-            .first_or_octet_stream()
-            .to_string()
-    };
-
-    // Check If-None-Match (ETag validation)
-    if let Some(if_none_match) = headers.get(IF_NONE_MATCH) {
-        if if_none_match.to_str().is_ok_and(|v| v == etag) {
-            return Ok((StatusCode::NOT_MODIFIED, ()).into_response());
-        }
-    }
-
-    // Check for range request
-    if let Some(range_header) = headers.get(RANGE) {
-        return serve_range_request(&data, range_header, &etag, &content_type, &headers);
-    }
-
-    // Serve complete file
-    Ok(build_file_response(data, &etag, &content_type, None))
 }
 
 /// Serve a range request (partial content)
@@ -212,16 +560,26 @@ fn serve_range_request(
     data: &[u8],
     range_header: &HeaderValue,
     etag: &str,
+    last_modified: Option<SystemTime>,
     content_type: &str,
     headers: &HeaderMap,
 ) -> Result<Response, FileServingError> {
     let file_size = data.len();
 
-    // Check If-Range header (validate ETag before serving range)
+    // Check If-Range header (validate ETag or date before serving range)
     if let Some(if_range) = headers.get(IF_RANGE) {
-        if if_range.to_str().map_or(true, |v| v != etag) {
-            // ETag doesn't match, serve full file instead
-            return Ok(build_file_response(data.to_vec(), etag, content_type, None));
+        if !if_range
+            .to_str()
+            .is_ok_and(|v| if_range_matches(v, etag, last_modified))
+        {
+            // Validator doesn't match, serve full file instead
+            return Ok(build_file_response(
+                data.to_vec(),
+                etag,
+                last_modified,
+                content_type,
+                None,
+            ));
         }
     }
 
@@ -280,6 +638,7 @@ fn serve_range_request(
     Ok(build_file_response(
         range_data,
         etag,
+        last_modified,
         content_type,
         Some((&content_range, StatusCode::PARTIAL_CONTENT)),
     ))
@@ -289,6 +648,7 @@ fn serve_range_request(
 fn build_file_response(
     data: Vec<u8>,
     etag: &str,
+    last_modified: Option<SystemTime>,
     content_type: &str,
     range_info: Option<(&str, StatusCode)>,
 ) -> Response {
@@ -311,12 +671,10 @@ fn build_file_response(
     }
 
     // Cache headers
-    response = response
-        .header(CACHE_CONTROL, "public, max-age=86400")
-        .header(
-            LAST_MODIFIED,
-            httpdate::fmt_http_date(SystemTime::now()),
-        );
+    response = response.header(CACHE_CONTROL, "public, max-age=86400");
+    if let Some(last_modified) = last_modified {
+        response = response.header(LAST_MODIFIED, httpdate::fmt_http_date(last_modified));
+    }
 
     response
         .body(Body::from(data))
@@ -374,15 +732,163 @@ impl IntoResponse for FileServingError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::htmx::storage::{LocalFileStorage, UploadedFile};
+    use crate::htmx::storage::{LocalFileStorage, MockFileStorage, UploadedFile};
     use tempfile::TempDir;
 
     #[test]
     fn test_etag_generation() {
-        let file_id = "test-file-123";
-        let data = b"Hello, World!";
-        let etag = format!(r#""{}-{}""#, file_id, data.len());
-        assert_eq!(etag, r#""test-file-123-13""#);
+        let etag = content_etag(b"Hello, World!");
+        assert_eq!(etag, r#""dffd6021bb2bd5b0af676290809ec3a5""#);
+
+        // Same size, different content
+        assert_ne!(content_etag(b"Hello, World?"), etag);
+    }
+
+    #[test]
+    fn test_etag_list_matching() {
+        let etag = r#""abc""#;
+        assert!(etag_list_matches(r#""abc""#, etag));
+        assert!(etag_list_matches(r#""xyz", W/"abc""#, etag));
+        assert!(etag_list_matches("*", etag));
+        assert!(!etag_list_matches(r#""xyz""#, etag));
+
+        // If-Range uses strong comparison
+        assert!(if_range_matches(r#""abc""#, etag, None));
+        assert!(!if_range_matches(r#"W/"abc""#, etag, None));
+    }
+
+    /// Store a file and fetch it once, returning its ID and first response
+    async fn store_and_serve(
+        storage: &Arc<LocalFileStorage>,
+        data: &[u8],
+    ) -> (String, Response) {
+        let file = UploadedFile::new("avatar.png", "image/png", data.to_vec());
+        let stored = storage.store(file).await.unwrap();
+        let response = serve_file(State(storage.clone()), Path(stored.id.clone()), HeaderMap::new())
+            .await
+            .unwrap();
+        (stored.id, response)
+    }
+
+    #[tokio::test]
+    async fn test_if_none_match_returns_304() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let (id, response) = store_and_serve(&storage, b"avatar bytes").await;
+        let etag = response.headers().get(ETAG).unwrap().clone();
+        assert!(response.headers().contains_key(LAST_MODIFIED));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, etag.clone());
+        let response = serve_file(State(storage.clone()), Path(id), headers)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(ETAG).unwrap(), etag);
+        assert!(response.headers().contains_key(CACHE_CONTROL));
+        assert!(!response.headers().contains_key(CONTENT_LENGTH));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_if_none_match_mismatch_returns_200() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let (id, _) = store_and_serve(&storage, b"avatar bytes").await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_static("\"stale\""));
+        // If-None-Match takes precedence over a matching If-Modified-Since
+        headers.insert(
+            IF_MODIFIED_SINCE,
+            HeaderValue::from_str(&httpdate::fmt_http_date(SystemTime::now())).unwrap(),
+        );
+        let response = serve_file(State(storage.clone()), Path(id), headers)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_LENGTH).unwrap(), "12");
+    }
+
+    #[tokio::test]
+    async fn test_if_modified_since() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let (id, response) = store_and_serve(&storage, b"avatar bytes").await;
+        let last_modified = response.headers().get(LAST_MODIFIED).unwrap().clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_MODIFIED_SINCE, last_modified);
+        let response = serve_file(State(storage.clone()), Path(id.clone()), headers)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Thu, 01 Jan 1970 00:00:00 GMT"),
+        );
+        let response = serve_file(State(storage.clone()), Path(id), headers)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_if_none_match_applies_before_range() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let (id, response) = store_and_serve(&storage, &[42u8; 100]).await;
+        let etag = response.headers().get(ETAG).unwrap().clone();
+        let last_modified = response.headers().get(LAST_MODIFIED).unwrap().clone();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RANGE, HeaderValue::from_static("bytes=0-49"));
+        headers.insert(IF_NONE_MATCH, etag);
+        let response = serve_file(State(storage.clone()), Path(id.clone()), headers)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        // If-Range also accepts the Last-Modified date
+        let mut headers = HeaderMap::new();
+        headers.insert(RANGE, HeaderValue::from_static("bytes=0-49"));
+        headers.insert(IF_RANGE, last_modified);
+        let response = serve_file(State(storage.clone()), Path(id), headers)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_access_control_checked_before_conditional_headers() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let (id, response) = store_and_serve(&storage, b"private").await;
+        let etag = response.headers().get(ETAG).unwrap().clone();
+
+        let access_control: FileAccessControl = Arc::new(|user_id, _file_id| {
+            Box::pin(async move { Ok(user_id.as_deref() == Some("owner")) })
+        });
+        let files = FileServingMiddleware::new(storage).with_access_control(access_control);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, etag);
+
+        let result = files.serve_for(Some("other".to_string()), &id, &headers).await;
+        assert!(matches!(result, Err(FileServingError::AccessDenied)));
+
+        let response = files
+            .serve_for(Some("owner".to_string()), &id, &headers)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
     }
 
     #[tokio::test]
@@ -976,4 +1482,159 @@ mod tests {
         let content_length = response.headers().get(CONTENT_LENGTH).unwrap();
         assert_eq!(content_length, "100");
     }
+
+    /// Store `app.css` plus a variant written in the storage layout
+    async fn store_with_variant(
+        temp: &TempDir,
+        storage: &LocalFileStorage,
+        encoding: PrecompressedEncoding,
+    ) -> String {
+        let file = UploadedFile::new("app.css", "text/css", b"body { color: red }".to_vec());
+        let stored = storage.store(file).await.unwrap();
+
+        let variant_id = encoding.variant_id(&stored.id);
+        let dir = temp.path().join(&stored.id[..2]).join(&variant_id);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("app.css.enc"), b"compressed").unwrap();
+
+        stored.id
+    }
+
+    #[tokio::test]
+    async fn test_precompressed_variant_served() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let id = store_with_variant(&temp, &storage, PrecompressedEncoding::Brotli).await;
+
+        let files = FileServingMiddleware::new(storage)
+            .with_precompressed([PrecompressedEncoding::Brotli, PrecompressedEncoding::Gzip]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip, br"));
+        headers.insert(RANGE, HeaderValue::from_static("bytes=0-3"));
+
+        let response = files.serve(&id, &headers).await.unwrap();
+
+        // Range is ignored for the encoded body
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "br");
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "text/css");
+        assert_eq!(response.headers().get(VARY).unwrap(), "accept-encoding");
+        assert_eq!(response.headers().get(ACCEPT_RANGES).unwrap(), "none");
+        assert_eq!(response.headers().get(CONTENT_LENGTH).unwrap(), "10");
+        assert_eq!(
+            response.headers().get(ETAG).unwrap().to_str().unwrap(),
+            content_etag(b"compressed")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_precompressed_falls_back_to_raw() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let id = store_with_variant(&temp, &storage, PrecompressedEncoding::Gzip).await;
+
+        let files = FileServingMiddleware::new(storage)
+            .with_precompressed([PrecompressedEncoding::Brotli, PrecompressedEncoding::Gzip]);
+
+        // Only br accepted, but only a gzip variant exists
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("br, gzip;q=0"));
+
+        let response = files.serve(&id, &headers).await.unwrap();
+
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(response.headers().get(VARY).unwrap(), "accept-encoding");
+        assert_eq!(response.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(
+            response.headers().get(ETAG).unwrap().to_str().unwrap(),
+            content_etag(b"body { color: red }")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_precompressed_etag_revalidation() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let id = store_with_variant(&temp, &storage, PrecompressedEncoding::Gzip).await;
+
+        let files = FileServingMiddleware::new(storage)
+            .with_precompressed([PrecompressedEncoding::Gzip]);
+
+        // The raw ETag does not validate the gzip variant
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+        headers.insert(
+            IF_NONE_MATCH,
+            HeaderValue::from_str(&content_etag(b"body { color: red }")).unwrap(),
+        );
+        let response = files.serve(&id, &headers).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        headers.insert(
+            IF_NONE_MATCH,
+            HeaderValue::from_str(&content_etag(b"compressed")).unwrap(),
+        );
+        let response = files.serve(&id, &headers).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[tokio::test]
+    async fn test_presigned_url_redirects() {
+        let mut storage = MockFileStorage::new();
+        storage
+            .expect_presigned_url()
+            .withf(|id, expires_in| id == "abc" && *expires_in == Duration::from_secs(300))
+            .returning(|_, _| Ok(Some("https://bucket.example.com/abc?sig=1".to_string())));
+        storage.expect_get_metadata().never();
+        storage.expect_retrieve().never();
+
+        let files = FileServingMiddleware::new(Arc::new(storage))
+            .with_presigned_urls(Duration::from_secs(300));
+
+        let response = files.serve("abc", &HeaderMap::new()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(
+            response.headers().get("location").unwrap(),
+            "https://bucket.example.com/abc?sig=1"
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("HX-Request", HeaderValue::from_static("true"));
+        let response = files.serve("abc", &headers).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("hx-redirect").unwrap(),
+            "https://bucket.example.com/abc?sig=1"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_presigned_url_falls_back_to_serving() {
+        let temp = TempDir::new().unwrap();
+        let storage = Arc::new(LocalFileStorage::new(temp.path().to_path_buf()).unwrap());
+        let file = UploadedFile::new("notes.txt", "text/plain", b"hello".to_vec());
+        let stored = storage.store(file).await.unwrap();
+
+        let files = FileServingMiddleware::new(storage)
+            .with_presigned_urls(Duration::from_secs(300));
+
+        let response = files.serve(&stored.id, &HeaderMap::new()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "text/plain");
+    }
+
+    #[test]
+    fn test_accepts_encoding() {
+        let mut headers = HeaderMap::new();
+        assert!(!accepts_encoding(&headers, "br"));
+
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("gzip;q=0.8, br;q=0"));
+        assert!(accepts_encoding(&headers, "gzip"));
+        assert!(!accepts_encoding(&headers, "br"));
+
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static("*;q=0.5, gzip;q=0"));
+        assert!(accepts_encoding(&headers, "br"));
+        assert!(!accepts_encoding(&headers, "gzip"));
+    }
 }
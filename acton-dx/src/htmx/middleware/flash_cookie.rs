@@ -0,0 +1,406 @@
+//! Flash messages stored in a signed cookie
+//!
+//! By default flash messages live in the server-side session. With
+//! [`FlashCookieLayer`] installed they are kept in a short-lived cookie
+//! signed with [`CookieSigner`] instead, so they survive a redirect without
+//! any server-side state:
+//! - [`Session::add_flash`](crate::htmx::auth::Session::add_flash) and
+//!   [`prg`](crate::htmx::responses::prg) write to the cookie
+//! - [`FlashExtractor`](crate::htmx::extractors::FlashExtractor) reads the
+//!   messages and clears the cookie, so each flash is shown once
+//! - Handlers without a session can use the [`FlashJar`] extractor directly
+//!
+//! Cookies are limited to about 4KB; when the messages don't fit, the oldest
+//! are dropped.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::auth::FlashMessage;
+//! use acton_dx::htmx::middleware::{FlashCookieLayer, FlashJar};
+//! use axum::{response::Redirect, routing::post, Router};
+//!
+//! async fn save(flash: FlashJar) -> Redirect {
+//!     flash.add(FlashMessage::success("Saved!"));
+//!     Redirect::to("/")
+//! }
+//!
+//! let app = Router::new()
+//!     .route("/save", post(save))
+//!     .layer(FlashCookieLayer::new(&state));
+//! ```
+
+use super::cookie_signer::CookieSigner;
+use crate::htmx::auth::session::FlashMessage;
+use crate::htmx::state::ActonHtmxState;
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, Request},
+    http::{
+        header::{COOKIE, SET_COOKIE},
+        request::Parts,
+        StatusCode,
+    },
+    response::Response,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::{Layer, Service};
+
+/// Flash cookie name
+pub const FLASH_COOKIE_NAME: &str = "acton_flash";
+
+/// Largest flash cookie sent, including name and attributes
+pub const MAX_FLASH_COOKIE_BYTES: usize = 4096;
+
+/// How long flash cookies stay valid by default
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(300);
+
+/// [`CookieSigner::for_purpose`] key of the flash cookie
+const SIGNING_PURPOSE: &str = "flash";
+
+/// Flash messages of the current request, shared with the middleware
+///
+/// Inserted into request extensions by [`FlashCookieLayer`].
+#[derive(Debug, Clone, Default)]
+pub struct FlashJar {
+    state: Arc<Mutex<FlashJarState>>,
+}
+
+#[derive(Debug, Default)]
+struct FlashJarState {
+    messages: Vec<FlashMessage>,
+    changed: bool,
+}
+
+impl FlashJar {
+    fn with_messages(messages: Vec<FlashMessage>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FlashJarState {
+                messages,
+                changed: false,
+            })),
+        }
+    }
+
+    /// Add a flash message for the next request
+    pub fn add(&self, message: FlashMessage) {
+        let mut state = self.state.lock();
+        state.messages.push(message);
+        state.changed = true;
+    }
+
+    /// Take all flash messages, clearing the cookie
+    #[must_use]
+    pub fn take(&self) -> Vec<FlashMessage> {
+        let mut state = self.state.lock();
+        state.changed = true;
+        std::mem::take(&mut state.messages)
+    }
+
+    /// Check if there are any flash messages
+    #[must_use]
+    pub fn has_flashes(&self) -> bool {
+        !self.state.lock().messages.is_empty()
+    }
+
+    /// Messages to store, if they changed during the request
+    fn changed_messages(&self) -> Option<Vec<FlashMessage>> {
+        let state = self.state.lock();
+        state.changed.then(|| state.messages.clone())
+    }
+}
+
+/// Requires [`FlashCookieLayer`] to be applied to the router.
+impl<S> FromRequestParts<S> for FlashJar
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Self>()
+            .cloned()
+            .ok_or((StatusCode::INTERNAL_SERVER_ERROR, "Flash cookies not enabled"))
+    }
+}
+
+/// Layer storing flash messages in a signed cookie
+#[derive(Debug, Clone)]
+pub struct FlashCookieLayer {
+    signer: CookieSigner,
+    max_age: Duration,
+    secure: bool,
+}
+
+impl FlashCookieLayer {
+    /// Sign flash cookies with the state's cookie signer
+    #[must_use]
+    pub fn new(state: &ActonHtmxState) -> Self {
+        Self::with_signer(state.cookie_signer().clone())
+    }
+
+    /// Sign flash cookies with `signer`
+    #[must_use]
+    pub const fn with_signer(signer: CookieSigner) -> Self {
+        Self {
+            signer,
+            max_age: DEFAULT_MAX_AGE,
+            secure: !cfg!(debug_assertions),
+        }
+    }
+
+    /// Discard flash cookies older than `max_age` (default: 5 minutes)
+    #[must_use]
+    pub const fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Send the cookie over HTTPS only (default: true in release builds)
+    #[must_use]
+    pub const fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+}
+
+impl<S> Layer<S> for FlashCookieLayer {
+    type Service = FlashCookieMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FlashCookieMiddleware {
+            inner,
+            signer: self.signer.for_purpose(SIGNING_PURPOSE).with_max_age(self.max_age),
+            max_age: self.max_age,
+            secure: self.secure,
+        }
+    }
+}
+
+/// Middleware reading and writing the flash cookie
+#[derive(Debug, Clone)]
+pub struct FlashCookieMiddleware<S> {
+    inner: S,
+    signer: CookieSigner,
+    max_age: Duration,
+    secure: bool,
+}
+
+impl<S> Service<Request> for FlashCookieMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let signer = self.signer.clone();
+        let (max_age, secure) = (self.max_age, self.secure);
+
+        Box::pin(async move {
+            let cookie = flash_cookie(&req).map(ToString::to_string);
+            let messages = cookie
+                .as_deref()
+                .and_then(|value| decode(&signer, value))
+                .unwrap_or_default();
+
+            let jar = FlashJar::with_messages(messages);
+            req.extensions_mut().insert(jar.clone());
+
+            let mut response = inner.call(req).await?;
+
+            let value = match jar.changed_messages() {
+                Some(messages) if messages.is_empty() => cookie.map(|_| String::new()),
+                Some(messages) => Some(encode(&signer, messages, max_age, secure)),
+                None => None,
+            };
+            if let Some(value) = value {
+                set_flash_cookie(&mut response, &value, max_age, secure);
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// Find the flash cookie in the request
+fn flash_cookie(req: &Request) -> Option<&str> {
+    let cookie_str = req.headers().get(COOKIE)?.to_str().ok()?;
+
+    cookie_str
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| name.trim() == FLASH_COOKIE_NAME)
+        .map(|(_, value)| value.trim())
+}
+
+/// Verify and decode a flash cookie value
+fn decode(signer: &CookieSigner, value: &str) -> Option<Vec<FlashMessage>> {
+    let payload = signer.verify(value)?;
+    let json = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+/// Sign `messages`, dropping the oldest until the cookie fits
+fn encode(
+    signer: &CookieSigner,
+    mut messages: Vec<FlashMessage>,
+    max_age: Duration,
+    secure: bool,
+) -> String {
+    loop {
+        let json = serde_json::to_vec(&messages).unwrap_or_default();
+        let value = signer.sign(&URL_SAFE_NO_PAD.encode(json));
+        if messages.is_empty() || cookie_header(&value, max_age, secure).len() <= MAX_FLASH_COOKIE_BYTES
+        {
+            return value;
+        }
+        tracing::warn!("Flash cookie too large; dropping the oldest flash message");
+        messages.remove(0);
+    }
+}
+
+fn cookie_header(value: &str, max_age: Duration, secure: bool) -> String {
+    let max_age = if value.is_empty() { 0 } else { max_age.as_secs() };
+    let secure = if secure { "; Secure" } else { "" };
+    format!("{FLASH_COOKIE_NAME}={value}; Path=/; Max-Age={max_age}; SameSite=Lax; HttpOnly{secure}")
+}
+
+/// Set (or, with an empty value, clear) the flash cookie on response
+fn set_flash_cookie(response: &mut Response<Body>, value: &str, max_age: Duration, secure: bool) {
+    if let Ok(header_value) = cookie_header(value, max_age, secure).parse() {
+        response.headers_mut().append(SET_COOKIE, header_value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::extractors::FlashExtractor;
+    use axum::{response::Redirect, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route(
+                "/save",
+                get(|flash: FlashJar| async move {
+                    flash.add(FlashMessage::success("Saved!"));
+                    Redirect::to("/")
+                }),
+            )
+            .route(
+                "/",
+                get(|FlashExtractor(messages): FlashExtractor| async move {
+                    messages
+                        .into_iter()
+                        .map(|flash| flash.message)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }),
+            )
+            .route("/other", get(|| async { "other" }))
+            .layer(FlashCookieLayer::with_signer(CookieSigner::new(b"test-secret")))
+    }
+
+    /// Send a request with an optional flash cookie, returning the body and
+    /// the `Set-Cookie` value for the flash cookie
+    async fn send(path: &str, cookie: Option<&str>) -> (String, Option<String>) {
+        let mut request = Request::builder().uri(path);
+        if let Some(cookie) = cookie {
+            request = request.header(COOKIE, format!("{FLASH_COOKIE_NAME}={cookie}"));
+        }
+        let response = app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let set_cookie = response
+            .headers()
+            .get(SET_COOKIE)
+            .map(|value| value.to_str().unwrap().to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let value = set_cookie.map(|header| {
+            header
+                .split(';')
+                .next()
+                .unwrap()
+                .trim_start_matches(&format!("{FLASH_COOKIE_NAME}="))
+                .to_string()
+        });
+        (String::from_utf8(body.to_vec()).unwrap(), value)
+    }
+
+    #[tokio::test]
+    async fn test_flash_survives_redirect_once() {
+        let (_, cookie) = send("/save", None).await;
+        let cookie = cookie.unwrap();
+        assert!(!cookie.is_empty());
+
+        // Requests that don't read flashes leave the cookie alone
+        let (_, unchanged) = send("/other", Some(&cookie)).await;
+        assert!(unchanged.is_none());
+
+        // Shown once, then cleared
+        let (body, cleared) = send("/", Some(&cookie)).await;
+        assert_eq!(body, "Saved!");
+        assert_eq!(cleared.as_deref(), Some(""));
+
+        let (body, _) = send("/", None).await;
+        assert_eq!(body, "");
+    }
+
+    #[tokio::test]
+    async fn test_forged_cookie_is_ignored() {
+        let forged = URL_SAFE_NO_PAD.encode(r#"[{"level":"success","message":"Hi","title":null}]"#);
+        let (body, _) = send("/", Some(&format!("{forged}.0.bad"))).await;
+        assert_eq!(body, "");
+    }
+
+    #[test]
+    fn test_session_flashes_go_to_jar() {
+        use crate::htmx::auth::{Session, SessionData, SessionId};
+
+        let jar = FlashJar::default();
+        let mut session =
+            Session::new(SessionId::generate(), SessionData::new()).with_flash_jar(jar.clone());
+        session.add_flash(FlashMessage::success("Saved!"));
+
+        assert!(session.data().flash_messages.is_empty());
+        assert!(jar.has_flashes());
+        assert_eq!(session.take_flashes().len(), 1);
+        assert_eq!(jar.changed_messages(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_oldest_flashes_dropped_to_fit() {
+        let signer = CookieSigner::new(b"test-secret");
+        let messages = (0..100)
+            .map(|i| FlashMessage::info(format!("{i:03} {}", "x".repeat(100))))
+            .collect::<Vec<_>>();
+
+        let value = encode(&signer, messages, DEFAULT_MAX_AGE, true);
+        assert!(cookie_header(&value, DEFAULT_MAX_AGE, true).len() <= MAX_FLASH_COOKIE_BYTES);
+
+        let kept = decode(&signer, &value).unwrap();
+        assert!(!kept.is_empty() && kept.len() < 100);
+        assert!(kept.last().unwrap().message.starts_with("099"));
+    }
+}
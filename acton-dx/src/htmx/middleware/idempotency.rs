@@ -0,0 +1,961 @@
+//! Idempotency-key middleware
+//!
+//! Makes retried or double-submitted requests safe: the first response to a
+//! request carrying an idempotency key is stored, and later requests with
+//! the same key, path, and user get that response replayed instead of
+//! running the handler again.
+//!
+//! The key is read from the `Idempotency-Key` header or, for urlencoded
+//! forms, from the `_idempotency_key` field (see
+//! [`idempotency_key_input`](crate::htmx::template::helpers::idempotency_key_input)).
+//! Only unsafe methods (`POST`, `PUT`, `PATCH`, `DELETE`) are handled;
+//! requests without a key pass through unchanged, as do requests with no
+//! logged-in user or session, since there is no owner to scope the key to.
+//! A form body too large to search for the key is rejected with
+//! `413 Payload Too Large`.
+//!
+//! - Stored: status, body, `Content-Type`, `Location`, and `HX-*` headers
+//! - Not stored: `5xx` responses and bodies over
+//!   [`IdempotencyConfig::max_body_bytes`], so those requests can be retried
+//! - Replayed responses carry `Idempotent-Replayed: true`
+//!
+//! A duplicate arriving while the first request is still running waits for
+//! its response ([`InFlightPolicy::Wait`]) or is rejected with
+//! `409 Conflict` ([`InFlightPolicy::Reject`]).
+//!
+//! Responses are kept in memory, or in Redis with
+//! [`IdempotencyLayer::with_redis`] so every instance sees them. Redis
+//! errors fall back to the in-memory store.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::{IdempotencyConfig, IdempotencyLayer};
+//! use axum::{routing::post, Router};
+//! use std::time::Duration;
+//!
+//! let config = IdempotencyConfig::new().with_ttl(Duration::from_secs(600));
+//! let app: Router = Router::new()
+//!     .route("/orders", post(|| async { "created" }))
+//!     .layer(IdempotencyLayer::new(config));
+//! ```
+
+#[cfg(feature = "redis")]
+use deadpool_redis::Pool as RedisPool;
+
+use crate::htmx::auth::session::{SessionData, SessionId};
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::{header, response::Parts, HeaderName, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Response},
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tower::{Layer, Service};
+
+/// Idempotency key header name
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Idempotency key form field name
+pub const IDEMPOTENCY_FORM_FIELD: &str = "_idempotency_key";
+
+/// Header marking a replayed response
+pub const IDEMPOTENCY_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Longest accepted idempotency key
+const MAX_KEY_LEN: usize = 255;
+
+/// Largest form body buffered while looking for the form field
+const MAX_FORM_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Redis value marking a request that is still running
+#[cfg(feature = "redis")]
+const REDIS_IN_FLIGHT: &str = "in-flight";
+
+/// How often a waiting duplicate checks Redis for the first response
+#[cfg(feature = "redis")]
+const REDIS_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// What to do with a duplicate while the first request is still running
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InFlightPolicy {
+    /// Wait up to [`IdempotencyConfig::wait_timeout`] and replay the first
+    /// response, then give up with `409 Conflict`
+    #[default]
+    Wait,
+    /// Respond `409 Conflict` immediately
+    Reject,
+}
+
+/// Configuration for [`IdempotencyLayer`]
+#[derive(Debug, Clone)]
+pub struct IdempotencyConfig {
+    /// How long responses are replayed (default: 1 hour)
+    pub ttl: Duration,
+    /// Header carrying the key (default: "idempotency-key")
+    pub header_name: String,
+    /// Form field carrying the key (default: "_idempotency_key")
+    pub form_field: String,
+    /// Handling of duplicates that arrive while the first is running
+    pub in_flight: InFlightPolicy,
+    /// Longest wait for an in-flight request (default: 10 seconds)
+    ///
+    /// With Redis, this is also how long a running request blocks
+    /// duplicates, so a crashed instance doesn't block them for the full TTL.
+    pub wait_timeout: Duration,
+    /// Largest response body stored (default: 1 MiB)
+    pub max_body_bytes: usize,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(3600),
+            header_name: IDEMPOTENCY_KEY_HEADER.to_string(),
+            form_field: IDEMPOTENCY_FORM_FIELD.to_string(),
+            in_flight: InFlightPolicy::default(),
+            wait_timeout: Duration::from_secs(10),
+            max_body_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl IdempotencyConfig {
+    /// Create config with default values
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how long responses are replayed
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set the handling of in-flight duplicates
+    #[must_use]
+    pub const fn with_in_flight(mut self, policy: InFlightPolicy) -> Self {
+        self.in_flight = policy;
+        self
+    }
+
+    /// Set the longest wait for an in-flight request
+    #[must_use]
+    pub const fn with_wait_timeout(mut self, timeout: Duration) -> Self {
+        self.wait_timeout = timeout;
+        self
+    }
+}
+
+/// Response stored for replay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl StoredResponse {
+    fn new(parts: &Parts, body: &[u8]) -> Self {
+        let headers = parts
+            .headers
+            .iter()
+            .filter(|(name, _)| is_replayed_header(name))
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        Self {
+            status: parts.status.as_u16(),
+            headers,
+            body: body.to_vec(),
+        }
+    }
+
+    fn into_response(self) -> Response {
+        let mut response = Response::new(Body::from(self.body));
+        *response.status_mut() = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        let headers = response.headers_mut();
+        for (name, value) in self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(name),
+                HeaderValue::try_from(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        headers.insert(IDEMPOTENCY_REPLAYED_HEADER, HeaderValue::from_static("true"));
+        response
+    }
+}
+
+/// Headers worth replaying; cookies are deliberately left out
+fn is_replayed_header(name: &HeaderName) -> bool {
+    name == header::CONTENT_TYPE || name == header::LOCATION || name.as_str().starts_with("hx-")
+}
+
+/// In-memory entry for one key
+#[derive(Debug)]
+enum Slot {
+    /// First request still running; the sender is dropped when it finishes
+    InFlight(watch::Receiver<()>),
+    /// Response to replay until `expires_at`
+    Done {
+        response: StoredResponse,
+        expires_at: Instant,
+    },
+}
+
+/// Result of looking up a key
+enum Lookup {
+    /// This request runs the handler
+    Claimed(Claim),
+    /// Replay this response
+    Replay(StoredResponse),
+    /// Another request is running; the receiver (in-memory only) signals
+    /// when it finishes
+    InFlight(Option<watch::Receiver<()>>),
+}
+
+/// Responses shared by clones of the layer
+#[derive(Clone)]
+struct IdempotencyStore {
+    memory: Arc<Mutex<HashMap<String, Slot>>>,
+    #[cfg(feature = "redis")]
+    redis_pool: Option<RedisPool>,
+}
+
+impl IdempotencyStore {
+    #[cfg_attr(not(feature = "redis"), allow(clippy::unused_async, unused_variables))]
+    async fn claim(&self, key: &str, config: &IdempotencyConfig) -> Lookup {
+        #[cfg(feature = "redis")]
+        if let Some(pool) = &self.redis_pool {
+            match self.claim_redis(pool, key, config).await {
+                Ok(lookup) => return lookup,
+                Err(e) => {
+                    tracing::warn!(error = %e, key = %key, "Redis idempotency lookup failed, falling back to in-memory");
+                }
+            }
+        }
+
+        self.claim_memory(key)
+    }
+
+    fn claim_memory(&self, key: &str) -> Lookup {
+        let mut slots = self.memory.lock();
+        match slots.get(key) {
+            Some(Slot::Done {
+                response,
+                expires_at,
+            }) if *expires_at > Instant::now() => return Lookup::Replay(response.clone()),
+            Some(Slot::InFlight(finished)) => return Lookup::InFlight(Some(finished.clone())),
+            _ => {}
+        }
+
+        let (sender, receiver) = watch::channel(());
+        slots.insert(key.to_string(), Slot::InFlight(receiver));
+        drop(slots);
+
+        Lookup::Claimed(Claim {
+            store: self.clone(),
+            key: key.to_string(),
+            in_redis: false,
+            _finished: Some(sender),
+            completed: false,
+        })
+    }
+
+    #[cfg(feature = "redis")]
+    async fn claim_redis(
+        &self,
+        pool: &RedisPool,
+        key: &str,
+        config: &IdempotencyConfig,
+    ) -> Result<Lookup, String> {
+        let mut conn = pool
+            .get()
+            .await
+            .map_err(|e| format!("Failed to get Redis connection: {e}"))?;
+
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(REDIS_IN_FLIGHT)
+            .arg("NX")
+            .arg("PX")
+            .arg(millis(config.wait_timeout))
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| format!("Redis SET failed: {e}"))?;
+        if claimed.is_some() {
+            return Ok(Lookup::Claimed(Claim {
+                store: self.clone(),
+                key: key.to_string(),
+                in_redis: true,
+                _finished: None,
+                completed: false,
+            }));
+        }
+
+        let value: Option<String> = redis::cmd("GET")
+            .arg(key)
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| format!("Redis GET failed: {e}"))?;
+        match value.as_deref() {
+            // Expired or released in between; the next attempt claims it
+            None | Some(REDIS_IN_FLIGHT) => Ok(Lookup::InFlight(None)),
+            Some(json) => serde_json::from_str(json)
+                .map(Lookup::Replay)
+                .map_err(|e| format!("Invalid stored response: {e}")),
+        }
+    }
+
+    fn remove_in_flight(&self, key: &str) {
+        let mut slots = self.memory.lock();
+        if matches!(slots.get(key), Some(Slot::InFlight(_))) {
+            slots.remove(key);
+        }
+        drop(slots);
+    }
+}
+
+/// Right to run the handler for a key
+///
+/// Dropping it without [`complete`](Self::complete) (error response,
+/// cancelled request) releases the key so a retry runs the handler.
+struct Claim {
+    store: IdempotencyStore,
+    key: String,
+    in_redis: bool,
+    /// Dropped last, waking in-memory waiters
+    _finished: Option<watch::Sender<()>>,
+    completed: bool,
+}
+
+impl Claim {
+    #[cfg_attr(not(feature = "redis"), allow(clippy::unused_async))]
+    async fn complete(mut self, response: StoredResponse, ttl: Duration) {
+        self.completed = true;
+
+        #[cfg(feature = "redis")]
+        if self.in_redis {
+            if let Some(pool) = &self.store.redis_pool {
+                match store_redis(pool, &self.key, &response, ttl).await {
+                    Ok(()) => return,
+                    Err(e) => {
+                        tracing::warn!(error = %e, key = %self.key, "Failed to store idempotent response in Redis, falling back to in-memory");
+                    }
+                }
+            }
+        }
+
+        self.store.memory.lock().insert(
+            self.key.clone(),
+            Slot::Done {
+                response,
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        if !self.in_redis {
+            self.store.remove_in_flight(&self.key);
+        }
+
+        #[cfg(feature = "redis")]
+        if let (true, Some(pool), Ok(runtime)) = (
+            self.in_redis,
+            self.store.redis_pool.clone(),
+            tokio::runtime::Handle::try_current(),
+        ) {
+            let key = self.key.clone();
+            runtime.spawn(async move {
+                if let Ok(mut conn) = pool.get().await {
+                    let _: Result<(), _> = redis::cmd("DEL").arg(&key).query_async(&mut *conn).await;
+                }
+            });
+        }
+    }
+}
+
+#[cfg(feature = "redis")]
+async fn store_redis(
+    pool: &RedisPool,
+    key: &str,
+    response: &StoredResponse,
+    ttl: Duration,
+) -> Result<(), String> {
+    let json = serde_json::to_string(response).map_err(|e| e.to_string())?;
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|e| format!("Failed to get Redis connection: {e}"))?;
+    redis::cmd("SET")
+        .arg(key)
+        .arg(json)
+        .arg("PX")
+        .arg(millis(ttl))
+        .query_async::<()>(&mut *conn)
+        .await
+        .map_err(|e| format!("Redis SET failed: {e}"))
+}
+
+#[cfg(feature = "redis")]
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX).max(1)
+}
+
+/// Layer replaying responses to requests with a repeated idempotency key
+#[derive(Clone)]
+pub struct IdempotencyLayer {
+    config: Arc<IdempotencyConfig>,
+    store: IdempotencyStore,
+}
+
+impl std::fmt::Debug for IdempotencyLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyLayer")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IdempotencyLayer {
+    /// Create a layer storing responses in memory
+    #[must_use]
+    pub fn new(config: IdempotencyConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            store: IdempotencyStore {
+                memory: Arc::new(Mutex::new(HashMap::new())),
+                #[cfg(feature = "redis")]
+                redis_pool: None,
+            },
+        }
+    }
+
+    /// Store responses in Redis, shared by all instances
+    #[cfg(feature = "redis")]
+    #[must_use]
+    pub fn with_redis(mut self, pool: RedisPool) -> Self {
+        self.store.redis_pool = Some(pool);
+        self
+    }
+
+    /// Remove expired responses from the in-memory store
+    ///
+    /// Should be called periodically. Returns the number of entries removed.
+    #[must_use]
+    pub fn cleanup_expired(&self) -> usize {
+        let now = Instant::now();
+        let mut slots = self.store.memory.lock();
+        let before = slots.len();
+        slots.retain(|_, slot| match slot {
+            Slot::InFlight(_) => true,
+            Slot::Done { expires_at, .. } => *expires_at > now,
+        });
+        let removed = before - slots.len();
+        drop(slots);
+        removed
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyMiddleware {
+            inner,
+            config: self.config.clone(),
+            store: self.store.clone(),
+        }
+    }
+}
+
+/// Middleware service created by [`IdempotencyLayer`]
+#[derive(Clone)]
+pub struct IdempotencyMiddleware<S> {
+    inner: S,
+    config: Arc<IdempotencyConfig>,
+    store: IdempotencyStore,
+}
+
+impl<S: std::fmt::Debug> std::fmt::Debug for IdempotencyMiddleware<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyMiddleware")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<Request> for IdempotencyMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let config = self.config.clone();
+        let store = self.store.clone();
+
+        Box::pin(async move {
+            if is_method_safe(req.method()) {
+                return inner.call(req).await;
+            }
+
+            let (req, key) = match extract_key(req, &config).await {
+                Ok(extracted) => extracted,
+                Err(response) => return Ok(response),
+            };
+            let Some(key) = key else {
+                return inner.call(req).await;
+            };
+            if key.len() > MAX_KEY_LEN {
+                return Ok((StatusCode::BAD_REQUEST, "Idempotency key too long").into_response());
+            }
+            let Some(key) = storage_key(&req, &key) else {
+                tracing::debug!("No user or session to scope the idempotency key to");
+                return inner.call(req).await;
+            };
+
+            let deadline = Instant::now() + config.wait_timeout;
+            let claim = loop {
+                match store.claim(&key, &config).await {
+                    Lookup::Claimed(claim) => break claim,
+                    Lookup::Replay(response) => {
+                        tracing::debug!(key = %key, "Replaying idempotent response");
+                        return Ok(response.into_response());
+                    }
+                    Lookup::InFlight(finished) => {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        if config.in_flight == InFlightPolicy::Reject || remaining.is_zero() {
+                            return Ok(conflict());
+                        }
+                        wait_for(finished, remaining).await;
+                    }
+                }
+            };
+
+            let response = inner.call(req).await?;
+            if response.status().is_server_error() {
+                return Ok(response);
+            }
+
+            let (parts, body) = response.into_parts();
+            let max = config.max_body_bytes;
+            let fits = body
+                .size_hint()
+                .upper()
+                .is_some_and(|len| usize::try_from(len).is_ok_and(|len| len <= max));
+            if !fits {
+                return Ok(Response::from_parts(parts, body));
+            }
+
+            match axum::body::to_bytes(body, max).await {
+                Ok(bytes) => {
+                    claim
+                        .complete(StoredResponse::new(&parts, &bytes), config.ttl)
+                        .await;
+                    Ok(Response::from_parts(parts, Body::from(bytes)))
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to buffer response for idempotency");
+                    Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+                }
+            }
+        })
+    }
+}
+
+/// Wait until an in-flight request finishes, at most `timeout`
+async fn wait_for(finished: Option<watch::Receiver<()>>, timeout: Duration) {
+    match finished {
+        // Resolves with an error once the running request drops its sender
+        Some(mut finished) => {
+            let _ = tokio::time::timeout(timeout, finished.changed()).await;
+        }
+        #[cfg(feature = "redis")]
+        None => tokio::time::sleep(timeout.min(REDIS_POLL_INTERVAL)).await,
+        #[cfg(not(feature = "redis"))]
+        None => {}
+    }
+}
+
+fn conflict() -> Response {
+    (
+        StatusCode::CONFLICT,
+        "A request with this idempotency key is already in progress",
+    )
+        .into_response()
+}
+
+/// Check if HTTP method is considered safe (doesn't modify state)
+const fn is_method_safe(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+/// Scope the key to the user (or session) and path
+///
+/// Returns `None` without a user or session: a shared anonymous scope would
+/// replay one client's response to another client reusing the key.
+fn storage_key(req: &Request, key: &str) -> Option<String> {
+    let extensions = req.extensions();
+    let owner = extensions
+        .get::<SessionData>()
+        .and_then(|session| session.user_id)
+        .map(|user_id| format!("user:{user_id}"))
+        .or_else(|| {
+            extensions
+                .get::<SessionId>()
+                .map(|session_id| format!("session:{session_id}"))
+        })?;
+    Some(format!(
+        "idempotency:{owner}:{} {}:{key}",
+        req.method(),
+        req.uri().path()
+    ))
+}
+
+/// Extract the idempotency key from the header or form field
+///
+/// An urlencoded form body is buffered to look for the field; the returned
+/// request carries the same body for the handler.
+///
+/// # Errors
+///
+/// Returns a `413 Payload Too Large` response if the form body can't be
+/// buffered, rather than running the handler without its body
+async fn extract_key(
+    req: Request,
+    config: &IdempotencyConfig,
+) -> Result<(Request, Option<String>), Response> {
+    let header_key = req
+        .headers()
+        .get(&config.header_name)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string);
+    if header_key.is_some() {
+        return Ok((req, header_key));
+    }
+
+    if !is_form_request(&req) {
+        return Ok((req, None));
+    }
+
+    let (parts, body) = req.into_parts();
+    match axum::body::to_bytes(body, MAX_FORM_BODY_BYTES).await {
+        Ok(bytes) => {
+            let key = form_field_key(&bytes, &config.form_field);
+            Ok((Request::from_parts(parts, Body::from(bytes)), key))
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "Failed to buffer form body for idempotency key");
+            Err((StatusCode::PAYLOAD_TOO_LARGE, "Form body too large").into_response())
+        }
+    }
+}
+
+/// Check whether the request carries an urlencoded form body
+fn is_form_request(req: &Request) -> bool {
+    req.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"))
+}
+
+/// Find the key form field in an urlencoded body
+fn form_field_key(body: &[u8], form_field: &str) -> Option<String> {
+    serde_html_form::from_bytes::<Vec<(String, String)>>(body)
+        .ok()?
+        .into_iter()
+        .find(|(name, value)| name == form_field && !value.is_empty())
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::LazyLock;
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    /// Handler counting its calls, responding with the call number
+    fn counting_app(layer: IdempotencyLayer) -> (Router, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = Router::new()
+            .route(
+                "/orders",
+                post(move || {
+                    let call = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                    async move { ([("hx-trigger", "orderCreated")], format!("order {call}")) }
+                }),
+            )
+            .layer(layer);
+        (app, calls)
+    }
+
+    /// Session shared by requests that should be treated as one client
+    static SESSION_ID: LazyLock<SessionId> = LazyLock::new(SessionId::generate);
+
+    fn post_with_key(key: &str) -> Request {
+        let mut request = anonymous_post_with_key(key);
+        request.extensions_mut().insert(SESSION_ID.clone());
+        request
+    }
+
+    fn anonymous_post_with_key(key: &str) -> Request {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/orders")
+            .header(IDEMPOTENCY_KEY_HEADER, key)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn body_text(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_is_replayed() {
+        let (app, calls) = counting_app(IdempotencyLayer::new(IdempotencyConfig::new()));
+
+        let first = app.clone().oneshot(post_with_key("abc")).await.unwrap();
+        assert!(first.headers().get(IDEMPOTENCY_REPLAYED_HEADER).is_none());
+        assert_eq!(body_text(first).await, "order 1");
+
+        let second = app.clone().oneshot(post_with_key("abc")).await.unwrap();
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(second.headers()[IDEMPOTENCY_REPLAYED_HEADER], "true");
+        assert_eq!(second.headers()["hx-trigger"], "orderCreated");
+        assert_eq!(body_text(second).await, "order 1");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Another key runs the handler again
+        let other = app.oneshot(post_with_key("def")).await.unwrap();
+        assert_eq!(body_text(other).await, "order 2");
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_scoped_per_user() {
+        let (app, calls) = counting_app(IdempotencyLayer::new(IdempotencyConfig::new()));
+
+        for user_id in [1, 2] {
+            let mut session = SessionData::new();
+            session.user_id = Some(user_id);
+            let mut request = post_with_key("abc");
+            request.extensions_mut().insert(session);
+            app.clone().oneshot(request).await.unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_clients_are_not_replayed() {
+        let (app, calls) = counting_app(IdempotencyLayer::new(IdempotencyConfig::new()));
+
+        let first = app.clone().oneshot(anonymous_post_with_key("abc")).await.unwrap();
+        assert_eq!(body_text(first).await, "order 1");
+
+        let second = app.oneshot(anonymous_post_with_key("abc")).await.unwrap();
+        assert!(second.headers().get(IDEMPOTENCY_REPLAYED_HEADER).is_none());
+        assert_eq!(body_text(second).await, "order 2");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_form_field_key() {
+        let (app, calls) = counting_app(IdempotencyLayer::new(IdempotencyConfig::new()));
+
+        for _ in 0..2 {
+            let mut request = Request::builder()
+                .method(Method::POST)
+                .uri("/orders")
+                .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from(format!("item=book&{IDEMPOTENCY_FORM_FIELD}=abc")))
+                .unwrap();
+            request.extensions_mut().insert(SESSION_ID.clone());
+            app.clone().oneshot(request).await.unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_form_body_is_rejected() {
+        let (app, calls) = counting_app(IdempotencyLayer::new(IdempotencyConfig::new()));
+
+        let mut request = Request::builder()
+            .method(Method::POST)
+            .uri("/orders")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(vec![b'a'; MAX_FORM_BODY_BYTES + 1]))
+            .unwrap();
+        request.extensions_mut().insert(SESSION_ID.clone());
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_key_or_safe_methods_pass_through() {
+        let (app, calls) = counting_app(IdempotencyLayer::new(IdempotencyConfig::new()));
+
+        for _ in 0..2 {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/orders")
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ttl_expiry() {
+        let layer = IdempotencyLayer::new(IdempotencyConfig::new().with_ttl(Duration::from_secs(60)));
+        let (app, calls) = counting_app(layer.clone());
+
+        app.clone().oneshot(post_with_key("abc")).await.unwrap();
+        tokio::time::advance(Duration::from_secs(59)).await;
+        app.clone().oneshot(post_with_key("abc")).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        tokio::time::advance(Duration::from_secs(2)).await;
+        assert_eq!(layer.cleanup_expired(), 1);
+        let response = app.oneshot(post_with_key("abc")).await.unwrap();
+        assert!(response.headers().get(IDEMPOTENCY_REPLAYED_HEADER).is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_not_stored() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let app = Router::new()
+            .route(
+                "/orders",
+                post(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async { StatusCode::SERVICE_UNAVAILABLE }
+                }),
+            )
+            .layer(IdempotencyLayer::new(IdempotencyConfig::new()));
+
+        for _ in 0..2 {
+            let response = app.clone().oneshot(post_with_key("abc")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// App whose handler blocks until `release` is notified
+    fn blocking_app(policy: InFlightPolicy) -> (Router, Arc<Notify>, Arc<Notify>) {
+        let started = Arc::new(Notify::new());
+        let release = Arc::new(Notify::new());
+        let (on_start, gate) = (started.clone(), release.clone());
+        let app = Router::new()
+            .route(
+                "/orders",
+                post(move || {
+                    let (on_start, gate) = (on_start.clone(), gate.clone());
+                    async move {
+                        on_start.notify_one();
+                        gate.notified().await;
+                        "created"
+                    }
+                }),
+            )
+            .layer(IdempotencyLayer::new(
+                IdempotencyConfig::new().with_in_flight(policy),
+            ));
+        (app, started, release)
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicate_is_rejected() {
+        let (app, started, release) = blocking_app(InFlightPolicy::Reject);
+
+        let first = tokio::spawn(app.clone().oneshot(post_with_key("abc")));
+        started.notified().await;
+
+        let duplicate = app.oneshot(post_with_key("abc")).await.unwrap();
+        assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicate_waits_for_first_response() {
+        let (app, started, release) = blocking_app(InFlightPolicy::Wait);
+
+        let first = tokio::spawn(app.clone().oneshot(post_with_key("abc")));
+        started.notified().await;
+
+        let duplicate = tokio::spawn(app.oneshot(post_with_key("abc")));
+        tokio::task::yield_now().await;
+        release.notify_one();
+
+        assert_eq!(body_text(first.await.unwrap().unwrap()).await, "created");
+        let duplicate = duplicate.await.unwrap().unwrap();
+        assert_eq!(duplicate.headers()[IDEMPOTENCY_REPLAYED_HEADER], "true");
+        assert_eq!(body_text(duplicate).await, "created");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_times_out_with_conflict() {
+        let (app, started, _release) = blocking_app(InFlightPolicy::Wait);
+
+        let _first = tokio::spawn(app.clone().oneshot(post_with_key("abc")));
+        started.notified().await;
+
+        let duplicate = app.oneshot(post_with_key("abc")).await.unwrap();
+        assert_eq!(duplicate.status(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn test_form_field_key_parsing() {
+        assert_eq!(
+            form_field_key(b"a=1&_idempotency_key=k%2D1", IDEMPOTENCY_FORM_FIELD).as_deref(),
+            Some("k-1")
+        );
+        assert!(form_field_key(b"_idempotency_key=", IDEMPOTENCY_FORM_FIELD).is_none());
+    }
+}
@@ -0,0 +1,306 @@
+//! Development live-reload middleware
+//!
+//! Injects a small script before `</body>` of full-page (non-HTMX) HTML
+//! responses. The script connects to the live-reload WebSocket run by
+//! `acton-dx htmx dev` and reloads the page when the dev server reports a
+//! change, waiting until the restarted server answers again.
+//!
+//! The dev server passes the WebSocket URL to the application in the
+//! [`LIVE_RELOAD_URL_ENV`] environment variable; without it the layer does
+//! nothing.
+//!
+//! # Production Safety
+//!
+//! The middleware is a pass-through in release builds (`debug_assertions`
+//! off), regardless of configuration.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::LiveReloadLayer;
+//! use axum::{routing::get, Router};
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "Hello" }))
+//!     .layer(LiveReloadLayer::from_env());
+//! ```
+
+use crate::htmx::middleware::{is_htmx_request, CspNonce};
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::header,
+    response::Response,
+};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Environment variable carrying the live-reload WebSocket URL
+pub const LIVE_RELOAD_URL_ENV: &str = "ACTON_LIVE_RELOAD_URL";
+
+/// Largest HTML body the script is injected into
+const MAX_INJECT_BODY: usize = 8 * 1024 * 1024;
+
+/// Layer that injects the live-reload script in debug builds
+#[derive(Debug, Clone, Default)]
+pub struct LiveReloadLayer {
+    url: Option<Arc<str>>,
+}
+
+impl LiveReloadLayer {
+    /// Connect to the WebSocket at `url`
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: Some(Arc::from(url.into())),
+        }
+    }
+
+    /// Connect to the URL in [`LIVE_RELOAD_URL_ENV`], if set
+    ///
+    /// The variable is set by `acton-dx htmx dev`; otherwise the layer is
+    /// disabled.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self {
+            url: std::env::var(LIVE_RELOAD_URL_ENV)
+                .ok()
+                .filter(|url| !url.is_empty())
+                .map(Arc::from),
+        }
+    }
+
+    /// Whether responses are rewritten
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        cfg!(debug_assertions) && self.url.is_some()
+    }
+}
+
+impl<S> Layer<S> for LiveReloadLayer {
+    type Service = LiveReloadMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LiveReloadMiddleware {
+            inner,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Development live-reload middleware service
+#[derive(Debug, Clone)]
+pub struct LiveReloadMiddleware<S> {
+    inner: S,
+    config: LiveReloadLayer,
+}
+
+impl<S> Service<Request> for LiveReloadMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let url = match &self.config.url {
+            Some(url) if self.config.is_enabled() && !is_htmx_request(req.headers()) => {
+                url.clone()
+            }
+            _ => return Box::pin(self.inner.call(req)),
+        };
+
+        let nonce = req.extensions().get::<CspNonce>().cloned();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let response = future.await?;
+            Ok(inject_live_reload(response, &url, nonce.as_ref()).await)
+        })
+    }
+}
+
+/// Insert the live-reload script before `</body>`
+///
+/// Responses that aren't uncompressed HTML, are larger than 8 MiB (or of
+/// unknown size), or have no `</body>`, are returned untouched.
+pub async fn inject_live_reload(response: Response, url: &str, nonce: Option<&CspNonce>) -> Response {
+    if !is_html(&response) || !fits(response.body()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_INJECT_BODY).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            // The body is gone, so its advertised length no longer holds
+            tracing::error!(error = %e, "Failed to buffer response for live reload");
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let html = String::from_utf8_lossy(&bytes);
+    let Some(index) = html.rfind("</body>") else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let script = render_script(url, nonce);
+    let mut injected = String::with_capacity(html.len() + script.len());
+    injected.push_str(&html[..index]);
+    injected.push_str(&script);
+    injected.push_str(&html[index..]);
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(injected))
+}
+
+/// Whether the body is known to be small enough to buffer
+fn fits(body: &Body) -> bool {
+    body.size_hint()
+        .upper()
+        .is_some_and(|len| usize::try_from(len).is_ok_and(|len| len <= MAX_INJECT_BODY))
+}
+
+fn is_html(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"))
+        && !response.headers().contains_key(header::CONTENT_ENCODING)
+}
+
+fn render_script(url: &str, nonce: Option<&CspNonce>) -> String {
+    // A JSON string is a valid JS string literal; `<` is escaped so the URL
+    // can't close the script element
+    let url = serde_json::to_string(url)
+        .unwrap_or_default()
+        .replace('<', "\\u003c");
+    let nonce = nonce
+        .map(|nonce| format!(" nonce=\"{}\"", nonce.as_str()))
+        .unwrap_or_default();
+
+    format!(
+        "<script id=\"acton-live-reload\"{nonce}>(() => {{\
+         const ready = () => fetch(location.href, {{ method: 'HEAD' }})\
+         .then(() => location.reload(), () => setTimeout(ready, 250));\
+         const connect = () => {{ const ws = new WebSocket({url});\
+         ws.onmessage = (e) => {{ if (e.data === 'reload') ready(); }};\
+         ws.onclose = () => setTimeout(connect, 1000); }};\
+         connect(); }})();</script>"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{response::Html, routing::get, Json, Router};
+    use tower::ServiceExt;
+
+    const URL: &str = "ws://127.0.0.1:35729/__livereload";
+
+    async fn body_string(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    fn html(body: &'static str) -> Response {
+        axum::response::IntoResponse::into_response(Html(body))
+    }
+
+    #[tokio::test]
+    async fn test_script_injected_before_body_end() {
+        let response = inject_live_reload(
+            html("<html><body><p>hi</p></body></html>"),
+            URL,
+            None,
+        )
+        .await;
+
+        assert!(response.headers().get(header::CONTENT_LENGTH).is_none());
+        let body = body_string(response).await;
+        assert!(body.starts_with("<html><body><p>hi</p><script id=\"acton-live-reload\">"));
+        assert!(body.contains(&format!("\"{URL}\"")));
+        assert!(body.ends_with("</script></body></html>"));
+    }
+
+    #[tokio::test]
+    async fn test_script_carries_csp_nonce() {
+        let nonce = CspNonce::generate();
+        let response = inject_live_reload(html("<body></body>"), URL, Some(&nonce)).await;
+
+        let body = body_string(response).await;
+        assert!(body.contains(&format!("nonce=\"{}\"", nonce.as_str())));
+    }
+
+    #[tokio::test]
+    async fn test_non_html_and_fragments_untouched() {
+        let json = axum::response::IntoResponse::into_response(Json(
+            serde_json::json!({ "html": "</body>" }),
+        ));
+        let response = inject_live_reload(json, URL, None).await;
+        assert_eq!(body_string(response).await, r#"{"html":"</body>"}"#);
+
+        let response = inject_live_reload(html("<p>fragment</p>"), URL, None).await;
+        assert_eq!(body_string(response).await, "<p>fragment</p>");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_and_streamed_bodies_untouched() {
+        let large = format!("<body>{}</body>", "x".repeat(MAX_INJECT_BODY));
+        let response = axum::response::IntoResponse::into_response(Html(large.clone()));
+        let length = response.headers().get(header::CONTENT_LENGTH).cloned();
+
+        let response = inject_live_reload(response, URL, None).await;
+        assert_eq!(response.headers().get(header::CONTENT_LENGTH).cloned(), length);
+        assert_eq!(body_string(response).await, large);
+
+        let chunks = futures_util::stream::iter([Ok::<_, std::io::Error>("<body></body>")]);
+        let response = Response::builder()
+            .header(header::CONTENT_TYPE, "text/html")
+            .body(Body::from_stream(chunks))
+            .unwrap();
+        let response = inject_live_reload(response, URL, None).await;
+        assert_eq!(body_string(response).await, "<body></body>");
+    }
+
+    #[tokio::test]
+    async fn test_layer_skips_htmx_requests_and_disabled_layer() {
+        let app = |layer: LiveReloadLayer| {
+            Router::new()
+                .route("/", get(|| async { Html("<body></body>") }))
+                .layer(layer)
+        };
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app(LiveReloadLayer::new(URL)).oneshot(request).await.unwrap();
+        assert_eq!(
+            body_string(response).await.contains("acton-live-reload"),
+            cfg!(debug_assertions)
+        );
+
+        let request = Request::builder()
+            .uri("/")
+            .header("HX-Request", "true")
+            .body(Body::empty())
+            .unwrap();
+        let response = app(LiveReloadLayer::new(URL)).oneshot(request).await.unwrap();
+        assert_eq!(body_string(response).await, "<body></body>");
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app(LiveReloadLayer::default()).oneshot(request).await.unwrap();
+        assert_eq!(body_string(response).await, "<body></body>");
+    }
+}
@@ -0,0 +1,323 @@
+//! Maintenance mode
+//!
+//! [`MaintenanceLayer`] answers every request with `503 Service Unavailable`
+//! while the [`MAINTENANCE_FLAG`] feature flag is on, so deploys can take the
+//! application offline without stopping the process. The flag starts from
+//! the `[features]` section of the configuration and can be switched at
+//! runtime with
+//! [`set_maintenance`](crate::htmx::handlers::maintenance::set_maintenance).
+//!
+//! - Full-page requests get the `errors/503.html` template
+//! - HTMX requests get `HX-Refresh: true`, so the browser reloads the whole
+//!   page and shows the maintenance page instead of swapping a fragment
+//! - API requests get `{ "error": "..." }`
+//!
+//! Every maintenance response carries a `Retry-After` header. Paths on the
+//! allowlist (by default `/health` and `/admin`, including everything below
+//! them) are served normally so health checks keep passing and admins can
+//! turn maintenance off again.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::config::FeatureFlags;
+//! use acton_dx::htmx::middleware::MaintenanceLayer;
+//! use axum::{routing::get, Router};
+//! use std::time::Duration;
+//!
+//! let flags = FeatureFlags::default();
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "Hello" }))
+//!     .route("/status", get(|| async { "ok" }))
+//!     .layer(
+//!         MaintenanceLayer::new(flags.clone())
+//!             .with_allowed_path("/status")
+//!             .with_retry_after(Duration::from_secs(600)),
+//!     );
+//!
+//! flags.set("maintenance", true);
+//! ```
+//!
+//! ```toml
+//! [features]
+//! maintenance = true
+//! ```
+
+use crate::htmx::config::FeatureFlags;
+use crate::htmx::error::render_error_page;
+use crate::htmx::state::ActonHtmxState;
+use crate::htmx::template::ResponseFormat;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::{Layer, Service};
+
+/// Feature flag that turns maintenance mode on
+pub const MAINTENANCE_FLAG: &str = "maintenance";
+
+/// Default `Retry-After` value (5 minutes)
+pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Message shown while maintenance mode is on
+const MAINTENANCE_MESSAGE: &str =
+    "We're performing scheduled maintenance. Please check back in a few minutes.";
+
+/// Layer serving a maintenance page while [`MAINTENANCE_FLAG`] is on
+#[derive(Debug, Clone)]
+pub struct MaintenanceLayer {
+    flags: FeatureFlags,
+    allowed_paths: Arc<Vec<String>>,
+    retry_after: Duration,
+}
+
+impl MaintenanceLayer {
+    /// Watch [`MAINTENANCE_FLAG`] in `flags`
+    ///
+    /// `/health` and `/admin` are allowed by default.
+    #[must_use]
+    pub fn new(flags: FeatureFlags) -> Self {
+        Self {
+            flags,
+            allowed_paths: Arc::new(vec!["/health".to_string(), "/admin".to_string()]),
+            retry_after: DEFAULT_RETRY_AFTER,
+        }
+    }
+
+    /// Watch the application's feature flags
+    #[must_use]
+    pub fn from_state(state: &ActonHtmxState) -> Self {
+        Self::new(state.feature_flags().clone())
+    }
+
+    /// Keep serving `path` and everything below it during maintenance
+    #[must_use]
+    pub fn with_allowed_path(mut self, path: impl Into<String>) -> Self {
+        let path = path.into().trim_end_matches('/').to_string();
+        Arc::make_mut(&mut self.allowed_paths).push(path);
+        self
+    }
+
+    /// Value of the `Retry-After` header
+    #[must_use]
+    pub const fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// Whether maintenance mode is currently on
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.flags.is_enabled(MAINTENANCE_FLAG)
+    }
+
+    /// Whether `path` is served during maintenance
+    #[must_use]
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.allowed_paths.iter().any(|allowed| {
+            path.strip_prefix(allowed.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+impl<S> Layer<S> for MaintenanceLayer {
+    type Service = MaintenanceMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MaintenanceMiddleware {
+            inner,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Middleware serving the maintenance page
+#[derive(Debug, Clone)]
+pub struct MaintenanceMiddleware<S> {
+    inner: S,
+    config: MaintenanceLayer,
+}
+
+impl<S> Service<Request> for MaintenanceMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if !self.config.is_active() || self.config.is_allowed(req.uri().path()) {
+            let future = self.inner.call(req);
+            return Box::pin(future);
+        }
+
+        let format = ResponseFormat::from_headers(req.headers());
+        let response = maintenance_response(format, self.config.retry_after);
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// Build the `503` response in the format the request asked for
+fn maintenance_response(format: ResponseFormat, retry_after: Duration) -> Response {
+    let status = StatusCode::SERVICE_UNAVAILABLE;
+    let retry_after = retry_after.as_secs().to_string();
+    let headers = [
+        (header::RETRY_AFTER, retry_after),
+        (header::CACHE_CONTROL, "no-store".to_string()),
+    ];
+
+    match format {
+        ResponseFormat::Json => (
+            status,
+            headers,
+            Json(serde_json::json!({ "error": MAINTENANCE_MESSAGE })),
+        )
+            .into_response(),
+        ResponseFormat::HtmxPartial => (
+            status,
+            headers,
+            [(header::HeaderName::from_static("hx-refresh"), "true")],
+        )
+            .into_response(),
+        ResponseFormat::FullPage => (
+            status,
+            headers,
+            [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+            render_error_page(status, MAINTENANCE_MESSAGE),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(flags: &FeatureFlags) -> Router {
+        Router::new()
+            .route("/", get(|| async { "home" }))
+            .route("/health", get(|| async { "healthy" }))
+            .route("/healthz", get(|| async { "healthy" }))
+            .route("/admin/maintenance", get(|| async { "admin" }))
+            .route("/status", get(|| async { "ok" }))
+            .layer(
+                MaintenanceLayer::new(flags.clone())
+                    .with_allowed_path("/status/")
+                    .with_retry_after(Duration::from_secs(120)),
+            )
+    }
+
+    fn request(path: &str, headers: &[(&str, &str)]) -> Request {
+        let mut builder = Request::builder().uri(path);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_inactive_passes_through() {
+        let flags = FeatureFlags::default();
+        let response = app(&flags).oneshot(request("/", &[])).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+        assert_eq!(body(response).await, "home");
+    }
+
+    #[tokio::test]
+    async fn test_active_serves_maintenance_page() {
+        let flags = FeatureFlags::default();
+        flags.set(MAINTENANCE_FLAG, true);
+        let response = app(&flags).oneshot(request("/", &[])).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "120");
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        assert!(body(response).await.contains("maintenance"));
+    }
+
+    #[tokio::test]
+    async fn test_active_htmx_request_refreshes_page() {
+        let flags = FeatureFlags::default();
+        flags.set(MAINTENANCE_FLAG, true);
+        let response = app(&flags)
+            .oneshot(request("/", &[("HX-Request", "true")]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()["HX-Refresh"], "true");
+        assert_eq!(response.headers()[header::RETRY_AFTER], "120");
+    }
+
+    #[tokio::test]
+    async fn test_active_api_request_gets_json() {
+        let flags = FeatureFlags::default();
+        flags.set(MAINTENANCE_FLAG, true);
+        let response = app(&flags)
+            .oneshot(request("/", &[("Accept", "application/json")]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let json: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+        assert_eq!(json["error"], MAINTENANCE_MESSAGE);
+    }
+
+    #[tokio::test]
+    async fn test_allowlisted_paths_are_served() {
+        let flags = FeatureFlags::default();
+        flags.set(MAINTENANCE_FLAG, true);
+
+        for path in ["/health", "/admin/maintenance", "/status"] {
+            let response = app(&flags).oneshot(request(path, &[])).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{path}");
+        }
+
+        // Prefixes only match whole path segments
+        let response = app(&flags).oneshot(request("/healthz", &[])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_toggle() {
+        let flags = FeatureFlags::default();
+        let app = app(&flags);
+
+        flags.set(MAINTENANCE_FLAG, true);
+        let response = app.clone().oneshot(request("/", &[])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        flags.set(MAINTENANCE_FLAG, false);
+        let response = app.oneshot(request("/", &[])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -2,24 +2,56 @@
 //!
 //! Provides middleware for:
 //! - Session management (cookie-based sessions with agent backend)
+//! - Cookie signing (HMAC-SHA256 with tamper and expiry checks)
+//! - Request body size limits (global default with per-route overrides)
+//! - Response compression (brotli/gzip for text responses above a minimum size)
+//! - CORS (configured cross-origin access, none by default)
+//! - Flash messages in signed cookies (no server-side session needed)
 //! - Authentication (route protection)
 //! - CSRF protection (token-based CSRF validation)
 //! - Security headers (automatic security header injection)
+//! - Content-Security-Policy builder with per-request nonces
 //! - File serving (range requests, caching, access control)
 //! - Cedar authorization (policy-based access control, requires cedar feature)
 //! - Rate limiting (Redis-backed or in-memory, per-user/IP/route limits)
+//! - Path normalization (canonical trailing slashes and case)
+//! - Tenant resolution (subdomain, header, or user-based multi-tenancy)
+//! - Idempotency keys (replay responses to retried or double-submitted requests)
+//! - Maintenance mode (`503` page toggled by a feature flag, with an allowlist)
+//! - Panic recovery (panics rendered as `500` pages with a logged error id)
+//! - HTMX error targeting (retarget 4xx/5xx responses to visible containers)
+//! - Development query log (per-request SQL summary, debug builds only)
+//! - Development live reload (browser refresh on file changes, debug builds only)
+//! - Request logging (with header, query, and body field redaction)
+//! - Request tracing spans (route, status, latency, HTMX metadata, request id)
 
 pub mod auth;
+pub mod body_limit;
+pub mod catch_panic;
 #[cfg(feature = "cedar")]
 pub mod cedar;
 #[cfg(feature = "cedar")]
 pub mod cedar_template;
+pub mod compression;
+pub mod cookie_signer;
+pub mod cors;
+pub mod csp;
 pub mod csrf;
+pub mod error_target;
 pub mod file_serving;
+pub mod flash_cookie;
 pub mod helpers;
+pub mod idempotency;
+pub mod live_reload;
+pub mod maintenance;
+pub mod normalize_path;
+pub mod query_log;
 pub mod rate_limit;
+pub mod request_log;
+pub mod request_span;
 pub mod security_headers;
 pub mod session;
+pub mod tenant;
 
 // Re-exports are intentionally public even if not used within the crate itself
 #[allow(unused_imports)]
@@ -31,21 +63,71 @@ pub use cedar::{CedarAuthz, CedarAuthzBuilder, CedarError};
 #[allow(unused_imports)]
 pub use cedar_template::{AuthzContext, AuthzContextBuilder};
 #[allow(unused_imports)]
+pub use body_limit::{BodyLimitLayer, BodyLimitMiddleware, DEFAULT_MAX_BODY_BYTES};
+#[allow(unused_imports)]
+pub use catch_panic::{CatchPanicLayer, CatchPanicMiddleware, ERROR_ID_HEADER};
+#[allow(unused_imports)]
+pub use compression::{CompressionLayer, CompressionPredicate, DEFAULT_MIN_COMPRESS_BYTES};
+#[allow(unused_imports)]
+pub use cookie_signer::{CookieSigner, CookieSignerError};
+#[allow(unused_imports)]
+pub use cors::{CorsConfig, CorsError, CorsLayer};
+#[allow(unused_imports)]
+pub use csp::{CspBuilder, CspNonce, CSP_NONCE_SOURCE};
+#[allow(unused_imports)]
 pub use csrf::{
     CsrfConfig, CsrfLayer, CsrfMiddleware, CSRF_FORM_FIELD, CSRF_HEADER_NAME,
 };
 #[allow(unused_imports)]
+pub use error_target::{
+    ErrorFragmentRenderer, ErrorTargetConfig, ErrorTargetExt, ErrorTargetLayer,
+    ErrorTargetMiddleware, DEFAULT_ERROR_TARGET, ERROR_SWAP_META,
+};
+#[allow(unused_imports)]
 pub use file_serving::{
     serve_file, FileAccessControl, FileServingError, FileServingMiddleware,
+    PrecompressedEncoding,
+};
+#[allow(unused_imports)]
+pub use flash_cookie::{
+    FlashCookieLayer, FlashCookieMiddleware, FlashJar, FLASH_COOKIE_NAME, MAX_FLASH_COOKIE_BYTES,
 };
 #[allow(unused_imports)]
-pub use rate_limit::{RateLimit, RateLimitError};
+pub use idempotency::{
+    IdempotencyConfig, IdempotencyLayer, IdempotencyMiddleware, InFlightPolicy,
+    IDEMPOTENCY_FORM_FIELD, IDEMPOTENCY_KEY_HEADER, IDEMPOTENCY_REPLAYED_HEADER,
+};
+#[allow(unused_imports)]
+pub use live_reload::{
+    inject_live_reload, LiveReloadLayer, LiveReloadMiddleware, LIVE_RELOAD_URL_ENV,
+};
+#[allow(unused_imports)]
+pub use maintenance::{
+    MaintenanceLayer, MaintenanceMiddleware, DEFAULT_RETRY_AFTER, MAINTENANCE_FLAG,
+};
+#[allow(unused_imports)]
+pub use normalize_path::{
+    NormalizePathConfig, NormalizePathLayer, NormalizePathMiddleware, TrailingSlash,
+};
+#[allow(unused_imports)]
+pub use query_log::{DevQueryLogLayer, DevQueryLogMiddleware, QUERY_COUNT_HEADER};
+#[allow(unused_imports)]
+pub use rate_limit::{RateLimit, RateLimitBuilder, RateLimitError, RouteRateLimitBuilder};
+#[allow(unused_imports)]
+pub use request_log::{RequestLogLayer, RequestLogMiddleware, REQUEST_LOG_TARGET};
+#[allow(unused_imports)]
+pub use request_span::{TracingLayer, TracingMiddleware, REQUEST_ID_HEADER};
 #[allow(unused_imports)]
 pub use security_headers::{
     FrameOptions, HstsConfig, ReferrerPolicy, SecurityHeadersConfig, SecurityHeadersLayer,
     SecurityHeadersMiddleware,
 };
 #[allow(unused_imports)]
-pub use session::{SameSite, SessionConfig, SessionLayer, SessionMiddleware, SESSION_COOKIE_NAME};
+pub use session::{
+    ConfirmSessionSave, RegenerateSessionId, SameSite, SessionConfig, SessionLayer,
+    SessionMiddleware, SESSION_COOKIE_NAME,
+};
+#[allow(unused_imports)]
+pub use tenant::{TenantLayer, TenantMiddleware};
 #[allow(unused_imports)]
 pub use helpers::is_htmx_request;
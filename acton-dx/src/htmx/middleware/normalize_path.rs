@@ -0,0 +1,346 @@
+//! Path normalization middleware
+//!
+//! Redirects requests to a canonical form of their path so that `/posts/`
+//! and `/Posts` do not become duplicate URLs (and do not break HTMX history
+//! snapshots keyed by URL):
+//! - Trailing slash handling: trim (`/path/` → `/path`) or append
+//!   (`/path` → `/path/`)
+//! - Optional lowercasing of the path
+//! - Repeated leading slashes and backslashes are always collapsed
+//!   (`//evil.com`, `/\evil.com` → `/evil.com`) so a redirect can never
+//!   become a protocol-relative, off-site `Location`
+//!
+//! Query strings are always preserved unchanged.
+//!
+//! # Redirect Semantics
+//!
+//! Only method-preserving redirects are used: `308 Permanent Redirect` by
+//! default, or `307 Temporary Redirect` when [`NormalizePathConfig::permanent`]
+//! is disabled. A `POST` body is therefore never turned into a `GET`.
+//!
+//! HTMX follows these redirects transparently inside its XHR, so the
+//! original `hx-target`/`hx-swap` still apply to the canonical response.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::{NormalizePathConfig, NormalizePathLayer, TrailingSlash};
+//! use axum::{routing::get, Router};
+//!
+//! let config = NormalizePathConfig::new()
+//!     .with_trailing_slash(TrailingSlash::Trim)
+//!     .with_lowercase(true);
+//!
+//! let app: Router = Router::new()
+//!     .route("/posts", get(|| async { "posts" }))
+//!     .layer(NormalizePathLayer::new(config));
+//! ```
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// How trailing slashes are normalized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingSlash {
+    /// Remove trailing slashes (`/path/` → `/path`)
+    #[default]
+    Trim,
+    /// Add a trailing slash (`/path` → `/path/`)
+    ///
+    /// Paths whose last segment contains a `.` (e.g. `/app.css`) are left
+    /// alone so static assets keep working.
+    Append,
+    /// Leave trailing slashes as-is
+    Ignore,
+}
+
+/// Configuration for path normalization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizePathConfig {
+    /// Trailing slash policy
+    pub trailing_slash: TrailingSlash,
+    /// Lowercase the path
+    pub lowercase: bool,
+    /// Use `308 Permanent Redirect` (true) or `307 Temporary Redirect` (false)
+    pub permanent: bool,
+}
+
+impl Default for NormalizePathConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NormalizePathConfig {
+    /// Trim trailing slashes, keep case, permanent redirects
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            trailing_slash: TrailingSlash::Trim,
+            lowercase: false,
+            permanent: true,
+        }
+    }
+
+    /// Set the trailing slash policy
+    #[must_use]
+    pub const fn with_trailing_slash(mut self, trailing_slash: TrailingSlash) -> Self {
+        self.trailing_slash = trailing_slash;
+        self
+    }
+
+    /// Enable or disable path lowercasing
+    #[must_use]
+    pub const fn with_lowercase(mut self, lowercase: bool) -> Self {
+        self.lowercase = lowercase;
+        self
+    }
+
+    /// Use permanent (308) or temporary (307) redirects
+    #[must_use]
+    pub const fn with_permanent(mut self, permanent: bool) -> Self {
+        self.permanent = permanent;
+        self
+    }
+
+    /// Compute the canonical form of `path`
+    ///
+    /// Returns `None` if the path is already canonical.
+    #[must_use]
+    pub fn normalize(&self, path: &str) -> Option<String> {
+        let mut normalized = if self.lowercase {
+            path.to_lowercase()
+        } else {
+            path.to_string()
+        };
+
+        match self.trailing_slash {
+            TrailingSlash::Trim => {
+                let trimmed = normalized.trim_end_matches('/');
+                normalized = if trimmed.is_empty() {
+                    "/".to_string()
+                } else {
+                    trimmed.to_string()
+                };
+            }
+            TrailingSlash::Append => {
+                let last_segment = normalized.rsplit('/').next().unwrap_or_default();
+                if !normalized.ends_with('/') && !last_segment.contains('.') {
+                    normalized.push('/');
+                }
+            }
+            TrailingSlash::Ignore => {}
+        }
+
+        // `//host` in a `Location` header is protocol-relative and would send
+        // the browser off-site. Browsers read `\` as `/`, so `/\host` is too.
+        let rest = normalized.trim_start_matches(['/', '\\']);
+        if normalized.len() - rest.len() > 1 {
+            normalized = format!("/{rest}");
+        }
+
+        (normalized != path).then_some(normalized)
+    }
+
+    const fn redirect_status(self) -> StatusCode {
+        if self.permanent {
+            StatusCode::PERMANENT_REDIRECT
+        } else {
+            StatusCode::TEMPORARY_REDIRECT
+        }
+    }
+}
+
+/// Layer that redirects requests to their canonical path
+#[derive(Debug, Clone, Default)]
+pub struct NormalizePathLayer {
+    config: NormalizePathConfig,
+}
+
+impl NormalizePathLayer {
+    /// Create a new path normalization layer
+    #[must_use]
+    pub const fn new(config: NormalizePathConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<S> Layer<S> for NormalizePathLayer {
+    type Service = NormalizePathMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        NormalizePathMiddleware {
+            inner,
+            config: self.config,
+        }
+    }
+}
+
+/// Path normalization middleware service
+#[derive(Debug, Clone)]
+pub struct NormalizePathMiddleware<S> {
+    inner: S,
+    config: NormalizePathConfig,
+}
+
+impl<S> Service<Request> for NormalizePathMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if let Some(path) = self.config.normalize(req.uri().path()) {
+            let location = req
+                .uri()
+                .query()
+                .map_or_else(|| path.clone(), |query| format!("{path}?{query}"));
+            let status = self.config.redirect_status();
+
+            return Box::pin(async move {
+                Ok((status, [(header::LOCATION, location)]).into_response())
+            });
+        }
+
+        let future = self.inner.call(req);
+        Box::pin(future)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(config: NormalizePathConfig) -> Router {
+        Router::new()
+            .route("/posts", get(|| async { "posts" }).post(|| async { "created" }))
+            .layer(NormalizePathLayer::new(config))
+    }
+
+    #[test]
+    fn test_normalize_trim() {
+        let config = NormalizePathConfig::new();
+        assert_eq!(config.normalize("/posts/"), Some("/posts".to_string()));
+        assert_eq!(config.normalize("/posts//"), Some("/posts".to_string()));
+        assert_eq!(config.normalize("/posts"), None);
+        assert_eq!(config.normalize("/"), None);
+    }
+
+    #[test]
+    fn test_normalize_append() {
+        let config = NormalizePathConfig::new().with_trailing_slash(TrailingSlash::Append);
+        assert_eq!(config.normalize("/posts"), Some("/posts/".to_string()));
+        assert_eq!(config.normalize("/posts/"), None);
+        assert_eq!(config.normalize("/static/app.css"), None);
+    }
+
+    #[test]
+    fn test_normalize_lowercase() {
+        let config = NormalizePathConfig::new()
+            .with_trailing_slash(TrailingSlash::Ignore)
+            .with_lowercase(true);
+        assert_eq!(config.normalize("/Posts/"), Some("/posts/".to_string()));
+        assert_eq!(config.normalize("/posts/"), None);
+    }
+
+    #[test]
+    fn test_normalize_collapses_leading_slashes() {
+        let config = NormalizePathConfig::new();
+        assert_eq!(config.normalize("//evil.com/"), Some("/evil.com".to_string()));
+        assert_eq!(config.normalize("///"), Some("/".to_string()));
+
+        assert_eq!(config.normalize("/\\evil.com/"), Some("/evil.com".to_string()));
+        assert_eq!(config.normalize("\\/\\evil.com"), Some("/evil.com".to_string()));
+
+        // `evil.com` looks like a file, so no trailing slash is appended
+        let config = NormalizePathConfig::new().with_trailing_slash(TrailingSlash::Append);
+        assert_eq!(config.normalize("//evil.com"), Some("/evil.com".to_string()));
+        assert_eq!(config.normalize("//evil"), Some("/evil/".to_string()));
+
+        let config = NormalizePathConfig::new().with_trailing_slash(TrailingSlash::Ignore);
+        assert_eq!(config.normalize("//evil.com/"), Some("/evil.com/".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_is_never_protocol_relative() {
+        let trailing_slashes = [TrailingSlash::Trim, TrailingSlash::Append, TrailingSlash::Ignore];
+        for trailing_slash in trailing_slashes {
+            for uri in ["//evil.com/", "/\\evil.com/", "/%5Cevil.com/"] {
+                let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+                let config = NormalizePathConfig::new()
+                    .with_trailing_slash(trailing_slash)
+                    .with_lowercase(true);
+                let response = app(config).oneshot(request).await.unwrap();
+
+                if let Some(location) = response.headers().get(header::LOCATION) {
+                    let location = location.to_str().unwrap();
+                    assert!(!location.starts_with("//"), "{uri} -> {location}");
+                    assert!(!location.starts_with("/\\"), "{uri} -> {location}");
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redirect_preserves_query() {
+        let request = Request::builder()
+            .uri("/Posts/?page=2&Sort=Title")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app(NormalizePathConfig::new().with_lowercase(true))
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            response.headers().get(header::LOCATION).unwrap(),
+            "/posts?page=2&Sort=Title"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_uses_method_preserving_redirect() {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/posts/")
+            .header("HX-Request", "true")
+            .body(Body::from("title=hello"))
+            .unwrap();
+
+        let response = app(NormalizePathConfig::new().with_permanent(false))
+            .oneshot(request)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(response.headers().get(header::LOCATION).unwrap(), "/posts");
+    }
+
+    #[tokio::test]
+    async fn test_canonical_path_passes_through() {
+        let request = Request::builder().uri("/posts").body(Body::empty()).unwrap();
+
+        let response = app(NormalizePathConfig::new()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
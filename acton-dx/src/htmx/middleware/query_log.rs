@@ -0,0 +1,302 @@
+//! Development query log middleware
+//!
+//! Captures the SQL queries issued while handling each request and attaches
+//! a summary to the response:
+//! - A `Server-Timing` header (`db` total and `db-slowest`), visible in the
+//!   browser devtools network panel for full pages and HTMX requests alike
+//! - An `X-Query-Count` header
+//! - Optionally, a small toolbar injected before `</body>` of full-page
+//!   (non-HTMX) HTML responses
+//!
+//! Queries are recorded by
+//! [`QueryCaptureLayer`](crate::htmx::observability::query_log::QueryCaptureLayer),
+//! which must be installed in the tracing subscriber.
+//!
+//! # Production Safety
+//!
+//! The middleware is a pass-through in release builds (`debug_assertions`
+//! off), regardless of configuration.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::DevQueryLogLayer;
+//! use axum::{routing::get, Router};
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "Hello" }))
+//!     .layer(DevQueryLogLayer::new().with_toolbar(true));
+//! ```
+
+use crate::htmx::middleware::is_htmx_request;
+use crate::htmx::observability::query_log::{QueryLog, QueryLogSummary, QueryRecord};
+use crate::htmx::template::escape_html;
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderValue},
+    response::Response,
+};
+use std::fmt::Write;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// Header carrying the number of queries of the request
+pub const QUERY_COUNT_HEADER: &str = "x-query-count";
+
+/// Largest HTML body the toolbar is injected into
+const MAX_TOOLBAR_BODY: usize = 8 * 1024 * 1024;
+
+/// Layer that attaches a per-request SQL query summary in debug builds
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DevQueryLogLayer {
+    toolbar: bool,
+}
+
+impl DevQueryLogLayer {
+    /// Create a query log layer emitting headers only
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { toolbar: false }
+    }
+
+    /// Inject an HTML toolbar into full-page responses
+    #[must_use]
+    pub const fn with_toolbar(mut self, toolbar: bool) -> Self {
+        self.toolbar = toolbar;
+        self
+    }
+}
+
+impl<S> Layer<S> for DevQueryLogLayer {
+    type Service = DevQueryLogMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DevQueryLogMiddleware {
+            inner,
+            toolbar: self.toolbar,
+        }
+    }
+}
+
+/// Development query log middleware service
+#[derive(Debug, Clone)]
+pub struct DevQueryLogMiddleware<S> {
+    inner: S,
+    toolbar: bool,
+}
+
+impl<S> Service<Request> for DevQueryLogMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if !cfg!(debug_assertions) {
+            return Box::pin(self.inner.call(req));
+        }
+
+        let inject_toolbar = self.toolbar && !is_htmx_request(req.headers());
+        let log = QueryLog::new();
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let mut response = log.scope(future).await?;
+            let summary = log.summary();
+
+            tracing::debug!(
+                queries = summary.count,
+                total_ms = summary.total.as_secs_f64() * 1000.0,
+                "Request query log"
+            );
+
+            if let Ok(value) = HeaderValue::from_str(&summary.server_timing()) {
+                response.headers_mut().append("server-timing", value);
+            }
+            response
+                .headers_mut()
+                .insert(QUERY_COUNT_HEADER, HeaderValue::from(summary.count));
+
+            if inject_toolbar && is_html(&response) && fits(response.body()) {
+                response = inject(response, &summary, &log.queries()).await;
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// Whether the body is known to be small enough to buffer
+///
+/// Larger bodies, and streams of unknown size, are passed through without
+/// the toolbar.
+fn fits(body: &Body) -> bool {
+    body.size_hint()
+        .upper()
+        .is_some_and(|len| usize::try_from(len).is_ok_and(|len| len <= MAX_TOOLBAR_BODY))
+}
+
+fn is_html(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"))
+        && !response.headers().contains_key(header::CONTENT_ENCODING)
+}
+
+/// Insert the toolbar before `</body>`, leaving other responses untouched
+async fn inject(response: Response, summary: &QueryLogSummary, queries: &[QueryRecord]) -> Response {
+    let (mut parts, body) = response.into_parts();
+
+    let bytes = match axum::body::to_bytes(body, MAX_TOOLBAR_BODY).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            // The body is gone, so its advertised length no longer holds
+            tracing::error!(error = %e, "Failed to buffer response for query log toolbar");
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let html = String::from_utf8_lossy(&bytes);
+    let Some(index) = html.rfind("</body>") else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let mut injected = String::with_capacity(html.len() + 512);
+    injected.push_str(&html[..index]);
+    injected.push_str(&render_toolbar(summary, queries));
+    injected.push_str(&html[index..]);
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(injected))
+}
+
+fn render_toolbar(summary: &QueryLogSummary, queries: &[QueryRecord]) -> String {
+    let mut html = format!(
+        "<details id=\"acton-query-log\" style=\"position:fixed;bottom:0;right:0;z-index:99999;\
+         max-width:50vw;max-height:50vh;overflow:auto;background:#111;color:#eee;\
+         font:12px monospace;padding:4px 8px\"><summary>{} queries, {:.2} ms</summary><ol>",
+        summary.count,
+        summary.total.as_secs_f64() * 1000.0
+    );
+    for query in queries {
+        let _ = write!(
+            html,
+            "<li>{:.2} ms &middot; {}</li>",
+            query.elapsed.as_secs_f64() * 1000.0,
+            escape_html(&query.statement)
+        );
+    }
+    html.push_str("</ol></details>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::observability::query_log::QueryCaptureLayer;
+    use axum::{http::StatusCode, response::Html, routing::get, Router};
+    use tower::ServiceExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    async fn handler() -> Html<&'static str> {
+        tracing::debug!(
+            target: "sqlx::query",
+            summary = "SELECT * FROM posts",
+            db.statement = "",
+            rows_affected = 0_u64,
+            rows_returned = 3_u64,
+            elapsed_secs = 0.005,
+        );
+        Html("<html><body><p>posts</p></body></html>")
+    }
+
+    fn app(layer: DevQueryLogLayer) -> Router {
+        Router::new().route("/", get(handler)).layer(layer)
+    }
+
+    #[tokio::test]
+    async fn test_server_timing_header() {
+        let subscriber = tracing_subscriber::registry().with(QueryCaptureLayer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app(DevQueryLogLayer::new()).oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(QUERY_COUNT_HEADER).unwrap(), "1");
+        assert_eq!(
+            response.headers().get("server-timing").unwrap(),
+            "db;dur=5.00;desc=\"1 queries\", db-slowest;dur=5.00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_toolbar_injected_for_full_pages_only() {
+        let subscriber = tracing_subscriber::registry().with(QueryCaptureLayer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app(DevQueryLogLayer::new().with_toolbar(true))
+            .oneshot(request)
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("id=\"acton-query-log\""));
+        assert!(body.contains("SELECT * FROM posts"));
+        assert!(body.ends_with("</details></body></html>"));
+
+        let request = Request::builder()
+            .uri("/")
+            .header("HX-Request", "true")
+            .body(Body::empty())
+            .unwrap();
+        let response = app(DevQueryLogLayer::new().with_toolbar(true))
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert!(response.headers().contains_key("server-timing"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"<html><body><p>posts</p></body></html>");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_pages_untouched() {
+        let subscriber = tracing_subscriber::registry().with(QueryCaptureLayer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let large = format!("<body>{}</body>", "x".repeat(MAX_TOOLBAR_BODY));
+        let page = large.clone();
+        let app = Router::new()
+            .route("/", get(move || async move { Html(page) }))
+            .layer(DevQueryLogLayer::new().with_toolbar(true));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(
+            response.headers().get(header::CONTENT_LENGTH).unwrap(),
+            &large.len().to_string()
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body.len(), large.len());
+    }
+}
@@ -8,6 +8,7 @@
 //!
 //! - **Multiple Identifiers**: Rate limit by user ID (authenticated), IP address (anonymous), or both
 //! - **Route-Specific Limits**: Apply stricter limits to sensitive endpoints (e.g., `/login`, `/register`)
+//! - **Per-Route Overrides**: Register individual limits per route pattern with [`RateLimit::builder`]
 //! - **Redis Backend**: Distributed rate limiting for multi-instance deployments (requires `cache` feature)
 //! - **In-Memory Fallback**: Automatic fallback to in-memory rate limiting if Redis is unavailable
 //! - **Failure Modes**: Configurable behavior on backend errors (fail-open or fail-closed)
@@ -41,6 +42,23 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Per-Route Overrides
+//!
+//! Overrides are matched in registration order and take precedence over
+//! `strict_routes` and the global per-user/per-IP limits. Patterns follow
+//! axum routing: exact paths, `:param`/`{param}` segments, and `*`/`{*rest}`
+//! wildcards.
+//!
+//! ```rust,no_run
+//! use acton_htmx::middleware::rate_limit::RateLimit;
+//!
+//! let rate_limit = RateLimit::builder()
+//!     .route("/login").rpm(5)
+//!     .route("/api/search").rpm(1000)
+//!     .route("/posts/:id/comments").rpm(20)
+//!     .build();
+//! ```
 
 #[cfg(feature = "redis")]
 use deadpool_redis::Pool as RedisPool;
@@ -72,6 +90,87 @@ struct RateLimitEntry {
 /// In-memory rate limit store
 type InMemoryStore = Arc<RwLock<HashMap<String, RateLimitEntry>>>;
 
+/// One segment of a route pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// Matches this exact segment
+    Literal(String),
+    /// Matches any single segment (`:id`, `{id}`, `*`)
+    Param,
+    /// Matches one or more trailing segments (`*rest`, `{*rest}`)
+    CatchAll,
+}
+
+/// Route pattern matched against request paths
+///
+/// Uses axum's routing syntax: `/users/:id`, `/users/{id}`, `/files/{*path}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RoutePattern {
+    /// Pattern as registered, used in rate limit keys
+    raw: String,
+    segments: Vec<Segment>,
+}
+
+impl RoutePattern {
+    /// Parse a route pattern
+    fn parse(pattern: &str) -> Self {
+        let segments = split_path(pattern)
+            .map(|segment| {
+                let inner = segment
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.strip_suffix('}'));
+                match (segment, inner) {
+                    (_, Some(name)) if name.starts_with('*') => Segment::CatchAll,
+                    (_, Some(_)) | ("*", None) => Segment::Param,
+                    (s, None) if s.starts_with('*') => Segment::CatchAll,
+                    (s, None) if s.starts_with(':') => Segment::Param,
+                    (s, None) => Segment::Literal(s.to_string()),
+                }
+            })
+            .collect();
+
+        Self {
+            raw: pattern.to_string(),
+            segments,
+        }
+    }
+
+    /// Check whether a request path matches this pattern
+    fn matches(&self, path: &str) -> bool {
+        let mut parts = split_path(path);
+
+        for segment in &self.segments {
+            match segment {
+                Segment::CatchAll => return parts.next().is_some(),
+                Segment::Param => {
+                    if parts.next().is_none() {
+                        return false;
+                    }
+                }
+                Segment::Literal(literal) => {
+                    if parts.next() != Some(literal.as_str()) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        parts.next().is_none()
+    }
+}
+
+/// Split a path into its non-empty segments (ignores trailing slashes)
+fn split_path(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+/// Per-route limit registered through [`RateLimitBuilder`]
+#[derive(Debug, Clone)]
+struct RouteLimit {
+    pattern: RoutePattern,
+    rpm: u32,
+}
+
 /// Rate limiting middleware
 ///
 /// Enforces configurable rate limits per user, IP address, and route.
@@ -82,6 +181,8 @@ pub struct RateLimit {
     #[cfg(feature = "redis")]
     redis_pool: Option<RedisPool>,
     in_memory_store: InMemoryStore,
+    /// Per-route overrides, checked in registration order
+    route_limits: Arc<[RouteLimit]>,
 }
 
 impl RateLimit {
@@ -119,6 +220,7 @@ impl RateLimit {
             config,
             redis_pool,
             in_memory_store: Arc::new(RwLock::new(HashMap::new())),
+            route_limits: Arc::from([]),
         }
     }
 
@@ -129,9 +231,18 @@ impl RateLimit {
         Self {
             config,
             in_memory_store: Arc::new(RwLock::new(HashMap::new())),
+            route_limits: Arc::from([]),
         }
     }
 
+    /// Create a builder for registering per-route limit overrides
+    ///
+    /// Starts from [`RateLimitConfig::default`] without Redis.
+    #[must_use]
+    pub fn builder() -> RateLimitBuilder {
+        RateLimitBuilder::default()
+    }
+
     /// Middleware function to enforce rate limits
     ///
     /// This middleware:
@@ -188,6 +299,19 @@ impl RateLimit {
         ip_addr: Option<&str>,
         path: &str,
     ) -> (String, u32) {
+        // Per-route overrides take precedence; first registered match wins
+        if let Some(route) = self
+            .route_limits
+            .iter()
+            .find(|route| route.pattern.matches(path))
+        {
+            let pattern = &route.pattern.raw;
+            let key = user_id.map_or_else(|| {
+                ip_addr.map_or_else(|| format!("ratelimit:route:{pattern}:unknown"), |ip| format!("ratelimit:route:{pattern}:ip:{ip}"))
+            }, |uid| format!("ratelimit:route:{pattern}:user:{uid}"));
+            return (key, route.rpm);
+        }
+
         // Check if path matches strict routes
         let is_strict_route = self
             .config
@@ -373,6 +497,96 @@ impl RateLimit {
     }
 }
 
+/// Builder for [`RateLimit`] with per-route overrides
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use acton_htmx::middleware::rate_limit::RateLimit;
+///
+/// let rate_limit = RateLimit::builder()
+///     .route("/login").rpm(5)
+///     .route("/api/search").rpm(1000)
+///     .build();
+/// ```
+#[derive(Clone, Default)]
+pub struct RateLimitBuilder {
+    config: RateLimitConfig,
+    #[cfg(feature = "redis")]
+    redis_pool: Option<RedisPool>,
+    route_limits: Vec<RouteLimit>,
+}
+
+impl std::fmt::Debug for RateLimitBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimitBuilder")
+            .field("config", &self.config)
+            .field("route_limits", &self.route_limits)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RateLimitBuilder {
+    /// Use `config` for global limits and `strict_routes`
+    #[must_use]
+    pub fn config(mut self, config: RateLimitConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Use Redis for distributed rate limiting
+    #[must_use]
+    #[cfg(feature = "redis")]
+    pub fn redis_pool(mut self, redis_pool: RedisPool) -> Self {
+        self.redis_pool = Some(redis_pool);
+        self
+    }
+
+    /// Start a limit override for requests matching `pattern`
+    ///
+    /// Complete it with [`RouteRateLimitBuilder::rpm`].
+    pub fn route(self, pattern: impl Into<String>) -> RouteRateLimitBuilder {
+        RouteRateLimitBuilder {
+            builder: self,
+            pattern: pattern.into(),
+        }
+    }
+
+    /// Build the rate limiting middleware
+    #[must_use]
+    pub fn build(self) -> RateLimit {
+        #[cfg(feature = "redis")]
+        let rate_limit = RateLimit::new(self.config, self.redis_pool);
+        #[cfg(not(feature = "redis"))]
+        let rate_limit = RateLimit::new(self.config, None);
+
+        RateLimit {
+            route_limits: self.route_limits.into(),
+            ..rate_limit
+        }
+    }
+}
+
+/// Pending per-route override returned by [`RateLimitBuilder::route`]
+#[derive(Debug)]
+#[must_use = "call `rpm` to register the route limit"]
+pub struct RouteRateLimitBuilder {
+    builder: RateLimitBuilder,
+    pattern: String,
+}
+
+impl RouteRateLimitBuilder {
+    /// Allow `rpm` requests per window on this route
+    #[must_use]
+    pub fn rpm(mut self, rpm: u32) -> RateLimitBuilder {
+        self.builder.route_limits.push(RouteLimit {
+            pattern: RoutePattern::parse(&self.pattern),
+            rpm,
+        });
+        self.builder
+    }
+}
+
 /// Rate limit errors
 #[derive(Debug, thiserror::Error)]
 pub enum RateLimitError {
@@ -615,4 +829,98 @@ mod tests {
         let error = RateLimitError::Backend("Redis connection failed".to_string());
         assert!(error.to_string().contains("Redis connection failed"));
     }
+
+    #[test]
+    fn test_route_pattern_matching() {
+        let exact = RoutePattern::parse("/login");
+        assert!(exact.matches("/login"));
+        assert!(exact.matches("/login/"));
+        assert!(!exact.matches("/login/extra"));
+        assert!(!exact.matches("/logout"));
+
+        for pattern in ["/posts/:id/comments", "/posts/{id}/comments", "/posts/*/comments"] {
+            let param = RoutePattern::parse(pattern);
+            assert!(param.matches("/posts/42/comments"), "{pattern}");
+            assert!(!param.matches("/posts/comments"), "{pattern}");
+            assert!(!param.matches("/posts/42/comments/7"), "{pattern}");
+        }
+
+        for pattern in ["/api/*rest", "/api/{*rest}"] {
+            let catch_all = RoutePattern::parse(pattern);
+            assert!(catch_all.matches("/api/search"), "{pattern}");
+            assert!(catch_all.matches("/api/v1/search/items"), "{pattern}");
+            assert!(!catch_all.matches("/api"), "{pattern}");
+            assert!(!catch_all.matches("/apis/search"), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn test_route_override_precedence() {
+        let rate_limit = RateLimit::builder()
+            .route("/login").rpm(5)
+            .route("/api/search").rpm(1000)
+            .build();
+
+        // Override beats strict_routes
+        let (key, limit) = rate_limit.determine_key_and_limit(None, Some("192.168.1.1"), "/login");
+        assert_eq!(key, "ratelimit:route:/login:ip:192.168.1.1");
+        assert_eq!(limit, 5);
+
+        // Override beats the global per-user limit
+        let (key, limit) = rate_limit.determine_key_and_limit(Some(123), None, "/api/search");
+        assert_eq!(key, "ratelimit:route:/api/search:user:123");
+        assert_eq!(limit, 1000);
+
+        // Config-driven limits still apply elsewhere
+        let (key, limit) = rate_limit.determine_key_and_limit(None, Some("192.168.1.1"), "/register");
+        assert_eq!(key, "ratelimit:route:ip:192.168.1.1");
+        assert_eq!(limit, 30);
+        let (_, limit) = rate_limit.determine_key_and_limit(Some(123), None, "/posts");
+        assert_eq!(limit, 120);
+    }
+
+    #[test]
+    fn test_route_overrides_first_match_wins() {
+        let rate_limit = RateLimit::builder()
+            .config(RateLimitConfig {
+                strict_routes: vec![],
+                ..RateLimitConfig::default()
+            })
+            .route("/api/admin/{*rest}").rpm(10)
+            .route("/api/*rest").rpm(500)
+            .build();
+
+        let (_, limit) = rate_limit.determine_key_and_limit(Some(1), None, "/api/admin/users");
+        assert_eq!(limit, 10);
+        let (_, limit) = rate_limit.determine_key_and_limit(Some(1), None, "/api/users");
+        assert_eq!(limit, 500);
+        let (_, limit) = rate_limit.determine_key_and_limit(Some(1), None, "/login");
+        assert_eq!(limit, 120);
+    }
+
+    #[tokio::test]
+    async fn test_route_override_enforced_by_middleware() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let rate_limit = RateLimit::builder()
+            .config(RateLimitConfig {
+                redis_enabled: false,
+                ..RateLimitConfig::default()
+            })
+            .route("/login").rpm(2)
+            .build();
+        let app = Router::new()
+            .route("/login", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(rate_limit, RateLimit::middleware));
+
+        for expected in [StatusCode::OK, StatusCode::OK, StatusCode::TOO_MANY_REQUESTS] {
+            let response = app
+                .clone()
+                .oneshot(Request::get("/login").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), expected);
+        }
+    }
 }
@@ -0,0 +1,281 @@
+//! Request logging with redaction
+//!
+//! Emits one `info` event per request on the `acton_dx::request` target with
+//! the method, path and query, headers, status, and latency. Sensitive
+//! headers, query parameters, and body fields are masked by a
+//! [`Redactor`] built from [`LoggingSettings`].
+//!
+//! Request bodies are only logged when enabled, when the body is form or
+//! JSON, and when its `Content-Length` is within `max_body_bytes`; streamed
+//! bodies of unknown length are never buffered.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::config::LoggingSettings;
+//! use acton_dx::htmx::middleware::RequestLogLayer;
+//! use axum::{routing::get, Router};
+//!
+//! let settings = LoggingSettings {
+//!     log_bodies: true,
+//!     ..LoggingSettings::default()
+//! };
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(|| async { "Hello" }))
+//!     .layer(RequestLogLayer::new(&settings));
+//! ```
+
+use crate::htmx::config::LoggingSettings;
+use crate::htmx::observability::redaction::Redactor;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderMap},
+    response::Response,
+};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+
+/// Tracing target of request log events
+pub const REQUEST_LOG_TARGET: &str = "acton_dx::request";
+
+/// Layer that logs each request with sensitive data redacted
+#[derive(Debug, Clone)]
+pub struct RequestLogLayer {
+    redactor: Arc<Redactor>,
+    log_bodies: bool,
+    max_body_bytes: usize,
+}
+
+impl Default for RequestLogLayer {
+    fn default() -> Self {
+        Self::new(&LoggingSettings::default())
+    }
+}
+
+impl RequestLogLayer {
+    /// Create a request log layer from logging settings
+    #[must_use]
+    pub fn new(settings: &LoggingSettings) -> Self {
+        Self {
+            redactor: Arc::new(Redactor::new(settings)),
+            log_bodies: settings.log_bodies,
+            max_body_bytes: settings.max_body_bytes,
+        }
+    }
+}
+
+impl<S> Layer<S> for RequestLogLayer {
+    type Service = RequestLogMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestLogMiddleware {
+            inner,
+            redactor: self.redactor.clone(),
+            log_bodies: self.log_bodies,
+            max_body_bytes: self.max_body_bytes,
+        }
+    }
+}
+
+/// Request logging middleware service
+#[derive(Debug, Clone)]
+pub struct RequestLogMiddleware<S> {
+    inner: S,
+    redactor: Arc<Redactor>,
+    log_bodies: bool,
+    max_body_bytes: usize,
+}
+
+impl<S> Service<Request> for RequestLogMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let redactor = self.redactor.clone();
+        let log_bodies = self.log_bodies;
+        let max_body_bytes = self.max_body_bytes;
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let method = req.method().clone();
+            let uri = redactor.redact_uri(
+                req.uri()
+                    .path_and_query()
+                    .map_or_else(|| req.uri().path(), |pq| pq.as_str()),
+            );
+            let headers = format!("{:?}", redactor.redact_headers(req.headers()));
+
+            let (req, body) = if log_bodies {
+                capture_body(req, &redactor, max_body_bytes).await
+            } else {
+                (req, None)
+            };
+
+            let response = inner.call(req).await?;
+
+            tracing::info!(
+                target: REQUEST_LOG_TARGET,
+                method = %method,
+                uri = %uri,
+                status = response.status().as_u16(),
+                latency_ms = start.elapsed().as_secs_f64() * 1000.0,
+                headers = %headers,
+                body = body.as_deref(),
+                "Request"
+            );
+
+            Ok(response)
+        })
+    }
+}
+
+/// Buffer a small form/JSON body, returning the request and its redacted body
+async fn capture_body(
+    req: Request,
+    redactor: &Redactor,
+    max_body_bytes: usize,
+) -> (Request, Option<String>) {
+    let Some(content_type) = loggable_content_type(req.headers(), max_body_bytes) else {
+        return (req, None);
+    };
+
+    let (parts, body) = req.into_parts();
+    match axum::body::to_bytes(body, max_body_bytes).await {
+        Ok(bytes) => {
+            let redacted = redactor.redact_body(&content_type, &bytes);
+            (Request::from_parts(parts, Body::from(bytes)), redacted)
+        }
+        Err(err) => {
+            // Content-Length lied; the body is gone, let the handler see an empty one
+            tracing::warn!(error = %err, "Failed to buffer request body for logging");
+            (Request::from_parts(parts, Body::empty()), None)
+        }
+    }
+}
+
+/// Content type of a body worth logging: form or JSON with a known, small length
+fn loggable_content_type(headers: &HeaderMap, max_body_bytes: usize) -> Option<String> {
+    let length: usize = headers
+        .get(header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    if length == 0 || length > max_body_bytes {
+        return None;
+    }
+
+    let content_type = headers.get(header::CONTENT_TYPE)?.to_str().ok()?;
+    let mime = content_type.split(';').next()?.trim();
+    (mime == "application/x-www-form-urlencoded" || mime == "application/json" || mime.ends_with("+json"))
+        .then(|| content_type.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Router};
+    use parking_lot::Mutex;
+    use std::io::Write;
+    use tower::ServiceExt;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn run(request: Request) -> (String, String) {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let settings = LoggingSettings {
+            log_bodies: true,
+            ..LoggingSettings::default()
+        };
+        let app = Router::new()
+            .route("/login", post(|body: String| async move { body }))
+            .layer(RequestLogLayer::new(&settings));
+
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        let log = String::from_utf8(captured.0.lock().clone()).unwrap();
+        (log, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_form_body_and_headers_redacted() {
+        let body = "email=a%40b.com&password=hunter2&_csrf_token=tok";
+        let request = Request::builder()
+            .method("POST")
+            .uri("/login?token=abc&next=%2F")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::CONTENT_LENGTH, body.len())
+            .header(header::COOKIE, "acton_session=secret-session")
+            .body(Body::from(body))
+            .unwrap();
+
+        let (log, echoed) = run(request).await;
+
+        // Handler still receives the original body
+        assert_eq!(echoed, body);
+
+        assert!(log.contains("/login?token=[REDACTED]&next=%2F"));
+        assert!(log.contains("email=a%40b.com&password=[REDACTED]&_csrf_token=[REDACTED]"));
+        assert!(!log.contains("hunter2"));
+        assert!(!log.contains("secret-session"));
+        assert!(log.contains("status=200"));
+    }
+
+    #[tokio::test]
+    async fn test_nested_json_body_redacted() {
+        let body = r#"{"user":{"email":"a@b.com","password":"hunter2"}}"#;
+        let request = Request::builder()
+            .method("POST")
+            .uri("/login")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::CONTENT_LENGTH, body.len())
+            .header(header::AUTHORIZATION, "Bearer top-secret")
+            .body(Body::from(body))
+            .unwrap();
+
+        let (log, echoed) = run(request).await;
+
+        assert_eq!(echoed, body);
+        assert!(log.contains("a@b.com"));
+        assert!(!log.contains("hunter2"));
+        assert!(!log.contains("top-secret"));
+    }
+}
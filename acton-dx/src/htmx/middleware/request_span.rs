@@ -0,0 +1,324 @@
+//! Per-request tracing spans
+//!
+//! [`TracingLayer`] opens one `info` span per request on the
+//! `acton_dx::request` target and runs the handler inside it, so every event
+//! logged while handling the request carries its context. The span records:
+//! - `method` and `route` (the matched route template such as `/posts/{id}`,
+//!   never the raw path, to keep cardinality low)
+//! - `request_id`, also sent back in the `X-Request-Id` response header
+//! - `hx_request`, `hx_target`, and `hx_trigger` for HTMX requests
+//! - `status` and `latency_ms` once the response is ready
+//!
+//! With [`TracingLayer::with_recorder`], each finished request is also
+//! counted in a [`PrometheusRecorder`] under the same route label.
+//!
+//! An incoming `X-Request-Id` is reused when it is short and printable, so
+//! ids from a load balancer stay correlated; otherwise a UUID is generated.
+//! The id is also set on the request headers for downstream middleware and
+//! handlers.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::TracingLayer;
+//! use axum::{routing::get, Router};
+//!
+//! let app: Router = Router::new()
+//!     .route("/posts/{id}", get(|| async { "Post" }))
+//!     .layer(TracingLayer::new());
+//! ```
+
+use super::request_log::REQUEST_LOG_TARGET;
+use crate::htmx::observability::metrics::PrometheusRecorder;
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request},
+    http::{HeaderMap, HeaderValue},
+    response::Response,
+};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+use tracing::{field::Empty, Instrument};
+
+/// Header carrying the request id
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest incoming request id that is reused
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Layer that wraps each request in a tracing span with HTMX metadata
+#[derive(Debug, Clone, Default)]
+pub struct TracingLayer {
+    recorder: Option<PrometheusRecorder>,
+}
+
+impl TracingLayer {
+    /// Create a tracing layer
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { recorder: None }
+    }
+
+    /// Also count requests and latency in `recorder`
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: PrometheusRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+}
+
+impl<S> Layer<S> for TracingLayer {
+    type Service = TracingMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TracingMiddleware {
+            inner,
+            recorder: self.recorder.clone(),
+        }
+    }
+}
+
+/// Request tracing middleware service
+#[derive(Debug, Clone)]
+pub struct TracingMiddleware<S> {
+    inner: S,
+    recorder: Option<PrometheusRecorder>,
+}
+
+impl<S> Service<Request> for TracingMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let recorder = self.recorder.clone();
+
+        let request_id = request_id(req.headers());
+        req.headers_mut()
+            .insert(REQUEST_ID_HEADER, request_id.clone());
+
+        let method = req.method().clone();
+        let route = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map_or("<unmatched>", MatchedPath::as_str)
+            .to_string();
+        let headers = req.headers();
+        let span = tracing::info_span!(
+            target: REQUEST_LOG_TARGET,
+            "request",
+            method = %method,
+            route = %route,
+            request_id = request_id.to_str().unwrap_or_default(),
+            hx_request = Empty,
+            hx_target = Empty,
+            hx_trigger = Empty,
+            status = Empty,
+            latency_ms = Empty,
+        );
+        if super::is_htmx_request(headers) {
+            span.record("hx_request", true);
+            if let Some(target) = header_str(headers, "hx-target") {
+                span.record("hx_target", target);
+            }
+            if let Some(trigger) = header_str(headers, "hx-trigger") {
+                span.record("hx_trigger", trigger);
+            }
+        }
+
+        Box::pin(
+            async move {
+                let start = Instant::now();
+                let mut response = inner.call(req).await?;
+
+                let status = response.status().as_u16();
+                let latency = start.elapsed();
+                let span = tracing::Span::current();
+                span.record("status", status);
+                span.record("latency_ms", latency.as_secs_f64() * 1000.0);
+                if let Some(recorder) = recorder {
+                    recorder.record_request(method.as_str(), &route, status, latency);
+                }
+
+                response
+                    .headers_mut()
+                    .insert(REQUEST_ID_HEADER, request_id);
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Reuse a sane incoming request id, or generate one
+fn request_id(headers: &HeaderMap) -> HeaderValue {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .filter(|value| {
+            let bytes = value.as_bytes();
+            !bytes.is_empty()
+                && bytes.len() <= MAX_REQUEST_ID_LEN
+                && bytes.iter().all(u8::is_ascii_graphic)
+        })
+        .cloned()
+        .unwrap_or_else(|| {
+            HeaderValue::from_str(&uuid::Uuid::new_v4().to_string())
+                .expect("UUID is a valid header value")
+        })
+}
+
+/// Header value as a string, if present and valid UTF-8
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Subscriber,
+    };
+    use tracing_subscriber::{
+        layer::{self, SubscriberExt},
+        registry::LookupSpan,
+    };
+
+    /// Collects the fields of `request` spans
+    #[derive(Clone, Default)]
+    struct SpanFields(Arc<Mutex<HashMap<String, String>>>);
+
+    impl Visit for SpanFields {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl<S> layer::Layer<S> for SpanFields
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: layer::Context<'_, S>) {
+            if attrs.metadata().name() == "request" {
+                attrs.record(&mut self.clone());
+            }
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: layer::Context<'_, S>) {
+            if ctx.span(id).is_some_and(|span| span.name() == "request") {
+                values.record(&mut self.clone());
+            }
+        }
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/posts/{id}", get(|| async { "Post" }))
+            .layer(TracingLayer::new())
+    }
+
+    #[tokio::test]
+    async fn test_htmx_request_span_fields() {
+        let fields = SpanFields::default();
+        let subscriber = tracing_subscriber::registry().with(fields.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let request = Request::builder()
+            .uri("/posts/42")
+            .header("HX-Request", "true")
+            .header("HX-Target", "#post")
+            .header("HX-Trigger", "load-post")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+
+        let request_id = response.headers()[REQUEST_ID_HEADER].to_str().unwrap();
+        let fields = fields.0.lock().unwrap().clone();
+        assert_eq!(fields["method"], "GET");
+        assert_eq!(fields["route"], "/posts/{id}");
+        assert_eq!(fields["request_id"], request_id);
+        assert_eq!(fields["hx_request"], "true");
+        assert_eq!(fields["hx_target"], "#post");
+        assert_eq!(fields["hx_trigger"], "load-post");
+        assert_eq!(fields["status"], "200");
+        assert!(fields.contains_key("latency_ms"));
+    }
+
+    #[tokio::test]
+    async fn test_regular_request_has_no_htmx_fields() {
+        let fields = SpanFields::default();
+        let subscriber = tracing_subscriber::registry().with(fields.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let request = Request::builder()
+            .uri("/posts/42")
+            .body(Body::empty())
+            .unwrap();
+        app().oneshot(request).await.unwrap();
+
+        let fields = fields.0.lock().unwrap().clone();
+        assert_eq!(fields["route"], "/posts/{id}");
+        assert!(!fields.contains_key("hx_request"));
+        assert!(!fields.contains_key("hx_target"));
+    }
+
+    #[tokio::test]
+    async fn test_recorder_counts_matched_route() {
+        let recorder = PrometheusRecorder::new();
+        let app = Router::new()
+            .route("/posts/{id}", get(|| async { "Post" }))
+            .layer(TracingLayer::new().with_recorder(recorder.clone()));
+
+        for post in [1, 2] {
+            let request = Request::builder()
+                .uri(format!("/posts/{post}"))
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let output = recorder.render(None);
+        assert!(output.contains(
+            r#"http_requests_total{method="GET",route="/posts/{id}",status="200"} 2"#
+        ));
+        assert!(!output.contains("/posts/1"));
+    }
+
+    #[test]
+    fn test_request_id_reuse() {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("lb-1234"));
+        assert_eq!(request_id(&headers), "lb-1234");
+
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("has space"));
+        let generated = request_id(&headers);
+        assert!(uuid::Uuid::parse_str(generated.to_str().unwrap()).is_ok());
+    }
+}
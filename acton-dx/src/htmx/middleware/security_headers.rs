@@ -8,6 +8,10 @@
 //! - Content-Security-Policy: Control resource loading
 //! - Referrer-Policy: Control referrer information
 //!
+//! Policies built with [`CspBuilder`](super::csp::CspBuilder) nonces get a
+//! fresh nonce per request, available to handlers as
+//! [`CspNonce`](super::csp::CspNonce).
+//!
 //! # Example
 //!
 //! ```rust,no_run
@@ -29,6 +33,8 @@ use axum::{
 };
 use std::fmt;
 
+use super::csp::{CspNonce, NONCE_PLACEHOLDER};
+
 /// Configuration for security headers middleware
 ///
 /// Provides preset configurations for different security levels:
@@ -60,7 +66,7 @@ pub struct SecurityHeadersConfig {
     pub hsts: Option<HstsConfig>,
 
     /// Content-Security-Policy header
-    /// - Some(policy): Set CSP policy
+    /// - Some(policy): Set CSP policy; see [`CspBuilder`](super::csp::CspBuilder)
     /// - None: Disable header
     pub csp: Option<String>,
 
@@ -339,20 +345,44 @@ where
         self.inner.poll_ready(cx)
     }
 
-    fn call(&mut self, request: Request<Body>) -> Self::Future {
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
         let config = self.config.clone();
+        let nonce = request_nonce(&mut request, &config);
         let future = self.inner.call(request);
 
         Box::pin(async move {
-            let mut response = future.await?;
-            add_security_headers(&mut response, &config);
+            let mut response = match &nonce {
+                Some(nonce) => nonce.scope(future).await?,
+                None => future.await?,
+            };
+            add_security_headers(&mut response, &config, nonce.as_ref());
             Ok(response)
         })
     }
 }
 
+/// Generate a nonce for the request if the policy uses one
+///
+/// The nonce is added to the request extensions; the caller also runs the
+/// inner service in [`CspNonce::scope`] so template helpers can read it.
+fn request_nonce(request: &mut Request<Body>, config: &SecurityHeadersConfig) -> Option<CspNonce> {
+    let uses_nonce = config
+        .csp
+        .as_ref()
+        .is_some_and(|csp| csp.contains(NONCE_PLACEHOLDER));
+    uses_nonce.then(|| {
+        let nonce = CspNonce::generate();
+        request.extensions_mut().insert(nonce.clone());
+        nonce
+    })
+}
+
 /// Add security headers to a response
-fn add_security_headers(response: &mut Response<Body>, config: &SecurityHeadersConfig) {
+fn add_security_headers(
+    response: &mut Response<Body>,
+    config: &SecurityHeadersConfig,
+    nonce: Option<&CspNonce>,
+) {
     let headers = response.headers_mut();
 
     // X-Frame-Options
@@ -388,6 +418,7 @@ fn add_security_headers(response: &mut Response<Body>, config: &SecurityHeadersC
 
     // Content-Security-Policy
     if let Some(csp) = &config.csp {
+        let csp = nonce.map_or_else(|| csp.clone(), |nonce| nonce.apply(csp));
         headers.insert(
             header::CONTENT_SECURITY_POLICY,
             csp.parse().unwrap(),
@@ -422,18 +453,23 @@ fn add_security_headers(response: &mut Response<Body>, config: &SecurityHeadersC
 /// # }
 /// ```
 pub async fn security_headers(
-    request: Request<Body>,
+    mut request: Request<Body>,
     next: Next,
     config: SecurityHeadersConfig,
 ) -> impl IntoResponse {
-    let mut response = next.run(request).await;
-    add_security_headers(&mut response, &config);
+    let nonce = request_nonce(&mut request, &config);
+    let mut response = match &nonce {
+        Some(nonce) => nonce.scope(next.run(request)).await,
+        None => next.run(request).await,
+    };
+    add_security_headers(&mut response, &config, nonce.as_ref());
     response
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::htmx::middleware::csp::CspBuilder;
     use axum::{
         body::Body,
         http::{Request, StatusCode},
@@ -517,6 +553,50 @@ mod tests {
         assert!(!headers.contains_key("content-security-policy"));
     }
 
+    #[tokio::test]
+    async fn test_csp_nonce_propagation() {
+        let config = SecurityHeadersConfig::custom().with_csp(CspBuilder::htmx().build());
+        let app = Router::new()
+            .route("/", get(|nonce: CspNonce| async move { nonce.to_string() }))
+            .layer(SecurityHeadersLayer::new(config));
+
+        let mut nonces = Vec::new();
+        for _ in 0..2 {
+            let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let csp = response.headers()[header::CONTENT_SECURITY_POLICY]
+                .to_str()
+                .unwrap()
+                .to_string();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let nonce = String::from_utf8(body.to_vec()).unwrap();
+
+            // The header carries the same nonce the handler saw
+            assert!(csp.contains(&format!("script-src 'self' 'nonce-{nonce}'")));
+            assert!(!csp.contains(NONCE_PLACEHOLDER));
+            nonces.push(nonce);
+        }
+        assert_ne!(nonces[0], nonces[1]);
+    }
+
+    #[tokio::test]
+    async fn test_no_nonce_without_nonce_policy() {
+        let app = Router::new()
+            .route("/", get(|nonce: Option<CspNonce>| async move { nonce.is_some().to_string() }))
+            .layer(SecurityHeadersLayer::new(SecurityHeadersConfig::strict()));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"false");
+    }
+
     #[test]
     fn test_hsts_config_display() {
         let hsts = HstsConfig::strict();
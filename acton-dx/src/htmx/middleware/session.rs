@@ -3,17 +3,39 @@
 //! Provides middleware that handles session cookie extraction, validation,
 //! and persistence across requests. Integrates with the `SessionManagerAgent`
 //! for session storage.
+//!
+//! The session cookie carries the session ID signed with a [`CookieSigner`],
+//! so forged or expired cookies start a fresh session without an agent
+//! lookup.
+//!
+//! The session ID is regenerated whenever the logged-in user changes, so an
+//! ID planted before login (session fixation) is useless afterwards; see
+//! [`SessionConfig::regenerate_on_user_change`] and [`RegenerateSessionId`].
+//!
+//! Each session records the client's `User-Agent` and IP address for
+//! listing a user's active sessions. Logins send [`LimitUserSessions`] so the
+//! session manager can enforce its per-user session limit.
+//!
+//! With [`SessionLayer::with_remember_me`], requests without a live session
+//! are logged back in from a remember-me cookie; see
+//! [`remember`](crate::htmx::auth::remember).
 
-use crate::htmx::agents::{LoadSession, SaveSession};
+use super::cookie_signer::CookieSigner;
+use crate::htmx::agents::{
+    DeleteSession, LimitUserSessions, LoadSession, RegenerateSession, SaveSession,
+};
+use crate::htmx::auth::remember::{ForgetAllLogins, ForgetLogin, RememberLogin, RememberMe};
 use crate::htmx::auth::session::{SessionData, SessionId};
+use crate::htmx::config::SameSitePolicy;
 use crate::htmx::state::ActonHtmxState;
 use acton_reactive::prelude::{AgentHandle, AgentHandleInterface};
 use axum::{
     body::Body,
-    extract::Request,
-    http::header::{COOKIE, SET_COOKIE},
+    extract::{ConnectInfo, Request},
+    http::header::{COOKIE, SET_COOKIE, USER_AGENT},
     response::Response,
 };
+use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -23,7 +45,32 @@ use tower::{Layer, Service};
 /// Session cookie name
 pub const SESSION_COOKIE_NAME: &str = "acton_session";
 
+/// Longest `User-Agent` kept in session data, in characters
+const MAX_USER_AGENT_CHARS: usize = 256;
+
+/// [`CookieSigner::for_purpose`] key of the session cookie
+const SIGNING_PURPOSE: &str = "session";
+
+/// Response extension requesting a confirmed session save
+///
+/// By default the session is saved fire-and-forget after the handler runs.
+/// When a response carries this marker, the middleware waits for the session
+/// manager to confirm the write (bounded by `agent_timeout_ms`) before the
+/// response is sent, so a redirect target is guaranteed to see the update.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConfirmSessionSave;
+
+/// Response extension requesting a new session ID
+///
+/// The middleware moves the session to a freshly generated ID, invalidates
+/// the old one, and sends the new session cookie. The login and logout
+/// handlers set this so the ID changes even when
+/// [`SessionConfig::regenerate_on_user_change`] is off.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RegenerateSessionId;
+
 /// Session configuration for middleware
+#[allow(clippy::struct_excessive_bools)] // Independent cookie and regeneration switches
 #[derive(Clone, Debug)]
 pub struct SessionConfig {
     /// Cookie name for session ID
@@ -40,6 +87,14 @@ pub struct SessionConfig {
     pub max_age_secs: u64,
     /// Timeout for agent communication in milliseconds
     pub agent_timeout_ms: u64,
+    /// Regenerate the session ID whenever the session's `user_id` changes
+    /// (default: true)
+    pub regenerate_on_user_change: bool,
+    /// Keep custom session data when the ID is regenerated (default: true)
+    ///
+    /// When false, only the user ID and flash messages carry over to the new
+    /// session.
+    pub preserve_data_on_regenerate: bool,
 }
 
 impl Default for SessionConfig {
@@ -52,12 +107,14 @@ impl Default for SessionConfig {
             same_site: SameSite::Lax,
             max_age_secs: 86400, // 24 hours
             agent_timeout_ms: 100,
+            regenerate_on_user_change: true,
+            preserve_data_on_regenerate: true,
         }
     }
 }
 
 /// SameSite cookie policy
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum SameSite {
     /// Strict same-site policy
     Strict,
@@ -68,6 +125,16 @@ pub enum SameSite {
     None,
 }
 
+impl From<SameSitePolicy> for SameSite {
+    fn from(policy: SameSitePolicy) -> Self {
+        match policy {
+            SameSitePolicy::Strict => Self::Strict,
+            SameSitePolicy::Lax => Self::Lax,
+            SameSitePolicy::None => Self::None,
+        }
+    }
+}
+
 impl SameSite {
     /// Convert to cookie attribute string
     #[must_use]
@@ -88,6 +155,8 @@ impl SameSite {
 pub struct SessionLayer {
     config: SessionConfig,
     session_manager: AgentHandle,
+    signer: CookieSigner,
+    remember_me: Option<RememberMe>,
 }
 
 impl std::fmt::Debug for SessionLayer {
@@ -95,18 +164,19 @@ impl std::fmt::Debug for SessionLayer {
         f.debug_struct("SessionLayer")
             .field("config", &self.config)
             .field("session_manager", &"AgentHandle")
+            .field("signer", &self.signer)
+            .field("remember_me", &self.remember_me)
             .finish()
     }
 }
 
 impl SessionLayer {
     /// Create new session layer with session manager from state
+    ///
+    /// Session cookies are signed with the state's cookie signer.
     #[must_use]
     pub fn new(state: &ActonHtmxState) -> Self {
-        Self {
-            config: SessionConfig::default(),
-            session_manager: state.session_manager().clone(),
-        }
+        Self::with_config(state, SessionConfig::default())
     }
 
     /// Create session layer with custom configuration
@@ -115,27 +185,60 @@ impl SessionLayer {
         Self {
             config,
             session_manager: state.session_manager().clone(),
+            signer: state.cookie_signer().clone(),
+            remember_me: None,
         }
     }
 
     /// Create session layer from an existing agent handle
+    ///
+    /// Session cookies are signed with a random key unless
+    /// [`with_signer`](Self::with_signer) is called.
     #[must_use]
     pub fn from_handle(session_manager: AgentHandle) -> Self {
         Self {
             config: SessionConfig::default(),
             session_manager,
+            signer: CookieSigner::random(),
+            remember_me: None,
         }
     }
+
+    /// Sign session cookies with `signer`
+    #[must_use]
+    pub fn with_signer(mut self, signer: CookieSigner) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// Re-establish sessions from remember-me cookies
+    ///
+    /// When a request has no live session but carries a valid remember-me
+    /// cookie, the new session is logged in as the token's user and the
+    /// token is rotated. Responses carrying [`RememberLogin`] or
+    /// [`ForgetLogin`] issue or revoke the cookie; [`ForgetAllLogins`]
+    /// revokes every token of a user.
+    #[must_use]
+    pub fn with_remember_me(mut self, remember_me: RememberMe) -> Self {
+        self.remember_me = Some(remember_me);
+        self
+    }
 }
 
 impl<S> Layer<S> for SessionLayer {
     type Service = SessionMiddleware<S>;
 
     fn layer(&self, inner: S) -> Self::Service {
+        let signer = self
+            .signer
+            .for_purpose(SIGNING_PURPOSE)
+            .with_max_age(Duration::from_secs(self.config.max_age_secs));
         SessionMiddleware {
             inner,
             config: Arc::new(self.config.clone()),
             session_manager: self.session_manager.clone(),
+            signer,
+            remember_me: self.remember_me.clone(),
         }
     }
 }
@@ -149,6 +252,8 @@ pub struct SessionMiddleware<S> {
     inner: S,
     config: Arc<SessionConfig>,
     session_manager: AgentHandle,
+    signer: CookieSigner,
+    remember_me: Option<RememberMe>,
 }
 
 impl<S: std::fmt::Debug> std::fmt::Debug for SessionMiddleware<S> {
@@ -157,6 +262,8 @@ impl<S: std::fmt::Debug> std::fmt::Debug for SessionMiddleware<S> {
             .field("inner", &self.inner)
             .field("config", &self.config)
             .field("session_manager", &"AgentHandle")
+            .field("signer", &self.signer)
+            .field("remember_me", &self.remember_me)
             .finish()
     }
 }
@@ -179,15 +286,17 @@ where
     fn call(&mut self, mut req: Request) -> Self::Future {
         let config = self.config.clone();
         let session_manager = self.session_manager.clone();
+        let signer = self.signer.clone();
+        let remember_me = self.remember_me.clone();
         let mut inner = self.inner.clone();
         let timeout = Duration::from_millis(config.agent_timeout_ms);
 
         Box::pin(async move {
             // Extract session ID from cookie
-            let existing_session_id = extract_session_id(&req, &config.cookie_name);
+            let existing_session_id = extract_session_id(&req, &config.cookie_name, &signer);
 
             // Load or create session
-            let (session_id, session_data, is_new) = if let Some(id) = existing_session_id {
+            let (session_id, mut session_data, is_new) = if let Some(id) = existing_session_id {
                 // Try to load existing session from agent
                 let (request, rx) = LoadSession::with_response(id.clone());
                 session_manager.send(request).await;
@@ -206,6 +315,28 @@ where
                 (id, SessionData::new(), true)
             };
 
+            // Log a new session back in from the remember-me cookie
+            let remember_cookie = remember_me
+                .as_ref()
+                .and_then(|remember| cookie_value(&req, remember.cookie_name()))
+                .map(ToString::to_string);
+            let mut rotated_cookie = None;
+            if let (true, Some(remember), Some(cookie)) =
+                (is_new, remember_me.as_ref(), remember_cookie.as_deref())
+            {
+                match remember.rotate(cookie).await {
+                    Ok(Some((user_id, value))) => {
+                        session_data.user_id = Some(user_id);
+                        rotated_cookie = Some(value);
+                    }
+                    Ok(None) => rotated_cookie = Some(String::new()),
+                    Err(e) => tracing::error!(error = %e, "Remember-me login failed"),
+                }
+            }
+
+            let initial_user_id = session_data.user_id;
+            record_client(&req, &mut session_data);
+
             // Insert session into request extensions for handlers to access
             req.extensions_mut().insert(session_id.clone());
             req.extensions_mut().insert(session_data.clone());
@@ -215,19 +346,67 @@ where
 
             // Get potentially modified session data from response extensions
             // (handlers can modify it via SessionExtractor)
-            let final_session_data = response
+            let mut final_session_data = response
                 .extensions()
                 .get::<SessionData>()
                 .cloned()
                 .unwrap_or(session_data);
 
-            // Save session to agent (fire-and-forget for performance)
-            let save_request = SaveSession::new(session_id.clone(), final_session_data);
-            session_manager.send(save_request).await;
+            // New sessions already have an ID nobody else knows
+            let regenerate = !is_new
+                && (response.extensions().get::<RegenerateSessionId>().is_some()
+                    || (config.regenerate_on_user_change
+                        && final_session_data.user_id != initial_user_id));
+            // Remember-me restores count as logins too
+            let logged_in = final_session_data
+                .user_id
+                .filter(|_| is_new || final_session_data.user_id != initial_user_id);
+
+            let session_id = if regenerate {
+                if !config.preserve_data_on_regenerate {
+                    final_session_data = fresh_session_data(final_session_data);
+                }
+                regenerate_session_id(&session_manager, session_id, timeout).await
+            } else {
+                session_id
+            };
+
+            let confirm = response.extensions().get::<ConfirmSessionSave>().is_some();
+            save_session(&session_manager, &session_id, final_session_data, confirm, timeout)
+                .await;
 
-            // Set session cookie if new
-            if is_new {
-                set_session_cookie(&mut response, &session_id, &config);
+            if let Some(user_id) = logged_in {
+                session_manager
+                    .send(LimitUserSessions::new(user_id, session_id.clone()))
+                    .await;
+            }
+
+            // Set session cookie if new or regenerated
+            if is_new || regenerate {
+                set_session_cookie(&mut response, &session_id, &signer, &config);
+            }
+
+            if let Some(remember) = &remember_me {
+                let forget_all = response.extensions().get::<ForgetAllLogins>().copied();
+                if let Some(ForgetAllLogins { user_id }) = forget_all {
+                    if let Err(e) = remember.revoke_all(user_id).await {
+                        tracing::error!(error = %e, "Failed to revoke remember-me tokens");
+                    }
+                }
+                let forget =
+                    forget_all.is_some() || response.extensions().get::<ForgetLogin>().is_some();
+                let login = response.extensions().get::<RememberLogin>().copied();
+                let cookie = remember_cookie_update(
+                    remember,
+                    forget,
+                    login,
+                    remember_cookie.as_deref(),
+                    rotated_cookie,
+                )
+                .await;
+                if let Some(value) = cookie {
+                    set_remember_cookie(&mut response, remember, &value, &config);
+                }
             }
 
             Ok(response)
@@ -235,34 +414,182 @@ where
     }
 }
 
-/// Extract session ID from request cookies
-fn extract_session_id(req: &Request, cookie_name: &str) -> Option<SessionId> {
-    let cookie_header = req.headers().get(COOKIE)?;
-    let cookie_str = cookie_header.to_str().ok()?;
-
-    // Parse cookies looking for our session cookie
-    for cookie in cookie_str.split(';') {
-        let cookie = cookie.trim();
-        if let Some((name, value)) = cookie.split_once('=') {
-            if name.trim() == cookie_name {
-                return SessionId::from_str(value.trim()).ok();
+/// Move the session to a new ID through the session manager
+///
+/// If the manager doesn't answer in time, a new ID is used anyway and the old
+/// session is deleted.
+async fn regenerate_session_id(
+    session_manager: &AgentHandle,
+    old_id: SessionId,
+    timeout: Duration,
+) -> SessionId {
+    let (request, rx) = RegenerateSession::with_response(old_id.clone());
+    session_manager.send(request).await;
+    if let Ok(Ok(new_id)) = tokio::time::timeout(timeout, rx).await {
+        return new_id;
+    }
+
+    tracing::warn!("Session regeneration was not confirmed; discarding the old session");
+    session_manager
+        .send(DeleteSession {
+            session_id: old_id,
+        })
+        .await;
+    SessionId::generate()
+}
+
+/// Save the session through the session manager
+///
+/// Fire-and-forget for performance unless `confirm` is set, in which case
+/// the write is awaited so the next request sees it.
+async fn save_session(
+    session_manager: &AgentHandle,
+    session_id: &SessionId,
+    data: SessionData,
+    confirm: bool,
+    timeout: Duration,
+) {
+    if confirm {
+        let (save_request, rx) = SaveSession::with_confirmation(session_id.clone(), data);
+        session_manager.send(save_request).await;
+        if !matches!(tokio::time::timeout(timeout, rx).await, Ok(Ok(true))) {
+            tracing::warn!("Session save was not confirmed before responding");
+        }
+    } else {
+        session_manager
+            .send(SaveSession::new(session_id.clone(), data))
+            .await;
+    }
+}
+
+/// Session data for a regenerated session that drops custom data
+///
+/// Keeps the login, flash messages, expiry, and client details.
+fn fresh_session_data(data: SessionData) -> SessionData {
+    SessionData {
+        user_id: data.user_id,
+        user_name: data.user_name,
+        flash_messages: data.flash_messages,
+        expires_at: data.expires_at,
+        user_agent: data.user_agent,
+        ip_address: data.ip_address,
+        ..SessionData::new()
+    }
+}
+
+/// Record the request's `User-Agent` and peer IP address in the session
+///
+/// The IP address is only known when the server is run with
+/// `into_make_service_with_connect_info::<SocketAddr>()`.
+fn record_client(req: &Request, data: &mut SessionData) {
+    if let Some(user_agent) = req
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+    {
+        data.user_agent = Some(user_agent.chars().take(MAX_USER_AGENT_CHARS).collect());
+    }
+    if let Some(ConnectInfo(addr)) = req.extensions().get::<ConnectInfo<SocketAddr>>() {
+        data.ip_address = Some(addr.ip().to_string());
+    }
+}
+
+/// Extract the session ID from a correctly signed session cookie
+fn extract_session_id(req: &Request, cookie_name: &str, signer: &CookieSigner) -> Option<SessionId> {
+    let session_id = signer.verify(cookie_value(req, cookie_name)?)?;
+    SessionId::from_str(&session_id).ok()
+}
+
+/// Find a cookie's value in the request
+fn cookie_value<'a>(req: &'a Request, cookie_name: &str) -> Option<&'a str> {
+    let cookie_str = req.headers().get(COOKIE)?.to_str().ok()?;
+
+    cookie_str
+        .split(';')
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| name.trim() == cookie_name)
+        .map(|(_, value)| value.trim())
+}
+
+/// Work out the remember-me cookie to send after the handler ran
+///
+/// `forget` and `login` reflect the response's [`ForgetLogin`] and
+/// [`RememberLogin`] extensions. Returns the new cookie value, an empty string to clear the cookie, or
+/// `None` to leave it alone. [`ForgetLogin`] wins over [`RememberLogin`],
+/// which wins over a rotation done before the handler.
+async fn remember_cookie_update(
+    remember: &RememberMe,
+    forget: bool,
+    login: Option<RememberLogin>,
+    request_cookie: Option<&str>,
+    rotated_cookie: Option<String>,
+) -> Option<String> {
+    if forget {
+        let revoked = rotated_cookie.as_deref().filter(|value| !value.is_empty());
+        for cookie in request_cookie.into_iter().chain(revoked) {
+            if let Err(e) = remember.revoke(cookie).await {
+                tracing::error!(error = %e, "Failed to revoke remember-me token");
+            }
+        }
+        return request_cookie.map(|_| String::new());
+    }
+
+    if let Some(RememberLogin { user_id }) = login {
+        if let Some(cookie) = request_cookie {
+            if let Err(e) = remember.revoke(cookie).await {
+                tracing::error!(error = %e, "Failed to revoke remember-me token");
             }
         }
+        match remember.issue(user_id).await {
+            Ok(value) => return Some(value),
+            Err(e) => tracing::error!(error = %e, "Failed to issue remember-me token"),
+        }
     }
 
-    None
+    rotated_cookie
+}
+
+/// Set (or, with an empty value, clear) the remember-me cookie on response
+fn set_remember_cookie(
+    response: &mut Response<Body>,
+    remember: &RememberMe,
+    value: &str,
+    config: &SessionConfig,
+) {
+    let max_age = if value.is_empty() {
+        0
+    } else {
+        remember.max_age_secs()
+    };
+    let mut cookie_value = format!(
+        "{}={}; Path={}; Max-Age={}; SameSite={}; HttpOnly",
+        remember.cookie_name(),
+        value,
+        config.cookie_path,
+        max_age,
+        config.same_site.as_str()
+    );
+
+    if config.secure {
+        cookie_value.push_str("; Secure");
+    }
+
+    if let Ok(header_value) = cookie_value.parse() {
+        response.headers_mut().append(SET_COOKIE, header_value);
+    }
 }
 
 /// Set session cookie on response
 fn set_session_cookie(
     response: &mut Response<Body>,
     session_id: &SessionId,
+    signer: &CookieSigner,
     config: &SessionConfig,
 ) {
     let mut cookie_value = format!(
         "{}={}; Path={}; Max-Age={}; SameSite={}",
         config.cookie_name,
-        session_id.as_str(),
+        signer.sign(session_id.as_str()),
         config.cookie_path,
         config.max_age_secs,
         config.same_site.as_str()
@@ -284,6 +611,394 @@ fn set_session_cookie(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::htmx::agents::{InMemorySessionStore, ListUserSessions, SessionManagerAgent};
+    use crate::htmx::auth::remember::InMemoryRememberTokenStore;
+    use acton_reactive::prelude::ActonApp;
+    use axum::{response::IntoResponse, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    const REMEMBER_COOKIE: &str = crate::htmx::auth::remember::REMEMBER_COOKIE_NAME;
+
+    fn remember_app(session_manager: AgentHandle) -> Router {
+        let remember_me =
+            RememberMe::new(Arc::new(InMemoryRememberTokenStore::new()), b"test-secret");
+
+        Router::new()
+            .route(
+                "/login",
+                get(|| async {
+                    let mut response = "ok".into_response();
+                    response.extensions_mut().insert(RememberLogin { user_id: 7 });
+                    response
+                }),
+            )
+            .route(
+                "/logout",
+                get(|| async {
+                    let mut response = "ok".into_response();
+                    response.extensions_mut().insert(ForgetLogin);
+                    response
+                }),
+            )
+            .route(
+                "/logout-everywhere",
+                get(|| async {
+                    let mut response = "ok".into_response();
+                    response.extensions_mut().insert(ForgetAllLogins { user_id: 7 });
+                    response
+                }),
+            )
+            .route(
+                "/whoami",
+                get(|Extension(data): Extension<SessionData>| async move {
+                    data.user_id.map_or_else(|| "anonymous".to_string(), |id| id.to_string())
+                }),
+            )
+            .layer(SessionLayer::from_handle(session_manager).with_remember_me(remember_me))
+    }
+
+    /// Send a request with an optional remember cookie (and no session cookie)
+    async fn send(app: &Router, path: &str, remember: Option<&str>) -> (String, Option<String>) {
+        let mut request = Request::get(path);
+        if let Some(value) = remember {
+            request = request.header(COOKIE, format!("{REMEMBER_COOKIE}={value}"));
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let remember_cookie = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|cookie| cookie.strip_prefix(&format!("{REMEMBER_COOKIE}=")))
+            .map(|cookie| cookie.split(';').next().unwrap_or_default().to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (String::from_utf8(body.to_vec()).unwrap(), remember_cookie)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remember_me_restores_and_rotates() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+        let app = remember_app(session_manager);
+
+        let (_, issued) = send(&app, "/login", None).await;
+        let issued = issued.unwrap();
+
+        let (user, rotated) = send(&app, "/whoami", Some(&issued)).await;
+        assert_eq!(user, "7");
+        let rotated = rotated.unwrap();
+        assert_ne!(rotated, issued);
+
+        // The consumed cookie is rejected and cleared
+        let (user, cleared) = send(&app, "/whoami", Some(&issued)).await;
+        assert_eq!(user, "anonymous");
+        assert_eq!(cleared.as_deref(), Some(""));
+
+        let (user, _) = send(&app, "/whoami", Some(&rotated)).await;
+        assert_eq!(user, "7");
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_logout_revokes_remember_me() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+        let app = remember_app(session_manager);
+
+        let (_, issued) = send(&app, "/login", None).await;
+        let issued = issued.unwrap();
+
+        let (_, cleared) = send(&app, "/logout", Some(&issued)).await;
+        assert_eq!(cleared.as_deref(), Some(""));
+
+        let (user, _) = send(&app, "/whoami", Some(&issued)).await;
+        assert_eq!(user, "anonymous");
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_forget_all_logins_revokes_every_device() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+        let app = remember_app(session_manager);
+
+        let (_, laptop) = send(&app, "/login", None).await;
+        let (_, phone) = send(&app, "/login", None).await;
+
+        let (_, cleared) = send(&app, "/logout-everywhere", phone.as_deref()).await;
+        assert_eq!(cleared.as_deref(), Some(""));
+
+        for cookie in [laptop, phone] {
+            let (user, _) = send(&app, "/whoami", cookie.as_deref()).await;
+            assert_eq!(user, "anonymous");
+        }
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_session_cookie_is_signed() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+        let app = Router::new()
+            .route(
+                "/",
+                get(|Extension(id): Extension<SessionId>| async move { id.as_str().to_string() }),
+            )
+            .layer(
+                SessionLayer::from_handle(session_manager)
+                    .with_signer(CookieSigner::new(b"test-secret")),
+            );
+
+        let get = |cookie: Option<String>| {
+            let app = app.clone();
+            async move {
+                let mut request = axum::http::Request::builder().uri("/");
+                if let Some(cookie) = cookie {
+                    request = request.header(COOKIE, format!("{SESSION_COOKIE_NAME}={cookie}"));
+                }
+                let response = app
+                    .oneshot(request.body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let set_cookie = response.headers().get(SET_COOKIE).map(|value| {
+                    let value = value.to_str().unwrap();
+                    let value = value.strip_prefix(&format!("{SESSION_COOKIE_NAME}=")).unwrap();
+                    value.split(';').next().unwrap().to_string()
+                });
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (String::from_utf8(body.to_vec()).unwrap(), set_cookie)
+            }
+        };
+
+        let (session_id, cookie) = get(None).await;
+        let cookie = cookie.unwrap();
+        let signer = CookieSigner::new(b"test-secret");
+        assert_eq!(
+            signer.for_purpose(SIGNING_PURPOSE).verify(&cookie).as_deref(),
+            Some(session_id.as_str())
+        );
+
+        // The signed cookie resumes the session
+        let (resumed, set_cookie) = get(Some(cookie.clone())).await;
+        assert_eq!(resumed, session_id);
+        assert!(set_cookie.is_none());
+
+        // A bare or re-signed session ID starts a new session
+        let (fresh, set_cookie) = get(Some(session_id.clone())).await;
+        assert_ne!(fresh, session_id);
+        assert!(set_cookie.is_some());
+
+        let forged = CookieSigner::new(b"wrong-secret").sign(&session_id);
+        let (fresh, _) = get(Some(forged)).await;
+        assert_ne!(fresh, session_id);
+
+        // So does the session ID signed by the right key for another purpose
+        let flash = signer.for_purpose("flash").sign(&session_id);
+        let (fresh, _) = get(Some(flash)).await;
+        assert_ne!(fresh, session_id);
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    fn regenerate_app(session_manager: AgentHandle, config: SessionConfig) -> Router {
+        fn with_data(data: SessionData, body: &'static str) -> Response {
+            let mut response = body.into_response();
+            response.extensions_mut().insert(data);
+            response
+        }
+
+        Router::new()
+            .route(
+                "/stash",
+                get(|Extension(mut data): Extension<SessionData>| async move {
+                    data.set("cart".to_string(), vec![1, 2, 3]).unwrap();
+                    with_data(data, "ok")
+                }),
+            )
+            .route(
+                "/login",
+                get(|Extension(mut data): Extension<SessionData>| async move {
+                    data.user_id = Some(7);
+                    with_data(data, "ok")
+                }),
+            )
+            .route(
+                "/rotate",
+                get(|| async {
+                    let mut response = "ok".into_response();
+                    response.extensions_mut().insert(RegenerateSessionId);
+                    response
+                }),
+            )
+            .route(
+                "/whoami",
+                get(|Extension(data): Extension<SessionData>| async move {
+                    format!(
+                        "{}:{}",
+                        data.user_id.map_or_else(|| "anonymous".to_string(), |id| id.to_string()),
+                        data.get::<Vec<i32>>("cart").map_or(0, |cart| cart.len())
+                    )
+                }),
+            )
+            .layer(SessionLayer {
+                config,
+                ..SessionLayer::from_handle(session_manager)
+            })
+    }
+
+    /// Send a request with an optional session cookie
+    async fn send_session(
+        app: &Router,
+        path: &str,
+        cookie: Option<&str>,
+    ) -> (String, Option<String>) {
+        let mut request = Request::get(path).header(USER_AGENT, "test-agent");
+        if let Some(value) = cookie {
+            request = request.header(COOKIE, format!("{SESSION_COOKIE_NAME}={value}"));
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let session_cookie = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(|cookie| cookie.strip_prefix(&format!("{SESSION_COOKIE_NAME}=")))
+            .map(|cookie| cookie.split(';').next().unwrap_or_default().to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (String::from_utf8(body.to_vec()).unwrap(), session_cookie)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_login_regenerates_session_id() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+        let app = regenerate_app(session_manager, SessionConfig::default());
+
+        let (_, before) = send_session(&app, "/stash", None).await;
+        let before = before.unwrap();
+
+        // Requests that don't change the user keep the ID
+        let (_, unchanged) = send_session(&app, "/whoami", Some(&before)).await;
+        assert!(unchanged.is_none());
+
+        let (_, after) = send_session(&app, "/login", Some(&before)).await;
+        let after = after.expect("Login should issue a new session cookie");
+        assert_ne!(after, before);
+
+        // Prior data moves with the login; the old ID is dead
+        let (user, _) = send_session(&app, "/whoami", Some(&after)).await;
+        assert_eq!(user, "7:3");
+        let (user, fresh) = send_session(&app, "/whoami", Some(&before)).await;
+        assert_eq!(user, "anonymous:0");
+        assert!(fresh.is_some());
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_login_evicts_sessions_over_limit() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn_with_session_limit(
+            &mut runtime,
+            Box::new(InMemorySessionStore::new()),
+            Some(1),
+        )
+        .await
+        .unwrap();
+        let app = regenerate_app(session_manager.clone(), SessionConfig::default());
+
+        let (_, first) = send_session(&app, "/stash", None).await;
+        let (_, first) = send_session(&app, "/login", first.as_deref()).await;
+        let (_, second) = send_session(&app, "/stash", None).await;
+        let (_, second) = send_session(&app, "/login", second.as_deref()).await;
+
+        // The second login pushed the first session out
+        let (user, _) = send_session(&app, "/whoami", first.as_deref()).await;
+        assert_eq!(user, "anonymous:0");
+        let (user, _) = send_session(&app, "/whoami", second.as_deref()).await;
+        assert_eq!(user, "7:3");
+
+        let (request, rx) = ListUserSessions::with_response(7);
+        session_manager.send(request).await;
+        let sessions = rx.await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user_agent.as_deref(), Some("test-agent"));
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_regenerate_can_clear_prior_data() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+        let app = regenerate_app(
+            session_manager,
+            SessionConfig {
+                preserve_data_on_regenerate: false,
+                ..SessionConfig::default()
+            },
+        );
+
+        let (_, before) = send_session(&app, "/stash", None).await;
+        let (_, after) = send_session(&app, "/login", before.as_deref()).await;
+        let after = after.unwrap();
+        assert_ne!(Some(&after), before.as_ref());
+
+        let (user, _) = send_session(&app, "/whoami", Some(&after)).await;
+        assert_eq!(user, "7:0");
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_regenerate_marker_without_user_change() {
+        let mut runtime = ActonApp::launch();
+        let session_manager = SessionManagerAgent::spawn(&mut runtime).await.unwrap();
+        let app = regenerate_app(
+            session_manager,
+            SessionConfig {
+                regenerate_on_user_change: false,
+                ..SessionConfig::default()
+            },
+        );
+
+        let (_, before) = send_session(&app, "/stash", None).await;
+        let before = before.unwrap();
+
+        // With automatic regeneration off, a user change keeps the ID...
+        let (_, kept) = send_session(&app, "/login", Some(&before)).await;
+        assert!(kept.is_none());
+
+        // ...but an explicit request still rotates it
+        let (_, after) = send_session(&app, "/rotate", Some(&before)).await;
+        let after = after.unwrap();
+        assert_ne!(after, before);
+        let (user, _) = send_session(&app, "/whoami", Some(&after)).await;
+        assert_eq!(user, "7:3");
+
+        runtime.shutdown_all().await.expect("Failed to shutdown");
+    }
 
     #[test]
     fn test_session_config_default() {
@@ -291,6 +1006,8 @@ mod tests {
         assert_eq!(config.cookie_name, SESSION_COOKIE_NAME);
         assert!(config.http_only);
         assert_eq!(config.max_age_secs, 86400);
+        assert!(config.regenerate_on_user_change);
+        assert!(config.preserve_data_on_regenerate);
     }
 
     #[test]
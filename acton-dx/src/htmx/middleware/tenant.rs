@@ -0,0 +1,282 @@
+//! Tenant resolution middleware
+//!
+//! Resolves the [`Tenant`] for each request and stores it in request
+//! extensions for the [`Tenant`] extractor. Requests are wrapped in a
+//! `tenant` tracing span carrying the tenant identifier.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::TenantLayer;
+//! use acton_dx::htmx::tenant::{StaticTenantResolver, Tenant, TenantSource};
+//! use axum::{routing::get, Router};
+//!
+//! async fn handler(tenant: Tenant) -> String {
+//!     tenant.to_string()
+//! }
+//!
+//! # fn example() -> Result<(), acton_dx::htmx::tenant::TenantError> {
+//! let resolver = StaticTenantResolver::new().with_tenant(Tenant::new("acme")?);
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(handler))
+//!     .layer(TenantLayer::new(TenantSource::header(), resolver));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::htmx::auth::session::SessionData;
+use crate::htmx::tenant::{Tenant, TenantError, TenantResolver, TenantSource};
+use axum::{
+    body::Body,
+    extract::Request,
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::Instrument;
+
+/// Layer that resolves the tenant for each request
+///
+/// When a session is present (i.e. `SessionLayer` runs outside this layer),
+/// subdomain and header tenants are checked against the user's membership
+/// and rejected with `403 Forbidden` if the user does not belong to them.
+#[derive(Clone)]
+pub struct TenantLayer {
+    source: Arc<TenantSource>,
+    resolver: Arc<dyn TenantResolver>,
+}
+
+impl std::fmt::Debug for TenantLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantLayer")
+            .field("source", &self.source)
+            .field("resolver", &"TenantResolver")
+            .finish()
+    }
+}
+
+impl TenantLayer {
+    /// Create a tenant layer reading from `source` and validating with `resolver`
+    #[must_use]
+    pub fn new<R: TenantResolver>(source: TenantSource, resolver: R) -> Self {
+        Self::from_arc(source, Arc::new(resolver))
+    }
+
+    /// Create a tenant layer from a shared resolver
+    #[must_use]
+    pub fn from_arc(source: TenantSource, resolver: Arc<dyn TenantResolver>) -> Self {
+        Self {
+            source: Arc::new(source),
+            resolver,
+        }
+    }
+}
+
+impl<S> Layer<S> for TenantLayer {
+    type Service = TenantMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TenantMiddleware {
+            inner,
+            source: self.source.clone(),
+            resolver: self.resolver.clone(),
+        }
+    }
+}
+
+/// Tenant resolution middleware service
+#[derive(Clone)]
+pub struct TenantMiddleware<S> {
+    inner: S,
+    source: Arc<TenantSource>,
+    resolver: Arc<dyn TenantResolver>,
+}
+
+impl<S: std::fmt::Debug> std::fmt::Debug for TenantMiddleware<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantMiddleware")
+            .field("inner", &self.inner)
+            .field("source", &self.source)
+            .field("resolver", &"TenantResolver")
+            .finish()
+    }
+}
+
+impl<S> Service<Request> for TenantMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let source = self.source.clone();
+        let resolver = self.resolver.clone();
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let (mut parts, body) = req.into_parts();
+
+            let tenant = match resolve_tenant(&parts, &source, resolver.as_ref()).await {
+                Ok(tenant) => tenant,
+                Err(error) => {
+                    tracing::debug!(%error, "Rejecting request without a valid tenant");
+                    return Ok(error.into_response());
+                }
+            };
+
+            let span = tracing::info_span!("tenant", tenant_id = %tenant.id());
+            parts.extensions.insert(tenant);
+
+            inner
+                .call(Request::from_parts(parts, body))
+                .instrument(span)
+                .await
+        })
+    }
+}
+
+/// Resolve and authorize the tenant for a request
+async fn resolve_tenant(
+    parts: &Parts,
+    source: &TenantSource,
+    resolver: &dyn TenantResolver,
+) -> Result<Tenant, TenantError> {
+    let user_id = parts
+        .extensions
+        .get::<SessionData>()
+        .and_then(|session| session.user_id);
+
+    if matches!(source, TenantSource::User) {
+        let user_id = user_id.ok_or(TenantError::Missing)?;
+        return resolver
+            .tenant_for_user(user_id)
+            .await?
+            .ok_or(TenantError::Missing);
+    }
+
+    let key = source
+        .key_from_headers(&parts.headers)
+        .ok_or(TenantError::Missing)?;
+
+    let tenant = resolver
+        .resolve(&key)
+        .await?
+        .ok_or(TenantError::Unknown(key))?;
+
+    if let Some(user_id) = user_id {
+        if !resolver.is_member(&tenant, user_id).await? {
+            return Err(TenantError::Forbidden(tenant.id().to_string()));
+        }
+    }
+
+    Ok(tenant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::tenant::{StaticTenantResolver, TENANT_HEADER_NAME};
+    use axum::{http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn handler(tenant: Tenant) -> String {
+        tenant.id().to_string()
+    }
+
+    fn resolver() -> StaticTenantResolver {
+        let acme = Tenant::new("acme").unwrap();
+        StaticTenantResolver::new()
+            .with_tenant(acme.clone())
+            .with_member(&acme, 1)
+    }
+
+    fn app(source: TenantSource) -> Router {
+        Router::new()
+            .route("/", get(handler))
+            .layer(TenantLayer::new(source, resolver()))
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_subdomain_tenant_resolved() {
+        let source = TenantSource::Subdomain {
+            base_domain: "example.com".to_string(),
+        };
+        let request = Request::builder()
+            .uri("/")
+            .header("host", "acme.example.com")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app(source).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_string(response).await, "acme");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tenant_is_not_found() {
+        let request = Request::builder()
+            .uri("/")
+            .header(TENANT_HEADER_NAME, "globex")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app(TenantSource::header()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_missing_tenant_is_not_found() {
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+
+        let response = app(TenantSource::header()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_non_member_is_forbidden() {
+        let mut session = SessionData::new();
+        session.user_id = Some(2);
+
+        let mut request = Request::builder()
+            .uri("/")
+            .header(TENANT_HEADER_NAME, "acme")
+            .body(Body::empty())
+            .unwrap();
+        request.extensions_mut().insert(session);
+
+        let response = app(TenantSource::header()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_user_source_uses_home_tenant() {
+        let mut session = SessionData::new();
+        session.user_id = Some(1);
+
+        let mut request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        request.extensions_mut().insert(session);
+
+        let response = app(TenantSource::User).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body_string(response).await, "acme");
+    }
+}
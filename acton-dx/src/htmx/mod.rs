@@ -3,12 +3,19 @@
 //! This module contains all the HTMX web framework functionality:
 //! - Authentication and sessions
 //! - Form handling with validation
+//! - Internationalization (locale negotiation and translations)
 //! - HTMX response types
 //! - Middleware (CSRF, sessions, security headers)
 //! - Email sending
 //! - File storage
+//! - Generic CRUD repositories
 //! - Background jobs
+//! - Domain event bus
+//! - Real-time topic broadcasts for SSE/WebSocket
 //! - OAuth2 authentication
+//! - Multi-tenant request scoping
+//! - Route introspection (registry of mounted endpoints)
+//! - Production server with graceful shutdown
 //!
 //! # Quick Start
 //!
@@ -30,9 +37,8 @@
 //!         .layer(SessionLayer::new(&state))
 //!         .with_state(state);
 //!
-//!     // Start server
-//!     let listener = tokio::net::TcpListener::bind("127.0.0.1:3000").await?;
-//!     axum::serve(listener, app).await?;
+//!     // Serve until SIGINT/SIGTERM, then drain in-flight requests
+//!     ActonServer::new("127.0.0.1:3000").serve(app).await?;
 //!
 //!     // Shutdown the agent runtime
 //!     runtime.shutdown_all().await?;
@@ -49,20 +55,28 @@
 pub mod agents;
 pub mod auth;
 pub mod config;
+pub mod db;
 pub mod email;
 pub mod error;
+pub mod events;
 pub mod extractors;
 pub mod forms;
 pub mod handlers;
 pub mod health;
+pub mod i18n;
 pub mod jobs;
 pub mod middleware;
 pub mod oauth2;
 pub mod observability;
+pub mod pagination;
+pub mod realtime;
 pub mod responses;
+pub mod routes;
+pub mod server;
 pub mod state;
 pub mod storage;
 pub mod template;
+pub mod tenant;
 
 // Testing utilities module (available in test builds)
 #[cfg(test)]
@@ -104,12 +118,25 @@ pub mod prelude {
         HxTrigger,
         HxTriggerName,
         // acton-dx extensions
+        prg,
+        HxResponse,
+        HxSse,
+        HxSseEvent,
         HxSwapOob,
+        HxTriggerEvent,
+        HxTriggers,
+        PostRedirectGet,
         SwapStrategy,
     };
 
     // Template traits
-    pub use super::template::{HxTemplate, TemplateRegistry};
+    pub use super::template::{HxTemplate, ResponseFormat, TemplateRegistry};
+
+    // Data access
+    pub use super::db::{Record, Repository, SqlxRepository};
+
+    // Pagination
+    pub use super::pagination::{Page, PaginationLimits, PaginationQuery, PaginationTemplate};
 
     // Form handling
     pub use super::forms::{
@@ -123,25 +150,37 @@ pub mod prelude {
     // Extractors
     pub use super::extractors::{
         FileUpload, FileUploadError, FlashExtractor, MultiFileUpload, SessionExtractor,
+        StreamingFileUpload,
     };
 
     // Storage
     pub use super::storage::{
         FileStorage, LocalFileStorage, StorageError, StoredFile, UploadedFile,
     };
+    #[cfg(feature = "s3")]
+    pub use super::storage::S3FileStorage;
+
+    // Multi-tenancy
+    pub use super::tenant::{Tenant, TenantError, TenantJob, TenantSource};
 
     // Error types
-    pub use super::error::ActonHtmxError;
+    pub use super::error::{ActonHtmxError, OptionalRecordExt, RecordExt};
 
     // Application state
     pub use super::state::ActonHtmxState;
 
+    // Server
+    pub use super::server::ActonServer;
+
     // Session middleware
-    pub use super::middleware::{SessionConfig, SessionLayer};
+    pub use super::middleware::{SessionConfig, SessionLayer, TenantLayer};
 
     // Background jobs
     pub use super::jobs::{Job, JobAgent, JobError, JobId, JobResult, JobStatus};
 
+    // Domain events
+    pub use super::events::{DomainEvent, EventBus, EventSubscriber};
+
     // Email system
     pub use super::email::{
         AwsSesBackend, ConsoleBackend, Email, EmailError, EmailSender, EmailTemplate,
@@ -150,8 +189,8 @@ pub mod prelude {
 
     // OAuth2 authentication (postgres-independent types)
     pub use super::oauth2::{
-        GitHubProvider, GoogleProvider, OAuth2Agent, OAuthConfig, OAuthError, OAuthProvider,
-        OAuthState, OAuthToken, OAuthUserInfo, OidcProvider, ProviderConfig,
+        DiscordProvider, GitHubProvider, GoogleProvider, OAuth2Agent, OAuthConfig, OAuthError,
+        OAuthProvider, OAuthState, OAuthToken, OAuthUserInfo, OidcProvider, ProviderConfig,
     };
 
     // OAuth2 handlers and models (postgres only)
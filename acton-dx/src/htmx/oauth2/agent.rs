@@ -1,15 +1,20 @@
 //! OAuth2 state management agent
 //!
 //! This module provides an acton-reactive agent for managing OAuth2 state tokens
-//! and preventing CSRF attacks during the OAuth2 flow.
+//! and preventing CSRF attacks during the OAuth2 flow. It also holds each flow's
+//! PKCE verifier alongside its state token, so the verifier never has to leave
+//! the server, and keeps access tokens fresh via their refresh tokens.
 
 use acton_reactive::prelude::*;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tokio::sync::{oneshot, Mutex};
 
-use super::types::{OAuthProvider, OAuthState};
+use super::types::{OAuthError, OAuthProvider, OAuthState, OAuthToken, ProviderConfig};
+
+/// How long before expiry [`RefreshIfExpired`] refreshes a token
+pub const REFRESH_MARGIN: Duration = Duration::from_secs(60);
 
 /// Type alias for response channels (web handler pattern)
 pub type ResponseChannel<T> = Arc<Mutex<Option<oneshot::Sender<T>>>>;
@@ -17,18 +22,85 @@ pub type ResponseChannel<T> = Arc<Mutex<Option<oneshot::Sender<T>>>>;
 /// OAuth2 state management agent
 ///
 /// This agent stores and validates OAuth2 state tokens to prevent CSRF attacks.
-/// State tokens are ephemeral and expire after 10 minutes.
+/// State tokens and PKCE verifiers are ephemeral and expire after 10 minutes.
 #[derive(Debug, Default, Clone)]
 pub struct OAuth2Agent {
     /// Map of state tokens to their metadata
     states: HashMap<String, OAuthState>,
+    /// Map of state tokens to their PKCE verifiers
+    verifiers: HashMap<String, PkceVerifier>,
+    /// Map of caller-chosen keys to access tokens
+    tokens: HashMap<String, StoredToken>,
+}
+
+/// Access token held for refreshing
+#[derive(Debug, Clone)]
+struct StoredToken {
+    provider: OAuthProvider,
+    token: OAuthToken,
+}
+
+/// What [`RefreshIfExpired`] should do with a stored token
+#[derive(Debug)]
+enum RefreshPlan {
+    /// Token is still fresh (or can't be refreshed but hasn't expired yet)
+    Current(OAuthToken),
+    /// Token is nearing expiry and has a refresh token
+    Refresh(OAuthProvider, String),
+}
+
+/// PKCE verifier held for a pending OAuth2 flow
+#[derive(Debug, Clone)]
+struct PkceVerifier {
+    verifier: String,
+    expires_at: SystemTime,
 }
 
 impl OAuth2Agent {
-    /// Clean up expired state tokens
+    /// Clean up expired state tokens and PKCE verifiers
     fn cleanup_expired(&mut self) {
         let now = SystemTime::now();
         self.states.retain(|_, state| state.expires_at > now);
+        self.verifiers.retain(|_, pkce| pkce.expires_at > now);
+    }
+
+    /// Store the PKCE verifier for `state`
+    ///
+    /// The verifier expires with its state token, or after 10 minutes if the
+    /// state token is unknown.
+    fn store_pkce(&mut self, state: String, verifier: String) {
+        let expires_at = self.states.get(&state).map_or_else(
+            || SystemTime::now() + Duration::from_secs(600),
+            |oauth_state| oauth_state.expires_at,
+        );
+        self.verifiers
+            .insert(state, PkceVerifier { verifier, expires_at });
+    }
+
+    /// Decide how to answer a [`RefreshIfExpired`] request for `key`
+    fn refresh_plan(&self, key: &str) -> Result<RefreshPlan, OAuthError> {
+        let stored = self
+            .tokens
+            .get(key)
+            .ok_or_else(|| OAuthError::Generic(format!("No OAuth2 token stored for {key}")))?;
+
+        if !stored.token.expires_within(REFRESH_MARGIN) {
+            return Ok(RefreshPlan::Current(stored.token.clone()));
+        }
+
+        match &stored.token.refresh_token {
+            Some(refresh_token) => Ok(RefreshPlan::Refresh(stored.provider, refresh_token.clone())),
+            None if stored.token.is_expired() => Err(OAuthError::TokenExpired),
+            None => Ok(RefreshPlan::Current(stored.token.clone())),
+        }
+    }
+
+    /// Remove and return the PKCE verifier for `state` if it has not expired
+    fn take_pkce(&mut self, state: &str) -> Option<String> {
+        self.verifiers
+            .remove(state)
+            .filter(|pkce| pkce.expires_at > SystemTime::now())
+            .map(|pkce| pkce.verifier)
     }
 }
 
@@ -87,6 +159,88 @@ pub struct RemoveState {
     pub token: String,
 }
 
+/// Message to store the PKCE verifier for a state token
+#[derive(Debug, Clone)]
+pub struct StorePkce {
+    /// State token the verifier belongs to
+    pub state: String,
+    /// PKCE verifier
+    pub verifier: String,
+}
+
+/// Message to retrieve and remove the PKCE verifier for a state token (web handler)
+///
+/// A verifier can be taken only once; the response is `None` if it was never
+/// stored, was already taken, or has expired.
+#[derive(Debug, Clone)]
+pub struct TakePkce {
+    /// State token the verifier belongs to
+    pub state: String,
+    /// Response channel
+    pub response_tx: ResponseChannel<Option<String>>,
+}
+
+impl TakePkce {
+    /// Create a new take PKCE request with response channel
+    #[must_use]
+    pub fn new(state: String) -> (Self, oneshot::Receiver<Option<String>>) {
+        let (tx, rx) = oneshot::channel();
+        (
+            Self {
+                state,
+                response_tx: Arc::new(Mutex::new(Some(tx))),
+            },
+            rx,
+        )
+    }
+}
+
+/// Message to store an access token for later refreshing
+#[derive(Debug, Clone)]
+pub struct StoreToken {
+    /// Key to store the token under (e.g. `"{user_id}:{provider}"`)
+    pub key: String,
+    /// Provider that issued the token
+    pub provider: OAuthProvider,
+    /// Token, including its `expires_at` and refresh token
+    pub token: OAuthToken,
+}
+
+/// Message to get a stored token, refreshing it first if it is near expiry (web handler)
+///
+/// Tokens expiring within [`REFRESH_MARGIN`] are exchanged for new ones using
+/// their refresh token, and the new token replaces the stored one. The
+/// response is an error if no token is stored under `key`, the token has
+/// expired without a refresh token, or the provider rejects the refresh.
+#[derive(Debug, Clone)]
+pub struct RefreshIfExpired {
+    /// Key the token was stored under
+    pub key: String,
+    /// Configuration of the provider that issued the token
+    pub config: ProviderConfig,
+    /// Response channel
+    pub response_tx: ResponseChannel<Result<OAuthToken, OAuthError>>,
+}
+
+impl RefreshIfExpired {
+    /// Create a new refresh request with response channel
+    #[must_use]
+    pub fn new(
+        key: String,
+        config: ProviderConfig,
+    ) -> (Self, oneshot::Receiver<Result<OAuthToken, OAuthError>>) {
+        let (tx, rx) = oneshot::channel();
+        (
+            Self {
+                key,
+                config,
+                response_tx: Arc::new(Mutex::new(Some(tx))),
+            },
+            rx,
+        )
+    }
+}
+
 /// Message to clean up expired state tokens
 #[derive(Debug, Clone)]
 pub struct CleanupExpired;
@@ -97,6 +251,7 @@ impl OAuth2Agent {
     /// # Errors
     ///
     /// Returns error if agent configuration or spawning fails
+    #[allow(clippy::too_many_lines)]
     pub async fn spawn(runtime: &mut AgentRuntime) -> anyhow::Result<AgentHandle> {
         let config = AgentConfig::new(Ern::with_root("oauth2_manager")?, None, None)?;
 
@@ -166,10 +321,79 @@ impl OAuth2Agent {
 
                 AgentReply::immediate()
             })
+            .mutate_on::<StorePkce>(|agent, envelope| {
+                let msg = envelope.message();
+                agent.model.cleanup_expired();
+                agent.model.store_pkce(msg.state.clone(), msg.verifier.clone());
+                tracing::debug!(token = %msg.state, "Stored OAuth2 PKCE verifier");
+
+                AgentReply::immediate()
+            })
+            .mutate_on::<TakePkce>(|agent, envelope| {
+                let token = envelope.message().state.clone();
+                let response_tx = envelope.message().response_tx.clone();
+
+                let verifier = agent.model.take_pkce(&token);
+                if verifier.is_none() {
+                    tracing::warn!(token = %token, "OAuth2 PKCE verifier missing or expired");
+                }
+
+                AgentReply::from_async(async move {
+                    let mut guard = response_tx.lock().await;
+                    if let Some(tx) = guard.take() {
+                        let _ = tx.send(verifier);
+                    }
+                })
+            })
+            .mutate_on::<StoreToken>(|agent, envelope| {
+                let msg = envelope.message().clone();
+                tracing::debug!(key = %msg.key, provider = ?msg.provider, "Stored OAuth2 token");
+                agent.model.tokens.insert(
+                    msg.key,
+                    StoredToken { provider: msg.provider, token: msg.token },
+                );
+                AgentReply::immediate()
+            })
+            .mutate_on::<RefreshIfExpired>(|agent, envelope| {
+                let msg = envelope.message().clone();
+                let plan = agent.model.refresh_plan(&msg.key);
+                let handle = agent.handle().clone();
+
+                AgentReply::from_async(async move {
+                    let result = match plan {
+                        Ok(RefreshPlan::Current(token)) => Ok(token),
+                        Ok(RefreshPlan::Refresh(provider, refresh_token)) => {
+                            let refreshed = provider.refresh(&msg.config, &refresh_token).await;
+                            match &refreshed {
+                                Ok(token) => {
+                                    tracing::debug!(key = %msg.key, "Refreshed OAuth2 token");
+                                    handle
+                                        .send(StoreToken {
+                                            key: msg.key.clone(),
+                                            provider,
+                                            token: token.clone(),
+                                        })
+                                        .await;
+                                }
+                                Err(e) => {
+                                    tracing::warn!(key = %msg.key, error = %e, "OAuth2 token refresh failed");
+                                }
+                            }
+                            refreshed
+                        }
+                        Err(e) => Err(e),
+                    };
+
+                    let mut guard = msg.response_tx.lock().await;
+                    if let Some(tx) = guard.take() {
+                        let _ = tx.send(result);
+                    }
+                })
+            })
             .mutate_on::<CleanupExpired>(|agent, _envelope| {
-                let before = agent.model.states.len();
+                let before = agent.model.states.len() + agent.model.verifiers.len();
                 agent.model.cleanup_expired();
-                let removed = before - agent.model.states.len();
+                let removed = before - agent.model.states.len() - agent.model.verifiers.len();
 
                 if removed > 0 {
                     tracing::debug!(
@@ -197,3 +421,205 @@ impl OAuth2Agent {
         Ok(builder.start().await)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::post, Form, Json, Router};
+
+    /// Serve a token endpoint accepting only the refresh token `"valid"`
+    async fn spawn_token_endpoint() -> String {
+        async fn token(
+            Form(form): Form<HashMap<String, String>>,
+        ) -> (StatusCode, Json<serde_json::Value>) {
+            let valid = form.get("grant_type").map(String::as_str) == Some("refresh_token")
+                && form.get("refresh_token").map(String::as_str) == Some("valid");
+            if valid {
+                (
+                    StatusCode::OK,
+                    Json(serde_json::json!({
+                        "access_token": "new-access",
+                        "token_type": "bearer",
+                        "expires_in": 3600
+                    })),
+                )
+            } else {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": "invalid_grant",
+                        "error_description": "Token has been revoked"
+                    })),
+                )
+            }
+        }
+
+        let app = Router::new().route("/token", post(token));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        format!("http://{addr}")
+    }
+
+    fn oidc_config(base_url: &str) -> ProviderConfig {
+        ProviderConfig {
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            redirect_uri: "http://localhost:3000/auth/oidc/callback".to_string(),
+            scopes: vec![],
+            auth_url: Some(format!("{base_url}/authorize")),
+            token_url: Some(format!("{base_url}/token")),
+            userinfo_url: Some(format!("{base_url}/userinfo")),
+        }
+    }
+
+    fn expiring_token(refresh_token: &str) -> OAuthToken {
+        OAuthToken {
+            access_token: "old-access".to_string(),
+            refresh_token: Some(refresh_token.to_string()),
+            token_type: "Bearer".to_string(),
+            expires_at: Some(SystemTime::now() + Duration::from_secs(10)),
+            scopes: None,
+        }
+    }
+
+    async fn refresh(
+        handle: &AgentHandle,
+        key: &str,
+        config: ProviderConfig,
+    ) -> Result<OAuthToken, OAuthError> {
+        let (request, rx) = RefreshIfExpired::new(key.to_string(), config);
+        handle.send(request).await;
+        rx.await.unwrap()
+    }
+
+    #[test]
+    fn test_pkce_verifier_taken_once() {
+        let mut agent = OAuth2Agent::default();
+        agent.store_pkce("state".to_string(), "verifier".to_string());
+
+        assert_eq!(agent.take_pkce("state").as_deref(), Some("verifier"));
+        assert_eq!(agent.take_pkce("state"), None);
+        assert_eq!(agent.take_pkce("unknown"), None);
+    }
+
+    #[test]
+    fn test_pkce_verifier_shares_state_expiry() {
+        let mut agent = OAuth2Agent::default();
+        let mut state = OAuthState::generate(OAuthProvider::GitHub);
+        state.expires_at = SystemTime::now() - Duration::from_secs(1);
+        agent.states.insert(state.token.clone(), state.clone());
+
+        agent.store_pkce(state.token.clone(), "verifier".to_string());
+
+        assert_eq!(agent.take_pkce(&state.token), None);
+    }
+
+    #[test]
+    fn test_cleanup_removes_expired_verifiers() {
+        let mut agent = OAuth2Agent::default();
+        agent.store_pkce("fresh".to_string(), "a".to_string());
+        agent.verifiers.insert(
+            "stale".to_string(),
+            PkceVerifier {
+                verifier: "b".to_string(),
+                expires_at: SystemTime::now() - Duration::from_secs(1),
+            },
+        );
+
+        agent.cleanup_expired();
+
+        assert_eq!(agent.verifiers.len(), 1);
+        assert!(agent.verifiers.contains_key("fresh"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_store_and_take_pkce_messages() {
+        let mut runtime = ActonApp::launch();
+        let handle = OAuth2Agent::spawn(&mut runtime).await.unwrap();
+
+        let (generate, rx) = GenerateState::new(OAuthProvider::Google);
+        handle.send(generate).await;
+        let state = rx.await.unwrap();
+
+        handle
+            .send(StorePkce {
+                state: state.token.clone(),
+                verifier: "verifier".to_string(),
+            })
+            .await;
+
+        let (take, rx) = TakePkce::new(state.token.clone());
+        handle.send(take).await;
+        assert_eq!(rx.await.unwrap().as_deref(), Some("verifier"));
+
+        let (take, rx) = TakePkce::new(state.token);
+        handle.send(take).await;
+        assert_eq!(rx.await.unwrap(), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refresh_if_expired_refreshes_and_stores() {
+        let config = oidc_config(&spawn_token_endpoint().await);
+        let mut runtime = ActonApp::launch();
+        let handle = OAuth2Agent::spawn(&mut runtime).await.unwrap();
+
+        handle
+            .send(StoreToken {
+                key: "1:oidc".to_string(),
+                provider: OAuthProvider::Oidc,
+                token: expiring_token("valid"),
+            })
+            .await;
+
+        let token = refresh(&handle, "1:oidc", config.clone()).await.unwrap();
+        assert_eq!(token.access_token, "new-access");
+        assert_eq!(token.refresh_token.as_deref(), Some("valid"));
+        assert!(!token.expires_within(REFRESH_MARGIN));
+
+        // The refreshed token replaced the stored one
+        let token = refresh(&handle, "1:oidc", config).await.unwrap();
+        assert_eq!(token.access_token, "new-access");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refresh_if_expired_revoked_token() {
+        let config = oidc_config(&spawn_token_endpoint().await);
+        let mut runtime = ActonApp::launch();
+        let handle = OAuth2Agent::spawn(&mut runtime).await.unwrap();
+
+        handle
+            .send(StoreToken {
+                key: "1:oidc".to_string(),
+                provider: OAuthProvider::Oidc,
+                token: expiring_token("revoked"),
+            })
+            .await;
+
+        let error = refresh(&handle, "1:oidc", config).await.unwrap_err();
+        assert!(matches!(error, OAuthError::TokenRefreshFailed(_)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_refresh_if_expired_skips_fresh_and_unknown_tokens() {
+        // Nothing listens here, so any refresh attempt would fail
+        let config = oidc_config("http://127.0.0.1:9");
+        let mut runtime = ActonApp::launch();
+        let handle = OAuth2Agent::spawn(&mut runtime).await.unwrap();
+
+        let mut fresh = expiring_token("valid");
+        fresh.expires_at = Some(SystemTime::now() + Duration::from_secs(3600));
+        handle
+            .send(StoreToken {
+                key: "1:oidc".to_string(),
+                provider: OAuthProvider::Oidc,
+                token: fresh,
+            })
+            .await;
+
+        let token = refresh(&handle, "1:oidc", config.clone()).await.unwrap();
+        assert_eq!(token.access_token, "old-access");
+        assert!(refresh(&handle, "2:oidc", config).await.is_err());
+    }
+}
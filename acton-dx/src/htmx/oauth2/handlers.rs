@@ -19,10 +19,10 @@ use crate::htmx::{
     error::ActonHtmxError,
     responses::{HxRedirect, HxResponseTrigger},
     oauth2::{
-        agent::{GenerateState, ValidateState, RemoveState},
+        agent::{GenerateState, RemoveState, StorePkce, TakePkce, ValidateState},
         models::OAuthAccount,
-        providers::{GitHubProvider, GoogleProvider, OidcProvider},
-        types::{OAuthProvider, OAuthUserInfo, ProviderConfig},
+        providers::{DiscordProvider, GitHubProvider, GoogleProvider, OidcProvider},
+        types::{OAuthProvider, OAuthUserInfo, PkceStorage, ProviderConfig},
     },
     state::ActonHtmxState,
 };
@@ -44,7 +44,8 @@ pub struct OAuthCallback {
 ///
 /// This handler initiates the OAuth2 authorization code flow by:
 /// 1. Generating a CSRF state token
-/// 2. Storing the state token and PKCE verifier in session
+/// 2. Storing the state token in session and the PKCE verifier in the OAuth2
+///    agent (or in session when `pkce_storage` is `session`)
 /// 3. Redirecting to the provider's authorization endpoint
 ///
 /// # Errors
@@ -85,6 +86,11 @@ pub async fn initiate_oauth(
                 .map_err(|e| ActonHtmxError::ServerError(format!("GitHub OAuth error: {e}")))?;
             github.authorization_url()
         }
+        OAuthProvider::Discord => {
+            let discord = DiscordProvider::new(provider_config)
+                .map_err(|e| ActonHtmxError::ServerError(format!("Discord OAuth error: {e}")))?;
+            discord.authorization_url()
+        }
         OAuthProvider::Oidc => {
             let oidc = OidcProvider::new(provider_config)
                 .await
@@ -93,9 +99,24 @@ pub async fn initiate_oauth(
         }
     };
 
-    // Store state and PKCE verifier in session
+    // Store PKCE verifier alongside the state token
+    match oauth_config.pkce_storage {
+        PkceStorage::Agent => {
+            state
+                .oauth2_agent()
+                .send(StorePkce {
+                    state: oauth_state.token.clone(),
+                    verifier: pkce_verifier,
+                })
+                .await;
+        }
+        PkceStorage::Session => {
+            session.set("oauth2_pkce_verifier".to_string(), &pkce_verifier)?;
+        }
+    }
+
+    // Store state in session
     session.set("oauth2_state".to_string(), &oauth_state.token)?;
-    session.set("oauth2_pkce_verifier".to_string(), &pkce_verifier)?;
     session.set("oauth2_provider".to_string(), &provider_name)?;
 
     // Redirect to provider's authorization endpoint
@@ -149,6 +170,31 @@ async fn validate_oauth_state(
     Ok(())
 }
 
+/// Retrieve the PKCE verifier stored by [`initiate_oauth`]
+///
+/// # Errors
+///
+/// Returns error if the verifier is missing, already used, or expired
+async fn take_pkce_verifier(
+    state: &ActonHtmxState,
+    session: &Session,
+    params: &OAuthCallback,
+) -> Result<String, ActonHtmxError> {
+    match state.config().oauth2.pkce_storage {
+        PkceStorage::Agent => {
+            let (take_msg, take_rx) = TakePkce::new(params.state.clone());
+            state.oauth2_agent().send(take_msg).await;
+            take_rx
+                .await
+                .map_err(|e| ActonHtmxError::ServerError(format!("Failed to retrieve PKCE verifier: {e}")))?
+                .ok_or_else(|| ActonHtmxError::BadRequest("Missing or expired PKCE verifier".to_string()))
+        }
+        PkceStorage::Session => session
+            .get("oauth2_pkce_verifier")
+            .ok_or_else(|| ActonHtmxError::BadRequest("No PKCE verifier in session".to_string())),
+    }
+}
+
 /// Exchange authorization code for access token and fetch user info
 ///
 /// # Errors
@@ -189,6 +235,20 @@ async fn exchange_code_and_fetch_user(
                 .await
                 .map_err(|e| ActonHtmxError::ServerError(format!("Failed to fetch user info: {e}")))
         }
+        OAuthProvider::Discord => {
+            let discord = DiscordProvider::new(provider_config)
+                .map_err(|e| ActonHtmxError::ServerError(format!("Discord OAuth error: {e}")))?;
+
+            let token = discord
+                .exchange_code(code, pkce_verifier)
+                .await
+                .map_err(|e| ActonHtmxError::ServerError(format!("Token exchange failed: {e}")))?;
+
+            discord
+                .fetch_user_info(&token.access_token)
+                .await
+                .map_err(|e| ActonHtmxError::ServerError(format!("Failed to fetch user info: {e}")))
+        }
         OAuthProvider::Oidc => {
             let oidc = OidcProvider::new(provider_config)
                 .await
@@ -288,10 +348,8 @@ pub async fn handle_oauth_callback(
     // Validate CSRF state token
     validate_oauth_state(&state, &session, &params, &provider_name).await?;
 
-    // Get PKCE verifier from session
-    let pkce_verifier: String = session
-        .get("oauth2_pkce_verifier")
-        .ok_or_else(|| ActonHtmxError::BadRequest("No PKCE verifier in session".to_string()))?;
+    // Get PKCE verifier
+    let pkce_verifier = take_pkce_verifier(&state, &session, &params).await?;
 
     // Get OAuth2 config
     let provider_config = state.config().oauth2
@@ -4,7 +4,9 @@
 //! including:
 //! - Google OAuth2 (with OpenID Connect)
 //! - GitHub OAuth2
+//! - Discord OAuth2
 //! - Generic OpenID Connect provider
+//! - Sign in with Apple (`apple` feature)
 //!
 //! # Features
 //!
@@ -17,7 +19,7 @@
 //! # Example Usage
 //!
 //! ```rust,no_run
-//! use acton_htmx::oauth2::{OAuthConfig, ProviderConfig};
+//! use acton_htmx::oauth2::{OAuthConfig, PkceStorage, ProviderConfig};
 //! use axum::{Router, routing::get};
 //!
 //! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
@@ -41,7 +43,9 @@
 //!         token_url: None,
 //!         userinfo_url: None,
 //!     }),
+//!     discord: None,
 //!     oidc: None,
+//!     pkce_storage: PkceStorage::Agent,
 //! };
 //!
 //! // Add OAuth2 routes to your router
@@ -70,6 +74,12 @@
 //! client_secret = "your-github-client-secret"
 //! redirect_uri = "http://localhost:3000/auth/github/callback"
 //! scopes = ["read:user", "user:email"]
+//!
+//! [oauth2.discord]
+//! client_id = "your-discord-client-id"
+//! client_secret = "your-discord-client-secret"
+//! redirect_uri = "http://localhost:3000/auth/discord/callback"
+//! scopes = ["identify", "email"]
 //! ```
 //!
 //! # Security Considerations
@@ -80,17 +90,21 @@
 //!   code interception attacks
 //! - **State Validation**: State tokens are validated server-side using the OAuth2Agent
 //! - **One-Time Use**: State tokens are removed after successful validation
-//! - **Session Storage**: PKCE verifiers are stored in secure HTTP-only session cookies
+//! - **PKCE Storage**: PKCE verifiers are held by the OAuth2Agent alongside their state
+//!   token (same expiry, one-time use). Set `pkce_storage = "session"` to keep them in
+//!   the session cookie instead
+//! - **Token Refresh**: Tokens stored with `StoreToken` are refreshed by `RefreshIfExpired`
+//!   shortly before their `expires_at`, using the provider's refresh token grant
 //!
 //! # Database Schema
 //!
-//! The OAuth2 module requires the `oauth_accounts` table (see migration 002):
+//! The OAuth2 module requires the `oauth_accounts` table (see migrations 002 and 004):
 //!
 //! ```sql
 //! CREATE TABLE oauth_accounts (
 //!     id BIGSERIAL PRIMARY KEY,
 //!     user_id BIGINT NOT NULL,
-//!     provider TEXT NOT NULL CHECK (provider IN ('google', 'github', 'oidc')),
+//!     provider TEXT NOT NULL CHECK (provider IN ('google', 'github', 'discord', 'oidc')),
 //!     provider_user_id TEXT NOT NULL,
 //!     email TEXT NOT NULL,
 //!     name TEXT,
@@ -111,12 +125,18 @@ pub mod models;
 pub mod providers;
 pub mod types;
 
-pub use agent::{OAuth2Agent, GenerateState, ValidateState, RemoveState, CleanupExpired};
+pub use agent::{
+    CleanupExpired, GenerateState, OAuth2Agent, RefreshIfExpired, RemoveState, StorePkce,
+    StoreToken, TakePkce, ValidateState, REFRESH_MARGIN,
+};
 #[cfg(feature = "postgres")]
 pub use handlers::{initiate_oauth, handle_oauth_callback, unlink_oauth_account};
 #[cfg(feature = "postgres")]
 pub use models::OAuthAccount;
-pub use providers::{GitHubProvider, GoogleProvider, OidcProvider};
+pub use providers::{DiscordProvider, GitHubProvider, GoogleProvider, OidcProvider};
+#[cfg(feature = "apple")]
+pub use providers::{AppleCallback, AppleConfig, AppleProvider, AppleTokens};
 pub use types::{
-    OAuthConfig, OAuthError, OAuthProvider, OAuthState, OAuthToken, OAuthUserInfo, ProviderConfig,
+    OAuthConfig, OAuthError, OAuthProvider, OAuthState, OAuthToken, OAuthUserInfo, PkceStorage,
+    ProviderConfig,
 };
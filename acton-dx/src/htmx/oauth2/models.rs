@@ -12,7 +12,7 @@ use super::types::{OAuthProvider, OAuthUserInfo};
 /// OAuth2 account linked to a user
 ///
 /// This represents a connection between a local user account and an OAuth2
-/// provider account (Google, GitHub, or generic OIDC).
+/// provider account (Google, GitHub, Discord, or generic OIDC).
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct OAuthAccount {
     /// Primary key
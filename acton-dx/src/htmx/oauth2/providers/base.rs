@@ -4,9 +4,9 @@
 //! OAuth2 logic shared between Google, GitHub, and OIDC providers.
 
 use oauth2::{
-    basic::BasicClient, AuthUrl, AuthorizationCode, ClientId,
-    ClientSecret, CsrfToken, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, Scope,
-    TokenResponse, TokenUrl,
+    basic::{BasicClient, BasicTokenResponse}, AuthUrl, AuthorizationCode, ClientId,
+    ClientSecret, CsrfToken, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, RefreshToken,
+    Scope, TokenResponse, TokenUrl,
 };
 
 use crate::htmx::oauth2::http::async_http_client;
@@ -118,7 +118,41 @@ impl BaseOAuthProvider {
             .await
             .map_err(|e| OAuthError::TokenExchangeFailed(e.to_string()))?;
 
-        Ok(OAuthToken {
+        Ok(Self::to_oauth_token(&token_response))
+    }
+
+    /// Exchange a refresh token for a new access token
+    ///
+    /// Uses the token endpoint with `grant_type=refresh_token`. Providers that
+    /// don't rotate refresh tokens omit one from the response, in which case
+    /// `refresh_token` is kept on the returned token.
+    ///
+    /// # Arguments
+    ///
+    /// * `refresh_token` - Refresh token from a previous exchange
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the provider rejects the refresh token (e.g. it was
+    /// revoked) or the request fails
+    pub async fn refresh(&self, refresh_token: &str) -> Result<OAuthToken, OAuthError> {
+        let token_response = self
+            .client
+            .exchange_refresh_token(&RefreshToken::new(refresh_token.to_string()))
+            .request_async(&async_http_client)
+            .await
+            .map_err(|e| OAuthError::TokenRefreshFailed(e.to_string()))?;
+
+        let mut token = Self::to_oauth_token(&token_response);
+        token
+            .refresh_token
+            .get_or_insert_with(|| refresh_token.to_string());
+        Ok(token)
+    }
+
+    /// Convert a token endpoint response into an [`OAuthToken`]
+    fn to_oauth_token(token_response: &BasicTokenResponse) -> OAuthToken {
+        OAuthToken {
             access_token: token_response.access_token().secret().clone(),
             refresh_token: token_response
                 .refresh_token()
@@ -130,7 +164,7 @@ impl BaseOAuthProvider {
             scopes: token_response
                 .scopes()
                 .map(|scopes| scopes.iter().map(|s| s.to_string()).collect()),
-        })
+        }
     }
 
     /// Fetch user info JSON from the configured endpoint
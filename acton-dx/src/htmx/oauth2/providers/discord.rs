@@ -0,0 +1,154 @@
+//! Discord OAuth2 provider implementation
+//!
+//! This module implements OAuth2 authentication with Discord using their OAuth2 API.
+
+use serde::{Deserialize, Serialize};
+
+use super::base::BaseOAuthProvider;
+use crate::htmx::oauth2::types::{OAuthError, OAuthToken, OAuthUserInfo, ProviderConfig};
+
+/// Base URL for Discord user avatars
+const DISCORD_AVATAR_CDN: &str = "https://cdn.discordapp.com/avatars";
+
+/// Discord OAuth2 provider
+pub struct DiscordProvider {
+    base: BaseOAuthProvider,
+}
+
+impl DiscordProvider {
+    /// Create a new Discord OAuth2 provider
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the configuration is invalid
+    pub fn new(config: &ProviderConfig) -> Result<Self, OAuthError> {
+        Ok(Self {
+            base: BaseOAuthProvider::new(
+                "https://discord.com/api/oauth2/authorize",
+                "https://discord.com/api/oauth2/token",
+                config,
+                "https://discord.com/api/users/@me".to_string(),
+            )?,
+        })
+    }
+
+    /// Generate authorization URL and CSRF state
+    ///
+    /// Returns tuple of (authorization_url, csrf_state, pkce_verifier)
+    #[must_use]
+    pub fn authorization_url(&self) -> (String, String, String) {
+        self.base.authorization_url(&["identify", "email"])
+    }
+
+    /// Exchange authorization code for access token
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the token exchange fails
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        pkce_verifier: &str,
+    ) -> Result<OAuthToken, OAuthError> {
+        self.base.exchange_code(code, pkce_verifier).await
+    }
+
+    /// Exchange a refresh token for a new access token
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the refresh token is rejected or the request fails
+    pub async fn refresh(&self, refresh_token: &str) -> Result<OAuthToken, OAuthError> {
+        self.base.refresh(refresh_token).await
+    }
+
+    /// Fetch user information using access token
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the user info request fails or the account has no email
+    pub async fn fetch_user_info(&self, access_token: &str) -> Result<OAuthUserInfo, OAuthError> {
+        let json = self.base.fetch_user_info_json(access_token).await?;
+
+        let discord_user: DiscordUser = serde_json::from_value(json)
+            .map_err(|e| OAuthError::UserInfoFailed(format!("Failed to parse Discord user: {e}")))?;
+
+        let email = discord_user.email.clone().ok_or_else(|| {
+            OAuthError::UserInfoFailed("Discord account has no email address".to_string())
+        })?;
+
+        Ok(OAuthUserInfo {
+            avatar_url: discord_user
+                .avatar
+                .as_deref()
+                .map(|hash| avatar_url(&discord_user.id, hash)),
+            provider_user_id: discord_user.id,
+            email,
+            name: discord_user.global_name.or(Some(discord_user.username)),
+            email_verified: discord_user.verified.unwrap_or(false),
+        })
+    }
+}
+
+/// Build the CDN URL for a Discord avatar hash
+///
+/// Hashes prefixed with `a_` are animated and served as GIFs; all others are PNGs.
+fn avatar_url(user_id: &str, hash: &str) -> String {
+    let extension = if hash.starts_with("a_") { "gif" } else { "png" };
+    format!("{DISCORD_AVATAR_CDN}/{user_id}/{hash}.{extension}")
+}
+
+/// Discord user response
+#[derive(Debug, Deserialize, Serialize)]
+struct DiscordUser {
+    id: String,
+    username: String,
+    global_name: Option<String>,
+    email: Option<String>,
+    verified: Option<bool>,
+    avatar: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discord_provider_creation() {
+        let config = ProviderConfig {
+            client_id: "test-client-id".to_string(),
+            client_secret: "test-client-secret".to_string(),
+            redirect_uri: "http://localhost:3000/auth/discord/callback".to_string(),
+            scopes: vec!["identify".to_string(), "email".to_string()],
+            auth_url: None,
+            token_url: None,
+            userinfo_url: None,
+        };
+
+        let provider = DiscordProvider::new(&config).unwrap();
+        let (auth_url, csrf_state, pkce_verifier) = provider.authorization_url();
+
+        assert!(auth_url.starts_with("https://discord.com/api/oauth2/authorize"));
+        assert!(auth_url.contains("client_id=test-client-id"));
+        assert!(auth_url.contains("redirect_uri"));
+        assert!(auth_url.contains("scope=identify+email"));
+        assert!(!csrf_state.is_empty());
+        assert!(!pkce_verifier.is_empty());
+    }
+
+    #[test]
+    fn test_avatar_url_static() {
+        assert_eq!(
+            avatar_url("80351110224678912", "8342729096ea3675442027381ff50dfe"),
+            "https://cdn.discordapp.com/avatars/80351110224678912/8342729096ea3675442027381ff50dfe.png"
+        );
+    }
+
+    #[test]
+    fn test_avatar_url_animated() {
+        assert_eq!(
+            avatar_url("80351110224678912", "a_1269e74af4df7417b13759eae50c83dc"),
+            "https://cdn.discordapp.com/avatars/80351110224678912/a_1269e74af4df7417b13759eae50c83dc.gif"
+        );
+    }
+}
@@ -52,6 +52,15 @@ impl GoogleProvider {
         self.base.exchange_code(code, pkce_verifier).await
     }
 
+    /// Exchange a refresh token for a new access token
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the refresh token is rejected or the request fails
+    pub async fn refresh(&self, refresh_token: &str) -> Result<OAuthToken, OAuthError> {
+        self.base.refresh(refresh_token).await
+    }
+
     /// Fetch user information using access token
     ///
     /// # Errors
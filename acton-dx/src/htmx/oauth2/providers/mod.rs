@@ -3,16 +3,47 @@
 //! This module contains implementations for various OAuth2 providers:
 //! - Google OAuth2 (with OpenID Connect)
 //! - GitHub OAuth2
+//! - Discord OAuth2
 //! - Generic OpenID Connect provider
+//! - Sign in with Apple (`apple` feature)
 //!
 //! All providers use a shared `BaseOAuthProvider` to eliminate code duplication.
 
+#[cfg(feature = "apple")]
+pub mod apple;
 pub mod base;
+pub mod discord;
 pub mod github;
 pub mod google;
 pub mod oidc;
 
+#[cfg(feature = "apple")]
+pub use apple::{AppleCallback, AppleConfig, AppleProvider, AppleTokens};
 pub use base::BaseOAuthProvider;
+pub use discord::DiscordProvider;
 pub use github::GitHubProvider;
 pub use google::GoogleProvider;
 pub use oidc::OidcProvider;
+
+use crate::htmx::oauth2::types::{OAuthError, OAuthProvider, OAuthToken, ProviderConfig};
+
+impl OAuthProvider {
+    /// Exchange a refresh token for a new access token with this provider
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the provider cannot be created from `config`, or if the
+    /// refresh token is rejected (e.g. revoked)
+    pub async fn refresh(
+        &self,
+        config: &ProviderConfig,
+        refresh_token: &str,
+    ) -> Result<OAuthToken, OAuthError> {
+        match self {
+            Self::Google => GoogleProvider::new(config)?.refresh(refresh_token).await,
+            Self::GitHub => GitHubProvider::new(config)?.refresh(refresh_token).await,
+            Self::Discord => DiscordProvider::new(config)?.refresh(refresh_token).await,
+            Self::Oidc => OidcProvider::new(config).await?.refresh(refresh_token).await,
+        }
+    }
+}
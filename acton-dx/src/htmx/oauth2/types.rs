@@ -11,7 +11,7 @@ use std::time::{Duration, SystemTime};
 
 /// Type alias for a configured OAuth2 client with auth and token endpoints set
 ///
-/// This is the standard client type used by all OAuth2 providers (Google, GitHub, OIDC).
+/// This is the standard client type used by all OAuth2 providers (Google, GitHub, Discord, OIDC).
 /// The type parameters indicate which endpoints are configured:
 /// - `EndpointSet` for `HasAuthUrl` - Authorization endpoint is configured
 /// - `EndpointNotSet` for `HasDeviceAuthUrl` - Device auth not used
@@ -34,6 +34,8 @@ pub enum OAuthProvider {
     Google,
     /// GitHub OAuth2
     GitHub,
+    /// Discord OAuth2
+    Discord,
     /// Generic OpenID Connect provider
     Oidc,
 }
@@ -45,6 +47,7 @@ impl OAuthProvider {
         match self {
             Self::Google => "google",
             Self::GitHub => "github",
+            Self::Discord => "discord",
             Self::Oidc => "oidc",
         }
     }
@@ -57,6 +60,7 @@ impl FromStr for OAuthProvider {
         match s.to_lowercase().as_str() {
             "google" => Ok(Self::Google),
             "github" => Ok(Self::GitHub),
+            "discord" => Ok(Self::Discord),
             "oidc" => Ok(Self::Oidc),
             _ => Err(OAuthError::UnknownProvider(s.to_string())),
         }
@@ -94,9 +98,26 @@ pub struct OAuthConfig {
     /// GitHub OAuth2 configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub github: Option<ProviderConfig>,
+    /// Discord OAuth2 configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discord: Option<ProviderConfig>,
     /// Generic OIDC configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub oidc: Option<ProviderConfig>,
+    /// Where PKCE verifiers are kept between authorization and callback
+    #[serde(default)]
+    pub pkce_storage: PkceStorage,
+}
+
+/// Where PKCE verifiers are kept during an OAuth2 flow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PkceStorage {
+    /// Held server-side by the OAuth2 agent, keyed by state token
+    #[default]
+    Agent,
+    /// Stored in the session cookie
+    Session,
 }
 
 impl OAuthConfig {
@@ -106,7 +127,9 @@ impl OAuthConfig {
         Self {
             google: None,
             github: None,
+            discord: None,
             oidc: None,
+            pkce_storage: PkceStorage::Agent,
         }
     }
 
@@ -118,6 +141,7 @@ impl OAuthConfig {
         match provider {
             OAuthProvider::Google => self.google.as_ref(),
             OAuthProvider::GitHub => self.github.as_ref(),
+            OAuthProvider::Discord => self.discord.as_ref(),
             OAuthProvider::Oidc => self.oidc.as_ref(),
         }
     }
@@ -205,6 +229,15 @@ impl OAuthToken {
         self.expires_at
             .is_some_and(|expires| SystemTime::now() > expires)
     }
+
+    /// Check if the access token expires within `margin`
+    ///
+    /// Tokens without an expiry never need refreshing.
+    #[must_use]
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        self.expires_at
+            .is_some_and(|expires| SystemTime::now() + margin > expires)
+    }
 }
 
 /// User information from OAuth2 provider
@@ -247,6 +280,10 @@ pub enum OAuthError {
     #[error("Failed to exchange authorization code for token: {0}")]
     TokenExchangeFailed(String),
 
+    /// Refresh token grant failed (e.g. the refresh token was revoked)
+    #[error("Failed to refresh OAuth2 token: {0}")]
+    TokenRefreshFailed(String),
+
     /// Failed to fetch user info
     #[error("Failed to fetch user information: {0}")]
     UserInfoFailed(String),
@@ -268,6 +305,7 @@ mod tests {
     fn test_provider_as_str() {
         assert_eq!(OAuthProvider::Google.as_str(), "google");
         assert_eq!(OAuthProvider::GitHub.as_str(), "github");
+        assert_eq!(OAuthProvider::Discord.as_str(), "discord");
         assert_eq!(OAuthProvider::Oidc.as_str(), "oidc");
     }
 
@@ -285,6 +323,10 @@ mod tests {
             "github".parse::<OAuthProvider>().unwrap(),
             OAuthProvider::GitHub
         );
+        assert_eq!(
+            "discord".parse::<OAuthProvider>().unwrap(),
+            OAuthProvider::Discord
+        );
         assert_eq!(
             "oidc".parse::<OAuthProvider>().unwrap(),
             OAuthProvider::Oidc
@@ -297,6 +339,7 @@ mod tests {
         let config = OAuthConfig::default();
         assert!(config.google.is_none());
         assert!(config.github.is_none());
+        assert!(config.discord.is_none());
         assert!(config.oidc.is_none());
     }
 
@@ -347,4 +390,23 @@ mod tests {
         };
         assert!(expired_token.is_expired());
     }
+
+    #[test]
+    fn test_oauth_token_expires_within() {
+        let mut token = OAuthToken {
+            access_token: "test".to_string(),
+            refresh_token: None,
+            token_type: "Bearer".to_string(),
+            expires_at: None,
+            scopes: None,
+        };
+        assert!(!token.expires_within(Duration::from_secs(60)));
+
+        token.expires_at = Some(SystemTime::now() + Duration::from_secs(30));
+        assert!(token.expires_within(Duration::from_secs(60)));
+        assert!(!token.is_expired());
+
+        token.expires_at = Some(SystemTime::now() + Duration::from_secs(3600));
+        assert!(!token.expires_within(Duration::from_secs(60)));
+    }
 }
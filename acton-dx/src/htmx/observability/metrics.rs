@@ -2,22 +2,50 @@
 //!
 //! Provides Prometheus-compatible metrics for monitoring application performance.
 //!
+//! [`PrometheusRecorder`] lives in
+//! [`ActonHtmxState`](crate::htmx::state::ActonHtmxState) and counts HTTP
+//! requests per route when passed to
+//! [`TracingLayer::with_recorder`](crate::htmx::middleware::TracingLayer::with_recorder).
+//! [`metrics_handler`] renders those counters together with the job agent's
+//! [`JobMetrics`] in Prometheus text format.
+//!
 //! # Example
 //!
-//! ```rust,no_run
-//! use axum::{Router, routing::get};
-//! use acton_htmx::observability::metrics::metrics_handler;
+//! ```rust,ignore
+//! use acton_htmx::middleware::TracingLayer;
+//! use acton_htmx::observability::metrics::metrics_routes;
 //!
 //! let app = Router::new()
-//!     .route("/metrics", get(metrics_handler));
+//!     .route("/", get(index))
+//!     .merge(metrics_routes())
+//!     .layer(TracingLayer::new().with_recorder(state.metrics().clone()))
+//!     .with_state(state);
 //! ```
 
 use axum::{
+    extract::State,
     http::StatusCode,
     response::{IntoResponse, Response},
+    routing::get,
+    Router,
 };
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt::Write;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+
+use crate::htmx::jobs::agent::JobMetrics;
+use crate::htmx::state::ActonHtmxState;
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Upper bounds (seconds) of the HTTP latency histogram buckets
+pub const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
 
 /// Prometheus metrics collector
 #[derive(Debug, Clone)]
@@ -89,8 +117,6 @@ impl MetricsCollector {
     /// Generate Prometheus metrics output
     #[must_use]
     pub fn render(&self) -> String {
-        use std::fmt::Write;
-
         let mut output = String::new();
 
         // HTTP metrics
@@ -136,32 +162,200 @@ impl MetricsCollector {
     }
 }
 
+/// Latency histogram of one route
+#[derive(Debug, Clone, Default)]
+struct LatencyHistogram {
+    /// Non-cumulative count per bucket of [`LATENCY_BUCKETS`]
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum_secs: f64,
+}
+
+impl LatencyHistogram {
+    fn observe(&mut self, secs: f64) {
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| secs <= *bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum_secs += secs;
+    }
+}
+
+/// Counters recorded by [`PrometheusRecorder`]
+#[derive(Debug, Default)]
+struct HttpMetrics {
+    /// Requests by (method, route, status)
+    requests: BTreeMap<(String, String, u16), u64>,
+    /// Latency by route
+    latency: BTreeMap<String, LatencyHistogram>,
+}
+
+/// HTTP request metrics in Prometheus format
+///
+/// Counts requests by method, matched route, and status, and keeps a latency
+/// histogram per route. Clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct PrometheusRecorder {
+    http: Arc<Mutex<HttpMetrics>>,
+}
+
+impl PrometheusRecorder {
+    /// Create an empty recorder
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a finished request
+    ///
+    /// `route` should be the matched route template (`/posts/{id}`), not the
+    /// raw path, to keep label cardinality low.
+    pub fn record_request(&self, method: &str, route: &str, status: u16, latency: Duration) {
+        let mut http = self.http.lock();
+        *http
+            .requests
+            .entry((method.to_string(), route.to_string(), status))
+            .or_default() += 1;
+        http.latency
+            .entry(route.to_string())
+            .or_default()
+            .observe(latency.as_secs_f64());
+    }
+
+    /// Render HTTP metrics, and job metrics when available, in Prometheus
+    /// text format
+    #[must_use]
+    pub fn render(&self, jobs: Option<&JobMetrics>) -> String {
+        let mut output = String::new();
+        self.render_http(&mut output);
+        if let Some(jobs) = jobs {
+            render_jobs(&mut output, jobs);
+        }
+        output
+    }
+
+    fn render_http(&self, output: &mut String) {
+        // Snapshot so requests are not blocked while formatting
+        let (requests, latency) = {
+            let http = self.http.lock();
+            (http.requests.clone(), http.latency.clone())
+        };
+
+        output.push_str("# HELP http_requests_total Total number of HTTP requests\n");
+        output.push_str("# TYPE http_requests_total counter\n");
+        for ((method, route, status), count) in &requests {
+            let _ = writeln!(
+                output,
+                "http_requests_total{{method=\"{}\",route=\"{}\",status=\"{status}\"}} {count}",
+                escape_label(method),
+                escape_label(route),
+            );
+        }
+        output.push('\n');
+
+        output.push_str("# HELP http_request_duration_seconds HTTP request latency in seconds\n");
+        output.push_str("# TYPE http_request_duration_seconds histogram\n");
+        for (route, histogram) in &latency {
+            let route = escape_label(route);
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    output,
+                    "http_request_duration_seconds_bucket{{route=\"{route}\",le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                output,
+                "http_request_duration_seconds_bucket{{route=\"{route}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                output,
+                "http_request_duration_seconds_sum{{route=\"{route}\"}} {}",
+                histogram.sum_secs
+            );
+            let _ = writeln!(
+                output,
+                "http_request_duration_seconds_count{{route=\"{route}\"}} {}",
+                histogram.count
+            );
+        }
+        output.push('\n');
+    }
+}
+
+/// Append the job agent metrics
+fn render_jobs(output: &mut String, jobs: &JobMetrics) {
+    let samples: [(&str, &str, &str, u64); 7] = [
+        ("jobs_enqueued_total", "counter", "Total number of jobs enqueued", jobs.jobs_enqueued),
+        ("jobs_completed_total", "counter", "Total number of jobs completed successfully", jobs.jobs_completed),
+        ("jobs_failed_total", "counter", "Total number of jobs that failed", jobs.jobs_failed),
+        ("jobs_rejected_total", "counter", "Total number of jobs rejected because the queue was full", jobs.jobs_rejected),
+        ("jobs_in_dlq", "gauge", "Number of jobs in the dead letter queue", jobs.jobs_in_dlq),
+        ("jobs_queued", "gauge", "Number of jobs waiting in the queue", jobs.current_queue_size as u64),
+        ("jobs_running", "gauge", "Number of jobs currently running", jobs.current_running as u64),
+    ];
+
+    for (name, kind, help, value) in samples {
+        let _ = writeln!(output, "# HELP {name} {help}");
+        let _ = writeln!(output, "# TYPE {name} {kind}");
+        let _ = writeln!(output, "{name} {value}");
+        output.push('\n');
+    }
+}
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
 /// Metrics handler for Prometheus scraping
 ///
-/// Returns Prometheus-formatted metrics in text format.
+/// Renders the state's [`PrometheusRecorder`] and the job agent's metrics.
+/// Job metrics are left out (and a warning logged) if the job agent does not
+/// answer.
 ///
 /// # Example
 ///
 /// ```rust,no_run
 /// use axum::{Router, routing::get};
 /// use acton_htmx::observability::metrics::metrics_handler;
+/// use acton_htmx::state::ActonHtmxState;
 ///
-/// let app = Router::new()
+/// let app: Router<ActonHtmxState> = Router::new()
 ///     .route("/metrics", get(metrics_handler));
 /// ```
-#[allow(clippy::unused_async)]
-pub async fn metrics_handler() -> impl IntoResponse {
-    let collector = MetricsCollector::new();
-    metrics_response(&collector)
+pub async fn metrics_handler(State(state): State<ActonHtmxState>) -> Response {
+    let jobs = match state.get_job_metrics().await {
+        Ok(jobs) => Some(jobs),
+        Err(e) => {
+            tracing::warn!(error = %e, "Job metrics unavailable for /metrics");
+            None
+        }
+    };
+    prometheus_response(state.metrics().render(jobs.as_ref()))
+}
+
+/// Router serving [`metrics_handler`] at `/metrics`
+pub fn metrics_routes() -> Router<ActonHtmxState> {
+    Router::new().route("/metrics", get(metrics_handler))
 }
 
 /// Generate metrics response from collector
 #[must_use]
 pub fn metrics_response(collector: &MetricsCollector) -> Response {
-    let body = collector.render();
+    prometheus_response(collector.render())
+}
+
+/// Wrap a rendered exposition in a response with the Prometheus content type
+fn prometheus_response(body: String) -> Response {
     (
         StatusCode::OK,
-        [("Content-Type", "text/plain; version=0.0.4; charset=utf-8")],
+        [("Content-Type", PROMETHEUS_CONTENT_TYPE)],
         body,
     )
         .into_response()
@@ -238,10 +432,134 @@ mod tests {
         assert!(output.contains("# TYPE"));
     }
 
-    #[tokio::test]
-    async fn test_metrics_handler() {
-        let response = metrics_handler().await.into_response();
-        assert_eq!(response.status(), StatusCode::OK);
+    /// Parse a text exposition, checking each sample follows a `# TYPE` of
+    /// its family, and return `(series, value)` pairs
+    fn parse_exposition(output: &str) -> Vec<(String, String)> {
+        let valid_name = |name: &str| {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        };
+
+        let mut families = Vec::new();
+        let mut samples = Vec::new();
+        for line in output.lines().filter(|line| !line.is_empty()) {
+            if let Some(comment) = line.strip_prefix("# ") {
+                let mut parts = comment.splitn(3, ' ');
+                let (keyword, name) = (parts.next().unwrap(), parts.next().unwrap());
+                assert!(valid_name(name), "invalid metric name in {line:?}");
+                if keyword == "TYPE" {
+                    let kind = parts.next().unwrap();
+                    assert!(["counter", "gauge", "histogram"].contains(&kind), "{line:?}");
+                    families.push(name.to_string());
+                } else {
+                    assert_eq!(keyword, "HELP", "{line:?}");
+                }
+                continue;
+            }
+
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            let name = series.split('{').next().unwrap();
+            assert!(valid_name(name), "invalid metric name in {line:?}");
+            if let Some(labels) = series.strip_prefix(name).filter(|l| !l.is_empty()) {
+                let labels = labels.strip_prefix('{').and_then(|l| l.strip_suffix('}')).unwrap();
+                for label in labels.split("\",").filter(|l| !l.is_empty()) {
+                    let (key, _) = label.split_once("=\"").unwrap();
+                    assert!(valid_name(key), "invalid label in {line:?}");
+                }
+            }
+            let family = families.last().expect("sample before # TYPE");
+            assert!(name.starts_with(family.as_str()), "{name} outside family {family}");
+
+            assert!(value.parse::<f64>().is_ok(), "invalid value in {line:?}");
+            samples.push((series.to_string(), value.to_string()));
+        }
+        samples
+    }
+
+    fn sample<'a>(samples: &'a [(String, String)], series: &str) -> &'a str {
+        &samples
+            .iter()
+            .find(|(name, _)| name == series)
+            .unwrap_or_else(|| panic!("missing {series}"))
+            .1
+    }
+
+    #[test]
+    fn test_recorder_renders_valid_exposition() {
+        let recorder = PrometheusRecorder::new();
+        recorder.record_request("GET", "/posts/{id}", 200, Duration::from_millis(3));
+        recorder.record_request("GET", "/posts/{id}", 200, Duration::from_millis(80));
+        recorder.record_request("POST", "/posts", 422, Duration::from_secs(20));
+
+        let jobs = JobMetrics {
+            jobs_enqueued: 12,
+            jobs_rejected: 2,
+            jobs_in_dlq: 1,
+            current_queue_size: 4,
+            current_running: 3,
+            ..JobMetrics::default()
+        };
+        let samples = parse_exposition(&recorder.render(Some(&jobs)));
+
+        assert_eq!(
+            sample(&samples, r#"http_requests_total{method="GET",route="/posts/{id}",status="200"}"#),
+            "2"
+        );
+        assert_eq!(
+            sample(&samples, r#"http_request_duration_seconds_bucket{route="/posts/{id}",le="0.005"}"#),
+            "1"
+        );
+        assert_eq!(
+            sample(&samples, r#"http_request_duration_seconds_bucket{route="/posts/{id}",le="0.1"}"#),
+            "2"
+        );
+        // 20s is beyond the last bucket and only counted in +Inf
+        assert_eq!(
+            sample(&samples, r#"http_request_duration_seconds_bucket{route="/posts",le="10"}"#),
+            "0"
+        );
+        assert_eq!(
+            sample(&samples, r#"http_request_duration_seconds_bucket{route="/posts",le="+Inf"}"#),
+            "1"
+        );
+        assert_eq!(
+            sample(&samples, r#"http_request_duration_seconds_count{route="/posts/{id}"}"#),
+            "2"
+        );
+
+        assert_eq!(sample(&samples, "jobs_enqueued_total"), "12");
+        assert_eq!(sample(&samples, "jobs_rejected_total"), "2");
+        assert_eq!(sample(&samples, "jobs_in_dlq"), "1");
+        assert_eq!(sample(&samples, "jobs_queued"), "4");
+        assert_eq!(sample(&samples, "jobs_running"), "3");
+    }
+
+    #[test]
+    fn test_recorder_without_jobs() {
+        let output = PrometheusRecorder::new().render(None);
+        assert!(parse_exposition(&output).is_empty());
+        assert!(output.contains("# TYPE http_requests_total counter"));
+        assert!(!output.contains("jobs_"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_label("line\nbreak"), "line\\nbreak");
+    }
+
+    #[test]
+    fn test_recorder_clones_share_counters() {
+        let recorder = PrometheusRecorder::new();
+        let clone = recorder.clone();
+        clone.record_request("GET", "/", 200, Duration::from_millis(1));
+
+        let samples = parse_exposition(&recorder.render(None));
+        assert_eq!(
+            sample(&samples, r#"http_requests_total{method="GET",route="/",status="200"}"#),
+            "1"
+        );
     }
 
     #[test]
@@ -4,6 +4,8 @@
 //! via OpenTelemetry integration.
 
 pub mod metrics;
+pub mod query_log;
+pub mod redaction;
 
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
@@ -13,6 +15,7 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilte
 /// - Structured logging with JSON formatting (production) or pretty formatting (dev)
 /// - Environment-based log level filtering
 /// - Request ID correlation
+/// - SQL query capture for `DevQueryLogLayer` (debug builds only)
 ///
 /// # Errors
 ///
@@ -32,6 +35,63 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilte
 /// # }
 /// ```
 pub fn init() -> anyhow::Result<()> {
+    init_subscriber(tracing_subscriber::layer::Identity::new());
+    Ok(())
+}
+
+/// Initialize observability with OpenTelemetry export
+///
+/// With the `otel-metrics` feature, installs an OTLP (gRPC) trace pipeline
+/// when `config.tracing_enabled` and an OTLP metrics pipeline when
+/// `config.metrics_enabled`, both tagged with `config.service_name` as the
+/// `service.name` resource attribute. The collector endpoint is taken from
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` (default `http://localhost:4317`). Logging
+/// is set up as in [`init`] either way.
+///
+/// Without the feature, or with both flags off, this is the same as
+/// [`init`]. Keep the returned [`OtelGuard`] alive for the lifetime of the
+/// application; dropping it flushes pending spans and metrics.
+///
+/// # Errors
+///
+/// Returns an error if an OTLP exporter cannot be built, or for the same
+/// reasons as [`init`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use acton_htmx::observability::{self, ObservabilityConfig};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = ObservabilityConfig::new("blog").with_tracing().with_metrics();
+/// let _otel = observability::init_with_otel(&config)?;
+/// tracing::info!("Application started");
+/// # Ok(())
+/// # }
+/// ```
+pub fn init_with_otel(config: &ObservabilityConfig) -> anyhow::Result<OtelGuard> {
+    #[cfg(feature = "otel-metrics")]
+    {
+        let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok();
+        let guard = OtelGuard::build(config, endpoint.as_deref())?;
+        guard.install_global();
+        init_subscriber(guard.tracing_layer(&config.service_name));
+        Ok(guard)
+    }
+
+    #[cfg(not(feature = "otel-metrics"))]
+    {
+        let _ = config;
+        init()?;
+        Ok(OtelGuard::default())
+    }
+}
+
+/// Install the global subscriber with `extra` ahead of the formatter
+fn init_subscriber<L>(extra: L)
+where
+    L: tracing_subscriber::Layer<tracing_subscriber::Registry> + Send + Sync + 'static,
+{
     let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
         if cfg!(debug_assertions) {
             EnvFilter::new("debug,acton_htmx=trace")
@@ -42,10 +102,12 @@ pub fn init() -> anyhow::Result<()> {
 
     #[cfg(debug_assertions)]
     {
-        // Pretty formatting for development
+        // Pretty formatting for development, plus per-request query capture
         tracing_subscriber::registry()
+            .with(extra)
             .with(env_filter)
             .with(tracing_subscriber::fmt::layer().pretty())
+            .with(query_log::QueryCaptureLayer)
             .init();
     }
 
@@ -53,12 +115,145 @@ pub fn init() -> anyhow::Result<()> {
     {
         // JSON formatting for production
         tracing_subscriber::registry()
+            .with(extra)
             .with(env_filter)
             .with(tracing_subscriber::fmt::layer().json())
             .init();
     }
+}
 
-    Ok(())
+/// OpenTelemetry pipelines installed by [`init_with_otel`]
+///
+/// Flushes and shuts down the trace and metrics pipelines when dropped.
+#[derive(Debug, Default)]
+pub struct OtelGuard {
+    #[cfg(feature = "otel-metrics")]
+    tracer_provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+    #[cfg(feature = "otel-metrics")]
+    meter_provider: Option<opentelemetry_sdk::metrics::SdkMeterProvider>,
+}
+
+impl OtelGuard {
+    /// Whether spans are exported over OTLP
+    #[must_use]
+    pub const fn tracing_active(&self) -> bool {
+        #[cfg(feature = "otel-metrics")]
+        {
+            self.tracer_provider.is_some()
+        }
+        #[cfg(not(feature = "otel-metrics"))]
+        {
+            false
+        }
+    }
+
+    /// Whether metrics are exported over OTLP
+    #[must_use]
+    pub const fn metrics_active(&self) -> bool {
+        #[cfg(feature = "otel-metrics")]
+        {
+            self.meter_provider.is_some()
+        }
+        #[cfg(not(feature = "otel-metrics"))]
+        {
+            false
+        }
+    }
+}
+
+#[cfg(feature = "otel-metrics")]
+impl OtelGuard {
+    /// Build the pipelines enabled in `config`, exporting to `endpoint`
+    fn build(config: &ObservabilityConfig, endpoint: Option<&str>) -> anyhow::Result<Self> {
+        use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+        use opentelemetry_sdk::{
+            metrics::{PeriodicReader, SdkMeterProvider},
+            trace::SdkTracerProvider,
+            Resource,
+        };
+
+        let resource = Resource::builder()
+            .with_service_name(config.service_name.clone())
+            .build();
+
+        let tracer_provider = if config.tracing_enabled {
+            let mut exporter = SpanExporter::builder().with_tonic();
+            if let Some(endpoint) = endpoint {
+                exporter = exporter.with_endpoint(endpoint);
+            }
+            Some(
+                SdkTracerProvider::builder()
+                    .with_batch_exporter(exporter.build()?)
+                    .with_resource(resource.clone())
+                    .build(),
+            )
+        } else {
+            None
+        };
+
+        let meter_provider = if config.metrics_enabled {
+            let mut exporter = MetricExporter::builder().with_tonic();
+            if let Some(endpoint) = endpoint {
+                exporter = exporter.with_endpoint(endpoint);
+            }
+            Some(
+                SdkMeterProvider::builder()
+                    .with_reader(PeriodicReader::builder(exporter.build()?).build())
+                    .with_resource(resource)
+                    .build(),
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            tracer_provider,
+            meter_provider,
+        })
+    }
+
+    /// Register the providers as the `opentelemetry` globals
+    fn install_global(&self) {
+        if let Some(provider) = &self.tracer_provider {
+            opentelemetry::global::set_tracer_provider(provider.clone());
+        }
+        if let Some(provider) = &self.meter_provider {
+            opentelemetry::global::set_meter_provider(provider.clone());
+        }
+    }
+
+    /// Layer bridging `tracing` spans to the OTLP trace pipeline, if enabled
+    fn tracing_layer(
+        &self,
+        service_name: &str,
+    ) -> Option<
+        tracing_opentelemetry::OpenTelemetryLayer<
+            tracing_subscriber::Registry,
+            opentelemetry_sdk::trace::Tracer,
+        >,
+    > {
+        use opentelemetry::trace::TracerProvider as _;
+
+        self.tracer_provider.as_ref().map(|provider| {
+            tracing_opentelemetry::layer().with_tracer(provider.tracer(service_name.to_string()))
+        })
+    }
+}
+
+#[cfg(feature = "otel-metrics")]
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.tracer_provider.take() {
+            if let Err(err) = provider.shutdown() {
+                tracing::warn!(error = %err, "Failed to flush OTLP spans");
+            }
+        }
+        if let Some(provider) = self.meter_provider.take() {
+            if let Err(err) = provider.shutdown() {
+                tracing::warn!(error = %err, "Failed to flush OTLP metrics");
+            }
+        }
+    }
 }
 
 /// Observability configuration
@@ -130,4 +325,35 @@ mod tests {
         assert!(config.metrics_enabled);
         assert!(config.tracing_enabled);
     }
+
+    #[cfg(feature = "otel-metrics")]
+    #[tokio::test]
+    async fn test_otel_guard_with_dummy_endpoint() {
+        let config = ObservabilityConfig::new("my-app")
+            .with_metrics()
+            .with_tracing();
+
+        let guard = OtelGuard::build(&config, Some("http://127.0.0.1:4317")).unwrap();
+        assert!(guard.tracing_active());
+        assert!(guard.metrics_active());
+        assert!(guard.tracing_layer(&config.service_name).is_some());
+    }
+
+    #[cfg(feature = "otel-metrics")]
+    #[tokio::test]
+    async fn test_otel_guard_skips_disabled_pipelines() {
+        let config = ObservabilityConfig::new("my-app");
+
+        let guard = OtelGuard::build(&config, Some("http://127.0.0.1:4317")).unwrap();
+        assert!(!guard.tracing_active());
+        assert!(!guard.metrics_active());
+        assert!(guard.tracing_layer(&config.service_name).is_none());
+    }
+
+    #[test]
+    fn test_default_guard_is_inactive() {
+        let guard = OtelGuard::default();
+        assert!(!guard.tracing_active());
+        assert!(!guard.metrics_active());
+    }
 }
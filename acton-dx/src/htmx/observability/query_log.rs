@@ -0,0 +1,331 @@
+//! Request-scoped SQL query capture for development
+//!
+//! [`QueryCaptureLayer`] is a `tracing-subscriber` layer that listens for the
+//! events sqlx emits on the `sqlx::query` target and records them into the
+//! [`QueryLog`] of the request currently being handled. The request scope is
+//! established by [`DevQueryLogLayer`](crate::htmx::middleware::DevQueryLogLayer).
+//!
+//! Capture only happens in debug builds; in release builds the layer ignores
+//! every event. sqlx logs statements with placeholders (`$1`, `?`), never
+//! bound values, and string literals inlined in the SQL are redacted before
+//! they are stored.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::observability::query_log::QueryCaptureLayer;
+//! use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+//!
+//! tracing_subscriber::registry()
+//!     .with(EnvFilter::new("info,sqlx::query=debug"))
+//!     .with(tracing_subscriber::fmt::layer())
+//!     .with(QueryCaptureLayer)
+//!     .init();
+//! ```
+
+use parking_lot::Mutex;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Tracing target sqlx emits query events on
+pub const SQLX_QUERY_TARGET: &str = "sqlx::query";
+
+/// Maximum stored statement length before truncation
+const MAX_STATEMENT_LEN: usize = 500;
+
+tokio::task_local! {
+    static CURRENT_QUERY_LOG: QueryLog;
+}
+
+/// A single captured query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryRecord {
+    /// Redacted SQL statement
+    pub statement: String,
+    /// Execution time reported by sqlx
+    pub elapsed: Duration,
+    /// Rows returned
+    pub rows_returned: u64,
+    /// Rows affected
+    pub rows_affected: u64,
+}
+
+/// Aggregate view of the queries of one request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryLogSummary {
+    /// Number of queries
+    pub count: usize,
+    /// Sum of query execution times
+    pub total: Duration,
+    /// Slowest query, if any
+    pub slowest: Option<QueryRecord>,
+}
+
+impl QueryLogSummary {
+    /// Format as a `Server-Timing` header value
+    ///
+    /// Produces a `db` entry with the total time, plus a `db-slowest` entry
+    /// when at least one query ran.
+    #[must_use]
+    pub fn server_timing(&self) -> String {
+        let mut value = format!(
+            "db;dur={:.2};desc=\"{} queries\"",
+            duration_ms(self.total),
+            self.count
+        );
+        if let Some(slowest) = &self.slowest {
+            let _ = write!(value, ", db-slowest;dur={:.2}", duration_ms(slowest.elapsed));
+        }
+        value
+    }
+}
+
+/// Queries captured during one request
+///
+/// Cheap to clone; clones share the same underlying list.
+#[derive(Debug, Clone, Default)]
+pub struct QueryLog {
+    queries: Arc<Mutex<Vec<QueryRecord>>>,
+}
+
+impl QueryLog {
+    /// Create an empty query log
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `future` with this log as the current request's query log
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT_QUERY_LOG.scope(self.clone(), future).await
+    }
+
+    /// The query log of the current request, if inside [`scope`](Self::scope)
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CURRENT_QUERY_LOG.try_with(Clone::clone).ok()
+    }
+
+    /// Record a query
+    pub fn record(&self, query: QueryRecord) {
+        self.queries.lock().push(query);
+    }
+
+    /// All captured queries, in execution order
+    #[must_use]
+    pub fn queries(&self) -> Vec<QueryRecord> {
+        self.queries.lock().clone()
+    }
+
+    /// Summarize the captured queries
+    #[must_use]
+    pub fn summary(&self) -> QueryLogSummary {
+        let queries = self.queries.lock();
+        QueryLogSummary {
+            count: queries.len(),
+            total: queries.iter().map(|q| q.elapsed).sum(),
+            slowest: queries.iter().max_by_key(|q| q.elapsed).cloned(),
+        }
+    }
+}
+
+/// Tracing layer that records sqlx query events into the current [`QueryLog`]
+///
+/// Events are only recorded in debug builds and only while a request is
+/// inside a [`QueryLog::scope`]. The `sqlx::query` target must be enabled at
+/// `debug` level (sqlx's default statement level) by the subscriber filter.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryCaptureLayer;
+
+impl<S: Subscriber> Layer<S> for QueryCaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if !cfg!(debug_assertions) || event.metadata().target() != SQLX_QUERY_TARGET {
+            return;
+        }
+
+        let Some(log) = QueryLog::current() else {
+            return;
+        };
+
+        let mut visitor = QueryEventVisitor::default();
+        event.record(&mut visitor);
+        log.record(visitor.into_record());
+    }
+}
+
+/// Collects the fields of a sqlx query event
+#[derive(Debug, Default)]
+struct QueryEventVisitor {
+    summary: String,
+    statement: String,
+    elapsed_secs: f64,
+    rows_returned: u64,
+    rows_affected: u64,
+}
+
+impl QueryEventVisitor {
+    fn into_record(self) -> QueryRecord {
+        // sqlx only fills `db.statement` when the summary was abbreviated
+        let statement = if self.statement.trim().is_empty() {
+            self.summary
+        } else {
+            self.statement
+        };
+
+        QueryRecord {
+            statement: redact_statement(&statement),
+            elapsed: Duration::try_from_secs_f64(self.elapsed_secs).unwrap_or_default(),
+            rows_returned: self.rows_returned,
+            rows_affected: self.rows_affected,
+        }
+    }
+}
+
+impl Visit for QueryEventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.statement = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = value;
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "rows_returned" => self.rows_returned = value,
+            "rows_affected" => self.rows_affected = value,
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// Redact string literals and collapse whitespace in a SQL statement
+///
+/// `WHERE email = 'a@example.com'` becomes `WHERE email = '?'`. Statements
+/// longer than 500 characters are truncated.
+#[must_use]
+pub fn redact_statement(sql: &str) -> String {
+    let mut redacted = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut last_was_space = false;
+
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            // Skip to the closing quote, honoring '' escapes
+            while let Some(inner) = chars.next() {
+                if inner == '\'' {
+                    if chars.peek() == Some(&'\'') {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            redacted.push_str("'?'");
+            last_was_space = false;
+        } else if c.is_whitespace() {
+            if !last_was_space && !redacted.is_empty() {
+                redacted.push(' ');
+            }
+            last_was_space = true;
+        } else {
+            redacted.push(c);
+            last_was_space = false;
+        }
+    }
+
+    let trimmed = redacted.trim_end();
+    if trimmed.chars().count() > MAX_STATEMENT_LEN {
+        let mut truncated: String = trimmed.chars().take(MAX_STATEMENT_LEN).collect();
+        truncated.push('…');
+        truncated
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_redact_string_literals() {
+        assert_eq!(
+            redact_statement("SELECT * FROM users WHERE email = 'a@b.com' AND name = 'O''Brien'"),
+            "SELECT * FROM users WHERE email = '?' AND name = '?'"
+        );
+        assert_eq!(
+            redact_statement("SELECT id\n   FROM posts\n  WHERE id = $1\n"),
+            "SELECT id FROM posts WHERE id = $1"
+        );
+    }
+
+    #[test]
+    fn test_summary_and_server_timing() {
+        let log = QueryLog::new();
+        for ms in [2, 10, 3] {
+            log.record(QueryRecord {
+                statement: format!("SELECT {ms}"),
+                elapsed: Duration::from_millis(ms),
+                rows_returned: 1,
+                rows_affected: 0,
+            });
+        }
+
+        let summary = log.summary();
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.total, Duration::from_millis(15));
+        assert_eq!(summary.slowest.as_ref().unwrap().statement, "SELECT 10");
+        assert_eq!(
+            summary.server_timing(),
+            "db;dur=15.00;desc=\"3 queries\", db-slowest;dur=10.00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_layer_captures_only_inside_scope() {
+        let subscriber = tracing_subscriber::registry().with(QueryCaptureLayer);
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let emit = || {
+            tracing::debug!(
+                target: "sqlx::query",
+                summary = "SELECT * FROM users WHERE email = 'x@y.z'",
+                db.statement = "",
+                rows_affected = 0_u64,
+                rows_returned = 1_u64,
+                elapsed_secs = 0.004,
+            );
+        };
+
+        // Outside a request: ignored
+        emit();
+
+        let log = QueryLog::new();
+        log.scope(async { emit() }).await;
+
+        let queries = log.queries();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].statement, "SELECT * FROM users WHERE email = '?'");
+        assert_eq!(queries[0].elapsed, Duration::from_millis(4));
+        assert_eq!(queries[0].rows_returned, 1);
+    }
+}
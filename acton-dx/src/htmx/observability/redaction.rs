@@ -0,0 +1,263 @@
+//! Redaction of sensitive request data before it is logged
+//!
+//! [`Redactor`] masks header values and named fields in query strings, form
+//! bodies, and JSON bodies (at any nesting depth). It is built from
+//! [`LoggingSettings`], whose defaults cover the framework's own sensitive
+//! names: credentials, session cookies, CSRF tokens, and OAuth tokens.
+//!
+//! # Example
+//!
+//! ```rust
+//! use acton_dx::htmx::config::LoggingSettings;
+//! use acton_dx::htmx::observability::redaction::Redactor;
+//!
+//! let redactor = Redactor::new(&LoggingSettings::default());
+//! assert_eq!(
+//!     redactor.redact_form("email=a%40b.com&password=hunter2"),
+//!     "email=a%40b.com&password=[REDACTED]"
+//! );
+//! ```
+
+use crate::htmx::config::LoggingSettings;
+use axum::http::HeaderMap;
+use std::collections::HashSet;
+
+/// Replacement for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Masks configured headers and fields
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    headers: HashSet<String>,
+    fields: HashSet<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new(&LoggingSettings::default())
+    }
+}
+
+impl Redactor {
+    /// Build a redactor from logging settings
+    #[must_use]
+    pub fn new(settings: &LoggingSettings) -> Self {
+        Self {
+            headers: settings
+                .redact_headers
+                .iter()
+                .map(|h| h.to_ascii_lowercase())
+                .collect(),
+            fields: settings
+                .redact_fields
+                .iter()
+                .map(|f| f.to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Whether a header's value is masked
+    #[must_use]
+    pub fn is_sensitive_header(&self, name: &str) -> bool {
+        self.headers.contains(&name.to_ascii_lowercase())
+    }
+
+    /// Whether a field's value is masked
+    ///
+    /// Bracketed form names match on their last segment, so
+    /// `user[password]` is treated like `password`.
+    #[must_use]
+    pub fn is_sensitive_field(&self, name: &str) -> bool {
+        let name = name.trim_end_matches(']');
+        let leaf = name.rsplit('[').next().unwrap_or(name);
+        self.fields.contains(&leaf.to_ascii_lowercase())
+    }
+
+    /// Headers as `(name, value)` pairs with sensitive values masked
+    #[must_use]
+    pub fn redact_headers(&self, headers: &HeaderMap) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.is_sensitive_header(name.as_str()) {
+                    REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.as_str().to_string(), value)
+            })
+            .collect()
+    }
+
+    /// Mask sensitive pairs of a URL-encoded query string or form body
+    ///
+    /// Pairs are kept in their original (encoded) form; only values of
+    /// sensitive fields are replaced.
+    #[must_use]
+    pub fn redact_form(&self, encoded: &str) -> String {
+        encoded
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((key, _)) if self.is_sensitive_field(&decode_component(key)) => {
+                    format!("{key}={REDACTED}")
+                }
+                _ => pair.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Mask a request path and query, e.g. `/callback?code=abc&state=xyz`
+    #[must_use]
+    pub fn redact_uri(&self, path_and_query: &str) -> String {
+        match path_and_query.split_once('?') {
+            Some((path, query)) => format!("{path}?{}", self.redact_form(query)),
+            None => path_and_query.to_string(),
+        }
+    }
+
+    /// Mask sensitive fields of a JSON value in place, at any depth
+    pub fn redact_json(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.is_sensitive_field(key) {
+                        *value = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_json(value);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact_json(item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Render a request body for logging, masking sensitive fields
+    ///
+    /// Only form and JSON bodies are rendered; other content types (and
+    /// JSON that fails to parse) return `None` so raw bytes never reach the
+    /// logs.
+    #[must_use]
+    pub fn redact_body(&self, content_type: &str, body: &[u8]) -> Option<String> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        if mime == "application/x-www-form-urlencoded" {
+            return Some(self.redact_form(&String::from_utf8_lossy(body)));
+        }
+
+        if mime == "application/json" || mime.ends_with("+json") {
+            let mut value: serde_json::Value = serde_json::from_slice(body).ok()?;
+            self.redact_json(&mut value);
+            return Some(value.to_string());
+        }
+
+        None
+    }
+}
+
+/// Percent-decode a form key (`+` is a space)
+fn decode_component(encoded: &str) -> String {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("Authorization", HeaderValue::from_static("Bearer secret"));
+        headers.insert("cookie", HeaderValue::from_static("acton_session=abc"));
+        headers.insert("accept", HeaderValue::from_static("text/html"));
+
+        let redacted = Redactor::default().redact_headers(&headers);
+        assert!(redacted.contains(&("authorization".into(), REDACTED.into())));
+        assert!(redacted.contains(&("cookie".into(), REDACTED.into())));
+        assert!(redacted.contains(&("accept".into(), "text/html".into())));
+    }
+
+    #[test]
+    fn test_redact_form_and_query() {
+        let redactor = Redactor::default();
+        assert_eq!(
+            redactor.redact_form("email=a%40b.com&password=p%40ss&_csrf_token=t&user%5Bpassword%5D=x"),
+            "email=a%40b.com&password=[REDACTED]&_csrf_token=[REDACTED]&user%5Bpassword%5D=[REDACTED]"
+        );
+        assert_eq!(
+            redactor.redact_uri("/auth/callback?code=abc&state=xyz"),
+            "/auth/callback?code=[REDACTED]&state=xyz"
+        );
+        assert_eq!(redactor.redact_uri("/posts"), "/posts");
+    }
+
+    #[test]
+    fn test_redact_nested_json() {
+        let redactor = Redactor::default();
+        let body = json!({
+            "email": "a@b.com",
+            "Password": "hunter2",
+            "profile": {"tokens": [{"access_token": "x", "scope": "read"}]},
+        });
+
+        let rendered = redactor
+            .redact_body("application/json; charset=utf-8", body.to_string().as_bytes())
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(value["email"], "a@b.com");
+        assert_eq!(value["Password"], REDACTED);
+        assert_eq!(value["profile"]["tokens"][0]["access_token"], REDACTED);
+        assert_eq!(value["profile"]["tokens"][0]["scope"], "read");
+    }
+
+    #[test]
+    fn test_unsupported_bodies_are_not_rendered() {
+        let redactor = Redactor::default();
+        assert!(redactor.redact_body("application/octet-stream", b"\x00\x01").is_none());
+        assert!(redactor.redact_body("application/json", b"{not json").is_none());
+    }
+
+    #[test]
+    fn test_custom_fields() {
+        let settings = LoggingSettings {
+            redact_fields: vec!["SSN".to_string()],
+            ..LoggingSettings::default()
+        };
+        let redactor = Redactor::new(&settings);
+        assert_eq!(redactor.redact_form("ssn=123&password=x"), "ssn=[REDACTED]&password=x");
+    }
+}
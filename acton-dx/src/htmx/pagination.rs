@@ -0,0 +1,627 @@
+//! Pagination helpers
+//!
+//! [`Page`] carries one page of records plus the numbers needed to navigate,
+//! with the same shape and semantics as the job history
+//! [`JobHistoryPage`](crate::htmx::jobs::agent::JobHistoryPage).
+//! [`PaginationTemplate`] renders numbered page links and an HTMX
+//! "load more" button for it, and [`PaginationQuery`] extracts
+//! `?page=&page_size=` from the request with bounds applied.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::pagination::{PaginationQuery, PaginationTemplate};
+//! use askama::Template;
+//!
+//! async fn list_posts(
+//!     State(repo): State<Arc<PostRepository>>,
+//!     pagination: PaginationQuery,
+//! ) -> Html<String> {
+//!     let posts = pagination.list(&*repo).await?;
+//!
+//!     let nav = PaginationTemplate::new(&posts, "/posts").load_more_target("#post-rows");
+//!     Html(nav.render()?)
+//! }
+//! ```
+
+use std::convert::Infallible;
+
+use askama::Template;
+use axum::{extract::FromRequestParts, http::request::Parts};
+use serde::{Deserialize, Serialize};
+
+use crate::htmx::db::Repository;
+use crate::htmx::jobs::agent::{JobHistoryPage, JobHistoryRecord};
+
+/// Numbered links shown on each side of the current page
+const LINK_WINDOW: usize = 2;
+
+/// Page size used when the request doesn't specify one
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+
+/// Largest page size a request may ask for unless overridden
+pub const DEFAULT_MAX_PAGE_SIZE: u32 = 100;
+
+/// One page of records with pagination info
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Records on this page
+    pub items: Vec<T>,
+    /// Current page number (1-indexed)
+    pub page: usize,
+    /// Number of records per page
+    pub page_size: usize,
+    /// Total number of matching records across all pages
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    /// Create a page from records and pagination info
+    #[must_use]
+    pub const fn new(items: Vec<T>, page: usize, page_size: usize, total: usize) -> Self {
+        Self {
+            items,
+            page,
+            page_size,
+            total,
+        }
+    }
+
+    /// Number of records to skip to reach this page (for SQL `OFFSET`)
+    #[must_use]
+    pub const fn offset(page: usize, page_size: usize) -> usize {
+        page.saturating_sub(1).saturating_mul(page_size)
+    }
+
+    /// Total number of pages (0 when there are no records)
+    #[must_use]
+    pub const fn total_pages(&self) -> usize {
+        if self.page_size == 0 {
+            0
+        } else {
+            self.total.div_ceil(self.page_size)
+        }
+    }
+
+    /// Whether there is a next page
+    #[must_use]
+    pub const fn has_next(&self) -> bool {
+        self.page < self.total_pages()
+    }
+
+    /// Whether there is a previous page
+    #[must_use]
+    pub const fn has_prev(&self) -> bool {
+        self.page > 1
+    }
+
+    /// Get the previous page number
+    #[must_use]
+    pub fn prev_page(&self) -> usize {
+        self.page.saturating_sub(1).max(1)
+    }
+
+    /// Get the next page number
+    #[must_use]
+    pub const fn next_page(&self) -> usize {
+        self.page + 1
+    }
+
+    /// Get the starting record number for this page (1-indexed, 0 if empty)
+    #[must_use]
+    pub fn page_start(&self) -> usize {
+        if self.items.is_empty() {
+            0
+        } else {
+            Self::offset(self.page, self.page_size) + 1
+        }
+    }
+
+    /// Get the ending record number for this page (1-indexed, 0 if empty)
+    #[must_use]
+    pub fn page_end(&self) -> usize {
+        if self.items.is_empty() {
+            0
+        } else {
+            self.page_start() + self.items.len() - 1
+        }
+    }
+
+    /// Convert the records, keeping the pagination info
+    #[must_use]
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            page: self.page,
+            page_size: self.page_size,
+            total: self.total,
+        }
+    }
+}
+
+impl From<JobHistoryPage> for Page<JobHistoryRecord> {
+    fn from(history: JobHistoryPage) -> Self {
+        Self::new(
+            history.jobs,
+            history.page,
+            history.page_size,
+            history.total_count,
+        )
+    }
+}
+
+/// Page size bounds for [`PaginationQuery`]
+///
+/// Add to a router or route as an [`Extension`](axum::Extension) to change
+/// the defaults; without one, [`PaginationLimits::default`] applies.
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::pagination::PaginationLimits;
+/// use axum::{routing::get, Extension, Router};
+///
+/// let app = Router::new()
+///     .route("/posts", get(list_posts))
+///     .layer(Extension(PaginationLimits::new(50, 500)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginationLimits {
+    /// Page size used when the request doesn't specify a usable one
+    pub default_page_size: u32,
+    /// Largest page size a request may ask for
+    pub max_page_size: u32,
+}
+
+impl PaginationLimits {
+    /// Create limits, keeping both sizes at least 1 and the default no
+    /// larger than the maximum
+    #[must_use]
+    pub fn new(default_page_size: u32, max_page_size: u32) -> Self {
+        let max_page_size = max_page_size.max(1);
+        Self {
+            default_page_size: default_page_size.clamp(1, max_page_size),
+            max_page_size,
+        }
+    }
+}
+
+impl Default for PaginationLimits {
+    fn default() -> Self {
+        Self::new(DEFAULT_PAGE_SIZE, DEFAULT_MAX_PAGE_SIZE)
+    }
+}
+
+/// Page number and size from the `page` and `page_size` query parameters
+///
+/// Never rejects a request: missing or non-numeric values fall back to
+/// page 1 and the default page size, and out-of-range values are clamped
+/// (`page` to at least 1, `page_size` to `1..=max_page_size`). Bounds come
+/// from a [`PaginationLimits`] extension if one is present.
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::pagination::PaginationQuery;
+///
+/// // GET /posts?page=3&page_size=1000 -> page 3 of 100 posts
+/// async fn list_posts(pagination: PaginationQuery) -> Html<String> {
+///     let (posts, total) = Post::paginate(pagination.offset(), pagination.page_size).await?;
+///     let posts = pagination.page_of(posts, total);
+///     // ...
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PaginationQuery {
+    /// Requested page number (1-indexed)
+    pub page: u32,
+    /// Requested number of records per page
+    pub page_size: u32,
+}
+
+impl PaginationQuery {
+    /// Parse a raw query string with the given bounds
+    #[must_use]
+    pub fn from_query(query: &str, limits: PaginationLimits) -> Self {
+        let pairs: Vec<(String, String)> = serde_html_form::from_str(query).unwrap_or_default();
+        let param = |name: &str| {
+            pairs
+                .iter()
+                .find(|(key, _)| key == name)
+                .and_then(|(_, value)| parse_bound(value))
+        };
+
+        let page = param("page").map_or(1, |page| clamp_to_u32(page, 1, u32::MAX));
+        let page_size = param("page_size").map_or(limits.default_page_size, |size| {
+            clamp_to_u32(size, 1, limits.max_page_size)
+        });
+
+        Self { page, page_size }
+    }
+
+    /// Number of records to skip to reach this page (for SQL `OFFSET`)
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        Page::<()>::offset(self.page as usize, self.page_size as usize)
+    }
+
+    /// Wrap one page of records fetched for this query in a [`Page`]
+    #[must_use]
+    pub const fn page_of<T>(&self, items: Vec<T>, total: usize) -> Page<T> {
+        Page::new(items, self.page as usize, self.page_size as usize, total)
+    }
+
+    /// Fetch this page from a [`Repository`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the repository's database error.
+    pub async fn list<T, Id, R>(&self, repository: &R) -> Result<Page<T>, sqlx::Error>
+    where
+        R: Repository<T, Id> + ?Sized,
+    {
+        repository
+            .list(self.page as usize, self.page_size as usize)
+            .await
+    }
+}
+
+impl Default for PaginationQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            page_size: PaginationLimits::default().default_page_size,
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for PaginationQuery
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let limits = parts
+            .extensions
+            .get::<PaginationLimits>()
+            .copied()
+            .unwrap_or_default();
+        Ok(Self::from_query(
+            parts.uri.query().unwrap_or_default(),
+            limits,
+        ))
+    }
+}
+
+/// Parse an integer parameter, saturating values too large for `i64`
+///
+/// Returns `None` for empty or non-numeric input.
+fn parse_bound(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(number) = value.parse() {
+        return Some(number);
+    }
+    let negative = value.starts_with('-');
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    (!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+        .then_some(if negative { i64::MIN } else { i64::MAX })
+}
+
+/// Clamp `value` to `min..=max`
+fn clamp_to_u32(value: i64, min: u32, max: u32) -> u32 {
+    u32::try_from(value.clamp(i64::from(min), i64::from(max))).unwrap_or(min)
+}
+
+/// A numbered link (or gap) in [`PaginationTemplate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageLink {
+    /// Page number, or `None` for an elided range
+    pub number: Option<usize>,
+    /// URL of the page
+    pub url: String,
+    /// Whether this is the current page
+    pub current: bool,
+}
+
+/// Pagination controls for a [`Page`]
+///
+/// Renders a `<nav class="pagination">` with previous/next and numbered
+/// links (pages far from the current one are elided), followed by a
+/// "load more" button that appends the next page to `load_more_target` with
+/// `hx-swap="beforeend"`. Links use `?page=N` on `base_url`.
+///
+/// The button has `id="load-more"` so the next page's response can replace
+/// it out-of-band with the template rendered for that page.
+#[derive(Debug, Clone, Template)]
+#[template(
+    source = r#"{% if total_pages > 1 %}<nav class="pagination" aria-label="Pagination">
+{% if let Some(url) = prev_url %}<a href="{{ url }}" rel="prev">Previous</a>
+{% endif %}{% for link in links %}{% if let Some(number) = link.number %}{% if link.current %}<span aria-current="page">{{ number }}</span>
+{% else %}<a href="{{ link.url }}">{{ number }}</a>
+{% endif %}{% else %}<span class="pagination-gap">&hellip;</span>
+{% endif %}{% endfor %}{% if let Some(url) = next_url %}<a href="{{ url }}" rel="next">Next</a>
+{% endif %}</nav>
+{% endif %}{% if let Some(url) = next_url %}<button id="load-more" class="load-more" hx-get="{{ url }}" hx-target="{{ load_more_target }}" hx-swap="beforeend">Load more</button>
+{% endif %}"#,
+    ext = "html"
+)]
+pub struct PaginationTemplate {
+    /// Total number of pages
+    pub total_pages: usize,
+    /// URL of the previous page, if any
+    pub prev_url: Option<String>,
+    /// URL of the next page, if any
+    pub next_url: Option<String>,
+    /// Numbered links
+    pub links: Vec<PageLink>,
+    /// Selector the "load more" button appends to
+    pub load_more_target: String,
+}
+
+impl PaginationTemplate {
+    /// Build pagination controls for `page`, linking to `base_url?page=N`
+    ///
+    /// The "load more" button targets `#items` until
+    /// [`load_more_target`](Self::load_more_target) is set.
+    #[must_use]
+    pub fn new<T>(page: &Page<T>, base_url: &str) -> Self {
+        let page_url = |number: usize| {
+            let separator = if base_url.contains('?') { '&' } else { '?' };
+            format!("{base_url}{separator}page={number}")
+        };
+
+        let total_pages = page.total_pages();
+        let mut links = Vec::new();
+        let mut gap = false;
+        for number in 1..=total_pages {
+            let near_current = number.abs_diff(page.page) <= LINK_WINDOW;
+            if number == 1 || number == total_pages || near_current {
+                links.push(PageLink {
+                    number: Some(number),
+                    url: page_url(number),
+                    current: number == page.page,
+                });
+                gap = false;
+            } else if !gap {
+                links.push(PageLink {
+                    number: None,
+                    url: String::new(),
+                    current: false,
+                });
+                gap = true;
+            }
+        }
+
+        Self {
+            total_pages,
+            prev_url: page.has_prev().then(|| page_url(page.prev_page())),
+            next_url: page.has_next().then(|| page_url(page.next_page())),
+            links,
+            load_more_target: "#items".to_string(),
+        }
+    }
+
+    /// Set the selector the "load more" button appends to
+    #[must_use]
+    pub fn load_more_target(mut self, selector: impl Into<String>) -> Self {
+        self.load_more_target = selector.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(current: usize, total: usize) -> Page<usize> {
+        let start = Page::<usize>::offset(current, 10);
+        let items = (start..total.min(start + 10)).collect();
+        Page::new(items, current, 10, total)
+    }
+
+    fn link_numbers(template: &PaginationTemplate) -> Vec<Option<usize>> {
+        template.links.iter().map(|link| link.number).collect()
+    }
+
+    #[test]
+    fn test_empty_results() {
+        let empty = page(1, 0);
+
+        assert_eq!(empty.total_pages(), 0);
+        assert!(!empty.has_next());
+        assert!(!empty.has_prev());
+        assert_eq!(empty.page_start(), 0);
+        assert_eq!(empty.page_end(), 0);
+
+        let html = PaginationTemplate::new(&empty, "/posts").render().unwrap();
+        assert!(html.is_empty());
+    }
+
+    #[test]
+    fn test_single_page() {
+        let single = page(1, 7);
+
+        assert_eq!(single.total_pages(), 1);
+        assert!(!single.has_next());
+        assert!(!single.has_prev());
+        assert_eq!((single.page_start(), single.page_end()), (1, 7));
+
+        // No navigation needed for one page
+        let html = PaginationTemplate::new(&single, "/posts").render().unwrap();
+        assert!(html.is_empty());
+    }
+
+    #[test]
+    fn test_last_page() {
+        let last = page(3, 25);
+
+        assert_eq!(last.total_pages(), 3);
+        assert!(!last.has_next());
+        assert!(last.has_prev());
+        assert_eq!((last.page_start(), last.page_end()), (21, 25));
+
+        let html = PaginationTemplate::new(&last, "/posts").render().unwrap();
+        assert!(html.contains(r#"<a href="/posts?page=2" rel="prev">Previous</a>"#));
+        assert!(html.contains(r#"<span aria-current="page">3</span>"#));
+        assert!(!html.contains("rel=\"next\""));
+        assert!(!html.contains("load-more"));
+    }
+
+    #[test]
+    fn test_load_more_button() {
+        let first = page(1, 25);
+        let html = PaginationTemplate::new(&first, "/posts?q=rust")
+            .load_more_target("#post-rows")
+            .render()
+            .unwrap();
+
+        assert!(html.contains(
+            r##"<button id="load-more" class="load-more" hx-get="/posts?q=rust&#38;page=2" hx-target="#post-rows" hx-swap="beforeend">"##
+        ));
+        assert!(!html.contains("rel=\"prev\""));
+    }
+
+    #[test]
+    fn test_links_elide_distant_pages() {
+        let middle = page(10, 200);
+        let template = PaginationTemplate::new(&middle, "/posts");
+
+        assert_eq!(
+            link_numbers(&template),
+            vec![
+                Some(1),
+                None,
+                Some(8),
+                Some(9),
+                Some(10),
+                Some(11),
+                Some(12),
+                None,
+                Some(20)
+            ]
+        );
+        assert!(template.render().unwrap().contains("&hellip;"));
+    }
+
+    #[test]
+    fn test_from_job_history_page() {
+        let history = JobHistoryPage::new(Vec::new(), 2, 20, 45);
+        let page = Page::from(history.clone());
+
+        assert_eq!(page.total_pages(), 3);
+        assert_eq!(page.has_next(), history.has_next);
+        assert_eq!(page.has_prev(), history.has_prev);
+    }
+
+    #[test]
+    fn test_pagination_query_defaults() {
+        let limits = PaginationLimits::default();
+
+        for query in ["", "q=rust", "page=&page_size="] {
+            assert_eq!(
+                PaginationQuery::from_query(query, limits),
+                PaginationQuery {
+                    page: 1,
+                    page_size: 20
+                },
+                "query {query:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_pagination_query_clamps_out_of_range() {
+        let limits = PaginationLimits::default();
+
+        let query = PaginationQuery::from_query("page=3&page_size=1000", limits);
+        assert_eq!((query.page, query.page_size), (3, 100));
+
+        let query = PaginationQuery::from_query("page=0&page_size=0", limits);
+        assert_eq!((query.page, query.page_size), (1, 1));
+
+        let query = PaginationQuery::from_query("page=-4&page_size=-10", limits);
+        assert_eq!((query.page, query.page_size), (1, 1));
+
+        let query =
+            PaginationQuery::from_query("page=99999999999999999999999&page_size=99999999999", limits);
+        assert_eq!((query.page, query.page_size), (u32::MAX, 100));
+
+        let custom = PaginationLimits::new(10, 25);
+        let query = PaginationQuery::from_query("page_size=50", custom);
+        assert_eq!(query.page_size, 25);
+        assert_eq!(PaginationQuery::from_query("", custom).page_size, 10);
+    }
+
+    #[test]
+    fn test_pagination_query_ignores_non_numeric_input() {
+        let limits = PaginationLimits::default();
+
+        let query = PaginationQuery::from_query("page=abc&page_size=lots", limits);
+        assert_eq!((query.page, query.page_size), (1, 20));
+
+        let query = PaginationQuery::from_query("page=2.5&page_size=1e3", limits);
+        assert_eq!((query.page, query.page_size), (1, 20));
+
+        // Malformed query strings don't reject the request either
+        let query = PaginationQuery::from_query("page=%zz&page_size=%", limits);
+        assert_eq!((query.page, query.page_size), (1, 20));
+
+        // Surrounding whitespace is tolerated
+        let query = PaginationQuery::from_query("page=+2+&page_size=%2015", limits);
+        assert_eq!((query.page, query.page_size), (2, 15));
+    }
+
+    #[test]
+    fn test_pagination_query_builds_page() {
+        let query = PaginationQuery::from_query("page=3&page_size=10", PaginationLimits::default());
+
+        assert_eq!(query.offset(), 20);
+        let page = query.page_of(vec![21, 22], 22);
+        assert_eq!((page.page, page.page_size, page.total), (3, 10, 22));
+        assert_eq!((page.page_start(), page.page_end()), (21, 22));
+    }
+
+    #[test]
+    fn test_pagination_limits_new_keeps_default_in_bounds() {
+        assert_eq!(
+            PaginationLimits::new(50, 10),
+            PaginationLimits {
+                default_page_size: 10,
+                max_page_size: 10
+            }
+        );
+        assert_eq!(PaginationLimits::new(0, 0).default_page_size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pagination_query_extractor_uses_limits_extension() {
+        use axum::{body::Body, http::Request, routing::get, Extension, Router};
+        use tower::ServiceExt;
+
+        async fn handler(pagination: PaginationQuery) -> String {
+            format!("{}:{}", pagination.page, pagination.page_size)
+        }
+
+        let app = Router::new()
+            .route("/default", get(handler))
+            .route(
+                "/limited",
+                get(handler).layer(Extension(PaginationLimits::new(5, 10))),
+            );
+
+        let body = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                String::from_utf8(bytes.to_vec()).unwrap()
+            }
+        };
+
+        assert_eq!(body("/default?page=x&page_size=500").await, "1:100");
+        assert_eq!(body("/limited").await, "1:5");
+        assert_eq!(body("/limited?page=2&page_size=500").await, "2:10");
+    }
+}
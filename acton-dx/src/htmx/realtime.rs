@@ -0,0 +1,268 @@
+//! Topic-keyed broadcast channels for real-time pushes
+//!
+//! [`RealtimeHub`] lets handlers and jobs push rendered HTML fragments to the
+//! SSE and WebSocket connections subscribed to a topic ("user:42",
+//! "dashboard"). Each topic is backed by its own bounded
+//! `tokio::sync::broadcast` channel, created on first subscription and
+//! removed once its last subscriber disconnects.
+//!
+//! # Slow Consumers
+//!
+//! A subscriber that falls more than the topic capacity behind loses the
+//! oldest fragments. Instead of silently skipping them, the subscription
+//! yields [`HubEvent::Resync`] once, telling the connection to re-render its
+//! view from scratch (for example by triggering an HTMX refresh).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::realtime::{HubEvent, RealtimeHub};
+//!
+//! // In a handler or job
+//! state.realtime().publish(&RealtimeHub::user_topic(42), "<div id=\"inbox\" hx-swap-oob=\"true\">3</div>");
+//!
+//! // In an SSE/WebSocket handler
+//! let mut subscription = state.realtime().subscribe(&RealtimeHub::user_topic(42));
+//! while let Some(event) = subscription.recv().await {
+//!     match event {
+//!         HubEvent::Fragment(html) => send(html).await,
+//!         HubEvent::Resync => send_full_refresh().await,
+//!     }
+//! }
+//! ```
+
+use futures_util::stream::{self, Stream};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Default number of buffered fragments per topic
+pub const DEFAULT_TOPIC_CAPACITY: usize = 64;
+
+/// An update delivered to a subscriber
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HubEvent {
+    /// A published HTML fragment
+    Fragment(Arc<str>),
+
+    /// The subscriber fell behind and missed fragments; re-render from scratch
+    Resync,
+}
+
+/// Registry of topic-keyed broadcast channels
+///
+/// Cheap to clone; clones share the same topics.
+#[derive(Debug, Clone)]
+pub struct RealtimeHub {
+    inner: Arc<HubInner>,
+}
+
+#[derive(Debug)]
+struct HubInner {
+    topics: Mutex<HashMap<String, broadcast::Sender<Arc<str>>>>,
+    capacity: usize,
+}
+
+impl Default for RealtimeHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RealtimeHub {
+    /// Create a hub buffering [`DEFAULT_TOPIC_CAPACITY`] fragments per topic
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_TOPIC_CAPACITY)
+    }
+
+    /// Create a hub buffering `capacity` fragments per topic
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0, "topic capacity must be greater than zero");
+        Self {
+            inner: Arc::new(HubInner {
+                topics: Mutex::new(HashMap::new()),
+                capacity,
+            }),
+        }
+    }
+
+    /// Conventional topic name for pushes to a single user
+    #[must_use]
+    pub fn user_topic(user_id: i64) -> String {
+        format!("user:{user_id}")
+    }
+
+    /// Publish an HTML fragment to every subscriber of `topic`
+    ///
+    /// Returns the number of subscribers the fragment was delivered to.
+    /// Publishing to a topic without subscribers is a no-op.
+    pub fn publish(&self, topic: &str, fragment: impl Into<Arc<str>>) -> usize {
+        let Some(sender) = self.inner.topics.lock().get(topic).cloned() else {
+            return 0;
+        };
+
+        sender.send(fragment.into()).unwrap_or(0)
+    }
+
+    /// Subscribe to `topic`, creating it if needed
+    #[must_use]
+    pub fn subscribe(&self, topic: &str) -> Subscription {
+        let receiver = self
+            .inner
+            .topics
+            .lock()
+            .entry(topic.to_string())
+            .or_insert_with(|| broadcast::channel(self.inner.capacity).0)
+            .subscribe();
+
+        Subscription {
+            topic: topic.to_string(),
+            receiver: Some(receiver),
+            hub: self.clone(),
+        }
+    }
+
+    /// Number of subscribers of `topic`
+    #[must_use]
+    pub fn subscriber_count(&self, topic: &str) -> usize {
+        self.inner
+            .topics
+            .lock()
+            .get(topic)
+            .map_or(0, broadcast::Sender::receiver_count)
+    }
+
+    /// Number of topics with at least one subscriber
+    #[must_use]
+    pub fn topic_count(&self) -> usize {
+        self.inner.topics.lock().len()
+    }
+
+    /// Remove `topic` if its last subscriber is gone
+    fn release(&self, topic: &str) {
+        let mut topics = self.inner.topics.lock();
+        if topics
+            .get(topic)
+            .is_some_and(|sender| sender.receiver_count() == 0)
+        {
+            topics.remove(topic);
+        }
+    }
+}
+
+/// A connection's subscription to one topic
+///
+/// Dropping the subscription unsubscribes; the topic is removed from the hub
+/// when its last subscription is dropped.
+#[derive(Debug)]
+pub struct Subscription {
+    topic: String,
+    receiver: Option<broadcast::Receiver<Arc<str>>>,
+    hub: RealtimeHub,
+}
+
+impl Subscription {
+    /// The subscribed topic
+    #[must_use]
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Wait for the next update
+    ///
+    /// Returns `None` once the topic has been closed. A subscriber that
+    /// lagged behind receives [`HubEvent::Resync`] and then continues with
+    /// the oldest fragment still buffered.
+    pub async fn recv(&mut self) -> Option<HubEvent> {
+        let receiver = self.receiver.as_mut()?;
+        match receiver.recv().await {
+            Ok(fragment) => Some(HubEvent::Fragment(fragment)),
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(topic = %self.topic, skipped, "Real-time subscriber lagged");
+                Some(HubEvent::Resync)
+            }
+            Err(RecvError::Closed) => None,
+        }
+    }
+
+    /// Convert into a stream of updates
+    pub fn into_stream(self) -> impl Stream<Item = HubEvent> {
+        stream::unfold(self, |mut subscription| async move {
+            let event = subscription.recv().await?;
+            Some((event, subscription))
+        })
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        // Drop the receiver first so the count no longer includes it
+        self.receiver.take();
+        self.hub.release(&self.topic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    #[tokio::test]
+    async fn test_publish_reaches_topic_subscribers_only() {
+        let hub = RealtimeHub::new();
+        let mut alice = hub.subscribe("user:1");
+        let mut alice_tab = hub.subscribe("user:1");
+        let mut bob = hub.subscribe("user:2");
+
+        assert_eq!(hub.publish("user:1", "<p>hi</p>"), 2);
+        assert_eq!(alice.recv().await, Some(HubEvent::Fragment("<p>hi</p>".into())));
+        assert_eq!(alice_tab.recv().await, Some(HubEvent::Fragment("<p>hi</p>".into())));
+
+        hub.publish("user:2", "<p>bob</p>");
+        assert_eq!(bob.recv().await, Some(HubEvent::Fragment("<p>bob</p>".into())));
+    }
+
+    #[test]
+    fn test_empty_topics_are_removed() {
+        let hub = RealtimeHub::new();
+        assert_eq!(hub.publish("dashboard", "<p>nobody</p>"), 0);
+        assert_eq!(hub.topic_count(), 0);
+
+        let first = hub.subscribe("dashboard");
+        let second = hub.subscribe("dashboard");
+        assert_eq!(hub.subscriber_count("dashboard"), 2);
+
+        drop(first);
+        assert_eq!(hub.topic_count(), 1);
+        drop(second);
+        assert_eq!(hub.topic_count(), 0);
+        assert_eq!(hub.subscriber_count("dashboard"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_gets_resync() {
+        let hub = RealtimeHub::with_capacity(2);
+        let subscription = hub.subscribe("feed");
+
+        for i in 0..5 {
+            hub.publish("feed", format!("<p>{i}</p>"));
+        }
+
+        let events: Vec<_> = subscription.into_stream().take(3).collect().await;
+        assert_eq!(
+            events,
+            vec![
+                HubEvent::Resync,
+                HubEvent::Fragment("<p>3</p>".into()),
+                HubEvent::Fragment("<p>4</p>".into()),
+            ]
+        );
+    }
+}
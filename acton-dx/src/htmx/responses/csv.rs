@@ -0,0 +1,281 @@
+//! CSV export responses
+//!
+//! [`Csv`] streams rows implementing `Serialize` as an RFC 4180 CSV
+//! attachment. The header row is taken from the struct's field names.
+//!
+//! An "Export" button wired with `hx-get` would swap the file into the page,
+//! so [`CsvDownload`] answers HTMX requests with `HX-Redirect` to the download
+//! URL instead; the browser then fetches it as a regular download.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::responses::{Csv, CsvDownload, HxRequest};
+//!
+//! async fn export_users(
+//!     HxRequest(is_htmx): HxRequest,
+//!     State(state): State<ActonHtmxState>,
+//! ) -> Result<CsvDownload<Vec<UserRow>>, AppError> {
+//!     if is_htmx {
+//!         return Ok(CsvDownload::redirect("/admin/users/export"));
+//!     }
+//!     let users = UserRow::all(state.database_pool()).await?;
+//!     Ok(CsvDownload::file(Csv::new("users.csv", users)))
+//! }
+//! ```
+
+use axum::{
+    body::{Body, Bytes},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+/// Streaming CSV attachment response
+///
+/// Each row is serialized and sent as it is pulled from the iterator, so
+/// large exports are never held in memory. An empty iterator produces an
+/// empty body (no header row), because headers come from the first record.
+#[derive(Debug, Clone)]
+pub struct Csv<I> {
+    filename: String,
+    rows: I,
+}
+
+impl<I> Csv<I> {
+    /// Create a CSV attachment named `filename`
+    #[must_use]
+    pub fn new(filename: impl Into<String>, rows: I) -> Self {
+        Self {
+            filename: filename.into(),
+            rows,
+        }
+    }
+
+    /// Attachment filename
+    #[must_use]
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+}
+
+impl<I, T> IntoResponse for Csv<I>
+where
+    I: IntoIterator<Item = T>,
+    I::IntoIter: Send + 'static,
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        let Ok(disposition) = HeaderValue::from_str(&content_disposition(&self.filename)) else {
+            tracing::error!(filename = %self.filename, "Invalid CSV filename");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        };
+
+        // One chunk per row; only the first row writes the header
+        let mut first = true;
+        let chunks = self.rows.into_iter().map(move |row| {
+            let mut writer = ::csv::WriterBuilder::new()
+                .has_headers(std::mem::take(&mut first))
+                .terminator(::csv::Terminator::CRLF)
+                .from_writer(Vec::new());
+            writer.serialize(row)?;
+            let chunk = writer.into_inner().map_err(::csv::IntoInnerError::into_error)?;
+            Ok::<_, ::csv::Error>(Bytes::from(chunk))
+        });
+
+        (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static("text/csv; charset=utf-8"),
+                ),
+                (header::CONTENT_DISPOSITION, disposition),
+            ],
+            Body::from_stream(futures_util::stream::iter(chunks)),
+        )
+            .into_response()
+    }
+}
+
+/// CSV export that redirects HTMX requests to the download URL
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Clone)]
+pub enum CsvDownload<I> {
+    /// Send the CSV file
+    File(Csv<I>),
+    /// Respond with `HX-Redirect` to this URL
+    Redirect(String),
+}
+
+impl<I> CsvDownload<I> {
+    /// Send `csv` as the response
+    #[must_use]
+    pub const fn file(csv: Csv<I>) -> Self {
+        Self::File(csv)
+    }
+
+    /// Ask HTMX to navigate to `download_url`
+    #[must_use]
+    pub fn redirect(download_url: impl Into<String>) -> Self {
+        Self::Redirect(download_url.into())
+    }
+
+    /// Redirect HTMX requests, otherwise build and send the CSV
+    ///
+    /// `csv` is only called for non-HTMX requests, so rows are not loaded
+    /// for the redirect.
+    #[must_use]
+    pub fn new(
+        is_htmx: bool,
+        download_url: impl Into<String>,
+        csv: impl FnOnce() -> Csv<I>,
+    ) -> Self {
+        if is_htmx {
+            Self::redirect(download_url)
+        } else {
+            Self::File(csv())
+        }
+    }
+}
+
+impl<I, T> IntoResponse for CsvDownload<I>
+where
+    I: IntoIterator<Item = T>,
+    I::IntoIter: Send + 'static,
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        match self {
+            Self::File(csv) => csv.into_response(),
+            Self::Redirect(url) => {
+                let Ok(location) = HeaderValue::from_str(&url) else {
+                    tracing::error!(url = %url, "Invalid CSV download URL");
+                    return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                };
+                (StatusCode::OK, [("HX-Redirect", location)]).into_response()
+            }
+        }
+    }
+}
+
+/// `Content-Disposition` value for an attachment named `filename`
+///
+/// Quotes and backslashes are escaped and control characters dropped so the
+/// filename cannot break out of the quoted string.
+fn content_disposition(filename: &str) -> String {
+    let mut escaped = String::with_capacity(filename.len());
+    for c in filename.chars().filter(|c| !c.is_control()) {
+        if matches!(c, '"' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    format!("attachment; filename=\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: &'static str,
+        note: &'static str,
+        count: u32,
+    }
+
+    async fn body_string(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_csv_header_and_quoting() {
+        let rows = vec![
+            Row {
+                name: "plain",
+                note: "no quoting",
+                count: 1,
+            },
+            Row {
+                name: "Smith, Jane",
+                note: "said \"hi\"\nthen left",
+                count: 2,
+            },
+        ];
+        let response = Csv::new("people.csv", rows).into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/csv; charset=utf-8"
+        );
+        assert_eq!(
+            body_string(response).await,
+            "name,note,count\r\n\
+             plain,no quoting,1\r\n\
+             \"Smith, Jane\",\"said \"\"hi\"\"\nthen left\",2\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csv_streams_lazy_iterator() {
+        let rows = (0..3).map(|i| Row {
+            name: "row",
+            note: "",
+            count: i,
+        });
+        let body = body_string(Csv::new("rows.csv", rows).into_response()).await;
+
+        assert_eq!(body.lines().count(), 4);
+        assert!(body.ends_with("row,,2\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_csv_empty() {
+        let body = body_string(Csv::new("empty.csv", Vec::<Row>::new()).into_response()).await;
+        assert!(body.is_empty());
+    }
+
+    #[test]
+    fn test_content_disposition_header() {
+        let response = Csv::new("report.csv", Vec::<Row>::new()).into_response();
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"report.csv\""
+        );
+
+        assert_eq!(
+            content_disposition("a\"b\\c\r\n.csv"),
+            "attachment; filename=\"a\\\"b\\\\c.csv\""
+        );
+    }
+
+    #[test]
+    fn test_csv_download_redirects_htmx_requests() {
+        let response = CsvDownload::new(true, "/export.csv", || -> Csv<Vec<Row>> {
+            unreachable!("rows are not built for HTMX requests")
+        })
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["HX-Redirect"], "/export.csv");
+        assert!(response.headers().get(header::CONTENT_DISPOSITION).is_none());
+    }
+
+    #[test]
+    fn test_csv_download_sends_file_otherwise() {
+        let response =
+            CsvDownload::new(false, "/export.csv", || Csv::new("export.csv", Vec::<Row>::new()))
+                .into_response();
+
+        assert!(response.headers().get("HX-Redirect").is_none());
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"export.csv\""
+        );
+    }
+}
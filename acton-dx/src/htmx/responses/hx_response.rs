@@ -0,0 +1,267 @@
+//! Composable HTMX responses
+//!
+//! [`HxResponse`] collects a status, an optional HTML body, and any number
+//! of `HX-*` response headers, so handlers don't have to stack several
+//! `axum-htmx` response types in a tuple:
+//!
+//! ```rust
+//! use acton_dx::htmx::responses::{HxResponse, SwapStrategy};
+//!
+//! async fn update_row() -> HxResponse {
+//!     HxResponse::html(r#"<tr id="row-5">...</tr>"#)
+//!         .trigger("saved")
+//!         .retarget("#row-5")
+//!         .reswap(SwapStrategy::OuterHTML)
+//! }
+//! ```
+//!
+//! Events are collected into [`HxTriggers`], so several events share one
+//! `HX-Trigger` header.
+
+use super::{HxTriggerEvent, HxTriggers, SwapStrategy};
+use axum::{
+    http::{HeaderName, HeaderValue, StatusCode},
+    response::{Html, IntoResponse, Response},
+};
+use serde_json::Value;
+
+/// HTMX response built from a body and `HX-*` headers
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct HxResponse {
+    status: StatusCode,
+    body: Option<String>,
+    headers: Vec<(&'static str, String)>,
+    triggers: HxTriggers,
+    after_swap: HxTriggers,
+    after_settle: HxTriggers,
+}
+
+impl Default for HxResponse {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl HxResponse {
+    /// `200 OK` with no body
+    pub const fn empty() -> Self {
+        Self {
+            status: StatusCode::OK,
+            body: None,
+            headers: Vec::new(),
+            triggers: HxTriggers::new(),
+            after_swap: HxTriggers::after_swap(),
+            after_settle: HxTriggers::after_settle(),
+        }
+    }
+
+    /// `200 OK` with an HTML body
+    pub fn html(body: impl Into<String>) -> Self {
+        Self {
+            body: Some(body.into()),
+            ..Self::empty()
+        }
+    }
+
+    /// Full-page redirect to `url` (`HX-Redirect`)
+    pub fn redirect(url: impl Into<String>) -> Self {
+        Self::empty().header("hx-redirect", url.into())
+    }
+
+    /// Client-side navigation to `url` without a full reload (`HX-Location`)
+    pub fn location(url: impl Into<String>) -> Self {
+        Self::empty().header("hx-location", url.into())
+    }
+
+    /// Full page refresh (`HX-Refresh`)
+    pub fn refresh() -> Self {
+        Self::empty().header("hx-refresh", "true".to_string())
+    }
+
+    /// Set the status code
+    pub const fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Swap the body into `selector` instead of the request's target
+    /// (`HX-Retarget`)
+    pub fn retarget(self, selector: impl Into<String>) -> Self {
+        self.header("hx-retarget", selector.into())
+    }
+
+    /// Swap the body with `strategy` (`HX-Reswap`)
+    pub fn reswap(self, strategy: SwapStrategy) -> Self {
+        self.header("hx-reswap", strategy.as_str().to_string())
+    }
+
+    /// Swap only the part of the body matching `selector` (`HX-Reselect`)
+    pub fn reselect(self, selector: impl Into<String>) -> Self {
+        self.header("hx-reselect", selector.into())
+    }
+
+    /// Push `url` onto the browser history (`HX-Push-Url`)
+    pub fn push_url(self, url: impl Into<String>) -> Self {
+        self.header("hx-push-url", url.into())
+    }
+
+    /// Replace the current URL in the location bar (`HX-Replace-Url`)
+    pub fn replace_url(self, url: impl Into<String>) -> Self {
+        self.header("hx-replace-url", url.into())
+    }
+
+    /// Fire `event` on the client when the response arrives (`HX-Trigger`)
+    pub fn trigger(self, event: impl Into<String>) -> Self {
+        self.trigger_event(HxTriggerEvent::name(event))
+    }
+
+    /// Fire `event` with a detail payload when the response arrives
+    pub fn trigger_with(self, event: impl Into<String>, detail: Value) -> Self {
+        self.trigger_event(HxTriggerEvent::name(event).detail(detail))
+    }
+
+    /// Fire a typed event when the response arrives
+    pub fn trigger_event(mut self, event: HxTriggerEvent) -> Self {
+        self.triggers.push(event);
+        self
+    }
+
+    /// Fire `event` after the swap (`HX-Trigger-After-Swap`)
+    pub fn trigger_after_swap(mut self, event: impl Into<String>) -> Self {
+        self.after_swap.push(HxTriggerEvent::name(event));
+        self
+    }
+
+    /// Fire `event` after the settle step (`HX-Trigger-After-Settle`)
+    pub fn trigger_after_settle(mut self, event: impl Into<String>) -> Self {
+        self.after_settle.push(HxTriggerEvent::name(event));
+        self
+    }
+
+    /// Set a header, replacing an earlier value for the same header
+    fn header(mut self, name: &'static str, value: String) -> Self {
+        self.headers.retain(|(existing, _)| *existing != name);
+        self.headers.push((name, value));
+        self
+    }
+}
+
+impl IntoResponse for HxResponse {
+    fn into_response(self) -> Response {
+        let mut headers = Vec::new();
+        for (name, value) in self.headers {
+            let Ok(value) = HeaderValue::from_str(&value) else {
+                tracing::error!(header = name, %value, "Invalid HTMX response header value");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            };
+            headers.push((HeaderName::from_static(name), value));
+        }
+
+        let triggers = (self.triggers, self.after_swap, self.after_settle);
+        let mut response = match self.body {
+            Some(body) => (self.status, triggers, Html(body)).into_response(),
+            None => (self.status, triggers).into_response(),
+        };
+        response.headers_mut().extend(headers);
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header;
+
+    fn header<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+        response
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_html_with_trigger_retarget_and_reswap() {
+        let response = HxResponse::html("<tr id=\"row-5\"></tr>")
+            .trigger("saved")
+            .retarget("#row-5")
+            .reswap(SwapStrategy::OuterHTML)
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(header(&response, "HX-Trigger"), Some("saved"));
+        assert_eq!(header(&response, "HX-Retarget"), Some("#row-5"));
+        assert_eq!(header(&response, "HX-Reswap"), Some("outerHTML"));
+        assert!(header(&response, header::CONTENT_TYPE.as_str())
+            .unwrap()
+            .starts_with("text/html"));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, "<tr id=\"row-5\"></tr>");
+    }
+
+    #[test]
+    fn test_multiple_triggers() {
+        let response = HxResponse::empty()
+            .trigger("saved")
+            .trigger("refresh-list")
+            .trigger_after_settle("focus-input")
+            .into_response();
+
+        assert_eq!(header(&response, "HX-Trigger"), Some("saved, refresh-list"));
+        assert_eq!(
+            header(&response, "HX-Trigger-After-Settle"),
+            Some("focus-input")
+        );
+        assert!(header(&response, "HX-Trigger-After-Swap").is_none());
+    }
+
+    #[test]
+    fn test_trigger_with_detail_uses_json() {
+        let response = HxResponse::empty()
+            .trigger("saved")
+            .trigger_with("toast", serde_json::json!({ "message": "Saved" }))
+            .into_response();
+
+        let value: Value = serde_json::from_str(header(&response, "HX-Trigger").unwrap()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "saved": null, "toast": { "message": "Saved" } })
+        );
+    }
+
+    #[test]
+    fn test_redirect_and_refresh() {
+        let response = HxResponse::redirect("/login").into_response();
+        assert_eq!(header(&response, "HX-Redirect"), Some("/login"));
+        assert!(header(&response, header::CONTENT_TYPE.as_str()).is_none());
+
+        let response = HxResponse::refresh().into_response();
+        assert_eq!(header(&response, "HX-Refresh"), Some("true"));
+    }
+
+    #[test]
+    fn test_status_and_url_headers() {
+        let response = HxResponse::html("<p>Created</p>")
+            .status(StatusCode::CREATED)
+            .push_url("/items/1")
+            .reselect("#content")
+            .retarget("#a")
+            .retarget("#b")
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(header(&response, "HX-Push-Url"), Some("/items/1"));
+        assert_eq!(header(&response, "HX-Reselect"), Some("#content"));
+        assert_eq!(header(&response, "HX-Retarget"), Some("#b"));
+        assert_eq!(response.headers().get_all("HX-Retarget").iter().count(), 1);
+    }
+
+    #[test]
+    fn test_invalid_header_value_is_server_error() {
+        let response = HxResponse::redirect("/bad\nurl").into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -3,7 +3,11 @@
 //! This module builds on `axum-htmx` with additional features:
 //! - Out-of-band swaps (`HxSwapOob`)
 //! - Automatic template detection (`HxTemplate`)
-//! - Smart response enum (`HxResponse`)
+//! - Composable response builder ([`HxResponse`])
+//! - Typed `HX-Trigger` events with JSON details ([`HxTriggers`])
+//! - Post/Redirect/Get with flash messages ([`prg`])
+//! - Server-sent events for the HTMX SSE extension ([`HxSse`])
+//! - Streaming CSV exports ([`Csv`], [`CsvDownload`])
 //!
 //! # Re-exported from axum-htmx
 //!
@@ -51,5 +55,15 @@ pub use axum_htmx::{
 pub use axum_htmx::{AutoVaryLayer, HxRequestGuardLayer};
 
 // acton-dx extensions
+mod csv;
+mod hx_response;
+mod prg;
+mod sse;
 mod swap_oob;
+mod trigger;
+pub use csv::{Csv, CsvDownload};
+pub use hx_response::HxResponse;
+pub use prg::{prg, PostRedirectGet};
+pub use sse::{HxSse, HxSseEvent, DEFAULT_KEEP_ALIVE_INTERVAL};
 pub use swap_oob::{HxSwapOob, SwapStrategy};
+pub use trigger::{HxTriggerError, HxTriggerEvent, HxTriggerTiming, HxTriggers};
@@ -0,0 +1,125 @@
+//! Post/Redirect/Get helper
+//!
+//! [`prg`] adds a flash message to the session and redirects in one call.
+//! The returned [`PostRedirectGet`] response:
+//! - Carries the updated session data so `SessionMiddleware` persists it
+//! - Asks the middleware to confirm the session write before the response
+//!   is sent ([`ConfirmSessionSave`]), so the flash is visible on the page
+//!   the browser is redirected to
+//! - Uses `HX-Redirect` for HTMX requests (including boosted forms) and
+//!   `303 See Other` otherwise, so the follow-up request is always a `GET`
+
+use crate::htmx::auth::session::{FlashMessage, SessionData};
+use crate::htmx::auth::Session;
+use crate::htmx::middleware::ConfirmSessionSave;
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+/// Redirect after a successful form submission, keeping session changes
+///
+/// Build with [`prg`] or [`PostRedirectGet::new`].
+#[derive(Debug, Clone)]
+pub struct PostRedirectGet {
+    location: String,
+    session_data: SessionData,
+    htmx: bool,
+}
+
+impl PostRedirectGet {
+    /// Redirect to `location`, persisting the current state of `session`
+    #[must_use]
+    pub fn new(session: &Session, location: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            session_data: session.data().clone(),
+            htmx: session.is_htmx_request(),
+        }
+    }
+
+    /// Redirect target
+    #[must_use]
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+}
+
+impl IntoResponse for PostRedirectGet {
+    fn into_response(self) -> Response {
+        let Ok(location) = HeaderValue::from_str(&self.location) else {
+            tracing::error!(location = %self.location, "Invalid redirect location");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        };
+
+        let mut response = if self.htmx {
+            (StatusCode::OK, [("HX-Redirect", location)]).into_response()
+        } else {
+            (StatusCode::SEE_OTHER, [(header::LOCATION, location)]).into_response()
+        };
+
+        response.extensions_mut().insert(self.session_data);
+        response.extensions_mut().insert(ConfirmSessionSave);
+        response
+    }
+}
+
+/// Add a flash message and redirect (Post/Redirect/Get)
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use acton_dx::htmx::auth::{FlashMessage, Session};
+/// use acton_dx::htmx::responses::{prg, PostRedirectGet};
+///
+/// async fn update_profile(mut session: Session) -> PostRedirectGet {
+///     // ... save the profile ...
+///     prg(&mut session, "/dashboard", FlashMessage::success("Saved!"))
+/// }
+/// ```
+#[must_use]
+pub fn prg(
+    session: &mut Session,
+    location: impl Into<String>,
+    flash: FlashMessage,
+) -> PostRedirectGet {
+    session.add_flash(flash);
+    PostRedirectGet::new(session, location)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::htmx::auth::session::SessionId;
+
+    fn session(htmx: bool) -> Session {
+        Session::new(SessionId::generate(), SessionData::new()).with_htmx_request(htmx)
+    }
+
+    #[test]
+    fn test_prg_regular_request_uses_see_other() {
+        let mut session = session(false);
+        let response = prg(&mut session, "/dashboard", FlashMessage::success("Saved!"))
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(response.headers().get(header::LOCATION).unwrap(), "/dashboard");
+        assert!(response.extensions().get::<ConfirmSessionSave>().is_some());
+
+        let data = response.extensions().get::<SessionData>().unwrap();
+        assert_eq!(data.flash_messages.len(), 1);
+        assert_eq!(data.flash_messages[0].message, "Saved!");
+    }
+
+    #[test]
+    fn test_prg_htmx_request_uses_hx_redirect() {
+        let mut session = session(true);
+        let response =
+            prg(&mut session, "/dashboard", FlashMessage::info("Done")).into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get("HX-Redirect").unwrap(), "/dashboard");
+        assert!(response.headers().get(header::LOCATION).is_none());
+        assert!(response.extensions().get::<SessionData>().is_some());
+    }
+}
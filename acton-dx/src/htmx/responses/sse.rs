@@ -0,0 +1,264 @@
+//! Server-sent events for the HTMX SSE extension
+//!
+//! Provides [`HxSse`], a response that streams [`HxSseEvent`]s as
+//! `text/event-stream`, for use with the
+//! [HTMX SSE extension](https://htmx.org/extensions/sse/):
+//!
+//! ```html
+//! <div hx-ext="sse" sse-connect="/notifications" sse-swap="notification"></div>
+//! ```
+//!
+//! Each event carries rendered HTML that HTMX swaps into the elements whose
+//! `sse-swap` matches the event name. Idle connections receive periodic
+//! keep-alive comments so proxies don't close them.
+
+use axum::{
+    http::{header::HeaderName, HeaderValue},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use futures_util::{Stream, StreamExt};
+use std::convert::Infallible;
+use std::time::Duration;
+
+/// Default interval between keep-alive comments
+pub const DEFAULT_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Text of keep-alive comments (`: keep-alive`)
+const KEEP_ALIVE_TEXT: &str = "keep-alive";
+
+/// A single server-sent event carrying an HTML fragment
+///
+/// Without an [`event`](Self::event) name, browsers dispatch the event as
+/// `message`, which matches `sse-swap="message"`.
+///
+/// # Examples
+///
+/// ```rust
+/// use acton_dx::htmx::responses::HxSseEvent;
+/// use std::time::Duration;
+///
+/// let event = HxSseEvent::new("<li>New comment</li>")
+///     .event("comment")
+///     .id("42")
+///     .retry(Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HxSseEvent {
+    html: String,
+    event: Option<String>,
+    id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl HxSseEvent {
+    /// Create an event carrying rendered HTML
+    ///
+    /// Multi-line HTML is split across several `data:` lines, which clients
+    /// join back together with `\n`.
+    #[must_use]
+    pub fn new(html: impl Into<String>) -> Self {
+        Self {
+            html: html.into(),
+            event: None,
+            id: None,
+            retry: None,
+        }
+    }
+
+    /// Create an event from a rendered Askama template
+    ///
+    /// # Errors
+    ///
+    /// Returns the template's rendering error
+    pub fn from_template<T: askama::Template>(template: &T) -> askama::Result<Self> {
+        template.render().map(Self::new)
+    }
+
+    /// Set the event name matched by `sse-swap`
+    ///
+    /// Line breaks are removed, since they would end the field.
+    #[must_use]
+    pub fn event(mut self, name: impl AsRef<str>) -> Self {
+        self.event = Some(single_line(name.as_ref()));
+        self
+    }
+
+    /// Set the event ID, sent back by browsers as `Last-Event-ID` on reconnect
+    ///
+    /// Line breaks and NUL characters are removed.
+    #[must_use]
+    pub fn id(mut self, id: impl AsRef<str>) -> Self {
+        self.id = Some(single_line(id.as_ref()));
+        self
+    }
+
+    /// Set how long clients wait before reconnecting after a disconnect
+    #[must_use]
+    pub const fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// The HTML payload
+    #[must_use]
+    pub fn html(&self) -> &str {
+        &self.html
+    }
+}
+
+impl From<HxSseEvent> for Event {
+    fn from(value: HxSseEvent) -> Self {
+        let mut event = Self::default();
+        if let Some(name) = value.event {
+            event = event.event(name);
+        }
+        if let Some(id) = value.id {
+            event = event.id(id);
+        }
+        if let Some(retry) = value.retry {
+            event = event.retry(retry);
+        }
+        // Normalize line endings so CRLF doesn't produce empty data lines
+        event.data(value.html.replace("\r\n", "\n").replace('\r', "\n"))
+    }
+}
+
+/// Server-sent event stream response
+///
+/// Sets `Content-Type: text/event-stream`, disables caching and proxy
+/// buffering, and sends a `: keep-alive` comment whenever the stream has been
+/// idle for [`DEFAULT_KEEP_ALIVE_INTERVAL`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use acton_dx::htmx::responses::{HxSse, HxSseEvent};
+/// use futures_util::stream;
+/// use std::time::Duration;
+///
+/// async fn clock() -> HxSse<impl futures_util::Stream<Item = HxSseEvent>> {
+///     let ticks = stream::unfold(tokio::time::interval(Duration::from_secs(1)), |mut interval| async {
+///         interval.tick().await;
+///         let html = format!("<span>{:?}</span>", std::time::SystemTime::now());
+///         Some((HxSseEvent::new(html).event("tick"), interval))
+///     });
+///     HxSse::new(ticks)
+/// }
+/// ```
+#[derive(Debug)]
+pub struct HxSse<S> {
+    stream: S,
+    keep_alive: Option<Duration>,
+}
+
+impl<S> HxSse<S>
+where
+    S: Stream<Item = HxSseEvent> + Send + 'static,
+{
+    /// Stream `events` with the default keep-alive interval
+    #[must_use]
+    pub const fn new(events: S) -> Self {
+        Self {
+            stream: events,
+            keep_alive: Some(DEFAULT_KEEP_ALIVE_INTERVAL),
+        }
+    }
+
+    /// Set the interval between keep-alive comments
+    #[must_use]
+    pub const fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = Some(interval);
+        self
+    }
+
+    /// Disable keep-alive comments
+    #[must_use]
+    pub const fn without_keep_alive(mut self) -> Self {
+        self.keep_alive = None;
+        self
+    }
+}
+
+impl<S> IntoResponse for HxSse<S>
+where
+    S: Stream<Item = HxSseEvent> + Send + 'static,
+{
+    fn into_response(self) -> Response {
+        let events = self.stream.map(|event| Ok::<_, Infallible>(Event::from(event)));
+
+        let mut response = match self.keep_alive {
+            Some(interval) => Sse::new(events)
+                .keep_alive(KeepAlive::new().interval(interval).text(KEEP_ALIVE_TEXT))
+                .into_response(),
+            None => Sse::new(events).into_response(),
+        };
+
+        // Stop nginx from buffering the stream
+        response.headers_mut().insert(
+            HeaderName::from_static("x-accel-buffering"),
+            HeaderValue::from_static("no"),
+        );
+        response
+    }
+}
+
+/// Strip characters that would break a single-line SSE field
+fn single_line(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '\r' | '\n' | '\0'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::{CACHE_CONTROL, CONTENT_TYPE};
+    use futures_util::stream;
+
+    #[tokio::test]
+    async fn test_events_wire_format() {
+        let events = stream::iter([
+            HxSseEvent::new("<p>Hello</p>")
+                .event("greeting")
+                .id("1")
+                .retry(Duration::from_secs(3)),
+            HxSseEvent::new("<ul>\n<li>a</li>\r\n</ul>"),
+        ]);
+
+        let response = HxSse::new(events).into_response();
+        assert_eq!(response.headers().get(CONTENT_TYPE).unwrap(), "text/event-stream");
+        assert_eq!(response.headers().get(CACHE_CONTROL).unwrap(), "no-cache");
+        assert_eq!(response.headers().get("x-accel-buffering").unwrap(), "no");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            "event: greeting\nid: 1\nretry: 3000\ndata: <p>Hello</p>\n\n\
+             data: <ul>\ndata: <li>a</li>\ndata: </ul>\n\n"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_keep_alive_comment() {
+        let response = HxSse::new(stream::pending::<HxSseEvent>())
+            .keep_alive(Duration::from_secs(5))
+            .into_response();
+
+        let mut body = response.into_body().into_data_stream();
+        let frame = body.next().await.unwrap().unwrap();
+        assert_eq!(&frame[..], b": keep-alive\n\n");
+    }
+
+    #[test]
+    fn test_fields_are_single_line() {
+        let event = HxSseEvent::new("x").event("up\ndate").id("4\r\n2\0");
+        assert_eq!(event.event.as_deref(), Some("update"));
+        assert_eq!(event.id.as_deref(), Some("42"));
+    }
+}
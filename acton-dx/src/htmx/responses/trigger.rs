@@ -0,0 +1,288 @@
+//! Typed `HX-Trigger` events
+//!
+//! [`HxTriggers`] collects [`HxTriggerEvent`]s into a single trigger header.
+//! Events without details are sent as a comma-separated list; once any event
+//! carries a detail the whole header uses the JSON object form
+//! (`{"event": {...}}`), which HTMX passes to listeners as `event.detail`.
+//!
+//! ```rust
+//! use acton_dx::htmx::responses::{HxTriggerEvent, HxTriggers};
+//! use axum::response::Html;
+//! use serde_json::json;
+//!
+//! async fn save() -> (HxTriggers, Html<&'static str>) {
+//!     let triggers = HxTriggers::new()
+//!         .with(HxTriggerEvent::name("saved").detail(json!({ "id": 5 })))
+//!         .with(HxTriggerEvent::name("toast").detail(json!({ "message": "Saved" })));
+//!     (triggers, Html("<p>Saved</p>"))
+//! }
+//! ```
+//!
+//! Use [`HxTriggers::after_swap`] or [`HxTriggers::after_settle`] to fire
+//! the events later in the swap cycle.
+
+use axum::{
+    http::{HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// When HTMX fires the events of a trigger header
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HxTriggerTiming {
+    /// As soon as the response is received (`HX-Trigger`)
+    #[default]
+    Received,
+    /// After the new content is swapped in (`HX-Trigger-After-Swap`)
+    AfterSwap,
+    /// After the settle step (`HX-Trigger-After-Settle`)
+    AfterSettle,
+}
+
+impl HxTriggerTiming {
+    /// Response header carrying events with this timing
+    #[must_use]
+    pub const fn header_name(self) -> &'static str {
+        match self {
+            Self::Received => "hx-trigger",
+            Self::AfterSwap => "hx-trigger-after-swap",
+            Self::AfterSettle => "hx-trigger-after-settle",
+        }
+    }
+}
+
+/// Errors building a trigger header
+#[derive(Debug, Error)]
+pub enum HxTriggerError {
+    /// An event has an empty name
+    #[error("HX-Trigger event name must not be empty")]
+    EmptyName,
+
+    /// The serialized events are not a valid header value
+    #[error("Invalid HX-Trigger header value: {0}")]
+    InvalidHeaderValue(String),
+}
+
+impl IntoResponse for HxTriggerError {
+    fn into_response(self) -> Response {
+        tracing::error!(error = %self, "Failed to build HX-Trigger header");
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    }
+}
+
+/// Client-side event fired through a trigger header
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct HxTriggerEvent {
+    name: String,
+    detail: Option<Value>,
+}
+
+impl HxTriggerEvent {
+    /// Event called `name`
+    pub fn name(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            detail: None,
+        }
+    }
+
+    /// Attach `detail`, available to listeners as `event.detail`
+    pub fn detail(mut self, detail: Value) -> Self {
+        self.detail = Some(detail);
+        self
+    }
+
+    /// Event name
+    #[must_use]
+    pub fn event(&self) -> &str {
+        &self.name
+    }
+
+    /// Detail payload, if any
+    #[must_use]
+    pub const fn payload(&self) -> Option<&Value> {
+        self.detail.as_ref()
+    }
+}
+
+/// Events sent together in one trigger header
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct HxTriggers {
+    timing: HxTriggerTiming,
+    events: Vec<HxTriggerEvent>,
+}
+
+impl HxTriggers {
+    /// Events fired when the response is received (`HX-Trigger`)
+    pub const fn new() -> Self {
+        Self {
+            timing: HxTriggerTiming::Received,
+            events: Vec::new(),
+        }
+    }
+
+    /// Events fired after the swap (`HX-Trigger-After-Swap`)
+    pub const fn after_swap() -> Self {
+        Self {
+            timing: HxTriggerTiming::AfterSwap,
+            events: Vec::new(),
+        }
+    }
+
+    /// Events fired after the settle step (`HX-Trigger-After-Settle`)
+    pub const fn after_settle() -> Self {
+        Self {
+            timing: HxTriggerTiming::AfterSettle,
+            events: Vec::new(),
+        }
+    }
+
+    /// Add an event
+    pub fn with(mut self, event: HxTriggerEvent) -> Self {
+        self.push(event);
+        self
+    }
+
+    /// Add an event in place
+    pub fn push(&mut self, event: HxTriggerEvent) {
+        self.events.push(event);
+    }
+
+    /// When the events fire
+    #[must_use]
+    pub const fn timing(&self) -> HxTriggerTiming {
+        self.timing
+    }
+
+    /// Whether there are no events
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Serialized header value
+    ///
+    /// # Errors
+    ///
+    /// Returns [`HxTriggerError::EmptyName`] if an event name is empty, and
+    /// [`HxTriggerError::InvalidHeaderValue`] if the value contains
+    /// characters not allowed in headers.
+    pub fn header_value(&self) -> Result<HeaderValue, HxTriggerError> {
+        if self.events.iter().any(|event| event.name.trim().is_empty()) {
+            return Err(HxTriggerError::EmptyName);
+        }
+
+        let value = if self.events.iter().all(|event| event.detail.is_none()) {
+            let names: Vec<_> = self.events.iter().map(|event| event.name.as_str()).collect();
+            names.join(", ")
+        } else {
+            // Keeps insertion order: serde_json's `preserve_order` is enabled
+            let object: Map<String, Value> = self
+                .events
+                .iter()
+                .map(|event| {
+                    (
+                        event.name.clone(),
+                        event.detail.clone().unwrap_or(Value::Null),
+                    )
+                })
+                .collect();
+            Value::Object(object).to_string()
+        };
+
+        HeaderValue::from_str(&value).map_err(|_| HxTriggerError::InvalidHeaderValue(value))
+    }
+}
+
+impl IntoResponseParts for HxTriggers {
+    type Error = HxTriggerError;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if !self.is_empty() {
+            res.headers_mut().insert(
+                HeaderName::from_static(self.timing.header_name()),
+                self.header_value()?,
+            );
+        }
+        Ok(res)
+    }
+}
+
+impl IntoResponse for HxTriggers {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_two_events_with_details() {
+        let triggers = HxTriggers::new()
+            .with(HxTriggerEvent::name("saved").detail(json!({ "id": 5 })))
+            .with(HxTriggerEvent::name("toast").detail(json!({ "message": "Saved" })));
+
+        let value = triggers.header_value().unwrap();
+        assert_eq!(
+            value.to_str().unwrap(),
+            r#"{"saved":{"id":5},"toast":{"message":"Saved"}}"#
+        );
+
+        let response = triggers.into_response();
+        assert_eq!(response.headers()["HX-Trigger"], value);
+    }
+
+    #[test]
+    fn test_events_without_details_use_list_form() {
+        let triggers = HxTriggers::new()
+            .with(HxTriggerEvent::name("saved"))
+            .with(HxTriggerEvent::name("refresh"));
+        assert_eq!(triggers.header_value().unwrap(), "saved, refresh");
+
+        // One detail switches the whole header to the object form
+        let triggers = triggers.with(HxTriggerEvent::name("toast").detail(json!("Hi")));
+        assert_eq!(
+            triggers.header_value().unwrap(),
+            r#"{"saved":null,"refresh":null,"toast":"Hi"}"#
+        );
+    }
+
+    #[test]
+    fn test_timing_selects_header() {
+        let response = HxTriggers::after_settle()
+            .with(HxTriggerEvent::name("focus"))
+            .into_response();
+        assert_eq!(response.headers()["HX-Trigger-After-Settle"], "focus");
+        assert!(response.headers().get("HX-Trigger").is_none());
+
+        let response = HxTriggers::after_swap()
+            .with(HxTriggerEvent::name("highlight"))
+            .into_response();
+        assert_eq!(response.headers()["HX-Trigger-After-Swap"], "highlight");
+    }
+
+    #[test]
+    fn test_empty_name_is_rejected() {
+        let triggers = HxTriggers::new().with(HxTriggerEvent::name(" "));
+        assert!(matches!(
+            triggers.header_value(),
+            Err(HxTriggerError::EmptyName)
+        ));
+        assert_eq!(
+            triggers.into_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn test_empty_collection_sets_no_header() {
+        let response = HxTriggers::new().into_response();
+        assert!(response.headers().get("HX-Trigger").is_none());
+    }
+}
@@ -0,0 +1,451 @@
+//! Route introspection
+//!
+//! [`RouteBuilder`] wraps an axum [`Router`] and records each route it mounts
+//! (method, path, handler, access requirements) in a [`RouteRegistry`]. The
+//! registry renders the routes as a table, and
+//! [`dev_endpoint`](RouteRegistry::dev_endpoint) serves that table at
+//! [`ROUTES_ENDPOINT`] in debug builds, where `acton-dx htmx routes` reads it.
+//!
+//! Access requirements are documentation only; enforcement stays with the
+//! [`Authenticated`](crate::htmx::auth::Authenticated) extractor and the
+//! authorization middleware.
+//!
+//! # Example
+//!
+//! ```rust
+//! use acton_dx::htmx::routes::RouteBuilder;
+//!
+//! async fn index() -> &'static str { "Home" }
+//! async fn dashboard() -> &'static str { "Dashboard" }
+//! async fn list_users() -> &'static str { "Users" }
+//!
+//! let (router, routes) = RouteBuilder::<()>::new()
+//!     .get("/", index)
+//!     .get("/dashboard", dashboard)
+//!     .authenticated()
+//!     .get("/admin/users", list_users)
+//!     .roles(["admin"])
+//!     .into_parts();
+//!
+//! let app: axum::Router = router.merge(routes.dev_endpoint());
+//! assert_eq!(routes.len(), 3);
+//! ```
+
+use axum::{
+    handler::Handler,
+    http::Method,
+    routing::{self, MethodRouter},
+    Router,
+};
+use std::fmt;
+
+/// Path of the development route listing
+pub const ROUTES_ENDPOINT: &str = "/_routes";
+
+/// Access requirement recorded for a route
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RouteAccess {
+    /// Anyone may call the route
+    #[default]
+    Public,
+    /// A signed-in user is required
+    Authenticated,
+    /// A signed-in user with one of these roles is required
+    Roles(Vec<String>),
+}
+
+impl fmt::Display for RouteAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Public => f.write_str("public"),
+            Self::Authenticated => f.write_str("authenticated"),
+            Self::Roles(roles) => write!(f, "roles: {}", roles.join(", ")),
+        }
+    }
+}
+
+/// A mounted route
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteInfo {
+    /// HTTP method
+    pub method: Method,
+    /// Path pattern, including any nest prefix
+    pub path: String,
+    /// Handler name (the handler's type path unless overridden)
+    pub handler: String,
+    /// Access requirement
+    pub access: RouteAccess,
+}
+
+/// Routes recorded by a [`RouteBuilder`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteRegistry {
+    routes: Vec<RouteInfo>,
+}
+
+impl RouteRegistry {
+    /// Create an empty registry
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Record a route
+    pub fn register(&mut self, route: RouteInfo) {
+        self.routes.push(route);
+    }
+
+    /// Routes sorted by path, then method
+    #[must_use]
+    pub fn routes(&self) -> Vec<&RouteInfo> {
+        let mut routes: Vec<&RouteInfo> = self.routes.iter().collect();
+        routes.sort_by(|a, b| {
+            a.path
+                .cmp(&b.path)
+                .then_with(|| method_rank(&a.method).cmp(&method_rank(&b.method)))
+                .then_with(|| a.method.as_str().cmp(b.method.as_str()))
+        });
+        routes
+    }
+
+    /// Number of recorded routes
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Whether no routes are recorded
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Render the sorted routes as a plain-text table
+    #[must_use]
+    pub fn table(&self) -> String {
+        self.to_string()
+    }
+
+    /// Router serving [`table`](Self::table) at [`ROUTES_ENDPOINT`]
+    ///
+    /// Empty in release builds, so the listing never reaches production.
+    pub fn dev_endpoint<S>(&self) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        if !cfg!(debug_assertions) {
+            return Router::new();
+        }
+
+        let table = self.table();
+        Router::new().route(ROUTES_ENDPOINT, routing::get(|| async move { table }))
+    }
+}
+
+impl fmt::Display for RouteRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let routes = self.routes();
+        let headers = ["METHOD", "PATH", "HANDLER", "ACCESS"];
+        let rows: Vec<[String; 4]> = routes
+            .iter()
+            .map(|route| {
+                [
+                    route.method.to_string(),
+                    route.path.clone(),
+                    route.handler.clone(),
+                    route.access.to_string(),
+                ]
+            })
+            .collect();
+
+        let mut widths = headers.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut write_row = |cells: [&str; 4]| {
+            let line = cells
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())
+        };
+
+        write_row(headers)?;
+        for row in &rows {
+            write_row([&row[0], &row[1], &row[2], &row[3]])?;
+        }
+        Ok(())
+    }
+}
+
+/// Position of common methods in listings; others sort after them
+const fn method_rank(method: &Method) -> u8 {
+    match *method {
+        Method::GET => 0,
+        Method::POST => 1,
+        Method::PUT => 2,
+        Method::PATCH => 3,
+        Method::DELETE => 4,
+        _ => 5,
+    }
+}
+
+/// Router builder that records the routes it mounts
+///
+/// Each method registers one handler for one HTTP method; routes on the same
+/// path are merged by axum as usual. [`authenticated`](Self::authenticated),
+/// [`roles`](Self::roles), and [`named`](Self::named) annotate the route
+/// added last.
+#[derive(Debug)]
+pub struct RouteBuilder<S = ()> {
+    router: Router<S>,
+    registry: RouteRegistry,
+}
+
+impl<S> Default for RouteBuilder<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> RouteBuilder<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    /// Create an empty builder
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            router: Router::new(),
+            registry: RouteRegistry::new(),
+        }
+    }
+
+    /// Mount a `GET` handler
+    #[must_use]
+    pub fn get<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.method(Method::GET, path, routing::get(handler), handler_name::<H>())
+    }
+
+    /// Mount a `POST` handler
+    #[must_use]
+    pub fn post<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.method(Method::POST, path, routing::post(handler), handler_name::<H>())
+    }
+
+    /// Mount a `PUT` handler
+    #[must_use]
+    pub fn put<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.method(Method::PUT, path, routing::put(handler), handler_name::<H>())
+    }
+
+    /// Mount a `PATCH` handler
+    #[must_use]
+    pub fn patch<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.method(Method::PATCH, path, routing::patch(handler), handler_name::<H>())
+    }
+
+    /// Mount a `DELETE` handler
+    #[must_use]
+    pub fn delete<H, T>(self, path: &str, handler: H) -> Self
+    where
+        H: Handler<T, S>,
+        T: 'static,
+    {
+        self.method(Method::DELETE, path, routing::delete(handler), handler_name::<H>())
+    }
+
+    fn method(mut self, method: Method, path: &str, route: MethodRouter<S>, handler: String) -> Self {
+        self.router = self.router.route(path, route);
+        self.registry.register(RouteInfo {
+            method,
+            path: path.to_string(),
+            handler,
+            access: RouteAccess::Public,
+        });
+        self
+    }
+
+    /// Record that the last route requires a signed-in user
+    #[must_use]
+    pub fn authenticated(self) -> Self {
+        self.annotate(|route| route.access = RouteAccess::Authenticated)
+    }
+
+    /// Record that the last route requires one of `roles`
+    #[must_use]
+    pub fn roles<I, R>(self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<String>,
+    {
+        let roles = roles.into_iter().map(Into::into).collect();
+        self.annotate(|route| route.access = RouteAccess::Roles(roles))
+    }
+
+    /// Override the handler name recorded for the last route
+    #[must_use]
+    pub fn named(self, handler: impl Into<String>) -> Self {
+        let handler = handler.into();
+        self.annotate(|route| route.handler = handler)
+    }
+
+    fn annotate(mut self, update: impl FnOnce(&mut RouteInfo)) -> Self {
+        if let Some(route) = self.registry.routes.last_mut() {
+            update(route);
+        }
+        self
+    }
+
+    /// Mount `other` under `prefix`, recording its routes with the prefix
+    #[must_use]
+    pub fn nest(mut self, prefix: &str, other: Self) -> Self {
+        let prefix = prefix.trim_end_matches('/');
+        for mut route in other.registry.routes {
+            route.path = if route.path == "/" {
+                prefix.to_string()
+            } else {
+                format!("{prefix}{}", route.path)
+            };
+            self.registry.register(route);
+        }
+        self.router = self.router.nest(prefix, other.router);
+        self
+    }
+
+    /// Mount the routes of `other` alongside these
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.registry.routes.extend(other.registry.routes);
+        self.router = self.router.merge(other.router);
+        self
+    }
+
+    /// The recorded routes so far
+    #[must_use]
+    pub const fn registry(&self) -> &RouteRegistry {
+        &self.registry
+    }
+
+    /// Finish building, returning the router and its route registry
+    pub fn into_parts(self) -> (Router<S>, RouteRegistry) {
+        (self.router, self.registry)
+    }
+}
+
+/// Handler name from its type path (`my_app::handlers::posts::list`)
+fn handler_name<H>() -> String {
+    std::any::type_name::<H>().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Request};
+    use tower::ServiceExt;
+
+    async fn list_posts() -> &'static str {
+        "posts"
+    }
+
+    async fn create_post() -> &'static str {
+        "created"
+    }
+
+    #[test]
+    fn test_two_routes_sorted_listing() {
+        let (_, routes) = RouteBuilder::<()>::new()
+            .post("/posts", create_post)
+            .authenticated()
+            .get("/posts", list_posts)
+            .into_parts();
+
+        let listing: Vec<(String, &str, &str, String)> = routes
+            .routes()
+            .into_iter()
+            .map(|r| {
+                (
+                    r.method.to_string(),
+                    r.path.as_str(),
+                    r.handler.rsplit("::").next().unwrap(),
+                    r.access.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            listing,
+            [
+                ("GET".to_string(), "/posts", "list_posts", "public".to_string()),
+                ("POST".to_string(), "/posts", "create_post", "authenticated".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_nested_and_named_routes() {
+        let admin = RouteBuilder::<()>::new()
+            .get("/", list_posts)
+            .named("admin::index")
+            .roles(["admin", "editor"])
+            .delete("/posts/{id}", create_post)
+            .named("admin::delete_post");
+        let (_, routes) = RouteBuilder::<()>::new()
+            .get("/posts", list_posts)
+            .named("posts::list")
+            .nest("/admin", admin)
+            .into_parts();
+
+        assert_eq!(
+            routes.table(),
+            "METHOD  PATH               HANDLER             ACCESS\n\
+             GET     /admin             admin::index        roles: admin, editor\n\
+             DELETE  /admin/posts/{id}  admin::delete_post  public\n\
+             GET     /posts             posts::list         public\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_routes_are_mounted_and_listed() {
+        let (router, registry) = RouteBuilder::<()>::new()
+            .get("/posts", list_posts)
+            .post("/posts", create_post)
+            .into_parts();
+        let app = router.merge(registry.dev_endpoint());
+
+        let request = Request::post("/posts").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"created");
+
+        let request = Request::get(ROUTES_ENDPOINT).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        if cfg!(debug_assertions) {
+            assert_eq!(String::from_utf8(body.to_vec()).unwrap(), registry.table());
+        }
+    }
+}
@@ -0,0 +1,532 @@
+//! Production server entry point
+//!
+//! [`ActonServer`] wraps listener setup and `axum::serve` with the settings
+//! production deployments need:
+//! - Per-request timeout (`408 Request Timeout` when exceeded)
+//! - Graceful shutdown on `SIGINT`/`SIGTERM` with a bounded grace period
+//! - Maximum concurrent connections
+//! - TCP keep-alive and `TCP_NODELAY`
+//! - Panics in handlers answered with `500` pages
+//!   ([`CatchPanicLayer`](crate::htmx::middleware::CatchPanicLayer))
+//! - Request format negotiation for error responses
+//!   ([`ResponseFormatLayer`](crate::htmx::template::ResponseFormatLayer))
+//!
+//! Bind failures are reported as [`ServerError`] variants with a hint on how
+//! to fix them (address in use, permission denied, invalid address).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::server::ActonServer;
+//! use axum::{routing::get, Router};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), acton_dx::htmx::server::ServerError> {
+//! let app = Router::new().route("/", get(|| async { "Hello" }));
+//!
+//! ActonServer::new("0.0.0.0:3000")
+//!     .with_request_timeout(Duration::from_secs(30))
+//!     .with_grace_period(Duration::from_secs(10))
+//!     .with_max_connections(10_000)
+//!     .serve(app)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Draining background jobs
+//!
+//! `serve` returns once connections have drained; drain running jobs with
+//! [`ActonHtmxState::graceful_shutdown`](crate::htmx::state::ActonHtmxState::graceful_shutdown)
+//! before stopping the agents:
+//!
+//! ```rust,ignore
+//! ActonServer::new("0.0.0.0:3000")
+//!     .with_grace_period(Duration::from_secs(10))
+//!     .serve(app.with_state(state.clone()))
+//!     .await?;
+//!
+//! state.graceful_shutdown(Duration::from_secs(30)).await;
+//! runtime.shutdown_all().await?;
+//! ```
+
+use axum::{http::StatusCode, serve::ListenerExt, Router};
+use socket2::{SockRef, TcpKeepalive};
+use std::future::{Future, IntoFuture};
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
+use tower_http::timeout::TimeoutLayer;
+
+use crate::htmx::middleware::CatchPanicLayer;
+use crate::htmx::template::ResponseFormatLayer;
+
+/// Errors starting or running the server
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    /// The listen address could not be parsed or resolved
+    #[error("Invalid listen address `{addr}`: {source}. Use host:port, e.g. 127.0.0.1:3000")]
+    InvalidAddress {
+        /// Address as configured
+        addr: String,
+        /// Underlying error
+        #[source]
+        source: io::Error,
+    },
+
+    /// Another process is already listening on the address
+    #[error(
+        "Address {addr} is already in use. Stop the other process or choose a different port"
+    )]
+    AddressInUse {
+        /// Address as configured
+        addr: String,
+    },
+
+    /// Binding to the address is not permitted
+    #[error(
+        "Permission denied binding {addr}. Ports below 1024 need elevated privileges; use a higher port"
+    )]
+    PermissionDenied {
+        /// Address as configured
+        addr: String,
+    },
+
+    /// Any other bind failure
+    #[error("Failed to bind {addr}: {source}")]
+    Bind {
+        /// Address as configured
+        addr: String,
+        /// Underlying error
+        #[source]
+        source: io::Error,
+    },
+
+    /// The server failed while running
+    #[error("Server error: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl ServerError {
+    /// Classify an I/O error returned while binding `addr`
+    fn from_bind(addr: &str, source: io::Error) -> Self {
+        let addr = addr.to_string();
+        match source.kind() {
+            io::ErrorKind::AddrInUse => Self::AddressInUse { addr },
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied { addr },
+            io::ErrorKind::InvalidInput => Self::InvalidAddress { addr, source },
+            _ => Self::Bind { addr, source },
+        }
+    }
+}
+
+/// Production server builder
+#[derive(Debug, Clone)]
+pub struct ActonServer {
+    addr: String,
+    request_timeout: Option<Duration>,
+    grace_period: Duration,
+    max_connections: Option<usize>,
+    tcp_keepalive: Option<Duration>,
+    tcp_nodelay: bool,
+}
+
+impl ActonServer {
+    /// Create a server listening on `addr` (e.g. `"0.0.0.0:3000"`)
+    ///
+    /// Defaults: no request timeout, 30 second grace period, unlimited
+    /// connections, 60 second TCP keep-alive, `TCP_NODELAY` on.
+    #[must_use]
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            request_timeout: None,
+            grace_period: Duration::from_secs(30),
+            max_connections: None,
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            tcp_nodelay: true,
+        }
+    }
+
+    /// Respond `408 Request Timeout` to requests taking longer than `timeout`
+    #[must_use]
+    pub const fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// How long in-flight requests may run after a shutdown signal
+    #[must_use]
+    pub const fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Limit concurrent connections; further connections wait to be accepted
+    #[must_use]
+    pub const fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = Some(max_connections);
+        self
+    }
+
+    /// Set the TCP keep-alive idle time, or disable keep-alive with `None`
+    #[must_use]
+    pub const fn with_tcp_keepalive(mut self, keepalive: Option<Duration>) -> Self {
+        self.tcp_keepalive = keepalive;
+        self
+    }
+
+    /// Enable or disable `TCP_NODELAY` on accepted connections
+    #[must_use]
+    pub const fn with_tcp_nodelay(mut self, nodelay: bool) -> Self {
+        self.tcp_nodelay = nodelay;
+        self
+    }
+
+    /// Bind the listener
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError`] describing why the address could not be bound.
+    pub async fn bind(&self) -> Result<BoundServer, ServerError> {
+        let listener = TcpListener::bind(&self.addr)
+            .await
+            .map_err(|e| ServerError::from_bind(&self.addr, e))?;
+
+        Ok(BoundServer {
+            config: self.clone(),
+            listener,
+        })
+    }
+
+    /// Bind and serve `router` until `SIGINT` or `SIGTERM`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError`] if binding fails or the server stops with an
+    /// I/O error.
+    pub async fn serve(self, router: Router) -> Result<(), ServerError> {
+        self.bind().await?.serve(router).await
+    }
+
+    /// Bind and serve `router` until `signal` completes
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError`] if binding fails or the server stops with an
+    /// I/O error.
+    pub async fn serve_with_shutdown<F>(self, router: Router, signal: F) -> Result<(), ServerError>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.bind()
+            .await?
+            .serve_with_shutdown(router, signal)
+            .await
+    }
+}
+
+/// A server whose listener is bound but not yet serving
+///
+/// Useful for binding to port `0` and reading the assigned address.
+#[derive(Debug)]
+pub struct BoundServer {
+    config: ActonServer,
+    listener: TcpListener,
+}
+
+impl BoundServer {
+    /// Address the listener is bound to
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the local address cannot be read from the socket.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serve `router` until `SIGINT` or `SIGTERM`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError::Io`] if the server stops with an I/O error.
+    pub async fn serve(self, router: Router) -> Result<(), ServerError> {
+        self.serve_with_shutdown(router, shutdown_signal()).await
+    }
+
+    /// Serve `router` until `signal` completes, then drain for the grace period
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ServerError::Io`] if the server stops with an I/O error.
+    pub async fn serve_with_shutdown<F>(self, router: Router, signal: F) -> Result<(), ServerError>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let Self { config, listener } = self;
+
+        let router = match config.request_timeout {
+            Some(timeout) => router.layer(TimeoutLayer::with_status_code(
+                StatusCode::REQUEST_TIMEOUT,
+                timeout,
+            )),
+            None => router,
+        }
+        .layer(CatchPanicLayer)
+        .layer(ResponseFormatLayer);
+
+        let addr = listener.local_addr()?;
+        let (keepalive, nodelay) = (config.tcp_keepalive, config.tcp_nodelay);
+        let listener = ServerListener {
+            listener,
+            connections: config.max_connections.map(|n| Arc::new(Semaphore::new(n))),
+        }
+        .tap_io(move |io: &mut ServerStream| configure_stream(&io.stream, keepalive, nodelay));
+
+        tracing::info!(%addr, "Server listening");
+
+        let (drain_tx, drain_rx) = oneshot::channel::<()>();
+        let server = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async {
+            let _ = drain_rx.await;
+        })
+        .into_future();
+        tokio::pin!(server);
+
+        tokio::select! {
+            result = &mut server => return result.map_err(ServerError::Io),
+            () = signal => {}
+        }
+
+        tracing::info!(
+            grace_period_secs = config.grace_period.as_secs_f64(),
+            "Shutdown signal received, draining connections"
+        );
+        let _ = drain_tx.send(());
+
+        if let Ok(result) = tokio::time::timeout(config.grace_period, server).await {
+            result?;
+            tracing::info!("Server stopped");
+        } else {
+            tracing::warn!("Grace period elapsed, closing remaining connections");
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolve when the process receives `SIGINT` (Ctrl-C) or `SIGTERM`
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(error) = tokio::signal::ctrl_c().await {
+            tracing::error!(%error, "Failed to listen for Ctrl-C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                tracing::error!(%error, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {}
+        () = terminate => {}
+    }
+}
+
+/// TCP listener applying connection limits and socket options
+struct ServerListener {
+    listener: TcpListener,
+    connections: Option<Arc<Semaphore>>,
+}
+
+impl axum::serve::Listener for ServerListener {
+    type Io = ServerStream;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        // Wait for a free slot before accepting, so excess connections queue
+        // in the kernel backlog instead of being accepted and starved
+        let permit = match &self.connections {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+
+        loop {
+            match self.listener.accept().await {
+                Ok((stream, addr)) => {
+                    return (
+                        ServerStream {
+                            stream,
+                            _permit: permit,
+                        },
+                        addr,
+                    );
+                }
+                Err(error) => {
+                    // Typically EMFILE; back off instead of spinning
+                    tracing::error!(%error, "Failed to accept connection");
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+/// Apply socket options to an accepted connection
+fn configure_stream(stream: &TcpStream, keepalive: Option<Duration>, nodelay: bool) {
+    if let Err(error) = stream.set_nodelay(nodelay) {
+        tracing::debug!(%error, "Failed to set TCP_NODELAY");
+    }
+
+    if let Some(idle) = keepalive {
+        let keepalive = TcpKeepalive::new().with_time(idle);
+        if let Err(error) = SockRef::from(stream).set_tcp_keepalive(&keepalive) {
+            tracing::debug!(%error, "Failed to set TCP keep-alive");
+        }
+    }
+}
+
+/// Accepted connection holding its connection-limit permit
+struct ServerStream {
+    stream: TcpStream,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl AsyncRead for ServerStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ServerStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    async fn get_raw(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_address_in_use_is_actionable() {
+        let existing = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = existing.local_addr().unwrap().to_string();
+
+        let error = ActonServer::new(addr.clone()).bind().await.unwrap_err();
+        assert!(matches!(error, ServerError::AddressInUse { .. }));
+        assert!(error.to_string().contains("already in use"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_address() {
+        let error = ActonServer::new("not-an-address").bind().await.unwrap_err();
+        assert!(matches!(
+            error,
+            ServerError::InvalidAddress { .. } | ServerError::Bind { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_serves_and_shuts_down_gracefully() {
+        let app = Router::new()
+            .route("/", get(|| async { "hello" }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    "slow"
+                }),
+            );
+
+        let bound = ActonServer::new("127.0.0.1:0")
+            .with_request_timeout(Duration::from_millis(50))
+            .with_max_connections(4)
+            .bind()
+            .await
+            .unwrap();
+        let addr = bound.local_addr().unwrap();
+
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(bound.serve_with_shutdown(app, async {
+            let _ = stop_rx.await;
+        }));
+
+        let ok = get_raw(addr, "/").await;
+        assert!(ok.starts_with("HTTP/1.1 200"));
+        assert!(ok.ends_with("hello"));
+
+        let timed_out = get_raw(addr, "/slow").await;
+        assert!(timed_out.starts_with("HTTP/1.1 408"));
+
+        stop_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
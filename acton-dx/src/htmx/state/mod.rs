@@ -3,11 +3,21 @@
 //! Combines acton-service infrastructure with acton-reactive actors and
 //! HTMX-specific components.
 
-use crate::htmx::agents::{CsrfManagerAgent, SessionManagerAgent};
-use crate::htmx::jobs::JobAgent;
+use crate::htmx::agents::{
+    BroadcastAgent, ChannelAuthorizer, CsrfManagerAgent, InMemorySessionStore,
+    SessionManagerAgent, SessionStore,
+};
+use crate::htmx::auth::session::SessionData;
+use crate::htmx::events::EventBus;
+use crate::htmx::middleware::CookieSigner;
+use crate::htmx::jobs::agent::{start_scheduler_loop, ScheduledJobAgent};
+use crate::htmx::jobs::{Job, JobAgent, JobError, JobId, JobSchedule, JobShutdownCoordinator, ShutdownResult};
 use crate::htmx::oauth2::OAuth2Agent;
+use crate::htmx::realtime::RealtimeHub;
 use crate::htmx::template::FrameworkTemplates;
-use crate::htmx::{config::ActonHtmxConfig, observability::ObservabilityConfig};
+use crate::htmx::observability::metrics::PrometheusRecorder;
+use crate::htmx::config::{ActonHtmxConfig, ConfigValidation, FeatureFlags};
+use crate::htmx::observability::ObservabilityConfig;
 use acton_reactive::prelude::{AgentHandle, AgentRuntime};
 use std::sync::Arc;
 
@@ -17,6 +27,8 @@ use sqlx::PgPool;
 #[cfg(feature = "sqlite")]
 use sqlx::SqlitePool;
 
+#[cfg(feature = "redis")]
+use crate::htmx::agents::RedisSessionStore;
 #[cfg(feature = "redis")]
 use deadpool_redis::Pool as RedisPool;
 
@@ -29,6 +41,8 @@ use deadpool_redis::Pool as RedisPool;
 /// - CSRF protection agent (from acton-reactive)
 /// - OAuth2 manager agent (from acton-reactive)
 /// - Job processing agent (from acton-reactive)
+/// - WebSocket broadcast agent (from acton-reactive)
+/// - Domain event bus (from acton-reactive)
 /// - Database connection pool (PostgreSQL via SQLx)
 /// - Redis cache (optional, for distributed sessions and job persistence)
 /// - Framework templates (runtime-loadable HTML templates)
@@ -87,6 +101,50 @@ pub struct ActonHtmxState {
     /// Clone this freely - `AgentHandle` is designed for concurrent access
     job_agent: AgentHandle,
 
+    /// Scheduled job agent handle
+    ///
+    /// Enqueues recurring and delayed jobs on the job agent when they are due
+    scheduler: AgentHandle,
+
+    /// WebSocket broadcast agent handle
+    ///
+    /// Clone this freely - `AgentHandle` is designed for concurrent access
+    broadcast: AgentHandle,
+
+    /// Decides which `?channel=` subscriptions `ws_handler` accepts
+    ///
+    /// Requested channels are refused while unset
+    channel_authorizer: Option<ChannelAuthorizer>,
+
+    /// Domain event bus
+    ///
+    /// Publishes application events to subscriber agents via the runtime broker
+    event_bus: EventBus,
+
+    /// Real-time broadcast hub
+    ///
+    /// Topic-keyed channels pushing HTML fragments to SSE/WebSocket connections
+    realtime: RealtimeHub,
+
+    /// Job shutdown coordinator
+    ///
+    /// Job runners register running jobs with its cancellation manager so
+    /// `graceful_shutdown` can wait for them
+    job_shutdown: JobShutdownCoordinator,
+
+    /// Prometheus metrics recorder
+    ///
+    /// Fed by `TracingLayer` and rendered at `/metrics`
+    metrics: PrometheusRecorder,
+
+    /// Cookie signer keyed by `security.secret_key`
+    ///
+    /// Signs session cookies
+    cookie_signer: CookieSigner,
+
+    /// Runtime feature flags, seeded from `config.features`
+    feature_flags: FeatureFlags,
+
     /// PostgreSQL database connection pool
     ///
     /// Shared across all requests for efficient connection management
@@ -123,7 +181,8 @@ impl ActonHtmxState {
     ///
     /// # Errors
     ///
-    /// Returns error if agent spawning fails
+    /// Returns error if agent spawning fails, or in release builds because
+    /// the default configuration has no `security.secret_key`
     ///
     /// # Example
     ///
@@ -135,33 +194,19 @@ impl ActonHtmxState {
     /// let state = ActonHtmxState::new(&mut runtime).await?;
     /// ```
     pub async fn new(runtime: &mut AgentRuntime) -> anyhow::Result<Self> {
-        let config = ActonHtmxConfig::default();
-        let observability = ObservabilityConfig::default();
-        let session_manager = SessionManagerAgent::spawn(runtime).await?;
-        let csrf_manager = CsrfManagerAgent::spawn(runtime).await?;
-        let oauth2_manager = OAuth2Agent::spawn(runtime).await?;
-        let job_agent = JobAgent::spawn(runtime).await?;
-        let templates = FrameworkTemplates::new()?;
-
-        Ok(Self {
-            config: Arc::new(config),
-            observability: Arc::new(observability),
-            session_manager,
-            csrf_manager,
-            oauth2_manager,
-            job_agent,
-            #[cfg(feature = "postgres")]
-            pg_pool: None,
-            #[cfg(feature = "sqlite")]
-            sqlite_pool: None,
-            #[cfg(feature = "redis")]
-            redis_pool: None,
-            templates,
-        })
+        Self::build(runtime, ActonHtmxConfig::default(), ObservabilityConfig::default()).await
     }
 
     /// Create application state with custom configuration
     ///
+    /// If `config.redis` is set, sessions are stored in Redis with a TTL of
+    /// `security.session_max_age_secs`.
+    ///
+    /// The configuration is first checked with
+    /// [`ActonHtmxConfig::validate`] for `deploy.env`. Problems are logged;
+    /// with `deploy.validation = "enforce"` (the default), errors abort
+    /// startup.
+    ///
     /// # Arguments
     ///
     /// * `runtime` - Mutable reference to the Acton runtime
@@ -169,7 +214,8 @@ impl ActonHtmxState {
     ///
     /// # Errors
     ///
-    /// Returns error if agent spawning fails
+    /// Returns error if agent spawning fails, if validation finds errors and
+    /// is enforced, or if `security.secret_key` is unset in a release build
     ///
     /// # Example
     ///
@@ -185,12 +231,56 @@ impl ActonHtmxState {
         runtime: &mut AgentRuntime,
         config: ActonHtmxConfig,
     ) -> anyhow::Result<Self> {
-        let observability = ObservabilityConfig::new("acton-dx");
-        let session_manager = SessionManagerAgent::spawn(runtime).await?;
+        check_config(&config)?;
+        Box::pin(Self::build(runtime, config, ObservabilityConfig::new("acton-dx"))).await
+    }
+
+    /// Spawn agents and assemble state
+    ///
+    /// With a `[redis]` section configured, creates the Redis pool and stores
+    /// sessions in Redis; otherwise sessions are kept in memory.
+    async fn build(
+        runtime: &mut AgentRuntime,
+        config: ActonHtmxConfig,
+        observability: ObservabilityConfig,
+    ) -> anyhow::Result<Self> {
+        let cookie_signer = CookieSigner::from_settings(&config.security)?;
+
+        #[cfg(feature = "redis")]
+        let redis_pool = config
+            .redis
+            .as_ref()
+            .map(|redis| {
+                deadpool_redis::Config::from_url(&redis.url)
+                    .create_pool(Some(deadpool_redis::Runtime::Tokio1))
+            })
+            .transpose()?;
+        #[cfg(feature = "redis")]
+        let session_store: Box<dyn SessionStore> = match &redis_pool {
+            Some(pool) => Box::new(
+                RedisSessionStore::new(pool.clone())
+                    .with_ttl_secs(config.security.session_max_age_secs),
+            ),
+            None => Box::new(InMemorySessionStore::new()),
+        };
+        #[cfg(not(feature = "redis"))]
+        let session_store: Box<dyn SessionStore> = Box::new(InMemorySessionStore::new());
+        let session_manager = SessionManagerAgent::spawn_with_session_limit(
+            runtime,
+            session_store,
+            config.security.max_sessions_per_user,
+        )
+        .await?;
+
         let csrf_manager = CsrfManagerAgent::spawn(runtime).await?;
         let oauth2_manager = OAuth2Agent::spawn(runtime).await?;
         let job_agent = JobAgent::spawn(runtime).await?;
+        let scheduler = ScheduledJobAgent::spawn(runtime, job_agent.clone()).await?;
+        start_scheduler_loop(scheduler.clone()).await?;
+        let broadcast = BroadcastAgent::spawn(runtime).await?;
+        let event_bus = EventBus::new(runtime);
         let templates = FrameworkTemplates::new()?;
+        let feature_flags = FeatureFlags::new(&config.features);
 
         Ok(Self {
             config: Arc::new(config),
@@ -199,12 +289,21 @@ impl ActonHtmxState {
             csrf_manager,
             oauth2_manager,
             job_agent,
+            scheduler,
+            broadcast,
+            channel_authorizer: None,
+            event_bus,
+            realtime: RealtimeHub::new(),
+            job_shutdown: JobShutdownCoordinator::new(),
+            metrics: PrometheusRecorder::new(),
+            cookie_signer,
+            feature_flags,
             #[cfg(feature = "postgres")]
             pg_pool: None,
             #[cfg(feature = "sqlite")]
             sqlite_pool: None,
             #[cfg(feature = "redis")]
-            redis_pool: None,
+            redis_pool,
             templates,
         })
     }
@@ -322,19 +421,385 @@ impl ActonHtmxState {
     /// # Example
     ///
     /// ```rust,ignore
-    /// use acton_htmx::jobs::{EnqueueJob, JobId};
+    /// use acton_htmx::jobs::agent::GetMetricsRequest;
     ///
     /// async fn handler(State(state): State<ActonHtmxState>) {
-    ///     let job_handle = state.job_agent();
-    ///     // Enqueue a job directly
-    ///     job_handle.send(EnqueueJob { /* ... */ }).await;
+    ///     let (request, rx) = GetMetricsRequest::new();
+    ///     state.job_agent().send(request).await;
     /// }
     /// ```
+    ///
+    /// To enqueue jobs, use [`enqueue`](Self::enqueue).
     #[must_use]
     pub const fn job_agent(&self) -> &AgentHandle {
         &self.job_agent
     }
 
+    /// Get the scheduled job agent handle
+    ///
+    /// Send [`ScheduledJobMessage`](crate::htmx::jobs::agent::ScheduledJobMessage)s
+    /// to list, enable, or remove scheduled jobs. To register one, use
+    /// [`enqueue_scheduled`](Self::enqueue_scheduled).
+    #[must_use]
+    pub const fn scheduler(&self) -> &AgentHandle {
+        &self.scheduler
+    }
+
+    /// Enqueue a typed job for background processing
+    ///
+    /// Serializes the job, derives its type name, and copies priority,
+    /// retries, backoff, and timeout from its [`Job`] implementation. Waits
+    /// up to 100ms for the job agent to accept it.
+    ///
+    /// # Errors
+    ///
+    /// Returns:
+    /// - [`JobError::SerializationError`] if the job cannot be serialized
+    /// - [`JobError::QueueFull`] if the queue is at capacity
+    /// - [`JobError::AgentUnavailable`] if the agent is shutting down,
+    ///   stopped, or doesn't respond within the timeout
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// async fn signup(
+    ///     State(state): State<ActonHtmxState>,
+    ///     Form(form): Form<SignupForm>,
+    /// ) -> Result<impl IntoResponse, StatusCode> {
+    ///     let job_id = state
+    ///         .enqueue(WelcomeEmailJob { to: form.email })
+    ///         .await
+    ///         .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    ///     Ok(format!("Queued {job_id}"))
+    /// }
+    /// ```
+    pub async fn enqueue<J: Job>(&self, job: J) -> Result<JobId, JobError> {
+        let message = super::jobs::agent::EnqueueJob::from_job(&job)?;
+        self.send_enqueue(message).await
+    }
+
+    /// Enqueue a typed job with an explicit priority
+    ///
+    /// Like [`enqueue`](Self::enqueue), but overrides [`Job::priority`].
+    /// Higher priorities are processed first.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`enqueue`](Self::enqueue).
+    pub async fn enqueue_with_priority<J: Job>(
+        &self,
+        job: J,
+        priority: i32,
+    ) -> Result<JobId, JobError> {
+        let mut message = super::jobs::agent::EnqueueJob::from_job(&job)?;
+        message.priority = priority;
+        self.send_enqueue(message).await
+    }
+
+    /// Register a typed job to run on a schedule
+    ///
+    /// The scheduler enqueues a copy of the job each time the schedule is
+    /// due. The returned id identifies the schedule, not an individual run.
+    ///
+    /// # Errors
+    ///
+    /// Returns:
+    /// - [`JobError::SerializationError`] if the job cannot be serialized
+    /// - [`JobError::InvalidSchedule`] if the schedule never fires
+    /// - [`JobError::AgentUnavailable`] if the scheduler doesn't respond
+    ///   within 100ms
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// state
+    ///     .enqueue_scheduled(CleanupJob::default(), JobSchedule::cron("0 3 * * *")?)
+    ///     .await?;
+    /// ```
+    pub async fn enqueue_scheduled<J: Job>(
+        &self,
+        job: J,
+        schedule: JobSchedule,
+    ) -> Result<JobId, JobError> {
+        self.register_scheduled(&job, schedule, false).await
+    }
+
+    /// Register a recurring job at startup
+    ///
+    /// Like [`enqueue_scheduled`](Self::enqueue_scheduled), but a fire is
+    /// skipped while the previous run is still queued or running, so slow
+    /// runs never overlap. Registering the same job and schedule again (for
+    /// example on every boot) returns the existing schedule id. Schedules
+    /// survive restarts only when the scheduler is spawned with
+    /// [`ScheduledJobAgent::spawn_with_persistence`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`enqueue_scheduled`](Self::enqueue_scheduled).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let state = ActonHtmxState::new(&mut runtime).await?;
+    /// state
+    ///     .schedule_recurring(JobSchedule::cron("0 3 * * *")?, CleanupJob::default())
+    ///     .await?;
+    /// ```
+    pub async fn schedule_recurring<J: Job + Clone>(
+        &self,
+        schedule: JobSchedule,
+        job: J,
+    ) -> Result<JobId, JobError> {
+        self.register_scheduled(&job, schedule, true).await
+    }
+
+    /// Send a scheduled job registration and await the scheduler's answer
+    async fn register_scheduled<J: Job>(
+        &self,
+        job: &J,
+        schedule: JobSchedule,
+        skip_if_running: bool,
+    ) -> Result<JobId, JobError> {
+        use super::jobs::agent::{EnqueueJob, RegisterScheduledJobRequest};
+        use acton_reactive::prelude::AgentHandleInterface;
+        use std::time::Duration;
+
+        let (request, rx) = RegisterScheduledJobRequest::new(EnqueueJob::from_job(job)?, schedule);
+        self.scheduler()
+            .send(request.with_skip_if_running(skip_if_running))
+            .await;
+
+        let timeout = Duration::from_millis(100);
+        tokio::time::timeout(timeout, rx)
+            .await
+            .map_err(|_| JobError::AgentUnavailable)?
+            .map_err(|_| JobError::AgentUnavailable)?
+    }
+
+    /// Send an enqueue request and await the agent's answer
+    async fn send_enqueue(&self, job: super::jobs::agent::EnqueueJob) -> Result<JobId, JobError> {
+        use super::jobs::agent::EnqueueJobRequest;
+        use acton_reactive::prelude::AgentHandleInterface;
+        use std::time::Duration;
+
+        let (request, rx) = EnqueueJobRequest::new(job);
+        self.job_agent().send(request).await;
+
+        let timeout = Duration::from_millis(100);
+        tokio::time::timeout(timeout, rx)
+            .await
+            .map_err(|_| JobError::AgentUnavailable)?
+            .map_err(|_| JobError::AgentUnavailable)?
+    }
+
+    /// Get the WebSocket broadcast agent handle
+    ///
+    /// Publish HTML fragments to connections registered by
+    /// [`ws_handler`](crate::htmx::handlers::ws_handler).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use acton_dx::htmx::agents::Publish;
+    ///
+    /// async fn post_message(State(state): State<ActonHtmxState>) {
+    ///     state
+    ///         .broadcast()
+    ///         .send(Publish::new("room:7", "<div id=\"messages\" hx-swap-oob=\"beforeend\"><p>Hi</p></div>"))
+    ///         .await;
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn broadcast(&self) -> &AgentHandle {
+        &self.broadcast
+    }
+
+    /// Get the authorizer for WebSocket channel subscriptions, if set
+    #[must_use]
+    pub const fn channel_authorizer(&self) -> Option<&ChannelAuthorizer> {
+        self.channel_authorizer.as_ref()
+    }
+
+    /// Allow [`ws_handler`](crate::htmx::handlers::ws_handler) connections
+    /// to subscribe to the channels `authorize` accepts
+    ///
+    /// `authorize` receives the requested channel and the connection's
+    /// session. Without an authorizer every `?channel=` request is refused;
+    /// a connection's own session channel is always allowed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// state.set_channel_authorizer(|channel, session| match channel {
+    ///     "dashboard" => session.is_some_and(|data| data.user_id.is_some()),
+    ///     channel => channel.starts_with("public:"),
+    /// });
+    /// ```
+    pub fn set_channel_authorizer(
+        &mut self,
+        authorize: impl Fn(&str, Option<&SessionData>) -> bool + Send + Sync + 'static,
+    ) {
+        self.channel_authorizer = Some(Arc::new(authorize));
+    }
+
+    /// Get the domain event bus
+    ///
+    /// Publish application events from handlers; subscribers are registered
+    /// at startup via [`EventBus::subscribe`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// async fn register(State(state): State<ActonHtmxState>) {
+    ///     state.events().publish(UserRegistered { user_id: 42 }).await;
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn events(&self) -> &EventBus {
+        &self.event_bus
+    }
+
+    /// Get the real-time broadcast hub
+    ///
+    /// Publish HTML fragments from handlers and jobs; SSE and WebSocket
+    /// handlers subscribe to the same topics.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use acton_dx::htmx::realtime::RealtimeHub;
+    ///
+    /// async fn mark_read(State(state): State<ActonHtmxState>) {
+    ///     state.realtime().publish(&RealtimeHub::user_topic(42), "<span id=\"unread\">0</span>");
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn realtime(&self) -> &RealtimeHub {
+        &self.realtime
+    }
+
+    /// Get the job shutdown coordinator
+    ///
+    /// Code that runs jobs registers a [`CancellationToken`](crate::htmx::jobs::CancellationToken)
+    /// per running job with its cancellation manager, unregisters it when the
+    /// job finishes, and stops picking up work once the shutdown token is
+    /// cancelled.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use acton_htmx::jobs::CancellationToken;
+    ///
+    /// let jobs = state.job_shutdown().cancellation_manager();
+    /// let token = CancellationToken::new();
+    /// jobs.register(job_id, token.clone());
+    /// run_job(&token).await;
+    /// jobs.unregister(&job_id);
+    /// ```
+    #[must_use]
+    pub const fn job_shutdown(&self) -> &JobShutdownCoordinator {
+        &self.job_shutdown
+    }
+
+    /// Drain background jobs before the runtime shuts down
+    ///
+    /// Stops the job agent accepting new jobs (persisting the jobs still
+    /// queued when Redis persistence is enabled), then waits up to `timeout`
+    /// for running jobs registered with [`job_shutdown`](Self::job_shutdown)
+    /// to finish. Jobs still running after the timeout are cancelled.
+    ///
+    /// Call it after the HTTP server has stopped and before
+    /// `runtime.shutdown_all()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// ActonServer::new("0.0.0.0:3000").serve(app).await?;
+    ///
+    /// let result = state.graceful_shutdown(Duration::from_secs(30)).await;
+    /// tracing::info!(
+    ///     completed = result.jobs_completed(),
+    ///     abandoned = result.jobs_remaining(),
+    ///     "Jobs drained"
+    /// );
+    /// runtime.shutdown_all().await?;
+    /// ```
+    pub async fn graceful_shutdown(&self, timeout: std::time::Duration) -> ShutdownResult {
+        use super::jobs::agent::StopAcceptingJobsRequest;
+        use acton_reactive::prelude::AgentHandleInterface;
+        use std::time::Duration;
+
+        let (request, rx) = StopAcceptingJobsRequest::new();
+        self.job_agent().send(request).await;
+        if let Ok(Ok(queued)) = tokio::time::timeout(Duration::from_millis(500), rx).await {
+            tracing::info!(queued, "Job agent stopped accepting jobs");
+        } else {
+            tracing::warn!("Job agent did not confirm it stopped accepting jobs");
+        }
+
+        let result = self.job_shutdown.drain(timeout).await;
+        tracing::info!(
+            completed = result.jobs_completed(),
+            abandoned = result.jobs_remaining(),
+            "Background jobs drained"
+        );
+        result
+    }
+
+    /// Get the Prometheus metrics recorder
+    ///
+    /// Pass it to [`TracingLayer::with_recorder`](crate::htmx::middleware::TracingLayer::with_recorder)
+    /// to count HTTP requests; [`metrics_handler`](crate::htmx::observability::metrics::metrics_handler)
+    /// renders it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use acton_dx::htmx::middleware::TracingLayer;
+    /// use acton_dx::htmx::observability::metrics::metrics_routes;
+    ///
+    /// let app = Router::new()
+    ///     .route("/", get(index))
+    ///     .merge(metrics_routes())
+    ///     .layer(TracingLayer::new().with_recorder(state.metrics().clone()))
+    ///     .with_state(state);
+    /// ```
+    #[must_use]
+    pub const fn metrics(&self) -> &PrometheusRecorder {
+        &self.metrics
+    }
+
+    /// Get the cookie signer keyed by `security.secret_key`
+    ///
+    /// `SessionLayer` signs session cookies with it; use it for any other
+    /// cookie whose value must not be forged.
+    #[must_use]
+    pub const fn cookie_signer(&self) -> &CookieSigner {
+        &self.cookie_signer
+    }
+
+    /// Get the runtime feature flags
+    ///
+    /// Starts from `config.features`; changes made at runtime (e.g. through
+    /// [`toggle_flag`](crate::htmx::handlers::feature_flags::toggle_flag))
+    /// are visible to every clone of the state.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// async fn checkout(State(state): State<ActonHtmxState>) -> impl IntoResponse {
+    ///     if state.feature_flags().is_enabled("new_checkout") {
+    ///         NewCheckoutTemplate::default().into_response()
+    ///     } else {
+    ///         CheckoutTemplate::default().into_response()
+    ///     }
+    /// }
+    /// ```
+    #[must_use]
+    pub const fn feature_flags(&self) -> &FeatureFlags {
+        &self.feature_flags
+    }
+
     /// Get the PostgreSQL database connection pool
     ///
     /// # Panics
@@ -457,6 +922,9 @@ impl ActonHtmxState {
 
     /// Set the Redis connection pool
     ///
+    /// The session store is chosen when the state is created, so sessions
+    /// only use Redis when it is configured through the `[redis]` section.
+    ///
     /// # Example
     ///
     /// ```rust,ignore
@@ -554,6 +1022,110 @@ impl ActonHtmxState {
         let timeout = Duration::from_millis(100);
         Ok(tokio::time::timeout(timeout, rx).await??)
     }
+
+    /// List dead letter queue entries with timeout.
+    ///
+    /// Returns one page (1-indexed, most recently enqueued first) of failed
+    /// jobs with their type, last error, attempt count, and enqueue time.
+    /// The page size is clamped to 1-100.
+    ///
+    /// Uses a 100ms timeout to prevent handlers from hanging if the agent
+    /// is slow or stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - Agent doesn't respond within timeout
+    /// - Response channel is closed (agent stopped)
+    pub async fn get_dead_letter_jobs(
+        &self,
+        page: usize,
+        page_size: usize,
+    ) -> Result<super::jobs::agent::DeadLetterPage, anyhow::Error> {
+        use acton_reactive::prelude::AgentHandleInterface;
+        use super::jobs::agent::GetDeadLetterJobsRequest;
+        use std::time::Duration;
+
+        let (request, rx) = GetDeadLetterJobsRequest::new(page, page_size);
+        self.job_agent().send(request).await;
+
+        let timeout = Duration::from_millis(100);
+        Ok(tokio::time::timeout(timeout, rx).await??)
+    }
+
+    /// Get the latest progress of a running job with timeout.
+    ///
+    /// Returns `None` when the job isn't running, has finished, or hasn't
+    /// reported progress yet. Jobs report progress with
+    /// [`JobContext::report_progress`](crate::htmx::jobs::JobContext::report_progress).
+    ///
+    /// Uses a 100ms timeout to prevent handlers from hanging if the agent
+    /// is slow or stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if:
+    /// - Agent doesn't respond within timeout
+    /// - Response channel is closed (agent stopped)
+    ///
+    /// # Example
+    ///
+    /// Poll from the progress bar partial with
+    /// `hx-get="/imports/{id}/progress" hx-trigger="every 1s"`:
+    ///
+    /// ```rust,ignore
+    /// async fn import_progress(
+    ///     State(state): State<ActonHtmxState>,
+    ///     Path(job_id): Path<JobId>,
+    /// ) -> Result<impl IntoResponse, StatusCode> {
+    ///     let progress = state.get_job_progress(job_id).await
+    ///         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    ///
+    ///     Ok(ProgressBarTemplate { progress })
+    /// }
+    /// ```
+    pub async fn get_job_progress(
+        &self,
+        id: JobId,
+    ) -> Result<Option<super::jobs::JobProgress>, anyhow::Error> {
+        use acton_reactive::prelude::AgentHandleInterface;
+        use super::jobs::agent::GetJobProgressRequest;
+        use std::time::Duration;
+
+        let (request, rx) = GetJobProgressRequest::new(id);
+        self.job_agent().send(request).await;
+
+        let timeout = Duration::from_millis(100);
+        Ok(tokio::time::timeout(timeout, rx).await??)
+    }
+}
+
+/// Validate `config` according to its `deploy` settings
+fn check_config(config: &ActonHtmxConfig) -> anyhow::Result<()> {
+    let deploy = &config.deploy;
+    if deploy.validation == ConfigValidation::Off {
+        return Ok(());
+    }
+    let Err(problems) = config.validate(deploy.env) else {
+        return Ok(());
+    };
+
+    for problem in &problems {
+        if problem.is_error() {
+            tracing::error!(env = ?deploy.env, "Insecure configuration: {problem}");
+        } else {
+            tracing::warn!(env = ?deploy.env, "Insecure configuration: {problem}");
+        }
+    }
+
+    let errors = problems.iter().filter(|problem| problem.is_error()).count();
+    if errors > 0 && deploy.validation == ConfigValidation::Enforce {
+        anyhow::bail!(
+            "refusing to start: {errors} insecure setting(s) for {:?}; set deploy.validation = \"warn\" to override",
+            deploy.env
+        );
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -583,6 +1155,25 @@ mod tests {
         assert_eq!(state.config().htmx.request_timeout_ms, 10000);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_with_config_enforces_production_validation() {
+        let mut runtime = ActonApp::launch();
+        let mut config = ActonHtmxConfig::default();
+        config.deploy.env = crate::htmx::config::DeployEnv::Production;
+        config.security.csrf_enabled = false;
+
+        let result = ActonHtmxState::with_config(&mut runtime, config.clone()).await;
+        let error = result.err().expect("insecure production config was accepted");
+        assert!(error.to_string().contains("insecure setting"));
+
+        // Warn mode logs the problems and starts anyway
+        config.deploy.validation = ConfigValidation::Warn;
+        let state = ActonHtmxState::with_config(&mut runtime, config)
+            .await
+            .expect("Failed to create state");
+        assert!(!state.config().security.csrf_enabled);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_clone_state() {
         let mut runtime = ActonApp::launch();
@@ -605,4 +1196,170 @@ mod tests {
         // Should be able to get the session manager handle
         let _handle = state.session_manager();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_enqueue_returns_pending_job() {
+        use crate::htmx::jobs::{JobStatus, TestJob};
+
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime)
+            .await
+            .expect("Failed to create state");
+
+        let id = state
+            .enqueue(TestJob::new("hello".to_string(), true))
+            .await
+            .expect("Failed to enqueue job");
+        let status = state.get_job_status(id).await.expect("Status query failed");
+        assert_eq!(status, Some(JobStatus::Pending));
+
+        let urgent = state
+            .enqueue_with_priority(TestJob::new("urgent".to_string(), true), 100)
+            .await
+            .expect("Failed to enqueue job");
+        assert_ne!(urgent, id);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_enqueue_scheduled() {
+        use crate::htmx::jobs::TestJob;
+        use std::time::Duration;
+
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime)
+            .await
+            .expect("Failed to create state");
+
+        let delayed = JobSchedule::after(Duration::from_secs(60));
+        assert!(state
+            .enqueue_scheduled(TestJob::new("later".to_string(), true), delayed)
+            .await
+            .is_ok());
+
+        let invalid = JobSchedule::after(Duration::MAX);
+        assert!(matches!(
+            state
+                .enqueue_scheduled(TestJob::new("never".to_string(), true), invalid)
+                .await,
+            Err(JobError::InvalidSchedule(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_job_progress_observable_mid_execution() {
+        use crate::htmx::jobs::agent::JobFinished;
+        use crate::htmx::jobs::{JobContext, JobResult};
+        use acton_reactive::prelude::AgentHandleInterface;
+        use serde::{Deserialize, Serialize};
+        use std::time::Duration;
+
+        #[derive(Serialize, Deserialize)]
+        struct ImportJob;
+
+        #[async_trait::async_trait]
+        impl Job for ImportJob {
+            type Result = ();
+
+            async fn execute(&self, ctx: &JobContext) -> JobResult<Self::Result> {
+                ctx.report_progress(50, "Halfway").await;
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                Ok(())
+            }
+        }
+
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime)
+            .await
+            .expect("Failed to create state");
+
+        let id = state.enqueue(ImportJob).await.expect("Failed to enqueue job");
+        let ctx = JobContext::new().with_progress(id, state.job_agent().clone());
+        let running = tokio::spawn(async move { ImportJob.execute(&ctx).await });
+
+        let mut progress = None;
+        for _ in 0..20 {
+            progress = state.get_job_progress(id).await.expect("Progress query failed");
+            if progress.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let progress = progress.expect("Progress not reported");
+        assert_eq!(progress.percent, 50);
+        assert_eq!(progress.message, "Halfway");
+
+        running.await.unwrap().unwrap();
+        state
+            .job_agent()
+            .send(JobFinished {
+                id,
+                success: true,
+                error: None,
+            })
+            .await;
+        assert_eq!(state.get_job_progress(id).await.unwrap(), None);
+    }
+
+    /// Trigger the scheduler until the job agent has seen `count` enqueues
+    async fn fire_until_enqueued(state: &ActonHtmxState, count: u64) -> u64 {
+        use crate::htmx::jobs::agent::ScheduledJobMessage;
+        use acton_reactive::prelude::AgentHandleInterface;
+        use std::time::Duration;
+
+        let mut enqueued = 0;
+        for _ in 0..20 {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            state
+                .scheduler()
+                .send(ScheduledJobMessage::ProcessScheduledJobs)
+                .await;
+            enqueued = state.get_job_metrics().await.unwrap().jobs_enqueued;
+            if enqueued >= count {
+                break;
+            }
+        }
+        enqueued
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_recurring_schedule_enqueues_repeatedly() {
+        use crate::htmx::jobs::TestJob;
+        use std::time::Duration;
+
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime)
+            .await
+            .expect("Failed to create state");
+
+        let schedule = JobSchedule::every(Duration::from_millis(20));
+        state
+            .enqueue_scheduled(TestJob::new("tick".to_string(), true), schedule)
+            .await
+            .expect("Failed to schedule job");
+
+        assert!(fire_until_enqueued(&state, 3).await >= 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_schedule_recurring_is_idempotent() {
+        use crate::htmx::jobs::TestJob;
+        use std::time::Duration;
+
+        let mut runtime = ActonApp::launch();
+        let state = ActonHtmxState::new(&mut runtime)
+            .await
+            .expect("Failed to create state");
+
+        let job = TestJob::new("cleanup".to_string(), true);
+        let schedule = JobSchedule::every(Duration::from_millis(20));
+        let id = state
+            .schedule_recurring(schedule.clone(), job.clone())
+            .await
+            .expect("Failed to schedule job");
+        // Registering again at the next boot reuses the schedule
+        assert_eq!(state.schedule_recurring(schedule, job).await.unwrap(), id);
+
+        // The first run is never picked up, so later fires are skipped
+        assert_eq!(fire_until_enqueued(&state, 2).await, 1);
+    }
 }
@@ -1,9 +1,11 @@
 //! Local filesystem storage implementation
 
 use super::traits::FileStorage;
-use super::types::{StorageError, StorageResult, StoredFile, UploadedFile};
+use super::types::{ByteStream, StorageError, StorageResult, StoredFile, UploadedFile};
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
@@ -95,8 +97,18 @@ impl LocalFileStorage {
     }
 
     /// Gets the full filesystem path for a stored file
-    fn get_file_path(&self, id: &str, filename: &str) -> PathBuf {
-        self.get_file_directory(id).join(filename)
+    ///
+    /// Only the last component of the client-supplied `filename` is used, so
+    /// names like `../../etc/passwd` or `/etc/passwd` stay inside the file's
+    /// directory. Hidden names are rejected: they would clash with the
+    /// metadata sidecar and be skipped by [`Self::find_data_file`].
+    fn get_file_path(&self, id: &str, filename: &str) -> StorageResult<PathBuf> {
+        let name = Path::new(filename)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| !name.starts_with('.'))
+            .ok_or_else(|| StorageError::InvalidPath(format!("Invalid filename: {filename}")))?;
+        Ok(self.get_file_directory(id).join(name))
     }
 
     /// Gets the path to the metadata file for a stored file
@@ -104,6 +116,37 @@ impl LocalFileStorage {
         self.get_file_directory(id).join(".metadata.json")
     }
 
+    /// Finds the file holding the data of a stored file
+    ///
+    /// We don't know the filename, so this returns the first non-hidden
+    /// file in the ID directory.
+    async fn find_data_file(&self, id: &str) -> StorageResult<PathBuf> {
+        let dir = self.get_file_directory(id);
+
+        if !dir.exists() {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+
+        let mut entries = fs::read_dir(&dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let file_path = entry.path();
+            // Use async metadata check
+            if let Ok(metadata) = entry.metadata().await {
+                if metadata.is_file() {
+                    // Skip hidden files (like .metadata.json)
+                    if let Some(name) = file_path.file_name().and_then(|n| n.to_str()) {
+                        if !name.starts_with('.') {
+                            return Ok(file_path);
+                        }
+                    }
+                }
+            }
+        }
+
+        Err(StorageError::NotFound(id.to_string()))
+    }
+
     /// Ensures the storage directory exists
     async fn ensure_directory(&self, path: &Path) -> StorageResult<()> {
         fs::create_dir_all(path).await?;
@@ -116,13 +159,13 @@ impl FileStorage for LocalFileStorage {
     async fn store(&self, file: UploadedFile) -> StorageResult<StoredFile> {
         // Generate unique ID
         let id = Uuid::new_v4().to_string();
+        let file_path = self.get_file_path(&id, &file.filename)?;
 
         // Create directory structure
         let dir = self.get_file_directory(&id);
         self.ensure_directory(&dir).await?;
 
         // Write file to disk
-        let file_path = self.get_file_path(&id, &file.filename);
         let mut f = fs::File::create(&file_path).await?;
         f.write_all(&file.data).await?;
         f.flush().await?;
@@ -145,35 +188,57 @@ impl FileStorage for LocalFileStorage {
         Ok(stored)
     }
 
-    async fn retrieve(&self, id: &str) -> StorageResult<Vec<u8>> {
-        // We need to find the file by ID, but we don't know the filename
-        // List files in the ID directory and read the first non-hidden file
-        let dir = self.get_file_directory(id);
-
-        if !dir.exists() {
-            return Err(StorageError::NotFound(id.to_string()));
-        }
+    async fn store_stream(
+        &self,
+        filename: &str,
+        content_type: &str,
+        mut stream: ByteStream,
+    ) -> StorageResult<StoredFile> {
+        let id = Uuid::new_v4().to_string();
+        let file_path = self.get_file_path(&id, filename)?;
 
-        let mut entries = fs::read_dir(&dir).await?;
+        let dir = self.get_file_directory(&id);
+        self.ensure_directory(&dir).await?;
 
-        // Read the first non-hidden file in the directory
-        while let Some(entry) = entries.next_entry().await? {
-            let file_path = entry.path();
-            // Use async metadata check
-            if let Ok(metadata) = entry.metadata().await {
-                if metadata.is_file() {
-                    // Skip hidden files (like .metadata.json)
-                    if let Some(name) = file_path.file_name().and_then(|n| n.to_str()) {
-                        if !name.starts_with('.') {
-                            let data = fs::read(&file_path).await?;
-                            return Ok(data);
-                        }
-                    }
-                }
+        // Write chunks as they arrive; remove the partial file on failure
+        let mut f = fs::File::create(&file_path).await?;
+        let mut size: u64 = 0;
+        let written: StorageResult<()> = async {
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                f.write_all(&chunk).await?;
+                size += chunk.len() as u64;
             }
+            f.flush().await?;
+            Ok(())
         }
+        .await;
 
-        Err(StorageError::NotFound(id.to_string()))
+        if let Err(e) = written {
+            drop(f);
+            let _ = fs::remove_dir_all(&dir).await;
+            return Err(e);
+        }
+
+        let stored = StoredFile {
+            id: id.clone(),
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            size,
+            storage_path: file_path.to_string_lossy().to_string(),
+        };
+
+        let metadata_path = self.get_metadata_path(&id);
+        let metadata_json = serde_json::to_string_pretty(&stored)
+            .map_err(|e| StorageError::Other(format!("Failed to serialize metadata: {e}")))?;
+        fs::write(&metadata_path, metadata_json).await?;
+
+        Ok(stored)
+    }
+
+    async fn retrieve(&self, id: &str) -> StorageResult<Vec<u8>> {
+        let file_path = self.find_data_file(id).await?;
+        Ok(fs::read(&file_path).await?)
     }
 
     async fn delete(&self, id: &str) -> StorageResult<()> {
@@ -241,6 +306,11 @@ impl FileStorage for LocalFileStorage {
 
         Ok(stored)
     }
+
+    async fn last_modified(&self, id: &str) -> StorageResult<Option<SystemTime>> {
+        let file_path = self.find_data_file(id).await?;
+        Ok(fs::metadata(&file_path).await?.modified().ok())
+    }
 }
 
 #[cfg(test)]
@@ -400,6 +470,21 @@ mod tests {
         assert!(matches!(result.unwrap_err(), StorageError::NotFound(_)));
     }
 
+    #[tokio::test]
+    async fn test_last_modified() {
+        let (storage, _temp) = create_test_storage();
+
+        let before = SystemTime::now() - std::time::Duration::from_secs(1);
+        let file = UploadedFile::new("test.txt", "text/plain", b"Hello".to_vec());
+        let stored = storage.store(file).await.unwrap();
+
+        let modified = storage.last_modified(&stored.id).await.unwrap().unwrap();
+        assert!(modified >= before);
+
+        let result = storage.last_modified("nonexistent-id").await;
+        assert!(matches!(result.unwrap_err(), StorageError::NotFound(_)));
+    }
+
     #[tokio::test]
     async fn test_metadata_file_created() {
         let (storage, _temp) = create_test_storage();
@@ -418,4 +503,53 @@ mod tests {
         assert_eq!(metadata.filename, "test.txt");
         assert_eq!(metadata.content_type, "text/plain");
     }
+
+    #[tokio::test]
+    async fn test_presigned_url_defaults_to_none() {
+        let (storage, _temp) = create_test_storage();
+
+        let file = UploadedFile::new("test.txt", "text/plain", b"Hello".to_vec());
+        let stored = storage.store(file).await.unwrap();
+
+        let url = storage
+            .presigned_url(&stored.id, std::time::Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert!(url.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_traversing_filenames_stay_in_storage() {
+        let (storage, temp) = create_test_storage();
+
+        let stream = futures_util::stream::iter([Ok(bytes::Bytes::from_static(b"data"))]).boxed();
+        let stored = storage
+            .store_stream("../../escaped.txt", "text/plain", stream)
+            .await
+            .unwrap();
+        let prefix = &stored.id[..2];
+        let expected_path = temp.path().join(prefix).join(&stored.id).join("escaped.txt");
+        assert!(expected_path.exists());
+        assert!(!temp.path().join("escaped.txt").exists());
+        assert_eq!(storage.retrieve(&stored.id).await.unwrap(), b"data");
+
+        let file = UploadedFile::new("/tmp/absolute.txt", "text/plain", b"data".to_vec());
+        let stored = storage.store(file).await.unwrap();
+        assert!(stored.storage_path.starts_with(temp.path().to_str().unwrap()));
+        assert!(stored.storage_path.ends_with("absolute.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_filenames_are_rejected() {
+        let (storage, _temp) = create_test_storage();
+
+        for filename in ["", ".", "..", "../", ".metadata.json"] {
+            let stream = futures_util::stream::iter([Ok(bytes::Bytes::from_static(b"x"))]).boxed();
+            let result = storage.store_stream(filename, "text/plain", stream).await;
+            assert!(
+                matches!(result, Err(StorageError::InvalidPath(_))),
+                "{filename:?} should be rejected"
+            );
+        }
+    }
 }
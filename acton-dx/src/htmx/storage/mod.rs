@@ -2,7 +2,7 @@
 //!
 //! This module provides a trait-based abstraction for file storage with multiple backends:
 //! - Local filesystem storage (for development and small deployments)
-//! - S3-compatible storage (AWS S3, MinIO, etc.) - planned for Week 8
+//! - S3-compatible storage (AWS S3, MinIO, etc.) - requires the `s3` feature
 //! - Azure Blob Storage - planned for Week 8
 //!
 //! # Architecture
@@ -45,6 +45,8 @@
 mod local;
 pub mod policy;
 pub mod processing;
+#[cfg(feature = "s3")]
+mod s3;
 pub mod scanning;
 mod traits;
 mod types;
@@ -52,10 +54,14 @@ pub mod validation;
 
 pub use local::LocalFileStorage;
 pub use policy::{PolicyBuilder, UploadPolicy};
-pub use processing::ImageProcessor;
+pub use processing::{ImageProcessor, ProcessedImage, VariantSpec};
+#[cfg(feature = "s3")]
+pub use s3::S3FileStorage;
 pub use scanning::{ClamAvScanner, NoOpScanner, QuarantineScanner, ScanResult, VirusScanner};
 #[cfg(feature = "clamav")]
 pub use scanning::ClamAvConnection;
 pub use traits::FileStorage;
-pub use types::{StorageError, StorageResult, StoredFile, UploadedFile};
+#[cfg(test)]
+pub use traits::MockFileStorage;
+pub use types::{ByteStream, StorageError, StorageResult, StoredFile, UploadedFile};
 pub use validation::MimeValidator;
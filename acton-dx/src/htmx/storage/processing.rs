@@ -2,9 +2,11 @@
 //!
 //! This module provides utilities for processing uploaded images:
 //! - Thumbnail generation
+//! - Named size variants (e.g. `thumb`, `medium`) in one pass
 //! - Image resizing
 //! - Format conversion
 //! - EXIF metadata stripping (for privacy)
+//! - Upload sanitization: upright pixels, no metadata
 //!
 //! # Examples
 //!
@@ -31,13 +33,109 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Sanitizing Uploads
+//!
+//! Run [`ImageProcessor::sanitize`] after the upload policy accepts a file
+//! and before it reaches storage:
+//!
+//! ```rust,no_run
+//! use acton_htmx::storage::{FileStorage, LocalFileStorage, UploadPolicy, UploadedFile};
+//! use acton_htmx::storage::processing::ImageProcessor;
+//!
+//! # async fn example(
+//! #     storage: LocalFileStorage,
+//! #     policy: UploadPolicy,
+//! #     mut file: UploadedFile,
+//! #     usage: u64,
+//! # ) -> Result<(), Box<dyn std::error::Error>> {
+//! policy.allows_upload(file.size(), &file.content_type, usage)?;
+//! file.data = ImageProcessor::new().sanitize(&file.data)?;
+//! let stored = storage.store(file).await?;
+//! # Ok(())
+//! # }
+//! ```
 
 use super::types::{StorageError, StorageResult, UploadedFile};
 use image::{
-    imageops::FilterType, DynamicImage, ImageFormat, ImageReader,
+    codecs::{gif::GifDecoder, jpeg::JpegEncoder},
+    imageops::FilterType,
+    AnimationDecoder, DynamicImage, ImageDecoder, ImageFormat, ImageReader,
 };
 use std::io::Cursor;
 
+/// JPEG quality used when re-encoding sanitized uploads
+const SANITIZE_JPEG_QUALITY: u8 = 90;
+
+/// A named output size for [`ImageProcessor::generate_variants`]
+///
+/// The image is scaled down, keeping its aspect ratio, until its longest
+/// side is at most `max_dim` pixels. Images already within bounds are not
+/// upscaled.
+///
+/// # Examples
+///
+/// ```rust
+/// use acton_htmx::storage::processing::VariantSpec;
+/// use image::ImageFormat;
+///
+/// let specs = [
+///     VariantSpec::new("thumb", 128).with_format(ImageFormat::WebP),
+///     VariantSpec::new("medium", 512),
+/// ];
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantSpec {
+    /// Variant name, used to derive storage keys (e.g. `thumb`)
+    pub name: String,
+    /// Maximum length of the longest side in pixels
+    pub max_dim: u32,
+    /// Output format; `None` keeps the source format
+    pub format: Option<ImageFormat>,
+}
+
+impl VariantSpec {
+    /// Creates a variant that keeps the source format
+    #[must_use]
+    pub fn new(name: impl Into<String>, max_dim: u32) -> Self {
+        Self {
+            name: name.into(),
+            max_dim,
+            format: None,
+        }
+    }
+
+    /// Converts the variant to `format` (e.g. `ImageFormat::WebP`)
+    #[must_use]
+    pub const fn with_format(mut self, format: ImageFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+/// An image variant produced by [`ImageProcessor::generate_variants`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessedImage {
+    /// Name of the [`VariantSpec`] that produced this image
+    pub name: String,
+    /// Encoded image data
+    pub data: Vec<u8>,
+    /// MIME type of `data`
+    pub content_type: String,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+}
+
+impl ProcessedImage {
+    /// File extension matching the encoded format (e.g. `webp`)
+    #[must_use]
+    pub fn extension(&self) -> &'static str {
+        ImageFormat::from_mime_type(&self.content_type).map_or("bin", format_extension)
+    }
+}
+
 /// Image processing utilities
 ///
 /// Provides methods for common image operations like resizing,
@@ -270,6 +368,139 @@ impl ImageProcessor {
         })
     }
 
+    /// Generates named size variants of an image
+    ///
+    /// The image is decoded once and each [`VariantSpec`] is scaled to fit its
+    /// `max_dim` (see [`VariantSpec`]) and encoded in its requested format,
+    /// or the source format if none is given. Variants are returned in the
+    /// order of `specs`, ready to be stored under keys derived from their
+    /// names (e.g. `{id}/thumb.webp`).
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `data` is not a valid image or a variant cannot be
+    /// encoded in its format
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use acton_htmx::storage::processing::{ImageProcessor, VariantSpec};
+    /// use image::ImageFormat;
+    ///
+    /// # fn example(data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    /// let processor = ImageProcessor::new();
+    /// let variants = processor.generate_variants(
+    ///     data,
+    ///     &[
+    ///         VariantSpec::new("thumb", 128).with_format(ImageFormat::WebP),
+    ///         VariantSpec::new("medium", 512),
+    ///     ],
+    /// )?;
+    ///
+    /// for variant in &variants {
+    ///     println!("{}: {}x{}", variant.name, variant.width, variant.height);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generate_variants(
+        &self,
+        data: &[u8],
+        specs: &[VariantSpec],
+    ) -> StorageResult<Vec<ProcessedImage>> {
+        let reader = ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .map_err(|e| StorageError::Other(format!("Failed to read image: {e}")))?;
+        let source_format = reader
+            .format()
+            .ok_or_else(|| StorageError::Other("Unrecognized image format".to_string()))?;
+        let img = reader
+            .decode()
+            .map_err(|e| StorageError::Other(format!("Failed to decode image: {e}")))?;
+
+        specs
+            .iter()
+            .map(|spec| {
+                let variant = if img.width().max(img.height()) > spec.max_dim {
+                    img.resize(spec.max_dim, spec.max_dim, self.filter)
+                } else {
+                    img.clone()
+                };
+                let format = spec.format.unwrap_or(source_format);
+
+                Ok(ProcessedImage {
+                    name: spec.name.clone(),
+                    data: Self::encode_image(&variant, format)?,
+                    content_type: format.to_mime_type().to_string(),
+                    width: variant.width(),
+                    height: variant.height(),
+                })
+            })
+            .collect()
+    }
+
+    /// Sanitizes an uploaded image for storage
+    ///
+    /// Decodes the image, applies its EXIF orientation so the stored pixels
+    /// are upright, and re-encodes it in the original format without any
+    /// metadata (EXIF, GPS, ICC, XMP). PNG, GIF, and WebP are re-encoded
+    /// losslessly; JPEG is re-encoded at quality 90.
+    ///
+    /// Animated GIFs are returned unchanged: re-encoding would keep only the
+    /// first frame, and GIF has no EXIF orientation to apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if `data` is not a valid image or cannot be re-encoded
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use acton_htmx::storage::{UploadedFile, processing::ImageProcessor};
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut file = UploadedFile::new("photo.jpg", "image/jpeg", vec![/* ... */]);
+    /// file.data = ImageProcessor::new().sanitize(&file.data)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sanitize(&self, data: &[u8]) -> StorageResult<Vec<u8>> {
+        let reader = ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .map_err(|e| StorageError::Other(format!("Failed to read image: {e}")))?;
+        let format = reader
+            .format()
+            .ok_or_else(|| StorageError::Other("Unrecognized image format".to_string()))?;
+
+        if format == ImageFormat::Gif && is_animated_gif(data) {
+            return Ok(data.to_vec());
+        }
+
+        let mut decoder = reader
+            .into_decoder()
+            .map_err(|e| StorageError::Other(format!("Failed to decode image: {e}")))?;
+        let orientation = decoder
+            .orientation()
+            .map_err(|e| StorageError::Other(format!("Failed to read orientation: {e}")))?;
+        let mut img = DynamicImage::from_decoder(decoder)
+            .map_err(|e| StorageError::Other(format!("Failed to decode image: {e}")))?;
+        img.apply_orientation(orientation);
+
+        if format == ImageFormat::Jpeg {
+            let mut buffer = Vec::new();
+            // JPEG has no alpha channel
+            DynamicImage::ImageRgb8(img.to_rgb8())
+                .write_with_encoder(JpegEncoder::new_with_quality(
+                    &mut buffer,
+                    SANITIZE_JPEG_QUALITY,
+                ))
+                .map_err(|e| StorageError::Other(format!("Failed to encode image: {e}")))?;
+            return Ok(buffer);
+        }
+
+        Self::encode_image(&img, format)
+    }
+
     /// Strips EXIF metadata from an image for privacy
     ///
     /// Removes all EXIF metadata (location, camera info, etc.) from an image.
@@ -341,6 +572,12 @@ impl ImageProcessor {
     }
 }
 
+/// Whether GIF data holds more than one frame
+fn is_animated_gif(data: &[u8]) -> bool {
+    GifDecoder::new(Cursor::new(data))
+        .is_ok_and(|decoder| decoder.into_frames().take(2).count() > 1)
+}
+
 /// Helper function to get file extension for image format
 const fn format_extension(format: ImageFormat) -> &'static str {
     match format {
@@ -359,7 +596,7 @@ const fn format_extension(format: ImageFormat) -> &'static str {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use image::{ImageBuffer, Rgb};
+    use image::{GenericImageView, ImageBuffer, Rgb};
 
     /// Helper to create a test PNG image
     fn create_test_png(width: u32, height: u32) -> Vec<u8> {
@@ -437,6 +674,211 @@ mod tests {
         assert!(height <= 50);
     }
 
+    fn variant_specs() -> [VariantSpec; 2] {
+        [
+            VariantSpec::new("thumb", 128),
+            VariantSpec::new("medium", 512),
+        ]
+    }
+
+    #[test]
+    fn test_variants_landscape_respect_max_dim() {
+        let processor = ImageProcessor::new();
+        let variants = processor
+            .generate_variants(&create_test_png(1024, 640), &variant_specs())
+            .unwrap();
+
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[0].name, "thumb");
+        assert_eq!((variants[0].width, variants[0].height), (128, 80));
+        assert_eq!(variants[1].name, "medium");
+        assert_eq!((variants[1].width, variants[1].height), (512, 320));
+
+        for variant in &variants {
+            let file = UploadedFile::new("v.png", variant.content_type.clone(), variant.data.clone());
+            assert_eq!(
+                processor.get_dimensions(&file).unwrap(),
+                (variant.width, variant.height)
+            );
+        }
+    }
+
+    #[test]
+    fn test_variants_portrait_respect_max_dim() {
+        let processor = ImageProcessor::new();
+        let variants = processor
+            .generate_variants(&create_test_png(300, 1200), &variant_specs())
+            .unwrap();
+
+        assert_eq!((variants[0].width, variants[0].height), (32, 128));
+        assert_eq!((variants[1].width, variants[1].height), (128, 512));
+        assert!(variants.iter().all(|v| v.content_type == "image/png"));
+    }
+
+    #[test]
+    fn test_variants_do_not_upscale() {
+        let processor = ImageProcessor::new();
+        let variants = processor
+            .generate_variants(&create_test_png(100, 60), &variant_specs())
+            .unwrap();
+
+        assert!(variants.iter().all(|v| (v.width, v.height) == (100, 60)));
+    }
+
+    #[test]
+    fn test_variants_convert_to_webp() {
+        let processor = ImageProcessor::new();
+        let specs = [VariantSpec::new("thumb", 64).with_format(ImageFormat::WebP)];
+        let variants = processor
+            .generate_variants(&create_test_png(200, 100), &specs)
+            .unwrap();
+
+        assert_eq!(variants[0].content_type, "image/webp");
+        assert_eq!(variants[0].extension(), "webp");
+        assert_eq!(
+            image::guess_format(&variants[0].data).unwrap(),
+            ImageFormat::WebP
+        );
+        assert_eq!((variants[0].width, variants[0].height), (64, 32));
+    }
+
+    #[test]
+    fn test_variants_invalid_image() {
+        let processor = ImageProcessor::new();
+        assert!(processor
+            .generate_variants(b"not an image", &variant_specs())
+            .is_err());
+    }
+
+    /// Big-endian TIFF block holding only an orientation tag
+    fn exif_orientation(orientation: u16) -> Vec<u8> {
+        let mut exif = b"MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+        exif.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1]);
+        exif.extend_from_slice(&orientation.to_be_bytes());
+        exif.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        exif
+    }
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        !bytes.iter().fold(!0_u32, |crc, &byte| {
+            (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+                (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+            })
+        })
+    }
+
+    /// 4x2 red PNG with a green marker at (0, 0) and an `eXIf` orientation
+    fn oriented_png(orientation: u16) -> Vec<u8> {
+        let img: ImageBuffer<Rgb<u8>, Vec<u8>> = ImageBuffer::from_fn(4, 2, |x, y| {
+            if (x, y) == (0, 0) { Rgb([0, 255, 0]) } else { Rgb([255, 0, 0]) }
+        });
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        // Insert the eXIf chunk right after the signature and IHDR chunk
+        let exif = exif_orientation(orientation);
+        let mut chunk = u32::try_from(exif.len()).unwrap().to_be_bytes().to_vec();
+        let body = [b"eXIf".as_slice(), &exif].concat();
+        chunk.extend_from_slice(&body);
+        chunk.extend_from_slice(&crc32(&body).to_be_bytes());
+        png.splice(33..33, chunk);
+        png
+    }
+
+    fn decode_sanitized(data: &[u8]) -> (DynamicImage, Option<Vec<u8>>) {
+        let mut decoder = ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .unwrap()
+            .into_decoder()
+            .unwrap();
+        let exif = decoder.exif_metadata().unwrap();
+        (DynamicImage::from_decoder(decoder).unwrap(), exif)
+    }
+
+    fn green_marker(img: &DynamicImage) -> (u32, u32) {
+        let rgb = img.to_rgb8();
+        let (x, y, _) = rgb
+            .enumerate_pixels()
+            .find(|(_, _, pixel)| pixel.0 == [0, 255, 0])
+            .unwrap();
+        (x, y)
+    }
+
+    #[test]
+    fn test_sanitize_orientation_3_rotates_180() {
+        let fixture = oriented_png(3);
+        let (_, exif) = decode_sanitized(&fixture);
+        assert!(exif.is_some());
+
+        let (img, exif) = decode_sanitized(&ImageProcessor::new().sanitize(&fixture).unwrap());
+
+        assert!(exif.is_none());
+        assert_eq!(img.dimensions(), (4, 2));
+        assert_eq!(green_marker(&img), (3, 1));
+    }
+
+    #[test]
+    fn test_sanitize_orientation_6_rotates_90_clockwise() {
+        let sanitized = ImageProcessor::new().sanitize(&oriented_png(6)).unwrap();
+        let (img, exif) = decode_sanitized(&sanitized);
+
+        assert!(exif.is_none());
+        assert_eq!(image::guess_format(&sanitized).unwrap(), ImageFormat::Png);
+        assert_eq!(img.dimensions(), (2, 4));
+        assert_eq!(green_marker(&img), (1, 0));
+    }
+
+    #[test]
+    fn test_sanitize_orientation_8_rotates_90_counter_clockwise() {
+        let sanitized = ImageProcessor::new().sanitize(&oriented_png(8)).unwrap();
+        let (img, exif) = decode_sanitized(&sanitized);
+
+        assert!(exif.is_none());
+        assert_eq!(img.dimensions(), (2, 4));
+        assert_eq!(green_marker(&img), (0, 3));
+    }
+
+    #[test]
+    fn test_sanitize_jpeg_with_exif_app1() {
+        let mut jpeg = Vec::new();
+        DynamicImage::ImageRgb8(ImageBuffer::from_pixel(40, 20, Rgb([200, 10, 10])))
+            .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+            .unwrap();
+
+        let payload = [b"Exif\0\0".as_slice(), &exif_orientation(6)].concat();
+        let mut app1 = vec![0xFF, 0xE1];
+        app1.extend_from_slice(&u16::try_from(payload.len() + 2).unwrap().to_be_bytes());
+        app1.extend_from_slice(&payload);
+        jpeg.splice(2..2, app1);
+
+        let sanitized = ImageProcessor::new().sanitize(&jpeg).unwrap();
+        let (img, exif) = decode_sanitized(&sanitized);
+
+        assert!(exif.is_none());
+        assert_eq!(image::guess_format(&sanitized).unwrap(), ImageFormat::Jpeg);
+        assert_eq!(img.dimensions(), (20, 40));
+    }
+
+    #[test]
+    fn test_sanitize_passes_animated_gif_through() {
+        use image::{codecs::gif::GifEncoder, Frame, Rgba, RgbaImage};
+
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            encoder
+                .encode_frames([
+                    Frame::new(RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 255]))),
+                    Frame::new(RgbaImage::from_pixel(4, 4, Rgba([0, 0, 255, 255]))),
+                ])
+                .unwrap();
+        }
+
+        assert_eq!(ImageProcessor::new().sanitize(&gif).unwrap(), gif);
+    }
+
     #[test]
     fn test_invalid_image() {
         let file = UploadedFile::new("test.png", "image/png", b"not an image".to_vec());
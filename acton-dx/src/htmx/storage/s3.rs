@@ -0,0 +1,575 @@
+//! S3-compatible storage implementation
+//!
+//! Works with AWS S3 and S3-compatible services such as MinIO. Requires the
+//! `s3` feature to be enabled.
+
+use super::traits::FileStorage;
+use super::types::{StorageError, StorageResult, StoredFile, UploadedFile};
+use async_trait::async_trait;
+use aws_sdk_s3::config::Region;
+use aws_sdk_s3::error::{DisplayErrorContext, SdkError};
+use aws_sdk_s3::operation::get_object::GetObjectError;
+use aws_sdk_s3::operation::head_object::HeadObjectError;
+use aws_sdk_s3::presigning::PresigningConfig;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use aws_sdk_s3::Client;
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+/// Default size above which uploads use S3 multipart upload (8 MiB)
+const DEFAULT_MULTIPART_THRESHOLD: usize = 8 * 1024 * 1024;
+
+/// Minimum part size accepted by S3 for all but the last part (5 MiB)
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// Object metadata key holding the original filename
+const FILENAME_METADATA_KEY: &str = "filename";
+
+/// S3-compatible storage backend
+///
+/// Stores each file as a single object whose key is the configured key prefix
+/// followed by the file ID. The original filename is kept in the object's
+/// user metadata, so [`FileStorage::get_metadata`] works without a sidecar
+/// object. Uploads larger than the multipart threshold are sent with S3
+/// multipart upload.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "s3")]
+/// # {
+/// use acton_htmx::storage::{FileStorage, S3FileStorage, UploadedFile};
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// // AWS S3 (credentials from the default AWS provider chain)
+/// let storage = S3FileStorage::new("my-uploads", "us-east-1", None).await?;
+///
+/// // MinIO or another S3-compatible service
+/// let minio = S3FileStorage::new(
+///     "uploads",
+///     "us-east-1",
+///     Some("http://localhost:9000".to_string()),
+/// )
+/// .await?
+/// .with_key_prefix("avatars/");
+///
+/// let file = UploadedFile::new("photo.jpg", "image/jpeg", vec![/* ... */]);
+/// let stored = storage.store(file).await?;
+/// let data = storage.retrieve(&stored.id).await?;
+/// storage.delete(&stored.id).await?;
+/// # Ok(())
+/// # }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct S3FileStorage {
+    /// S3 client
+    client: Client,
+    /// Bucket name
+    bucket: String,
+    /// Custom endpoint (MinIO, etc.); `None` for AWS S3
+    endpoint: Option<String>,
+    /// Prefix prepended to every object key
+    key_prefix: String,
+    /// Uploads larger than this many bytes use multipart upload
+    multipart_threshold: usize,
+}
+
+impl S3FileStorage {
+    /// Creates a new S3 storage backend
+    ///
+    /// Credentials are loaded from the default AWS provider chain
+    /// (environment variables, `~/.aws/credentials`, instance profile).
+    /// When `endpoint` is set, path-style addressing is used so that
+    /// S3-compatible services such as MinIO work out of the box.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Other` if the bucket name is empty
+    pub async fn new(
+        bucket: impl Into<String>,
+        region: impl Into<String>,
+        endpoint: Option<String>,
+    ) -> StorageResult<Self> {
+        let bucket = bucket.into();
+        if bucket.is_empty() {
+            return Err(StorageError::Other("S3 bucket name must not be empty".to_string()));
+        }
+
+        let sdk_config = aws_config::defaults(aws_config::BehaviorVersion::latest())
+            .region(Region::new(region.into()))
+            .load()
+            .await;
+
+        let mut builder = aws_sdk_s3::config::Builder::from(&sdk_config);
+        if let Some(endpoint) = &endpoint {
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        }
+
+        Ok(Self {
+            client: Client::from_conf(builder.build()),
+            bucket,
+            endpoint,
+            key_prefix: String::new(),
+            multipart_threshold: DEFAULT_MULTIPART_THRESHOLD,
+        })
+    }
+
+    /// Creates a storage backend from an existing S3 client
+    ///
+    /// `endpoint` should match the endpoint the client was configured with;
+    /// it is only used to build public URLs.
+    #[must_use]
+    pub fn from_client(client: Client, bucket: impl Into<String>, endpoint: Option<String>) -> Self {
+        Self {
+            client,
+            bucket: bucket.into(),
+            endpoint,
+            key_prefix: String::new(),
+            multipart_threshold: DEFAULT_MULTIPART_THRESHOLD,
+        }
+    }
+
+    /// Sets a prefix prepended to every object key (e.g., `"uploads/"`)
+    #[must_use]
+    pub fn with_key_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.key_prefix = prefix.into();
+        self
+    }
+
+    /// Sets the size in bytes above which uploads use multipart upload
+    ///
+    /// Parts are never smaller than the 5 MiB minimum S3 accepts.
+    #[must_use]
+    pub const fn with_multipart_threshold(mut self, bytes: usize) -> Self {
+        self.multipart_threshold = bytes;
+        self
+    }
+
+    /// Returns the bucket name
+    #[must_use]
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// Returns the underlying S3 client
+    #[must_use]
+    pub const fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Maps a file ID to its object key
+    ///
+    /// Rejects IDs that could escape the key prefix.
+    fn object_key(&self, id: &str) -> StorageResult<String> {
+        if id.is_empty() || id.starts_with('/') || id.contains("..") || id.contains('\\') {
+            return Err(StorageError::InvalidPath(format!("Invalid file ID: {id}")));
+        }
+        Ok(format!("{}{id}", self.key_prefix))
+    }
+
+    /// Builds the public URL of an object
+    fn public_url(&self, key: &str) -> String {
+        self.endpoint.as_deref().map_or_else(
+            || {
+                let region = self
+                    .client
+                    .config()
+                    .region()
+                    .map_or("us-east-1", Region::as_ref);
+                format!("https://{}.s3.{region}.amazonaws.com/{key}", self.bucket)
+            },
+            |endpoint| format!("{}/{}/{key}", endpoint.trim_end_matches('/'), self.bucket),
+        )
+    }
+
+    /// Part size used for multipart uploads
+    fn part_size(&self) -> usize {
+        self.multipart_threshold.max(MIN_PART_SIZE)
+    }
+
+    /// Uploads `data` in parts, aborting the upload on failure
+    async fn multipart_upload(&self, key: &str, file: &UploadedFile) -> StorageResult<()> {
+        let upload = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(&file.content_type)
+            .metadata(FILENAME_METADATA_KEY, metadata_filename(&file.filename))
+            .send()
+            .await
+            .map_err(|e| sdk_error("create multipart upload", e))?;
+
+        let upload_id = upload
+            .upload_id()
+            .ok_or_else(|| StorageError::Other("S3 returned no multipart upload ID".to_string()))?;
+
+        match self.upload_parts(key, upload_id, &file.data).await {
+            Ok(parts) => {
+                self.client
+                    .complete_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .multipart_upload(
+                        CompletedMultipartUpload::builder()
+                            .set_parts(Some(parts))
+                            .build(),
+                    )
+                    .send()
+                    .await
+                    .map_err(|e| sdk_error("complete multipart upload", e))?;
+                Ok(())
+            }
+            Err(error) => {
+                // Best effort: uncompleted uploads otherwise accrue storage costs
+                if let Err(abort_error) = self
+                    .client
+                    .abort_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .send()
+                    .await
+                {
+                    tracing::warn!(
+                        key,
+                        error = %DisplayErrorContext(abort_error),
+                        "Failed to abort S3 multipart upload"
+                    );
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Uploads each chunk of `data` as one part
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        data: &[u8],
+    ) -> StorageResult<Vec<CompletedPart>> {
+        let mut parts = Vec::new();
+        for (index, chunk) in data.chunks(self.part_size()).enumerate() {
+            let part_number = i32::try_from(index + 1)
+                .map_err(|_| StorageError::Other("Too many multipart upload parts".to_string()))?;
+
+            let part = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(ByteStream::from(chunk.to_vec()))
+                .send()
+                .await
+                .map_err(|e| sdk_error("upload part", e))?;
+
+            parts.push(
+                CompletedPart::builder()
+                    .set_e_tag(part.e_tag().map(ToString::to_string))
+                    .part_number(part_number)
+                    .build(),
+            );
+        }
+        Ok(parts)
+    }
+}
+
+#[async_trait]
+impl FileStorage for S3FileStorage {
+    async fn store(&self, file: UploadedFile) -> StorageResult<StoredFile> {
+        let id = Uuid::new_v4().to_string();
+        let key = self.object_key(&id)?;
+
+        if file.data.len() > self.multipart_threshold {
+            self.multipart_upload(&key, &file).await?;
+        } else {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(&key)
+                .content_type(&file.content_type)
+                .metadata(FILENAME_METADATA_KEY, metadata_filename(&file.filename))
+                .body(ByteStream::from(file.data.clone()))
+                .send()
+                .await
+                .map_err(|e| sdk_error("put object", e))?;
+        }
+
+        Ok(StoredFile {
+            id,
+            filename: file.filename.clone(),
+            content_type: file.content_type.clone(),
+            size: file.size(),
+            storage_path: key,
+        })
+    }
+
+    async fn retrieve(&self, id: &str) -> StorageResult<Vec<u8>> {
+        let key = self.object_key(id)?;
+
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.as_service_error().is_some_and(GetObjectError::is_no_such_key) {
+                    StorageError::NotFound(id.to_string())
+                } else {
+                    sdk_error("get object", e)
+                }
+            })?;
+
+        let data = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| StorageError::Other(format!("Failed to read S3 object body: {e}")))?;
+
+        Ok(data.into_bytes().to_vec())
+    }
+
+    async fn delete(&self, id: &str) -> StorageResult<()> {
+        let key = self.object_key(id)?;
+
+        // S3 deletes are idempotent: missing keys succeed
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await
+            .map_err(|e| sdk_error("delete object", e))?;
+
+        Ok(())
+    }
+
+    async fn url(&self, id: &str) -> StorageResult<String> {
+        let key = self.object_key(id)?;
+
+        if !self.exists(id).await? {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+
+        Ok(self.public_url(&key))
+    }
+
+    async fn exists(&self, id: &str) -> StorageResult<bool> {
+        let key = self.object_key(id)?;
+
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(HeadObjectError::is_not_found) => Ok(false),
+            Err(e) => Err(sdk_error("head object", e)),
+        }
+    }
+
+    async fn get_metadata(&self, id: &str) -> StorageResult<StoredFile> {
+        let key = self.object_key(id)?;
+
+        let output = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.as_service_error().is_some_and(HeadObjectError::is_not_found) {
+                    StorageError::NotFound(id.to_string())
+                } else {
+                    sdk_error("head object", e)
+                }
+            })?;
+
+        let filename = output
+            .metadata()
+            .and_then(|metadata| metadata.get(FILENAME_METADATA_KEY))
+            .cloned()
+            .unwrap_or_else(|| id.to_string());
+
+        Ok(StoredFile {
+            id: id.to_string(),
+            filename,
+            content_type: output
+                .content_type()
+                .unwrap_or("application/octet-stream")
+                .to_string(),
+            size: output
+                .content_length()
+                .and_then(|len| u64::try_from(len).ok())
+                .unwrap_or_default(),
+            storage_path: key,
+        })
+    }
+
+    async fn presigned_url(&self, id: &str, expires_in: Duration) -> StorageResult<Option<String>> {
+        let key = self.object_key(id)?;
+
+        let presigning = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| StorageError::Other(format!("Invalid presigned URL expiry: {e}")))?;
+
+        let request = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .presigned(presigning)
+            .await
+            .map_err(|e| sdk_error("presign get object", e))?;
+
+        Ok(Some(request.uri().to_string()))
+    }
+
+    async fn last_modified(&self, id: &str) -> StorageResult<Option<SystemTime>> {
+        let key = self.object_key(id)?;
+
+        let output = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.as_service_error().is_some_and(HeadObjectError::is_not_found) {
+                    StorageError::NotFound(id.to_string())
+                } else {
+                    sdk_error("head object", e)
+                }
+            })?;
+
+        Ok(output
+            .last_modified()
+            .and_then(|modified| SystemTime::try_from(*modified).ok()))
+    }
+}
+
+/// Converts an SDK error into a `StorageError`
+fn sdk_error<E, R>(operation: &str, error: SdkError<E, R>) -> StorageError
+where
+    E: std::error::Error + Send + Sync + 'static,
+    R: std::fmt::Debug,
+{
+    StorageError::Other(format!("S3 {operation} failed: {}", DisplayErrorContext(error)))
+}
+
+/// Makes a filename safe for S3 user metadata, which must be ASCII
+fn metadata_filename(filename: &str) -> String {
+    filename
+        .chars()
+        .map(|c| if c.is_ascii_graphic() || c == ' ' { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_s3::config::BehaviorVersion;
+
+    fn test_storage(endpoint: Option<&str>) -> S3FileStorage {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("eu-west-1"))
+            .build();
+        S3FileStorage::from_client(
+            Client::from_conf(config),
+            "uploads",
+            endpoint.map(ToString::to_string),
+        )
+    }
+
+    #[test]
+    fn test_object_key_uses_prefix() {
+        let storage = test_storage(None).with_key_prefix("files/");
+        assert_eq!(
+            storage.object_key("550e8400-e29b-41d4-a716-446655440000").unwrap(),
+            "files/550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
+    #[test]
+    fn test_object_key_rejects_traversal() {
+        let storage = test_storage(None);
+        assert!(matches!(storage.object_key(""), Err(StorageError::InvalidPath(_))));
+        assert!(matches!(storage.object_key("../secret"), Err(StorageError::InvalidPath(_))));
+        assert!(matches!(storage.object_key("/abs"), Err(StorageError::InvalidPath(_))));
+        assert!(matches!(storage.object_key("a\\b"), Err(StorageError::InvalidPath(_))));
+    }
+
+    #[test]
+    fn test_public_url_aws() {
+        let storage = test_storage(None);
+        assert_eq!(
+            storage.public_url("abc"),
+            "https://uploads.s3.eu-west-1.amazonaws.com/abc"
+        );
+    }
+
+    #[test]
+    fn test_public_url_custom_endpoint() {
+        let storage = test_storage(Some("http://localhost:9000/"));
+        assert_eq!(storage.public_url("abc"), "http://localhost:9000/uploads/abc");
+    }
+
+    #[test]
+    fn test_part_size_respects_s3_minimum() {
+        let storage = test_storage(None).with_multipart_threshold(1024);
+        assert_eq!(storage.part_size(), MIN_PART_SIZE);
+
+        let storage = storage.with_multipart_threshold(16 * 1024 * 1024);
+        assert_eq!(storage.part_size(), 16 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_metadata_filename_is_ascii() {
+        assert_eq!(metadata_filename("report 2024.pdf"), "report 2024.pdf");
+        assert_eq!(metadata_filename("café.png"), "caf_.png");
+    }
+
+    #[tokio::test]
+    async fn test_presigned_url_is_signed_get() {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("eu-west-1"))
+            .credentials_provider(aws_sdk_s3::config::Credentials::new(
+                "AKIDEXAMPLE",
+                "secret",
+                None,
+                None,
+                "test",
+            ))
+            .build();
+        let storage = S3FileStorage::from_client(Client::from_conf(config), "uploads", None);
+
+        let url = storage
+            .presigned_url("abc", Duration::from_secs(300))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(url.starts_with("https://uploads.s3.eu-west-1.amazonaws.com/abc?"));
+        assert!(url.contains("X-Amz-Expires=300"));
+        assert!(url.contains("X-Amz-Signature="));
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_empty_bucket() {
+        assert!(S3FileStorage::new("", "us-east-1", None).await.is_err());
+    }
+}
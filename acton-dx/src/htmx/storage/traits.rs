@@ -1,7 +1,9 @@
 //! File storage trait definitions
 
-use super::types::{StorageResult, StoredFile, UploadedFile};
+use super::types::{ByteStream, StorageResult, StoredFile, UploadedFile};
 use async_trait::async_trait;
+use futures_util::StreamExt;
+use std::time::{Duration, SystemTime};
 
 /// Abstraction for file storage backends
 ///
@@ -80,6 +82,35 @@ pub trait FileStorage: Send + Sync {
     /// ```
     async fn store(&self, file: UploadedFile) -> StorageResult<StoredFile>;
 
+    /// Stores a file from a stream of chunks
+    ///
+    /// Lets large uploads (see
+    /// [`StreamingFileUpload`](crate::htmx::extractors::StreamingFileUpload))
+    /// reach the backend without holding the whole file in memory. The first
+    /// error yielded by the stream aborts the store and is returned as-is.
+    ///
+    /// The default implementation collects the stream into memory and calls
+    /// [`store`](Self::store); backends that can write incrementally should
+    /// override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The stream yields an error (e.g., the upload exceeded its size limit)
+    /// - Storing the collected file fails
+    async fn store_stream(
+        &self,
+        filename: &str,
+        content_type: &str,
+        mut stream: ByteStream,
+    ) -> StorageResult<StoredFile> {
+        let mut data = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            data.extend_from_slice(&chunk?);
+        }
+        self.store(UploadedFile::new(filename, content_type, data)).await
+    }
+
     /// Retrieves file data by ID
     ///
     /// # Errors
@@ -201,4 +232,55 @@ pub trait FileStorage: Send + Sync {
     /// # }
     /// ```
     async fn get_metadata(&self, id: &str) -> StorageResult<StoredFile>;
+
+    /// Returns a short-lived URL granting direct read access to the file
+    ///
+    /// Backends that can sign URLs (such as S3) return `Some(url)` valid for
+    /// `expires_in`, letting clients download private files straight from the
+    /// backend instead of proxying bytes through the application. Backends
+    /// without signing support return `None`, which is the default; callers
+    /// should then fall back to serving the file themselves.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The file ID is invalid
+    /// - URL signing fails (e.g., missing credentials)
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use acton_htmx::storage::{FileStorage, LocalFileStorage};
+    /// # use std::path::PathBuf;
+    /// # use std::time::Duration;
+    /// # async fn example() -> anyhow::Result<()> {
+    /// # let storage = LocalFileStorage::new(PathBuf::from("/tmp"))?;
+    /// let id = "550e8400-e29b-41d4-a716-446655440000";
+    /// match storage.presigned_url(id, Duration::from_secs(300)).await? {
+    ///     Some(url) => println!("Download directly from: {url}"),
+    ///     None => println!("Serve through the application"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn presigned_url(&self, id: &str, expires_in: Duration) -> StorageResult<Option<String>> {
+        let _ = (id, expires_in);
+        Ok(None)
+    }
+
+    /// Returns when the file's content last changed
+    ///
+    /// Used for `Last-Modified` and `If-Modified-Since` when serving files.
+    /// Backends that don't track modification times return `None`, which is
+    /// the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The file doesn't exist (`StorageError::NotFound`)
+    /// - The storage backend is unavailable
+    async fn last_modified(&self, id: &str) -> StorageResult<Option<SystemTime>> {
+        let _ = id;
+        Ok(None)
+    }
 }
@@ -1,5 +1,7 @@
 //! Core types for file storage
 
+use bytes::Bytes;
+use futures_util::stream::BoxStream;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use thiserror::Error;
@@ -49,6 +51,11 @@ pub enum StorageError {
 /// Result type for storage operations
 pub type StorageResult<T> = Result<T, StorageError>;
 
+/// Stream of file data chunks passed to [`FileStorage::store_stream`]
+///
+/// [`FileStorage::store_stream`]: super::FileStorage::store_stream
+pub type ByteStream = BoxStream<'static, StorageResult<Bytes>>;
+
 /// A file that has been uploaded but not yet stored
 ///
 /// This represents the in-memory state of an uploaded file before it's
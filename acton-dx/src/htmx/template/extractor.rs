@@ -76,13 +76,9 @@ pub fn extract_by_id<'a>(html: &'a str, id: &str) -> Option<Cow<'a, str>> {
     let pattern_single = format!(r"<div id='{id}'");
 
     // Find the start tag
-    let start_pos = if let Some(pos) = html.find(&pattern_double) {
-        pos
-    } else if let Some(pos) = html.find(&pattern_single) {
-        pos
-    } else {
-        return None;
-    };
+    let start_pos = html
+        .find(&pattern_double)
+        .or_else(|| html.find(&pattern_single))?;
 
     // Find the end of the opening tag (>)
     let tag_start = &html[start_pos..];
@@ -119,6 +115,103 @@ pub fn extract_by_id<'a>(html: &'a str, id: &str) -> Option<Cow<'a, str>> {
     None
 }
 
+/// HTML elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source",
+    "track", "wbr",
+];
+
+/// Extract an element, including its own tags, by its `id` attribute
+///
+/// Unlike [`extract_by_id`], this works for any tag (`<table>`, `<tr>`,
+/// `<section>`, ...) and returns the element itself rather than its inner
+/// content, so the result can replace the element with an `outerHTML` swap.
+/// Nested elements with the same tag name are matched by depth. Expects
+/// well-formed HTML.
+///
+/// # Examples
+///
+/// ```rust
+/// use acton_htmx::template::extractor::extract_element_by_id;
+///
+/// let html = r#"
+/// <main>
+///     <table id="results"><tr><td>1</td></tr></table>
+/// </main>
+/// "#;
+///
+/// let table = extract_element_by_id(html, "results");
+/// assert_eq!(table, Some(r#"<table id="results"><tr><td>1</td></tr></table>"#));
+/// ```
+#[must_use]
+pub fn extract_element_by_id<'a>(html: &'a str, id: &str) -> Option<&'a str> {
+    let start = find_id_attribute(html, id)?;
+
+    let open_end = start + html[start..].find('>')? + 1;
+    let tag_name: String = html[start + 1..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    if html[..open_end].ends_with("/>") || VOID_ELEMENTS.contains(&tag_name.as_str()) {
+        return Some(&html[start..open_end]);
+    }
+
+    let open_tag = format!("<{tag_name}");
+    let close_tag = format!("</{tag_name}");
+    let mut depth = 1;
+    let mut pos = open_end;
+
+    while let Some(offset) = html[pos..].find('<') {
+        pos += offset;
+        let rest = &html[pos..];
+        if starts_tag(rest, &close_tag) {
+            depth -= 1;
+            let end = pos + rest.find('>')? + 1;
+            if depth == 0 {
+                return Some(&html[start..end]);
+            }
+            pos = end;
+        } else {
+            if starts_tag(rest, &open_tag) {
+                depth += 1;
+            }
+            pos += 1;
+        }
+    }
+
+    None
+}
+
+/// Byte offset of the `<` opening the tag whose `id` attribute is `id`
+fn find_id_attribute(html: &str, id: &str) -> Option<usize> {
+    let patterns = [format!(r#"id="{id}""#), format!("id='{id}'")];
+
+    patterns.iter().find_map(|pattern| {
+        html.match_indices(pattern.as_str()).find_map(|(pos, _)| {
+            // Must be an attribute (preceded by whitespace) inside a tag
+            let preceded_by_space = html[..pos]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_whitespace);
+            let tag_start = html[..pos].rfind('<')?;
+            let inside_tag = !html[tag_start..pos].contains('>');
+            (preceded_by_space && inside_tag).then_some(tag_start)
+        })
+    })
+}
+
+/// Whether `rest` begins with `tag` followed by the end of the tag name
+fn starts_tag(rest: &str, tag: &str) -> bool {
+    rest.len() > tag.len()
+        && rest[..tag.len()].eq_ignore_ascii_case(tag)
+        && rest[tag.len()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_whitespace() || c == '>' || c == '/')
+}
+
 /// Extract main content block from template
 ///
 /// Uses multiple strategies to find the main content:
@@ -230,6 +323,40 @@ mod tests {
         assert!(content.is_none());
     }
 
+    #[test]
+    fn test_extract_element_by_id_any_tag() {
+        let html = r#"<main><h1>Title</h1><table id="results" class="grid"><tr><td>1</td></tr></table></main>"#;
+        assert_eq!(
+            extract_element_by_id(html, "results"),
+            Some(r#"<table id="results" class="grid"><tr><td>1</td></tr></table>"#)
+        );
+    }
+
+    #[test]
+    fn test_extract_element_by_id_same_tag_nesting() {
+        let html = r#"<div id="outer"><div><div>deep</div></div><p>after</p></div><div>sibling</div>"#;
+        assert_eq!(
+            extract_element_by_id(html, "outer"),
+            Some(r#"<div id="outer"><div><div>deep</div></div><p>after</p></div>"#)
+        );
+    }
+
+    #[test]
+    fn test_extract_element_by_id_void_and_self_closing() {
+        let html = r#"<form><input type="text" id='query' name="q"><br id="gap"/></form>"#;
+        assert_eq!(
+            extract_element_by_id(html, "query"),
+            Some(r#"<input type="text" id='query' name="q">"#)
+        );
+        assert_eq!(extract_element_by_id(html, "gap"), Some(r#"<br id="gap"/>"#));
+    }
+
+    #[test]
+    fn test_extract_element_by_id_ignores_text_and_other_attributes() {
+        let html = r#"<p>set id="results" in text</p><a data-id="results">x</a>"#;
+        assert_eq!(extract_element_by_id(html, "results"), None);
+    }
+
     #[test]
     fn test_extract_main_content_with_markers() {
         let html = r"
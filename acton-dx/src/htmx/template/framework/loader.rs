@@ -150,6 +150,7 @@ impl FrameworkTemplates {
         // Configure environment
         env.set_trim_blocks(true);
         env.set_lstrip_blocks(true);
+        env.add_function("t", crate::htmx::i18n::template_function);
 
         // Load all templates
         for name in TEMPLATE_NAMES {
@@ -16,13 +16,28 @@
 //! ```
 
 use crate::htmx::auth::session::FlashMessage;
+use crate::htmx::i18n::AcceptLanguage;
+use crate::htmx::middleware::{CspNonce, IDEMPOTENCY_FORM_FIELD};
 use crate::htmx::template::FrameworkTemplates;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha384};
 use std::sync::OnceLock;
 
 /// Get or initialize the framework templates (lazy singleton)
-fn templates() -> &'static FrameworkTemplates {
-    static TEMPLATES: OnceLock<FrameworkTemplates> = OnceLock::new();
-    TEMPLATES.get_or_init(|| FrameworkTemplates::new().expect("Failed to initialize templates"))
+pub(crate) fn templates() -> &'static FrameworkTemplates {
+    try_templates().expect("Failed to initialize templates")
+}
+
+/// Like [`templates`], but `None` when they are not installed
+pub(crate) fn try_templates() -> Option<&'static FrameworkTemplates> {
+    static TEMPLATES: OnceLock<Option<FrameworkTemplates>> = OnceLock::new();
+    TEMPLATES
+        .get_or_init(|| {
+            FrameworkTemplates::new()
+                .map_err(|e| tracing::error!(error = %e, "Failed to initialize framework templates"))
+                .ok()
+        })
+        .as_ref()
 }
 
 /// Generate CSRF token input field
@@ -70,6 +85,26 @@ pub fn csrf_token_with(token: &str) -> String {
         .expect("Failed to render CSRF token template - run `acton-dx templates init`")
 }
 
+/// Generate a hidden input carrying a fresh idempotency key
+///
+/// Submitting the same rendered form twice sends the same key, so
+/// `IdempotencyLayer` replays the first response instead of running the
+/// handler again.
+///
+/// Usage in templates:
+/// ```html
+/// <form hx-post="/orders">
+///     {{ idempotency_key_input()|safe }}
+/// </form>
+/// ```
+#[must_use]
+pub fn idempotency_key_input() -> String {
+    format!(
+        r#"<input type="hidden" name="{IDEMPOTENCY_FORM_FIELD}" value="{}">"#,
+        uuid::Uuid::new_v4()
+    )
+}
+
 /// Render flash messages as HTML
 ///
 /// Renders a collection of flash messages with appropriate styling and ARIA attributes.
@@ -81,6 +116,8 @@ pub fn csrf_token_with(token: &str) -> String {
 /// - ARIA role and live region attributes for accessibility
 /// - Optional title in a `<strong>` tag
 /// - Message text in a `<span>` tag
+/// - A `data-dismiss-after` attribute (milliseconds) for messages built with
+///   [`FlashMessage::dismiss_after`]
 ///
 /// # Examples
 ///
@@ -126,16 +163,7 @@ pub fn flash_messages(messages: &[FlashMessage]) -> String {
     }
 
     // Convert to serializable format for template
-    let msgs: Vec<_> = messages
-        .iter()
-        .map(|m| {
-            minijinja::context! {
-                css_class => m.css_class(),
-                title => m.title.as_deref(),
-                message => &m.message,
-            }
-        })
-        .collect();
+    let msgs: Vec<_> = messages.iter().map(flash_context).collect();
 
     templates()
         .render(
@@ -148,6 +176,19 @@ pub fn flash_messages(messages: &[FlashMessage]) -> String {
         .expect("Failed to render flash messages template - run `acton-dx templates init`")
 }
 
+/// Template context for a single flash message
+///
+/// Matches the variables used by `flash/message.html` and each entry of
+/// `flash/container.html`.
+fn flash_context(message: &FlashMessage) -> minijinja::Value {
+    minijinja::context! {
+        css_class => message.css_class(),
+        title => message.title.as_deref(),
+        message => &message.message,
+        dismiss_ms => message.dismiss_ms,
+    }
+}
+
 // Note: The route() helper has been removed as named routes are not currently implemented.
 // Use hardcoded paths in your templates instead:
 //   href="/posts/{{ post.id }}"
@@ -199,6 +240,103 @@ pub fn asset(path: &str) -> String {
     path.to_string()
 }
 
+// =============================================================================
+// Content-Security-Policy Helpers
+// =============================================================================
+
+/// The current request's CSP nonce
+///
+/// Set by `SecurityHeadersLayer` when its policy uses a nonce (see
+/// [`CspBuilder`](crate::htmx::middleware::CspBuilder)). Returns an empty
+/// string when there is none, e.g. because CSP is disabled.
+///
+/// Usage in templates:
+/// ```html
+/// <script nonce="{{ csp_nonce() }}">
+///     document.body.classList.add("js");
+/// </script>
+/// ```
+#[must_use]
+pub fn csp_nonce() -> String {
+    CspNonce::current().map(|nonce| nonce.to_string()).unwrap_or_default()
+}
+
+/// Generate a `<script>` tag for `src` carrying the current request's nonce
+///
+/// With `integrity`, the tag also gets an `integrity` attribute (see
+/// [`integrity_hash`]) and `crossorigin="anonymous"`, which browsers need to
+/// check it on cross-origin scripts. The `nonce` attribute is omitted when
+/// the request has no CSP nonce.
+///
+/// # Examples
+///
+/// ```rust
+/// use acton_dx::htmx::template::helpers::script;
+///
+/// assert_eq!(script("/js/app.js", None), r#"<script src="/js/app.js"></script>"#);
+/// ```
+///
+/// Usage in templates:
+/// ```html
+/// {{ script("https://unpkg.com/htmx.org@2.0.4", Some("sha384-…"))|safe }}
+/// ```
+#[must_use]
+pub fn script(src: &str, integrity: Option<&str>) -> String {
+    let nonce = CspNonce::current()
+        .map(|nonce| format!(r#" nonce="{nonce}""#))
+        .unwrap_or_default();
+    let integrity = integrity
+        .map(|integrity| {
+            format!(
+                r#" integrity="{}" crossorigin="anonymous""#,
+                escape_attr(integrity)
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        r#"<script src="{}"{nonce}{integrity}></script>"#,
+        escape_attr(src)
+    )
+}
+
+/// Compute a subresource integrity value (`sha384-…`) for `content`
+///
+/// # Examples
+///
+/// ```rust
+/// use acton_dx::htmx::template::helpers::integrity_hash;
+///
+/// assert!(integrity_hash(b"console.log(1);").starts_with("sha384-"));
+/// ```
+#[must_use]
+pub fn integrity_hash(content: &[u8]) -> String {
+    format!("sha384-{}", STANDARD.encode(Sha384::digest(content)))
+}
+
+// =============================================================================
+// Translation Helpers
+// =============================================================================
+
+/// Translate `key` into the current request's locale
+///
+/// `{name}` placeholders in the translation are replaced by matching
+/// `args`. Uses the locale chosen by [`I18nLayer`](crate::htmx::i18n::I18nLayer);
+/// without it, returns the key.
+///
+/// Usage in templates:
+/// ```html
+/// <h1>{{ t("greeting", [("name", user.name.as_str())]) }}</h1>
+/// ```
+#[must_use]
+pub fn t(key: &str, args: &[(&str, &str)]) -> String {
+    AcceptLanguage::current().map_or_else(|| key.to_string(), |language| language.t(key, args))
+}
+
+/// Escape a string for use inside a double-quoted attribute
+fn escape_attr(s: &str) -> String {
+    escape_html(s).replace('"', "&quot;")
+}
+
 // =============================================================================
 // HTMX Attribute Helpers
 // =============================================================================
@@ -556,12 +694,85 @@ mod tests {
         assert!(token.contains(r#"value="abc123""#));
     }
 
+    #[test]
+    fn test_idempotency_key_input() {
+        let first = idempotency_key_input();
+        assert!(first.starts_with(r#"<input type="hidden" name="_idempotency_key" value=""#));
+        assert_ne!(first, idempotency_key_input());
+    }
+
     #[test]
     fn test_asset() {
         let path = asset("/css/styles.css");
         assert_eq!(path, "/css/styles.css");
     }
 
+    #[tokio::test]
+    async fn test_csp_helpers_use_request_nonce() {
+        use crate::htmx::middleware::{CspBuilder, SecurityHeadersConfig, SecurityHeadersLayer};
+        use axum::{body::Body, http::Request, routing::get, Router};
+        use tower::ServiceExt;
+
+        let config = SecurityHeadersConfig::custom().with_csp(CspBuilder::htmx().build());
+        let app = Router::new()
+            .route(
+                "/",
+                get(|nonce: CspNonce| async move {
+                    assert_eq!(csp_nonce(), nonce.as_str());
+                    script("/js/app.js", None)
+                }),
+            )
+            .layer(SecurityHeadersLayer::new(config));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let csp = response.headers()["content-security-policy"]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        let nonce = body
+            .split(r#"nonce=""#)
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap();
+        assert!(csp.contains(&format!("'nonce-{nonce}'")));
+    }
+
+    #[test]
+    fn test_csp_helpers_without_nonce() {
+        assert_eq!(csp_nonce(), "");
+        assert_eq!(script("/js/app.js", None), r#"<script src="/js/app.js"></script>"#);
+    }
+
+    #[tokio::test]
+    async fn test_script_with_integrity() {
+        let nonce = CspNonce::generate();
+        let tag = nonce
+            .scope(async { script("/js/a.js?x=\"1\"", Some("sha384-abc")) })
+            .await;
+
+        assert_eq!(
+            tag,
+            format!(
+                r#"<script src="/js/a.js?x=&quot;1&quot;" nonce="{nonce}" integrity="sha384-abc" crossorigin="anonymous"></script>"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_integrity_hash() {
+        // Known SRI value for an empty resource
+        assert_eq!(
+            integrity_hash(b""),
+            "sha384-OLBgp1GsljhM2TJ+sbHjaiH9txEUvgdDTAzHv2P24donTt6/529l+9Ua0vFImLlb"
+        );
+    }
+
     #[test]
     fn test_hx_post() {
         let attrs = hx_post("/api/items", "#list", "innerHTML");
@@ -758,4 +969,60 @@ mod tests {
         assert_eq!(escape_html("Hello 123 !@#$%^*()_+-=[]{}|;:',./? "),
                    "Hello 123 !@#$%^*()_+-=[]{}|;:',./? ");
     }
+
+    /// Render a bundled default template, bypassing the XDG template loader
+    fn render_default(name: &str, source: &'static str, ctx: minijinja::Value) -> String {
+        let mut env = minijinja::Environment::new();
+        env.add_template(name, source).unwrap();
+        env.get_template(name).unwrap().render(ctx).unwrap()
+    }
+
+    #[test]
+    fn test_flash_message_template_dismiss_after() {
+        let source = include_str!("framework/defaults/flash/message.html");
+        let flash = FlashMessage::success("Saved").dismiss_after(std::time::Duration::from_secs(3));
+
+        let html = render_default("flash/message.html", source, flash_context(&flash));
+        assert!(html.contains(r#"data-dismiss-after="3000""#));
+
+        let html = render_default(
+            "flash/message.html",
+            source,
+            flash_context(&FlashMessage::success("Saved")),
+        );
+        assert!(!html.contains("data-dismiss-after"));
+    }
+
+    #[test]
+    fn test_flash_container_template_dismiss_after() {
+        let messages = [
+            FlashMessage::info("Heads up").dismiss_after(std::time::Duration::from_millis(1500)),
+            FlashMessage::error("Failed"),
+        ];
+        let html = render_default(
+            "flash/container.html",
+            include_str!("framework/defaults/flash/container.html"),
+            minijinja::context! {
+                container_class => "flash-messages",
+                messages => messages.iter().map(flash_context).collect::<Vec<_>>(),
+            },
+        );
+
+        assert_eq!(html.matches("data-dismiss-after").count(), 1);
+        assert!(html.contains(r#"<div class="flash-info" role="alert" data-dismiss-after="1500">"#));
+    }
+
+    #[test]
+    fn test_flash_message_template_level_classes() {
+        let source = include_str!("framework/defaults/flash/message.html");
+        for (flash, class) in [
+            (FlashMessage::success("a"), "flash-success"),
+            (FlashMessage::info("b"), "flash-info"),
+            (FlashMessage::warning("c"), "flash-warning"),
+            (FlashMessage::error("d"), "flash-error"),
+        ] {
+            let html = render_default("flash/message.html", source, flash_context(&flash));
+            assert!(html.starts_with(&format!(r#"<div class="{class}" role="alert">"#)));
+        }
+    }
 }
@@ -5,6 +5,7 @@
 //! - Template registry with optional caching
 //! - HTMX-aware template helpers
 //! - Integration with axum-htmx response types
+//! - JSON/HTML content negotiation via [`ResponseFormat`]
 //!
 //! # Examples
 //!
@@ -30,18 +31,22 @@
 
 use askama::Template;
 use axum::{
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::{Html, IntoResponse, Response},
+    Json,
 };
+use serde::Serialize;
 
 pub mod extractor;
 pub mod framework;
 pub mod helpers;
+pub mod negotiate;
 pub mod registry;
 
 pub use extractor::*;
 pub use framework::{FrameworkTemplateError, FrameworkTemplates};
 pub use helpers::*;
+pub use negotiate::{ResponseFormat, ResponseFormatLayer, ResponseFormatMiddleware};
 pub use registry::TemplateRegistry;
 
 /// Extension trait for Askama templates with HTMX support
@@ -136,6 +141,57 @@ pub trait HxTemplate: Template {
         }
     }
 
+    /// Render a single element of the template by its `id`
+    ///
+    /// Renders the full template, then returns only the element whose `id`
+    /// attribute equals `block`, including the element's own tags (see
+    /// [`extractor::extract_element_by_id`]). This lets one template serve the
+    /// full page, [`render_partial`](Self::render_partial), and targeted swaps
+    /// such as `hx-target="#results" hx-swap="outerHTML"`.
+    ///
+    /// Returns `404 Not Found` with an empty body if no element has that `id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StatusCode::INTERNAL_SERVER_ERROR` if template rendering fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use askama::Template;
+    /// use acton_htmx::template::HxTemplate;
+    ///
+    /// #[derive(Template)]
+    /// #[template(source = r#"<h1>Search</h1><ul id="results">{% for r in results %}<li>{{ r }}</li>{% endfor %}</ul>"#, ext = "html")]
+    /// struct SearchPage { results: Vec<String> }
+    ///
+    /// let page = SearchPage { results: vec!["one".into()] };
+    /// // Returns: <ul id="results"><li>one</li></ul>
+    /// let response = page.render_fragment("results");
+    /// ```
+    fn render_fragment(self, block: &str) -> Response
+    where
+        Self: Sized,
+    {
+        match self.render() {
+            Ok(html) => extractor::extract_element_by_id(&html, block).map_or_else(
+                || {
+                    tracing::debug!("Fragment #{} not found in rendered template", block);
+                    StatusCode::NOT_FOUND.into_response()
+                },
+                |fragment| Html(fragment.to_string()).into_response(),
+            ),
+            Err(err) => {
+                tracing::error!("Template rendering error: {}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Template rendering failed",
+                )
+                    .into_response()
+            }
+        }
+    }
+
     /// Render as out-of-band swap content
     ///
     /// Wraps the template content in an element with `hx-swap-oob="true"`.
@@ -219,6 +275,27 @@ pub trait HxTemplate: Template {
             r#"<div id="{target_id}" hx-swap-oob="{swap_attr}">{html}</div>"#
         ))
     }
+
+    /// Render as JSON, an HTMX partial, or a full page depending on the request
+    ///
+    /// Serializes the template's fields as JSON when `Accept` lists
+    /// `application/json`, otherwise defers to
+    /// [`render_htmx`](Self::render_htmx) based on `HX-Request`. See
+    /// [`ResponseFormat::from_headers`] for the exact rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StatusCode::INTERNAL_SERVER_ERROR` if template rendering or
+    /// serialization fails.
+    fn respond_negotiated(self, headers: &HeaderMap) -> Response
+    where
+        Self: Sized + Serialize,
+    {
+        match ResponseFormat::from_headers(headers) {
+            ResponseFormat::Json => Json(self).into_response(),
+            format => self.render_htmx(format.is_htmx()),
+        }
+    }
 }
 
 // Blanket implementation for all Askama templates
@@ -265,6 +342,51 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[derive(Template, Serialize)]
+    #[template(
+        source = r#"<html><body><nav>Nav</nav><div id="main-content"><h1>{{ title }}</h1><table id="results"><tr id="row-1"><td>One</td></tr><tr id="row-2"><td>Two</td></tr></table></div></body></html>"#,
+        ext = "html"
+    )]
+    struct PageTemplate {
+        title: String,
+    }
+
+    async fn fragment_body(block: &str) -> (StatusCode, String) {
+        let response = PageTemplate {
+            title: "Results".to_string(),
+        }
+        .render_fragment(block);
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_render_fragment_existing_id() {
+        let (status, body) = fragment_body("results").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            r#"<table id="results"><tr id="row-1"><td>One</td></tr><tr id="row-2"><td>Two</td></tr></table>"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_fragment_nested_id() {
+        let (status, body) = fragment_body("row-2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, r#"<tr id="row-2"><td>Two</td></tr>"#);
+    }
+
+    #[tokio::test]
+    async fn test_render_fragment_missing_id() {
+        let (status, body) = fragment_body("missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body.is_empty());
+    }
+
     #[test]
     fn test_render_oob() {
         let template = TestTemplate {
@@ -306,4 +428,56 @@ mod tests {
         let oob_str = template.render_oob_str("target-id", Some("innerHTML")).unwrap();
         assert!(oob_str.contains(r#"hx-swap-oob="innerHTML""#));
     }
+
+    async fn negotiated_body(accept: &'static str, htmx: bool) -> (String, String) {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::ACCEPT,
+            axum::http::HeaderValue::from_static(accept),
+        );
+        if htmx {
+            headers.insert("HX-Request", axum::http::HeaderValue::from_static("true"));
+        }
+
+        let response = PageTemplate {
+            title: "Results".to_string(),
+        }
+        .respond_negotiated(&headers);
+        assert_eq!(response.status(), StatusCode::OK);
+        let content_type = response.headers()[axum::http::header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (content_type, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_respond_negotiated_json() {
+        let (content_type, body) = negotiated_body("application/json", false).await;
+        assert_eq!(content_type, "application/json");
+        assert_eq!(body, r#"{"title":"Results"}"#);
+
+        // JSON wins even for HTMX requests that explicitly ask for it
+        let (content_type, _) = negotiated_body("application/json", true).await;
+        assert_eq!(content_type, "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_respond_negotiated_htmx_partial() {
+        let (content_type, body) = negotiated_body("*/*", true).await;
+        assert!(content_type.starts_with("text/html"));
+        assert!(body.contains("<h1>Results</h1>"));
+        assert!(!body.contains("<nav>"));
+    }
+
+    #[tokio::test]
+    async fn test_respond_negotiated_full_page() {
+        let (content_type, body) = negotiated_body("text/html", false).await;
+        assert!(content_type.starts_with("text/html"));
+        assert!(body.starts_with("<html>"));
+        assert!(body.contains("<nav>Nav</nav>"));
+    }
 }
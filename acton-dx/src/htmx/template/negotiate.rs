@@ -0,0 +1,245 @@
+//! Content negotiation between HTML pages, HTMX partials, and JSON
+//!
+//! [`ResponseFormat`] classifies a request from its `Accept` and `HX-Request`
+//! headers. [`HxTemplate::respond_negotiated`](super::HxTemplate::respond_negotiated)
+//! uses it to serve the same template struct to browsers, HTMX, and API
+//! clients.
+//!
+//! [`ResponseFormatLayer`] makes the request's format available to code
+//! without access to the request through [`ResponseFormat::current`], e.g.
+//! `IntoResponse` for [`ActonHtmxError`](crate::htmx::ActonHtmxError).
+//! `ActonServer` installs it automatically.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! use acton_dx::htmx::template::{HxTemplate, ResponseFormat};
+//!
+//! // Let the template decide
+//! async fn show(headers: HeaderMap) -> Response {
+//!     PostTemplate { post: load_post().await }.respond_negotiated(&headers)
+//! }
+//!
+//! // Or branch explicitly
+//! async fn index(format: ResponseFormat) -> Response {
+//!     match format {
+//!         ResponseFormat::Json => Json(load_posts().await).into_response(),
+//!         _ => PostsTemplate { posts: load_posts().await }.render_htmx(format.is_htmx()),
+//!     }
+//! }
+//! ```
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::task::{Context, Poll};
+
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, HeaderMap},
+    response::Response,
+};
+use tower::{Layer, Service};
+
+use crate::htmx::middleware::is_htmx_request;
+
+tokio::task_local! {
+    static CURRENT_FORMAT: ResponseFormat;
+}
+
+/// The representation a request asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// `Accept: application/json`
+    Json,
+    /// HTMX request (`HX-Request: true`): render the partial
+    HtmxPartial,
+    /// Regular browser request: render the full page
+    FullPage,
+}
+
+impl ResponseFormat {
+    /// Classify a request from its headers
+    ///
+    /// JSON wins when `Accept` lists `application/json` (HTMX itself sends
+    /// `Accept: */*`), then `HX-Request` selects the partial.
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        if accepts_json(headers) {
+            Self::Json
+        } else if is_htmx_request(headers) {
+            Self::HtmxPartial
+        } else {
+            Self::FullPage
+        }
+    }
+
+    /// Whether the request is an HTMX request wanting HTML
+    #[must_use]
+    pub const fn is_htmx(self) -> bool {
+        matches!(self, Self::HtmxPartial)
+    }
+
+    /// Run `future` with this as the current request's format
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_FORMAT.scope(self, future).await
+    }
+
+    /// The current request's format, if inside [`scope`](Self::scope)
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        CURRENT_FORMAT.try_with(|format| *format).ok()
+    }
+}
+
+impl<S> FromRequestParts<S> for ResponseFormat
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// Layer making each request's format available via [`ResponseFormat::current`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResponseFormatLayer;
+
+impl<S> Layer<S> for ResponseFormatLayer {
+    type Service = ResponseFormatMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ResponseFormatMiddleware { inner }
+    }
+}
+
+/// Middleware service created by [`ResponseFormatLayer`]
+#[derive(Debug, Clone)]
+pub struct ResponseFormatMiddleware<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for ResponseFormatMiddleware<S>
+where
+    S: Service<Request, Response = Response<Body>> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let format = ResponseFormat::from_headers(req.headers());
+        Box::pin(format.scope(self.inner.call(req)))
+    }
+}
+
+/// Whether `Accept` lists `application/json` with a non-zero quality
+fn accepts_json(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|range| {
+            let mut params = range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default();
+            media_type.eq_ignore_ascii_case("application/json")
+                && !params.any(|param| {
+                    param
+                        .strip_prefix("q=")
+                        .and_then(|q| q.parse::<f32>().ok())
+                        .is_some_and(|q| q <= 0.0)
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(accept: Option<&'static str>, htmx: bool) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(accept) = accept {
+            headers.insert(header::ACCEPT, HeaderValue::from_static(accept));
+        }
+        if htmx {
+            headers.insert("HX-Request", HeaderValue::from_static("true"));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_classifies_requests() {
+        assert_eq!(
+            ResponseFormat::from_headers(&headers(Some("application/json"), false)),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&headers(Some("*/*"), true)),
+            ResponseFormat::HtmxPartial
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&headers(Some("text/html,application/xhtml+xml"), false)),
+            ResponseFormat::FullPage
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&headers(None, false)),
+            ResponseFormat::FullPage
+        );
+    }
+
+    #[test]
+    fn test_accept_parameters() {
+        assert!(accepts_json(&headers(
+            Some("text/html;q=0.9, Application/JSON; charset=utf-8"),
+            false
+        )));
+        assert!(!accepts_json(&headers(Some("application/json;q=0"), false)));
+        assert!(!accepts_json(&headers(Some("application/jsonp"), false)));
+    }
+
+    #[tokio::test]
+    async fn test_layer_sets_current_format() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route("/", get(|| async { format!("{:?}", ResponseFormat::current()) }))
+            .layer(ResponseFormatLayer);
+
+        let request = axum::http::Request::builder()
+            .uri("/")
+            .header(header::ACCEPT, "application/json")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"Some(Json)");
+        assert_eq!(ResponseFormat::current(), None);
+    }
+
+    #[tokio::test]
+    async fn test_extractor() {
+        let (mut parts, ()) = axum::http::Request::builder()
+            .header("HX-Request", "true")
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        let format = ResponseFormat::from_request_parts(&mut parts, &()).await.unwrap();
+        assert_eq!(format, ResponseFormat::HtmxPartial);
+        assert!(format.is_htmx());
+    }
+}
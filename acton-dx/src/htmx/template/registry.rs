@@ -1,13 +1,27 @@
 //! Template registry with caching support
 //!
 //! Provides a registry for managing compiled templates with optional caching
-//! for improved performance.
+//! for improved performance. With hot reload enabled, [`TemplateRegistry::watch`]
+//! keeps cached templates in sync with their files on disk.
 
 #![allow(dead_code)]
 
-use parking_lot::RwLock;
-use std::collections::HashMap;
-use std::sync::Arc;
+use crate::htmx::config::TemplateSettings;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::{Mutex, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Quiet period after the last file event before reloading
+///
+/// Editors often write a file several times per save (truncate, write,
+/// rename); reloading once the burst settles avoids reading partial files.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(100);
+
+type TemplateCache = RwLock<HashMap<String, String>>;
 
 /// Template registry for caching compiled templates
 ///
@@ -15,8 +29,12 @@ use std::sync::Arc;
 /// In production mode, templates are cached after first compilation.
 #[derive(Clone)]
 pub struct TemplateRegistry {
-    cache: Arc<RwLock<HashMap<String, String>>>,
+    cache: Arc<TemplateCache>,
     cache_enabled: bool,
+    hot_reload: bool,
+    watch_extensions: Arc<[String]>,
+    /// Active filesystem watchers; dropping them stops the reload tasks
+    watchers: Arc<Mutex<Vec<RecommendedWatcher>>>,
 }
 
 impl Default for TemplateRegistry {
@@ -31,19 +49,80 @@ impl TemplateRegistry {
     /// Caching is disabled in debug builds and enabled in release builds.
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            cache: Arc::new(RwLock::new(HashMap::new())),
-            cache_enabled: !cfg!(debug_assertions),
-        }
+        Self::with_caching(!cfg!(debug_assertions))
     }
 
     /// Create a new template registry with explicit cache control
     #[must_use]
     pub fn with_caching(cache_enabled: bool) -> Self {
+        let defaults = TemplateSettings::default();
         Self {
             cache: Arc::new(RwLock::new(HashMap::new())),
             cache_enabled,
+            hot_reload: defaults.hot_reload,
+            watch_extensions: defaults.watch_extensions.into(),
+            watchers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Create a template registry from configuration
+    ///
+    /// Uses `cache_enabled`, `hot_reload`, and `watch_extensions` from the
+    /// settings.
+    #[must_use]
+    pub fn from_settings(settings: &TemplateSettings) -> Self {
+        Self {
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            cache_enabled: settings.cache_enabled,
+            hot_reload: settings.hot_reload,
+            watch_extensions: settings.watch_extensions.clone().into(),
+            watchers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Watch a template directory and reload cached templates when they change
+    ///
+    /// Cache entries are keyed by path relative to `dir` (e.g.
+    /// `partials/row.html`). When a file with one of the `watch_extensions`
+    /// changes, its cache entry alone is replaced with the new contents, or
+    /// removed if the file was deleted; other entries are untouched. Bursts
+    /// of events from a single editor save are debounced into one reload.
+    ///
+    /// Does nothing when hot reload is disabled. Watching runs on a
+    /// background task that stops once every clone of the registry has been
+    /// dropped. Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dir` cannot be resolved or watched.
+    pub fn watch(&self, dir: &Path) -> notify::Result<()> {
+        if !self.hot_reload {
+            return Ok(());
         }
+
+        // Events carry paths under the directory as it was registered
+        let dir = dir.canonicalize().map_err(notify::Error::io)?;
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            match event {
+                Ok(event) => {
+                    let _ = events_tx.send(event);
+                }
+                Err(e) => tracing::warn!(error = %e, "Template watcher error"),
+            }
+        })?;
+        watcher.watch(&dir, RecursiveMode::Recursive)?;
+
+        tokio::spawn(reload_on_change(
+            dir.clone(),
+            Arc::clone(&self.watch_extensions),
+            Arc::downgrade(&self.cache),
+            events_rx,
+        ));
+        self.watchers.lock().push(watcher);
+
+        tracing::debug!(dir = %dir.display(), "Watching templates for changes");
+        Ok(())
     }
 
     /// Get a cached template
@@ -87,6 +166,105 @@ impl TemplateRegistry {
     pub fn cache_size(&self) -> usize {
         self.cache.read().len()
     }
+
+    /// Check if hot reload is enabled
+    #[must_use]
+    pub const fn is_hot_reload_enabled(&self) -> bool {
+        self.hot_reload
+    }
+}
+
+/// Background task applying debounced file events to the cache
+///
+/// Ends when the watcher (and with it the event sender) is dropped, or when
+/// the cache itself is gone.
+async fn reload_on_change(
+    dir: PathBuf,
+    extensions: Arc<[String]>,
+    cache: Weak<TemplateCache>,
+    mut events: mpsc::UnboundedReceiver<Event>,
+) {
+    while let Some(event) = events.recv().await {
+        let mut changed = HashSet::new();
+        collect_paths(&event, &extensions, &mut changed);
+
+        // Keep collecting until the burst of events settles
+        let mut closed = false;
+        loop {
+            match tokio::time::timeout(RELOAD_DEBOUNCE, events.recv()).await {
+                Ok(Some(event)) => collect_paths(&event, &extensions, &mut changed),
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        let Some(cache) = cache.upgrade() else {
+            return;
+        };
+        for path in changed {
+            reload_template(&dir, &path, &cache).await;
+        }
+        if closed {
+            return;
+        }
+    }
+}
+
+/// Add paths touched by `event` that have a watched extension
+fn collect_paths(event: &Event, extensions: &[String], changed: &mut HashSet<PathBuf>) {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+
+    changed.extend(
+        event
+            .paths
+            .iter()
+            .filter(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| extensions.iter().any(|watched| watched == ext))
+            })
+            .cloned(),
+    );
+}
+
+/// Refresh the cache entry for one template file
+async fn reload_template(dir: &Path, path: &Path, cache: &TemplateCache) {
+    let Some(name) = template_name(dir, path) else {
+        return;
+    };
+    if !cache.read().contains_key(&name) {
+        return;
+    }
+
+    match tokio::fs::read_to_string(path).await {
+        Ok(content) => {
+            cache.write().insert(name.clone(), content);
+            tracing::info!(template = %name, "Reloaded template");
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            cache.write().remove(&name);
+            tracing::info!(template = %name, "Removed deleted template from cache");
+        }
+        Err(e) => {
+            cache.write().remove(&name);
+            tracing::warn!(template = %name, error = %e, "Failed to reload template");
+        }
+    }
+}
+
+/// Cache key for `path`: its path relative to `dir`, with `/` separators
+fn template_name(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let parts: Vec<&str> = relative
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    Some(parts.join("/"))
 }
 
 #[cfg(test)]
@@ -115,6 +293,78 @@ mod tests {
         assert!(registry.get("test").is_none());
     }
 
+    #[test]
+    fn test_template_name_is_relative_with_slashes() {
+        let dir = Path::new("/app/templates");
+
+        assert_eq!(
+            template_name(dir, &dir.join("partials").join("row.html")).as_deref(),
+            Some("partials/row.html")
+        );
+        assert_eq!(template_name(dir, Path::new("/elsewhere/row.html")), None);
+    }
+
+    #[tokio::test]
+    async fn test_watch_is_noop_without_hot_reload() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let registry = TemplateRegistry::from_settings(&TemplateSettings {
+            hot_reload: false,
+            ..TemplateSettings::default()
+        });
+
+        registry.watch(dir.path()).unwrap();
+        assert!(registry.watchers.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_watch_reloads_changed_template() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let page = dir.path().join("page.html");
+        let other = dir.path().join("other.html");
+        std::fs::write(&page, "Hello {{ name }}").unwrap();
+        std::fs::write(&other, "Other").unwrap();
+
+        let registry = TemplateRegistry::from_settings(&TemplateSettings {
+            cache_enabled: true,
+            hot_reload: true,
+            ..TemplateSettings::default()
+        });
+        registry.insert("page.html".to_string(), "Hello {{ name }}".to_string());
+        registry.insert("other.html".to_string(), "Other".to_string());
+        registry.watch(dir.path()).unwrap();
+
+        let render = |registry: &TemplateRegistry| {
+            let source = registry.get("page.html").unwrap();
+            minijinja::Environment::new()
+                .render_str(&source, minijinja::context! { name => "Ada" })
+                .unwrap()
+        };
+        assert_eq!(render(&registry), "Hello Ada");
+
+        std::fs::write(&page, "Goodbye {{ name }}").unwrap();
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while render(&registry) != "Goodbye Ada" {
+            assert!(tokio::time::Instant::now() < deadline, "template was not reloaded");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(registry.get("other.html").as_deref(), Some("Other"));
+    }
+
+    #[tokio::test]
+    async fn test_dropping_registry_releases_watched_cache() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let registry = TemplateRegistry::from_settings(&TemplateSettings {
+            hot_reload: true,
+            ..TemplateSettings::default()
+        });
+        registry.watch(dir.path()).unwrap();
+
+        let cache = Arc::downgrade(&registry.cache);
+        drop(registry);
+        assert!(cache.upgrade().is_none());
+    }
+
     #[test]
     fn test_registry_clear() {
         let registry = TemplateRegistry::with_caching(true);
@@ -0,0 +1,134 @@
+//! Tenant-scoped background jobs
+
+use super::Tenant;
+use crate::htmx::jobs::{BackoffConfig, Job, JobContext, JobResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Wraps a job so it runs on behalf of a tenant
+///
+/// The tenant is serialized alongside the job payload, so it survives
+/// persistence and retries. At execution time the inner job receives a
+/// [`JobContext`] whose [`tenant`](JobContext::tenant) is set.
+///
+/// The inner job's [`dedup_key`](Job::dedup_key) is prefixed with the tenant
+/// ID, so identical jobs of different tenants are not collapsed.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use acton_dx::htmx::tenant::{Tenant, TenantJob};
+///
+/// async fn handler(tenant: Tenant) {
+///     let job = TenantJob::new(tenant, SendReportJob { report_id: 42 });
+///     // enqueue `job` as usual
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantJob<J> {
+    /// Tenant the job runs on behalf of
+    pub tenant: Tenant,
+    /// The wrapped job
+    pub job: J,
+}
+
+impl<J: Job> TenantJob<J> {
+    /// Wrap a job for a tenant
+    #[must_use]
+    pub const fn new(tenant: Tenant, job: J) -> Self {
+        Self { tenant, job }
+    }
+}
+
+#[async_trait]
+impl<J: Job> Job for TenantJob<J> {
+    type Result = J::Result;
+
+    async fn execute(&self, ctx: &JobContext) -> JobResult<Self::Result> {
+        let ctx = ctx.clone().with_tenant(self.tenant.clone());
+        self.job.execute(&ctx).await
+    }
+
+    fn max_retries(&self) -> u32 {
+        self.job.max_retries()
+    }
+
+    fn backoff(&self) -> BackoffConfig {
+        self.job.backoff()
+    }
+
+    fn timeout(&self) -> Duration {
+        self.job.timeout()
+    }
+
+    fn priority(&self) -> i32 {
+        self.job.priority()
+    }
+
+    fn dedup_key(&self) -> Option<String> {
+        self.job
+            .dedup_key()
+            .map(|key| format!("tenant:{}:{key}", self.tenant.id()))
+    }
+
+    fn job_type(&self) -> &'static str {
+        self.job.job_type()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct TenantEchoJob;
+
+    #[async_trait]
+    impl Job for TenantEchoJob {
+        type Result = Option<String>;
+
+        async fn execute(&self, ctx: &JobContext) -> JobResult<Self::Result> {
+            Ok(ctx.tenant().map(|tenant| tenant.id().to_string()))
+        }
+
+        fn max_retries(&self) -> u32 {
+            7
+        }
+
+        fn backoff(&self) -> BackoffConfig {
+            BackoffConfig::new(Duration::from_secs(10), Duration::from_secs(60))
+        }
+
+        fn dedup_key(&self) -> Option<String> {
+            Some("echo".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tenant_job_sets_context_tenant() {
+        let job = TenantJob::new(Tenant::new("acme").unwrap(), TenantEchoJob);
+        let result = job.execute(&JobContext::new()).await.unwrap();
+        assert_eq!(result.as_deref(), Some("acme"));
+        assert_eq!(job.max_retries(), 7);
+        assert_eq!(job.backoff(), TenantEchoJob.backoff());
+        assert_ne!(job.backoff(), BackoffConfig::default());
+    }
+
+    #[test]
+    fn test_tenant_job_dedup_key_is_tenant_scoped() {
+        let acme = TenantJob::new(Tenant::new("acme").unwrap(), TenantEchoJob);
+        let globex = TenantJob::new(Tenant::new("globex").unwrap(), TenantEchoJob);
+
+        assert_eq!(acme.dedup_key().as_deref(), Some("tenant:acme:echo"));
+        assert_ne!(acme.dedup_key(), globex.dedup_key());
+    }
+
+    #[test]
+    fn test_tenant_job_round_trips_tenant() {
+        let job = TenantJob::new(Tenant::new("acme").unwrap(), TenantEchoJob);
+        let json = serde_json::to_string(&job).unwrap();
+        let restored: TenantJob<TenantEchoJob> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.tenant.id(), "acme");
+    }
+}
@@ -0,0 +1,394 @@
+//! Multi-tenant request scoping
+//!
+//! Provides a [`Tenant`] extractor for SaaS deployments where every query,
+//! upload, and background job must be scoped to a single tenant.
+//!
+//! The tenant is resolved once per request by
+//! [`TenantLayer`](crate::htmx::middleware::TenantLayer) from one of:
+//! - The `Host` subdomain (`acme.example.com` → `acme`)
+//! - A request header (e.g. `X-Tenant-Id`)
+//! - The authenticated user (via [`TenantResolver::tenant_for_user`])
+//!
+//! The resolved tenant is stored in request extensions and recorded on the
+//! request's tracing span. Unknown tenants are rejected with `404 Not Found`,
+//! and users who are not members of the requested tenant get `403 Forbidden`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use acton_dx::htmx::middleware::TenantLayer;
+//! use acton_dx::htmx::tenant::{StaticTenantResolver, Tenant, TenantSource};
+//! use axum::{routing::get, Router};
+//!
+//! async fn dashboard(tenant: Tenant) -> String {
+//!     format!("Dashboard for {}", tenant.id())
+//! }
+//!
+//! # fn example() -> Result<(), acton_dx::htmx::tenant::TenantError> {
+//! let resolver = StaticTenantResolver::new()
+//!     .with_tenant(Tenant::new("acme")?)
+//!     .with_tenant(Tenant::new("globex")?);
+//!
+//! let app: Router = Router::new()
+//!     .route("/", get(dashboard))
+//!     .layer(TenantLayer::new(
+//!         TenantSource::Subdomain { base_domain: "example.com".to_string() },
+//!         resolver,
+//!     ));
+//! # Ok(())
+//! # }
+//! ```
+
+mod job;
+mod resolver;
+
+pub use job::TenantJob;
+pub use resolver::{StaticTenantResolver, TenantResolver};
+
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Default header used by [`TenantSource::Header`]
+pub const TENANT_HEADER_NAME: &str = "x-tenant-id";
+
+/// Default column name used by [`Tenant::push_filter`]
+pub const TENANT_COLUMN: &str = "tenant_id";
+
+/// Maximum length of a tenant identifier
+const MAX_TENANT_ID_LEN: usize = 63;
+
+/// A resolved tenant
+///
+/// Tenant identifiers are restricted to ASCII alphanumerics, `-` and `_`
+/// (max 63 characters, matching a DNS label) so they are safe to embed in
+/// storage paths and log fields.
+///
+/// Serializes as the bare identifier; deserializing goes through
+/// [`Tenant::new`], so a stored job payload can't smuggle in an invalid one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Tenant {
+    id: String,
+}
+
+impl Tenant {
+    /// Create a tenant from an identifier
+    ///
+    /// The identifier is lowercased before validation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TenantError::InvalidId`] if the identifier is empty, too long,
+    /// or contains characters other than ASCII alphanumerics, `-` and `_`.
+    pub fn new(id: impl Into<String>) -> Result<Self, TenantError> {
+        let id = id.into().to_ascii_lowercase();
+
+        let valid = !id.is_empty()
+            && id.len() <= MAX_TENANT_ID_LEN
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        if valid {
+            Ok(Self { id })
+        } else {
+            Err(TenantError::InvalidId(id))
+        }
+    }
+
+    /// Get the tenant identifier
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Storage prefix for this tenant's files (`tenants/{id}`)
+    #[must_use]
+    pub fn storage_prefix(&self) -> String {
+        format!("tenants/{}", self.id)
+    }
+
+    /// Scope a storage path to this tenant
+    ///
+    /// Leading slashes are stripped so the result always stays under
+    /// [`storage_prefix`](Self::storage_prefix).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use acton_dx::htmx::tenant::Tenant;
+    ///
+    /// let tenant = Tenant::new("acme").unwrap();
+    /// assert_eq!(tenant.scoped_path("/avatars/1.png"), "tenants/acme/avatars/1.png");
+    /// ```
+    #[must_use]
+    pub fn scoped_path(&self, path: &str) -> String {
+        format!("{}/{}", self.storage_prefix(), path.trim_start_matches('/'))
+    }
+
+    /// Push a `tenant_id = $n` filter onto a query builder
+    ///
+    /// The tenant identifier is always bound as a parameter, never
+    /// interpolated into the SQL string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use acton_dx::htmx::tenant::Tenant;
+    /// use sqlx::{Postgres, QueryBuilder};
+    ///
+    /// let tenant = Tenant::new("acme").unwrap();
+    /// let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM posts WHERE ");
+    /// tenant.push_filter(&mut query);
+    /// assert_eq!(query.sql(), "SELECT * FROM posts WHERE tenant_id = $1");
+    /// ```
+    pub fn push_filter<'args, DB>(&self, query: &mut sqlx::QueryBuilder<'args, DB>)
+    where
+        DB: sqlx::Database,
+        String: 'args + sqlx::Encode<'args, DB> + sqlx::Type<DB>,
+    {
+        self.push_filter_on(query, TENANT_COLUMN);
+    }
+
+    /// Push a `{column} = $n` filter onto a query builder
+    ///
+    /// Use this when the tenant column is not named `tenant_id` or needs a
+    /// table qualifier (e.g. `posts.tenant_id`).
+    pub fn push_filter_on<'args, DB>(&self, query: &mut sqlx::QueryBuilder<'args, DB>, column: &str)
+    where
+        DB: sqlx::Database,
+        String: 'args + sqlx::Encode<'args, DB> + sqlx::Type<DB>,
+    {
+        query.push(column);
+        query.push(" = ");
+        query.push_bind(self.id.clone());
+    }
+}
+
+impl fmt::Display for Tenant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id)
+    }
+}
+
+impl TryFrom<String> for Tenant {
+    type Error = TenantError;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        Self::new(id)
+    }
+}
+
+impl From<Tenant> for String {
+    fn from(tenant: Tenant) -> Self {
+        tenant.id
+    }
+}
+
+/// Where the tenant identifier is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TenantSource {
+    /// First label of the `Host` header below `base_domain`
+    ///
+    /// With `base_domain = "example.com"`, `acme.example.com:8080` resolves
+    /// to `acme`. The bare base domain has no tenant.
+    Subdomain {
+        /// Application domain that tenant subdomains hang off
+        base_domain: String,
+    },
+    /// A request header containing the tenant identifier
+    Header(String),
+    /// The tenant of the authenticated user
+    ///
+    /// Requires `SessionLayer` to run before `TenantLayer`.
+    User,
+}
+
+impl TenantSource {
+    /// Header source using [`TENANT_HEADER_NAME`]
+    #[must_use]
+    pub fn header() -> Self {
+        Self::Header(TENANT_HEADER_NAME.to_string())
+    }
+
+    /// Extract the raw tenant key from request headers
+    ///
+    /// Returns `None` for [`TenantSource::User`], which is resolved through
+    /// the [`TenantResolver`] instead.
+    #[must_use]
+    pub fn key_from_headers(&self, headers: &HeaderMap) -> Option<String> {
+        match self {
+            Self::Subdomain { base_domain } => {
+                let host = headers.get(axum::http::header::HOST)?.to_str().ok()?;
+                subdomain_of(host, base_domain)
+            }
+            Self::Header(name) => headers
+                .get(name.as_str())?
+                .to_str()
+                .ok()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+            Self::User => None,
+        }
+    }
+}
+
+/// Extract the tenant label from a host relative to `base_domain`
+fn subdomain_of(host: &str, base_domain: &str) -> Option<String> {
+    let host = host.split(':').next()?.to_ascii_lowercase();
+    let base_domain = base_domain.trim_start_matches('.').to_ascii_lowercase();
+
+    let prefix = host.strip_suffix(&base_domain)?.strip_suffix('.')?;
+
+    // Only the label directly below the base domain identifies the tenant
+    prefix
+        .rsplit('.')
+        .next()
+        .filter(|label| !label.is_empty())
+        .map(str::to_string)
+}
+
+/// Tenant resolution errors
+#[derive(Debug, thiserror::Error)]
+pub enum TenantError {
+    /// No tenant could be determined for the request
+    #[error("Tenant not specified")]
+    Missing,
+
+    /// The request named a tenant that does not exist
+    #[error("Unknown tenant: {0}")]
+    Unknown(String),
+
+    /// The authenticated user is not a member of the requested tenant
+    #[error("Access to tenant {0} is forbidden")]
+    Forbidden(String),
+
+    /// The tenant identifier is malformed
+    #[error("Invalid tenant identifier: {0}")]
+    InvalidId(String),
+
+    /// The resolver backend failed
+    #[error("Tenant lookup failed: {0}")]
+    Lookup(String),
+}
+
+impl IntoResponse for TenantError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            Self::Missing | Self::Unknown(_) | Self::InvalidId(_) => StatusCode::NOT_FOUND,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::Lookup(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        if status.is_server_error() {
+            tracing::error!(error = %self, "Tenant resolution failed");
+            return (status, "Internal server error").into_response();
+        }
+
+        (status, self.to_string()).into_response()
+    }
+}
+
+impl<S> FromRequestParts<S> for Tenant
+where
+    S: Send + Sync,
+{
+    type Rejection = TenantError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Self>()
+            .cloned()
+            .ok_or(TenantError::Missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_tenant_new_lowercases() {
+        let tenant = Tenant::new("Acme").unwrap();
+        assert_eq!(tenant.id(), "acme");
+    }
+
+    #[test]
+    fn test_tenant_new_rejects_invalid_ids() {
+        assert!(Tenant::new("").is_err());
+        assert!(Tenant::new("../etc").is_err());
+        assert!(Tenant::new("a b").is_err());
+        assert!(Tenant::new("a".repeat(64)).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_validates_id() {
+        let tenant: Tenant = serde_json::from_str(r#""acme""#).unwrap();
+        assert_eq!(tenant.id(), "acme");
+        assert_eq!(serde_json::to_string(&tenant).unwrap(), r#""acme""#);
+
+        assert!(serde_json::from_str::<Tenant>(r#""../x""#).is_err());
+        assert!(serde_json::from_str::<Tenant>("\"\"").is_err());
+    }
+
+    #[test]
+    fn test_storage_helpers() {
+        let tenant = Tenant::new("acme").unwrap();
+        assert_eq!(tenant.storage_prefix(), "tenants/acme");
+        assert_eq!(tenant.scoped_path("uploads/a.png"), "tenants/acme/uploads/a.png");
+        assert_eq!(tenant.scoped_path("//uploads/a.png"), "tenants/acme/uploads/a.png");
+    }
+
+    #[test]
+    fn test_push_filter_binds_parameter() {
+        let tenant = Tenant::new("acme").unwrap();
+        let mut query = sqlx::QueryBuilder::<sqlx::Postgres>::new("SELECT * FROM posts WHERE ");
+        tenant.push_filter_on(&mut query, "posts.tenant_id");
+        assert_eq!(query.sql(), "SELECT * FROM posts WHERE posts.tenant_id = $1");
+    }
+
+    #[test]
+    fn test_subdomain_extraction() {
+        assert_eq!(subdomain_of("acme.example.com", "example.com"), Some("acme".into()));
+        assert_eq!(subdomain_of("ACME.Example.com:8080", "example.com"), Some("acme".into()));
+        assert_eq!(subdomain_of("www.acme.example.com", "example.com"), Some("acme".into()));
+        assert_eq!(subdomain_of("example.com", "example.com"), None);
+        assert_eq!(subdomain_of("acme.other.com", "example.com"), None);
+        assert_eq!(subdomain_of("evilexample.com", "example.com"), None);
+    }
+
+    #[test]
+    fn test_header_source() {
+        let mut headers = HeaderMap::new();
+        headers.insert(TENANT_HEADER_NAME, HeaderValue::from_static(" acme "));
+        assert_eq!(TenantSource::header().key_from_headers(&headers), Some("acme".into()));
+
+        headers.insert(TENANT_HEADER_NAME, HeaderValue::from_static(""));
+        assert_eq!(TenantSource::header().key_from_headers(&headers), None);
+    }
+
+    #[test]
+    fn test_error_status_codes() {
+        assert_eq!(TenantError::Missing.into_response().status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            TenantError::Unknown("x".into()).into_response().status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            TenantError::Forbidden("x".into()).into_response().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            TenantError::Lookup("db down".into()).into_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Tenant lookup backends
+
+use super::{Tenant, TenantError};
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+
+/// Looks up tenants and tenant membership
+///
+/// Implement this against your tenants table. [`StaticTenantResolver`] is
+/// provided for tests and deployments with a fixed tenant list.
+#[async_trait]
+pub trait TenantResolver: Send + Sync + 'static {
+    /// Look up a tenant by the key extracted from the request
+    ///
+    /// Return `Ok(None)` for unknown tenants; the middleware responds `404`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TenantError::Lookup`] if the backend fails.
+    async fn resolve(&self, key: &str) -> Result<Option<Tenant>, TenantError>;
+
+    /// Look up the tenant of an authenticated user
+    ///
+    /// Used by [`TenantSource::User`](super::TenantSource::User). The default
+    /// implementation returns `Ok(None)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TenantError::Lookup`] if the backend fails.
+    async fn tenant_for_user(&self, _user_id: i64) -> Result<Option<Tenant>, TenantError> {
+        Ok(None)
+    }
+
+    /// Check whether an authenticated user may access a tenant
+    ///
+    /// Called for subdomain and header sources when the request carries an
+    /// authenticated session. The default implementation allows access.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TenantError::Lookup`] if the backend fails.
+    async fn is_member(&self, _tenant: &Tenant, _user_id: i64) -> Result<bool, TenantError> {
+        Ok(true)
+    }
+}
+
+/// In-memory resolver with a fixed set of tenants
+///
+/// Membership is only enforced if at least one member has been registered
+/// for a tenant.
+#[derive(Debug, Clone, Default)]
+pub struct StaticTenantResolver {
+    tenants: HashMap<String, Tenant>,
+    members: HashMap<String, HashSet<i64>>,
+    user_tenants: HashMap<i64, Tenant>,
+}
+
+impl StaticTenantResolver {
+    /// Create an empty resolver
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a tenant
+    #[must_use]
+    pub fn with_tenant(mut self, tenant: Tenant) -> Self {
+        self.tenants.insert(tenant.id().to_string(), tenant);
+        self
+    }
+
+    /// Register a user as a member of a tenant
+    ///
+    /// The first tenant registered for a user becomes their home tenant for
+    /// [`TenantSource::User`](super::TenantSource::User).
+    #[must_use]
+    pub fn with_member(mut self, tenant: &Tenant, user_id: i64) -> Self {
+        self.members
+            .entry(tenant.id().to_string())
+            .or_default()
+            .insert(user_id);
+        self.user_tenants
+            .entry(user_id)
+            .or_insert_with(|| tenant.clone());
+        self
+    }
+}
+
+#[async_trait]
+impl TenantResolver for StaticTenantResolver {
+    async fn resolve(&self, key: &str) -> Result<Option<Tenant>, TenantError> {
+        Ok(self.tenants.get(&key.to_ascii_lowercase()).cloned())
+    }
+
+    async fn tenant_for_user(&self, user_id: i64) -> Result<Option<Tenant>, TenantError> {
+        Ok(self.user_tenants.get(&user_id).cloned())
+    }
+
+    async fn is_member(&self, tenant: &Tenant, user_id: i64) -> Result<bool, TenantError> {
+        Ok(self
+            .members
+            .get(tenant.id())
+            .is_none_or(|members| members.contains(&user_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_static_resolver_lookup() {
+        let acme = Tenant::new("acme").unwrap();
+        let resolver = StaticTenantResolver::new().with_tenant(acme.clone());
+
+        assert_eq!(resolver.resolve("ACME").await.unwrap(), Some(acme));
+        assert_eq!(resolver.resolve("globex").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_static_resolver_membership() {
+        let acme = Tenant::new("acme").unwrap();
+        let globex = Tenant::new("globex").unwrap();
+        let resolver = StaticTenantResolver::new()
+            .with_tenant(acme.clone())
+            .with_tenant(globex.clone())
+            .with_member(&acme, 1);
+
+        assert!(resolver.is_member(&acme, 1).await.unwrap());
+        assert!(!resolver.is_member(&acme, 2).await.unwrap());
+        // No members registered: open tenant
+        assert!(resolver.is_member(&globex, 2).await.unwrap());
+        assert_eq!(resolver.tenant_for_user(1).await.unwrap(), Some(acme));
+        assert_eq!(resolver.tenant_for_user(2).await.unwrap(), None);
+    }
+}
@@ -179,6 +179,38 @@ pub async fn create_sqlite_pool() -> anyhow::Result<sqlx::SqlitePool> {
     Ok(pool)
 }
 
+/// Create an in-memory SQLite pool with `migration` applied
+///
+/// `migration` is raw SQL, typically `include_str!` of a file in
+/// `migrations/`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use acton_htmx::testing::create_sqlite_pool_with_migration;
+///
+/// #[tokio::test]
+/// async fn test_with_notes_table() {
+///     let pool = create_sqlite_pool_with_migration("CREATE TABLE notes (id INTEGER)")
+///         .await
+///         .unwrap();
+///     // Use pool for testing
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the SQLite pool cannot be created or the migration
+/// fails
+#[cfg(feature = "sqlite")]
+pub async fn create_sqlite_pool_with_migration(
+    migration: &str,
+) -> anyhow::Result<sqlx::SqlitePool> {
+    let pool = create_sqlite_pool().await?;
+    sqlx::raw_sql(migration).execute(&pool).await?;
+    Ok(pool)
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "sqlite")]
@@ -195,4 +227,18 @@ mod tests {
 
         assert_eq!(result.0, 1);
     }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_pool_with_migration() {
+        use super::*;
+        let pool = create_sqlite_pool_with_migration("CREATE TABLE notes (id INTEGER)")
+            .await
+            .unwrap();
+
+        sqlx::query("INSERT INTO notes (id) VALUES (1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
 }
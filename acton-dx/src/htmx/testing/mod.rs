@@ -53,6 +53,8 @@ pub mod server;
 pub use agents::{await_response, await_response_with_timeout, AgentTestRuntime};
 pub use assertions::*;
 pub use database::TestDatabase;
+#[cfg(feature = "sqlite")]
+pub use database::{create_sqlite_pool, create_sqlite_pool_with_migration};
 pub use email::MockEmailSender;
 pub use jobs::{
     assert_job_completes_within, assert_job_fails, assert_job_succeeds, TestJob, TestJobQueue,
@@ -4,6 +4,7 @@
 //! assertion helpers for integration testing.
 
 use axum::Router;
+use axum_test::TestResponse;
 
 /// Test server wrapper for integration testing
 ///
@@ -70,6 +71,236 @@ impl TestServer {
     pub const fn inner(&self) -> &axum_test::TestServer {
         &self.inner
     }
+
+    /// Assert that the out-of-band swap for `target_id` contains `needle`
+    ///
+    /// Looks for an element with an `hx-swap-oob` attribute targeting
+    /// `target_id` (its `id`, or a `#id` selector in the attribute value) and
+    /// checks its inner HTML.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no OOB swap targets `target_id` or its content doesn't
+    /// contain `needle`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use acton_htmx::testing::TestServer;
+    /// # async fn example(server: TestServer) {
+    /// let response = server.post("/cart/items").await;
+    /// server.assert_hx_oob_contains(&response, "cart-count", "3 items");
+    /// # }
+    /// ```
+    pub fn assert_hx_oob_contains(&self, response: &TestResponse, target_id: &str, needle: &str) {
+        let html = response.text();
+        let swaps = oob_swaps(&html);
+        let swap = swaps
+            .iter()
+            .find(|swap| swap.target == target_id)
+            .unwrap_or_else(|| {
+                panic!(
+                    "No hx-swap-oob element targets #{target_id}; found {:?}",
+                    targets(&swaps)
+                )
+            });
+        assert!(
+            swap.content.contains(needle),
+            "Expected OOB swap for #{target_id} to contain {needle:?}, got {:?}",
+            swap.content
+        );
+    }
+
+    /// Assert that the response swaps exactly these targets out of band
+    ///
+    /// Order doesn't matter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set of `hx-swap-oob` targets differs from `expected`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use acton_htmx::testing::TestServer;
+    /// # async fn example(server: TestServer) {
+    /// let response = server.post("/cart/items").await;
+    /// server.assert_hx_oob_targets(&response, &["cart-count", "flash"]);
+    /// # }
+    /// ```
+    pub fn assert_hx_oob_targets(&self, response: &TestResponse, expected: &[&str]) {
+        let html = response.text();
+        let mut actual = targets(&oob_swaps(&html));
+        actual.sort_unstable();
+        let mut expected = expected.to_vec();
+        expected.sort_unstable();
+        assert_eq!(actual, expected, "Unexpected hx-swap-oob targets");
+    }
+}
+
+/// An element swapped out of band
+#[derive(Debug)]
+struct OobSwap<'a> {
+    /// Id of the element being replaced
+    target: &'a str,
+    /// Inner HTML of the swapped element
+    content: &'a str,
+}
+
+/// Targets of the given swaps, in document order
+fn targets<'a>(swaps: &[OobSwap<'a>]) -> Vec<&'a str> {
+    swaps.iter().map(|swap| swap.target).collect()
+}
+
+/// Find the elements carrying an `hx-swap-oob` attribute
+///
+/// A small scanner rather than a full HTML parser: it handles quoted
+/// attributes and nested elements of the same tag, which is enough for
+/// fragments rendered by handlers.
+fn oob_swaps(html: &str) -> Vec<OobSwap<'_>> {
+    let mut swaps = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = html[pos..].find('<') {
+        let start = pos + offset;
+        let Some(tag) = parse_open_tag(html, start) else {
+            pos = start + 1;
+            continue;
+        };
+        pos = tag.end;
+
+        let Some(oob) = attribute(tag.attributes, "hx-swap-oob") else {
+            continue;
+        };
+        // `hx-swap-oob="outerHTML:#target"` selects the target explicitly
+        let target = oob
+            .split_once(':')
+            .and_then(|(_, selector)| selector.trim().strip_prefix('#'))
+            .or_else(|| attribute(tag.attributes, "id"));
+        let Some(target) = target else {
+            continue;
+        };
+
+        let content = if tag.self_closing {
+            ""
+        } else {
+            let close = matching_close(html, tag.name, tag.end);
+            &html[tag.end..close]
+        };
+        swaps.push(OobSwap { target, content });
+    }
+
+    swaps
+}
+
+/// An opening tag
+struct OpenTag<'a> {
+    name: &'a str,
+    attributes: &'a str,
+    self_closing: bool,
+    /// Byte offset just past the closing `>`
+    end: usize,
+}
+
+/// Parse an opening tag starting at the `<` at `start`
+fn parse_open_tag(html: &str, start: usize) -> Option<OpenTag<'_>> {
+    let rest = &html[start + 1..];
+    let name_len = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(rest.len());
+    if name_len == 0 {
+        // Closing tag, comment, doctype, or stray `<`
+        return None;
+    }
+    let name = &rest[..name_len];
+
+    let mut quote = None;
+    for (i, c) in rest[name_len..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => {
+                let attributes = &rest[name_len..name_len + i];
+                let self_closing = attributes.trim_end().ends_with('/');
+                return Some(OpenTag {
+                    name,
+                    attributes: attributes.trim_end_matches('/'),
+                    self_closing,
+                    end: start + 1 + name_len + i + 1,
+                });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Value of the attribute `name` in an attribute list
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes.trim_start();
+    while !rest.is_empty() {
+        let name_len = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let attr_name = &rest[..name_len];
+        rest = rest[name_len..].trim_start();
+
+        // Boolean attributes have no `=` and an empty value
+        let mut value = "";
+        if let Some(after_eq) = rest.strip_prefix('=') {
+            let (parsed, remaining) = attribute_value(after_eq.trim_start());
+            value = parsed;
+            rest = remaining.trim_start();
+        }
+
+        if attr_name.eq_ignore_ascii_case(name) {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Split a (possibly quoted) attribute value from the rest of the tag
+fn attribute_value(input: &str) -> (&str, &str) {
+    if let Some(quote @ ('"' | '\'')) = input.chars().next() {
+        let body = &input[1..];
+        let close = body.find(quote).unwrap_or(body.len());
+        (&body[..close], body.get(close + 1..).unwrap_or(""))
+    } else {
+        let end = input.find(char::is_whitespace).unwrap_or(input.len());
+        input.split_at(end)
+    }
+}
+
+/// Offset of the `</name>` closing the element whose content starts at `from`
+///
+/// Returns the end of the input if the element is never closed.
+fn matching_close(html: &str, name: &str, from: usize) -> usize {
+    let lower = html.to_ascii_lowercase();
+    let name = name.to_ascii_lowercase();
+    let open = format!("<{name}");
+    let close = format!("</{name}");
+
+    let mut depth = 0;
+    let mut pos = from;
+    loop {
+        let next_open = lower[pos..].find(&open).map(|i| pos + i);
+        let Some(next_close) = lower[pos..].find(&close).map(|i| pos + i) else {
+            return html.len();
+        };
+
+        match next_open {
+            Some(next_open) if next_open < next_close => {
+                depth += 1;
+                pos = next_open + open.len();
+            }
+            _ if depth == 0 => return next_close,
+            _ => {
+                depth -= 1;
+                pos = next_close + close.len();
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -102,4 +333,64 @@ mod tests {
         server.patch("/patch").await.assert_status_ok();
         server.delete("/delete").await.assert_status_ok();
     }
+
+    fn oob_server() -> TestServer {
+        use crate::htmx::responses::{HxSwapOob, SwapStrategy};
+
+        let app = Router::new().route(
+            "/items",
+            axum::routing::post(|| async {
+                let mut oob = HxSwapOob::with_primary("<li>New item</li>");
+                oob.add("item-count", "<span>3 items</span>", SwapStrategy::InnerHTML)
+                    .add(
+                        "flash",
+                        r#"<div id="flash" class="alert"><div>Item added</div></div>"#,
+                        SwapStrategy::OuterHTML,
+                    );
+                oob
+            }),
+        );
+        TestServer::new(app).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_assert_hx_oob_targets() {
+        let server = oob_server();
+        let response = server.post("/items").await;
+
+        server.assert_hx_oob_targets(&response, &["flash", "item-count"]);
+        server.assert_hx_oob_contains(&response, "item-count", "3 items");
+        server.assert_hx_oob_contains(&response, "flash", "<div>Item added</div>");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "No hx-swap-oob element targets #sidebar")]
+    async fn test_assert_hx_oob_contains_missing_target() {
+        let server = oob_server();
+        let response = server.post("/items").await;
+        server.assert_hx_oob_contains(&response, "sidebar", "anything");
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "to contain")]
+    async fn test_assert_hx_oob_contains_wrong_content() {
+        let server = oob_server();
+        let response = server.post("/items").await;
+        server.assert_hx_oob_contains(&response, "item-count", "4 items");
+    }
+
+    #[test]
+    fn test_oob_swaps_parsing() {
+        let html = r#"<p>main</p>
+            <div id="a" hx-swap-oob="true"><div>nested</div> tail</div>
+            <tr hx-swap-oob='outerHTML:#row-2' id="ignored"><td>2</td></tr>
+            <img id="b" hx-swap-oob="delete" />
+            <div id="plain">not oob</div>"#;
+
+        let swaps = oob_swaps(html);
+        assert_eq!(targets(&swaps), ["a", "row-2", "b"]);
+        assert_eq!(swaps[0].content, "<div>nested</div> tail");
+        assert_eq!(swaps[1].content, "<td>2</td>");
+        assert_eq!(swaps[2].content, "");
+    }
 }
@@ -13,8 +13,9 @@
 //! - `mysql` - MySQL database support
 //! - `redis` - Redis session and cache support (default)
 //! - `cedar` - Cedar policy-based authorization (default)
-//! - `otel-metrics` - OpenTelemetry metrics collection
+//! - `otel-metrics` - OpenTelemetry metrics collection and OTLP trace/metrics export
 //! - `aws-ses` - AWS SES email backend
+//! - `s3` - S3-compatible file storage backend
 //! - `clamav` - ClamAV virus scanning
 //!
 //! # Quick Start
@@ -85,6 +86,8 @@ pub use htmx::email;
 #[cfg(feature = "htmx")]
 pub use htmx::error;
 #[cfg(feature = "htmx")]
+pub use htmx::events;
+#[cfg(feature = "htmx")]
 pub use htmx::extractors;
 #[cfg(feature = "htmx")]
 pub use htmx::forms;
@@ -103,10 +106,16 @@ pub use htmx::observability;
 #[cfg(feature = "htmx")]
 pub use htmx::prelude;
 #[cfg(feature = "htmx")]
+pub use htmx::realtime;
+#[cfg(feature = "htmx")]
 pub use htmx::responses;
 #[cfg(feature = "htmx")]
+pub use htmx::server;
+#[cfg(feature = "htmx")]
 pub use htmx::state;
 #[cfg(feature = "htmx")]
 pub use htmx::storage;
 #[cfg(feature = "htmx")]
 pub use htmx::template;
+#[cfg(feature = "htmx")]
+pub use htmx::tenant;
@@ -0,0 +1,122 @@
+//! Integration tests for WebSocket broadcasts
+//!
+//! Tests fan-out of published fragments to connected WebSocket clients.
+
+use acton_dx::htmx::agents::Publish;
+use acton_dx::htmx::handlers::ws_handler;
+use acton_dx::htmx::state::ActonHtmxState;
+use acton_reactive::prelude::{ActonApp, AgentHandleInterface};
+use axum::{http::header::ORIGIN, routing::get, Router};
+use axum_test::TestServer;
+
+/// Serve `ws_handler`, sending this server's own `Origin` on every request
+fn server(state: ActonHtmxState) -> TestServer {
+    let app = Router::new()
+        .route("/ws", get(ws_handler))
+        .with_state(state);
+    let mut server = TestServer::builder().http_transport().build(app).unwrap();
+    let address = server.server_address().unwrap();
+    let origin = address.as_str().trim_end_matches('/').to_string();
+    server.add_header(ORIGIN, origin);
+    server
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_publish_reaches_all_connected_clients() {
+    let mut runtime = ActonApp::launch();
+    let mut state = ActonHtmxState::new(&mut runtime)
+        .await
+        .expect("Failed to create state");
+    state.set_channel_authorizer(|channel, _| matches!(channel, "dashboard" | "lobby"));
+    let broadcast = state.broadcast().clone();
+    let server = server(state);
+
+    let mut first = server
+        .get_websocket("/ws?channel=dashboard")
+        .await
+        .into_websocket()
+        .await;
+    let mut second = server
+        .get_websocket("/ws?channel=dashboard")
+        .await
+        .into_websocket()
+        .await;
+    let mut elsewhere = server
+        .get_websocket("/ws?channel=lobby")
+        .await
+        .into_websocket()
+        .await;
+
+    let fragment = r#"<div id="visitors" hx-swap-oob="true">2</div>"#;
+    broadcast.send(Publish::new("dashboard", fragment)).await;
+    broadcast.send(Publish::new("lobby", "<p>lobby</p>")).await;
+
+    first.assert_receive_text(fragment).await;
+    second.assert_receive_text(fragment).await;
+    elsewhere.assert_receive_text("<p>lobby</p>").await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_upgrade_without_channel_is_rejected() {
+    let mut runtime = ActonApp::launch();
+    let state = ActonHtmxState::new(&mut runtime)
+        .await
+        .expect("Failed to create state");
+    let server = server(state);
+
+    server
+        .get_websocket("/ws")
+        .expect_failure()
+        .await
+        .assert_status_bad_request();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cross_origin_upgrade_is_rejected() {
+    let mut runtime = ActonApp::launch();
+    let mut state = ActonHtmxState::new(&mut runtime)
+        .await
+        .expect("Failed to create state");
+    state.set_channel_authorizer(|_, _| true);
+
+    let app = Router::new()
+        .route("/ws", get(ws_handler))
+        .with_state(state);
+    let server = TestServer::builder().http_transport().build(app).unwrap();
+
+    server
+        .get_websocket("/ws?channel=dashboard")
+        .expect_failure()
+        .await
+        .assert_status_forbidden();
+    server
+        .get_websocket("/ws?channel=dashboard")
+        .add_header(ORIGIN, "https://evil.example.com")
+        .expect_failure()
+        .await
+        .assert_status_forbidden();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unauthorized_channel_is_rejected() {
+    let mut runtime = ActonApp::launch();
+    let mut state = ActonHtmxState::new(&mut runtime)
+        .await
+        .expect("Failed to create state");
+    let unauthorized = server(state.clone());
+    state.set_channel_authorizer(|channel, _| channel == "dashboard");
+    let authorized = server(state);
+
+    // Without an authorizer no channel can be requested
+    unauthorized
+        .get_websocket("/ws?channel=dashboard")
+        .expect_failure()
+        .await
+        .assert_status_forbidden();
+
+    authorized
+        .get_websocket("/ws?channel=session:someone-else")
+        .expect_failure()
+        .await
+        .assert_status_forbidden();
+}
@@ -9,8 +9,8 @@
 //! would require mocking external OAuth providers and a test database.
 
 use acton_dx::oauth2::{
-    CleanupExpired, GenerateState, OAuth2Agent, OAuthConfig, OAuthProvider, ProviderConfig,
-    RemoveState, ValidateState,
+    CleanupExpired, GenerateState, OAuth2Agent, OAuthConfig, OAuthProvider, PkceStorage,
+    ProviderConfig, RemoveState, ValidateState,
 };
 use acton_reactive::prelude::{ActonApp, AgentHandleInterface};
 
@@ -35,7 +35,9 @@ fn test_oauth_config() -> OAuthConfig {
             token_url: Some("https://github.com/login/oauth/access_token".to_string()),
             userinfo_url: Some("https://api.github.com/user".to_string()),
         }),
+        discord: None,
         oidc: None,
+        pkce_storage: PkceStorage::Agent,
     }
 }
 
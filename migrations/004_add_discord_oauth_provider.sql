@@ -0,0 +1,17 @@
+-- Allow Discord as an OAuth2 provider
+-- Migration: 004_add_discord_oauth_provider
+-- Purpose: Extend the oauth_accounts provider CHECK constraint with 'discord'
+
+ALTER TABLE oauth_accounts
+DROP CONSTRAINT IF EXISTS oauth_accounts_provider_check;
+
+ALTER TABLE oauth_accounts
+ADD CONSTRAINT oauth_accounts_provider_check
+CHECK (provider IN ('google', 'github', 'discord', 'oidc'));
+
+COMMENT ON COLUMN oauth_accounts.provider IS 'OAuth2 provider (google, github, discord, oidc)';
+
+-- ROLLBACK INSTRUCTIONS (if needed):
+-- ALTER TABLE oauth_accounts DROP CONSTRAINT IF EXISTS oauth_accounts_provider_check;
+-- ALTER TABLE oauth_accounts ADD CONSTRAINT oauth_accounts_provider_check
+--     CHECK (provider IN ('google', 'github', 'oidc'));
@@ -0,0 +1,18 @@
+-- Create sessions table for database-backed session persistence
+-- Migration: 005_create_sessions_table
+-- Purpose: Store serialized SessionData so sessions survive restarts and can be
+--          shared between application instances
+--
+-- Column types are portable between PostgreSQL and SQLite.
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    data TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+-- Index for expired session cleanup
+CREATE INDEX IF NOT EXISTS idx_sessions_expires_at ON sessions(expires_at);
+
+-- ROLLBACK INSTRUCTIONS (if needed):
+-- DROP TABLE IF EXISTS sessions;
@@ -0,0 +1,24 @@
+-- Create remember_tokens table for persistent "remember me" logins
+-- Migration: 006_create_remember_tokens_table
+-- Purpose: Store selector/validator token pairs that re-establish a session
+--          after the session cookie has gone
+--
+-- Only a SHA-256 hash of each validator is stored, so a leaked table cannot
+-- be replayed as cookies. Column types are portable between PostgreSQL and
+-- SQLite.
+
+CREATE TABLE IF NOT EXISTS remember_tokens (
+    selector TEXT PRIMARY KEY,
+    validator_hash TEXT NOT NULL,
+    user_id BIGINT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+-- Index for revoking all of a user's tokens
+CREATE INDEX IF NOT EXISTS idx_remember_tokens_user_id ON remember_tokens(user_id);
+
+-- Index for expired token cleanup
+CREATE INDEX IF NOT EXISTS idx_remember_tokens_expires_at ON remember_tokens(expires_at);
+
+-- ROLLBACK INSTRUCTIONS (if needed):
+-- DROP TABLE IF EXISTS remember_tokens;
@@ -0,0 +1,13 @@
+-- Record when users verified their email address
+-- Migration: 007_add_email_verified_at_to_users
+-- Purpose: Timestamp set by the email verification flow alongside
+--          users.email_verified (added in 003)
+--
+-- NULL means the address has not been verified. Column types are portable
+-- between PostgreSQL and SQLite.
+
+ALTER TABLE users
+ADD COLUMN email_verified_at TIMESTAMPTZ;
+
+-- ROLLBACK INSTRUCTIONS (if needed):
+-- ALTER TABLE users DROP COLUMN IF EXISTS email_verified_at;
@@ -0,0 +1,22 @@
+-- Create password_reset_tokens table for the password reset flow
+-- Migration: 008_create_password_reset_tokens_table
+-- Purpose: Store single-use, expiring password reset tokens
+--
+-- Only a SHA-256 hash of each token is stored, so a leaked table cannot be
+-- used to reset passwords. Tokens are deleted when used. Column types are
+-- portable between PostgreSQL and SQLite.
+
+CREATE TABLE IF NOT EXISTS password_reset_tokens (
+    token_hash TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+-- Index for revoking all of a user's tokens
+CREATE INDEX IF NOT EXISTS idx_password_reset_tokens_user_id ON password_reset_tokens(user_id);
+
+-- Index for expired token cleanup
+CREATE INDEX IF NOT EXISTS idx_password_reset_tokens_expires_at ON password_reset_tokens(expires_at);
+
+-- ROLLBACK INSTRUCTIONS (if needed):
+-- DROP TABLE IF EXISTS password_reset_tokens;
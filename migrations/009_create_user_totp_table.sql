@@ -0,0 +1,20 @@
+-- Create user_totp table for TOTP two-factor authentication
+-- Migration: 009_create_user_totp_table
+-- Purpose: Store each user's encrypted TOTP secret and hashed backup codes
+--
+-- The secret is encrypted with AES-256-GCM under a key derived from
+-- security.secret_key; backup_codes holds a JSON array of SHA-256 hashes of
+-- the unused codes. A row with enabled = FALSE is an unfinished enrollment.
+-- last_used_step stops a code from being accepted twice. Column types are
+-- portable between PostgreSQL and SQLite.
+
+CREATE TABLE IF NOT EXISTS user_totp (
+    user_id BIGINT PRIMARY KEY,
+    secret_ciphertext TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    last_used_step BIGINT,
+    backup_codes TEXT NOT NULL DEFAULT '[]'
+);
+
+-- ROLLBACK INSTRUCTIONS (if needed):
+-- DROP TABLE IF EXISTS user_totp;
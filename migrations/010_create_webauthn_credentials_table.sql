@@ -0,0 +1,22 @@
+-- Create webauthn_credentials table for passkey login
+-- Migration: 010_create_webauthn_credentials_table
+-- Purpose: Store each user's registered WebAuthn credentials (passkeys)
+--
+-- credential_id is the base64url credential ID chosen by the authenticator.
+-- passkey holds the serialized credential, including its public key and
+-- signature counter, and is updated after each successful login. Column
+-- types are portable between PostgreSQL and SQLite.
+
+CREATE TABLE IF NOT EXISTS webauthn_credentials (
+    credential_id TEXT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    passkey TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMPTZ
+);
+
+-- Index for listing a user's credentials
+CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user_id ON webauthn_credentials(user_id);
+
+-- ROLLBACK INSTRUCTIONS (if needed):
+-- DROP TABLE IF EXISTS webauthn_credentials;